@@ -0,0 +1,56 @@
+//! Batched swaps
+//!
+//! `swap_batch` executes several independent swaps, each along its own best
+//! route, under one authorization and one reentrancy lock. Requests run in
+//! order, so each is quoted against the pools as left by the previous ones.
+//! The policy decides what a request that cannot be executed does to the
+//! rest of the batch.
+
+use soroban_sdk::{contractevent, contracttype, Address, Vec};
+
+/// Maximum requests per batch; each one costs a full route search
+pub const MAX_BATCH_REQUESTS: u32 = 5;
+
+/// One swap of a batch
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SwapRequest {
+    pub token_in: Address,
+    pub token_out: Address,
+    pub amount_in: i128,
+    /// Minimum output; 0 applies the user's slippage preference, if set
+    pub min_out: i128,
+}
+
+/// What a request that cannot be executed does to the batch
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BatchPolicy {
+    /// Any failing request reverts the whole batch
+    AllOrNothing,
+    /// Requests without a route, or quoted below their `min_out`, are
+    /// skipped before any of their tokens move
+    BestEffort,
+}
+
+/// Outcome of a batch
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchFill {
+    /// Output of each request, in request order; 0 for skipped requests
+    pub amounts_out: Vec<i128>,
+    /// Requests executed
+    pub executed: u32,
+}
+
+/// Batch executed event, alongside the per-swap route events
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchSwapped {
+    #[topic]
+    pub user: Address,
+    pub policy: BatchPolicy,
+    pub requests: u32,
+    pub executed: u32,
+    pub amounts_out: Vec<i128>,
+}
@@ -15,19 +15,68 @@
 //! - Multi-hop path finding (up to 3 hops)
 //! - Quote comparison and best route selection
 //! - Slippage protection
-//! - Split routing for large orders (future)
-
+//! - Volume-based aggregator fee tiers (30-day rolling volume)
+//! - Single-auth input pull, by transfer or approve-then-`transfer_from`
+//! - Protocol deregistration (tombstoned IDs)
+//! - Optional oracle TWAP sanity bound on external protocol quotes
+//! - Read-only route simulation (per-hop amounts, price impact and fees)
+//! - Per-protocol route statistics (routed volume, wins, improvement vs runner-up)
+//! - Exact-output buys split across protocols (greedy marginal-price allocation)
+//! - Protocol quoting priority, per-call protocol filter and early stop near
+//!   the oracle price
+//! - Opt-in partial fills for orders too large for their price bound
+//! - Admin-managed connector tokens (XLM, USDC, ...) as the only
+//!   intermediates of two-hop routes
+//! - Per-user default max slippage, applied to swaps passing `min_out = 0`
+//! - Batched swaps along independent best routes, all-or-nothing or best-effort
+//! - Admin-configured per-hop slippage tolerance, with per-protocol overrides
+
+mod batch;
+mod partial;
+mod pull;
+mod simulate;
+mod split;
+mod stats;
 mod storage;
+mod volume;
+
+use astroswap_shared::{
+    apply_bps, calculate_deviation_bps, emit_route_executed, from_precision, get_amount_in,
+    mul_div_down, mul_div_up, require_bps_range, require_deadline, require_not_paused,
+    require_positive, route_endpoints, to_precision, validate_route, with_lock, AnalyticsClient,
+    AstroSwapError, FactoryClient, OracleClient, PairClient, Protocol, Rounding, RouteStep,
+    SwapRoute, MAX_FEE_BPS,
+};
+use soroban_sdk::{
+    contract, contractevent, contractimpl, token, Address, Env, IntoVal, Symbol, Vec,
+};
 
-use astroswap_shared::{AstroSwapError, PairClient, Protocol, RouteStep, SwapRoute};
-use soroban_sdk::{contract, contractimpl, token, Address, Env, IntoVal, Symbol, Vec};
-
+use crate::batch::MAX_BATCH_REQUESTS;
+pub use crate::batch::{BatchFill, BatchPolicy, BatchSwapped, SwapRequest};
+pub use crate::partial::PartialFill;
+use crate::partial::{largest_fill, PRICE_PROBE_DIVISOR};
+use crate::pull::pull_input;
+pub use crate::pull::{PullAuthorization, PullMode};
+use crate::simulate::{simulate_native_hop, simulate_quoted_hop};
+pub use crate::simulate::{HopSimulation, RouteSimulation};
+use crate::split::{allocate_exact_output, fee_on_top, SPLIT_CHUNKS};
+pub use crate::split::{SplitLeg, SplitQuote};
+use crate::stats::{
+    all_protocol_stats, average_improvement_bps, best_protocol, record_hop, record_win,
+};
 use crate::storage::{
-    extend_instance_ttl, get_admin, get_config, get_fee_recipient, get_protocol,
-    get_protocol_count, is_initialized, is_locked, is_paused, set_admin, set_config,
-    set_fee_recipient, set_initialized, set_locked, set_paused, set_protocol, set_protocol_count,
-    AggregatorConfig, ProtocolAdapter,
+    clear_protocol_removed, extend_instance_ttl, get_admin, get_analytics, get_config,
+    get_connectors, get_early_stop_bps, get_fee_recipient, get_protocol, get_protocol_count,
+    get_protocol_hop_slippage, get_protocol_priority, get_protocol_stats, get_quote_guard,
+    get_slippage_preference, is_initialized, is_paused, is_protocol_removed, protocol_exists,
+    quote_order, registered_protocols, remove_protocol, remove_quote_guard, set_admin,
+    set_analytics, set_config, set_connectors, set_early_stop_bps, set_fee_recipient,
+    set_fee_tiers, set_initialized, set_paused, set_protocol, set_protocol_count,
+    set_protocol_hop_slippage, set_protocol_priority, set_quote_guard, set_slippage_preference,
+    set_takes_deadline, takes_deadline, AggregatorConfig, DataKey, FeeTier, ProtocolAdapter,
+    ProtocolStats, QuoteGuard,
 };
+use crate::volume::{effective_fee_bps, fee_tiers, record_volume, rolling_volume, tier_for_volume};
 
 /// Basis points constant (100% = 10000)
 const BPS: u32 = 10_000;
@@ -35,6 +84,38 @@ const BPS: u32 = 10_000;
 /// Maximum hops allowed in a single route
 const MAX_HOPS: u32 = 3;
 
+/// Maximum protocols a split swap spreads over: the order is allocated in
+/// `SPLIT_CHUNKS` chunks, so there are never more legs
+const MAX_SPLITS: u32 = SPLIT_CHUNKS as u32;
+
+/// Default tolerated shortfall of a hop below its quoted output (1%)
+const DEFAULT_HOP_SLIPPAGE_BPS: u32 = 100;
+
+/// Bounds of the per-hop slippage tolerance: below the minimum, rounding in
+/// external adapters fails hops; above the maximum, the check no longer
+/// catches a sandwiched pool before the route's final `min_out`
+const MIN_HOP_SLIPPAGE_BPS: u32 = 1;
+const MAX_HOP_SLIPPAGE_BPS: u32 = 1_000;
+
+/// Maximum connector tokens; each one costs two rounds of quotes per search
+const MAX_CONNECTORS: u32 = 8;
+
+/// Connector token added event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConnectorAdded {
+    #[topic]
+    pub token: Address,
+}
+
+/// Connector token removed event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConnectorRemoved {
+    #[topic]
+    pub token: Address,
+}
+
 #[contract]
 pub struct AstroSwapAggregator;
 
@@ -64,6 +145,7 @@ impl AstroSwapAggregator {
             max_hops: MAX_HOPS,
             max_splits: 2,
             aggregator_fee_bps: 5, // 0.05%
+            hop_slippage_bps: DEFAULT_HOP_SLIPPAGE_BPS,
         };
         set_config(&env, &config);
 
@@ -90,7 +172,8 @@ impl AstroSwapAggregator {
     /// * `token_in` - Input token address
     /// * `token_out` - Output token address
     /// * `amount_in` - Amount of input tokens
-    /// * `min_out` - Minimum output amount (slippage protection)
+    /// * `min_out` - Minimum output amount (slippage protection); 0 applies
+    ///   the user's slippage preference, if set
     /// * `deadline` - Transaction deadline timestamp
     ///
     /// # Returns
@@ -106,59 +189,52 @@ impl AstroSwapAggregator {
         min_out: i128,
         deadline: u64,
     ) -> Result<i128, AstroSwapError> {
-        user.require_auth();
-        Self::require_not_paused(&env)?;
-        Self::check_deadline(&env, deadline)?;
-
-        // Acquire reentrancy lock
-        Self::acquire_lock(&env)?;
-
-        // Validate amounts
-        if amount_in <= 0 {
-            Self::release_lock(&env);
-            return Err(AstroSwapError::InvalidArgument);
-        }
-
-        // Find the best route
-        let route = match Self::find_best_route_internal(&env, &token_in, &token_out, amount_in) {
-            Ok(r) => r,
-            Err(e) => {
-                Self::release_lock(&env);
-                return Err(e);
-            }
-        };
-
-        // Verify minimum output
-        if route.expected_output < min_out {
-            Self::release_lock(&env);
-            return Err(AstroSwapError::SlippageExceeded);
-        }
-
-        // Execute the route
-        let actual_out = match Self::execute_route(&env, &user, &route, amount_in, deadline) {
-            Ok(out) => out,
-            Err(e) => {
-                Self::release_lock(&env);
-                return Err(e);
-            }
-        };
-
-        // Final slippage check
-        if actual_out < min_out {
-            Self::release_lock(&env);
-            return Err(AstroSwapError::SlippageExceeded);
-        }
+        Self::swap_internal(
+            &env,
+            &user,
+            &token_in,
+            &token_out,
+            amount_in,
+            min_out,
+            deadline,
+            PullMode::Transfer,
+        )
+    }
 
-        // Release reentrancy lock
-        Self::release_lock(&env);
-        extend_instance_ttl(&env);
-        Ok(actual_out)
+    /// Swap pulling the input from an allowance granted to the aggregator
+    ///
+    /// Same as `swap`, but the input is collected with `transfer_from`, so the
+    /// user only signs this invocation. Approve the aggregator for at least
+    /// `amount_in` of `token_in` first; see `pull_authorization`.
+    pub fn swap_with_allowance(
+        env: Env,
+        user: Address,
+        token_in: Address,
+        token_out: Address,
+        amount_in: i128,
+        min_out: i128,
+        deadline: u64,
+    ) -> Result<i128, AstroSwapError> {
+        Self::swap_internal(
+            &env,
+            &user,
+            &token_in,
+            &token_out,
+            amount_in,
+            min_out,
+            deadline,
+            PullMode::Allowance,
+        )
     }
 
     /// Execute a swap using a pre-computed route
     ///
     /// Useful when the user already knows the optimal route
-    /// or wants to use a specific path
+    /// or wants to use a specific path. A route through a protocol deactivated
+    /// or a native pool paused since it was quoted fails with
+    /// `ProtocolInactive` or `PoolPaused` before any tokens move. With
+    /// `min_out` 0, the user's slippage preference applies to the route's
+    /// `expected_output`.
     ///
     /// Uses reentrancy guard to prevent flash loan attacks
     pub fn swap_with_route(
@@ -169,38 +245,296 @@ impl AstroSwapAggregator {
         min_out: i128,
         deadline: u64,
     ) -> Result<i128, AstroSwapError> {
+        Self::swap_with_route_internal(
+            &env,
+            &user,
+            &route,
+            amount_in,
+            min_out,
+            deadline,
+            PullMode::Transfer,
+        )
+    }
+
+    /// Execute a pre-computed route pulling the input from an allowance
+    ///
+    /// Same as `swap_with_route`, but collects the input with `transfer_from`
+    pub fn swap_with_route_allowance(
+        env: Env,
+        user: Address,
+        route: SwapRoute,
+        amount_in: i128,
+        min_out: i128,
+        deadline: u64,
+    ) -> Result<i128, AstroSwapError> {
+        Self::swap_with_route_internal(
+            &env,
+            &user,
+            &route,
+            amount_in,
+            min_out,
+            deadline,
+            PullMode::Allowance,
+        )
+    }
+
+    /// Buy exactly `amount_out` of `token_out`, splitting the order across
+    /// the direct pools of up to `max_splits` protocols at minimum cost
+    ///
+    /// # Arguments
+    /// * `user` - User executing the swap
+    /// * `token_in` - Input token address
+    /// * `token_out` - Output token address
+    /// * `amount_out` - Exact amount of output tokens to buy
+    /// * `max_in` - Maximum input, aggregator fee included (slippage protection)
+    /// * `deadline` - Transaction deadline timestamp
+    ///
+    /// # Returns
+    /// * Input actually paid, aggregator fee included
+    ///
+    /// Uses reentrancy guard to prevent flash loan attacks
+    pub fn swap_exact_output_split(
+        env: Env,
+        user: Address,
+        token_in: Address,
+        token_out: Address,
+        amount_out: i128,
+        max_in: i128,
+        deadline: u64,
+    ) -> Result<i128, AstroSwapError> {
+        Self::swap_exact_output_split_internal(
+            &env,
+            &user,
+            &token_in,
+            &token_out,
+            amount_out,
+            max_in,
+            deadline,
+            PullMode::Transfer,
+        )
+    }
+
+    /// Exact-output split swap pulling the input from an allowance
+    ///
+    /// Same as `swap_exact_output_split`, but collects the input with
+    /// `transfer_from`. Approve the aggregator for `max_in` of `token_in` first.
+    pub fn swap_exact_output_split_allowance(
+        env: Env,
+        user: Address,
+        token_in: Address,
+        token_out: Address,
+        amount_out: i128,
+        max_in: i128,
+        deadline: u64,
+    ) -> Result<i128, AstroSwapError> {
+        Self::swap_exact_output_split_internal(
+            &env,
+            &user,
+            &token_in,
+            &token_out,
+            amount_out,
+            max_in,
+            deadline,
+            PullMode::Allowance,
+        )
+    }
+
+    /// Swap as much of `amount_in` as fits the price bounds, instead of
+    /// reverting when the full size cannot meet `min_out`
+    ///
+    /// Fills the largest part of the input whose execution price is within
+    /// `max_impact_bps` of the small-trade price and no worse than
+    /// `min_out / amount_in`. Only the filled input is pulled from the user,
+    /// so the rest never leaves their account; the transfer the wallet
+    /// authorizes is for the fill, as found by simulating this call.
+    ///
+    /// # Arguments
+    /// * `min_out` - Minimum output for the full size; the fill must meet it
+    ///   pro rata
+    /// * `max_impact_bps` - Maximum price impact vs the small-trade price
+    ///
+    /// # Returns
+    /// * Input filled, output received and input left unfilled
+    ///
+    /// Uses reentrancy guard to prevent flash loan attacks
+    #[allow(clippy::too_many_arguments)]
+    pub fn swap_partial(
+        env: Env,
+        user: Address,
+        token_in: Address,
+        token_out: Address,
+        amount_in: i128,
+        min_out: i128,
+        max_impact_bps: u32,
+        deadline: u64,
+    ) -> Result<PartialFill, AstroSwapError> {
+        Self::swap_partial_internal(
+            &env,
+            &user,
+            &token_in,
+            &token_out,
+            amount_in,
+            min_out,
+            max_impact_bps,
+            deadline,
+            PullMode::Transfer,
+        )
+    }
+
+    /// Partial-fill swap pulling the input from an allowance
+    ///
+    /// Same as `swap_partial`, but collects the fill with `transfer_from`.
+    /// Approve the aggregator for `amount_in` of `token_in` first; the
+    /// unfilled part of the allowance is left unspent.
+    #[allow(clippy::too_many_arguments)]
+    pub fn swap_partial_allowance(
+        env: Env,
+        user: Address,
+        token_in: Address,
+        token_out: Address,
+        amount_in: i128,
+        min_out: i128,
+        max_impact_bps: u32,
+        deadline: u64,
+    ) -> Result<PartialFill, AstroSwapError> {
+        Self::swap_partial_internal(
+            &env,
+            &user,
+            &token_in,
+            &token_out,
+            amount_in,
+            min_out,
+            max_impact_bps,
+            deadline,
+            PullMode::Allowance,
+        )
+    }
+
+    /// Execute several independent swaps, each along its own best route, in
+    /// one call
+    ///
+    /// Requests run in order, each against its own `min_out`. With
+    /// `BatchPolicy::AllOrNothing` any failing request reverts the batch. With
+    /// `BatchPolicy::BestEffort` a request without a route, or quoted below
+    /// its `min_out`, is skipped before any of its tokens move; a swap failing
+    /// once its input was pulled still reverts the batch.
+    ///
+    /// # Arguments
+    /// * `user` - User executing the swaps
+    /// * `requests` - Swaps to execute, 1 to `MAX_BATCH_REQUESTS`
+    /// * `deadline` - Transaction deadline timestamp
+    /// * `policy` - Whether a failing request reverts or is skipped
+    ///
+    /// # Returns
+    /// * Output of each request (0 if skipped) and the number executed
+    ///
+    /// Uses reentrancy guard to prevent flash loan attacks
+    pub fn swap_batch(
+        env: Env,
+        user: Address,
+        requests: Vec<SwapRequest>,
+        deadline: u64,
+        policy: BatchPolicy,
+    ) -> Result<BatchFill, AstroSwapError> {
         user.require_auth();
-        Self::require_not_paused(&env)?;
-        Self::check_deadline(&env, deadline)?;
+        require_not_paused(&env, &DataKey::Paused)?;
+        require_deadline(&env, deadline)?;
+        if requests.is_empty() || requests.len() > MAX_BATCH_REQUESTS {
+            return Err(AstroSwapError::InvalidArgument);
+        }
 
         // Acquire reentrancy lock
-        Self::acquire_lock(&env)?;
+        with_lock(&env, &DataKey::Locked, || {
+            let mut amounts_out = Vec::new(&env);
+            let mut executed = 0u32;
+            for request in requests.iter() {
+                let quote = Self::quote_swap(
+                    &env,
+                    &user,
+                    &request.token_in,
+                    &request.token_out,
+                    request.amount_in,
+                    request.min_out,
+                );
+                let (route, runner_up_output, min_out) = match (quote, policy) {
+                    (Ok(quote), _) => quote,
+                    (Err(_), BatchPolicy::BestEffort) => {
+                        amounts_out.push_back(0);
+                        continue;
+                    }
+                    (Err(err), BatchPolicy::AllOrNothing) => return Err(err),
+                };
+
+                let actual_out = Self::execute_route(
+                    &env,
+                    &user,
+                    &route,
+                    request.amount_in,
+                    deadline,
+                    PullMode::Transfer,
+                )?;
+                if actual_out < min_out {
+                    return Err(AstroSwapError::SlippageExceeded);
+                }
 
-        // Validate route
-        if route.steps.is_empty() {
-            Self::release_lock(&env);
-            return Err(AstroSwapError::InvalidPath);
-        }
+                let winner = route.steps.get(0).unwrap().protocol_id;
+                record_win(&env, winner, route.expected_output, runner_up_output)?;
 
-        // Execute the route
-        let actual_out = match Self::execute_route(&env, &user, &route, amount_in, deadline) {
-            Ok(out) => out,
-            Err(e) => {
-                Self::release_lock(&env);
-                return Err(e);
+                amounts_out.push_back(actual_out);
+                executed += 1;
             }
-        };
 
-        // Slippage check
-        if actual_out < min_out {
-            Self::release_lock(&env);
-            return Err(AstroSwapError::SlippageExceeded);
-        }
+            BatchSwapped {
+                user: user.clone(),
+                policy,
+                requests: requests.len(),
+                executed,
+                amounts_out: amounts_out.clone(),
+            }
+            .publish(&env);
+
+            extend_instance_ttl(&env);
+            Ok(BatchFill {
+                amounts_out,
+                executed,
+            })
+        })
+    }
 
-        // Release reentrancy lock
-        Self::release_lock(&env);
-        extend_instance_ttl(&env);
-        Ok(actual_out)
+    /// Token authorization a swap of `amount_in` of `token_in` needs from `user`
+    ///
+    /// For `PullMode::Transfer` the wallet must authorize the single
+    /// `token.transfer(user, spender, amount)` sub-invocation. For
+    /// `PullMode::Allowance` it must approve `allowance_shortfall` more for
+    /// `spender` before calling an `*_allowance` swap.
+    pub fn pull_authorization(
+        env: Env,
+        user: Address,
+        token_in: Address,
+        amount_in: i128,
+        mode: PullMode,
+    ) -> PullAuthorization {
+        pull::pull_authorization(&env, &user, &token_in, amount_in, mode)
+    }
+
+    // ==================== User Preferences ====================
+
+    /// Store the user's default max slippage in bps, or clear it with 0
+    ///
+    /// Swaps by `user` that pass `min_out = 0` then require at least the
+    /// quoted output, less the aggregator fee, less `max_slippage_bps`, so
+    /// integrations that cannot compute `min_out` still get protection.
+    pub fn set_slippage_preference(
+        env: Env,
+        user: Address,
+        max_slippage_bps: u32,
+    ) -> Result<(), AstroSwapError> {
+        user.require_auth();
+        if max_slippage_bps > BPS {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+        set_slippage_preference(&env, &user, max_slippage_bps);
+        Ok(())
     }
 
     // ==================== Route Finding ====================
@@ -221,33 +555,70 @@ impl AstroSwapAggregator {
         amount_in: i128,
     ) -> Result<SwapRoute, AstroSwapError> {
         extend_instance_ttl(&env);
-        Self::find_best_route_internal(&env, &token_in, &token_out, amount_in)
+        let (route, _) = Self::find_best_route_internal(&env, &token_in, &token_out, amount_in)?;
+        Ok(route)
+    }
+
+    /// Quote buying exactly `amount_out` of `token_out` across protocols
+    ///
+    /// Splits the order over the direct pools of up to `max_splits` protocols
+    /// (see `swap_exact_output_split`). The aggregator fee is the base fee;
+    /// volume discounts are not applied (see `user_fee_bps`).
+    ///
+    /// # Returns
+    /// * Per-protocol legs, the input they need and the aggregator fee on top
+    pub fn quote_exact_output_split(
+        env: Env,
+        token_in: Address,
+        token_out: Address,
+        amount_out: i128,
+    ) -> Result<SplitQuote, AstroSwapError> {
+        let fee_bps = get_config(&env).aggregator_fee_bps;
+        extend_instance_ttl(&env);
+        Self::quote_exact_output_split_internal(&env, &token_in, &token_out, amount_out, fee_bps)
     }
 
     /// Get quotes from all registered protocols for a swap
     ///
-    /// Returns a vector of (protocol_id, expected_output) pairs
+    /// Returns a vector of (protocol_id, expected_output) pairs. Same as
+    /// `get_quotes` without a protocol filter.
     pub fn get_all_quotes(
         env: Env,
         token_in: Address,
         token_out: Address,
         amount_in: i128,
     ) -> Vec<(u32, i128)> {
-        let mut quotes = Vec::new(&env);
-        let protocol_count = get_protocol_count(&env);
+        Self::get_quotes(env, token_in, token_out, amount_in, None)
+    }
 
-        for protocol_id in 0..protocol_count {
-            if let Some(adapter) = get_protocol(&env, protocol_id) {
-                if adapter.is_active {
-                    if let Ok(quote) = Self::get_protocol_quote_internal(
-                        &env,
-                        protocol_id,
-                        &token_in,
-                        &token_out,
-                        amount_in,
-                    ) {
-                        quotes.push_back((protocol_id, quote));
-                    }
+    /// Get quotes from the registered protocols, in quoting order
+    ///
+    /// Protocols on the priority list are quoted first. With `protocols`, only
+    /// those IDs are quoted. Once a quote within `early_stop_bps` of the oracle
+    /// fair price is found, the remaining protocols are not quoted.
+    ///
+    /// Returns a vector of (protocol_id, expected_output) pairs
+    pub fn get_quotes(
+        env: Env,
+        token_in: Address,
+        token_out: Address,
+        amount_in: i128,
+        protocols: Option<Vec<u32>>,
+    ) -> Vec<(u32, i128)> {
+        let mut quotes = Vec::new(&env);
+        let stop_at = Self::early_stop_output(&env, &token_in, &token_out, amount_in);
+
+        for protocol_id in quote_order(&env, &protocols).iter() {
+            if let Ok(quote) = Self::get_protocol_quote_internal(
+                &env,
+                protocol_id,
+                &token_in,
+                &token_out,
+                amount_in,
+            ) {
+                quotes.push_back((protocol_id, quote));
+                if stop_at.is_some_and(|stop_at| quote >= stop_at) {
+                    break;
                 }
             }
         }
@@ -269,10 +640,70 @@ impl AstroSwapAggregator {
         Self::get_protocol_quote_internal(&env, protocol_id, &token_in, &token_out, amount_in)
     }
 
+    /// Simulate a route against current pool state without executing it
+    ///
+    /// Walks the route hop by hop starting from `amount_in` minus the base
+    /// aggregator fee (volume discounts are not applied; see `user_fee_bps`).
+    /// Native hops are priced from the pair's reserves; external hops from
+    /// the protocol's quote.
+    ///
+    /// # Returns
+    /// * Per-hop input/output amounts, price impact and fees, and the route totals
+    pub fn simulate_route(
+        env: Env,
+        route: SwapRoute,
+        amount_in: i128,
+    ) -> Result<RouteSimulation, AstroSwapError> {
+        require_positive(amount_in)?;
+        let config = get_config(&env);
+        validate_route(&route, config.max_hops, |protocol_id| {
+            get_protocol(&env, protocol_id).is_some_and(|adapter| adapter.is_active)
+        })?;
+
+        let aggregator_fee = apply_bps(amount_in, config.aggregator_fee_bps)?;
+        let mut current_amount = amount_in - aggregator_fee;
+        let mut total_fee_bps = config.aggregator_fee_bps;
+        let mut hops = Vec::new(&env);
+
+        for step in route.steps.iter() {
+            let hop = if step.protocol_id == 0 {
+                simulate_native_hop(&env, &step, current_amount)?
+            } else {
+                let adapter =
+                    get_protocol(&env, step.protocol_id).ok_or(AstroSwapError::ProtocolNotFound)?;
+                let quote = Self::get_external_quote(
+                    &env,
+                    &adapter.factory_address,
+                    &step.token_in,
+                    &step.token_out,
+                    current_amount,
+                )?;
+                simulate_quoted_hop(&step, current_amount, quote, adapter.default_fee_bps)?
+            };
+
+            current_amount = hop.amount_out;
+            total_fee_bps += hop.fee_bps;
+            hops.push_back(hop);
+        }
+
+        extend_instance_ttl(&env);
+        Ok(RouteSimulation {
+            hops,
+            amount_in,
+            aggregator_fee,
+            amount_out: current_amount,
+            total_fee_bps,
+        })
+    }
+
     // ==================== Protocol Management ====================
 
     /// Register a new protocol adapter
     ///
+    /// Fails with `ProtocolExists` if the protocol is already registered; use
+    /// `update_protocol` to change its factory. A deregistered protocol can be
+    /// registered again.
+    ///
     /// # Arguments
     /// * `admin` - Must be contract admin
     /// * `protocol` - Protocol enum variant
@@ -288,6 +719,9 @@ impl AstroSwapAggregator {
         Self::require_admin(&env, &admin)?;
 
         let protocol_id = Self::protocol_to_id(&protocol);
+        if protocol_exists(&env, protocol_id) {
+            return Err(AstroSwapError::ProtocolExists);
+        }
 
         let adapter = ProtocolAdapter {
             protocol_id,
@@ -297,6 +731,7 @@ impl AstroSwapAggregator {
         };
 
         set_protocol(&env, protocol_id, &adapter);
+        clear_protocol_removed(&env, protocol_id);
 
         // Update protocol count if this is a new protocol
         let current_count = get_protocol_count(&env);
@@ -308,12 +743,15 @@ impl AstroSwapAggregator {
         Ok(())
     }
 
-    /// Enable or disable a protocol
-    pub fn set_protocol_active(
+    /// Change a registered protocol's factory address and default fee
+    ///
+    /// Keeps the protocol's active flag.
+    pub fn update_protocol(
         env: Env,
         admin: Address,
         protocol: Protocol,
-        is_active: bool,
+        factory_address: Address,
+        default_fee_bps: u32,
     ) -> Result<(), AstroSwapError> {
         Self::require_admin(&env, &admin)?;
 
@@ -322,67 +760,349 @@ impl AstroSwapAggregator {
         let mut adapter =
             get_protocol(&env, protocol_id).ok_or(AstroSwapError::ProtocolNotFound)?;
 
-        adapter.is_active = is_active;
+        adapter.factory_address = factory_address;
+        adapter.default_fee_bps = default_fee_bps;
         set_protocol(&env, protocol_id, &adapter);
 
         extend_instance_ttl(&env);
         Ok(())
     }
 
-    /// Get protocol adapter info
-    pub fn get_protocol_info(env: Env, protocol: Protocol) -> Option<ProtocolAdapter> {
-        let protocol_id = Self::protocol_to_id(&protocol);
-        extend_instance_ttl(&env);
-        get_protocol(&env, protocol_id)
-    }
-
-    // ==================== Admin Functions ====================
-
-    /// Update aggregator configuration
-    pub fn set_config(
+    /// Deregister a protocol
+    ///
+    /// Removes the adapter and leaves a tombstone for its ID; routing and
+    /// quoting skip it from then on. AstroSwap itself (Protocol 0) cannot be
+    /// deregistered, only disabled.
+    pub fn deregister_protocol(
         env: Env,
         admin: Address,
-        max_hops: u32,
-        max_splits: u32,
-        aggregator_fee_bps: u32,
+        protocol: Protocol,
     ) -> Result<(), AstroSwapError> {
         Self::require_admin(&env, &admin)?;
 
-        // Validate
-        if max_hops == 0 || max_hops > MAX_HOPS {
+        let protocol_id = Self::protocol_to_id(&protocol);
+        if protocol_id == 0 {
             return Err(AstroSwapError::InvalidArgument);
         }
-        if aggregator_fee_bps > 100 {
-            // Max 1%
-            return Err(AstroSwapError::FeeTooHigh);
+        if !protocol_exists(&env, protocol_id) {
+            return Err(AstroSwapError::ProtocolNotFound);
         }
 
-        let config = AggregatorConfig {
-            max_hops,
-            max_splits,
-            aggregator_fee_bps,
-        };
-        set_config(&env, &config);
+        remove_protocol(&env, protocol_id);
 
         extend_instance_ttl(&env);
         Ok(())
     }
 
-    /// Set fee recipient for aggregator fees
-    pub fn set_fee_recipient(
+    /// Enable or disable a protocol
+    pub fn set_protocol_active(
         env: Env,
         admin: Address,
-        recipient: Address,
+        protocol: Protocol,
+        is_active: bool,
     ) -> Result<(), AstroSwapError> {
         Self::require_admin(&env, &admin)?;
-        set_fee_recipient(&env, &recipient);
-        extend_instance_ttl(&env);
-        Ok(())
-    }
 
-    /// Transfer admin role
-    pub fn set_admin(env: Env, admin: Address, new_admin: Address) -> Result<(), AstroSwapError> {
-        Self::require_admin(&env, &admin)?;
+        let protocol_id = Self::protocol_to_id(&protocol);
+
+        let mut adapter =
+            get_protocol(&env, protocol_id).ok_or(AstroSwapError::ProtocolNotFound)?;
+
+        adapter.is_active = is_active;
+        set_protocol(&env, protocol_id, &adapter);
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set whether a protocol's adapter `swap` takes a deadline
+    ///
+    /// External adapters are called as `swap(recipient, token_in, min_out)`,
+    /// or `swap(recipient, token_in, min_out, deadline)` once this is set,
+    /// forwarding the route's deadline so the adapter can enforce it too.
+    pub fn set_protocol_deadline(
+        env: Env,
+        admin: Address,
+        protocol: Protocol,
+        takes_deadline: bool,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+
+        let protocol_id = Self::protocol_to_id(&protocol);
+        if protocol_id == 0 || !protocol_exists(&env, protocol_id) {
+            return Err(AstroSwapError::ProtocolNotFound);
+        }
+
+        set_takes_deadline(&env, protocol_id, takes_deadline);
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Check if a protocol's adapter `swap` takes a deadline
+    pub fn protocol_takes_deadline(env: Env, protocol: Protocol) -> bool {
+        takes_deadline(&env, Self::protocol_to_id(&protocol))
+    }
+
+    /// Override the per-hop slippage tolerance on a protocol's hops, or
+    /// fall back to the configured default with `None`
+    ///
+    /// Tight for protocols with stable pools, loose for illiquid ones; the
+    /// same 1 to 1000 bps bounds as the default apply.
+    pub fn set_protocol_hop_slippage(
+        env: Env,
+        admin: Address,
+        protocol: Protocol,
+        hop_slippage_bps: Option<u32>,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+
+        let protocol_id = Self::protocol_to_id(&protocol);
+        if !protocol_exists(&env, protocol_id) {
+            return Err(AstroSwapError::ProtocolNotFound);
+        }
+        if let Some(bps) = hop_slippage_bps {
+            Self::require_hop_slippage(bps)?;
+        }
+
+        set_protocol_hop_slippage(&env, protocol_id, hop_slippage_bps);
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Get the per-hop slippage tolerance applied to a protocol's hops, in bps
+    pub fn protocol_hop_slippage(env: Env, protocol: Protocol) -> u32 {
+        Self::hop_slippage_bps(&env, Self::protocol_to_id(&protocol), &get_config(&env))
+    }
+
+    /// Get protocol adapter info
+    pub fn get_protocol_info(env: Env, protocol: Protocol) -> Option<ProtocolAdapter> {
+        let protocol_id = Self::protocol_to_id(&protocol);
+        extend_instance_ttl(&env);
+        get_protocol(&env, protocol_id)
+    }
+
+    /// Check if a protocol was deregistered (and not registered again)
+    pub fn is_protocol_removed(env: Env, protocol: Protocol) -> bool {
+        is_protocol_removed(&env, Self::protocol_to_id(&protocol))
+    }
+
+    // ==================== Admin Functions ====================
+
+    /// Update aggregator configuration
+    ///
+    /// `max_splits` is between 1 and `SPLIT_CHUNKS` (10) protocols.
+    /// `hop_slippage_bps` is the tolerated shortfall of each hop below its
+    /// quoted output, between 1 and 1000 bps (0.01% to 10%).
+    pub fn set_config(
+        env: Env,
+        admin: Address,
+        max_hops: u32,
+        max_splits: u32,
+        aggregator_fee_bps: u32,
+        hop_slippage_bps: u32,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+
+        // Validate
+        if max_hops == 0 {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+        if max_hops > MAX_HOPS {
+            return Err(AstroSwapError::RouteTooLong);
+        }
+        if max_splits == 0 || max_splits > MAX_SPLITS {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+        require_bps_range(aggregator_fee_bps, MAX_FEE_BPS)?;
+        Self::require_hop_slippage(hop_slippage_bps)?;
+
+        let config = AggregatorConfig {
+            max_hops,
+            max_splits,
+            aggregator_fee_bps,
+            hop_slippage_bps,
+        };
+        set_config(&env, &config);
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Replace the volume-based fee tiers
+    ///
+    /// Tiers must start at zero volume, have strictly increasing thresholds and
+    /// non-increasing fees. A tier's fee never exceeds the configured
+    /// `aggregator_fee_bps`.
+    pub fn set_fee_tiers(
+        env: Env,
+        admin: Address,
+        tiers: Vec<FeeTier>,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+        volume::validate_fee_tiers(&tiers)?;
+        set_fee_tiers(&env, &tiers);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set fee recipient for aggregator fees
+    pub fn set_fee_recipient(
+        env: Env,
+        admin: Address,
+        recipient: Address,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+        set_fee_recipient(&env, &recipient);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Enable or update the oracle sanity bound on external protocol quotes
+    ///
+    /// Once set, a quote from any protocol other than AstroSwap is compared
+    /// with the output implied by the oracle's TWAP cross-rate of the two
+    /// tokens. Quotes deviating by more than `max_deviation_bps` are dropped
+    /// before route selection, as are quotes that cannot be checked because
+    /// the oracle has no TWAP for one of the tokens. Both TWAPs must be quoted
+    /// with the same decimals.
+    ///
+    /// # Arguments
+    /// * `oracle` - AstroSwap oracle providing TWAPs
+    /// * `twap_window` - TWAP window in seconds
+    /// * `max_deviation_bps` - Allowed deviation from the TWAP-implied output
+    pub fn set_quote_guard(
+        env: Env,
+        admin: Address,
+        oracle: Address,
+        twap_window: u64,
+        max_deviation_bps: u32,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+        if twap_window == 0 || max_deviation_bps > BPS {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+        set_quote_guard(
+            &env,
+            &QuoteGuard {
+                oracle,
+                twap_window,
+                max_deviation_bps,
+            },
+        );
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Stop checking external protocol quotes against the oracle
+    pub fn remove_quote_guard(env: Env, admin: Address) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+        remove_quote_guard(&env);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set the protocols quoted first, in order
+    ///
+    /// Routing and quoting walk these IDs before the remaining ones (in ID
+    /// order). IDs must be registered and listed once; an empty list restores
+    /// plain ID order.
+    pub fn set_protocol_priority(
+        env: Env,
+        admin: Address,
+        priority: Vec<u32>,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+        for (index, protocol_id) in priority.iter().enumerate() {
+            if !protocol_exists(&env, protocol_id) {
+                return Err(AstroSwapError::ProtocolNotFound);
+            }
+            if priority.first_index_of(protocol_id) != Some(index as u32) {
+                return Err(AstroSwapError::InvalidArgument);
+            }
+        }
+        set_protocol_priority(&env, &priority);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Stop quoting once a quote within `early_stop_bps` of the oracle fair
+    /// price is found
+    ///
+    /// The fair output comes from the quote guard's oracle TWAPs, so early
+    /// stop only applies while a quote guard is set and the oracle can price
+    /// both tokens. 0 disables it and every protocol is quoted.
+    pub fn set_early_stop_bps(
+        env: Env,
+        admin: Address,
+        early_stop_bps: u32,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+        if early_stop_bps > BPS {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+        set_early_stop_bps(&env, early_stop_bps);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Allow pathfinding to route through `token`
+    ///
+    /// Connectors are well-known, liquid tokens (XLM, USDC, the protocol
+    /// token). Two-hop routes only ever pass through a connector, which keeps
+    /// the search small and out of scam tokens. At most `MAX_CONNECTORS`.
+    pub fn add_connector(env: Env, admin: Address, token: Address) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+        let mut connectors = get_connectors(&env);
+        if connectors.contains(&token) || connectors.len() >= MAX_CONNECTORS {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+        connectors.push_back(token.clone());
+        set_connectors(&env, &connectors);
+        extend_instance_ttl(&env);
+
+        ConnectorAdded { token }.publish(&env);
+        Ok(())
+    }
+
+    /// Stop routing through `token`
+    pub fn remove_connector(
+        env: Env,
+        admin: Address,
+        token: Address,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+        let mut connectors = get_connectors(&env);
+        let index = connectors
+            .first_index_of(&token)
+            .ok_or(AstroSwapError::InvalidToken)?;
+        connectors.remove(index);
+        set_connectors(&env, &connectors);
+        extend_instance_ttl(&env);
+
+        ConnectorRemoved { token }.publish(&env);
+        Ok(())
+    }
+
+    /// Set or clear the analytics contract executed swaps are reported to
+    ///
+    /// The aggregator must be an allowlisted reporter of the analytics
+    /// contract. Reports are fire-and-forget and never fail a swap.
+    pub fn set_analytics(
+        env: Env,
+        admin: Address,
+        analytics: Option<Address>,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+        set_analytics(&env, &analytics);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Transfer admin role
+    pub fn set_admin(env: Env, admin: Address, new_admin: Address) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
         set_admin(&env, &new_admin);
         extend_instance_ttl(&env);
         Ok(())
@@ -410,6 +1130,36 @@ impl AstroSwapAggregator {
         get_admin(&env)
     }
 
+    /// Get the external quote guard, if enabled
+    pub fn quote_guard(env: Env) -> Option<QuoteGuard> {
+        extend_instance_ttl(&env);
+        get_quote_guard(&env)
+    }
+
+    /// Get the protocols quoted first, in order
+    pub fn protocol_priority(env: Env) -> Vec<u32> {
+        extend_instance_ttl(&env);
+        get_protocol_priority(&env)
+    }
+
+    /// Get the tokens two-hop routes may pass through, in the order added
+    pub fn connectors(env: Env) -> Vec<Address> {
+        extend_instance_ttl(&env);
+        get_connectors(&env)
+    }
+
+    /// Get the early-stop distance from the oracle fair price (0 = disabled)
+    pub fn early_stop_bps(env: Env) -> u32 {
+        extend_instance_ttl(&env);
+        get_early_stop_bps(&env)
+    }
+
+    /// Get the analytics contract executed swaps are reported to
+    pub fn analytics(env: Env) -> Option<Address> {
+        extend_instance_ttl(&env);
+        get_analytics(&env)
+    }
+
     /// Get fee recipient
     pub fn fee_recipient(env: Env) -> Option<Address> {
         extend_instance_ttl(&env);
@@ -421,69 +1171,298 @@ impl AstroSwapAggregator {
         is_paused(&env)
     }
 
-    /// Get number of registered protocols
+    /// Get the volume-based fee tiers
+    pub fn fee_tiers(env: Env) -> Vec<FeeTier> {
+        extend_instance_ttl(&env);
+        fee_tiers(&env)
+    }
+
+    /// Get a user's rolling 30-day swap volume
+    pub fn user_volume(env: Env, user: Address) -> i128 {
+        rolling_volume(&env, &user)
+    }
+
+    /// Get a user's current fee tier
+    pub fn user_fee_tier(env: Env, user: Address) -> FeeTier {
+        tier_for_volume(&env, rolling_volume(&env, &user))
+    }
+
+    /// Get the aggregator fee a user currently pays, in basis points
+    pub fn user_fee_bps(env: Env, user: Address) -> u32 {
+        effective_fee_bps(&env, &user, &get_config(&env))
+    }
+
+    /// Get a user's default max slippage in bps, if set
+    pub fn slippage_preference(env: Env, user: Address) -> Option<u32> {
+        get_slippage_preference(&env, &user)
+    }
+
+    /// Get the protocol ID bound: one past the highest ID ever registered
+    ///
+    /// IDs below it may be unused or deregistered; `get_protocol_info`
+    /// returns `None` for those.
     pub fn protocol_count(env: Env) -> u32 {
         extend_instance_ttl(&env);
         get_protocol_count(&env)
     }
 
+    /// Get the routing statistics of a protocol (zeroed if it never routed)
+    pub fn protocol_stats(env: Env, protocol_id: u32) -> ProtocolStats {
+        get_protocol_stats(&env, protocol_id)
+    }
+
+    /// Get the routing statistics of every registered protocol, in ID order
+    pub fn all_protocol_stats(env: Env) -> Vec<(u32, ProtocolStats)> {
+        all_protocol_stats(&env)
+    }
+
+    /// Get a protocol's average improvement over the second-best quote when
+    /// it won a contested `swap`, in basis points
+    pub fn avg_improvement_bps(env: Env, protocol_id: u32) -> u32 {
+        average_improvement_bps(&get_protocol_stats(&env, protocol_id))
+    }
+
+    /// Get the registered protocol that won the most `swap`s, if any
+    pub fn best_protocol(env: Env) -> Option<u32> {
+        best_protocol(&env)
+    }
+
     // ==================== Internal Functions ====================
 
     /// Find best route across all protocols
+    ///
+    /// Protocols are tried in quoting order, stopping early once one is
+    /// within `early_stop_bps` of the oracle fair price. Unless stopped, and
+    /// if `max_hops` allows, two-hop routes through each connector token are
+    /// tried next, each hop on its best protocol. Also returns the best
+    /// output quoted by any other route tried (0 if none)
     fn find_best_route_internal(
         env: &Env,
         token_in: &Address,
         token_out: &Address,
         amount_in: i128,
-    ) -> Result<SwapRoute, AstroSwapError> {
-        let protocol_count = get_protocol_count(env);
+    ) -> Result<(SwapRoute, i128), AstroSwapError> {
         let config = get_config(env);
+        let stop_at = Self::early_stop_output(env, token_in, token_out, amount_in);
 
         let mut best_route: Option<SwapRoute> = None;
         let mut best_output: i128 = 0;
+        let mut runner_up_output: i128 = 0;
+        let mut stopped = false;
 
         // Try direct swaps on each protocol
-        for protocol_id in 0..protocol_count {
-            if let Some(adapter) = get_protocol(env, protocol_id) {
-                if !adapter.is_active {
+        for protocol_id in quote_order(env, &None).iter() {
+            let Some(adapter) = get_protocol(env, protocol_id) else {
+                continue;
+            };
+            if !adapter.is_active {
+                continue;
+            }
+
+            // Try direct route
+            if let Ok((output, pair_address)) = Self::get_protocol_quote_with_pair(
+                env,
+                protocol_id,
+                &adapter,
+                token_in,
+                token_out,
+                amount_in,
+            ) {
+                if output <= best_output {
+                    runner_up_output = runner_up_output.max(output);
+                } else {
+                    runner_up_output = best_output;
+                    best_output = output;
+
+                    let step = RouteStep {
+                        protocol_id,
+                        pool_address: pair_address,
+                        token_in: token_in.clone(),
+                        token_out: token_out.clone(),
+                        amount_in,
+                        expected_out: output,
+                    };
+
+                    let mut steps = Vec::new(env);
+                    steps.push_back(step);
+
+                    best_route = Some(SwapRoute {
+                        steps,
+                        expected_output: output,
+                        total_fee_bps: adapter.default_fee_bps + config.aggregator_fee_bps,
+                    });
+                }
+                if stop_at.is_some_and(|stop_at| output >= stop_at) {
+                    stopped = true;
+                    break;
+                }
+            }
+        }
+
+        // Two-hop routes through connector tokens
+        if !stopped && config.max_hops >= 2 {
+            for connector in get_connectors(env).iter() {
+                if connector == *token_in || connector == *token_out {
                     continue;
                 }
+                let Some((first, first_fee)) = Self::best_hop(env, token_in, &connector, amount_in)
+                else {
+                    continue;
+                };
+                let Some((second, second_fee)) =
+                    Self::best_hop(env, &connector, token_out, first.expected_out)
+                else {
+                    continue;
+                };
 
-                // Try direct route
-                if let Ok((output, pair_address)) = Self::get_protocol_quote_with_pair(
-                    env,
+                let output = second.expected_out;
+                if output <= best_output {
+                    runner_up_output = runner_up_output.max(output);
+                    continue;
+                }
+                runner_up_output = best_output;
+                best_output = output;
+
+                let mut steps = Vec::new(env);
+                steps.push_back(first);
+                steps.push_back(second);
+                best_route = Some(SwapRoute {
+                    steps,
+                    expected_output: output,
+                    total_fee_bps: first_fee + second_fee + config.aggregator_fee_bps,
+                });
+                if stop_at.is_some_and(|stop_at| output >= stop_at) {
+                    break;
+                }
+            }
+        }
+
+        let best_route = best_route.ok_or(AstroSwapError::RouteNotFound)?;
+        Ok((best_route, runner_up_output))
+    }
+
+    /// Best single-pool hop over the active protocols, with the protocol's
+    /// default fee
+    fn best_hop(
+        env: &Env,
+        token_in: &Address,
+        token_out: &Address,
+        amount_in: i128,
+    ) -> Option<(RouteStep, u32)> {
+        let mut best: Option<(RouteStep, u32)> = None;
+        for protocol_id in quote_order(env, &None).iter() {
+            let Some(adapter) = get_protocol(env, protocol_id) else {
+                continue;
+            };
+            let Ok((output, pool_address)) = Self::get_protocol_quote_with_pair(
+                env,
+                protocol_id,
+                &adapter,
+                token_in,
+                token_out,
+                amount_in,
+            ) else {
+                continue;
+            };
+            if best
+                .as_ref()
+                .is_some_and(|(step, _)| output <= step.expected_out)
+            {
+                continue;
+            }
+            best = Some((
+                RouteStep {
                     protocol_id,
-                    &adapter,
-                    token_in,
-                    token_out,
+                    pool_address,
+                    token_in: token_in.clone(),
+                    token_out: token_out.clone(),
                     amount_in,
-                ) {
-                    if output > best_output {
-                        best_output = output;
-
-                        let step = RouteStep {
-                            protocol_id,
-                            pool_address: pair_address,
-                            token_in: token_in.clone(),
-                            token_out: token_out.clone(),
-                            amount_in,
-                            expected_out: output,
-                        };
-
-                        let mut steps = Vec::new(env);
-                        steps.push_back(step);
-
-                        best_route = Some(SwapRoute {
-                            steps,
-                            expected_output: output,
-                            total_fee_bps: adapter.default_fee_bps + config.aggregator_fee_bps,
-                        });
-                    }
+                    expected_out: output,
+                },
+                adapter.default_fee_bps,
+            ));
+        }
+        best
+    }
+
+    /// Cheapest split of an exact-output swap over the active protocols'
+    /// direct pools, with an aggregator fee of `fee_bps` on top
+    fn quote_exact_output_split_internal(
+        env: &Env,
+        token_in: &Address,
+        token_out: &Address,
+        amount_out: i128,
+        fee_bps: u32,
+    ) -> Result<SplitQuote, AstroSwapError> {
+        require_positive(amount_out)?;
+        let config = get_config(env);
+
+        // (protocol_id, pool, reserve_in, reserve_out, fee_bps); native pools are
+        // priced from their reserves, external protocols by their quote
+        let mut sources: Vec<(u32, Address, i128, i128, u32)> = Vec::new(env);
+        for adapter in registered_protocols(env).iter() {
+            if !adapter.is_active {
+                continue;
+            }
+            if adapter.protocol_id != 0 {
+                sources.push_back((adapter.protocol_id, adapter.factory_address, 0, 0, 0));
+                continue;
+            }
+
+            let factory = FactoryClient::new(env, &adapter.factory_address);
+            let Some(pair) = factory.get_pair(token_in, token_out) else {
+                continue;
+            };
+            let pair_client = PairClient::new(env, &pair);
+            let (reserve_0, reserve_1) = pair_client.get_reserves();
+            let (reserve_in, reserve_out) = if *token_in == pair_client.token_0() {
+                (reserve_0, reserve_1)
+            } else {
+                (reserve_1, reserve_0)
+            };
+            sources.push_back((0, pair, reserve_in, reserve_out, pair_client.fee_bps()));
+        }
+
+        let allocations = allocate_exact_output(
+            env,
+            amount_out,
+            sources.len(),
+            config.max_splits.max(1),
+            |source, amount_out| {
+                let (protocol_id, pool, reserve_in, reserve_out, fee_bps) =
+                    sources.get(source).unwrap();
+                if protocol_id == 0 {
+                    get_amount_in(amount_out, reserve_in, reserve_out, fee_bps)
+                } else {
+                    Self::get_external_quote_in(env, &pool, token_in, token_out, amount_out)
                 }
+            },
+        )?;
+
+        let mut legs = Vec::new(env);
+        let mut amount_in: i128 = 0;
+        for (source, (leg_out, leg_in)) in allocations.iter().enumerate() {
+            if leg_out == 0 {
+                continue;
             }
+            let (protocol_id, pool_address, _, _, _) = sources.get(source as u32).unwrap();
+            amount_in = amount_in
+                .checked_add(leg_in)
+                .ok_or(AstroSwapError::Overflow)?;
+            legs.push_back(SplitLeg {
+                protocol_id,
+                pool_address,
+                amount_in: leg_in,
+                amount_out: leg_out,
+            });
         }
 
-        best_route.ok_or(AstroSwapError::RouteNotFound)
+        Ok(SplitQuote {
+            legs,
+            amount_out,
+            amount_in,
+            aggregator_fee: fee_on_top(amount_in, fee_bps)?,
+        })
     }
 
     /// Get quote from a specific protocol
@@ -521,117 +1500,551 @@ impl AstroSwapAggregator {
         )
     }
 
-    /// Get quote and pair address from a specific protocol
-    fn get_protocol_quote_with_pair(
+    /// Get quote and pair address from a specific protocol
+    fn get_protocol_quote_with_pair(
+        env: &Env,
+        protocol_id: u32,
+        adapter: &ProtocolAdapter,
+        token_in: &Address,
+        token_out: &Address,
+        amount_in: i128,
+    ) -> Result<(i128, Address), AstroSwapError> {
+        if !adapter.is_active {
+            return Err(AstroSwapError::ProtocolNotFound);
+        }
+
+        // For AstroSwap (protocol 0), use our native interface
+        if protocol_id == 0 {
+            return Self::get_astroswap_quote_with_pair(
+                env,
+                &adapter.factory_address,
+                token_in,
+                token_out,
+                amount_in,
+            );
+        }
+
+        // For other protocols, use generic interface
+        // (For now, just return factory as pool address - external protocols may vary)
+        let quote = Self::get_external_quote(
+            env,
+            &adapter.factory_address,
+            token_in,
+            token_out,
+            amount_in,
+        )?;
+        Ok((quote, adapter.factory_address.clone()))
+    }
+
+    /// Get quote from AstroSwap (native protocol)
+    fn get_astroswap_quote(
+        env: &Env,
+        factory: &Address,
+        token_in: &Address,
+        token_out: &Address,
+        amount_in: i128,
+    ) -> Result<i128, AstroSwapError> {
+        let (quote, _pair) = Self::get_astroswap_quote_with_pair(env, factory, token_in, token_out, amount_in)?;
+        Ok(quote)
+    }
+
+    /// Get quote and pair address from AstroSwap (native protocol)
+    fn get_astroswap_quote_with_pair(
+        env: &Env,
+        factory: &Address,
+        token_in: &Address,
+        token_out: &Address,
+        amount_in: i128,
+    ) -> Result<(i128, Address), AstroSwapError> {
+        // Get pair address from factory
+        let pair_address = FactoryClient::new(env, factory).get_pair(token_in, token_out);
+
+        let pair = pair_address.ok_or(AstroSwapError::PairNotFound)?;
+        let pair_client = PairClient::new(env, &pair);
+
+        // Get reserves and calculate output
+        let (reserve_0, reserve_1) = pair_client.get_reserves();
+        let token_0 = pair_client.token_0();
+        let fee_bps = pair_client.fee_bps();
+
+        let (reserve_in, reserve_out) = if *token_in == token_0 {
+            (reserve_0, reserve_1)
+        } else {
+            (reserve_1, reserve_0)
+        };
+
+        // Calculate amount out using AMM formula
+        let quote = astroswap_shared::get_amount_out(amount_in, reserve_in, reserve_out, fee_bps)?;
+        Ok((quote, pair))
+    }
+
+    /// Get quote from external protocol using generic interface
+    ///
+    /// With a quote guard set, the quote must also pass `check_external_quote`.
+    fn get_external_quote(
+        env: &Env,
+        router: &Address,
+        token_in: &Address,
+        token_out: &Address,
+        amount_in: i128,
+    ) -> Result<i128, AstroSwapError> {
+        let quote =
+            Self::get_unchecked_external_quote(env, router, token_in, token_out, amount_in)?;
+        Self::check_external_quote(env, token_in, token_out, amount_in, quote)?;
+        Ok(quote)
+    }
+
+    /// Compare an external quote with the output implied by the oracle TWAPs
+    ///
+    /// Does nothing until a quote guard is set. Fails with
+    /// `TwapDeviationExceeded` if the quote is too far from the TWAP-implied
+    /// output, or `QuoteStale` if the oracle cannot price both tokens.
+    fn check_external_quote(
+        env: &Env,
+        token_in: &Address,
+        token_out: &Address,
+        amount_in: i128,
+        quote: i128,
+    ) -> Result<(), AstroSwapError> {
+        let guard = match get_quote_guard(env) {
+            Some(guard) => guard,
+            None => return Ok(()),
+        };
+
+        let twap_amount_out = Self::twap_amount_out(env, &guard, token_in, token_out, amount_in)?;
+        if calculate_deviation_bps(quote, twap_amount_out)? > guard.max_deviation_bps {
+            return Err(AstroSwapError::TwapDeviationExceeded);
+        }
+        Ok(())
+    }
+
+    /// Output implied by the oracle's TWAP cross-rate of the two tokens, in
+    /// `token_out` units
+    ///
+    /// Fails with `QuoteStale` if the oracle cannot price both tokens.
+    fn twap_amount_out(
+        env: &Env,
+        guard: &QuoteGuard,
+        token_in: &Address,
+        token_out: &Address,
+        amount_in: i128,
+    ) -> Result<i128, AstroSwapError> {
+        let oracle = OracleClient::new(env, &guard.oracle);
+        let twap_in = oracle.try_get_twap(token_in, guard.twap_window)?;
+        let twap_out = oracle.try_get_twap(token_out, guard.twap_window)?;
+        if twap_in <= 0 || twap_out <= 0 {
+            return Err(AstroSwapError::QuoteStale);
+        }
+        // TWAPs price whole tokens, so convert at 18 decimals
+        let decimals_in = token::Client::new(env, token_in).decimals();
+        let decimals_out = token::Client::new(env, token_out).decimals();
+        let value_out = mul_div_down(to_precision(amount_in, decimals_in)?, twap_in, twap_out)?;
+        from_precision(value_out, decimals_out, Rounding::Down)
+    }
+
+    /// Output at which quoting can stop: `early_stop_bps` below the
+    /// TWAP-implied fair output
+    ///
+    /// None while early stop is disabled, no quote guard is set, or the
+    /// oracle cannot price both tokens.
+    fn early_stop_output(
+        env: &Env,
+        token_in: &Address,
+        token_out: &Address,
+        amount_in: i128,
+    ) -> Option<i128> {
+        let early_stop_bps = get_early_stop_bps(env);
+        if early_stop_bps == 0 {
+            return None;
+        }
+        let guard = get_quote_guard(env)?;
+        let fair_out = Self::twap_amount_out(env, &guard, token_in, token_out, amount_in).ok()?;
+        Some(fair_out - apply_bps(fair_out, early_stop_bps).ok()?)
+    }
+
+    /// Input an external protocol needs for `amount_out`, from its
+    /// `get_amounts_in`
+    ///
+    /// With a quote guard set, the quote must also pass `check_external_quote`.
+    fn get_external_quote_in(
+        env: &Env,
+        router: &Address,
+        token_in: &Address,
+        token_out: &Address,
+        amount_out: i128,
+    ) -> Result<i128, AstroSwapError> {
+        let result = env.try_invoke_contract::<i128, soroban_sdk::Error>(
+            router,
+            &Symbol::new(env, "get_amounts_in"),
+            Vec::from_array(
+                env,
+                [
+                    amount_out.into_val(env),
+                    token_in.to_val(),
+                    token_out.to_val(),
+                ],
+            ),
+        );
+        let amount_in = match result {
+            Ok(Ok(amount)) if amount > 0 => amount,
+            _ => return Err(AstroSwapError::ProtocolNotFound),
+        };
+
+        Self::check_external_quote(env, token_in, token_out, amount_in, amount_out)?;
+        Ok(amount_in)
+    }
+
+    /// Raw quote from an external protocol's `get_amounts_out`
+    fn get_unchecked_external_quote(
+        env: &Env,
+        router: &Address,
+        token_in: &Address,
+        token_out: &Address,
+        amount_in: i128,
+    ) -> Result<i128, AstroSwapError> {
+        // Generic quote interface - most DEXs implement this
+        // Try "get_amount_out" or similar
+        // Note: try_invoke_contract returns Result<Result<T, E>, InvokeError>
+        // We need to flatten both error layers
+        let result = env.try_invoke_contract::<i128, soroban_sdk::Error>(
+            router,
+            &Symbol::new(env, "get_amounts_out"),
+            Vec::from_array(
+                env,
+                [
+                    amount_in.into_val(env),
+                    token_in.to_val(),
+                    token_out.to_val(),
+                ],
+            ),
+        );
+
+        match result {
+            Ok(Ok(amount)) => Ok(amount),
+            _ => Err(AstroSwapError::ProtocolNotFound),
+        }
+    }
+
+    /// Find the best route and execute it, collecting the input per `pull`
+    ///
+    /// Uses reentrancy guard to prevent flash loan attacks
+    #[allow(clippy::too_many_arguments)]
+    fn swap_internal(
+        env: &Env,
+        user: &Address,
+        token_in: &Address,
+        token_out: &Address,
+        amount_in: i128,
+        min_out: i128,
+        deadline: u64,
+        pull: PullMode,
+    ) -> Result<i128, AstroSwapError> {
+        user.require_auth();
+        require_not_paused(env, &DataKey::Paused)?;
+        require_deadline(env, deadline)?;
+
+        // Acquire reentrancy lock
+        with_lock(env, &DataKey::Locked, || {
+            let (route, runner_up_output, min_out) =
+                Self::quote_swap(env, user, token_in, token_out, amount_in, min_out)?;
+
+            // Execute the route
+            let actual_out = Self::execute_route(env, user, &route, amount_in, deadline, pull)?;
+
+            // Final slippage check
+            if actual_out < min_out {
+                return Err(AstroSwapError::SlippageExceeded);
+            }
+
+            let winner = route.steps.get(0).unwrap().protocol_id;
+            record_win(env, winner, route.expected_output, runner_up_output)?;
+
+            extend_instance_ttl(env);
+            Ok(actual_out)
+        })
+    }
+
+    /// Best route for a swap, its runner-up output and the `min_out` to
+    /// enforce, failing with `SlippageExceeded` if the quote is below it
+    fn quote_swap(
         env: &Env,
-        protocol_id: u32,
-        adapter: &ProtocolAdapter,
+        user: &Address,
         token_in: &Address,
         token_out: &Address,
         amount_in: i128,
-    ) -> Result<(i128, Address), AstroSwapError> {
-        if !adapter.is_active {
-            return Err(AstroSwapError::ProtocolNotFound);
+        min_out: i128,
+    ) -> Result<(SwapRoute, i128, i128), AstroSwapError> {
+        // Validate amounts
+        require_positive(amount_in)?;
+
+        // Find the best route
+        let (route, runner_up_output) =
+            Self::find_best_route_internal(env, token_in, token_out, amount_in)?;
+        let min_out = Self::resolve_min_out(env, user, route.expected_output, min_out)?;
+
+        // Verify minimum output
+        if route.expected_output < min_out {
+            return Err(AstroSwapError::SlippageExceeded);
         }
+        Ok((route, runner_up_output, min_out))
+    }
 
-        // For AstroSwap (protocol 0), use our native interface
-        if protocol_id == 0 {
-            return Self::get_astroswap_quote_with_pair(
-                env,
-                &adapter.factory_address,
-                token_in,
-                token_out,
-                amount_in,
-            );
+    /// `min_out` to enforce: as given when positive, otherwise
+    /// `expected_output` (quoted before the aggregator fee) less the user's
+    /// fee and slippage preference, or no bound without a preference
+    fn resolve_min_out(
+        env: &Env,
+        user: &Address,
+        expected_output: i128,
+        min_out: i128,
+    ) -> Result<i128, AstroSwapError> {
+        if min_out > 0 {
+            return Ok(min_out);
         }
+        let Some(max_slippage_bps) = get_slippage_preference(env, user) else {
+            return Ok(min_out);
+        };
 
-        // For other protocols, use generic interface
-        // (For now, just return factory as pool address - external protocols may vary)
-        let quote = Self::get_external_quote(
-            env,
-            &adapter.factory_address,
-            token_in,
-            token_out,
-            amount_in,
-        )?;
-        Ok((quote, adapter.factory_address.clone()))
+        // Output is concave in the input, so this never exceeds the quote
+        // for the input left after the fee
+        let fee_bps = effective_fee_bps(env, user, &get_config(env));
+        let net_output = expected_output - apply_bps(expected_output, fee_bps)?;
+        Ok(net_output - apply_bps(net_output, max_slippage_bps)?)
     }
 
-    /// Get quote from AstroSwap (native protocol)
-    fn get_astroswap_quote(
+    /// Find the largest fill within the price bounds and execute it,
+    /// collecting the fill per `pull`
+    ///
+    /// Uses reentrancy guard to prevent flash loan attacks
+    #[allow(clippy::too_many_arguments)]
+    fn swap_partial_internal(
         env: &Env,
-        factory: &Address,
+        user: &Address,
         token_in: &Address,
         token_out: &Address,
         amount_in: i128,
+        min_out: i128,
+        max_impact_bps: u32,
+        deadline: u64,
+        pull: PullMode,
+    ) -> Result<PartialFill, AstroSwapError> {
+        user.require_auth();
+        require_not_paused(env, &DataKey::Paused)?;
+        require_deadline(env, deadline)?;
+
+        // Acquire reentrancy lock
+        with_lock(env, &DataKey::Locked, || {
+            require_positive(amount_in)?;
+            require_bps_range(max_impact_bps, BPS)?;
+
+            // Quotes are for the input left after the aggregator fee, so the
+            // bounds hold for what the pools actually receive
+            let fee_bps = effective_fee_bps(env, user, &get_config(env));
+            let net_quote = |fill: i128| -> Result<i128, AstroSwapError> {
+                let net_in = fill - apply_bps(fill, fee_bps)?;
+                let (route, _) = Self::find_best_route_internal(env, token_in, token_out, net_in)?;
+                Ok(route.expected_output)
+            };
+
+            // Small-trade reference price from a slice of the order
+            let probe_in = (amount_in / PRICE_PROBE_DIVISOR).max(1);
+            let probe_out = net_quote(probe_in)?;
+
+            // Pro-rata share of `min_out`, the floor for the fill's output
+            let limit_out = |fill: i128| mul_div_up(min_out, fill, amount_in);
+            let min_fill_out = |fill: i128| -> Result<i128, AstroSwapError> {
+                let at_reference = mul_div_down(fill, probe_out, probe_in)?;
+                let bounded = at_reference - apply_bps(at_reference, max_impact_bps)?;
+                Ok(bounded.max(limit_out(fill)?))
+            };
+
+            let filled = largest_fill(amount_in, |fill| {
+                match (net_quote(fill), min_fill_out(fill)) {
+                    (Ok(quoted), Ok(floor)) => quoted >= floor,
+                    _ => false,
+                }
+            });
+            if filled == 0 {
+                return Err(AstroSwapError::SlippageExceeded);
+            }
+
+            let (route, runner_up_output) =
+                Self::find_best_route_internal(env, token_in, token_out, filled)?;
+            let amount_out = Self::execute_route(env, user, &route, filled, deadline, pull)?;
+
+            // The fill must still meet the limit price once executed
+            if amount_out < limit_out(filled)? {
+                return Err(AstroSwapError::SlippageExceeded);
+            }
+
+            let winner = route.steps.get(0).unwrap().protocol_id;
+            record_win(env, winner, route.expected_output, runner_up_output)?;
+
+            extend_instance_ttl(env);
+            Ok(PartialFill {
+                amount_in: filled,
+                amount_out,
+                unfilled: amount_in - filled,
+            })
+        })
+    }
+
+    /// Validate and execute a user-supplied route, collecting the input per `pull`
+    ///
+    /// Uses reentrancy guard to prevent flash loan attacks
+    fn swap_with_route_internal(
+        env: &Env,
+        user: &Address,
+        route: &SwapRoute,
+        amount_in: i128,
+        min_out: i128,
+        deadline: u64,
+        pull: PullMode,
     ) -> Result<i128, AstroSwapError> {
-        let (quote, _pair) = Self::get_astroswap_quote_with_pair(env, factory, token_in, token_out, amount_in)?;
-        Ok(quote)
+        user.require_auth();
+        require_not_paused(env, &DataKey::Paused)?;
+        require_deadline(env, deadline)?;
+
+        // Acquire reentrancy lock
+        with_lock(env, &DataKey::Locked, || {
+            // Validate the user-supplied route before any tokens move
+            validate_route(route, get_config(env).max_hops, |protocol_id| {
+                get_protocol(env, protocol_id).is_some()
+            })?;
+            Self::require_route_live(env, route)?;
+            let min_out = Self::resolve_min_out(env, user, route.expected_output, min_out)?;
+
+            // Execute the route
+            let actual_out = Self::execute_route(env, user, route, amount_in, deadline, pull)?;
+
+            // Slippage check
+            if actual_out < min_out {
+                return Err(AstroSwapError::SlippageExceeded);
+            }
+
+            extend_instance_ttl(env);
+            Ok(actual_out)
+        })
     }
 
-    /// Get quote and pair address from AstroSwap (native protocol)
-    fn get_astroswap_quote_with_pair(
+    /// Quote and execute an exact-output split swap, collecting the input per
+    /// `pull`
+    ///
+    /// Uses reentrancy guard to prevent flash loan attacks
+    #[allow(clippy::too_many_arguments)]
+    fn swap_exact_output_split_internal(
         env: &Env,
-        factory: &Address,
+        user: &Address,
         token_in: &Address,
         token_out: &Address,
-        amount_in: i128,
-    ) -> Result<(i128, Address), AstroSwapError> {
-        // Get pair address from factory
-        let pair_address: Option<Address> = env.invoke_contract(
-            factory,
-            &Symbol::new(env, "get_pair"),
-            Vec::from_array(env, [token_in.to_val(), token_out.to_val()]),
-        );
-
-        let pair = pair_address.ok_or(AstroSwapError::PairNotFound)?;
-        let pair_client = PairClient::new(env, &pair);
+        amount_out: i128,
+        max_in: i128,
+        deadline: u64,
+        pull: PullMode,
+    ) -> Result<i128, AstroSwapError> {
+        user.require_auth();
+        require_not_paused(env, &DataKey::Paused)?;
+        require_deadline(env, deadline)?;
 
-        // Get reserves and calculate output
-        let (reserve_0, reserve_1) = pair_client.get_reserves();
-        let token_0 = pair_client.token_0();
-        let fee_bps = pair_client.fee_bps();
+        // Acquire reentrancy lock
+        with_lock(env, &DataKey::Locked, || {
+            // Quote with the user's discounted fee so `max_in` is checked
+            // against what will actually be pulled
+            let fee_bps = effective_fee_bps(env, user, &get_config(env));
+            let quote = Self::quote_exact_output_split_internal(
+                env, token_in, token_out, amount_out, fee_bps,
+            )?;
+            if quote.total_in() > max_in {
+                return Err(AstroSwapError::ExcessiveInputAmount);
+            }
 
-        let (reserve_in, reserve_out) = if *token_in == token_0 {
-            (reserve_0, reserve_1)
-        } else {
-            (reserve_1, reserve_0)
-        };
+            let amount_in =
+                Self::execute_split(env, user, token_in, token_out, &quote, deadline, pull)?;
 
-        // Calculate amount out using AMM formula
-        let quote = astroswap_shared::get_amount_out(amount_in, reserve_in, reserve_out, fee_bps)?;
-        Ok((quote, pair))
+            extend_instance_ttl(env);
+            Ok(amount_in)
+        })
     }
 
-    /// Get quote from external protocol using generic interface
-    fn get_external_quote(
+    /// Execute the legs of a split quote, each paying its output to `user`
+    ///
+    /// Returns the input actually paid, aggregator fee included.
+    fn execute_split(
         env: &Env,
-        router: &Address,
+        user: &Address,
         token_in: &Address,
         token_out: &Address,
-        amount_in: i128,
+        quote: &SplitQuote,
+        deadline: u64,
+        pull: PullMode,
     ) -> Result<i128, AstroSwapError> {
-        // Generic quote interface - most DEXs implement this
-        // Try "get_amount_out" or similar
-        // Note: try_invoke_contract returns Result<Result<T, E>, InvokeError>
-        // We need to flatten both error layers
-        let result = env.try_invoke_contract::<i128, soroban_sdk::Error>(
-            router,
-            &Symbol::new(env, "get_amounts_out"),
-            Vec::from_array(
+        // Collect the whole input, fee included, with a single token call
+        let token_client = token::Client::new(env, token_in);
+        let aggregator = env.current_contract_address();
+        let balance_before = token_client.balance(&aggregator);
+        pull_input(env, user, token_in, quote.total_in(), pull)?;
+
+        // Forward fee to recipient; without one it stays in the aggregator
+        let mut retained_fee = 0;
+        if quote.aggregator_fee > 0 {
+            match get_fee_recipient(env) {
+                Some(fee_recipient) => {
+                    token_client.transfer(&aggregator, &fee_recipient, &quote.aggregator_fee)
+                }
+                None => retained_fee = quote.aggregator_fee,
+            }
+        }
+
+        let mut amount_out = 0;
+        for leg in quote.legs.iter() {
+            // Every leg runs before the deadline, not just the first
+            require_deadline(env, deadline)?;
+
+            token_client.transfer(&aggregator, &leg.pool_address, &leg.amount_in);
+            record_hop(env, leg.protocol_id, leg.amount_in);
+
+            let leg_out = Self::execute_protocol_swap(
                 env,
-                [
-                    amount_in.into_val(env),
-                    token_in.to_val(),
-                    token_out.to_val(),
-                ],
-            ),
-        );
+                leg.protocol_id,
+                &leg.pool_address,
+                token_in,
+                token_out,
+                leg.amount_in,
+                leg.amount_out,
+                user,
+                deadline,
+            )?;
 
-        match result {
-            Ok(Ok(amount)) => Ok(amount),
-            _ => Err(AstroSwapError::ProtocolNotFound),
+            // A leg falling short of its quote is slippage
+            if leg_out < leg.amount_out {
+                return Err(AstroSwapError::SlippageExceeded);
+            }
+            amount_out += leg_out;
         }
+
+        // Only the retained fee may stay behind: input handed back by a pool
+        // belongs to the user
+        let surplus = token_client.balance(&aggregator) - balance_before - retained_fee;
+        let amount_in = if surplus > 0 {
+            token_client.transfer(&aggregator, user, &surplus);
+            quote.total_in() - surplus
+        } else {
+            quote.total_in()
+        };
+
+        record_volume(env, user, amount_in);
+        emit_route_executed(
+            env,
+            user,
+            token_in,
+            token_out,
+            amount_in,
+            amount_out,
+            quote.legs.len(),
+        );
+        Self::report_routed_swap(env, token_in, amount_in, quote.aggregator_fee);
+
+        Ok(amount_in)
     }
 
     /// Execute a swap route
@@ -641,6 +2054,7 @@ impl AstroSwapAggregator {
         route: &SwapRoute,
         amount_in: i128,
         deadline: u64,
+        pull: PullMode,
     ) -> Result<i128, AstroSwapError> {
         if route.steps.is_empty() {
             return Err(AstroSwapError::InvalidPath);
@@ -649,31 +2063,40 @@ impl AstroSwapAggregator {
         let config = get_config(env);
         let mut current_amount = amount_in;
 
-        // Deduct aggregator fee upfront
-        if config.aggregator_fee_bps > 0 {
-            let fee = (current_amount * i128::from(config.aggregator_fee_bps)) / i128::from(BPS);
+        // Collect the whole input with a single token call
+        let first_step = route.steps.get(0).unwrap();
+        let token_client = token::Client::new(env, &first_step.token_in);
+        let aggregator = env.current_contract_address();
+        let balance_before = token_client.balance(&aggregator);
+        pull_input(env, user, &first_step.token_in, amount_in, pull)?;
+
+        // Deduct aggregator fee upfront, discounted by the user's volume tier
+        let fee_bps = effective_fee_bps(env, user, &config);
+        let mut retained_fee = 0;
+        let mut fee_charged = 0;
+        if fee_bps > 0 {
+            let fee = (current_amount * i128::from(fee_bps)) / i128::from(BPS);
             if fee > 0 {
-                let first_step = route.steps.get(0).unwrap();
-                let token_client = token::Client::new(env, &first_step.token_in);
-
-                // Transfer fee to recipient, or to aggregator contract if no recipient set
-                let fee_destination = get_fee_recipient(env)
-                    .unwrap_or_else(|| env.current_contract_address());
-
-                token_client.transfer(user, &fee_destination, &fee);
+                // Forward fee to recipient; without one it stays in the aggregator
+                match get_fee_recipient(env) {
+                    Some(fee_recipient) => token_client.transfer(&aggregator, &fee_recipient, &fee),
+                    None => retained_fee = fee,
+                }
                 current_amount -= fee;
+                fee_charged = fee;
             }
         }
 
+        // Send the rest of the input to the first pool
+        token_client.transfer(&aggregator, &first_step.pool_address, &current_amount);
+
         // Execute each step in the route with per-hop slippage validation
         for i in 0..route.steps.len() {
             let step = route.steps.get(i).unwrap();
 
-            // For the first step, transfer from user to pool
-            if i == 0 {
-                let token_client = token::Client::new(env, &step.token_in);
-                token_client.transfer(user, &step.pool_address, &current_amount);
-            }
+            // Every hop runs before the deadline, so a long route cannot
+            // straddle it
+            require_deadline(env, deadline)?;
 
             // Determine recipient (next pool or user)
             let recipient = if i == route.steps.len() - 1 {
@@ -682,16 +2105,18 @@ impl AstroSwapAggregator {
                 route.steps.get(i + 1).unwrap().pool_address.clone()
             };
 
-            // SECURITY: Calculate minimum per-hop output with tolerance
-            // Allow 1% slippage per hop to account for price movements
+            // SECURITY: Calculate minimum per-hop output with the configured
+            // tolerance for price movements, overridable per protocol
             // This prevents MEV sandwich attacks on individual hops
-            let per_hop_slippage_bps: i128 = 100; // 1% per hop
+            let per_hop_slippage_bps = Self::hop_slippage_bps(env, step.protocol_id, &config);
             let min_hop_out = step
                 .expected_out
-                .checked_mul(i128::from(BPS) - per_hop_slippage_bps)
+                .checked_mul(i128::from(BPS - per_hop_slippage_bps))
                 .unwrap_or(0)
                 / i128::from(BPS);
 
+            record_hop(env, step.protocol_id, current_amount);
+
             // Execute swap based on protocol with per-hop slippage protection
             current_amount = Self::execute_protocol_swap(
                 env,
@@ -711,9 +2136,41 @@ impl AstroSwapAggregator {
             }
         }
 
+        // Only the retained fee may stay behind: input handed back by a pool
+        // during the route belongs to the user
+        let surplus = token_client.balance(&aggregator) - balance_before - retained_fee;
+        let amount_in = if surplus > 0 {
+            token_client.transfer(&aggregator, user, &surplus);
+            amount_in - surplus
+        } else {
+            amount_in
+        };
+
+        record_volume(env, user, amount_in);
+
+        let (token_in, token_out) = route_endpoints(route)?;
+        emit_route_executed(
+            env,
+            user,
+            &token_in,
+            &token_out,
+            amount_in,
+            current_amount,
+            route.steps.len(),
+        );
+        Self::report_routed_swap(env, &token_in, amount_in, fee_charged);
+
         Ok(current_amount)
     }
 
+    /// Report an executed swap and the aggregator fee on it to the analytics
+    /// contract, if one is set
+    fn report_routed_swap(env: &Env, token_in: &Address, amount_in: i128, fee: i128) {
+        if let Some(analytics) = get_analytics(env) {
+            AnalyticsClient::new(env, &analytics).report_routed_swap(token_in, amount_in, fee);
+        }
+    }
+
     /// Execute a swap on a specific protocol with per-hop slippage protection
     #[allow(clippy::too_many_arguments)]
     fn execute_protocol_swap(
@@ -738,18 +2195,19 @@ impl AstroSwapAggregator {
         }
 
         // For external protocols, use generic swap interface with slippage protection
-        let result: i128 = env.invoke_contract(
-            pool,
-            &Symbol::new(env, "swap"),
-            Vec::from_array(
-                env,
-                [
-                    recipient.to_val(),
-                    token_in.to_val(),
-                    min_out.into_val(env), // SECURITY: Per-hop minimum output
-                ],
-            ),
+        let mut args = Vec::from_array(
+            env,
+            [
+                recipient.to_val(),
+                token_in.to_val(),
+                min_out.into_val(env), // SECURITY: Per-hop minimum output
+            ],
         );
+        // Adapters that support it enforce the deadline themselves
+        if takes_deadline(env, protocol_id) {
+            args.push_back(deadline.into_val(env));
+        }
+        let result: i128 = env.invoke_contract(pool, &Symbol::new(env, "swap"), args);
 
         Ok(result)
     }
@@ -764,6 +2222,23 @@ impl AstroSwapAggregator {
         }
     }
 
+    /// Fail fast on a route that went stale since it was quoted: every step's
+    /// protocol must still be active (`ProtocolInactive`) and every native
+    /// pool unpaused (`PoolPaused`)
+    fn require_route_live(env: &Env, route: &SwapRoute) -> Result<(), AstroSwapError> {
+        for step in route.steps.iter() {
+            let adapter =
+                get_protocol(env, step.protocol_id).ok_or(AstroSwapError::ProtocolNotFound)?;
+            if !adapter.is_active {
+                return Err(AstroSwapError::ProtocolInactive);
+            }
+            if step.protocol_id == 0 && PairClient::new(env, &step.pool_address).is_paused() {
+                return Err(AstroSwapError::PoolPaused);
+            }
+        }
+        Ok(())
+    }
+
     /// Verify caller is admin
     fn require_admin(env: &Env, caller: &Address) -> Result<(), AstroSwapError> {
         caller.require_auth();
@@ -773,34 +2248,19 @@ impl AstroSwapAggregator {
         Ok(())
     }
 
-    /// Verify contract is not paused
-    fn require_not_paused(env: &Env) -> Result<(), AstroSwapError> {
-        if is_paused(env) {
-            return Err(AstroSwapError::ContractPaused);
-        }
-        Ok(())
-    }
-
-    /// Check if deadline has passed
-    fn check_deadline(env: &Env, deadline: u64) -> Result<(), AstroSwapError> {
-        if env.ledger().timestamp() > deadline {
-            return Err(AstroSwapError::DeadlineExpired);
-        }
-        Ok(())
-    }
-
-    /// Internal function to acquire reentrancy lock
-    fn acquire_lock(env: &Env) -> Result<(), AstroSwapError> {
-        if is_locked(env) {
-            return Err(AstroSwapError::Reentrancy);
+    /// Fail with `InvalidArgument` unless a per-hop slippage tolerance is
+    /// within bounds
+    fn require_hop_slippage(hop_slippage_bps: u32) -> Result<(), AstroSwapError> {
+        if !(MIN_HOP_SLIPPAGE_BPS..=MAX_HOP_SLIPPAGE_BPS).contains(&hop_slippage_bps) {
+            return Err(AstroSwapError::InvalidArgument);
         }
-        set_locked(env, true);
         Ok(())
     }
 
-    /// Internal function to release reentrancy lock
-    fn release_lock(env: &Env) {
-        set_locked(env, false);
+    /// Per-hop slippage tolerance of a protocol: its override, or the
+    /// configured default
+    fn hop_slippage_bps(env: &Env, protocol_id: u32, config: &AggregatorConfig) -> u32 {
+        get_protocol_hop_slippage(env, protocol_id).unwrap_or(config.hop_slippage_bps)
     }
 }
 
@@ -871,6 +2331,65 @@ mod tests {
         assert!(info.is_active);
     }
 
+    #[test]
+    fn test_deregister_and_update_protocol() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(AstroSwapAggregator, ());
+        let client = AstroSwapAggregatorClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let factory = Address::generate(&env);
+        let soroswap = Address::generate(&env);
+        let soroswap_v2 = Address::generate(&env);
+
+        client.initialize(&admin, &factory);
+        client.register_protocol(&admin, &Protocol::Soroswap, &soroswap, &30);
+
+        // Registering again must not silently replace the factory
+        let result = client.try_register_protocol(&admin, &Protocol::Soroswap, &soroswap_v2, &30);
+        assert_eq!(result, Err(Ok(AstroSwapError::ProtocolExists)));
+
+        client.set_protocol_active(&admin, &Protocol::Soroswap, &false);
+        client.update_protocol(&admin, &Protocol::Soroswap, &soroswap_v2, &25);
+        let info = client.get_protocol_info(&Protocol::Soroswap).unwrap();
+        assert_eq!(info.factory_address, soroswap_v2);
+        assert_eq!(info.default_fee_bps, 25);
+        assert!(!info.is_active);
+
+        // Deregistering tombstones the ID without shrinking the ID range
+        client.deregister_protocol(&admin, &Protocol::Soroswap);
+        assert!(client.get_protocol_info(&Protocol::Soroswap).is_none());
+        assert!(client.is_protocol_removed(&Protocol::Soroswap));
+        assert_eq!(client.protocol_count(), 2);
+        assert_eq!(
+            client.try_update_protocol(&admin, &Protocol::Soroswap, &soroswap, &30),
+            Err(Ok(AstroSwapError::ProtocolNotFound))
+        );
+        assert_eq!(
+            client.try_deregister_protocol(&admin, &Protocol::Soroswap),
+            Err(Ok(AstroSwapError::ProtocolNotFound))
+        );
+        env.as_contract(&contract_id, || {
+            let live = registered_protocols(&env);
+            assert_eq!(live.len(), 1);
+            assert_eq!(live.get(0).unwrap().protocol_id, 0);
+        });
+
+        // The native protocol can only be disabled
+        assert_eq!(
+            client.try_deregister_protocol(&admin, &Protocol::AstroSwap),
+            Err(Ok(AstroSwapError::InvalidArgument))
+        );
+
+        // A deregistered protocol can be registered again
+        client.register_protocol(&admin, &Protocol::Soroswap, &soroswap, &30);
+        assert!(!client.is_protocol_removed(&Protocol::Soroswap));
+        let info = client.get_protocol_info(&Protocol::Soroswap).unwrap();
+        assert!(info.is_active);
+    }
+
     #[test]
     fn test_config_update() {
         let env = Env::default();
@@ -885,12 +2404,74 @@ mod tests {
         client.initialize(&admin, &factory);
 
         // Update config
-        client.set_config(&admin, &2, &1, &10);
+        assert_eq!(client.config().hop_slippage_bps, DEFAULT_HOP_SLIPPAGE_BPS);
+        client.set_config(&admin, &2, &1, &10, &50);
 
         let config = client.config();
         assert_eq!(config.max_hops, 2);
         assert_eq!(config.max_splits, 1);
         assert_eq!(config.aggregator_fee_bps, 10);
+        assert_eq!(config.hop_slippage_bps, 50);
+
+        // The per-hop tolerance is bounded
+        assert_eq!(
+            client.try_set_config(&admin, &2, &1, &10, &0),
+            Err(Ok(AstroSwapError::InvalidArgument))
+        );
+        assert_eq!(
+            client.try_set_config(&admin, &2, &1, &10, &(MAX_HOP_SLIPPAGE_BPS + 1)),
+            Err(Ok(AstroSwapError::InvalidArgument))
+        );
+
+        // So is the number of protocols a split spreads over
+        for max_splits in [0, MAX_SPLITS + 1] {
+            assert_eq!(
+                client.try_set_config(&admin, &2, &max_splits, &10, &50),
+                Err(Ok(AstroSwapError::InvalidArgument))
+            );
+        }
+        client.set_config(&admin, &2, &MAX_SPLITS, &10, &50);
+        assert_eq!(client.config().max_splits, MAX_SPLITS);
+    }
+
+    #[test]
+    fn test_protocol_hop_slippage_overrides() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(AstroSwapAggregator, ());
+        let client = AstroSwapAggregatorClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let factory = Address::generate(&env);
+        let soroswap = Address::generate(&env);
+
+        client.initialize(&admin, &factory);
+        client.register_protocol(&admin, &Protocol::Soroswap, &soroswap, &30);
+
+        // Protocols follow the configured default until overridden
+        assert_eq!(client.protocol_hop_slippage(&Protocol::AstroSwap), 100);
+        client.set_protocol_hop_slippage(&admin, &Protocol::AstroSwap, &Some(10));
+        client.set_protocol_hop_slippage(&admin, &Protocol::Soroswap, &Some(500));
+        client.set_config(&admin, &3, &2, &5, &200);
+        assert_eq!(client.protocol_hop_slippage(&Protocol::AstroSwap), 10);
+        assert_eq!(client.protocol_hop_slippage(&Protocol::Soroswap), 500);
+
+        assert_eq!(
+            client.try_set_protocol_hop_slippage(&admin, &Protocol::Soroswap, &Some(1_001)),
+            Err(Ok(AstroSwapError::InvalidArgument))
+        );
+        assert_eq!(
+            client.try_set_protocol_hop_slippage(&admin, &Protocol::Phoenix, &Some(50)),
+            Err(Ok(AstroSwapError::ProtocolNotFound))
+        );
+
+        // Clearing an override, or deregistering the protocol, restores the default
+        client.set_protocol_hop_slippage(&admin, &Protocol::AstroSwap, &None);
+        assert_eq!(client.protocol_hop_slippage(&Protocol::AstroSwap), 200);
+        client.deregister_protocol(&admin, &Protocol::Soroswap);
+        client.register_protocol(&admin, &Protocol::Soroswap, &soroswap, &30);
+        assert_eq!(client.protocol_hop_slippage(&Protocol::Soroswap), 200);
     }
 
     #[test]
@@ -0,0 +1,97 @@
+//! Partial-fill swaps
+//!
+//! For orders too large to meet `min_out` in full, `swap_partial` fills the
+//! largest part of the input whose execution price stays within
+//! `max_impact_bps` of the small-trade price (the best route's price for
+//! `1 / PRICE_PROBE_DIVISOR` of the order) and no worse than the limit price
+//! `min_out / amount_in`. The fill is found by binary search over route
+//! quotes, to within `amount_in / 2^FILL_SEARCH_STEPS`. The unfilled input is
+//! never pulled from the user.
+
+use soroban_sdk::contracttype;
+
+/// Fraction of the order quoted for the small-trade reference price
+pub const PRICE_PROBE_DIVISOR: i128 = 1_000;
+
+/// Binary search steps over the fill size
+pub const FILL_SEARCH_STEPS: u32 = 12;
+
+/// Outcome of a partial-fill swap
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PartialFill {
+    /// Input swapped, aggregator fee included
+    pub amount_in: i128,
+    /// Output received
+    pub amount_out: i128,
+    /// Input left with the user
+    pub unfilled: i128,
+}
+
+/// Largest fill of `amount_in` that `accepts`, 0 if none does
+///
+/// Execution price only worsens as the fill grows, so the accepted fills are
+/// a prefix of `0..=amount_in` and a binary search finds its end.
+pub fn largest_fill<F>(amount_in: i128, mut accepts: F) -> i128
+where
+    F: FnMut(i128) -> bool,
+{
+    if accepts(amount_in) {
+        return amount_in;
+    }
+
+    // Invariant: `low` is accepted (or 0), `high` is not
+    let mut low = 0i128;
+    let mut high = amount_in;
+    for _ in 0..FILL_SEARCH_STEPS {
+        let mid = low + (high - low) / 2;
+        if mid == low {
+            break;
+        }
+        if accepts(mid) {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astroswap_shared::get_amount_out;
+
+    /// Accept fills of a 0.3% constant-product pool whose price is within
+    /// `max_impact_bps` of the small-trade price
+    fn within_impact(
+        reserve_in: i128,
+        reserve_out: i128,
+        max_impact_bps: i128,
+    ) -> impl Fn(i128) -> bool {
+        move |fill| {
+            let out = get_amount_out(fill, reserve_in, reserve_out, 30).unwrap();
+            let reference = fill * reserve_out * 997 / (reserve_in * 1000);
+            out >= reference - reference * max_impact_bps / 10_000
+        }
+    }
+
+    #[test]
+    fn test_full_fill_when_accepted() {
+        assert_eq!(largest_fill(1_000, |_| true), 1_000);
+        assert_eq!(largest_fill(1_000, |_| false), 0);
+    }
+
+    #[test]
+    fn test_fill_stops_at_impact_bound() {
+        let (reserve_in, reserve_out) = (1_000_000_000, 1_000_000_000);
+        let accepts = within_impact(reserve_in, reserve_out, 100);
+
+        // A constant-product pool allows about 1% of its reserve for 1% impact
+        let amount_in = 100_000_000;
+        let filled = largest_fill(amount_in, &accepts);
+        assert!(accepts(filled));
+        assert!(!accepts(filled + amount_in / (1 << FILL_SEARCH_STEPS) + 1));
+        assert!(filled > 9_000_000 && filled < 11_000_000);
+    }
+}
@@ -0,0 +1,86 @@
+//! Input token pull
+//!
+//! Every swap moves the user's whole input into the aggregator in a single
+//! token call before the fee is split off and the rest is sent to the first
+//! pool. Wallets therefore see one of two fixed authorization shapes:
+//!
+//! - `PullMode::Transfer`: the user signs the swap invocation with exactly one
+//!   sub-invocation, `token_in.transfer(user, aggregator, amount_in)`
+//! - `PullMode::Allowance`: the user has approved the aggregator beforehand
+//!   (`token_in.approve(user, aggregator, amount, expiration_ledger)`) and signs
+//!   only the swap invocation; the aggregator pulls with `transfer_from`
+
+use astroswap_shared::AstroSwapError;
+use soroban_sdk::{contracttype, token, Address, Env};
+
+/// How the aggregator collects the user's input tokens
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PullMode {
+    /// `transfer` authorized by the user inside the swap invocation
+    Transfer,
+    /// `transfer_from` against an allowance granted to the aggregator
+    Allowance,
+}
+
+/// Token authorization a swap needs from the user
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PullAuthorization {
+    /// Token pulled from the user
+    pub token: Address,
+    /// Recipient of the transfer, or spender of the allowance (the aggregator)
+    pub spender: Address,
+    /// Amount pulled from the user
+    pub amount: i128,
+    /// Allowance the user still has to approve (always 0 for `Transfer`)
+    pub allowance_shortfall: i128,
+}
+
+/// Describe the authorization a swap of `amount_in` of `token_in` requires
+pub fn pull_authorization(
+    env: &Env,
+    user: &Address,
+    token_in: &Address,
+    amount_in: i128,
+    mode: PullMode,
+) -> PullAuthorization {
+    let spender = env.current_contract_address();
+    let allowance_shortfall = match mode {
+        PullMode::Transfer => 0,
+        PullMode::Allowance => {
+            let allowance = token::Client::new(env, token_in).allowance(user, &spender);
+            (amount_in - allowance).max(0)
+        }
+    };
+
+    PullAuthorization {
+        token: token_in.clone(),
+        spender,
+        amount: amount_in,
+        allowance_shortfall,
+    }
+}
+
+/// Move `amount` of `token` from `user` into the aggregator with one token call
+pub fn pull_input(
+    env: &Env,
+    user: &Address,
+    token: &Address,
+    amount: i128,
+    mode: PullMode,
+) -> Result<(), AstroSwapError> {
+    let token_client = token::Client::new(env, token);
+    let aggregator = env.current_contract_address();
+
+    match mode {
+        PullMode::Transfer => token_client.transfer(user, &aggregator, &amount),
+        PullMode::Allowance => {
+            if token_client.allowance(user, &aggregator) < amount {
+                return Err(AstroSwapError::InsufficientAllowance);
+            }
+            token_client.transfer_from(&aggregator, user, &aggregator, &amount);
+        }
+    }
+    Ok(())
+}
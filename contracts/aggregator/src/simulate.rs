@@ -0,0 +1,104 @@
+//! Route simulation
+//!
+//! `simulate_route` replays a route against current pool state without moving
+//! any tokens, so frontends can render an execution preview for a route
+//! returned by `find_best_route` before the user signs it.
+
+use astroswap_shared::{
+    apply_bps, calculate_price_impact, get_amount_out, AstroSwapError, PairClient, RouteStep,
+};
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+/// Simulated execution of one route step
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HopSimulation {
+    /// Protocol the step swaps on
+    pub protocol_id: u32,
+    /// Pool the step swaps in
+    pub pool_address: Address,
+    /// Token sold in this step
+    pub token_in: Address,
+    /// Token bought in this step
+    pub token_out: Address,
+    /// Amount reaching this step (after the aggregator fee and previous hops)
+    pub amount_in: i128,
+    /// Amount this step is expected to produce
+    pub amount_out: i128,
+    /// Pool fee in basis points
+    pub fee_bps: u32,
+    /// Pool fee in units of `token_in`
+    pub fee_amount: i128,
+    /// Price impact against the spot price, excluding the pool fee (basis points)
+    pub price_impact_bps: u32,
+}
+
+/// Simulated execution of a whole route
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RouteSimulation {
+    /// Per-step amounts, fees and price impact
+    pub hops: Vec<HopSimulation>,
+    /// Input amount the simulation started from
+    pub amount_in: i128,
+    /// Aggregator fee deducted from the input before the first hop
+    pub aggregator_fee: i128,
+    /// Output of the last hop
+    pub amount_out: i128,
+    /// Aggregator fee plus every hop's pool fee (basis points)
+    pub total_fee_bps: u32,
+}
+
+/// Simulate a native AstroSwap step against the pair's current reserves
+pub fn simulate_native_hop(
+    env: &Env,
+    step: &RouteStep,
+    amount_in: i128,
+) -> Result<HopSimulation, AstroSwapError> {
+    let pair_client = PairClient::new(env, &step.pool_address);
+    let (reserve_0, reserve_1) = pair_client.get_reserves();
+    let fee_bps = pair_client.fee_bps();
+
+    let (reserve_in, reserve_out) = if step.token_in == pair_client.token_0() {
+        (reserve_0, reserve_1)
+    } else if step.token_in == pair_client.token_1() {
+        (reserve_1, reserve_0)
+    } else {
+        return Err(AstroSwapError::InvalidRoute);
+    };
+
+    Ok(HopSimulation {
+        protocol_id: step.protocol_id,
+        pool_address: step.pool_address.clone(),
+        token_in: step.token_in.clone(),
+        token_out: step.token_out.clone(),
+        amount_in,
+        amount_out: get_amount_out(amount_in, reserve_in, reserve_out, fee_bps)?,
+        fee_bps,
+        fee_amount: apply_bps(amount_in, fee_bps)?,
+        price_impact_bps: calculate_price_impact(amount_in, reserve_in, reserve_out, 0)?,
+    })
+}
+
+/// Simulate an external protocol step from its quote
+///
+/// External pools expose only a quote, so the adapter's default fee is
+/// reported and the price impact is left at 0.
+pub fn simulate_quoted_hop(
+    step: &RouteStep,
+    amount_in: i128,
+    quote: i128,
+    fee_bps: u32,
+) -> Result<HopSimulation, AstroSwapError> {
+    Ok(HopSimulation {
+        protocol_id: step.protocol_id,
+        pool_address: step.pool_address.clone(),
+        token_in: step.token_in.clone(),
+        token_out: step.token_out.clone(),
+        amount_in,
+        amount_out: quote,
+        fee_bps,
+        fee_amount: apply_bps(amount_in, fee_bps)?,
+        price_impact_bps: 0,
+    })
+}
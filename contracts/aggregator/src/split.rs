@@ -0,0 +1,207 @@
+//! Exact-output split routing
+//!
+//! Buys an exact amount of `token_out` across the direct pools of several
+//! protocols. The target is cut into `SPLIT_CHUNKS` chunks and each chunk goes
+//! to the protocol whose next chunk costs the least input (greedy
+//! marginal-price allocation), using at most `max_splits` protocols. Pool
+//! pricing is convex, so the result is within one chunk of the cheapest split.
+
+use astroswap_shared::{mul_div_up, AstroSwapError};
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+/// Basis points constant (100% = 10000)
+const BPS: i128 = 10_000;
+
+/// Chunks the target output is allocated in
+pub const SPLIT_CHUNKS: i128 = 10;
+
+/// One protocol's share of a split swap
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SplitLeg {
+    /// Protocol the leg swaps on
+    pub protocol_id: u32,
+    /// Pool the leg swaps in
+    pub pool_address: Address,
+    /// Input the leg needs, rounded up
+    pub amount_in: i128,
+    /// Output the leg must produce
+    pub amount_out: i128,
+}
+
+/// Cheapest split found for an exact-output swap
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SplitQuote {
+    /// Legs in protocol order; their outputs sum to `amount_out`
+    pub legs: Vec<SplitLeg>,
+    /// Exact output bought
+    pub amount_out: i128,
+    /// Input sent to the pools (sum of the legs)
+    pub amount_in: i128,
+    /// Aggregator fee charged on top of `amount_in`
+    pub aggregator_fee: i128,
+}
+
+impl SplitQuote {
+    /// Input the user pays, aggregator fee included
+    pub fn total_in(&self) -> i128 {
+        self.amount_in + self.aggregator_fee
+    }
+}
+
+/// Allocate `amount_out` over `sources` liquidity sources
+///
+/// `cost(source, amount_out)` is the input `source` needs to produce
+/// `amount_out` on its own, or an error if it cannot. Returns the
+/// (output, input) allocated to each source; unused sources get (0, 0). Fails
+/// with `InsufficientLiquidity` if some chunk fits in no source.
+pub fn allocate_exact_output<F>(
+    env: &Env,
+    amount_out: i128,
+    sources: u32,
+    max_splits: u32,
+    mut cost: F,
+) -> Result<Vec<(i128, i128)>, AstroSwapError>
+where
+    F: FnMut(u32, i128) -> Result<i128, AstroSwapError>,
+{
+    let mut allocations = Vec::new(env);
+    for _ in 0..sources {
+        allocations.push_back((0i128, 0i128));
+    }
+    let mut used = 0u32;
+
+    for chunk_index in 0..SPLIT_CHUNKS {
+        // Chunk boundaries spread the remainder so the chunks sum to amount_out
+        let chunk =
+            amount_out * (chunk_index + 1) / SPLIT_CHUNKS - amount_out * chunk_index / SPLIT_CHUNKS;
+        if chunk == 0 {
+            continue;
+        }
+
+        // (source, input for its new total, marginal input for this chunk)
+        let mut best: Option<(u32, i128, i128)> = None;
+        for source in 0..sources {
+            let (allocated_out, allocated_in) = allocations.get(source).unwrap();
+            if allocated_out == 0 && used >= max_splits {
+                continue;
+            }
+            let Ok(total_in) = cost(source, allocated_out + chunk) else {
+                continue;
+            };
+            let marginal = total_in - allocated_in;
+            match best {
+                Some((_, _, best_marginal)) if best_marginal <= marginal => {}
+                _ => best = Some((source, total_in, marginal)),
+            }
+        }
+
+        let (source, total_in, _) = best.ok_or(AstroSwapError::InsufficientLiquidity)?;
+        let (allocated_out, _) = allocations.get(source).unwrap();
+        if allocated_out == 0 {
+            used += 1;
+        }
+        allocations.set(source, (allocated_out + chunk, total_in));
+    }
+
+    Ok(allocations)
+}
+
+/// Aggregator fee on top of `net_in` such that deducting `fee_bps` of the
+/// total (as `execute_route` does) leaves at least `net_in`
+pub fn fee_on_top(net_in: i128, fee_bps: u32) -> Result<i128, AstroSwapError> {
+    let fee_bps = i128::from(fee_bps);
+    if fee_bps == 0 {
+        return Ok(0);
+    }
+    let gross = mul_div_up(net_in, BPS, BPS - fee_bps)?;
+    Ok(gross - net_in)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astroswap_shared::get_amount_in;
+
+    /// Constant-product pools as (reserve_in, reserve_out)
+    fn pools_cost(
+        pools: &[(i128, i128)],
+    ) -> impl FnMut(u32, i128) -> Result<i128, AstroSwapError> + '_ {
+        move |source, amount_out| {
+            let (reserve_in, reserve_out) = pools[source as usize];
+            get_amount_in(amount_out, reserve_in, reserve_out, 30)
+        }
+    }
+
+    fn outputs(allocations: &Vec<(i128, i128)>) -> [i128; 3] {
+        let mut out = [0; 3];
+        for (i, (amount_out, _)) in allocations.iter().enumerate() {
+            out[i] = amount_out;
+        }
+        out
+    }
+
+    #[test]
+    fn test_equal_pools_split_evenly() {
+        let env = Env::default();
+        let pools = [(1_000_000, 1_000_000), (1_000_000, 1_000_000)];
+        let allocations = allocate_exact_output(&env, 100_000, 2, 2, pools_cost(&pools)).unwrap();
+        assert_eq!(outputs(&allocations), [50_000, 50_000, 0]);
+
+        // Splitting beats buying everything from one pool
+        let split_in: i128 = allocations.iter().map(|(_, amount_in)| amount_in).sum();
+        let single_in = get_amount_in(100_000, 1_000_000, 1_000_000, 30).unwrap();
+        assert!(split_in < single_in);
+    }
+
+    #[test]
+    fn test_deeper_pool_gets_more() {
+        let env = Env::default();
+        let pools = [(1_000_000, 1_000_000), (3_000_000, 3_000_000)];
+        let allocations = allocate_exact_output(&env, 100_003, 2, 2, pools_cost(&pools)).unwrap();
+        let [shallow, deep, _] = outputs(&allocations);
+        assert_eq!(shallow + deep, 100_003);
+        assert!(deep >= 2 * shallow);
+    }
+
+    #[test]
+    fn test_max_splits_and_failing_sources() {
+        let env = Env::default();
+        let pools = [
+            (1_000_000, 1_000_000),
+            (1_000_000, 1_000_000),
+            (2_000_000, 2_000_000),
+        ];
+
+        // Only the deepest pool is used when a single split is allowed
+        let allocations = allocate_exact_output(&env, 100_000, 3, 1, pools_cost(&pools)).unwrap();
+        assert_eq!(outputs(&allocations), [0, 0, 100_000]);
+
+        // A source that cannot quote is skipped
+        let mut cost = pools_cost(&pools);
+        let allocations = allocate_exact_output(&env, 100_000, 3, 3, |source, amount_out| {
+            if source == 2 {
+                return Err(AstroSwapError::PairNotFound);
+            }
+            cost(source, amount_out)
+        })
+        .unwrap();
+        assert_eq!(outputs(&allocations), [50_000, 50_000, 0]);
+
+        // More than every pool holds
+        assert_eq!(
+            allocate_exact_output(&env, 5_000_000, 3, 3, pools_cost(&pools)),
+            Err(AstroSwapError::InsufficientLiquidity)
+        );
+    }
+
+    #[test]
+    fn test_fee_on_top() {
+        assert_eq!(fee_on_top(1_000_000, 0), Ok(0));
+        let fee = fee_on_top(1_000_000, 5).unwrap();
+        let gross = 1_000_000 + fee;
+        assert!(gross - gross * 5 / BPS >= 1_000_000);
+        assert_eq!(fee, 501);
+    }
+}
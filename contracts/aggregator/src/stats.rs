@@ -0,0 +1,121 @@
+//! Route statistics
+//!
+//! Per-protocol counters showing which integrations actually carry flow:
+//! volume and hops executed on each protocol, how often it quoted the best
+//! route for a `swap`, and by how much it beat the runner-up quote.
+
+use astroswap_shared::{mul_div_down, AstroSwapError};
+use soroban_sdk::{Env, Vec};
+
+use crate::storage::{
+    extend_protocol_stats_ttl, get_protocol_stats, registered_protocols, set_protocol_stats,
+    ProtocolStats,
+};
+
+/// Basis points constant (100% = 10000)
+const BPS: i128 = 10_000;
+
+/// Count a hop of `amount_in` executed on `protocol_id`
+pub fn record_hop(env: &Env, protocol_id: u32, amount_in: i128) {
+    let mut stats = get_protocol_stats(env, protocol_id);
+    stats.routed_volume = stats.routed_volume.saturating_add(amount_in);
+    stats.hop_count = stats.hop_count.saturating_add(1);
+    set_protocol_stats(env, protocol_id, &stats);
+    extend_protocol_stats_ttl(env, protocol_id);
+}
+
+/// Count a best-route win of `protocol_id` quoting `best_output` against a
+/// runner-up quote of `runner_up_output` (0 if no other protocol quoted)
+pub fn record_win(
+    env: &Env,
+    protocol_id: u32,
+    best_output: i128,
+    runner_up_output: i128,
+) -> Result<(), AstroSwapError> {
+    let mut stats = get_protocol_stats(env, protocol_id);
+    stats.win_count = stats.win_count.saturating_add(1);
+    if runner_up_output > 0 {
+        let improvement = mul_div_down(best_output - runner_up_output, BPS, runner_up_output)?;
+        stats.contested_wins = stats.contested_wins.saturating_add(1);
+        stats.total_improvement_bps = stats
+            .total_improvement_bps
+            .saturating_add(u64::try_from(improvement).unwrap_or(u64::MAX));
+    }
+    set_protocol_stats(env, protocol_id, &stats);
+    extend_protocol_stats_ttl(env, protocol_id);
+    Ok(())
+}
+
+/// Average improvement over the runner-up quote on contested wins (basis points)
+pub fn average_improvement_bps(stats: &ProtocolStats) -> u32 {
+    if stats.contested_wins == 0 {
+        return 0;
+    }
+    let average = stats.total_improvement_bps / u64::from(stats.contested_wins);
+    u32::try_from(average).unwrap_or(u32::MAX)
+}
+
+/// Statistics of every registered protocol, in ID order
+pub fn all_protocol_stats(env: &Env) -> Vec<(u32, ProtocolStats)> {
+    let mut all = Vec::new(env);
+    for adapter in registered_protocols(env).iter() {
+        all.push_back((
+            adapter.protocol_id,
+            get_protocol_stats(env, adapter.protocol_id),
+        ));
+    }
+    all
+}
+
+/// Registered protocol with the most wins, ties broken by routed volume
+pub fn best_protocol(env: &Env) -> Option<u32> {
+    let mut best: Option<(u32, ProtocolStats)> = None;
+    for (protocol_id, stats) in all_protocol_stats(env).iter() {
+        if stats.win_count == 0 {
+            continue;
+        }
+        let better = match &best {
+            None => true,
+            Some((_, top)) => {
+                (stats.win_count, stats.routed_volume) > (top.win_count, top.routed_volume)
+            }
+        };
+        if better {
+            best = Some((protocol_id, stats));
+        }
+    }
+    best.map(|(protocol_id, _)| protocol_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AstroSwapAggregator;
+
+    #[test]
+    fn test_record_wins_and_hops() {
+        let env = Env::default();
+        let contract_id = env.register(AstroSwapAggregator, ());
+
+        env.as_contract(&contract_id, || {
+            assert_eq!(get_protocol_stats(&env, 1), ProtocolStats::default());
+
+            record_hop(&env, 1, 1_000);
+            record_hop(&env, 1, 500);
+            // Uncontested win: counted, but no improvement sample
+            record_win(&env, 1, 1_000, 0).unwrap();
+            // 2% and 1% better than the runner-up
+            record_win(&env, 1, 10_200, 10_000).unwrap();
+            record_win(&env, 1, 10_100, 10_000).unwrap();
+
+            let stats = get_protocol_stats(&env, 1);
+            assert_eq!(stats.routed_volume, 1_500);
+            assert_eq!(stats.hop_count, 2);
+            assert_eq!(stats.win_count, 3);
+            assert_eq!(stats.contested_wins, 2);
+            assert_eq!(stats.total_improvement_bps, 300);
+            assert_eq!(average_improvement_bps(&stats), 150);
+            assert_eq!(average_improvement_bps(&ProtocolStats::default()), 0);
+        });
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! Manages protocol adapters, routing configuration, and contract state.
 
-use soroban_sdk::{contracttype, Address, Env};
+use soroban_sdk::{contracttype, Address, Env, Vec};
 
 /// Protocol adapter information
 #[contracttype]
@@ -28,6 +28,58 @@ pub struct AggregatorConfig {
     pub max_splits: u32,
     /// Fee charged by aggregator in basis points
     pub aggregator_fee_bps: u32,
+    /// Tolerated shortfall of each hop below its quoted output, in basis
+    /// points, unless the hop's protocol has an override
+    pub hop_slippage_bps: u32,
+}
+
+/// Aggregator fee tier unlocked by 30-day trading volume
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeTier {
+    /// Minimum rolling volume (raw input-token units) to qualify
+    pub min_volume: i128,
+    /// Aggregator fee in basis points for this tier
+    pub fee_bps: u32,
+}
+
+/// Volume traded by a user during one epoch
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VolumeBucket {
+    /// Epoch index (ledger timestamp / epoch length)
+    pub epoch: u64,
+    /// Input volume swapped during the epoch
+    pub volume: i128,
+}
+
+/// Cumulative routing statistics of a protocol
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ProtocolStats {
+    /// Input routed through the protocol, summed over executed hops (raw units
+    /// of each hop's input token)
+    pub routed_volume: i128,
+    /// Executed hops on the protocol
+    pub hop_count: u32,
+    /// Executed `swap`s for which the protocol quoted the best route
+    pub win_count: u32,
+    /// Wins where another protocol also quoted the pair
+    pub contested_wins: u32,
+    /// Improvement over the second-best quote, summed over contested wins (basis points)
+    pub total_improvement_bps: u64,
+}
+
+/// Oracle cross-check applied to external protocol quotes
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QuoteGuard {
+    /// AstroSwap oracle providing TWAPs for both tokens of a quote
+    pub oracle: Address,
+    /// TWAP window in seconds
+    pub twap_window: u64,
+    /// Maximum deviation of a quote from the TWAP cross-rate output (basis points)
+    pub max_deviation_bps: u32,
 }
 
 /// Storage keys for the aggregator contract
@@ -41,10 +93,22 @@ pub enum DataKey {
     Locked, // Reentrancy lock for extra security
     Config,
     ProtocolCount,
+    FeeTiers,         // Volume-based fee tiers
+    QuoteGuard,       // Oracle check of external quotes (optional)
+    ProtocolPriority, // Protocol IDs quoted first, in order
+    EarlyStopBps,     // Stop quoting within this many bps of the oracle price
+    Analytics,        // Analytics contract executed swaps are reported to
+    Connectors,       // Intermediate tokens pathfinding may route through
 
     // Persistent storage
-    Protocol(u32), // Protocol adapter by ID
-    FeeRecipient,  // Address to receive aggregator fees
+    Protocol(u32),            // Protocol adapter by ID
+    ProtocolRemoved(u32),     // Tombstone of a deregistered protocol
+    ProtocolDeadline(u32),    // Adapter `swap` takes a deadline argument
+    FeeRecipient,             // Address to receive aggregator fees
+    UserVolume(Address),      // Per-user volume buckets
+    ProtocolStats(u32),       // Routing statistics by protocol ID
+    UserSlippage(Address),    // Per-user default max slippage in bps
+    ProtocolHopSlippage(u32), // Per-protocol per-hop tolerance override in bps
 }
 
 // ==================== Instance Storage ====================
@@ -88,19 +152,6 @@ pub fn set_paused(env: &Env, paused: bool) {
     env.storage().instance().set(&DataKey::Paused, &paused);
 }
 
-/// Check if the contract is locked (reentrancy protection)
-pub fn is_locked(env: &Env) -> bool {
-    env.storage()
-        .instance()
-        .get::<DataKey, bool>(&DataKey::Locked)
-        .unwrap_or(false)
-}
-
-/// Set the lock state
-pub fn set_locked(env: &Env, locked: bool) {
-    env.storage().instance().set(&DataKey::Locked, &locked);
-}
-
 /// Get aggregator configuration
 pub fn get_config(env: &Env) -> AggregatorConfig {
     env.storage()
@@ -110,6 +161,7 @@ pub fn get_config(env: &Env) -> AggregatorConfig {
             max_hops: 3,
             max_splits: 2,
             aggregator_fee_bps: 5, // 0.05% aggregator fee
+            hop_slippage_bps: 100, // 1% per hop
         })
 }
 
@@ -150,19 +202,169 @@ pub fn set_protocol(env: &Env, protocol_id: u32, adapter: &ProtocolAdapter) {
 }
 
 /// Check if a protocol exists
-#[allow(dead_code)]
 pub fn protocol_exists(env: &Env, protocol_id: u32) -> bool {
     env.storage()
         .persistent()
         .has(&DataKey::Protocol(protocol_id))
 }
 
-/// Remove a protocol adapter
-#[allow(dead_code)]
+/// Remove a protocol adapter, leaving a tombstone for its ID
 pub fn remove_protocol(env: &Env, protocol_id: u32) {
+    let storage = env.storage().persistent();
+    storage.remove(&DataKey::Protocol(protocol_id));
+    storage.remove(&DataKey::ProtocolDeadline(protocol_id));
+    storage.remove(&DataKey::ProtocolHopSlippage(protocol_id));
+    storage.set(&DataKey::ProtocolRemoved(protocol_id), &true);
+}
+
+/// Check if a protocol's adapter `swap` takes a deadline argument
+pub fn takes_deadline(env: &Env, protocol_id: u32) -> bool {
+    env.storage()
+        .persistent()
+        .get::<DataKey, bool>(&DataKey::ProtocolDeadline(protocol_id))
+        .unwrap_or(false)
+}
+
+/// Set whether a protocol's adapter `swap` takes a deadline argument
+pub fn set_takes_deadline(env: &Env, protocol_id: u32, takes_deadline: bool) {
+    let key = DataKey::ProtocolDeadline(protocol_id);
+    if !takes_deadline {
+        env.storage().persistent().remove(&key);
+        return;
+    }
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, &true);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+/// Get a protocol's per-hop slippage override in bps, if set
+pub fn get_protocol_hop_slippage(env: &Env, protocol_id: u32) -> Option<u32> {
+    env.storage()
+        .persistent()
+        .get::<DataKey, u32>(&DataKey::ProtocolHopSlippage(protocol_id))
+}
+
+/// Set a protocol's per-hop slippage override in bps, or clear it with `None`
+pub fn set_protocol_hop_slippage(env: &Env, protocol_id: u32, hop_slippage_bps: Option<u32>) {
+    let key = DataKey::ProtocolHopSlippage(protocol_id);
+    let Some(hop_slippage_bps) = hop_slippage_bps else {
+        env.storage().persistent().remove(&key);
+        return;
+    };
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, &hop_slippage_bps);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+/// Check if a protocol ID was deregistered
+pub fn is_protocol_removed(env: &Env, protocol_id: u32) -> bool {
+    env.storage()
+        .persistent()
+        .get::<DataKey, bool>(&DataKey::ProtocolRemoved(protocol_id))
+        .unwrap_or(false)
+}
+
+/// Clear the tombstone of a protocol ID being registered again
+pub fn clear_protocol_removed(env: &Env, protocol_id: u32) {
     env.storage()
         .persistent()
-        .remove(&DataKey::Protocol(protocol_id));
+        .remove(&DataKey::ProtocolRemoved(protocol_id));
+}
+
+/// Registered adapters in ID order, skipping unused and deregistered IDs
+pub fn registered_protocols(env: &Env) -> Vec<ProtocolAdapter> {
+    let mut adapters = Vec::new(env);
+    for protocol_id in 0..get_protocol_count(env) {
+        if let Some(adapter) = get_protocol(env, protocol_id) {
+            adapters.push_back(adapter);
+        }
+    }
+    adapters
+}
+
+/// Get the protocol priority list (empty if unset)
+pub fn get_protocol_priority(env: &Env) -> Vec<u32> {
+    env.storage()
+        .instance()
+        .get::<DataKey, Vec<u32>>(&DataKey::ProtocolPriority)
+        .unwrap_or(Vec::new(env))
+}
+
+/// Set the protocol priority list
+pub fn set_protocol_priority(env: &Env, priority: &Vec<u32>) {
+    env.storage()
+        .instance()
+        .set(&DataKey::ProtocolPriority, priority);
+}
+
+/// Protocol IDs in quoting order: the priority list first, then the other
+/// IDs in ascending order, restricted to `only` when given
+///
+/// Adapters are not loaded here; quoting loops read each one when its turn
+/// comes, so a loop that stops early never touches the rest.
+pub fn quote_order(env: &Env, only: &Option<Vec<u32>>) -> Vec<u32> {
+    let allowed = |protocol_id: u32| match only {
+        Some(ids) => ids.contains(protocol_id),
+        None => true,
+    };
+
+    let mut order = Vec::new(env);
+    let ids = get_protocol_priority(env)
+        .iter()
+        .chain(0..get_protocol_count(env));
+    for protocol_id in ids {
+        if allowed(protocol_id) && !order.contains(protocol_id) {
+            order.push_back(protocol_id);
+        }
+    }
+    order
+}
+
+/// Get the early-stop distance from the oracle price in bps (0 = disabled)
+pub fn get_early_stop_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get::<DataKey, u32>(&DataKey::EarlyStopBps)
+        .unwrap_or(0)
+}
+
+/// Set the early-stop distance from the oracle price in bps
+pub fn set_early_stop_bps(env: &Env, bps: u32) {
+    env.storage().instance().set(&DataKey::EarlyStopBps, &bps);
+}
+
+/// Get the analytics contract executed swaps are reported to
+pub fn get_analytics(env: &Env) -> Option<Address> {
+    env.storage()
+        .instance()
+        .get::<DataKey, Address>(&DataKey::Analytics)
+}
+
+/// Set or clear the analytics contract executed swaps are reported to
+pub fn set_analytics(env: &Env, analytics: &Option<Address>) {
+    match analytics {
+        Some(analytics) => env.storage().instance().set(&DataKey::Analytics, analytics),
+        None => env.storage().instance().remove(&DataKey::Analytics),
+    }
+}
+
+/// Get the connector tokens (empty if unset)
+pub fn get_connectors(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get::<DataKey, Vec<Address>>(&DataKey::Connectors)
+        .unwrap_or(Vec::new(env))
+}
+
+/// Set the connector tokens
+pub fn set_connectors(env: &Env, connectors: &Vec<Address>) {
+    env.storage()
+        .instance()
+        .set(&DataKey::Connectors, connectors);
 }
 
 // ==================== Fee Recipient ====================
@@ -181,6 +383,88 @@ pub fn set_fee_recipient(env: &Env, recipient: &Address) {
         .set(&DataKey::FeeRecipient, recipient);
 }
 
+/// Get the external quote guard, if enabled
+pub fn get_quote_guard(env: &Env) -> Option<QuoteGuard> {
+    env.storage()
+        .instance()
+        .get::<DataKey, QuoteGuard>(&DataKey::QuoteGuard)
+}
+
+/// Set the external quote guard
+pub fn set_quote_guard(env: &Env, guard: &QuoteGuard) {
+    env.storage().instance().set(&DataKey::QuoteGuard, guard);
+}
+
+/// Disable the external quote guard
+pub fn remove_quote_guard(env: &Env) {
+    env.storage().instance().remove(&DataKey::QuoteGuard);
+}
+
+// ==================== Volume Tracking ====================
+
+/// Get configured fee tiers, if any
+pub fn get_fee_tiers(env: &Env) -> Option<Vec<FeeTier>> {
+    env.storage()
+        .instance()
+        .get::<DataKey, Vec<FeeTier>>(&DataKey::FeeTiers)
+}
+
+/// Set fee tiers
+pub fn set_fee_tiers(env: &Env, tiers: &Vec<FeeTier>) {
+    env.storage().instance().set(&DataKey::FeeTiers, tiers);
+}
+
+/// Get a user's volume buckets (oldest first)
+pub fn get_user_volume(env: &Env, user: &Address) -> Vec<VolumeBucket> {
+    env.storage()
+        .persistent()
+        .get::<DataKey, Vec<VolumeBucket>>(&DataKey::UserVolume(user.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Set a user's volume buckets
+pub fn set_user_volume(env: &Env, user: &Address, buckets: &Vec<VolumeBucket>) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::UserVolume(user.clone()), buckets);
+}
+
+// ==================== User Preferences ====================
+
+/// Get a user's default max slippage in bps, if set
+pub fn get_slippage_preference(env: &Env, user: &Address) -> Option<u32> {
+    env.storage()
+        .persistent()
+        .get::<DataKey, u32>(&DataKey::UserSlippage(user.clone()))
+}
+
+/// Set a user's default max slippage in bps, or clear it with 0
+pub fn set_slippage_preference(env: &Env, user: &Address, max_slippage_bps: u32) {
+    let key = DataKey::UserSlippage(user.clone());
+    if max_slippage_bps == 0 {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &max_slippage_bps);
+    }
+}
+
+// ==================== Route Statistics ====================
+
+/// Get a protocol's routing statistics (zeroed if it never routed)
+pub fn get_protocol_stats(env: &Env, protocol_id: u32) -> ProtocolStats {
+    env.storage()
+        .persistent()
+        .get::<DataKey, ProtocolStats>(&DataKey::ProtocolStats(protocol_id))
+        .unwrap_or_default()
+}
+
+/// Set a protocol's routing statistics
+pub fn set_protocol_stats(env: &Env, protocol_id: u32, stats: &ProtocolStats) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::ProtocolStats(protocol_id), stats);
+}
+
 // ==================== TTL Management ====================
 
 /// Extend TTL for instance storage
@@ -197,3 +481,21 @@ pub fn extend_protocol_ttl(env: &Env, protocol_id: u32) {
         .persistent()
         .extend_ttl(&DataKey::Protocol(protocol_id), max_ttl - 1000, max_ttl);
 }
+
+/// Extend TTL for a protocol's routing statistics
+pub fn extend_protocol_stats_ttl(env: &Env, protocol_id: u32) {
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().extend_ttl(
+        &DataKey::ProtocolStats(protocol_id),
+        max_ttl - 1000,
+        max_ttl,
+    );
+}
+
+/// Extend TTL for a user's volume buckets
+pub fn extend_user_volume_ttl(env: &Env, user: &Address) {
+    let max_ttl = env.storage().max_ttl();
+    env.storage()
+        .persistent()
+        .extend_ttl(&DataKey::UserVolume(user.clone()), max_ttl - 1000, max_ttl);
+}
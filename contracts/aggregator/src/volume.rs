@@ -0,0 +1,235 @@
+//! Volume tracking and fee tiers
+//!
+//! Each user's swap volume is accumulated into daily epoch buckets; the sum of
+//! the buckets inside the 30-day window selects a fee tier. Volume is counted in
+//! raw units of the swap's input token (7 decimals on Stellar), so tier
+//! thresholds assume routes are mostly quoted from a common base asset.
+
+use astroswap_shared::{require_bps_range, AstroSwapError, MAX_FEE_BPS};
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::storage::{
+    extend_user_volume_ttl, get_fee_tiers, get_user_volume, set_user_volume, AggregatorConfig,
+    FeeTier, VolumeBucket,
+};
+
+/// Length of one volume epoch in seconds (1 day)
+pub const VOLUME_EPOCH_SECONDS: u64 = 86_400;
+
+/// Number of epochs in the rolling volume window (30 days)
+pub const VOLUME_WINDOW_EPOCHS: u64 = 30;
+
+/// Maximum number of fee tiers
+pub const MAX_FEE_TIERS: u32 = 8;
+
+/// One whole token in raw units (7 decimals)
+const TOKEN: i128 = 10_000_000;
+
+fn current_epoch(env: &Env) -> u64 {
+    env.ledger().timestamp() / VOLUME_EPOCH_SECONDS
+}
+
+/// Buckets of `user` still inside the rolling window
+fn live_buckets(env: &Env, user: &Address) -> Vec<VolumeBucket> {
+    let oldest = current_epoch(env).saturating_sub(VOLUME_WINDOW_EPOCHS - 1);
+    let mut live = Vec::new(env);
+    for bucket in get_user_volume(env, user).iter() {
+        if bucket.epoch >= oldest {
+            live.push_back(bucket);
+        }
+    }
+    live
+}
+
+/// Rolling 30-day volume of `user`
+pub fn rolling_volume(env: &Env, user: &Address) -> i128 {
+    live_buckets(env, user)
+        .iter()
+        .fold(0i128, |total, bucket| total.saturating_add(bucket.volume))
+}
+
+/// Add `amount` to the current epoch of `user`, dropping expired buckets
+pub fn record_volume(env: &Env, user: &Address, amount: i128) {
+    if amount <= 0 {
+        return;
+    }
+
+    let epoch = current_epoch(env);
+    let mut buckets = live_buckets(env, user);
+    match buckets.last() {
+        Some(mut last) if last.epoch == epoch => {
+            last.volume = last.volume.saturating_add(amount);
+            buckets.set(buckets.len() - 1, last);
+        }
+        _ => buckets.push_back(VolumeBucket {
+            epoch,
+            volume: amount,
+        }),
+    }
+
+    set_user_volume(env, user, &buckets);
+    extend_user_volume_ttl(env, user);
+}
+
+/// Tiers used until the admin configures their own:
+/// 0.05% base, 0.04% from 100k, 0.03% from 1M, 0.02% from 10M
+pub fn default_fee_tiers(env: &Env) -> Vec<FeeTier> {
+    Vec::from_array(
+        env,
+        [
+            FeeTier {
+                min_volume: 0,
+                fee_bps: 5,
+            },
+            FeeTier {
+                min_volume: 100_000 * TOKEN,
+                fee_bps: 4,
+            },
+            FeeTier {
+                min_volume: 1_000_000 * TOKEN,
+                fee_bps: 3,
+            },
+            FeeTier {
+                min_volume: 10_000_000 * TOKEN,
+                fee_bps: 2,
+            },
+        ],
+    )
+}
+
+/// Configured tiers, or the defaults
+pub fn fee_tiers(env: &Env) -> Vec<FeeTier> {
+    get_fee_tiers(env).unwrap_or_else(|| default_fee_tiers(env))
+}
+
+/// Highest tier whose threshold `volume` reaches
+pub fn tier_for_volume(env: &Env, volume: i128) -> FeeTier {
+    let tiers = fee_tiers(env);
+    let mut selected = tiers.get(0).unwrap_or(FeeTier {
+        min_volume: 0,
+        fee_bps: 0,
+    });
+    for tier in tiers.iter() {
+        if volume >= tier.min_volume {
+            selected = tier;
+        }
+    }
+    selected
+}
+
+/// Aggregator fee for `user`: their tier's fee, never above the configured fee
+pub fn effective_fee_bps(env: &Env, user: &Address, config: &AggregatorConfig) -> u32 {
+    tier_for_volume(env, rolling_volume(env, user))
+        .fee_bps
+        .min(config.aggregator_fee_bps)
+}
+
+/// Validate admin-supplied tiers: 1..=MAX_FEE_TIERS tiers, the first starting at
+/// zero volume, thresholds strictly increasing and fees never increasing
+pub fn validate_fee_tiers(tiers: &Vec<FeeTier>) -> Result<(), AstroSwapError> {
+    if tiers.is_empty() || tiers.len() > MAX_FEE_TIERS {
+        return Err(AstroSwapError::InvalidArgument);
+    }
+
+    let mut previous: Option<FeeTier> = None;
+    for tier in tiers.iter() {
+        require_bps_range(tier.fee_bps, MAX_FEE_BPS)?;
+        let ordered = match &previous {
+            None => tier.min_volume == 0,
+            Some(prev) => tier.min_volume > prev.min_volume && tier.fee_bps <= prev.fee_bps,
+        };
+        if !ordered {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+        previous = Some(tier);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AstroSwapAggregator;
+    use soroban_sdk::testutils::{Address as _, Ledger};
+
+    fn config(aggregator_fee_bps: u32) -> AggregatorConfig {
+        AggregatorConfig {
+            max_hops: 3,
+            max_splits: 2,
+            aggregator_fee_bps,
+            hop_slippage_bps: 100,
+        }
+    }
+
+    #[test]
+    fn test_volume_window_and_tiers() {
+        let env = Env::default();
+        let contract_id = env.register(AstroSwapAggregator, ());
+        let user = Address::generate(&env);
+
+        env.as_contract(&contract_id, || {
+            assert_eq!(effective_fee_bps(&env, &user, &config(5)), 5);
+
+            record_volume(&env, &user, 60_000 * TOKEN);
+            record_volume(&env, &user, 50_000 * TOKEN);
+            assert_eq!(get_user_volume(&env, &user).len(), 1);
+            assert_eq!(rolling_volume(&env, &user), 110_000 * TOKEN);
+            assert_eq!(effective_fee_bps(&env, &user, &config(5)), 4);
+            // A lower configured fee is never raised by the tier
+            assert_eq!(effective_fee_bps(&env, &user, &config(1)), 1);
+
+            // New epoch gets its own bucket
+            env.ledger()
+                .with_mut(|li| li.timestamp += VOLUME_EPOCH_SECONDS);
+            record_volume(&env, &user, 1_000_000 * TOKEN);
+            assert_eq!(get_user_volume(&env, &user).len(), 2);
+            assert_eq!(effective_fee_bps(&env, &user, &config(5)), 3);
+
+            // First bucket falls out of the 30-day window
+            env.ledger()
+                .with_mut(|li| li.timestamp += (VOLUME_WINDOW_EPOCHS - 1) * VOLUME_EPOCH_SECONDS);
+            assert_eq!(rolling_volume(&env, &user), 1_000_000 * TOKEN);
+
+            // Everything expired
+            env.ledger()
+                .with_mut(|li| li.timestamp += VOLUME_EPOCH_SECONDS);
+            assert_eq!(rolling_volume(&env, &user), 0);
+            assert_eq!(effective_fee_bps(&env, &user, &config(5)), 5);
+        });
+    }
+
+    #[test]
+    fn test_validate_fee_tiers() {
+        let env = Env::default();
+        assert_eq!(validate_fee_tiers(&default_fee_tiers(&env)), Ok(()));
+        assert_eq!(
+            validate_fee_tiers(&Vec::new(&env)),
+            Err(AstroSwapError::InvalidArgument)
+        );
+
+        let tier = |min_volume: i128, fee_bps: u32| FeeTier {
+            min_volume,
+            fee_bps,
+        };
+        let not_from_zero = Vec::from_array(&env, [tier(10, 5)]);
+        assert_eq!(
+            validate_fee_tiers(&not_from_zero),
+            Err(AstroSwapError::InvalidArgument)
+        );
+        let rising_fee = Vec::from_array(&env, [tier(0, 3), tier(10, 4)]);
+        assert_eq!(
+            validate_fee_tiers(&rising_fee),
+            Err(AstroSwapError::InvalidArgument)
+        );
+        let unsorted = Vec::from_array(&env, [tier(0, 5), tier(10, 4), tier(10, 3)]);
+        assert_eq!(
+            validate_fee_tiers(&unsorted),
+            Err(AstroSwapError::InvalidArgument)
+        );
+        let too_high = Vec::from_array(&env, [tier(0, MAX_FEE_BPS + 1)]);
+        assert_eq!(
+            validate_fee_tiers(&too_high),
+            Err(AstroSwapError::FeeTooHigh)
+        );
+    }
+}
@@ -0,0 +1,398 @@
+use astroswap_shared::{safe_add, AstroSwapError, FactoryClient};
+use soroban_sdk::{contract, contractimpl, Address, Env};
+
+use crate::storage::{
+    current_day, extend_instance_ttl, get_admin, get_factory, get_stats, get_totals,
+    is_initialized, is_known_pair, is_reporter, set_admin, set_factory, set_initialized,
+    set_known_pair, set_reporter, set_stats, set_totals, DataKey, ProtocolTotals, VolumeStats,
+};
+
+#[contract]
+pub struct AstroSwapAnalytics;
+
+#[contractimpl]
+impl AstroSwapAnalytics {
+    /// Initialize the analytics contract
+    ///
+    /// # Arguments
+    /// * `admin` - Manages the reporter allowlist
+    /// * `factory` - Factory whose pairs may report swaps
+    pub fn initialize(env: Env, admin: Address, factory: Address) -> Result<(), AstroSwapError> {
+        if is_initialized(&env) {
+            return Err(AstroSwapError::AlreadyInitialized);
+        }
+
+        set_admin(&env, &admin);
+        set_factory(&env, &factory);
+        set_initialized(&env);
+
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    // ==================== Reporting ====================
+
+    /// Record a swap executed by an AstroSwap pair
+    /// Only the pair itself can call, and only if the factory deployed it
+    ///
+    /// Counts `amount_in` and `fee` (both in `token_in`) towards the pair's
+    /// daily and cumulative counters and the protocol-wide counters of
+    /// `token_in`. Pairs call this fire-and-forget after each swap.
+    pub fn record_swap(
+        env: Env,
+        pair: Address,
+        token_0: Address,
+        token_1: Address,
+        token_in: Address,
+        amount_in: i128,
+        fee: i128,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_initialized(&env)?;
+        pair.require_auth();
+        Self::require_pair(&env, &pair, &token_0, &token_1)?;
+
+        if token_in != token_0 && token_in != token_1 {
+            return Err(AstroSwapError::InvalidToken);
+        }
+        Self::require_amounts(amount_in, fee)?;
+
+        let day = current_day(&env);
+        for key in [
+            DataKey::PairDay(pair.clone(), token_in.clone(), day),
+            DataKey::PairTotal(pair.clone(), token_in.clone()),
+            DataKey::TokenDay(token_in.clone(), day),
+            DataKey::TokenTotal(token_in.clone()),
+        ] {
+            Self::add_swap(&env, &key, amount_in, fee)?;
+        }
+
+        let mut totals = get_totals(&env);
+        totals.pair_swaps += 1;
+        set_totals(&env, &totals);
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Record a swap routed by an allowlisted reporter (e.g. the aggregator)
+    /// Only the reporter itself can call
+    ///
+    /// `fee` is the reporter's own fee on `amount_in`. Routed swaps are kept
+    /// apart from the per-token counters, since hops through AstroSwap pairs
+    /// are already reported by the pairs.
+    pub fn record_routed_swap(
+        env: Env,
+        reporter: Address,
+        token_in: Address,
+        amount_in: i128,
+        fee: i128,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_initialized(&env)?;
+        reporter.require_auth();
+
+        if !is_reporter(&env, &reporter) {
+            return Err(AstroSwapError::Unauthorized);
+        }
+        Self::require_amounts(amount_in, fee)?;
+
+        let day = current_day(&env);
+        for key in [
+            DataKey::ReporterDay(reporter.clone(), token_in.clone(), day),
+            DataKey::ReporterTotal(reporter.clone(), token_in.clone()),
+        ] {
+            Self::add_swap(&env, &key, amount_in, fee)?;
+        }
+
+        let mut totals = get_totals(&env);
+        totals.routed_swaps += 1;
+        set_totals(&env, &totals);
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    // ==================== Admin Functions ====================
+
+    /// Allow or disallow an address to report routed swaps
+    /// Only admin can call
+    pub fn set_reporter(
+        env: Env,
+        admin: Address,
+        reporter: Address,
+        allowed: bool,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+        set_reporter(&env, &reporter, allowed);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Transfer admin rights
+    /// Only admin can call
+    pub fn set_admin(env: Env, admin: Address, new_admin: Address) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+        set_admin(&env, &new_admin);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    // ==================== View Functions ====================
+
+    /// Swaps of `token_in` into a pair on `day`
+    pub fn pair_day(env: Env, pair: Address, token_in: Address, day: u64) -> VolumeStats {
+        get_stats(&env, &DataKey::PairDay(pair, token_in, day))
+    }
+
+    /// Swaps of `token_in` into a pair since it first reported
+    pub fn pair_total(env: Env, pair: Address, token_in: Address) -> VolumeStats {
+        get_stats(&env, &DataKey::PairTotal(pair, token_in))
+    }
+
+    /// Swaps of `token_in` into any pair on `day`
+    pub fn token_day(env: Env, token_in: Address, day: u64) -> VolumeStats {
+        get_stats(&env, &DataKey::TokenDay(token_in, day))
+    }
+
+    /// Swaps of `token_in` into any pair
+    pub fn token_total(env: Env, token_in: Address) -> VolumeStats {
+        get_stats(&env, &DataKey::TokenTotal(token_in))
+    }
+
+    /// Swaps of `token_in` routed by a reporter on `day`
+    pub fn reporter_day(env: Env, reporter: Address, token_in: Address, day: u64) -> VolumeStats {
+        get_stats(&env, &DataKey::ReporterDay(reporter, token_in, day))
+    }
+
+    /// Swaps of `token_in` routed by a reporter
+    pub fn reporter_total(env: Env, reporter: Address, token_in: Address) -> VolumeStats {
+        get_stats(&env, &DataKey::ReporterTotal(reporter, token_in))
+    }
+
+    /// Protocol-wide swap counts
+    pub fn totals(env: Env) -> ProtocolTotals {
+        get_totals(&env)
+    }
+
+    /// Current reporting day (ledger timestamp / 86400)
+    pub fn current_day(env: Env) -> u64 {
+        current_day(&env)
+    }
+
+    /// Check if an address may report routed swaps
+    pub fn is_reporter(env: Env, reporter: Address) -> bool {
+        is_reporter(&env, &reporter)
+    }
+
+    /// Get admin address
+    pub fn admin(env: Env) -> Address {
+        get_admin(&env)
+    }
+
+    /// Get factory address
+    pub fn factory(env: Env) -> Address {
+        get_factory(&env)
+    }
+
+    // ==================== Internal Functions ====================
+
+    /// Verify contract is initialized (prevents storage panics)
+    fn require_initialized(env: &Env) -> Result<(), AstroSwapError> {
+        if !is_initialized(env) {
+            return Err(AstroSwapError::NotInitialized);
+        }
+        Ok(())
+    }
+
+    /// Verify caller is admin
+    fn require_admin(env: &Env, caller: &Address) -> Result<(), AstroSwapError> {
+        Self::require_initialized(env)?;
+        caller.require_auth();
+
+        if *caller != get_admin(env) {
+            return Err(AstroSwapError::Unauthorized);
+        }
+        Ok(())
+    }
+
+    /// Verify `pair` is one of the factory's pairs for its tokens, in any fee tier
+    /// The factory is only asked once per pair
+    fn require_pair(
+        env: &Env,
+        pair: &Address,
+        token_0: &Address,
+        token_1: &Address,
+    ) -> Result<(), AstroSwapError> {
+        if is_known_pair(env, pair) {
+            return Ok(());
+        }
+
+        let factory = FactoryClient::new(env, &get_factory(env));
+        let is_factory_pair = factory
+            .get_pairs_all_tiers(token_0, token_1)
+            .iter()
+            .any(|(tier_pair, _)| tier_pair == *pair);
+        if !is_factory_pair {
+            return Err(AstroSwapError::Unauthorized);
+        }
+
+        set_known_pair(env, pair);
+        Ok(())
+    }
+
+    /// Reject negative amounts and fees above the amount
+    fn require_amounts(amount_in: i128, fee: i128) -> Result<(), AstroSwapError> {
+        if amount_in <= 0 || fee < 0 || fee > amount_in {
+            return Err(AstroSwapError::InvalidAmount);
+        }
+        Ok(())
+    }
+
+    /// Add one swap to the counters under `key`
+    fn add_swap(
+        env: &Env,
+        key: &DataKey,
+        amount_in: i128,
+        fee: i128,
+    ) -> Result<(), AstroSwapError> {
+        let mut stats = get_stats(env, key);
+        stats.swaps += 1;
+        stats.volume = safe_add(stats.volume, amount_in)?;
+        stats.fees = safe_add(stats.fees, fee)?;
+        set_stats(env, key, &stats);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, Ledger};
+    use soroban_sdk::Vec;
+
+    // Factory stub knowing a single default-tier pair
+    #[contract]
+    struct MockFactory;
+
+    #[contractimpl]
+    impl MockFactory {
+        pub fn set_pair(env: Env, pair: Address) {
+            env.storage().instance().set(&0u32, &pair);
+        }
+
+        pub fn get_pairs_all_tiers(
+            env: Env,
+            _token_a: Address,
+            _token_b: Address,
+        ) -> Vec<(Address, u32)> {
+            let mut pairs = Vec::new(&env);
+            if let Some(pair) = env.storage().instance().get(&0u32) {
+                pairs.push_back((pair, 30u32));
+            }
+            pairs
+        }
+    }
+
+    fn setup(env: &Env) -> (AstroSwapAnalyticsClient<'_>, Address, Address) {
+        env.mock_all_auths();
+
+        let factory = env.register(MockFactory, ());
+        let pair = Address::generate(env);
+        MockFactoryClient::new(env, &factory).set_pair(&pair);
+
+        let contract_id = env.register(AstroSwapAnalytics, ());
+        let client = AstroSwapAnalyticsClient::new(env, &contract_id);
+        let admin = Address::generate(env);
+        client.initialize(&admin, &factory);
+
+        (client, admin, pair)
+    }
+
+    #[test]
+    fn test_initialize() {
+        let env = Env::default();
+        let (client, admin, _) = setup(&env);
+
+        assert_eq!(client.admin(), admin);
+        assert_eq!(
+            client.try_initialize(&admin, &client.factory()),
+            Err(Ok(AstroSwapError::AlreadyInitialized))
+        );
+    }
+
+    #[test]
+    fn test_record_swap_daily_and_cumulative() {
+        let env = Env::default();
+        let (client, _, pair) = setup(&env);
+        let token_0 = Address::generate(&env);
+        let token_1 = Address::generate(&env);
+
+        env.ledger().with_mut(|li| li.timestamp = 3 * 86_400 + 10);
+        client.record_swap(&pair, &token_0, &token_1, &token_0, &1_000, &3);
+        client.record_swap(&pair, &token_0, &token_1, &token_0, &2_000, &6);
+        client.record_swap(&pair, &token_0, &token_1, &token_1, &500, &1);
+
+        env.ledger().with_mut(|li| li.timestamp += 86_400);
+        client.record_swap(&pair, &token_0, &token_1, &token_0, &4_000, &12);
+
+        let expected = VolumeStats {
+            swaps: 2,
+            volume: 3_000,
+            fees: 9,
+        };
+        assert_eq!(client.pair_day(&pair, &token_0, &3), expected);
+        assert_eq!(client.token_day(&token_0, &3), expected);
+        assert_eq!(client.pair_day(&pair, &token_0, &4).volume, 4_000);
+        assert_eq!(client.pair_total(&pair, &token_0).volume, 7_000);
+        assert_eq!(client.token_total(&token_1).fees, 1);
+        assert_eq!(client.totals().pair_swaps, 4);
+
+        // Only the factory's pair, for its own tokens
+        let other = Address::generate(&env);
+        assert_eq!(
+            client.try_record_swap(&other, &token_0, &token_1, &token_0, &1_000, &3),
+            Err(Ok(AstroSwapError::Unauthorized))
+        );
+        assert_eq!(
+            client.try_record_swap(&pair, &token_0, &token_1, &other, &1_000, &3),
+            Err(Ok(AstroSwapError::InvalidToken))
+        );
+        assert_eq!(
+            client.try_record_swap(&pair, &token_0, &token_1, &token_0, &1_000, &1_001),
+            Err(Ok(AstroSwapError::InvalidAmount))
+        );
+    }
+
+    #[test]
+    fn test_routed_swaps_need_allowlisted_reporter() {
+        let env = Env::default();
+        let (client, admin, _) = setup(&env);
+        let reporter = Address::generate(&env);
+        let token = Address::generate(&env);
+
+        assert_eq!(
+            client.try_record_routed_swap(&reporter, &token, &1_000, &5),
+            Err(Ok(AstroSwapError::Unauthorized))
+        );
+        assert_eq!(
+            client.try_set_reporter(&reporter, &reporter, &true),
+            Err(Ok(AstroSwapError::Unauthorized))
+        );
+
+        client.set_reporter(&admin, &reporter, &true);
+        assert!(client.is_reporter(&reporter));
+        client.record_routed_swap(&reporter, &token, &1_000, &5);
+
+        let day = client.current_day();
+        assert_eq!(client.reporter_day(&reporter, &token, &day).fees, 5);
+        assert_eq!(client.reporter_total(&reporter, &token).swaps, 1);
+        // Kept apart from the per-token pair counters
+        assert_eq!(client.token_total(&token), VolumeStats::default());
+        assert_eq!(client.totals().routed_swaps, 1);
+
+        client.set_reporter(&admin, &reporter, &false);
+        assert!(client
+            .try_record_routed_swap(&reporter, &token, &1_000, &5)
+            .is_err());
+    }
+}
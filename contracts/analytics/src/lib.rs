@@ -0,0 +1,7 @@
+#![no_std]
+
+mod contract;
+mod storage;
+
+pub use contract::{AstroSwapAnalytics, AstroSwapAnalyticsClient};
+pub use storage::{ProtocolTotals, VolumeStats, SECONDS_PER_DAY};
@@ -0,0 +1,177 @@
+//! Storage module for AstroSwap Analytics
+//!
+//! Swap counters are kept per day (ledger timestamp / `SECONDS_PER_DAY`) and
+//! cumulatively, in raw units of the swap's input token.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+/// Length of a reporting day in seconds
+pub const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Storage keys for the analytics contract
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Admin,
+    Factory,
+    Initialized,
+    Totals,
+    /// Allowlisted reporter (router, aggregator)
+    Reporter(Address),
+    /// Pair verified against the factory
+    KnownPair(Address),
+    /// (pair, token_in, day)
+    PairDay(Address, Address, u64),
+    /// (pair, token_in)
+    PairTotal(Address, Address),
+    /// (token_in, day), over all pairs
+    TokenDay(Address, u64),
+    /// token_in, over all pairs
+    TokenTotal(Address),
+    /// (reporter, token_in, day)
+    ReporterDay(Address, Address, u64),
+    /// (reporter, token_in)
+    ReporterTotal(Address, Address),
+}
+
+/// Swap counters of one input token
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct VolumeStats {
+    /// Swaps recorded
+    pub swaps: u64,
+    /// Input swapped
+    pub volume: i128,
+    /// Fees charged on the input
+    pub fees: i128,
+}
+
+/// Protocol-wide swap counts
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ProtocolTotals {
+    /// Swaps reported by AstroSwap pairs
+    pub pair_swaps: u64,
+    /// Swaps reported by allowlisted reporters
+    pub routed_swaps: u64,
+}
+
+/// Check if the contract is initialized
+pub fn is_initialized(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get::<DataKey, bool>(&DataKey::Initialized)
+        .unwrap_or(false)
+}
+
+/// Set initialized flag
+pub fn set_initialized(env: &Env) {
+    env.storage().instance().set(&DataKey::Initialized, &true);
+}
+
+/// Get the admin address
+pub fn get_admin(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get::<DataKey, Address>(&DataKey::Admin)
+        .expect("Admin not set")
+}
+
+/// Set the admin address
+pub fn set_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&DataKey::Admin, admin);
+}
+
+/// Get the factory whose pairs may report swaps
+pub fn get_factory(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get::<DataKey, Address>(&DataKey::Factory)
+        .expect("Factory not set")
+}
+
+/// Set the factory whose pairs may report swaps
+pub fn set_factory(env: &Env, factory: &Address) {
+    env.storage().instance().set(&DataKey::Factory, factory);
+}
+
+/// Get the protocol-wide swap counts
+pub fn get_totals(env: &Env) -> ProtocolTotals {
+    env.storage()
+        .instance()
+        .get::<DataKey, ProtocolTotals>(&DataKey::Totals)
+        .unwrap_or_default()
+}
+
+/// Set the protocol-wide swap counts
+pub fn set_totals(env: &Env, totals: &ProtocolTotals) {
+    env.storage().instance().set(&DataKey::Totals, totals);
+}
+
+/// Check if an address may report routed swaps
+pub fn is_reporter(env: &Env, reporter: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .get::<DataKey, bool>(&DataKey::Reporter(reporter.clone()))
+        .unwrap_or(false)
+}
+
+/// Allow or disallow an address to report routed swaps
+pub fn set_reporter(env: &Env, reporter: &Address, allowed: bool) {
+    let key = DataKey::Reporter(reporter.clone());
+    if !allowed {
+        env.storage().persistent().remove(&key);
+        return;
+    }
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, &true);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+/// Check if a pair was already verified against the factory
+pub fn is_known_pair(env: &Env, pair: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .get::<DataKey, bool>(&DataKey::KnownPair(pair.clone()))
+        .unwrap_or(false)
+}
+
+/// Remember a pair verified against the factory
+pub fn set_known_pair(env: &Env, pair: &Address) {
+    let key = DataKey::KnownPair(pair.clone());
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, &true);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+/// Get the counters stored under `key`
+pub fn get_stats(env: &Env, key: &DataKey) -> VolumeStats {
+    env.storage()
+        .persistent()
+        .get::<DataKey, VolumeStats>(key)
+        .unwrap_or_default()
+}
+
+/// Set the counters stored under `key`
+pub fn set_stats(env: &Env, key: &DataKey, stats: &VolumeStats) {
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(key, stats);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, max_ttl - 1000, max_ttl);
+}
+
+/// Current reporting day
+pub fn current_day(env: &Env) -> u64 {
+    env.ledger().timestamp() / SECONDS_PER_DAY
+}
+
+/// Extend TTL for instance storage
+pub fn extend_instance_ttl(env: &Env) {
+    let max_ttl = env.storage().max_ttl();
+    env.storage().instance().extend_ttl(max_ttl - 1000, max_ttl);
+}
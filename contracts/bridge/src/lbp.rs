@@ -0,0 +1,314 @@
+//! Liquidity bootstrapping pool (LBP) math
+//!
+//! A Balancer-style two-token weighted pool. Swaps follow the weighted
+//! constant-product invariant
+//!
+//! `out = balance_out * (1 - (balance_in / (balance_in + in_after_fee)) ^ (w_in / w_out))`
+//!
+//! with the token's weight falling linearly to 50% over the auction, so the
+//! price drifts down until buyers step in. At 50/50 the pool prices exactly
+//! like a constant-product pair, which is what it converts into at the end.
+//!
+//! Powers are computed in 18-decimal fixed point through `ln` and `exp`.
+
+use astroswap_shared::{
+    mul_div_down, safe_add, safe_mul, AstroSwapError, BPS_DENOMINATOR, PRECISION_DECIMALS,
+};
+
+use crate::storage::LbpPool;
+use crate::PRICE_PRECISION;
+
+/// Shortest auction (24 hours)
+pub const LBP_MIN_DURATION: u64 = 24 * 60 * 60;
+
+/// Longest auction (72 hours)
+pub const LBP_MAX_DURATION: u64 = 72 * 60 * 60;
+
+/// Token weight at the end of every auction (50%)
+pub const LBP_END_WEIGHT_BPS: u32 = 5_000;
+
+/// Highest token weight an auction may start at (98%)
+pub const LBP_MAX_START_WEIGHT_BPS: u32 = 9_800;
+
+/// Swap fee kept in the pool (1%)
+pub const LBP_SWAP_FEE_BPS: u32 = 100;
+
+/// Largest swap input relative to the input balance (30%)
+pub const LBP_MAX_IN_RATIO_BPS: u32 = 3_000;
+
+/// Time after an auction's end before a pool that could not be finalized
+/// can be refunded with `recover_lbp` (7 days)
+pub const LBP_RECOVERY_DELAY: u64 = 7 * 24 * 60 * 60;
+
+/// 1.0 in 18-decimal fixed point
+const ONE: i128 = 10i128.pow(PRECISION_DECIMALS);
+
+/// ln(2) in 18-decimal fixed point
+const LN_2: i128 = 693_147_180_559_945_309;
+
+/// Absolute error allowance added to computed powers, so rounding in the
+/// series never pays out more than the invariant allows
+const POW_ERROR_MARGIN: i128 = 1_000_000;
+
+/// Validate auction parameters
+pub fn validate_lbp_params(start_weight_bps: u32, duration: u64) -> Result<(), AstroSwapError> {
+    if start_weight_bps <= LBP_END_WEIGHT_BPS || start_weight_bps > LBP_MAX_START_WEIGHT_BPS {
+        return Err(AstroSwapError::InvalidArgument);
+    }
+    if !(LBP_MIN_DURATION..=LBP_MAX_DURATION).contains(&duration) {
+        return Err(AstroSwapError::InvalidArgument);
+    }
+    Ok(())
+}
+
+/// Token and quote weights (basis points) at `now`
+pub fn weights_at(pool: &LbpPool, now: u64) -> (u32, u32) {
+    let duration = pool.end_time - pool.start_time;
+    let elapsed = now.clamp(pool.start_time, pool.end_time) - pool.start_time;
+    let drop = u64::from(pool.start_weight_bps - LBP_END_WEIGHT_BPS) * elapsed / duration;
+    let token_weight = pool.start_weight_bps - drop as u32;
+    (token_weight, BPS_DENOMINATOR - token_weight)
+}
+
+/// Spot price of the token in quote units (7 decimals), excluding fees
+pub fn spot_price(pool: &LbpPool, now: u64) -> Result<i128, AstroSwapError> {
+    let (token_weight, quote_weight) = weights_at(pool, now);
+    mul_div_down(
+        safe_mul(pool.quote_balance, i128::from(token_weight))?,
+        PRICE_PRECISION,
+        safe_mul(pool.token_balance, i128::from(quote_weight))?,
+    )
+}
+
+/// Output of a swap of `amount_in` into the pool at `now`
+///
+/// `token_in_is_quote` selects the direction: buying the token with quote
+/// (`true`) or selling it back (`false`).
+pub fn amount_out(
+    pool: &LbpPool,
+    now: u64,
+    token_in_is_quote: bool,
+    amount_in: i128,
+) -> Result<i128, AstroSwapError> {
+    let (token_weight, quote_weight) = weights_at(pool, now);
+    let (balance_in, balance_out, weight_in, weight_out) = if token_in_is_quote {
+        (
+            pool.quote_balance,
+            pool.token_balance,
+            quote_weight,
+            token_weight,
+        )
+    } else {
+        (
+            pool.token_balance,
+            pool.quote_balance,
+            token_weight,
+            quote_weight,
+        )
+    };
+
+    let max_in = mul_div_down(
+        balance_in,
+        i128::from(LBP_MAX_IN_RATIO_BPS),
+        i128::from(BPS_DENOMINATOR),
+    )?;
+    if amount_in > max_in {
+        return Err(AstroSwapError::PriceImpactTooHigh);
+    }
+
+    let in_after_fee = mul_div_down(
+        amount_in,
+        i128::from(BPS_DENOMINATOR - LBP_SWAP_FEE_BPS),
+        i128::from(BPS_DENOMINATOR),
+    )?;
+    if in_after_fee <= 0 {
+        return Err(AstroSwapError::InvalidAmount);
+    }
+
+    // Round the base and the power up: the pool never pays out too much
+    let denominator = safe_add(balance_in, in_after_fee)?;
+    let base = (safe_mul(balance_in, ONE)? + denominator - 1) / denominator;
+    let exponent = mul_div_down(i128::from(weight_in), ONE, i128::from(weight_out))?;
+    let power = (pow_fixed(base, exponent)? + POW_ERROR_MARGIN).min(ONE);
+
+    mul_div_down(balance_out, ONE - power, ONE)
+}
+
+/// `base ^ exponent` for `base` in (0, 1] and a non-negative `exponent`,
+/// both in 18-decimal fixed point
+fn pow_fixed(base: i128, exponent: i128) -> Result<i128, AstroSwapError> {
+    if base <= 0 || base > ONE || exponent < 0 {
+        return Err(AstroSwapError::InvalidArgument);
+    }
+    if base == ONE || exponent == 0 {
+        return Ok(ONE);
+    }
+    Ok(exp_fixed(safe_mul(ln_fixed(base), exponent)? / ONE))
+}
+
+/// Natural logarithm of `x` in (0, 1], fixed point
+///
+/// Scales `x` into [1, 2) by powers of two, then sums the atanh series
+/// `ln(m) = 2 * (z + z^3/3 + z^5/5 + ...)` with `z = (m - 1) / (m + 1)`.
+fn ln_fixed(x: i128) -> i128 {
+    let mut m = x;
+    let mut halvings: i128 = 0;
+    while m < ONE {
+        m *= 2;
+        halvings += 1;
+    }
+
+    let z = (m - ONE) * ONE / (m + ONE);
+    let z_squared = z * z / ONE;
+    let mut term = z;
+    let mut sum = 0;
+    let mut n = 1;
+    while term != 0 {
+        sum += term / n;
+        term = term * z_squared / ONE;
+        n += 2;
+    }
+
+    2 * sum - halvings * LN_2
+}
+
+/// `e ^ y` for `y <= 0`, fixed point
+///
+/// Splits `y` into `-k * ln(2) + r` with `r` in (-ln 2, 0], sums the Taylor
+/// series of `e ^ r` and halves the result `k` times.
+fn exp_fixed(y: i128) -> i128 {
+    let halvings = -y / LN_2;
+    if halvings >= 127 {
+        return 0;
+    }
+    let r = y + halvings * LN_2;
+
+    let mut term = ONE;
+    let mut sum = ONE;
+    let mut n = 1;
+    while term != 0 {
+        term = term * r / (ONE * n);
+        sum += term;
+        n += 1;
+    }
+
+    sum >> halvings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astroswap_shared::TokenMetadata;
+    use soroban_sdk::{testutils::Address as _, Address, Env, String};
+
+    fn pool(env: &Env, token_balance: i128, quote_balance: i128) -> LbpPool {
+        let token = Address::generate(env);
+        LbpPool {
+            token: token.clone(),
+            quote_token: Address::generate(env),
+            funder: Address::generate(env),
+            token_balance,
+            quote_balance,
+            start_weight_bps: 9_000,
+            start_time: 1_000,
+            end_time: 1_000 + LBP_MIN_DURATION,
+            metadata: TokenMetadata {
+                name: String::from_str(env, "Test"),
+                symbol: String::from_str(env, "TEST"),
+                decimals: 7,
+                total_supply: token_balance,
+                creator: token,
+                graduation_time: 1_000,
+            },
+        }
+    }
+
+    #[test]
+    fn test_pow_fixed() {
+        assert_eq!(pow_fixed(ONE, 5 * ONE), Ok(ONE));
+        assert_eq!(pow_fixed(ONE / 2, 0), Ok(ONE));
+        // 0.5^2 = 0.25, 0.25^0.5 = 0.5
+        let quarter = pow_fixed(ONE / 2, 2 * ONE).unwrap();
+        assert!((quarter - ONE / 4).abs() < 1_000);
+        let half = pow_fixed(ONE / 4, ONE / 2).unwrap();
+        assert!((half - ONE / 2).abs() < 1_000);
+        assert_eq!(pow_fixed(0, ONE), Err(AstroSwapError::InvalidArgument));
+        assert_eq!(
+            pow_fixed(2 * ONE, ONE),
+            Err(AstroSwapError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn test_weights_shift_linearly() {
+        let env = Env::default();
+        let pool = pool(&env, 1_000_000, 100_000);
+
+        assert_eq!(weights_at(&pool, 0), (9_000, 1_000));
+        assert_eq!(weights_at(&pool, pool.start_time), (9_000, 1_000));
+        assert_eq!(
+            weights_at(&pool, pool.start_time + LBP_MIN_DURATION / 2),
+            (7_000, 3_000)
+        );
+        assert_eq!(weights_at(&pool, pool.end_time), (5_000, 5_000));
+        assert_eq!(weights_at(&pool, pool.end_time + 1), (5_000, 5_000));
+    }
+
+    #[test]
+    fn test_spot_price_falls_without_trades() {
+        let env = Env::default();
+        // 1M tokens against 100k quote, 90/10: 0.9 quote per token
+        let pool = pool(&env, 1_000_000_0000000, 100_000_0000000);
+
+        assert_eq!(spot_price(&pool, pool.start_time), Ok(9_000_000));
+        // 50/50 at the end: 0.1 quote per token, same as a pair with these reserves
+        assert_eq!(spot_price(&pool, pool.end_time), Ok(1_000_000));
+    }
+
+    #[test]
+    fn test_amount_out_matches_pair_at_equal_weights() {
+        let env = Env::default();
+        let pool = pool(&env, 1_000_000_0000000, 100_000_0000000);
+
+        // At 50/50 the exponent is 1 and the swap is constant product
+        let amount_in = 1_000_0000000i128;
+        let in_after_fee = amount_in * 9_900 / 10_000;
+        let expected = pool.token_balance * in_after_fee / (pool.quote_balance + in_after_fee);
+        let out = amount_out(&pool, pool.end_time, true, amount_in).unwrap();
+        assert!(out <= expected);
+        assert!(expected - out < 100);
+
+        // The heavier token weight early on makes the same buy cheaper in
+        // tokens received
+        let early = amount_out(&pool, pool.start_time, true, amount_in).unwrap();
+        assert!(early < out);
+    }
+
+    #[test]
+    fn test_amount_out_limits() {
+        let env = Env::default();
+        let pool = pool(&env, 1_000_000, 100_000);
+
+        assert_eq!(
+            amount_out(&pool, pool.start_time, true, 30_001),
+            Err(AstroSwapError::PriceImpactTooHigh)
+        );
+        assert_eq!(
+            amount_out(&pool, pool.start_time, true, 1),
+            Err(AstroSwapError::InvalidAmount)
+        );
+        assert_eq!(validate_lbp_params(9_000, LBP_MIN_DURATION), Ok(()));
+        assert_eq!(
+            validate_lbp_params(5_000, LBP_MIN_DURATION),
+            Err(AstroSwapError::InvalidArgument)
+        );
+        assert_eq!(
+            validate_lbp_params(9_900, LBP_MIN_DURATION),
+            Err(AstroSwapError::InvalidArgument)
+        );
+        assert_eq!(
+            validate_lbp_params(9_000, LBP_MAX_DURATION + 1),
+            Err(AstroSwapError::InvalidArgument)
+        );
+    }
+}
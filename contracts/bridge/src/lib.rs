@@ -9,21 +9,46 @@
 //! 2. Launchpad calls `graduate_token()` with liquidity
 //! 3. Bridge creates trading pair via Factory
 //! 4. Bridge adds initial liquidity to the pair
-//! 5. Bridge creates staking pool for LP tokens
+//! 5. Bridge creates staking pool for LP tokens, funded with starter
+//!    rewards when a reward bootstrap is configured
 //! 6. Bridge burns the LP tokens (permanently locked liquidity)
 //! 7. Graduation event is emitted
 //!
+//! ## Liquidity Bootstrapping (optional)
+//! `graduate_token_lbp()` launches the token through a Balancer-style
+//! weighted pool instead. The token's weight falls from its start weight to
+//! 50% over 24-72 hours, so the price decays until buyers step in. Once the
+//! auction ends anyone can call `finalize_lbp()`, which runs steps 3-7 with
+//! the pool's balances. If the pool cannot be finalized (e.g. its balances
+//! fail the depth checks), the admin or launchpad can refund them to the
+//! launchpad with `recover_lbp()` once `LBP_RECOVERY_DELAY` has passed.
+//!
 //! ## Security
 //! - Only registered launchpad can initiate graduations
 //! - LP tokens are burned (not held by any address)
+//! - Optional oracle checks reject graduations with too little quote value
+//!   or an implied price far from the token's oracle price
+//! - Tokens whose admin can still claw back or freeze balances are flagged
+//!   (`clawback_risk`), or refused once the admin enables rejection
 //! - Admin can pause in case of emergency
 
+mod lbp;
 mod storage;
 
+pub use lbp::LBP_RECOVERY_DELAY;
+pub use storage::RewardBootstrap;
+
 use astroswap_shared::{
-    emit_graduation, AstroSwapError, FactoryClient, GraduatedToken, PairClient, TokenMetadata,
+    calculate_deviation_bps, calculate_price, emit_graduation, mul_div_down, normalize_amount,
+    require_deadline, require_positive, with_lock, AstroSwapError, FactoryClient, GraduatedToken,
+    OracleClient, PairClient, PoolMetadata, PriceData, StakingClient, TokenMetadata,
+    BPS_DENOMINATOR, CANONICAL_PRICE_DECIMALS, MAX_POOL_NAME_LENGTH,
+};
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contractevent, contractimpl, symbol_short, token, vec, Address, Env, IntoVal, String,
+    Symbol, Vec,
 };
-use soroban_sdk::{contract, contractevent, contractimpl, token, Address, Env, IntoVal, Symbol, Vec};
 
 /// LP tokens burned event (permanent liquidity lock)
 #[contractevent]
@@ -33,17 +58,94 @@ pub struct LpBurned {
     pub amount: i128,
 }
 
+/// Starter rewards funded into a graduated staking pool event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RewardsBootstrapped {
+    #[topic]
+    pub token: Address,
+    pub pool_id: u32,
+    pub funder: Address,
+    pub amount: i128,
+    pub reward_per_second: i128,
+    pub end_time: u64,
+}
+
+/// Starter rewards skipped event: the funder's allowance or balance ran short
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RewardBootstrapSkipped {
+    #[topic]
+    pub token: Address,
+    pub pool_id: u32,
+    pub funder: Address,
+}
+
+/// Liquidity bootstrapping pool started event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LbpStarted {
+    #[topic]
+    pub token: Address,
+    pub token_amount: i128,
+    pub quote_amount: i128,
+    pub start_weight_bps: u32,
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+/// Liquidity bootstrapping pool refunded to its launchpad event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LbpRecovered {
+    #[topic]
+    pub token: Address,
+    pub funder: Address,
+    pub token_amount: i128,
+    pub quote_amount: i128,
+}
+
+/// Liquidity bootstrapping pool swap event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LbpSwap {
+    #[topic]
+    pub token: Address,
+    pub user: Address,
+    pub token_in: Address,
+    pub amount_in: i128,
+    pub amount_out: i128,
+}
+
 use crate::storage::{
-    acquire_lock, extend_graduated_token_ttl, extend_instance_ttl, get_admin, get_factory,
-    get_graduated_token, get_graduation_by_index, get_graduation_count, get_launchpad,
-    get_quote_token, get_staking, increment_graduation_count, is_initialized, is_paused,
-    is_token_graduated, release_lock, set_admin, set_factory, set_graduated_token,
-    set_graduation_index, set_initialized, set_launchpad, set_paused, set_quote_token, set_staking,
+    extend_graduated_token_ttl, extend_instance_ttl, get_admin, get_depth_config, get_factory,
+    get_graduated_token, get_graduation_by_index, get_graduation_count, get_launchpad, get_lbp,
+    get_quote_token, get_reward_bootstrap, get_staking, has_lbp, increment_graduation_count,
+    is_initialized, is_paused, is_token_graduated, rejects_clawback, remove_lbp, set_admin,
+    set_depth_config, set_factory, set_graduated_token, set_graduation_index, set_initialized,
+    set_launchpad, set_lbp, set_paused, set_quote_token, set_reject_clawback, set_reward_bootstrap,
+    set_staking, DataKey, DepthConfig, LbpPool, RewardBootstrap,
 };
 
 /// Default staking duration: 365 days
 const DEFAULT_STAKING_DURATION: u64 = 365 * 24 * 60 * 60;
 
+/// Metadata tag of the staking pools of graduated tokens
+const GRADUATED_POOL_TAG: Symbol = symbol_short!("graduated");
+
+/// Decimals of initial prices and USD values
+const PRICE_DECIMALS: u32 = CANONICAL_PRICE_DECIMALS;
+
+/// One whole unit of a price or USD value at `PRICE_DECIMALS`
+const PRICE_PRECISION: i128 = 10i128.pow(PRICE_DECIMALS);
+
+/// Largest oracle price decimals accepted
+const MAX_ORACLE_DECIMALS: u32 = 18;
+
+/// Largest gap between the listing price and the price of an existing pair
+/// with liquidity, in basis points (1%)
+pub const MAX_LISTING_SLIPPAGE_BPS: u32 = 100;
+
 #[contract]
 pub struct AstroSwapBridge;
 
@@ -113,135 +215,242 @@ impl AstroSwapBridge {
         Self::require_not_paused(&env)?;
         Self::require_launchpad(&env, &caller)?;
 
-        // SECURITY: Acquire reentrancy lock to prevent cross-contract attacks
+        // SECURITY: Hold the reentrancy lock across the external calls
         // This is critical since graduate_token makes multiple external calls
-        if !acquire_lock(&env) {
-            return Err(AstroSwapError::Reentrancy);
-        }
-
-        // Verify token hasn't already graduated
-        if is_token_graduated(&env, &token) {
-            release_lock(&env);
-            return Err(AstroSwapError::AlreadyGraduated);
-        }
-
-        // Validate amounts
-        if token_amount <= 0 || quote_amount <= 0 {
-            release_lock(&env);
-            return Err(AstroSwapError::InsufficientLiquidity);
-        }
-
-        let quote_token = match get_quote_token(&env) {
-            Some(qt) => qt,
-            None => {
-                release_lock(&env);
-                return Err(AstroSwapError::InvalidArgument);
+        with_lock(&env, &DataKey::Locked, || {
+            // Verify token hasn't already graduated
+            if is_token_graduated(&env, &token) {
+                return Err(AstroSwapError::AlreadyGraduated);
             }
-        };
-        let factory = get_factory(&env);
-
-        // Step 1: Create trading pair via factory
-        let factory_client = FactoryClient::new(&env, &factory);
-        let pair_address = factory_client.create_pair(&token, &quote_token)?;
-
-        // SECURITY: Verify pair was created successfully by checking it exists in factory
-        // This prevents potential issues if create_pair silently fails or returns wrong address
-        let verified_pair = factory_client.get_pair(&token, &quote_token);
-        if verified_pair.is_none() || verified_pair.as_ref().unwrap() != &pair_address {
-            release_lock(&env);
-            return Err(AstroSwapError::PairNotFound);
-        }
 
-        let pair_client = PairClient::new(&env, &pair_address);
+            // Tokens in a bootstrapping auction graduate when it is finalized
+            if has_lbp(&env, &token) {
+                return Err(AstroSwapError::LbpActive);
+            }
 
-        // Verify pair is properly initialized by checking it has the correct tokens
-        let pair_token_0 = pair_client.token_0();
-        let pair_token_1 = pair_client.token_1();
-        let has_token = pair_token_0 == token || pair_token_1 == token;
-        let has_quote = pair_token_0 == quote_token || pair_token_1 == quote_token;
-        if !has_token || !has_quote {
-            release_lock(&env);
-            return Err(AstroSwapError::InvalidPair);
-        }
+            // Validate amounts
+            if token_amount <= 0 || quote_amount <= 0 {
+                return Err(AstroSwapError::InsufficientLiquidity);
+            }
 
-        // Step 2: Transfer tokens from launchpad to bridge (for deposit)
-        // Note: Launchpad must approve bridge for these transfers beforehand
-        let token_client = token::Client::new(&env, &token);
-        let quote_client = token::Client::new(&env, &quote_token);
+            let quote_token = get_quote_token(&env).ok_or(AstroSwapError::QuoteTokenNotSet)?;
+
+            // Reject graduations that would open a thin or mispriced pool
+            Self::validate_market_depth(&env, &token, &quote_token, token_amount, quote_amount)?;
+            Self::validate_token_authority(&env, &token)?;
+
+            // Step 1: Transfer tokens from launchpad to bridge (for deposit)
+            let bridge = env.current_contract_address();
+            token::Client::new(&env, &token).transfer(&caller, &bridge, &token_amount);
+            token::Client::new(&env, &quote_token).transfer(&caller, &bridge, &quote_amount);
+
+            // Steps 2-7: pair, liquidity, LP burn, staking pool, record, event
+            let graduation_info = Self::list_graduated_token(
+                &env,
+                &caller,
+                &token,
+                &quote_token,
+                token_amount,
+                quote_amount,
+                metadata,
+            )?;
+
+            extend_instance_ttl(&env);
+            Ok(graduation_info)
+        })
+    }
 
-        // Transfer to this contract first
-        token_client.transfer(&caller, env.current_contract_address(), &token_amount);
-        quote_client.transfer(&caller, env.current_contract_address(), &quote_amount);
+    // ==================== Liquidity Bootstrapping ====================
 
-        // Approve pair contract to take tokens
-        token_client.approve(
-            &env.current_contract_address(),
-            &pair_address,
-            &token_amount,
-            &(env.ledger().sequence() + 1000),
-        );
-        quote_client.approve(
-            &env.current_contract_address(),
-            &pair_address,
-            &quote_amount,
-            &(env.ledger().sequence() + 1000),
-        );
+    /// Graduate a token through a Dutch-auction liquidity bootstrapping pool
+    ///
+    /// Instead of opening a pair at a fixed price, the liquidity is held in a
+    /// two-token weighted pool whose token weight falls linearly from
+    /// `start_weight_bps` to 50% over `duration` seconds. The price starts
+    /// high and drifts down until buyers step in, which takes the edge off
+    /// sniping the graduation liquidity. `finalize_lbp` converts the pool into
+    /// a standard pair once the auction ends.
+    ///
+    /// # Arguments
+    /// * `caller` - Must be the registered launchpad contract
+    /// * `token` - The graduating token address
+    /// * `token_amount` - Amount of graduating token for the pool
+    /// * `quote_amount` - Amount of quote token for the pool
+    /// * `metadata` - Token metadata from launchpad
+    /// * `duration` - Auction length in seconds (24 to 72 hours)
+    /// * `start_weight_bps` - Initial token weight (above 50%, at most 98%)
+    #[allow(clippy::too_many_arguments)]
+    pub fn graduate_token_lbp(
+        env: Env,
+        caller: Address,
+        token: Address,
+        token_amount: i128,
+        quote_amount: i128,
+        metadata: TokenMetadata,
+        duration: u64,
+        start_weight_bps: u32,
+    ) -> Result<LbpPool, AstroSwapError> {
+        Self::require_not_paused(&env)?;
+        Self::require_launchpad(&env, &caller)?;
 
-        // Step 3: Add initial liquidity
-        // Determine token order in the pair
-        let token_0 = pair_client.token_0();
-        let (amount_0, amount_1) = if token == token_0 {
-            (token_amount, quote_amount)
-        } else {
-            (quote_amount, token_amount)
-        };
+        with_lock(&env, &DataKey::Locked, || {
+            Self::start_lbp(
+                &env,
+                &caller,
+                &token,
+                token_amount,
+                quote_amount,
+                metadata,
+                duration,
+                start_weight_bps,
+            )
+        })
+    }
 
-        let (_, _, lp_tokens) = pair_client.deposit(
-            &env.current_contract_address(),
-            amount_0,
-            amount_1,
-            0, // min_amount_0 - accept any for initial deposit
-            0, // min_amount_1
-        );
+    /// Swap against a running bootstrapping pool
+    ///
+    /// `token_in` is either the pool's token (selling) or its quote token
+    /// (buying). The 1% swap fee stays in the pool and ends up in the pair.
+    ///
+    /// # Returns
+    /// * Amount of the other token sent to `user`
+    pub fn lbp_swap(
+        env: Env,
+        user: Address,
+        token: Address,
+        token_in: Address,
+        amount_in: i128,
+        min_out: i128,
+        deadline: u64,
+    ) -> Result<i128, AstroSwapError> {
+        Self::require_not_paused(&env)?;
+        user.require_auth();
+        require_deadline(&env, deadline)?;
+        require_positive(amount_in)?;
 
-        // Step 4: Burn LP tokens by sending to zero/dead address
-        // In Soroban, we can't send to address zero, so we use the contract itself
-        // and the tokens become permanently locked
-        // Alternative: Send to a known burn address or implement token burning
-        let _burn_result = Self::burn_lp_tokens(&env, &pair_address, lp_tokens);
+        with_lock(&env, &DataKey::Locked, || {
+            Self::execute_lbp_swap(&env, &user, &token, &token_in, amount_in, min_out)
+        })
+    }
 
-        // Step 5: Create staking pool for the pair (if staking contract is available)
-        let staking = get_staking(&env);
-        let pool_id = Self::create_staking_pool(&env, &staking, &pair_address)?;
+    /// Convert an ended bootstrapping pool into a standard pair
+    ///
+    /// Callable by anyone once the auction is over. The pool's balances are
+    /// deposited into a new pair at the auction's closing price, and the
+    /// token graduates exactly like one listed through `graduate_token`:
+    /// sellers may have drained the quote side, so the final balances must
+    /// pass the same depth checks. A pair created for the token in the
+    /// meantime is seeded if it is still empty, or joined at the closing
+    /// price if it holds liquidity (see `MAX_LISTING_SLIPPAGE_BPS`).
+    pub fn finalize_lbp(env: Env, token: Address) -> Result<GraduatedToken, AstroSwapError> {
+        Self::require_not_paused(&env)?;
+        let pool = get_lbp(&env, &token).ok_or(AstroSwapError::LbpNotFound)?;
+        if env.ledger().timestamp() < pool.end_time {
+            return Err(AstroSwapError::LbpActive);
+        }
+        Self::validate_market_depth(
+            &env,
+            &pool.token,
+            &pool.quote_token,
+            pool.token_balance,
+            pool.quote_balance,
+        )?;
+
+        with_lock(&env, &DataKey::Locked, || {
+            let graduation_info = Self::list_graduated_token(
+                &env,
+                &pool.funder,
+                &pool.token,
+                &pool.quote_token,
+                pool.token_balance,
+                pool.quote_balance,
+                pool.metadata,
+            )?;
+            remove_lbp(&env, &token);
+            extend_instance_ttl(&env);
+            Ok(graduation_info)
+        })
+    }
 
-        // Calculate initial price (always quote per token, regardless of token ordering)
-        // Price with 7 decimals: (quote / token) * 10^7
-        let initial_price = (quote_amount * 10_000_000) / token_amount;
+    /// Refund an ended bootstrapping pool that could not be finalized to the
+    /// launchpad that funded it
+    ///
+    /// Callable by the admin or the launchpad from `LBP_RECOVERY_DELAY` after
+    /// the auction's end (`LbpActive` before), leaving anyone time to
+    /// finalize it first. The token does not graduate and can be graduated
+    /// again later.
+    ///
+    /// # Returns
+    /// * The (token, quote) amounts refunded
+    pub fn recover_lbp(
+        env: Env,
+        caller: Address,
+        token: Address,
+    ) -> Result<(i128, i128), AstroSwapError> {
+        caller.require_auth();
+        if caller != get_admin(&env) && get_launchpad(&env).as_ref() != Some(&caller) {
+            return Err(AstroSwapError::Unauthorized);
+        }
+        let pool = get_lbp(&env, &token).ok_or(AstroSwapError::LbpNotFound)?;
+        if env.ledger().timestamp() < pool.end_time.saturating_add(lbp::LBP_RECOVERY_DELAY) {
+            return Err(AstroSwapError::LbpActive);
+        }
 
-        // Step 6: Store graduation info
-        let graduation_info = GraduatedToken {
-            token: token.clone(),
-            pair: pair_address.clone(),
-            staking_pool_id: pool_id,
-            initial_price,
-            graduation_time: env.ledger().timestamp(),
-            metadata: metadata.clone(),
-        };
+        with_lock(&env, &DataKey::Locked, || {
+            remove_lbp(&env, &token);
+            let bridge = env.current_contract_address();
+            token::Client::new(&env, &pool.token).transfer(
+                &bridge,
+                &pool.funder,
+                &pool.token_balance,
+            );
+            token::Client::new(&env, &pool.quote_token).transfer(
+                &bridge,
+                &pool.funder,
+                &pool.quote_balance,
+            );
+
+            LbpRecovered {
+                token: pool.token,
+                funder: pool.funder,
+                token_amount: pool.token_balance,
+                quote_amount: pool.quote_balance,
+            }
+            .publish(&env);
 
-        let index = increment_graduation_count(&env);
-        set_graduated_token(&env, &token, &graduation_info);
-        set_graduation_index(&env, index - 1, &token);
+            extend_instance_ttl(&env);
+            Ok((pool.token_balance, pool.quote_balance))
+        })
+    }
 
-        // Step 7: Emit graduation event
-        emit_graduation(&env, &token, &pair_address, initial_price);
+    /// Get a running bootstrapping pool
+    pub fn get_lbp(env: Env, token: Address) -> Result<LbpPool, AstroSwapError> {
+        get_lbp(&env, &token).ok_or(AstroSwapError::LbpNotFound)
+    }
 
-        extend_instance_ttl(&env);
-        extend_graduated_token_ttl(&env, &token);
+    /// Current token and quote weights of a bootstrapping pool (basis points)
+    pub fn lbp_weights(env: Env, token: Address) -> Result<(u32, u32), AstroSwapError> {
+        let pool = get_lbp(&env, &token).ok_or(AstroSwapError::LbpNotFound)?;
+        Ok(lbp::weights_at(&pool, env.ledger().timestamp()))
+    }
 
-        // SECURITY: Release reentrancy lock after all operations complete successfully
-        release_lock(&env);
+    /// Current price of the token in quote units (7 decimals), excluding fees
+    pub fn lbp_spot_price(env: Env, token: Address) -> Result<i128, AstroSwapError> {
+        let pool = get_lbp(&env, &token).ok_or(AstroSwapError::LbpNotFound)?;
+        lbp::spot_price(&pool, env.ledger().timestamp())
+    }
 
-        Ok(graduation_info)
+    /// Output `lbp_swap` would currently give for `amount_in` of `token_in`
+    pub fn lbp_quote(
+        env: Env,
+        token: Address,
+        token_in: Address,
+        amount_in: i128,
+    ) -> Result<i128, AstroSwapError> {
+        require_positive(amount_in)?;
+        let pool = get_lbp(&env, &token).ok_or(AstroSwapError::LbpNotFound)?;
+        let buying = Self::lbp_direction(&pool, &token_in)?;
+        lbp::amount_out(&pool, env.ledger().timestamp(), buying, amount_in)
     }
 
     // ==================== View Functions ====================
@@ -266,7 +475,8 @@ impl AstroSwapBridge {
 
     /// Get graduated token by index
     pub fn get_graduation_by_index(env: Env, index: u32) -> Result<GraduatedToken, AstroSwapError> {
-        let token = get_graduation_by_index(&env, index).ok_or(AstroSwapError::InvalidArgument)?;
+        let token = get_graduation_by_index(&env, index)
+            .ok_or(AstroSwapError::GraduationNotFound)?;
         Self::get_graduated_token(env, token)
     }
 
@@ -294,6 +504,12 @@ impl AstroSwapBridge {
         get_quote_token(&env)
     }
 
+    /// Get the market depth config, if checks are enabled
+    pub fn depth_config(env: Env) -> Option<DepthConfig> {
+        extend_instance_ttl(&env);
+        get_depth_config(&env)
+    }
+
     /// Get admin address
     pub fn admin(env: Env) -> Address {
         extend_instance_ttl(&env);
@@ -305,6 +521,16 @@ impl AstroSwapBridge {
         is_paused(&env)
     }
 
+    /// Get the starter rewards of graduated staking pools, if enabled
+    pub fn reward_bootstrap(env: Env) -> Option<RewardBootstrap> {
+        get_reward_bootstrap(&env)
+    }
+
+    /// Check if tokens whose admin can still claw back are refused
+    pub fn rejects_clawback(env: Env) -> bool {
+        rejects_clawback(&env)
+    }
+
     // ==================== Admin Functions ====================
 
     /// Update launchpad address
@@ -339,6 +565,78 @@ impl AstroSwapBridge {
         Ok(())
     }
 
+    /// Enable or update oracle-based market depth checks for graduations
+    ///
+    /// # Arguments
+    /// * `oracle` - AstroSwap oracle quoting USD prices
+    /// * `min_quote_usd` - Minimum USD value of the quote side (7 decimals)
+    /// * `max_price_deviation_bps` - Allowed deviation of the implied initial
+    ///   price from the token's oracle price, when the oracle has one
+    pub fn set_depth_config(
+        env: Env,
+        admin: Address,
+        oracle: Address,
+        min_quote_usd: i128,
+        max_price_deviation_bps: u32,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+        if min_quote_usd < 0 || max_price_deviation_bps > BPS_DENOMINATOR {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+        set_depth_config(
+            &env,
+            &DepthConfig {
+                oracle,
+                min_quote_usd,
+                max_price_deviation_bps,
+            },
+        );
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Fund every graduated staking pool with starter rewards, or stop (`None`)
+    ///
+    /// At each graduation `bootstrap.amount` of the staking reward token is
+    /// pulled from `bootstrap.funder`, who must have approved the bridge for
+    /// it, earmarked for the new pool with the staking contract's
+    /// `fund_pool`, and paid out by the pool over `bootstrap.duration`
+    /// seconds (at most a year) instead of the pool starting at zero
+    /// rewards. Rounding the rate down may leave up to `duration - 1` stroops
+    /// with the funder. When the allowance or balance falls short, the pool
+    /// starts unfunded and `RewardBootstrapSkipped` is emitted.
+    pub fn set_reward_bootstrap(
+        env: Env,
+        admin: Address,
+        bootstrap: Option<RewardBootstrap>,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+        if let Some(bootstrap) = &bootstrap {
+            if bootstrap.duration == 0
+                || bootstrap.duration > DEFAULT_STAKING_DURATION
+                || bootstrap.amount < i128::from(bootstrap.duration)
+            {
+                return Err(AstroSwapError::InvalidArgument);
+            }
+        }
+        set_reward_bootstrap(&env, &bootstrap);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Refuse (`true`) or only flag (`false`, the default) graduating tokens
+    /// that still have an admin able to claw back or freeze balances
+    pub fn set_reject_clawback(
+        env: Env,
+        admin: Address,
+        reject: bool,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+        set_reject_clawback(&env, reject);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
     /// Transfer admin role
     pub fn set_admin(env: Env, admin: Address, new_admin: Address) -> Result<(), AstroSwapError> {
         Self::require_admin(&env, &admin)?;
@@ -357,6 +655,271 @@ impl AstroSwapBridge {
 
     // ==================== Internal Functions ====================
 
+    /// List a graduating token on AstroSwap with liquidity the bridge holds
+    ///
+    /// Creates the pair, or uses one someone else created, deposits
+    /// `token_amount` and `quote_amount`, burns the LP tokens, creates the
+    /// staking pool and records the graduation. An existing pair with
+    /// liquidity is joined at the price of the two amounts, within
+    /// `MAX_LISTING_SLIPPAGE_BPS`; what it does not take goes back to
+    /// `funder`.
+    fn list_graduated_token(
+        env: &Env,
+        funder: &Address,
+        token: &Address,
+        quote_token: &Address,
+        token_amount: i128,
+        quote_amount: i128,
+        metadata: TokenMetadata,
+    ) -> Result<GraduatedToken, AstroSwapError> {
+        let factory = get_factory(env);
+
+        // Create trading pair via factory. Pair creation is permissionless, so
+        // one may already exist: it is seeded if nobody deposited, or joined.
+        let factory_client = FactoryClient::new(env, &factory);
+        let pair_address = match factory_client.get_pair(token, quote_token) {
+            Some(pair) => pair,
+            None => factory_client.create_pair_for(
+                &env.current_contract_address(),
+                token,
+                quote_token,
+            )?,
+        };
+
+        // SECURITY: Verify pair was created successfully by checking it exists in factory
+        // This prevents potential issues if create_pair silently fails or returns wrong address
+        let verified_pair = factory_client.get_pair(token, quote_token);
+        if verified_pair.as_ref() != Some(&pair_address) {
+            return Err(AstroSwapError::PairNotFound);
+        }
+
+        let pair_client = PairClient::new(env, &pair_address);
+
+        // Verify pair is properly initialized by checking it has the correct tokens
+        let pair_token_0 = pair_client.token_0();
+        let pair_token_1 = pair_client.token_1();
+        let has_token = pair_token_0 == *token || pair_token_1 == *token;
+        let has_quote = pair_token_0 == *quote_token || pair_token_1 == *quote_token;
+        if !has_token || !has_quote {
+            return Err(AstroSwapError::InvalidPair);
+        }
+
+        // Approve pair contract to take tokens
+        let bridge = env.current_contract_address();
+        let expiration_ledger = env.ledger().sequence() + 1000;
+        token::Client::new(env, token).approve(
+            &bridge,
+            &pair_address,
+            &token_amount,
+            &expiration_ledger,
+        );
+        token::Client::new(env, quote_token).approve(
+            &bridge,
+            &pair_address,
+            &quote_amount,
+            &expiration_ledger,
+        );
+
+        // Add initial liquidity in pair token order
+        let (amount_0, amount_1) = if *token == pair_token_0 {
+            (token_amount, quote_amount)
+        } else {
+            (quote_amount, token_amount)
+        };
+
+        // Accept any amounts for the initial deposit. A pair with liquidity
+        // must be within the slippage bound of the listing price.
+        let (min_0, min_1) = if pair_client.total_supply() == 0 {
+            (0, 0)
+        } else {
+            let keep_bps = i128::from(BPS_DENOMINATOR - MAX_LISTING_SLIPPAGE_BPS);
+            (
+                mul_div_down(amount_0, keep_bps, i128::from(BPS_DENOMINATOR))?,
+                mul_div_down(amount_1, keep_bps, i128::from(BPS_DENOMINATOR))?,
+            )
+        };
+        let (deposited_0, deposited_1, lp_tokens) = pair_client.try_deposit(
+            &bridge,
+            amount_0,
+            amount_1,
+            min_0,
+            min_1,
+            env.ledger().timestamp(),
+        )?;
+
+        // Return what a pair with liquidity did not take
+        for (pair_token, amount, deposited) in [
+            (&pair_token_0, amount_0, deposited_0),
+            (&pair_token_1, amount_1, deposited_1),
+        ] {
+            if amount > deposited {
+                token::Client::new(env, pair_token).transfer(
+                    &bridge,
+                    funder,
+                    &(amount - deposited),
+                );
+            }
+        }
+
+        // Burn LP tokens: the liquidity is permanently locked
+        let _burn_result = Self::burn_lp_tokens(env, &pair_address, lp_tokens);
+
+        // Create staking pool for the pair
+        let staking = get_staking(env);
+        let pool_id =
+            Self::create_staking_pool(env, &staking, &pair_address, token, &metadata.symbol)?;
+
+        // Calculate initial price (always quote per whole token, regardless of token
+        // ordering or decimals), with 7 decimals
+        let initial_price = calculate_price(
+            token_amount,
+            token::Client::new(env, token).decimals(),
+            quote_amount,
+            token::Client::new(env, quote_token).decimals(),
+            PRICE_DECIMALS,
+        )?;
+
+        // Store graduation info
+        let graduation_info = GraduatedToken {
+            token: token.clone(),
+            pair: pair_address.clone(),
+            staking_pool_id: pool_id,
+            initial_price,
+            graduation_time: env.ledger().timestamp(),
+            metadata,
+            clawback_risk: Self::token_admin(env, token).is_some(),
+        };
+
+        let index = increment_graduation_count(env);
+        set_graduated_token(env, token, &graduation_info);
+        set_graduation_index(env, index - 1, token);
+
+        emit_graduation(env, token, &pair_address, initial_price);
+        extend_graduated_token_ttl(env, token);
+
+        Ok(graduation_info)
+    }
+
+    /// Validate and open a bootstrapping pool funded by the launchpad
+    #[allow(clippy::too_many_arguments)]
+    fn start_lbp(
+        env: &Env,
+        caller: &Address,
+        token: &Address,
+        token_amount: i128,
+        quote_amount: i128,
+        metadata: TokenMetadata,
+        duration: u64,
+        start_weight_bps: u32,
+    ) -> Result<LbpPool, AstroSwapError> {
+        if is_token_graduated(env, token) {
+            return Err(AstroSwapError::AlreadyGraduated);
+        }
+        if has_lbp(env, token) {
+            return Err(AstroSwapError::LbpActive);
+        }
+        if token_amount <= 0 || quote_amount <= 0 {
+            return Err(AstroSwapError::InsufficientLiquidity);
+        }
+        let quote_token = get_quote_token(env).ok_or(AstroSwapError::QuoteTokenNotSet)?;
+        if *token == quote_token {
+            return Err(AstroSwapError::SameToken);
+        }
+        lbp::validate_lbp_params(start_weight_bps, duration)?;
+        Self::validate_token_authority(env, token)?;
+
+        let bridge = env.current_contract_address();
+        token::Client::new(env, token).transfer(caller, &bridge, &token_amount);
+        token::Client::new(env, &quote_token).transfer(caller, &bridge, &quote_amount);
+
+        let start_time = env.ledger().timestamp();
+        let pool = LbpPool {
+            token: token.clone(),
+            quote_token,
+            funder: caller.clone(),
+            token_balance: token_amount,
+            quote_balance: quote_amount,
+            start_weight_bps,
+            start_time,
+            end_time: start_time + duration,
+            metadata,
+        };
+        set_lbp(env, &pool);
+        extend_instance_ttl(env);
+
+        LbpStarted {
+            token: token.clone(),
+            token_amount,
+            quote_amount,
+            start_weight_bps,
+            start_time,
+            end_time: pool.end_time,
+        }
+        .publish(env);
+
+        Ok(pool)
+    }
+
+    /// Price and settle a swap against a bootstrapping pool
+    fn execute_lbp_swap(
+        env: &Env,
+        user: &Address,
+        token: &Address,
+        token_in: &Address,
+        amount_in: i128,
+        min_out: i128,
+    ) -> Result<i128, AstroSwapError> {
+        let mut pool = get_lbp(env, token).ok_or(AstroSwapError::LbpNotFound)?;
+        let now = env.ledger().timestamp();
+        if now >= pool.end_time {
+            return Err(AstroSwapError::LbpEnded);
+        }
+
+        let buying = Self::lbp_direction(&pool, token_in)?;
+        let amount_out = lbp::amount_out(&pool, now, buying, amount_in)?;
+        if amount_out <= 0 || amount_out < min_out {
+            return Err(AstroSwapError::SlippageExceeded);
+        }
+
+        // The whole input, fee included, stays in the pool
+        let token_out = if buying {
+            pool.quote_balance += amount_in;
+            pool.token_balance -= amount_out;
+            pool.token.clone()
+        } else {
+            pool.token_balance += amount_in;
+            pool.quote_balance -= amount_out;
+            pool.quote_token.clone()
+        };
+        set_lbp(env, &pool);
+
+        let bridge = env.current_contract_address();
+        token::Client::new(env, token_in).transfer(user, &bridge, &amount_in);
+        token::Client::new(env, &token_out).transfer(&bridge, user, &amount_out);
+
+        LbpSwap {
+            token: token.clone(),
+            user: user.clone(),
+            token_in: token_in.clone(),
+            amount_in,
+            amount_out,
+        }
+        .publish(env);
+
+        Ok(amount_out)
+    }
+
+    /// Whether `token_in` buys the pool's token (`true`) or sells it (`false`)
+    fn lbp_direction(pool: &LbpPool, token_in: &Address) -> Result<bool, AstroSwapError> {
+        if *token_in == pool.quote_token {
+            Ok(true)
+        } else if *token_in == pool.token {
+            Ok(false)
+        } else {
+            Err(AstroSwapError::InvalidToken)
+        }
+    }
+
     /// Burn LP tokens by locking them in the contract forever
     /// This ensures liquidity cannot be removed
     fn burn_lp_tokens(env: &Env, pair: &Address, amount: i128) -> Result<(), AstroSwapError> {
@@ -389,36 +952,230 @@ impl AstroSwapBridge {
         Ok(())
     }
 
+    /// Check the graduation's quote side and implied price against the oracle
+    ///
+    /// - the quote side must be worth at least `min_quote_usd`
+    ///   (`InsufficientLiquidity`)
+    /// - if the oracle also prices the graduated token, the implied initial
+    ///   price must be within `max_price_deviation_bps` of it
+    ///   (`InitialPriceOutOfBand`)
+    ///
+    /// Does nothing until a depth config is set. A missing or stale quote
+    /// asset price fails with `QuoteStale`.
+    fn validate_market_depth(
+        env: &Env,
+        token: &Address,
+        quote_token: &Address,
+        token_amount: i128,
+        quote_amount: i128,
+    ) -> Result<(), AstroSwapError> {
+        let config = match get_depth_config(env) {
+            Some(config) => config,
+            None => return Ok(()),
+        };
+        let oracle = OracleClient::new(env, &config.oracle);
+
+        // USD value of the quote side, 7 decimals
+        let quote_price = oracle.try_get_price(quote_token)?;
+        let quote_decimals = token::Client::new(env, quote_token).decimals();
+        let quote_usd = Self::to_usd(
+            normalize_amount(quote_amount, quote_decimals, PRICE_DECIMALS)?,
+            &quote_price,
+        )?;
+        if quote_usd < config.min_quote_usd {
+            return Err(AstroSwapError::InsufficientLiquidity);
+        }
+
+        // Newly graduated tokens usually have no feed yet
+        if let Ok(token_price) = oracle.try_get_price(token) {
+            let implied_usd = calculate_price(
+                token_amount,
+                token::Client::new(env, token).decimals(),
+                quote_usd,
+                PRICE_DECIMALS,
+                PRICE_DECIMALS,
+            )?;
+            let oracle_usd = Self::to_usd(PRICE_PRECISION, &token_price)?;
+            if oracle_usd <= 0
+                || calculate_deviation_bps(implied_usd, oracle_usd)?
+                    > config.max_price_deviation_bps
+            {
+                return Err(AstroSwapError::InitialPriceOutOfBand);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Refuse a token that still has an admin when rejection is enabled
+    ///
+    /// A Stellar asset's admin can claw back balances (when the issuer has
+    /// clawback enabled) or revoke authorizations, which would let it drain
+    /// or freeze the graduated pool.
+    fn validate_token_authority(env: &Env, token: &Address) -> Result<(), AstroSwapError> {
+        if rejects_clawback(env) && Self::token_admin(env, token).is_some() {
+            return Err(AstroSwapError::ClawbackEnabled);
+        }
+        Ok(())
+    }
+
+    /// Admin reported by the token, None if it exposes no `admin()`
+    fn token_admin(env: &Env, token: &Address) -> Option<Address> {
+        env.try_invoke_contract::<Address, soroban_sdk::Error>(
+            token,
+            &Symbol::new(env, "admin"),
+            Vec::new(env),
+        )
+        .ok()
+        .and_then(Result::ok)
+    }
+
+    /// USD value (7 decimals) of a 7-decimal `amount` at an oracle price
+    fn to_usd(amount: i128, price: &PriceData) -> Result<i128, AstroSwapError> {
+        if price.price <= 0 || price.decimals > MAX_ORACLE_DECIMALS {
+            return Err(AstroSwapError::QuoteStale);
+        }
+        mul_div_down(amount, price.price, 10i128.pow(price.decimals))
+    }
+
     /// Create a staking pool for a graduated pair
+    ///
+    /// With a reward bootstrap, the pool pays the pulled starter rewards out
+    /// over the bootstrap duration; otherwise it starts without rewards, to
+    /// be set by the admin. The pool is named after the token's `symbol`
+    /// and tagged "graduated", unless the symbol is too long for a pool name.
     fn create_staking_pool(
         env: &Env,
         staking: &Address,
         lp_token: &Address,
+        token: &Address,
+        symbol: &String,
     ) -> Result<u32, AstroSwapError> {
         // Call staking contract to create a new pool
-        // The pool will use LP tokens as the stake token
-        let admin = get_admin(env);
+        // The pool will use LP tokens as the stake token. The bridge must be
+        // an allowlisted pool creator of the staking contract.
         let current_time = env.ledger().timestamp();
-        let end_time = current_time + DEFAULT_STAKING_DURATION;
-
-        let pool_id: u32 = env.invoke_contract(
-            staking,
-            &Symbol::new(env, "create_pool"),
-            Vec::from_array(
-                env,
-                [
-                    admin.to_val(),
-                    lp_token.to_val(),
-                    0i128.into_val(env), // reward_per_second - to be set by admin
-                    current_time.into_val(env), // start_time
-                    end_time.into_val(env), // end_time
-                ],
-            ),
-        );
+        let bootstrap = get_reward_bootstrap(env);
+        let funded = match &bootstrap {
+            Some(bootstrap) => Self::bootstrap_reward_rate(env, staking, bootstrap)?,
+            None => None,
+        };
+        let (reward_per_second, end_time) = match (&bootstrap, funded) {
+            (Some(bootstrap), Some(reward_per_second)) => {
+                (reward_per_second, current_time + bootstrap.duration)
+            }
+            _ => (0, current_time + DEFAULT_STAKING_DURATION),
+        };
+
+        let staking_client = StakingClient::new(env, staking);
+        let creator = env.current_contract_address();
+        let pool_id = if (1..=MAX_POOL_NAME_LENGTH).contains(&symbol.len()) {
+            staking_client.try_create_pool_with_metadata(
+                &creator,
+                lp_token,
+                reward_per_second,
+                current_time,
+                end_time,
+                &PoolMetadata {
+                    name: symbol.clone(),
+                    tag: GRADUATED_POOL_TAG,
+                    url_hash: None,
+                },
+            )?
+        } else {
+            staking_client.try_create_pool(
+                &creator,
+                lp_token,
+                reward_per_second,
+                current_time,
+                end_time,
+            )?
+        };
+
+        if let Some(bootstrap) = bootstrap {
+            if reward_per_second > 0 {
+                let amount = reward_per_second * i128::from(bootstrap.duration);
+                Self::fund_bootstrap_pool(env, staking, &bootstrap.funder, pool_id, amount)?;
+                RewardsBootstrapped {
+                    token: token.clone(),
+                    pool_id,
+                    funder: bootstrap.funder,
+                    amount,
+                    reward_per_second,
+                    end_time,
+                }
+                .publish(env);
+            } else {
+                RewardBootstrapSkipped {
+                    token: token.clone(),
+                    pool_id,
+                    funder: bootstrap.funder,
+                }
+                .publish(env);
+            }
+        }
 
         Ok(pool_id)
     }
 
+    /// Reward rate a pool's starter rewards fund
+    ///
+    /// Returns None when the funder's allowance or balance falls short.
+    fn bootstrap_reward_rate(
+        env: &Env,
+        staking: &Address,
+        bootstrap: &RewardBootstrap,
+    ) -> Result<Option<i128>, AstroSwapError> {
+        let reward_token = StakingClient::new(env, staking)
+            .reward_token()
+            .ok_or(AstroSwapError::NotInitialized)?;
+        let duration = i128::from(bootstrap.duration);
+        let reward_per_second = bootstrap.amount / duration;
+        let amount = reward_per_second * duration;
+
+        let token_client = token::Client::new(env, &reward_token);
+        let bridge = env.current_contract_address();
+        if token_client.allowance(&bootstrap.funder, &bridge) < amount
+            || token_client.balance(&bootstrap.funder) < amount
+        {
+            return Ok(None);
+        }
+        Ok(Some(reward_per_second))
+    }
+
+    /// Move a pool's starter rewards from the funder into the pool
+    ///
+    /// The rewards pass through the bridge and are funded with `fund_pool`,
+    /// so they are earmarked for the new pool.
+    fn fund_bootstrap_pool(
+        env: &Env,
+        staking: &Address,
+        funder: &Address,
+        pool_id: u32,
+        amount: i128,
+    ) -> Result<(), AstroSwapError> {
+        let staking_client = StakingClient::new(env, staking);
+        let reward_token = staking_client
+            .reward_token()
+            .ok_or(AstroSwapError::NotInitialized)?;
+        let bridge = env.current_contract_address();
+        token::Client::new(env, &reward_token).transfer_from(&bridge, funder, &bridge, &amount);
+
+        // `fund_pool` pulls the rewards from the bridge
+        env.authorize_as_current_contract(vec![
+            env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: reward_token,
+                    fn_name: symbol_short!("transfer"),
+                    args: (bridge.clone(), staking.clone(), amount).into_val(env),
+                },
+                sub_invocations: vec![env],
+            }),
+        ]);
+        staking_client.try_fund_pool(&bridge, pool_id, amount)
+    }
+
     /// Verify caller is admin
     fn require_admin(env: &Env, caller: &Address) -> Result<(), AstroSwapError> {
         caller.require_auth();
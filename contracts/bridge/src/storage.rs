@@ -2,7 +2,7 @@
 //!
 //! Manages graduated token tracking and integration with Astro-Shiba launchpad.
 
-use astroswap_shared::GraduatedToken;
+use astroswap_shared::{GraduatedToken, TokenMetadata};
 use soroban_sdk::{contracttype, Address, Env};
 
 /// Storage keys for the bridge contract
@@ -19,10 +19,66 @@ pub enum DataKey {
     Launchpad,
     QuoteToken, // XLM or USDC address
     GraduationCount,
+    DepthConfig,     // Oracle-based market depth checks (optional)
+    RejectClawback,  // Refuse tokens whose admin can still claw back
+    RewardBootstrap, // Starter rewards for graduated staking pools (optional)
 
     // Persistent storage
     GraduatedToken(Address), // Token address -> GraduatedToken info
     GraduationIndex(u32),    // Index -> Token address (for enumeration)
+    Lbp(Address),            // Token address -> running bootstrapping pool
+}
+
+/// Market depth checks applied at graduation
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DepthConfig {
+    /// AstroSwap oracle quoting USD prices
+    pub oracle: Address,
+    /// Minimum USD value of the quote side (7 decimals)
+    pub min_quote_usd: i128,
+    /// Maximum deviation of the implied initial price from the token's own
+    /// oracle price, when the oracle has one (basis points)
+    pub max_price_deviation_bps: u32,
+}
+
+/// Starter rewards funded into every graduated staking pool
+///
+/// At graduation the bridge pulls the emission from `funder`'s allowance,
+/// funds the new pool with it (`fund_pool`) and sets the pool to pay it out
+/// over `duration` seconds.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RewardBootstrap {
+    /// Account that approved the bridge for the staking reward token
+    pub funder: Address,
+    /// Reward tokens per graduated pool
+    pub amount: i128,
+    /// Emission schedule length in seconds
+    pub duration: u64,
+}
+
+/// Liquidity bootstrapping pool of a token launching by Dutch auction
+///
+/// The token's weight falls linearly from `start_weight_bps` to 50% between
+/// `start_time` and `end_time`; the quote token holds the remaining weight.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LbpPool {
+    pub token: Address,
+    pub quote_token: Address,
+    /// Launchpad that funded the pool, refunded by `recover_lbp`
+    pub funder: Address,
+    /// Token balance held for the pool
+    pub token_balance: i128,
+    /// Quote balance held for the pool
+    pub quote_balance: i128,
+    /// Token weight at `start_time` (basis points)
+    pub start_weight_bps: u32,
+    pub start_time: u64,
+    pub end_time: u64,
+    /// Launchpad metadata, recorded on graduation
+    pub metadata: TokenMetadata,
 }
 
 // ==================== Instance Storage ====================
@@ -66,28 +122,6 @@ pub fn set_paused(env: &Env, paused: bool) {
     env.storage().instance().set(&DataKey::Paused, &paused);
 }
 
-/// Check if the contract is locked (reentrancy guard)
-pub fn is_locked(env: &Env) -> bool {
-    env.storage()
-        .instance()
-        .get::<DataKey, bool>(&DataKey::Locked)
-        .unwrap_or(false)
-}
-
-/// Acquire reentrancy lock - returns error if already locked
-pub fn acquire_lock(env: &Env) -> bool {
-    if is_locked(env) {
-        return false;
-    }
-    env.storage().instance().set(&DataKey::Locked, &true);
-    true
-}
-
-/// Release reentrancy lock
-pub fn release_lock(env: &Env) {
-    env.storage().instance().set(&DataKey::Locked, &false);
-}
-
 /// Get factory address
 pub fn get_factory(env: &Env) -> Address {
     env.storage()
@@ -140,6 +174,36 @@ pub fn set_quote_token(env: &Env, quote_token: &Address) {
         .set(&DataKey::QuoteToken, quote_token);
 }
 
+/// Get market depth config
+pub fn get_depth_config(env: &Env) -> Option<DepthConfig> {
+    env.storage()
+        .instance()
+        .get::<DataKey, DepthConfig>(&DataKey::DepthConfig)
+}
+
+/// Set market depth config
+pub fn set_depth_config(env: &Env, config: &DepthConfig) {
+    env.storage().instance().set(&DataKey::DepthConfig, config);
+}
+
+/// Get the staking reward bootstrap, if enabled
+pub fn get_reward_bootstrap(env: &Env) -> Option<RewardBootstrap> {
+    env.storage()
+        .instance()
+        .get::<DataKey, RewardBootstrap>(&DataKey::RewardBootstrap)
+}
+
+/// Set or clear the staking reward bootstrap
+pub fn set_reward_bootstrap(env: &Env, bootstrap: &Option<RewardBootstrap>) {
+    match bootstrap {
+        Some(bootstrap) => env
+            .storage()
+            .instance()
+            .set(&DataKey::RewardBootstrap, bootstrap),
+        None => env.storage().instance().remove(&DataKey::RewardBootstrap),
+    }
+}
+
 /// Get graduation count
 pub fn get_graduation_count(env: &Env) -> u32 {
     env.storage()
@@ -157,6 +221,21 @@ pub fn increment_graduation_count(env: &Env) -> u32 {
     count
 }
 
+/// Check if tokens with an active admin are refused at graduation
+pub fn rejects_clawback(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get::<DataKey, bool>(&DataKey::RejectClawback)
+        .unwrap_or(false)
+}
+
+/// Set whether tokens with an active admin are refused at graduation
+pub fn set_reject_clawback(env: &Env, reject: bool) {
+    env.storage()
+        .instance()
+        .set(&DataKey::RejectClawback, &reject);
+}
+
 // ==================== Graduated Token Storage ====================
 
 /// Get graduated token info
@@ -194,6 +273,37 @@ pub fn get_graduation_by_index(env: &Env, index: u32) -> Option<Address> {
         .get::<DataKey, Address>(&DataKey::GraduationIndex(index))
 }
 
+// ==================== Bootstrapping Pool Storage ====================
+
+/// Get a token's bootstrapping pool
+pub fn get_lbp(env: &Env, token: &Address) -> Option<LbpPool> {
+    env.storage()
+        .persistent()
+        .get::<DataKey, LbpPool>(&DataKey::Lbp(token.clone()))
+}
+
+/// Set a token's bootstrapping pool
+pub fn set_lbp(env: &Env, pool: &LbpPool) {
+    let key = DataKey::Lbp(pool.token.clone());
+    env.storage().persistent().set(&key, pool);
+    let max_ttl = env.storage().max_ttl();
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+/// Check if a token has a running bootstrapping pool
+pub fn has_lbp(env: &Env, token: &Address) -> bool {
+    env.storage().persistent().has(&DataKey::Lbp(token.clone()))
+}
+
+/// Remove a token's bootstrapping pool
+pub fn remove_lbp(env: &Env, token: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Lbp(token.clone()));
+}
+
 // ==================== TTL Management ====================
 
 /// Extend TTL for instance storage
@@ -1,12 +1,19 @@
-use astroswap_shared::{emit_pair_created, AstroSwapError, TokenMetadata};
-use soroban_sdk::{contract, contractimpl, xdr::ToXdr, Address, Bytes, BytesN, Env, Symbol, Vec};
+use astroswap_shared::{
+    emit_pair_created, require_bps_range, AstroSwapError, TokenMetadata, DEFAULT_SWAP_FEE_BPS,
+    MAX_FEE_BPS,
+};
+use soroban_sdk::{
+    contract, contractimpl, xdr::ToXdr, Address, Bytes, BytesN, Env, IntoVal, Symbol, Vec,
+};
 
 use crate::storage::{
-    add_pair_to_list, extend_instance_ttl, get_admin, get_fee_recipient, get_launchpad, get_pair,
-    get_pair_by_index, get_pair_wasm_hash, get_pairs_count, get_protocol_fee_bps,
+    add_pair_to_list, add_tier_pair, extend_instance_ttl, get_admin, get_fee_recipient,
+    get_fee_tiers, get_launchpad, get_pair, get_pair_by_index, get_pair_metadata,
+    get_pair_wasm_hash, get_pairs_count, get_protocol_fee_bps, get_tier_pairs,
     increment_pairs_count, is_initialized, is_paused, is_token_graduated, set_admin,
-    set_fee_recipient, set_graduated_token, set_initialized, set_launchpad, set_pair,
-    set_pair_wasm_hash, set_paused, set_protocol_fee_bps, sort_tokens, GraduatedTokenInfo,
+    set_fee_recipient, set_fee_tiers, set_graduated_token, set_initialized, set_launchpad,
+    set_pair, set_pair_metadata, set_pair_wasm_hash, set_paused, set_protocol_fee_bps, sort_tokens,
+    GraduatedTokenInfo, PairMetadata,
 };
 
 #[contract]
@@ -27,9 +34,7 @@ impl AstroSwapFactory {
         }
 
         // Validate fee (max 1% = 100 bps)
-        if protocol_fee_bps > 100 {
-            return Err(AstroSwapError::FeeTooHigh);
-        }
+        require_bps_range(protocol_fee_bps, MAX_FEE_BPS)?;
 
         set_admin(&env, &admin);
         set_pair_wasm_hash(&env, &pair_wasm_hash);
@@ -48,15 +53,73 @@ impl AstroSwapFactory {
         token_a: Address,
         token_b: Address,
     ) -> Result<Address, AstroSwapError> {
-        Self::require_not_paused(&env)?;
+        Self::deploy_pair(&env, token_a, token_b, None, false, DEFAULT_SWAP_FEE_BPS)
+    }
+
+    /// Create a new trading pair on behalf of `creator`
+    /// The creator (router, bridge or user) is recorded in the pair metadata
+    pub fn create_pair_for(
+        env: Env,
+        creator: Address,
+        token_a: Address,
+        token_b: Address,
+    ) -> Result<Address, AstroSwapError> {
+        creator.require_auth();
+        Self::deploy_pair(
+            &env,
+            token_a,
+            token_b,
+            Some(creator),
+            false,
+            DEFAULT_SWAP_FEE_BPS,
+        )
+    }
+
+    /// Create a pair in a non-default fee tier on behalf of `creator`
+    ///
+    /// `fee_bps` must be a tier enabled with `set_fee_tier`. Each fee tier
+    /// has its own pair for the two tokens; `get_pair` keeps returning the
+    /// default-tier pair, `get_pairs_all_tiers` lists them all.
+    pub fn create_pair_with_fee(
+        env: Env,
+        creator: Address,
+        token_a: Address,
+        token_b: Address,
+        fee_bps: u32,
+    ) -> Result<Address, AstroSwapError> {
+        creator.require_auth();
+        if fee_bps != DEFAULT_SWAP_FEE_BPS && !get_fee_tiers(&env).contains(fee_bps) {
+            return Err(AstroSwapError::InvalidFee);
+        }
+        Self::deploy_pair(&env, token_a, token_b, Some(creator), false, fee_bps)
+    }
+
+    /// Deploy and initialize a pair, recording its creation metadata
+    fn deploy_pair(
+        env: &Env,
+        token_a: Address,
+        token_b: Address,
+        creator: Option<Address>,
+        graduated: bool,
+        fee_bps: u32,
+    ) -> Result<Address, AstroSwapError> {
+        Self::require_not_paused(env)?;
 
         // Tokens must be different
         if token_a == token_b {
             return Err(AstroSwapError::SameToken);
         }
 
-        // Check if pair already exists
-        if get_pair(&env, &token_a, &token_b).is_some() {
+        // Check if pair already exists in this fee tier
+        let is_default_tier = fee_bps == DEFAULT_SWAP_FEE_BPS;
+        let exists = if is_default_tier {
+            get_pair(env, &token_a, &token_b).is_some()
+        } else {
+            Self::tier_pairs(env, &token_a, &token_b)
+                .iter()
+                .any(|(_, tier)| tier == fee_bps)
+        };
+        if exists {
             return Err(AstroSwapError::PairExists);
         }
 
@@ -64,13 +127,16 @@ impl AstroSwapFactory {
         let (token_0, token_1) = sort_tokens(&token_a, &token_b);
 
         // Deploy new pair contract
-        let pair_wasm_hash = get_pair_wasm_hash(&env);
+        let pair_wasm_hash = get_pair_wasm_hash(env);
 
         // Create deterministic contract ID based on tokens using XDR serialization
         // This is the recommended pattern from Stellar docs for deterministic addresses
-        let mut salt_preimage = Bytes::new(&env);
-        salt_preimage.append(&token_0.clone().to_xdr(&env));
-        salt_preimage.append(&token_1.clone().to_xdr(&env));
+        let mut salt_preimage = Bytes::new(env);
+        salt_preimage.append(&token_0.clone().to_xdr(env));
+        salt_preimage.append(&token_1.clone().to_xdr(env));
+        if !is_default_tier {
+            salt_preimage.append(&fee_bps.to_xdr(env));
+        }
         let salt = env.crypto().sha256(&salt_preimage);
 
         // Deploy pair contract with deploy_v2 (no constructor args)
@@ -82,9 +148,9 @@ impl AstroSwapFactory {
         // Initialize the pair contract via cross-contract call
         env.invoke_contract::<()>(
             &pair_address,
-            &Symbol::new(&env, "initialize"),
+            &Symbol::new(env, "initialize"),
             Vec::from_array(
-                &env,
+                env,
                 [
                     env.current_contract_address().to_val(), // factory
                     token_0.clone().to_val(),
@@ -94,16 +160,39 @@ impl AstroSwapFactory {
         );
 
         // Store pair mapping
-        set_pair(&env, &token_0, &token_1, &pair_address);
+        if is_default_tier {
+            set_pair(env, &token_0, &token_1, &pair_address);
+        } else {
+            env.invoke_contract::<()>(
+                &pair_address,
+                &Symbol::new(env, "set_fee_bps"),
+                Vec::from_array(env, [fee_bps.into_val(env)]),
+            );
+            add_tier_pair(env, &token_0, &token_1, &pair_address);
+        }
 
         // Add to list and get index
-        let pair_index = increment_pairs_count(&env);
-        add_pair_to_list(&env, &pair_address, pair_index - 1);
+        let pair_index = increment_pairs_count(env);
+        add_pair_to_list(env, &pair_address, pair_index - 1);
+
+        // Record how the pair was created
+        let graduated =
+            graduated || is_token_graduated(env, &token_0) || is_token_graduated(env, &token_1);
+        set_pair_metadata(
+            env,
+            &pair_address,
+            &PairMetadata {
+                creator,
+                created_at: env.ledger().timestamp(),
+                fee_bps,
+                graduated,
+            },
+        );
 
         // Emit event
-        emit_pair_created(&env, &token_0, &token_1, &pair_address, pair_index);
+        emit_pair_created(env, &token_0, &token_1, &pair_address, pair_index);
 
-        extend_instance_ttl(&env);
+        extend_instance_ttl(env);
 
         Ok(pair_address)
     }
@@ -114,6 +203,22 @@ impl AstroSwapFactory {
         get_pair(&env, &token_a, &token_b)
     }
 
+    /// Get the pairs of two tokens in every fee tier as (pair, fee_bps)
+    /// The default-tier pair comes first, then the other tiers in creation order
+    pub fn get_pairs_all_tiers(
+        env: Env,
+        token_a: Address,
+        token_b: Address,
+    ) -> Vec<(Address, u32)> {
+        extend_instance_ttl(&env);
+        let mut pairs = Vec::new(&env);
+        if let Some(pair) = get_pair(&env, &token_a, &token_b) {
+            pairs.push_back((pair, DEFAULT_SWAP_FEE_BPS));
+        }
+        pairs.append(&Self::tier_pairs(&env, &token_a, &token_b));
+        pairs
+    }
+
     /// Get pair by index
     pub fn get_pair_by_index(env: Env, index: u32) -> Option<Address> {
         extend_instance_ttl(&env);
@@ -148,15 +253,44 @@ impl AstroSwapFactory {
         Self::require_admin(&env, &caller)?;
 
         // Max fee is 1% (100 bps)
-        if fee_bps > 100 {
-            return Err(AstroSwapError::FeeTooHigh);
-        }
+        require_bps_range(fee_bps, MAX_FEE_BPS)?;
 
         set_protocol_fee_bps(&env, fee_bps);
         extend_instance_ttl(&env);
         Ok(())
     }
 
+    /// Enable or disable a swap fee tier for `create_pair_with_fee`
+    /// Only admin can call
+    ///
+    /// Disabling a tier only stops new pairs; existing pairs keep their fee.
+    pub fn set_fee_tier(
+        env: Env,
+        caller: Address,
+        fee_bps: u32,
+        enabled: bool,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &caller)?;
+
+        if fee_bps == 0 || fee_bps == DEFAULT_SWAP_FEE_BPS {
+            return Err(AstroSwapError::InvalidFee);
+        }
+        require_bps_range(fee_bps, MAX_FEE_BPS)?;
+
+        let mut tiers = get_fee_tiers(&env);
+        match (tiers.first_index_of(fee_bps), enabled) {
+            (None, true) => tiers.push_back(fee_bps),
+            (Some(index), false) => {
+                tiers.remove(index);
+            }
+            _ => {}
+        }
+        set_fee_tiers(&env, &tiers);
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
     /// Update the pair WASM hash for future deployments
     /// Only admin can call
     pub fn set_pair_wasm_hash(
@@ -201,6 +335,94 @@ impl AstroSwapFactory {
         Ok(())
     }
 
+    /// Set the reserve cap and swap floor of a pair (0 disables either)
+    /// Only admin can call
+    pub fn set_pair_reserve_bounds(
+        env: Env,
+        caller: Address,
+        pair: Address,
+        max_reserve: i128,
+        min_reserve: i128,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &caller)?;
+
+        if get_pair_metadata(&env, &pair).is_none() {
+            return Err(AstroSwapError::PairNotFound);
+        }
+
+        env.invoke_contract::<()>(
+            &pair,
+            &Symbol::new(&env, "set_reserve_bounds"),
+            Vec::from_array(
+                &env,
+                [max_reserve.into_val(&env), min_reserve.into_val(&env)],
+            ),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set a pair's large-withdrawal mode (0 threshold disables it)
+    /// Only admin can call
+    ///
+    /// Withdrawals above `threshold_bps` of the pair's reserves then need a
+    /// request `delay_seconds` ahead, executable for `window_seconds`.
+    pub fn set_pair_large_withdrawal(
+        env: Env,
+        caller: Address,
+        pair: Address,
+        threshold_bps: u32,
+        delay_seconds: u64,
+        window_seconds: u64,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &caller)?;
+
+        if get_pair_metadata(&env, &pair).is_none() {
+            return Err(AstroSwapError::PairNotFound);
+        }
+
+        env.invoke_contract::<()>(
+            &pair,
+            &Symbol::new(&env, "set_large_withdrawal"),
+            Vec::from_array(
+                &env,
+                [
+                    threshold_bps.into_val(&env),
+                    delay_seconds.into_val(&env),
+                    window_seconds.into_val(&env),
+                ],
+            ),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set or clear the analytics contract a pair reports its swaps to
+    /// Only admin can call
+    pub fn set_pair_analytics(
+        env: Env,
+        caller: Address,
+        pair: Address,
+        analytics: Option<Address>,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &caller)?;
+
+        if get_pair_metadata(&env, &pair).is_none() {
+            return Err(AstroSwapError::PairNotFound);
+        }
+
+        env.invoke_contract::<()>(
+            &pair,
+            &Symbol::new(&env, "set_analytics"),
+            Vec::from_array(&env, [analytics.into_val(&env)]),
+        );
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
     // ==================== Astro-Shiba Integration ====================
 
     /// Register a graduated token from Astro-Shiba launchpad
@@ -257,7 +479,14 @@ impl AstroSwapFactory {
         }
 
         // Create the pair
-        Self::create_pair(env, token, quote_token)
+        Self::deploy_pair(
+            &env,
+            token,
+            quote_token,
+            Some(caller),
+            true,
+            DEFAULT_SWAP_FEE_BPS,
+        )
     }
 
     // ==================== View Functions ====================
@@ -296,8 +525,31 @@ impl AstroSwapFactory {
         is_token_graduated(&env, &token)
     }
 
+    /// Swap fees pairs may be created with besides the default
+    pub fn fee_tiers(env: Env) -> Vec<u32> {
+        extend_instance_ttl(&env);
+        get_fee_tiers(&env)
+    }
+
+    /// Get the creation metadata of a pair (creator, timestamp, fee tier)
+    pub fn pair_metadata(env: Env, pair: Address) -> Option<PairMetadata> {
+        extend_instance_ttl(&env);
+        get_pair_metadata(&env, &pair)
+    }
+
     // ==================== Internal Functions ====================
 
+    /// Non-default-tier pairs of two tokens as (pair, fee_bps)
+    fn tier_pairs(env: &Env, token_a: &Address, token_b: &Address) -> Vec<(Address, u32)> {
+        let mut pairs = Vec::new(env);
+        for pair in get_tier_pairs(env, token_a, token_b).iter() {
+            if let Some(metadata) = get_pair_metadata(env, &pair) {
+                pairs.push_back((pair, metadata.fee_bps));
+            }
+        }
+        pairs
+    }
+
     /// Verify caller is admin
     fn require_admin(env: &Env, caller: &Address) -> Result<(), AstroSwapError> {
         caller.require_auth();
@@ -4,3 +4,4 @@ mod contract;
 mod storage;
 
 pub use contract::{AstroSwapFactory, AstroSwapFactoryClient};
+pub use storage::PairMetadata;
@@ -1,5 +1,5 @@
 use astroswap_shared::TokenMetadata;
-use soroban_sdk::{contracttype, Address, BytesN, Env};
+use soroban_sdk::{contracttype, Address, BytesN, Env, Vec};
 
 /// Storage keys for the factory contract
 #[contracttype]
@@ -14,11 +14,14 @@ pub enum DataKey {
     Paused,
     PairsCount,
     LaunchpadAddress,
+    FeeTiers, // Swap fees pairs may be created with besides the default
 
     // Persistent storage (unbounded)
     Pair(Address, Address),
+    TierPairs(Address, Address), // Pairs of the tokens in non-default fee tiers
     AllPairs(u32),
     GraduatedToken(Address),
+    PairMetadata(Address),
 }
 
 /// Check if the contract is initialized
@@ -131,6 +134,19 @@ pub fn set_launchpad(env: &Env, launchpad: &Address) {
         .set(&DataKey::LaunchpadAddress, launchpad);
 }
 
+/// Get the swap fees pairs may be created with besides the default
+pub fn get_fee_tiers(env: &Env) -> Vec<u32> {
+    env.storage()
+        .instance()
+        .get::<DataKey, Vec<u32>>(&DataKey::FeeTiers)
+        .unwrap_or(Vec::new(env))
+}
+
+/// Set the swap fees pairs may be created with besides the default
+pub fn set_fee_tiers(env: &Env, fee_tiers: &Vec<u32>) {
+    env.storage().instance().set(&DataKey::FeeTiers, fee_tiers);
+}
+
 /// Sort two token addresses to ensure consistent ordering
 pub fn sort_tokens(token_a: &Address, token_b: &Address) -> (Address, Address) {
     if token_a < token_b {
@@ -156,6 +172,25 @@ pub fn set_pair(env: &Env, token_a: &Address, token_b: &Address, pair: &Address)
         .set(&DataKey::Pair(token_0, token_1), pair);
 }
 
+/// Get the pairs of two tokens in non-default fee tiers, in creation order
+pub fn get_tier_pairs(env: &Env, token_a: &Address, token_b: &Address) -> Vec<Address> {
+    let (token_0, token_1) = sort_tokens(token_a, token_b);
+    env.storage()
+        .persistent()
+        .get::<DataKey, Vec<Address>>(&DataKey::TierPairs(token_0, token_1))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Add a pair of two tokens in a non-default fee tier
+pub fn add_tier_pair(env: &Env, token_a: &Address, token_b: &Address, pair: &Address) {
+    let (token_0, token_1) = sort_tokens(token_a, token_b);
+    let mut pairs = get_tier_pairs(env, &token_0, &token_1);
+    pairs.push_back(pair.clone());
+    env.storage()
+        .persistent()
+        .set(&DataKey::TierPairs(token_0, token_1), &pairs);
+}
+
 /// Get pair by index
 pub fn get_pair_by_index(env: &Env, index: u32) -> Option<Address> {
     env.storage()
@@ -170,6 +205,35 @@ pub fn add_pair_to_list(env: &Env, pair: &Address, index: u32) {
         .set(&DataKey::AllPairs(index), pair);
 }
 
+/// Creation details of a pair
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PairMetadata {
+    /// Contract or account that requested the pair (router, bridge, launchpad
+    /// or user); `None` for anonymous `create_pair` calls
+    pub creator: Option<Address>,
+    /// Ledger timestamp of the creation
+    pub created_at: u64,
+    /// Swap fee tier the pair was initialized with
+    pub fee_bps: u32,
+    /// Created for a token graduated from the launchpad
+    pub graduated: bool,
+}
+
+/// Get creation metadata of a pair
+pub fn get_pair_metadata(env: &Env, pair: &Address) -> Option<PairMetadata> {
+    env.storage()
+        .persistent()
+        .get::<DataKey, PairMetadata>(&DataKey::PairMetadata(pair.clone()))
+}
+
+/// Set creation metadata of a pair
+pub fn set_pair_metadata(env: &Env, pair: &Address, metadata: &PairMetadata) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::PairMetadata(pair.clone()), metadata);
+}
+
 /// Information about a graduated token
 #[contracttype]
 #[derive(Clone)]
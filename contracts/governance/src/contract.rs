@@ -0,0 +1,870 @@
+//! AstroSwap Governance Contract
+//!
+//! Token-weighted governance with a built-in timelock. Holders deposit the
+//! governance token for voting power, which is checkpointed so each proposal
+//! counts votes as of its creation. A proposal that reaches quorum and the
+//! approval threshold is queued, and after `timelock_delay` anyone can execute
+//! its calls, made by this contract. Contracts hand parameters such as fees
+//! and emissions to governance by making this contract their admin.
+
+use astroswap_shared::{require_positive, AstroSwapError, BPS_DENOMINATOR};
+use soroban_sdk::{
+    contract, contractevent, contractimpl, token, Address, Env, String, Symbol, TryFromVal, Val,
+    Vec,
+};
+
+use crate::storage::{
+    extend_instance_ttl, get_config, get_proposal, get_proposal_count, get_receipt, get_token,
+    is_initialized, latest_votes, push_checkpoint, set_config, set_initialized, set_proposal,
+    set_proposal_count, set_receipt, set_token, votes_before, GovernanceConfig, Proposal,
+    ProposalCall, ProposalState, VoteReceipt, VoteType, MAX_PROPOSAL_CALLS,
+};
+
+/// Function a proposal calls on this contract to change its own config
+const SET_CONFIG: &str = "set_config";
+
+/// Voting power deposited event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VotesDeposited {
+    #[topic]
+    pub user: Address,
+    pub amount: i128,
+    pub votes: i128,
+}
+
+/// Voting power withdrawn event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VotesWithdrawn {
+    #[topic]
+    pub user: Address,
+    pub amount: i128,
+    pub votes: i128,
+}
+
+/// Proposal created event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProposalCreated {
+    #[topic]
+    pub id: u64,
+    pub proposer: Address,
+    pub snapshot_ledger: u32,
+    pub vote_end: u64,
+}
+
+/// Vote cast event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VoteCast {
+    #[topic]
+    pub id: u64,
+    #[topic]
+    pub voter: Address,
+    pub support: VoteType,
+    pub votes: i128,
+}
+
+/// Proposal queued in the timelock event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProposalQueued {
+    #[topic]
+    pub id: u64,
+    pub eta: u64,
+}
+
+/// Proposal executed event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProposalExecuted {
+    #[topic]
+    pub id: u64,
+}
+
+/// Proposal canceled event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProposalCanceled {
+    #[topic]
+    pub id: u64,
+}
+
+#[contract]
+pub struct AstroSwapGovernance;
+
+#[contractimpl]
+impl AstroSwapGovernance {
+    /// Initialize the governance contract
+    ///
+    /// # Arguments
+    /// * `token` - Token deposited for voting power
+    /// * `config` - Voting and timelock parameters; afterwards only a
+    ///   proposal calling `set_config` on this contract can change them
+    pub fn initialize(
+        env: Env,
+        token: Address,
+        config: GovernanceConfig,
+    ) -> Result<(), AstroSwapError> {
+        if is_initialized(&env) {
+            return Err(AstroSwapError::AlreadyInitialized);
+        }
+        Self::validate_config(&config)?;
+
+        set_token(&env, &token);
+        set_config(&env, &config);
+        set_initialized(&env);
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    // ==================== Voting Power ====================
+
+    /// Deposit governance tokens for voting power
+    ///
+    /// Power counts for proposals created from the next ledger on.
+    ///
+    /// # Returns
+    /// * The user's voting power after the deposit
+    pub fn deposit(env: Env, user: Address, amount: i128) -> Result<i128, AstroSwapError> {
+        Self::require_initialized(&env)?;
+        user.require_auth();
+        require_positive(amount)?;
+
+        token::Client::new(&env, &get_token(&env)).transfer(
+            &user,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        let votes = latest_votes(&env, Some(&user)) + amount;
+        push_checkpoint(&env, Some(&user), votes);
+        let total = latest_votes(&env, None) + amount;
+        push_checkpoint(&env, None, total);
+
+        VotesDeposited {
+            user: user.clone(),
+            amount,
+            votes,
+        }
+        .publish(&env);
+
+        extend_instance_ttl(&env);
+        Ok(votes)
+    }
+
+    /// Withdraw deposited governance tokens
+    ///
+    /// Votes already cast keep their weight; the power withdrawn no longer
+    /// counts for proposals created afterwards.
+    ///
+    /// # Returns
+    /// * The user's voting power after the withdrawal
+    pub fn withdraw(env: Env, user: Address, amount: i128) -> Result<i128, AstroSwapError> {
+        Self::require_initialized(&env)?;
+        user.require_auth();
+        require_positive(amount)?;
+
+        let current = latest_votes(&env, Some(&user));
+        if current < amount {
+            return Err(AstroSwapError::InsufficientBalance);
+        }
+
+        let votes = current - amount;
+        push_checkpoint(&env, Some(&user), votes);
+        let total = latest_votes(&env, None) - amount;
+        push_checkpoint(&env, None, total);
+
+        token::Client::new(&env, &get_token(&env)).transfer(
+            &env.current_contract_address(),
+            &user,
+            &amount,
+        );
+
+        VotesWithdrawn {
+            user: user.clone(),
+            amount,
+            votes,
+        }
+        .publish(&env);
+
+        extend_instance_ttl(&env);
+        Ok(votes)
+    }
+
+    // ==================== Proposals ====================
+
+    /// Create a proposal
+    ///
+    /// The proposer needs `proposal_threshold` voting power as of the previous
+    /// ledger. Voting opens immediately, counting power held before this
+    /// ledger, and runs for `voting_period`. Quorum and the approval
+    /// threshold are fixed at creation.
+    ///
+    /// # Arguments
+    /// * `calls` - 1 to `MAX_PROPOSAL_CALLS` calls, made in order on execution
+    /// * `description` - Human-readable rationale
+    ///
+    /// # Returns
+    /// * The proposal ID
+    pub fn propose(
+        env: Env,
+        proposer: Address,
+        calls: Vec<ProposalCall>,
+        description: String,
+    ) -> Result<u64, AstroSwapError> {
+        Self::require_initialized(&env)?;
+        proposer.require_auth();
+
+        if calls.is_empty() || calls.len() > MAX_PROPOSAL_CALLS {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+        for call in calls.iter() {
+            if call.contract == env.current_contract_address() {
+                Self::self_call_config(&env, &call)?;
+            }
+        }
+
+        let config = get_config(&env);
+        let snapshot_ledger = env.ledger().sequence();
+        let power = votes_before(&env, Some(&proposer), snapshot_ledger);
+        if power < config.proposal_threshold {
+            return Err(AstroSwapError::BelowProposalThreshold);
+        }
+
+        let total = votes_before(&env, None, snapshot_ledger);
+        let id = get_proposal_count(&env);
+        let proposal = Proposal {
+            id,
+            proposer: proposer.clone(),
+            calls,
+            description,
+            snapshot_ledger,
+            vote_end: env.ledger().timestamp() + config.voting_period,
+            quorum: total * i128::from(config.quorum_bps) / i128::from(BPS_DENOMINATOR),
+            approval_bps: config.approval_bps,
+            for_votes: 0,
+            against_votes: 0,
+            abstain_votes: 0,
+            eta: None,
+            executed: false,
+            canceled: false,
+        };
+        set_proposal(&env, &proposal);
+        set_proposal_count(&env, id + 1);
+
+        ProposalCreated {
+            id,
+            proposer,
+            snapshot_ledger,
+            vote_end: proposal.vote_end,
+        }
+        .publish(&env);
+
+        extend_instance_ttl(&env);
+        Ok(id)
+    }
+
+    /// Vote on an active proposal with the power held before its snapshot
+    ///
+    /// # Returns
+    /// * The votes cast
+    pub fn vote(
+        env: Env,
+        voter: Address,
+        proposal_id: u64,
+        support: VoteType,
+    ) -> Result<i128, AstroSwapError> {
+        voter.require_auth();
+
+        let mut proposal = Self::load_proposal(&env, proposal_id)?;
+        if Self::state_of(&env, &proposal) != ProposalState::Active {
+            return Err(AstroSwapError::InvalidProposalState);
+        }
+        if get_receipt(&env, proposal_id, &voter).is_some() {
+            return Err(AstroSwapError::AlreadyVoted);
+        }
+
+        let votes = votes_before(&env, Some(&voter), proposal.snapshot_ledger);
+        if votes == 0 {
+            return Err(AstroSwapError::InsufficientBalance);
+        }
+
+        match support {
+            VoteType::Against => proposal.against_votes += votes,
+            VoteType::For => proposal.for_votes += votes,
+            VoteType::Abstain => proposal.abstain_votes += votes,
+        }
+        set_proposal(&env, &proposal);
+        set_receipt(&env, proposal_id, &voter, &VoteReceipt { support, votes });
+
+        VoteCast {
+            id: proposal_id,
+            voter,
+            support,
+            votes,
+        }
+        .publish(&env);
+
+        extend_instance_ttl(&env);
+        Ok(votes)
+    }
+
+    /// Queue a succeeded proposal in the timelock
+    /// Anyone can call
+    ///
+    /// # Returns
+    /// * The earliest execution time
+    pub fn queue(env: Env, proposal_id: u64) -> Result<u64, AstroSwapError> {
+        let mut proposal = Self::load_proposal(&env, proposal_id)?;
+        if Self::state_of(&env, &proposal) != ProposalState::Succeeded {
+            return Err(AstroSwapError::InvalidProposalState);
+        }
+
+        let eta = env.ledger().timestamp() + get_config(&env).timelock_delay;
+        proposal.eta = Some(eta);
+        set_proposal(&env, &proposal);
+
+        ProposalQueued {
+            id: proposal_id,
+            eta,
+        }
+        .publish(&env);
+
+        extend_instance_ttl(&env);
+        Ok(eta)
+    }
+
+    /// Execute a queued proposal once its timelock has passed
+    /// Anyone can call
+    ///
+    /// Calls are made in order by this contract; if one fails, the whole
+    /// execution reverts and the proposal stays queued.
+    pub fn execute(env: Env, proposal_id: u64) -> Result<(), AstroSwapError> {
+        let mut proposal = Self::load_proposal(&env, proposal_id)?;
+        if Self::state_of(&env, &proposal) != ProposalState::Queued {
+            return Err(AstroSwapError::InvalidProposalState);
+        }
+        if env.ledger().timestamp() < proposal.eta.unwrap() {
+            return Err(AstroSwapError::TimelockNotExpired);
+        }
+
+        // Mark executed before making any call
+        proposal.executed = true;
+        set_proposal(&env, &proposal);
+
+        for call in proposal.calls.iter() {
+            if call.contract == env.current_contract_address() {
+                // A contract cannot call itself, so config changes apply here
+                let config = Self::self_call_config(&env, &call)?;
+                set_config(&env, &config);
+            } else {
+                env.invoke_contract::<Val>(&call.contract, &call.function, call.args.clone());
+            }
+        }
+
+        ProposalExecuted { id: proposal_id }.publish(&env);
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Cancel a proposal that has not executed
+    /// Only the proposer can call
+    pub fn cancel(env: Env, proposer: Address, proposal_id: u64) -> Result<(), AstroSwapError> {
+        proposer.require_auth();
+
+        let mut proposal = Self::load_proposal(&env, proposal_id)?;
+        if proposal.proposer != proposer {
+            return Err(AstroSwapError::Unauthorized);
+        }
+        match Self::state_of(&env, &proposal) {
+            ProposalState::Executed | ProposalState::Canceled | ProposalState::Expired => {
+                return Err(AstroSwapError::InvalidProposalState);
+            }
+            _ => {}
+        }
+
+        proposal.canceled = true;
+        set_proposal(&env, &proposal);
+
+        ProposalCanceled { id: proposal_id }.publish(&env);
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    // ==================== View Functions ====================
+
+    /// Get the token deposited for voting power
+    pub fn token(env: Env) -> Address {
+        get_token(&env)
+    }
+
+    /// Get the voting and timelock parameters
+    pub fn config(env: Env) -> GovernanceConfig {
+        get_config(&env)
+    }
+
+    /// Get the number of proposals created (IDs are `0..count`)
+    pub fn proposal_count(env: Env) -> u64 {
+        get_proposal_count(&env)
+    }
+
+    /// Get a proposal and its tally
+    pub fn proposal(env: Env, proposal_id: u64) -> Result<Proposal, AstroSwapError> {
+        Self::load_proposal(&env, proposal_id)
+    }
+
+    /// Get the current state of a proposal
+    pub fn state(env: Env, proposal_id: u64) -> Result<ProposalState, AstroSwapError> {
+        let proposal = Self::load_proposal(&env, proposal_id)?;
+        Ok(Self::state_of(&env, &proposal))
+    }
+
+    /// Get how an account voted on a proposal, if it did
+    pub fn receipt(env: Env, proposal_id: u64, voter: Address) -> Option<VoteReceipt> {
+        get_receipt(&env, proposal_id, &voter)
+    }
+
+    /// Get an account's current voting power
+    pub fn votes(env: Env, user: Address) -> i128 {
+        latest_votes(&env, Some(&user))
+    }
+
+    /// Get an account's voting power as it stood before `ledger`
+    pub fn votes_before(env: Env, user: Address, ledger: u32) -> i128 {
+        votes_before(&env, Some(&user), ledger)
+    }
+
+    /// Get the total voting power deposited
+    pub fn total_votes(env: Env) -> i128 {
+        latest_votes(&env, None)
+    }
+
+    /// Get the total voting power as it stood before `ledger`
+    pub fn total_votes_before(env: Env, ledger: u32) -> i128 {
+        votes_before(&env, None, ledger)
+    }
+
+    // ==================== Internal Functions ====================
+
+    fn require_initialized(env: &Env) -> Result<(), AstroSwapError> {
+        if !is_initialized(env) {
+            return Err(AstroSwapError::NotInitialized);
+        }
+        Ok(())
+    }
+
+    fn load_proposal(env: &Env, proposal_id: u64) -> Result<Proposal, AstroSwapError> {
+        get_proposal(env, proposal_id).ok_or(AstroSwapError::ProposalNotFound)
+    }
+
+    /// Lifecycle state of `proposal` at the current ledger time
+    fn state_of(env: &Env, proposal: &Proposal) -> ProposalState {
+        if proposal.canceled {
+            return ProposalState::Canceled;
+        }
+        if proposal.executed {
+            return ProposalState::Executed;
+        }
+        let now = env.ledger().timestamp();
+        if now <= proposal.vote_end {
+            return ProposalState::Active;
+        }
+
+        let decided = proposal.for_votes + proposal.against_votes;
+        let approved = proposal.for_votes > 0
+            && proposal.for_votes * i128::from(BPS_DENOMINATOR)
+                >= decided * i128::from(proposal.approval_bps);
+        if proposal.for_votes + proposal.abstain_votes < proposal.quorum || !approved {
+            return ProposalState::Defeated;
+        }
+
+        match proposal.eta {
+            None => ProposalState::Succeeded,
+            Some(eta) if now > eta + get_config(env).grace_period => ProposalState::Expired,
+            Some(_) => ProposalState::Queued,
+        }
+    }
+
+    /// Config set by a proposal call on this contract; `set_config` with one
+    /// valid `GovernanceConfig` argument is the only call allowed
+    fn self_call_config(
+        env: &Env,
+        call: &ProposalCall,
+    ) -> Result<GovernanceConfig, AstroSwapError> {
+        if call.function != Symbol::new(env, SET_CONFIG) || call.args.len() != 1 {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+        let config = GovernanceConfig::try_from_val(env, &call.args.get(0).unwrap())
+            .map_err(|_| AstroSwapError::InvalidArgument)?;
+        Self::validate_config(&config)?;
+        Ok(config)
+    }
+
+    fn validate_config(config: &GovernanceConfig) -> Result<(), AstroSwapError> {
+        if config.proposal_threshold < 0
+            || config.quorum_bps > BPS_DENOMINATOR
+            || config.approval_bps == 0
+            || config.approval_bps > BPS_DENOMINATOR
+            || config.voting_period == 0
+        {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, Ledger};
+    use soroban_sdk::token::StellarAssetClient;
+    use soroban_sdk::{vec, IntoVal};
+
+    // Contract with a governable fee, administered by `admin`
+    #[contract]
+    struct MockTarget;
+
+    #[contractimpl]
+    impl MockTarget {
+        pub fn init(env: Env, admin: Address) {
+            env.storage().instance().set(&0u32, &admin);
+        }
+
+        pub fn set_fee(env: Env, admin: Address, fee_bps: u32) {
+            admin.require_auth();
+            let stored: Address = env.storage().instance().get(&0u32).unwrap();
+            assert_eq!(admin, stored);
+            env.storage().instance().set(&1u32, &fee_bps);
+        }
+
+        pub fn fee(env: Env) -> u32 {
+            env.storage().instance().get(&1u32).unwrap_or(0)
+        }
+    }
+
+    const DAY: u64 = 86_400;
+
+    fn test_config() -> GovernanceConfig {
+        GovernanceConfig {
+            proposal_threshold: 100,
+            quorum_bps: 2_000,
+            approval_bps: 5_000,
+            voting_period: 3 * DAY,
+            timelock_delay: 2 * DAY,
+            grace_period: 7 * DAY,
+        }
+    }
+
+    struct Setup<'a> {
+        env: Env,
+        client: AstroSwapGovernanceClient<'a>,
+        target: MockTargetClient<'a>,
+        alice: Address,
+        bob: Address,
+    }
+
+    /// Alice holds 600 and Bob 400 of 1000 votes, counted from the next ledger
+    fn setup<'a>() -> Setup<'a> {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let token_admin = Address::generate(&env);
+        let token = env
+            .register_stellar_asset_contract_v2(token_admin)
+            .address();
+        let contract_id = env.register(AstroSwapGovernance, ());
+        let client = AstroSwapGovernanceClient::new(&env, &contract_id);
+        client.initialize(&token, &test_config());
+
+        let target = MockTargetClient::new(&env, &env.register(MockTarget, ()));
+        target.init(&contract_id);
+
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        for (user, amount) in [(&alice, 600), (&bob, 400)] {
+            StellarAssetClient::new(&env, &token).mint(user, &amount);
+            client.deposit(user, &amount);
+        }
+        advance(&env, 0);
+
+        Setup {
+            env,
+            client,
+            target,
+            alice,
+            bob,
+        }
+    }
+
+    /// Move to the next ledger, `seconds` later
+    fn advance(env: &Env, seconds: u64) {
+        env.ledger().with_mut(|li| {
+            li.sequence_number += 1;
+            li.timestamp += seconds;
+        });
+    }
+
+    fn set_fee_call(s: &Setup, fee_bps: u32) -> Vec<ProposalCall> {
+        vec![
+            &s.env,
+            ProposalCall {
+                contract: s.target.address.clone(),
+                function: Symbol::new(&s.env, "set_fee"),
+                args: vec![
+                    &s.env,
+                    s.client.address.into_val(&s.env),
+                    fee_bps.into_val(&s.env),
+                ],
+            },
+        ]
+    }
+
+    fn propose_fee(s: &Setup, fee_bps: u32) -> u64 {
+        s.client.propose(
+            &s.alice,
+            &set_fee_call(s, fee_bps),
+            &String::from_str(&s.env, "Set fee"),
+        )
+    }
+
+    #[test]
+    fn test_initialize() {
+        let s = setup();
+        assert_eq!(s.client.config(), test_config());
+        assert_eq!(
+            s.client.try_initialize(&s.client.token(), &test_config()),
+            Err(Ok(AstroSwapError::AlreadyInitialized))
+        );
+    }
+
+    #[test]
+    fn test_checkpointed_votes() {
+        let s = setup();
+        let deposit_ledger = s.env.ledger().sequence() - 1;
+
+        assert_eq!(s.client.votes(&s.alice), 600);
+        assert_eq!(s.client.votes_before(&s.alice, &deposit_ledger), 0);
+        assert_eq!(s.client.votes_before(&s.alice, &(deposit_ledger + 1)), 600);
+        assert_eq!(s.client.total_votes(), 1_000);
+
+        advance(&s.env, 0);
+        let ledger = s.env.ledger().sequence();
+        s.client.withdraw(&s.alice, &200);
+        s.client.withdraw(&s.alice, &100);
+        assert_eq!(s.client.votes(&s.alice), 300);
+        assert_eq!(s.client.votes_before(&s.alice, &ledger), 600);
+        assert_eq!(s.client.votes_before(&s.alice, &(ledger + 1)), 300);
+        assert_eq!(s.client.total_votes_before(&(ledger + 1)), 700);
+        assert_eq!(
+            s.client.try_withdraw(&s.alice, &301),
+            Err(Ok(AstroSwapError::InsufficientBalance))
+        );
+    }
+
+    #[test]
+    fn test_long_checkpoint_history() {
+        let s = setup();
+        let token = StellarAssetClient::new(&s.env, &s.client.token());
+        let carol = Address::generate(&s.env);
+        token.mint(&carol, &1_000);
+
+        // A deposit in every ledger grows Carol's and the total's history
+        let first_ledger = s.env.ledger().sequence();
+        for _ in 0..300 {
+            s.client.deposit(&carol, &1);
+            advance(&s.env, 5);
+        }
+
+        assert_eq!(s.client.votes(&carol), 300);
+        assert_eq!(s.client.votes_before(&carol, &first_ledger), 0);
+        for i in [1, 2, 137, 299, 300] {
+            assert_eq!(
+                s.client.votes_before(&carol, &(first_ledger + i)),
+                i as i128
+            );
+            assert_eq!(
+                s.client.total_votes_before(&(first_ledger + i)),
+                1_000 + i as i128
+            );
+        }
+    }
+
+    #[test]
+    fn test_proposal_lifecycle() {
+        let s = setup();
+
+        // Proposing needs the threshold as of the previous ledger
+        let carol = Address::generate(&s.env);
+        assert_eq!(
+            s.client.try_propose(
+                &carol,
+                &set_fee_call(&s, 10),
+                &String::from_str(&s.env, "Set fee")
+            ),
+            Err(Ok(AstroSwapError::BelowProposalThreshold))
+        );
+
+        let id = propose_fee(&s, 10);
+        assert_eq!(s.client.state(&id), ProposalState::Active);
+        assert_eq!(s.client.proposal(&id).quorum, 200);
+        assert_eq!(s.client.vote(&s.alice, &id, &VoteType::For), 600);
+        assert_eq!(s.client.vote(&s.bob, &id, &VoteType::Against), 400);
+        assert_eq!(
+            s.client.try_vote(&s.bob, &id, &VoteType::For),
+            Err(Ok(AstroSwapError::AlreadyVoted))
+        );
+        assert_eq!(
+            s.client.receipt(&id, &s.bob),
+            Some(VoteReceipt {
+                support: VoteType::Against,
+                votes: 400,
+            })
+        );
+
+        // Nothing can be queued or executed while voting is open
+        assert_eq!(
+            s.client.try_queue(&id),
+            Err(Ok(AstroSwapError::InvalidProposalState))
+        );
+        advance(&s.env, 3 * DAY + 1);
+        assert_eq!(s.client.state(&id), ProposalState::Succeeded);
+        assert_eq!(
+            s.client.try_execute(&id),
+            Err(Ok(AstroSwapError::InvalidProposalState))
+        );
+
+        // The timelock runs from queueing
+        let eta = s.client.queue(&id);
+        assert_eq!(eta, s.env.ledger().timestamp() + 2 * DAY);
+        assert_eq!(s.client.state(&id), ProposalState::Queued);
+        assert_eq!(
+            s.client.try_execute(&id),
+            Err(Ok(AstroSwapError::TimelockNotExpired))
+        );
+
+        advance(&s.env, 2 * DAY);
+        s.client.execute(&id);
+        assert_eq!(s.target.fee(), 10);
+        assert_eq!(s.client.state(&id), ProposalState::Executed);
+        assert_eq!(
+            s.client.try_execute(&id),
+            Err(Ok(AstroSwapError::InvalidProposalState))
+        );
+    }
+
+    #[test]
+    fn test_quorum_approval_and_snapshot() {
+        let s = setup();
+
+        // Votes deposited after the snapshot do not count
+        let id = propose_fee(&s, 10);
+        let carol = Address::generate(&s.env);
+        StellarAssetClient::new(&s.env, &s.client.token()).mint(&carol, &5_000);
+        s.client.deposit(&carol, &5_000);
+        advance(&s.env, 0);
+        assert_eq!(
+            s.client.try_vote(&carol, &id, &VoteType::For),
+            Err(Ok(AstroSwapError::InsufficientBalance))
+        );
+
+        // 400 against 0 for fails approval
+        s.client.vote(&s.bob, &id, &VoteType::Against);
+        advance(&s.env, 3 * DAY + 1);
+        assert_eq!(s.client.state(&id), ProposalState::Defeated);
+        assert_eq!(
+            s.client.try_vote(&s.alice, &id, &VoteType::For),
+            Err(Ok(AstroSwapError::InvalidProposalState))
+        );
+
+        // Abstaining counts towards quorum but not approval. Bob's 400 is
+        // below the 20% quorum of the new 6000 total
+        let id = propose_fee(&s, 20);
+        s.client.vote(&s.bob, &id, &VoteType::For);
+        advance(&s.env, 3 * DAY + 1);
+        assert_eq!(s.client.state(&id), ProposalState::Defeated);
+
+        let id = propose_fee(&s, 20);
+        s.client.vote(&s.bob, &id, &VoteType::For);
+        s.client.vote(&carol, &id, &VoteType::Abstain);
+        advance(&s.env, 3 * DAY + 1);
+        assert_eq!(s.client.state(&id), ProposalState::Succeeded);
+    }
+
+    #[test]
+    fn test_cancel_and_expiry() {
+        let s = setup();
+
+        let id = propose_fee(&s, 10);
+        assert_eq!(
+            s.client.try_cancel(&s.bob, &id),
+            Err(Ok(AstroSwapError::Unauthorized))
+        );
+        s.client.cancel(&s.alice, &id);
+        assert_eq!(s.client.state(&id), ProposalState::Canceled);
+
+        // A queued proposal expires after the grace period
+        let id = propose_fee(&s, 10);
+        s.client.vote(&s.alice, &id, &VoteType::For);
+        advance(&s.env, 3 * DAY + 1);
+        s.client.queue(&id);
+        advance(&s.env, 9 * DAY + 1);
+        assert_eq!(s.client.state(&id), ProposalState::Expired);
+        assert_eq!(
+            s.client.try_execute(&id),
+            Err(Ok(AstroSwapError::InvalidProposalState))
+        );
+        assert_eq!(s.target.fee(), 0);
+        assert_eq!(
+            s.client.try_proposal(&99),
+            Err(Ok(AstroSwapError::ProposalNotFound))
+        );
+    }
+
+    #[test]
+    fn test_config_changed_by_proposal() {
+        let s = setup();
+        let mut config = test_config();
+        config.quorum_bps = 5_000;
+
+        let config_call = |function: &str, config: &GovernanceConfig| {
+            vec![
+                &s.env,
+                ProposalCall {
+                    contract: s.client.address.clone(),
+                    function: Symbol::new(&s.env, function),
+                    args: vec![&s.env, config.into_val(&s.env)],
+                },
+            ]
+        };
+        let description = String::from_str(&s.env, "Raise quorum");
+
+        // Only valid `set_config` calls may target governance itself
+        assert_eq!(
+            s.client
+                .try_propose(&s.alice, &config_call("initialize", &config), &description),
+            Err(Ok(AstroSwapError::InvalidArgument))
+        );
+        let mut invalid = config.clone();
+        invalid.approval_bps = 0;
+        assert_eq!(
+            s.client
+                .try_propose(&s.alice, &config_call(SET_CONFIG, &invalid), &description),
+            Err(Ok(AstroSwapError::InvalidArgument))
+        );
+
+        let id = s
+            .client
+            .propose(&s.alice, &config_call(SET_CONFIG, &config), &description);
+        s.client.vote(&s.alice, &id, &VoteType::For);
+        advance(&s.env, 3 * DAY + 1);
+        s.client.queue(&id);
+        advance(&s.env, 2 * DAY);
+        s.client.execute(&id);
+        assert_eq!(s.client.config(), config);
+    }
+}
@@ -0,0 +1,10 @@
+#![no_std]
+
+mod contract;
+mod storage;
+
+pub use contract::{AstroSwapGovernance, AstroSwapGovernanceClient};
+pub use storage::{
+    Checkpoint, GovernanceConfig, Proposal, ProposalCall, ProposalState, VoteReceipt, VoteType,
+    MAX_PROPOSAL_CALLS,
+};
@@ -0,0 +1,308 @@
+//! Storage module for AstroSwap Governance
+//!
+//! Voting power is the governance token deposited in the contract. Each
+//! account's power and the total are checkpointed by ledger sequence, so a
+//! proposal counts votes as they stood before its snapshot ledger.
+
+use soroban_sdk::{contracttype, Address, Env, String, Symbol, Val, Vec};
+
+/// Most calls a single proposal may execute
+pub const MAX_PROPOSAL_CALLS: u32 = 10;
+
+/// Storage keys for the governance contract
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Initialized,
+    Token,
+    Config,
+    ProposalCount,
+    TotalCheckpointCount,
+    TotalCheckpoint(u32),     // Total voting power history by index
+    CheckpointCount(Address), // Length of an account's voting power history
+    Checkpoint(Address, u32), // (account, index) -> voting power checkpoint
+    Proposal(u64),            // Proposal by ID
+    Receipt(u64, Address),    // (proposal ID, voter)
+}
+
+/// Voting and timelock parameters
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GovernanceConfig {
+    /// Voting power needed to create a proposal
+    pub proposal_threshold: i128,
+    /// Share of the snapshot total that must vote for or abstain (bps)
+    pub quorum_bps: u32,
+    /// Share of the for and against votes that must be for (bps)
+    pub approval_bps: u32,
+    /// Seconds a proposal is open for voting
+    pub voting_period: u64,
+    /// Seconds between queueing a proposal and its earliest execution
+    pub timelock_delay: u64,
+    /// Seconds after the timelock in which a queued proposal can execute
+    pub grace_period: u64,
+}
+
+/// Voting power from a ledger on
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Checkpoint {
+    pub ledger: u32,
+    pub votes: i128,
+}
+
+/// Contract call a proposal executes, made by the governance contract
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProposalCall {
+    pub contract: Address,
+    pub function: Symbol,
+    pub args: Vec<Val>,
+}
+
+/// Side a vote counts towards
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VoteType {
+    Against,
+    For,
+    Abstain,
+}
+
+/// Proposal lifecycle state, derived from the proposal and the ledger time
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProposalState {
+    /// Open for voting
+    Active,
+    /// Voting ended below quorum or approval
+    Defeated,
+    /// Voting passed, not queued yet
+    Succeeded,
+    /// Queued in the timelock
+    Queued,
+    /// Grace period after the timelock ran out without execution
+    Expired,
+    Executed,
+    Canceled,
+}
+
+/// A proposal and its tally
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposal {
+    pub id: u64,
+    pub proposer: Address,
+    pub calls: Vec<ProposalCall>,
+    pub description: String,
+    /// Votes count as they stood before this ledger
+    pub snapshot_ledger: u32,
+    /// Last second of voting
+    pub vote_end: u64,
+    /// For and abstain votes needed, fixed at creation
+    pub quorum: i128,
+    /// Approval threshold (bps), fixed at creation
+    pub approval_bps: u32,
+    pub for_votes: i128,
+    pub against_votes: i128,
+    pub abstain_votes: i128,
+    /// Earliest execution time once queued
+    pub eta: Option<u64>,
+    pub executed: bool,
+    pub canceled: bool,
+}
+
+/// How an account voted on a proposal
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VoteReceipt {
+    pub support: VoteType,
+    pub votes: i128,
+}
+
+/// Check if the contract is initialized
+pub fn is_initialized(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get::<DataKey, bool>(&DataKey::Initialized)
+        .unwrap_or(false)
+}
+
+/// Set initialized flag
+pub fn set_initialized(env: &Env) {
+    env.storage().instance().set(&DataKey::Initialized, &true);
+}
+
+/// Get the token deposited for voting power
+pub fn get_token(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get::<DataKey, Address>(&DataKey::Token)
+        .expect("Token not set")
+}
+
+/// Set the token deposited for voting power
+pub fn set_token(env: &Env, token: &Address) {
+    env.storage().instance().set(&DataKey::Token, token);
+}
+
+/// Get the voting and timelock parameters
+pub fn get_config(env: &Env) -> GovernanceConfig {
+    env.storage()
+        .instance()
+        .get::<DataKey, GovernanceConfig>(&DataKey::Config)
+        .expect("Config not set")
+}
+
+/// Set the voting and timelock parameters
+pub fn set_config(env: &Env, config: &GovernanceConfig) {
+    env.storage().instance().set(&DataKey::Config, config);
+}
+
+/// Get the number of proposals created
+pub fn get_proposal_count(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get::<DataKey, u64>(&DataKey::ProposalCount)
+        .unwrap_or(0)
+}
+
+/// Set the number of proposals created
+pub fn set_proposal_count(env: &Env, count: u64) {
+    env.storage()
+        .instance()
+        .set(&DataKey::ProposalCount, &count);
+}
+
+/// Get a proposal by ID
+pub fn get_proposal(env: &Env, id: u64) -> Option<Proposal> {
+    env.storage()
+        .persistent()
+        .get::<DataKey, Proposal>(&DataKey::Proposal(id))
+}
+
+/// Store a proposal
+pub fn set_proposal(env: &Env, proposal: &Proposal) {
+    let key = DataKey::Proposal(proposal.id);
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, proposal);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+/// Get how an account voted on a proposal
+pub fn get_receipt(env: &Env, id: u64, voter: &Address) -> Option<VoteReceipt> {
+    env.storage()
+        .persistent()
+        .get::<DataKey, VoteReceipt>(&DataKey::Receipt(id, voter.clone()))
+}
+
+/// Record how an account voted on a proposal
+pub fn set_receipt(env: &Env, id: u64, voter: &Address, receipt: &VoteReceipt) {
+    let key = DataKey::Receipt(id, voter.clone());
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, receipt);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+// ==================== Checkpoints ====================
+//
+// Each checkpoint is its own entry, indexed in ledger order, so recording one
+// costs the same however long the history grows.
+
+/// Key of an account's checkpoint count, or of the total's with `None`
+fn checkpoint_count_key(account: Option<&Address>) -> DataKey {
+    match account {
+        Some(account) => DataKey::CheckpointCount(account.clone()),
+        None => DataKey::TotalCheckpointCount,
+    }
+}
+
+/// Key of an account's checkpoint at `index`, or of the total's with `None`
+fn checkpoint_key(account: Option<&Address>, index: u32) -> DataKey {
+    match account {
+        Some(account) => DataKey::Checkpoint(account.clone(), index),
+        None => DataKey::TotalCheckpoint(index),
+    }
+}
+
+/// Get the number of checkpoints of an account, or of the total with `None`
+pub fn get_checkpoint_count(env: &Env, account: Option<&Address>) -> u32 {
+    env.storage()
+        .persistent()
+        .get::<DataKey, u32>(&checkpoint_count_key(account))
+        .unwrap_or(0)
+}
+
+/// Get the checkpoint of an account, or of the total with `None`, at `index`
+pub fn get_checkpoint(env: &Env, account: Option<&Address>, index: u32) -> Option<Checkpoint> {
+    env.storage()
+        .persistent()
+        .get::<DataKey, Checkpoint>(&checkpoint_key(account, index))
+}
+
+/// Record the voting power of an account, or of the total with `None`, from
+/// the current ledger on. Changes within one ledger share a checkpoint.
+pub fn push_checkpoint(env: &Env, account: Option<&Address>, votes: i128) {
+    let ledger = env.ledger().sequence();
+    let count = get_checkpoint_count(env, account);
+    let index = match count.checked_sub(1) {
+        Some(last) if get_checkpoint(env, account, last).is_some_and(|c| c.ledger == ledger) => {
+            last
+        }
+        _ => count,
+    };
+
+    let max_ttl = env.storage().max_ttl();
+    let key = checkpoint_key(account, index);
+    env.storage()
+        .persistent()
+        .set(&key, &Checkpoint { ledger, votes });
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+
+    let count_key = checkpoint_count_key(account);
+    env.storage().persistent().set(&count_key, &(index + 1));
+    env.storage()
+        .persistent()
+        .extend_ttl(&count_key, max_ttl - 1000, max_ttl);
+}
+
+/// Latest voting power of an account, or of the total with `None`
+pub fn latest_votes(env: &Env, account: Option<&Address>) -> i128 {
+    get_checkpoint_count(env, account)
+        .checked_sub(1)
+        .and_then(|last| get_checkpoint(env, account, last))
+        .map_or(0, |checkpoint| checkpoint.votes)
+}
+
+/// Voting power of an account, or of the total with `None`, as it stood
+/// before `ledger`
+pub fn votes_before(env: &Env, account: Option<&Address>, ledger: u32) -> i128 {
+    // First checkpoint at or after `ledger`; the one before it applies
+    let mut low = 0;
+    let mut high = get_checkpoint_count(env, account);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if get_checkpoint(env, account, mid).unwrap().ledger < ledger {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    if low == 0 {
+        0
+    } else {
+        get_checkpoint(env, account, low - 1).unwrap().votes
+    }
+}
+
+/// Extend TTL for instance storage
+pub fn extend_instance_ttl(env: &Env) {
+    let max_ttl = env.storage().max_ttl();
+    env.storage().instance().extend_ttl(max_ttl - 1000, max_ttl);
+}
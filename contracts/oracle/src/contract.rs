@@ -1,9 +1,56 @@
-use soroban_sdk::{contract, contractimpl, Address, Env, String};
+use astroswap_shared::{PairClient, BPS_DENOMINATOR, CANONICAL_PRICE_DECIMALS};
+use soroban_sdk::{contract, contractevent, contractimpl, token, Address, Env, String};
 
+use crate::dia::{self, MAX_DECIMALS};
 use crate::error::OracleError;
-use crate::storage::{DataKey, PriceData, MAX_STALENESS_THRESHOLD};
+use crate::lp;
+use crate::storage::{
+    DataKey, DiaConfig, KeeperBounty, PriceData, PriceSource, TwapRequirements, MAX_OBSERVATIONS,
+    MAX_STALENESS_THRESHOLD,
+};
 use crate::twap;
 
+/// Keeper rewarded event - emitted when a bounty is paid for a price update
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeeperRewarded {
+    #[topic]
+    pub updater: Address,
+    pub token: Address,
+    pub reward_token: Address,
+    pub amount: i128,
+}
+
+/// Price stored event - emitted for admin, keeper and DIA-sourced prices
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceUpdated {
+    #[topic]
+    pub token: Address,
+    pub price: i128,
+    pub decimals: u32,
+    pub source: String,
+    /// Time the price was observed, the DIA publication time for pulled prices
+    pub timestamp: u64,
+}
+
+/// Price feed registered event - emitted when a token's feed ID is set
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceFeedRegistered {
+    #[topic]
+    pub token: Address,
+    pub feed_id: String,
+}
+
+/// Staleness threshold changed event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StalenessThresholdUpdated {
+    pub old_threshold: u64,
+    pub new_threshold: u64,
+}
+
 /// AstroSwap Oracle Contract
 ///
 /// Provides price feeds for tokens with TWAP support
@@ -64,31 +111,117 @@ impl AstroSwapOracle {
         let admin = DataKey::get_admin(&env);
         admin.require_auth();
 
+        let timestamp = env.ledger().timestamp();
+        Self::store_price(&env, &token, price, decimals, source, timestamp)
+    }
+
+    /// Push a price as an allowlisted keeper
+    ///
+    /// Same as `update_price`, for updaters allowlisted by the admin and for
+    /// tokens with a configured feed. If a keeper bounty is configured and
+    /// funded, the update earns `reward_amount` when the previous price is at
+    /// least `min_interval` seconds old (or when there was none). Tokens
+    /// pulled from DIA are rejected with `PriceSourceMismatch`.
+    ///
+    /// # Returns
+    /// Reward paid to the updater (0 if none)
+    pub fn submit_price(
+        env: Env,
+        updater: Address,
+        token: Address,
+        price: i128,
+        decimals: u32,
+        source: String,
+    ) -> Result<i128, OracleError> {
+        updater.require_auth();
+
+        if !DataKey::is_updater(&env, &updater) {
+            return Err(OracleError::UpdaterNotAllowed);
+        }
+        if DataKey::get_feed_id(&env, &token).is_none() {
+            return Err(OracleError::PriceFeedNotFound);
+        }
+        if DataKey::get_price_source(&env, &token) != PriceSource::Push {
+            return Err(OracleError::PriceSourceMismatch);
+        }
+
+        let previous_timestamp = DataKey::get_price_data(&env, &token).map(|data| data.timestamp);
+        let now = env.ledger().timestamp();
+        Self::store_price(&env, &token, price, decimals, source, now)?;
+
+        let mut bounty = match DataKey::get_keeper_bounty(&env) {
+            Some(bounty) => bounty,
+            None => return Ok(0),
+        };
+        let fresh_enough = match previous_timestamp {
+            Some(timestamp) => now.saturating_sub(timestamp) >= bounty.min_interval,
+            None => true,
+        };
+        if !fresh_enough || bounty.balance < bounty.reward_amount {
+            return Ok(0);
+        }
+
+        bounty.balance -= bounty.reward_amount;
+        DataKey::set_keeper_bounty(&env, &bounty);
+        token::Client::new(&env, &bounty.reward_token).transfer(
+            &env.current_contract_address(),
+            &updater,
+            &bounty.reward_amount,
+        );
+
+        KeeperRewarded {
+            updater,
+            token,
+            reward_token: bounty.reward_token,
+            amount: bounty.reward_amount,
+        }
+        .publish(&env);
+
+        Ok(bounty.reward_amount)
+    }
+
+    /// Validate and store a price observed at `timestamp`, recording a TWAP
+    /// observation
+    fn store_price(
+        env: &Env,
+        token: &Address,
+        price: i128,
+        decimals: u32,
+        source: String,
+        timestamp: u64,
+    ) -> Result<(), OracleError> {
         // Validate price
         if price <= 0 {
             return Err(OracleError::InvalidPrice);
         }
 
         // Validate decimals (typically 8 for USD prices)
-        if decimals > 18 {
+        if decimals > MAX_DECIMALS {
             return Err(OracleError::InvalidDecimals);
         }
 
-        let timestamp = env.ledger().timestamp();
-
         // Create price data
         let price_data = PriceData {
             price,
             timestamp,
             decimals,
-            source,
+            source: source.clone(),
         };
 
         // Store price data
-        DataKey::set_price_data(&env, &token, &price_data);
+        DataKey::set_price_data(env, token, &price_data);
 
         // Add observation for TWAP
-        twap::add_observation(&env, &token, price)?;
+        twap::add_observation(env, token, price)?;
+
+        PriceUpdated {
+            token: token.clone(),
+            price,
+            decimals,
+            source,
+            timestamp,
+        }
+        .publish(env);
 
         Ok(())
     }
@@ -100,21 +233,94 @@ impl AstroSwapOracle {
     ///
     /// # Returns
     /// Price data if available and fresh
+    ///
+    /// A token pulled from DIA whose cached price is missing or stale is
+    /// refreshed from the DIA oracle first.
     pub fn get_price(env: Env, token: Address) -> Result<PriceData, OracleError> {
-        let price_data = DataKey::get_price_data(&env, &token)
-            .ok_or(OracleError::PriceFeedNotFound)?;
+        let cached = DataKey::get_price_data(&env, &token);
+        if DataKey::get_price_source(&env, &token) == PriceSource::Dia
+            && !cached
+                .as_ref()
+                .is_some_and(|data| Self::is_fresh(&env, data))
+        {
+            return Self::pull_dia_price(&env, &token);
+        }
 
-        // Check if price is fresh
-        let current_time = env.ledger().timestamp();
-        let staleness_threshold = DataKey::get_staleness_threshold(&env);
+        let price_data = cached.ok_or(OracleError::PriceFeedNotFound)?;
+        if !Self::is_fresh(&env, &price_data) {
+            return Err(OracleError::StalePrice);
+        }
+
+        Ok(price_data)
+    }
+
+    /// Get current price for a token rescaled to `target_decimals`
+    ///
+    /// Same as `get_price`, without the need to read and apply `decimals`.
+    /// Rounds down. Consumers should use `CANONICAL_PRICE_DECIMALS` unless
+    /// they need another scale.
+    ///
+    /// # Errors
+    /// * `InvalidDecimals` - `target_decimals` is above `MAX_DECIMALS`
+    /// * `Overflow` - The rescaled price does not fit in an `i128`
+    pub fn get_price_scaled(
+        env: Env,
+        token: Address,
+        target_decimals: u32,
+    ) -> Result<i128, OracleError> {
+        let price_data = Self::get_price(env, token)?;
+        dia::normalize(price_data.price, price_data.decimals, target_decimals)
+    }
+
+    /// Pull a token's latest price from DIA into the cache
+    ///
+    /// Permissionless: anyone may refresh a DIA-sourced token, e.g. ahead of
+    /// a TWAP window. Fails with `StalePrice` if DIA's own value is stale.
+    ///
+    /// # Returns
+    /// The cached price
+    pub fn refresh_price(env: Env, token: Address) -> Result<PriceData, OracleError> {
+        if DataKey::get_price_source(&env, &token) != PriceSource::Dia {
+            return Err(OracleError::PriceSourceMismatch);
+        }
+        Self::pull_dia_price(&env, &token)
+    }
 
-        if current_time.saturating_sub(price_data.timestamp) > staleness_threshold {
+    /// Fetch a price from DIA and cache it unless the cache is as recent
+    fn pull_dia_price(env: &Env, token: &Address) -> Result<PriceData, OracleError> {
+        let price_data = dia::fetch_price(env, token)?;
+        if price_data.timestamp > env.ledger().timestamp() {
+            return Err(OracleError::InvalidTimestamp);
+        }
+        if !Self::is_fresh(env, &price_data) {
             return Err(OracleError::StalePrice);
         }
 
+        if let Some(cached) = DataKey::get_price_data(env, token) {
+            if cached.timestamp >= price_data.timestamp {
+                return Ok(cached);
+            }
+        }
+        Self::store_price(
+            env,
+            token,
+            price_data.price,
+            price_data.decimals,
+            price_data.source.clone(),
+            price_data.timestamp,
+        )?;
+
         Ok(price_data)
     }
 
+    /// Whether a price is within the staleness threshold
+    fn is_fresh(env: &Env, price_data: &PriceData) -> bool {
+        let current_time = env.ledger().timestamp();
+        let staleness_threshold = DataKey::get_staleness_threshold(env);
+
+        current_time.saturating_sub(price_data.timestamp) <= staleness_threshold
+    }
+
     /// Get Time-Weighted Average Price (TWAP) for a token
     ///
     /// # Arguments
@@ -122,28 +328,52 @@ impl AstroSwapOracle {
     /// * `window` - Time window in seconds (e.g., 3600 for 1 hour)
     ///
     /// # Returns
-    /// TWAP price value
+    /// TWAP price value, or `InsufficientData` if the window has fewer
+    /// observations or less coverage than the `TwapRequirements`
     pub fn get_twap(env: Env, token: Address, window: u64) -> Result<i128, OracleError> {
         twap::calculate_twap(&env, &token, window)
     }
 
+    /// Get the fair price of one whole LP share of an AstroSwap pair
+    ///
+    /// Values the pair through its invariant at the oracle prices of both
+    /// tokens, `2 * sqrt(p0 * p1 * k) / total_supply`, so swapping against the
+    /// pair can't move it. In `CANONICAL_PRICE_DECIMALS`, rounded down.
+    ///
+    /// # Errors
+    /// * Any `get_price` error for either token
+    /// * `PriceNotAvailable` - The pair has no LP shares
+    /// * `Overflow` - The pool value does not fit in an `i128`
+    pub fn get_lp_price(env: Env, pair: Address) -> Result<i128, OracleError> {
+        let pair_client = PairClient::new(&env, &pair);
+        let (reserve_0, reserve_1) = pair_client.get_reserves();
+
+        let reserve_value = |token: Address, reserve: i128| {
+            let price =
+                Self::get_price_scaled(env.clone(), token.clone(), CANONICAL_PRICE_DECIMALS)?;
+            let decimals = token::Client::new(&env, &token).decimals();
+            lp::reserve_value(price, reserve, decimals)
+        };
+        let value_0 = reserve_value(pair_client.token_0(), reserve_0)?;
+        let value_1 = reserve_value(pair_client.token_1(), reserve_1)?;
+
+        let lp_decimals = token::Client::new(&env, &pair).decimals();
+        lp::fair_lp_price(value_0, value_1, pair_client.total_supply(), lp_decimals)
+    }
+
     /// Check if price is fresh (not stale)
     ///
     /// # Arguments
     /// * `token` - Token address
     ///
     /// # Returns
-    /// true if price is fresh, false otherwise
+    /// true if price is fresh, false otherwise (for DIA-sourced tokens, only
+    /// the cached price is checked)
     pub fn is_price_fresh(env: Env, token: Address) -> bool {
-        let price_data = match DataKey::get_price_data(&env, &token) {
-            Some(data) => data,
-            None => return false,
-        };
-
-        let current_time = env.ledger().timestamp();
-        let staleness_threshold = DataKey::get_staleness_threshold(&env);
-
-        current_time.saturating_sub(price_data.timestamp) <= staleness_threshold
+        match DataKey::get_price_data(&env, &token) {
+            Some(data) => Self::is_fresh(&env, &data),
+            None => false,
+        }
     }
 
     /// Set staleness threshold
@@ -160,11 +390,55 @@ impl AstroSwapOracle {
             return Err(OracleError::InvalidStalenessThreshold);
         }
 
+        let old_threshold = DataKey::get_staleness_threshold(&env);
         DataKey::set_staleness_threshold(&env, threshold);
 
+        StalenessThresholdUpdated {
+            old_threshold,
+            new_threshold: threshold,
+        }
+        .publish(&env);
+
         Ok(())
     }
 
+    /// Set the minimum data a TWAP must be computed from
+    ///
+    /// # Arguments
+    /// * `min_observations` - Observations the average must include (at least 1)
+    /// * `min_coverage_bps` - Share of the window the observations must span
+    pub fn set_twap_requirements(
+        env: Env,
+        min_observations: u32,
+        min_coverage_bps: u32,
+    ) -> Result<(), OracleError> {
+        // Only admin can update settings
+        let admin = DataKey::get_admin(&env);
+        admin.require_auth();
+
+        if min_observations == 0
+            || min_observations > MAX_OBSERVATIONS
+            || min_coverage_bps > BPS_DENOMINATOR
+        {
+            return Err(OracleError::InvalidTwapRequirements);
+        }
+
+        DataKey::set_twap_requirements(
+            &env,
+            &TwapRequirements {
+                min_observations,
+                min_coverage_bps,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Get the minimum data a TWAP must be computed from
+    pub fn get_twap_requirements(env: Env) -> TwapRequirements {
+        DataKey::get_twap_requirements(&env)
+    }
+
     /// Add or update price feed mapping for a token
     ///
     /// # Arguments
@@ -182,6 +456,8 @@ impl AstroSwapOracle {
 
         DataKey::set_feed_id(&env, &token, &feed_id);
 
+        PriceFeedRegistered { token, feed_id }.publish(&env);
+
         Ok(())
     }
 
@@ -196,6 +472,86 @@ impl AstroSwapOracle {
         DataKey::get_feed_id(&env, &token).ok_or(OracleError::PriceFeedNotFound)
     }
 
+    // ==================== DIA Adapter ====================
+
+    /// Configure the DIA oracle contract prices are pulled from
+    ///
+    /// # Arguments
+    /// * `contract` - DIA oracle contract address
+    /// * `key_suffix` - Appended to feed IDs to form DIA keys (e.g. "/USD"
+    ///   for feed ID "XLM"; empty if feed IDs are full DIA keys)
+    /// * `source_decimals` - Decimals of DIA values (8 on Stellar)
+    /// * `decimals` - Decimals pulled prices are stored with
+    pub fn set_dia_config(
+        env: Env,
+        contract: Address,
+        key_suffix: String,
+        source_decimals: u32,
+        decimals: u32,
+    ) -> Result<(), OracleError> {
+        // Only admin can configure the adapter
+        let admin = DataKey::get_admin(&env);
+        admin.require_auth();
+
+        if source_decimals.max(decimals) > MAX_DECIMALS {
+            return Err(OracleError::InvalidDecimals);
+        }
+
+        DataKey::set_dia_config(
+            &env,
+            &DiaConfig {
+                contract,
+                key_suffix,
+                source_decimals,
+                decimals,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Get the DIA adapter configuration
+    pub fn get_dia_config(env: Env) -> Option<DiaConfig> {
+        DataKey::get_dia_config(&env)
+    }
+
+    /// Select where a token's price comes from
+    ///
+    /// Pulling from DIA requires the adapter to be configured and the token
+    /// to have a feed ID. The admin can still override a DIA-sourced price
+    /// with `update_price`.
+    ///
+    /// # Arguments
+    /// * `token` - Token address
+    /// * `source` - Price source
+    pub fn set_price_source(
+        env: Env,
+        token: Address,
+        source: PriceSource,
+    ) -> Result<(), OracleError> {
+        // Only admin can select price sources
+        let admin = DataKey::get_admin(&env);
+        admin.require_auth();
+
+        if source == PriceSource::Dia {
+            if DataKey::get_dia_config(&env).is_none() {
+                return Err(OracleError::DiaNotConfigured);
+            }
+            if DataKey::get_feed_id(&env, &token).is_none() {
+                return Err(OracleError::PriceFeedNotFound);
+            }
+        }
+
+        DataKey::set_price_source(&env, &token, source);
+
+        Ok(())
+    }
+
+    /// Get where a token's price comes from
+    pub fn get_price_source(env: Env, token: Address) -> PriceSource {
+        DataKey::get_price_source(&env, &token)
+    }
+
     /// Get admin address
     pub fn get_admin(env: Env) -> Address {
         DataKey::get_admin(&env)
@@ -220,12 +576,201 @@ impl AstroSwapOracle {
 
         Ok(())
     }
+
+    // ==================== Keeper Incentives ====================
+
+    /// Add or remove an updater allowed to call `submit_price`
+    ///
+    /// # Arguments
+    /// * `updater` - Keeper address
+    /// * `allowed` - Whether the keeper is allowlisted
+    pub fn set_updater(env: Env, updater: Address, allowed: bool) -> Result<(), OracleError> {
+        // Only admin can manage updaters
+        let admin = DataKey::get_admin(&env);
+        admin.require_auth();
+
+        DataKey::set_updater(&env, &updater, allowed);
+
+        Ok(())
+    }
+
+    /// Check if an address is an allowlisted updater
+    pub fn is_updater(env: Env, updater: Address) -> bool {
+        DataKey::is_updater(&env, &updater)
+    }
+
+    /// Configure the keeper bounty
+    ///
+    /// The funded balance is kept when the bounty is reconfigured; the reward
+    /// token can only change once the balance has been withdrawn.
+    ///
+    /// # Arguments
+    /// * `reward_token` - Token the reward is paid in
+    /// * `reward_amount` - Reward per accepted update
+    /// * `min_interval` - Minimum age in seconds of the previous price for an
+    ///   update to earn the reward
+    pub fn set_keeper_bounty(
+        env: Env,
+        reward_token: Address,
+        reward_amount: i128,
+        min_interval: u64,
+    ) -> Result<(), OracleError> {
+        // Only admin can configure the bounty
+        let admin = DataKey::get_admin(&env);
+        admin.require_auth();
+
+        if reward_amount <= 0 || min_interval == 0 {
+            return Err(OracleError::InvalidBounty);
+        }
+
+        let balance = match DataKey::get_keeper_bounty(&env) {
+            Some(bounty) if bounty.balance > 0 && bounty.reward_token != reward_token => {
+                return Err(OracleError::InvalidBounty);
+            }
+            Some(bounty) => bounty.balance,
+            None => 0,
+        };
+
+        DataKey::set_keeper_bounty(
+            &env,
+            &KeeperBounty {
+                reward_token,
+                reward_amount,
+                min_interval,
+                balance,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Fund the keeper bounty from the admin's balance
+    ///
+    /// # Arguments
+    /// * `amount` - Amount of the reward token to add to the budget
+    pub fn fund_keeper_bounty(env: Env, amount: i128) -> Result<(), OracleError> {
+        let admin = DataKey::get_admin(&env);
+        admin.require_auth();
+
+        if amount <= 0 {
+            return Err(OracleError::InvalidBounty);
+        }
+        let mut bounty =
+            DataKey::get_keeper_bounty(&env).ok_or(OracleError::BountyNotConfigured)?;
+
+        token::Client::new(&env, &bounty.reward_token).transfer(
+            &admin,
+            &env.current_contract_address(),
+            &amount,
+        );
+        bounty.balance = bounty
+            .balance
+            .checked_add(amount)
+            .ok_or(OracleError::Overflow)?;
+        DataKey::set_keeper_bounty(&env, &bounty);
+
+        Ok(())
+    }
+
+    /// Withdraw unspent keeper bounty funds to the admin
+    ///
+    /// # Arguments
+    /// * `amount` - Amount of the reward token to withdraw
+    pub fn withdraw_keeper_bounty(env: Env, amount: i128) -> Result<(), OracleError> {
+        let admin = DataKey::get_admin(&env);
+        admin.require_auth();
+
+        if amount <= 0 {
+            return Err(OracleError::InvalidBounty);
+        }
+        let mut bounty =
+            DataKey::get_keeper_bounty(&env).ok_or(OracleError::BountyNotConfigured)?;
+        if amount > bounty.balance {
+            return Err(OracleError::InsufficientBountyBalance);
+        }
+
+        bounty.balance -= amount;
+        DataKey::set_keeper_bounty(&env, &bounty);
+        token::Client::new(&env, &bounty.reward_token).transfer(
+            &env.current_contract_address(),
+            &admin,
+            &amount,
+        );
+
+        Ok(())
+    }
+
+    /// Get the keeper bounty configuration and remaining budget
+    pub fn get_keeper_bounty(env: Env) -> Option<KeeperBounty> {
+        DataKey::get_keeper_bounty(&env)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use soroban_sdk::{testutils::{Address as _, Ledger}, Address, Env};
+    use astroswap_shared::CANONICAL_PRICE_DECIMALS;
+    use soroban_sdk::{
+        testutils::{Address as _, Events, Ledger},
+        Address, Env, Event,
+    };
+
+    /// Stand-in for the DIA oracle contract
+    #[contract]
+    pub struct MockDia;
+
+    #[contractimpl]
+    impl MockDia {
+        pub fn set_value(env: Env, key: String, value: u128, timestamp: u64) {
+            env.storage().instance().set(&key, &(value, timestamp));
+        }
+
+        pub fn get_value(env: Env, key: String) -> (u128, u64) {
+            env.storage().instance().get(&key).unwrap()
+        }
+    }
+
+    /// Stand-in for an AstroSwap pair, with 7-decimal LP shares
+    #[contract]
+    pub struct MockPair;
+
+    #[contractimpl]
+    impl MockPair {
+        pub fn set_state(
+            env: Env,
+            tokens: (Address, Address),
+            reserves: (i128, i128),
+            supply: i128,
+        ) {
+            env.storage()
+                .instance()
+                .set(&0u32, &(tokens, reserves, supply));
+        }
+
+        pub fn token_0(env: Env) -> Address {
+            Self::state(&env).0 .0
+        }
+
+        pub fn token_1(env: Env) -> Address {
+            Self::state(&env).0 .1
+        }
+
+        pub fn get_reserves(env: Env) -> (i128, i128) {
+            Self::state(&env).1
+        }
+
+        pub fn total_supply(env: Env) -> i128 {
+            Self::state(&env).2
+        }
+
+        pub fn decimals(_env: Env) -> u32 {
+            7
+        }
+
+        fn state(env: &Env) -> ((Address, Address), (i128, i128), i128) {
+            env.storage().instance().get(&0u32).unwrap()
+        }
+    }
 
     #[test]
     fn test_initialize() {
@@ -272,6 +817,39 @@ mod tests {
         assert_eq!(price_data.decimals, 6);
     }
 
+    #[test]
+    fn test_get_price_scaled() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(AstroSwapOracle, ());
+        let client = AstroSwapOracleClient::new(&env, &contract_id);
+        let token = Address::generate(&env);
+        client.initialize(&Address::generate(&env), &3600);
+
+        // $123.456789 with 6 decimals
+        client.update_price(&token, &123_456_789, &6, &String::from_str(&env, "DIA"));
+        assert_eq!(client.get_price_scaled(&token, &6), 123_456_789);
+        assert_eq!(client.get_price_scaled(&token, &2), 12_345);
+        assert_eq!(
+            client.get_price_scaled(&token, &18),
+            123_456_789_000_000_000_000
+        );
+        assert_eq!(
+            client.get_price_scaled(&token, &CANONICAL_PRICE_DECIMALS),
+            1_234_567_890
+        );
+
+        assert_eq!(
+            client.try_get_price_scaled(&token, &19),
+            Err(Ok(OracleError::InvalidDecimals))
+        );
+        assert_eq!(
+            client.try_get_price_scaled(&Address::generate(&env), &7),
+            Err(Ok(OracleError::PriceFeedNotFound))
+        );
+    }
+
     #[test]
     fn test_stale_price() {
         let env = Env::default();
@@ -378,6 +956,59 @@ mod tests {
         assert_eq!(threshold, 7200);
     }
 
+    #[test]
+    fn test_events() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(AstroSwapOracle, ());
+        let client = AstroSwapOracleClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let token = Address::generate(&env);
+        let source = String::from_str(&env, "Manual");
+
+        client.initialize(&admin, &3600);
+
+        // Topics ["price_updated", token], data [price, decimals, source, timestamp]
+        env.ledger().set_timestamp(1_000);
+        client.update_price(&token, &100_000_000, &6, &source);
+        assert_eq!(
+            env.events().all().filter_by_contract(&contract_id),
+            [PriceUpdated {
+                token: token.clone(),
+                price: 100_000_000,
+                decimals: 6,
+                source,
+                timestamp: 1_000,
+            }
+            .to_xdr(&env, &contract_id)]
+        );
+
+        // Topics ["price_feed_registered", token], data feed_id
+        let feed_id = String::from_str(&env, "XLM/USD");
+        client.add_price_feed(&token, &feed_id);
+        assert_eq!(
+            env.events().all().filter_by_contract(&contract_id),
+            [PriceFeedRegistered {
+                token: token.clone(),
+                feed_id,
+            }
+            .to_xdr(&env, &contract_id)]
+        );
+
+        // Topics ["staleness_threshold_updated"], data [old, new]
+        client.set_staleness_threshold(&7200);
+        assert_eq!(
+            env.events().all().filter_by_contract(&contract_id),
+            [StalenessThresholdUpdated {
+                old_threshold: 3600,
+                new_threshold: 7200,
+            }
+            .to_xdr(&env, &contract_id)]
+        );
+    }
+
     #[test]
     fn test_twap() {
         let env = Env::default();
@@ -407,6 +1038,85 @@ mod tests {
         assert!((100_000_000..=110_000_000).contains(&twap));
     }
 
+    #[test]
+    fn test_twap_requirements() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(AstroSwapOracle, ());
+        let client = AstroSwapOracleClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let token = Address::generate(&env);
+        let source = String::from_str(&env, "DIA");
+
+        client.initialize(&admin, &3600);
+        assert_eq!(
+            client.get_twap_requirements(),
+            TwapRequirements {
+                min_observations: 2,
+                min_coverage_bps: 5_000,
+            }
+        );
+
+        client.update_price(&token, &100_000_000, &6, &source);
+        env.ledger().set_timestamp(1000);
+        client.update_price(&token, &110_000_000, &6, &source);
+
+        // A single observation in the window is not an average
+        let result = client.try_get_twap(&token, &600);
+        assert_eq!(result, Err(Ok(OracleError::InsufficientData)));
+
+        client.set_twap_requirements(&1, &5_000);
+        assert_eq!(client.get_twap(&token, &600), 110_000_000);
+
+        // Observations span 500s of the last 1000s
+        env.ledger().set_timestamp(1500);
+        assert_eq!(client.get_twap(&token, &1000), 110_000_000);
+        client.set_twap_requirements(&1, &6_000);
+        let result = client.try_get_twap(&token, &1000);
+        assert_eq!(result, Err(Ok(OracleError::InsufficientData)));
+
+        let result = client.try_set_twap_requirements(&0, &5_000);
+        assert_eq!(result, Err(Ok(OracleError::InvalidTwapRequirements)));
+        let result = client.try_set_twap_requirements(&1, &10_001);
+        assert_eq!(result, Err(Ok(OracleError::InvalidTwapRequirements)));
+    }
+
+    #[test]
+    fn test_twap_after_observation_buffer_wraps() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(AstroSwapOracle, ());
+        let client = AstroSwapOracleClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let token = Address::generate(&env);
+        let source = String::from_str(&env, "DIA");
+
+        client.initialize(&admin, &3600);
+
+        // A full buffer at 1.0, then half of it overwritten at 2.0, a minute apart
+        let updates = MAX_OBSERVATIONS + MAX_OBSERVATIONS / 2;
+        for i in 0..updates {
+            env.ledger().set_timestamp(u64::from(i) * 60);
+            let price = if i < MAX_OBSERVATIONS {
+                100_000_000
+            } else {
+                200_000_000
+            };
+            client.update_price(&token, &price, &6, &source);
+        }
+
+        // The last 20 minutes are all at 2.0
+        assert_eq!(client.get_twap(&token, &1200), 200_000_000);
+
+        // The last 100 minutes are half at each price
+        let twap = client.get_twap(&token, &6000);
+        assert!((149_000_000..=151_000_000).contains(&twap));
+    }
+
     #[test]
     fn test_admin_change() {
         let env = Env::default();
@@ -426,4 +1136,186 @@ mod tests {
         let current_admin = client.get_admin();
         assert_eq!(current_admin, new_admin);
     }
+
+    #[test]
+    fn test_keeper_bounty() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(AstroSwapOracle, ());
+        let client = AstroSwapOracleClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let keeper = Address::generate(&env);
+        let token = Address::generate(&env);
+        let reward_token = env
+            .register_stellar_asset_contract_v2(admin.clone())
+            .address();
+        let reward_client = token::Client::new(&env, &reward_token);
+        token::StellarAssetClient::new(&env, &reward_token).mint(&admin, &1_000);
+        let source = String::from_str(&env, "DIA");
+
+        client.initialize(&admin, &3600);
+        client.add_price_feed(&token, &String::from_str(&env, "XLM/USD"));
+
+        // Only allowlisted updaters may push prices
+        let result = client.try_submit_price(&keeper, &token, &100_000_000, &6, &source);
+        assert_eq!(result, Err(Ok(OracleError::UpdaterNotAllowed)));
+        client.set_updater(&keeper, &true);
+        assert!(client.is_updater(&keeper));
+
+        // Accepted without a bounty, nothing paid
+        assert_eq!(
+            client.submit_price(&keeper, &token, &100_000_000, &6, &source),
+            0
+        );
+        assert_eq!(client.get_price(&token).price, 100_000_000);
+
+        let result = client.try_fund_keeper_bounty(&500);
+        assert_eq!(result, Err(Ok(OracleError::BountyNotConfigured)));
+        let result = client.try_set_keeper_bounty(&reward_token, &0, &600);
+        assert_eq!(result, Err(Ok(OracleError::InvalidBounty)));
+        client.set_keeper_bounty(&reward_token, &100, &600);
+        client.fund_keeper_bounty(&150);
+        assert_eq!(reward_client.balance(&contract_id), 150);
+
+        // Too soon after the previous update: accepted, not rewarded
+        env.ledger().set_timestamp(300);
+        assert_eq!(
+            client.submit_price(&keeper, &token, &101_000_000, &6, &source),
+            0
+        );
+
+        env.ledger().set_timestamp(900);
+        assert_eq!(
+            client.submit_price(&keeper, &token, &102_000_000, &6, &source),
+            100
+        );
+        assert_eq!(reward_client.balance(&keeper), 100);
+        assert_eq!(client.get_keeper_bounty().unwrap().balance, 50);
+
+        // Budget below one reward: accepted, not rewarded
+        env.ledger().set_timestamp(1_500);
+        assert_eq!(
+            client.submit_price(&keeper, &token, &103_000_000, &6, &source),
+            0
+        );
+        assert_eq!(client.get_price(&token).price, 103_000_000);
+
+        // Unspent funds go back to the admin
+        let result = client.try_withdraw_keeper_bounty(&51);
+        assert_eq!(result, Err(Ok(OracleError::InsufficientBountyBalance)));
+        client.withdraw_keeper_bounty(&50);
+        assert_eq!(reward_client.balance(&admin), 900);
+
+        // Removed updaters are rejected again
+        client.set_updater(&keeper, &false);
+        let result = client.try_submit_price(&keeper, &token, &103_000_000, &6, &source);
+        assert_eq!(result, Err(Ok(OracleError::UpdaterNotAllowed)));
+    }
+
+    #[test]
+    fn test_dia_adapter() {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(10_000);
+
+        let contract_id = env.register(AstroSwapOracle, ());
+        let client = AstroSwapOracleClient::new(&env, &contract_id);
+        let dia_id = env.register(MockDia, ());
+        let dia = MockDiaClient::new(&env, &dia_id);
+
+        let admin = Address::generate(&env);
+        let token = Address::generate(&env);
+        let key = String::from_str(&env, "XLM/USD");
+
+        client.initialize(&admin, &3600);
+        client.add_price_feed(&token, &String::from_str(&env, "XLM"));
+        assert_eq!(client.get_price_source(&token), PriceSource::Push);
+
+        let result = client.try_set_price_source(&token, &PriceSource::Dia);
+        assert_eq!(result, Err(Ok(OracleError::DiaNotConfigured)));
+        let result = client.try_set_dia_config(&dia_id, &String::from_str(&env, "/USD"), &8, &19);
+        assert_eq!(result, Err(Ok(OracleError::InvalidDecimals)));
+        client.set_dia_config(&dia_id, &String::from_str(&env, "/USD"), &8, &7);
+        let result = client.try_set_price_source(&Address::generate(&env), &PriceSource::Dia);
+        assert_eq!(result, Err(Ok(OracleError::PriceFeedNotFound)));
+        client.set_price_source(&token, &PriceSource::Dia);
+
+        // Nothing published by DIA yet
+        let result = client.try_get_price(&token);
+        assert_eq!(result, Err(Ok(OracleError::PriceNotAvailable)));
+
+        // Pulled on read, rescaled from 8 to 7 decimals with DIA's timestamp
+        dia.set_value(&key, &12_345_678, &9_900);
+        let price_data = client.get_price(&token);
+        assert_eq!(price_data.price, 1_234_567);
+        assert_eq!(price_data.decimals, 7);
+        assert_eq!(price_data.timestamp, 9_900);
+        assert_eq!(price_data.source, String::from_str(&env, "DIA"));
+
+        // Served from the cache while fresh
+        dia.set_value(&key, &20_000_000, &9_950);
+        assert_eq!(client.get_price(&token).price, 1_234_567);
+        assert_eq!(client.refresh_price(&token).price, 2_000_000);
+        assert_eq!(client.get_price(&token).price, 2_000_000);
+
+        // A stale DIA value is not cached
+        env.ledger().set_timestamp(20_000);
+        assert!(!client.is_price_fresh(&token));
+        let result = client.try_get_price(&token);
+        assert_eq!(result, Err(Ok(OracleError::StalePrice)));
+        dia.set_value(&key, &21_000_000, &19_990);
+        assert_eq!(client.get_price(&token).price, 2_100_000);
+        assert!(client.is_price_fresh(&token));
+
+        // Keepers cannot push pulled prices; pushed tokens cannot be pulled
+        let keeper = Address::generate(&env);
+        client.set_updater(&keeper, &true);
+        let result =
+            client.try_submit_price(&keeper, &token, &1, &7, &String::from_str(&env, "DIA"));
+        assert_eq!(result, Err(Ok(OracleError::PriceSourceMismatch)));
+        client.set_price_source(&token, &PriceSource::Push);
+        let result = client.try_refresh_price(&token);
+        assert_eq!(result, Err(Ok(OracleError::PriceSourceMismatch)));
+    }
+
+    #[test]
+    fn test_get_lp_price() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(AstroSwapOracle, ());
+        let client = AstroSwapOracleClient::new(&env, &contract_id);
+        client.initialize(&Address::generate(&env), &3600);
+
+        let admin = Address::generate(&env);
+        let token_0 = env
+            .register_stellar_asset_contract_v2(admin.clone())
+            .address();
+        let token_1 = env.register_stellar_asset_contract_v2(admin).address();
+        let pair_id = env.register(MockPair, ());
+        let pair = MockPairClient::new(&env, &pair_id);
+        let tokens = (token_0.clone(), token_1.clone());
+
+        // 1,000 tokens at $2 against 4,000 at $0.5, 2,000 LP shares
+        pair.set_state(&tokens, &(1_000_0000000, 4_000_0000000), &2_000_0000000);
+        let result = client.try_get_lp_price(&pair_id);
+        assert_eq!(result, Err(Ok(OracleError::PriceFeedNotFound)));
+        client.update_price(&token_0, &2_000_000, &6, &String::from_str(&env, "DIA"));
+        client.update_price(&token_1, &50_000_000, &8, &String::from_str(&env, "DIA"));
+        assert_eq!(client.get_lp_price(&pair_id), 20_000_000);
+
+        // Skewing the reserves at the same k leaves the price unchanged
+        pair.set_state(&tokens, &(500_0000000, 8_000_0000000), &2_000_0000000);
+        assert_eq!(client.get_lp_price(&pair_id), 20_000_000);
+
+        // Needs fresh prices for both tokens and some LP shares
+        pair.set_state(&tokens, &(0, 0), &0);
+        let result = client.try_get_lp_price(&pair_id);
+        assert_eq!(result, Err(Ok(OracleError::PriceNotAvailable)));
+        env.ledger().set_timestamp(7200);
+        let result = client.try_get_lp_price(&pair_id);
+        assert_eq!(result, Err(Ok(OracleError::StalePrice)));
+    }
 }
@@ -0,0 +1,116 @@
+//! DIA oracle adapter
+//!
+//! Reads prices straight from the DIA oracle contract deployed on Stellar,
+//! whose `get_value(key)` returns the latest `(value, timestamp)` for a key
+//! such as "XLM/USD". A token's key is its feed ID followed by the configured
+//! suffix, and values are rescaled from DIA's decimals to the adapter's.
+
+use soroban_sdk::{Address, Env, IntoVal, String, Symbol, Vec};
+
+use crate::error::OracleError;
+use crate::storage::{DataKey, DiaConfig, PriceData};
+
+/// Source recorded on prices pulled from DIA
+pub const DIA_SOURCE: &str = "DIA";
+
+/// Longest DIA key the adapter builds
+const MAX_KEY_LENGTH: usize = 64;
+
+/// Largest decimals a price can be rescaled from or to
+pub const MAX_DECIMALS: u32 = 18;
+
+/// Fetch the DIA price of a token, normalized to the adapter's decimals
+///
+/// The returned price carries DIA's timestamp, not the ledger's, so the
+/// usual staleness check measures the age of the upstream value.
+pub fn fetch_price(env: &Env, token: &Address) -> Result<PriceData, OracleError> {
+    let config = DataKey::get_dia_config(env).ok_or(OracleError::DiaNotConfigured)?;
+    let feed_id = DataKey::get_feed_id(env, token).ok_or(OracleError::PriceFeedNotFound)?;
+    let key = dia_key(env, &feed_id, &config.key_suffix)?;
+
+    let (value, timestamp) = get_value(env, &config, &key)?;
+    let value = i128::try_from(value).map_err(|_| OracleError::InvalidPrice)?;
+
+    Ok(PriceData {
+        price: normalize(value, config.source_decimals, config.decimals)?,
+        timestamp,
+        decimals: config.decimals,
+        source: String::from_str(env, DIA_SOURCE),
+    })
+}
+
+/// Rescale `price` from `from_decimals` to `to_decimals`, rounding down
+pub fn normalize(price: i128, from_decimals: u32, to_decimals: u32) -> Result<i128, OracleError> {
+    if from_decimals.max(to_decimals) > MAX_DECIMALS {
+        return Err(OracleError::InvalidDecimals);
+    }
+    if from_decimals >= to_decimals {
+        Ok(price / 10i128.pow(from_decimals - to_decimals))
+    } else {
+        price
+            .checked_mul(10i128.pow(to_decimals - from_decimals))
+            .ok_or(OracleError::Overflow)
+    }
+}
+
+/// DIA key of a feed: the feed ID followed by `key_suffix`
+fn dia_key(env: &Env, feed_id: &String, key_suffix: &String) -> Result<String, OracleError> {
+    let (feed_len, suffix_len) = (feed_id.len() as usize, key_suffix.len() as usize);
+    let key_len = feed_len + suffix_len;
+    if key_len > MAX_KEY_LENGTH {
+        return Err(OracleError::InvalidFeedId);
+    }
+
+    let mut buffer = [0u8; MAX_KEY_LENGTH];
+    feed_id.copy_into_slice(&mut buffer[..feed_len]);
+    key_suffix.copy_into_slice(&mut buffer[feed_len..key_len]);
+    Ok(String::from_bytes(env, &buffer[..key_len]))
+}
+
+/// Read the latest value for `key`, or `PriceNotAvailable` if DIA has none
+fn get_value(env: &Env, config: &DiaConfig, key: &String) -> Result<(u128, u64), OracleError> {
+    match env.try_invoke_contract::<(u128, u64), soroban_sdk::Error>(
+        &config.contract,
+        &Symbol::new(env, "get_value"),
+        Vec::from_array(env, [key.into_val(env)]),
+    ) {
+        Ok(Ok(value)) => Ok(value),
+        _ => Err(OracleError::PriceNotAvailable),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        // DIA's 8 decimals down to 7 and up to 18
+        assert_eq!(normalize(12_345_678, 8, 7), Ok(1_234_567));
+        assert_eq!(normalize(12_345_678, 8, 8), Ok(12_345_678));
+        assert_eq!(normalize(12_345_678, 8, 18), Ok(123_456_780_000_000_000));
+        assert_eq!(normalize(1, 8, 19), Err(OracleError::InvalidDecimals));
+        assert_eq!(normalize(i128::MAX, 0, 18), Err(OracleError::Overflow));
+    }
+
+    #[test]
+    fn test_dia_key() {
+        let env = Env::default();
+        let feed_id = String::from_str(&env, "XLM");
+
+        assert_eq!(
+            dia_key(&env, &feed_id, &String::from_str(&env, "/USD")),
+            Ok(String::from_str(&env, "XLM/USD"))
+        );
+        assert_eq!(
+            dia_key(&env, &feed_id, &String::from_str(&env, "")),
+            Ok(feed_id.clone())
+        );
+
+        let long_suffix = String::from_bytes(&env, &[b'A'; MAX_KEY_LENGTH]);
+        assert_eq!(
+            dia_key(&env, &feed_id, &long_suffix),
+            Err(OracleError::InvalidFeedId)
+        );
+    }
+}
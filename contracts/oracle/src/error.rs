@@ -1,6 +1,7 @@
+use astroswap_shared::AstroSwapError;
 use soroban_sdk::contracterror;
 
-/// Error codes for Oracle contract
+/// Error codes for Oracle contract (800-899 in the shared error registry)
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
@@ -11,6 +12,7 @@ pub enum OracleError {
 
     // Authorization errors (820-829)
     Unauthorized = 820,
+    UpdaterNotAllowed = 821,
 
     // Price feed errors (830-849)
     PriceFeedNotFound = 830,
@@ -24,13 +26,55 @@ pub enum OracleError {
     InvalidWindow = 851,
     WindowTooLarge = 852,
     ObservationTooOld = 853,
+    InsufficientData = 854,
 
     // Configuration errors (870-889)
     InvalidStalenessThreshold = 870,
     InvalidDecimals = 871,
     InvalidFeedId = 872,
+    InvalidBounty = 873,
+    BountyNotConfigured = 874,
+    InsufficientBountyBalance = 875,
+    DiaNotConfigured = 876,
+    PriceSourceMismatch = 877,
+    InvalidTwapRequirements = 878,
 
     // Math errors (890-899)
     Overflow = 890,
     DivisionByZero = 891,
 }
+
+/// Convert OracleError for contracts consuming oracle prices through AstroSwapError
+impl From<OracleError> for AstroSwapError {
+    fn from(err: OracleError) -> Self {
+        match err {
+            OracleError::AlreadyInitialized => AstroSwapError::AlreadyInitialized,
+            OracleError::NotInitialized => AstroSwapError::NotInitialized,
+            OracleError::Unauthorized | OracleError::UpdaterNotAllowed => {
+                AstroSwapError::Unauthorized
+            }
+            // A price that can't be trusted makes any quote built on it stale
+            OracleError::PriceFeedNotFound
+            | OracleError::StalePrice
+            | OracleError::PriceNotAvailable
+            | OracleError::InsufficientObservations
+            | OracleError::ObservationTooOld
+            | OracleError::InsufficientData => AstroSwapError::QuoteStale,
+            OracleError::InvalidPrice
+            | OracleError::InvalidTimestamp
+            | OracleError::InvalidWindow
+            | OracleError::WindowTooLarge
+            | OracleError::InvalidStalenessThreshold
+            | OracleError::InvalidDecimals
+            | OracleError::InvalidFeedId
+            | OracleError::InvalidBounty
+            | OracleError::BountyNotConfigured
+            | OracleError::DiaNotConfigured
+            | OracleError::PriceSourceMismatch
+            | OracleError::InvalidTwapRequirements => AstroSwapError::InvalidArgument,
+            OracleError::InsufficientBountyBalance => AstroSwapError::InsufficientBalance,
+            OracleError::Overflow => AstroSwapError::Overflow,
+            OracleError::DivisionByZero => AstroSwapError::DivisionByZero,
+        }
+    }
+}
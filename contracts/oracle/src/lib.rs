@@ -1,9 +1,12 @@
 #![no_std]
 
 mod contract;
+mod dia;
 mod error;
+mod lp;
 mod storage;
 mod twap;
 
-pub use contract::AstroSwapOracle;
+pub use contract::{AstroSwapOracle, AstroSwapOracleClient};
 pub use error::OracleError;
+pub use storage::{DiaConfig, KeeperBounty, PriceSource, TwapRequirements};
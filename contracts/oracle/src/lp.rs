@@ -0,0 +1,98 @@
+//! Fair LP token pricing
+//!
+//! An LP share is valued from the oracle prices of the pair's tokens and the
+//! pair's invariant, `2 * sqrt(p0 * p1 * k) / total_supply`, instead of its
+//! reserves. Swapping against the pair moves the reserves but not `k`, so the
+//! price can't be inflated by skewing the pool within a transaction.
+
+use astroswap_shared::{mul_div_down, sqrt, AstroSwapError};
+
+use crate::dia::MAX_DECIMALS;
+use crate::error::OracleError;
+
+/// Value of `reserve` raw units of a token with `decimals` at `price`
+/// per whole token, in the price's decimals - rounds DOWN
+pub fn reserve_value(price: i128, reserve: i128, decimals: u32) -> Result<i128, OracleError> {
+    if decimals > MAX_DECIMALS {
+        return Err(OracleError::InvalidDecimals);
+    }
+    mul_div_down(price, reserve, 10i128.pow(decimals)).map_err(math_error)
+}
+
+/// Price of one whole LP share with `lp_decimals`, from the values of both
+/// reserves - rounds DOWN
+///
+/// `value_0 * value_1` is `p0 * p1 * k` in value units, so the pool is worth
+/// twice its square root whatever the split between the reserves.
+pub fn fair_lp_price(
+    value_0: i128,
+    value_1: i128,
+    total_supply: i128,
+    lp_decimals: u32,
+) -> Result<i128, OracleError> {
+    if total_supply <= 0 {
+        return Err(OracleError::PriceNotAvailable);
+    }
+    if lp_decimals > MAX_DECIMALS {
+        return Err(OracleError::InvalidDecimals);
+    }
+    let product = value_0.checked_mul(value_1).ok_or(OracleError::Overflow)?;
+    let pool_value = sqrt(product).checked_mul(2).ok_or(OracleError::Overflow)?;
+    mul_div_down(pool_value, 10i128.pow(lp_decimals), total_supply).map_err(math_error)
+}
+
+fn math_error(err: AstroSwapError) -> OracleError {
+    match err {
+        AstroSwapError::DivisionByZero => OracleError::DivisionByZero,
+        _ => OracleError::Overflow,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fair_lp_price_ignores_reserve_split() {
+        // 1,000 tokens at $2 against 4,000 at $0.5, 2,000 LP shares: $2 each
+        let value_0 = reserve_value(20_000_000, 1_000_0000000, 7).unwrap();
+        let value_1 = reserve_value(5_000_000, 4_000_0000000, 7).unwrap();
+        assert_eq!(value_0, 2_000_0000000);
+        assert_eq!(
+            fair_lp_price(value_0, value_1, 2_000_0000000, 7),
+            Ok(20_000_000)
+        );
+
+        // Skewed to 500 / 8,000 by a swap, same k: spot value is $5,000
+        let value_0 = reserve_value(20_000_000, 500_0000000, 7).unwrap();
+        let value_1 = reserve_value(5_000_000, 8_000_0000000, 7).unwrap();
+        assert_eq!(value_0 + value_1, 5_000_0000000);
+        assert_eq!(
+            fair_lp_price(value_0, value_1, 2_000_0000000, 7),
+            Ok(20_000_000)
+        );
+
+        // An 18-decimal reserve is scaled by its own decimals
+        assert_eq!(
+            reserve_value(20_000_000, 1_000 * 10i128.pow(18), 18),
+            Ok(2_000_0000000)
+        );
+    }
+
+    #[test]
+    fn test_fair_lp_price_errors() {
+        assert_eq!(
+            fair_lp_price(1, 1, 0, 7),
+            Err(OracleError::PriceNotAvailable)
+        );
+        assert_eq!(
+            fair_lp_price(1, 1, 1, 19),
+            Err(OracleError::InvalidDecimals)
+        );
+        assert_eq!(
+            fair_lp_price(i128::MAX, 2, 1, 7),
+            Err(OracleError::Overflow)
+        );
+        assert_eq!(reserve_value(1, 1, 19), Err(OracleError::InvalidDecimals));
+    }
+}
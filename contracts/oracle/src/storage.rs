@@ -1,5 +1,7 @@
 use soroban_sdk::{contracttype, Address, Env, String};
 
+pub use astroswap_shared::PriceData;
+
 /// Storage keys for the Oracle contract
 #[derive(Clone)]
 #[contracttype]
@@ -18,20 +20,64 @@ pub enum DataKey {
     Observations(Address),
     /// Last observation index for a token
     LastObservationIndex(Address),
+    /// Allowlisted keeper allowed to push prices
+    Updater(Address),
+    /// Keeper bounty configuration and remaining budget
+    KeeperBounty,
+    /// DIA oracle adapter configuration
+    DiaConfig,
+    /// Where a token's price comes from (pushed when unset)
+    PriceSource(Address),
+    /// Minimum data a TWAP must be computed from
+    TwapRequirements,
+}
+
+/// Where a token's price comes from
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PriceSource {
+    /// Pushed by the admin or allowlisted keepers
+    Push,
+    /// Pulled from the DIA oracle contract when the cached price is stale
+    Dia,
 }
 
-/// Price data structure
+/// Deployed DIA oracle contract and how to read it
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct PriceData {
-    /// Price value (scaled by decimals)
-    pub price: i128,
-    /// Timestamp when price was last updated
-    pub timestamp: u64,
-    /// Number of decimals for the price (e.g., 8 for USD prices)
+pub struct DiaConfig {
+    /// DIA oracle contract address
+    pub contract: Address,
+    /// Appended to a token's feed ID to form its DIA key (e.g. "/USD")
+    pub key_suffix: String,
+    /// Decimals of the values DIA reports
+    pub source_decimals: u32,
+    /// Decimals pulled prices are stored with
     pub decimals: u32,
-    /// Price source/feed identifier
-    pub source: String,
+}
+
+/// Reward paid to allowlisted keepers for fresh price updates
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeeperBounty {
+    /// Token the reward is paid in
+    pub reward_token: Address,
+    /// Reward per accepted update
+    pub reward_amount: i128,
+    /// Minimum age of the previous price for an update to earn the reward
+    pub min_interval: u64,
+    /// Funded budget left for rewards
+    pub balance: i128,
+}
+
+/// Minimum data behind a TWAP, below which `get_twap` fails with `InsufficientData`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TwapRequirements {
+    /// Observations the average must include
+    pub min_observations: u32,
+    /// Share of the window the observations must span (bps)
+    pub min_coverage_bps: u32,
 }
 
 /// TWAP observation structure
@@ -40,7 +86,8 @@ pub struct PriceData {
 pub struct Observation {
     /// Timestamp of the observation
     pub timestamp: u64,
-    /// Cumulative price at this timestamp (for TWAP calculation)
+    /// Cumulative price at this timestamp (sum of price * seconds since the
+    /// previous observation)
     pub cumulative_price: i128,
     /// Actual price at this observation
     pub price: i128,
@@ -49,6 +96,12 @@ pub struct Observation {
 /// Maximum number of observations to store per token
 pub const MAX_OBSERVATIONS: u32 = 100;
 
+/// Default minimum observations in a TWAP
+pub const DEFAULT_MIN_TWAP_OBSERVATIONS: u32 = 2;
+
+/// Default minimum share of the window a TWAP's observations span (50%)
+pub const DEFAULT_MIN_TWAP_COVERAGE_BPS: u32 = 5_000;
+
 /// Default staleness threshold (1 hour)
 pub const DEFAULT_STALENESS_THRESHOLD: u64 = 3600;
 
@@ -126,4 +179,78 @@ impl DataKey {
             .persistent()
             .set(&DataKey::FeedId(token.clone()), feed_id);
     }
+
+    /// Check if an address is an allowlisted updater
+    pub fn is_updater(env: &Env, updater: &Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::Updater(updater.clone()))
+    }
+
+    /// Add or remove an allowlisted updater
+    pub fn set_updater(env: &Env, updater: &Address, allowed: bool) {
+        let key = DataKey::Updater(updater.clone());
+        if allowed {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Get the keeper bounty, if configured
+    pub fn get_keeper_bounty(env: &Env) -> Option<KeeperBounty> {
+        env.storage().instance().get(&DataKey::KeeperBounty)
+    }
+
+    /// Set the keeper bounty
+    pub fn set_keeper_bounty(env: &Env, bounty: &KeeperBounty) {
+        env.storage()
+            .instance()
+            .set(&DataKey::KeeperBounty, bounty);
+    }
+
+    /// Get the DIA adapter configuration, if set
+    pub fn get_dia_config(env: &Env) -> Option<DiaConfig> {
+        env.storage().instance().get(&DataKey::DiaConfig)
+    }
+
+    /// Set the DIA adapter configuration
+    pub fn set_dia_config(env: &Env, config: &DiaConfig) {
+        env.storage()
+            .instance()
+            .set(&DataKey::DiaConfig, config);
+    }
+
+    /// Get the price source of a token
+    pub fn get_price_source(env: &Env, token: &Address) -> PriceSource {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PriceSource(token.clone()))
+            .unwrap_or(PriceSource::Push)
+    }
+
+    /// Set the price source of a token
+    pub fn set_price_source(env: &Env, token: &Address, source: PriceSource) {
+        env.storage()
+            .persistent()
+            .set(&DataKey::PriceSource(token.clone()), &source);
+    }
+
+    /// Get the TWAP requirements, defaults when unset
+    pub fn get_twap_requirements(env: &Env) -> TwapRequirements {
+        env.storage()
+            .instance()
+            .get(&DataKey::TwapRequirements)
+            .unwrap_or(TwapRequirements {
+                min_observations: DEFAULT_MIN_TWAP_OBSERVATIONS,
+                min_coverage_bps: DEFAULT_MIN_TWAP_COVERAGE_BPS,
+            })
+    }
+
+    /// Set the TWAP requirements
+    pub fn set_twap_requirements(env: &Env, requirements: &TwapRequirements) {
+        env.storage()
+            .instance()
+            .set(&DataKey::TwapRequirements, requirements);
+    }
 }
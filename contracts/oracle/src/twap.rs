@@ -1,3 +1,4 @@
+use astroswap_shared::{weighted_avg, AstroSwapError, BPS_DENOMINATOR};
 use soroban_sdk::{Address, Env, Vec};
 
 use crate::error::OracleError;
@@ -76,6 +77,9 @@ pub fn add_observation(env: &Env, token: &Address, price: i128) -> Result<(), Or
 }
 
 /// Calculate Time-Weighted Average Price (TWAP) for a given window
+///
+/// Fails with `InsufficientData` when the observations in the window do not
+/// meet the configured `TwapRequirements`
 pub fn calculate_twap(env: &Env, token: &Address, window: u64) -> Result<i128, OracleError> {
     // Validate window
     if window < MIN_TWAP_WINDOW {
@@ -85,15 +89,7 @@ pub fn calculate_twap(env: &Env, token: &Address, window: u64) -> Result<i128, O
         return Err(OracleError::WindowTooLarge);
     }
 
-    let observations: Vec<Observation> = env
-        .storage()
-        .persistent()
-        .get(&DataKey::Observations(token.clone()))
-        .ok_or(OracleError::InsufficientObservations)?;
-
-    if observations.is_empty() {
-        return Err(OracleError::InsufficientObservations);
-    }
+    let observations = chronological_observations(env, token)?;
 
     let current_time = env.ledger().timestamp();
     let window_start = current_time.saturating_sub(window);
@@ -101,25 +97,73 @@ pub fn calculate_twap(env: &Env, token: &Address, window: u64) -> Result<i128, O
     // Find the two observations that bracket the window
     let (start_obs, end_obs) = find_bracketing_observations(&observations, window_start, current_time)?;
 
-    // Calculate TWAP: (cumulative_price_end - cumulative_price_start) / time_elapsed
-    let cumulative_diff = end_obs
-        .cumulative_price
-        .checked_sub(start_obs.cumulative_price)
-        .ok_or(OracleError::Overflow)?;
-
-    let time_elapsed = end_obs.timestamp.saturating_sub(start_obs.timestamp);
+    // TWAP: prices of the observations after start_obs up to end_obs, each
+    // weighted by the time since the observation before it
+    let mut prices: Vec<i128> = Vec::new(env);
+    let mut durations: Vec<u64> = Vec::new(env);
+    let mut last_timestamp = start_obs.timestamp;
+    for obs in observations.iter() {
+        if obs.timestamp <= start_obs.timestamp || obs.timestamp > end_obs.timestamp {
+            continue;
+        }
+        prices.push_back(obs.price);
+        durations.push_back(obs.timestamp.saturating_sub(last_timestamp));
+        last_timestamp = obs.timestamp;
+    }
 
-    if time_elapsed == 0 {
-        return Err(OracleError::DivisionByZero);
+    // Too few observations, or spanning too little of the window, make a
+    // misleading average
+    let requirements = DataKey::get_twap_requirements(env);
+    let covered = end_obs
+        .timestamp
+        .min(current_time)
+        .saturating_sub(start_obs.timestamp.max(window_start));
+    if prices.len() < requirements.min_observations
+        || covered * u64::from(BPS_DENOMINATOR) < window * u64::from(requirements.min_coverage_bps)
+    {
+        return Err(OracleError::InsufficientData);
     }
 
-    let twap = cumulative_diff
-        .checked_div(i128::from(time_elapsed))
-        .ok_or(OracleError::DivisionByZero)?;
+    let twap = weighted_avg(prices, durations).map_err(|err| match err {
+        AstroSwapError::DivisionByZero => OracleError::DivisionByZero,
+        _ => OracleError::Overflow,
+    })?;
 
     Ok(twap)
 }
 
+/// Observations of `token`, oldest first
+///
+/// Once the ring buffer is full, the oldest observation is the one after
+/// `LastObservationIndex` rather than the first in storage.
+fn chronological_observations(env: &Env, token: &Address) -> Result<Vec<Observation>, OracleError> {
+    let stored: Vec<Observation> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Observations(token.clone()))
+        .ok_or(OracleError::InsufficientObservations)?;
+
+    let len = stored.len();
+    if len == 0 {
+        return Err(OracleError::InsufficientObservations);
+    }
+
+    let last_index: u32 = env
+        .storage()
+        .persistent()
+        .get(&DataKey::LastObservationIndex(token.clone()))
+        .unwrap_or(len - 1);
+
+    let mut observations = Vec::new(env);
+    for offset in 1..=len {
+        let obs = stored
+            .get((last_index + offset) % len)
+            .ok_or(OracleError::InsufficientObservations)?;
+        observations.push_back(obs);
+    }
+    Ok(observations)
+}
+
 /// Find observations that bracket the given time window
 fn find_bracketing_observations(
     observations: &Vec<Observation>,
@@ -1,19 +1,69 @@
 use astroswap_shared::{
-    calculate_k, calculate_liquidity_tokens, calculate_withdrawal_amounts, emit_deposit, emit_swap,
-    emit_withdraw, get_amount_in, get_amount_out, safe_sub, update_reserves_add, update_reserves_sub,
-    update_reserves_swap, verify_k_invariant, AstroSwapError, PairInfo, DEFAULT_SWAP_FEE_BPS,
-    MINIMUM_LIQUIDITY, MIN_TRADE_AMOUNT,
+    calculate_deviation_bps, calculate_k, calculate_liquidity_tokens, calculate_price,
+    calculate_price_impact, calculate_withdrawal_amounts, emit_deposit, emit_swap, emit_withdraw,
+    get_amount_in, get_amount_out, mul_div_down, require_bps_range, require_deadline,
+    require_not_paused, require_positive, safe_add, safe_mul, safe_sub, split_swap_fee,
+    to_precision, update_reserves_add, update_reserves_sub, update_reserves_swap,
+    verify_k_invariant, with_ledger_lock, AnalyticsClient, AstroSwapError, FactoryClient,
+    OracleClient, PairInfo, BPS_DENOMINATOR, CANONICAL_PRICE_DECIMALS, DEFAULT_SWAP_FEE_BPS,
+    MAX_FEE_BPS, MINIMUM_LIQUIDITY, MIN_TRADE_AMOUNT,
 };
-use soroban_sdk::{contract, contractimpl, token, Address, Env, String};
+use soroban_sdk::{contract, contractevent, contractimpl, token, Address, Env, String, Vec};
 
 use crate::storage::{
-    extend_instance_ttl, get_balance, get_factory, get_fee_bps, get_k_last, get_reserves,
-    get_token_0, get_token_1, get_total_supply, is_initialized, is_locked, is_paused, set_factory,
-    set_fee_bps, set_initialized, set_k_last, set_locked, set_paused, set_reserves, set_token_0, set_token_1,
+    extend_instance_ttl, get_analytics, get_balance, get_factory, get_fee_bps, get_k_last,
+    get_large_withdrawal, get_observation_state, get_reserve_bounds, get_reserves, get_token_0,
+    get_token_1, get_total_supply, get_treasury, get_withdrawal_request, get_withdrawn,
+    is_initialized, is_paused, remove_withdrawal_request, set_analytics, set_factory, set_fee_bps,
+    set_initialized, set_k_last, set_large_withdrawal, set_paused, set_reserve_bounds,
+    set_reserves, set_token_0, set_token_1, set_treasury, set_withdrawal_request, set_withdrawn,
+    DataKey, DepthLevel, DepthQuote, LargeWithdrawalConfig, Observation, ObservationState,
+    WithdrawalRequest, WithdrawnShares,
 };
 
+use crate::observations;
 use crate::token as lp_token;
 
+/// Surplus skimmed event (balance above the reserves sent to the treasury)
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SurplusSkimmed {
+    #[topic]
+    pub token: Address,
+    pub treasury: Address,
+    pub amount: i128,
+}
+
+/// Large withdrawal announced event, so the market can adjust before it lands
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WithdrawalRequested {
+    #[topic]
+    pub user: Address,
+    pub shares: i128,
+    /// Share of the reserves the withdrawal would remove at request time
+    pub share_bps: u32,
+    pub ready_at: u64,
+    pub expires_at: u64,
+}
+
+/// Pending large withdrawal cancelled event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WithdrawalRequestCancelled {
+    #[topic]
+    pub user: Address,
+    pub shares: i128,
+}
+
+/// Observation ring buffer grown event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ObservationCardinalityIncreased {
+    pub cardinality_old: u32,
+    pub cardinality_new: u32,
+}
+
 #[contract]
 pub struct AstroSwapPair;
 
@@ -41,36 +91,23 @@ impl AstroSwapPair {
         set_token_1(&env, &token_1);
         set_fee_bps(&env, DEFAULT_SWAP_FEE_BPS);
         set_initialized(&env);
-        set_locked(&env, false);
+        lp_token::init_metadata(&env, &token_0, &token_1);
+        observations::initialize(&env);
 
         extend_instance_ttl(&env);
 
         Ok(())
     }
 
-    // ==================== Reentrancy Guard ====================
-
-    /// Internal function to acquire reentrancy lock
-    fn acquire_lock(env: &Env) -> Result<(), AstroSwapError> {
-        if is_locked(env) {
-            return Err(AstroSwapError::Reentrancy);
-        }
-        set_locked(env, true);
-        Ok(())
-    }
+    /// TWAP window used by `swap_with_twap_guard` (30 minutes)
+    const TWAP_GUARD_WINDOW: u64 = 1_800;
 
-    /// Internal function to release reentrancy lock
-    fn release_lock(env: &Env) {
-        set_locked(env, false);
-    }
+    /// Most trade sizes `quote_depth` prices in one call
+    const MAX_DEPTH_LEVELS: u32 = 20;
 
-    /// Verify contract is not paused
-    fn require_not_paused(env: &Env) -> Result<(), AstroSwapError> {
-        if is_paused(env) {
-            return Err(AstroSwapError::ContractPaused);
-        }
-        Ok(())
-    }
+    /// Longest large-withdrawal delay and execution window (30 days each)
+    const MAX_WITHDRAWAL_DELAY: u64 = 30 * 86_400;
+    const MAX_WITHDRAWAL_WINDOW: u64 = 30 * 86_400;
 
     /// Verify contract is initialized (prevents storage panics)
     fn require_initialized(env: &Env) -> Result<(), AstroSwapError> {
@@ -104,6 +141,230 @@ impl AstroSwapPair {
         is_paused(&env)
     }
 
+    /// Set the swap fee
+    /// Only factory can call, when creating a pair in a non-default fee tier
+    pub fn set_fee_bps(env: Env, fee_bps: u32) -> Result<(), AstroSwapError> {
+        Self::require_factory(&env)?;
+
+        if fee_bps == 0 {
+            return Err(AstroSwapError::InvalidFee);
+        }
+        require_bps_range(fee_bps, MAX_FEE_BPS)?;
+
+        set_fee_bps(&env, fee_bps);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set the treasury that receives donated surplus
+    /// Only factory can call (which requires admin auth)
+    ///
+    /// Once set, any token balance above the reserves is skimmed to the
+    /// treasury on the next deposit or swap.
+    pub fn set_treasury(env: Env, treasury: Address) -> Result<(), AstroSwapError> {
+        Self::require_factory(&env)?;
+        set_treasury(&env, &treasury);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set or clear the analytics contract swaps are reported to
+    /// Only factory can call (which requires admin auth)
+    ///
+    /// Reports are fire-and-forget: a failing analytics contract never fails
+    /// a swap.
+    pub fn set_analytics(env: Env, analytics: Option<Address>) -> Result<(), AstroSwapError> {
+        Self::require_factory(&env)?;
+        set_analytics(&env, &analytics);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set the reserve cap and floor
+    /// Only factory can call (which requires admin auth)
+    ///
+    /// Deposits and swaps may not push either reserve above `max_reserve`,
+    /// keeping k well inside i128 for high-decimal tokens. Swaps may not
+    /// drain the output reserve below `min_reserve`. 0 disables either bound.
+    pub fn set_reserve_bounds(
+        env: Env,
+        max_reserve: i128,
+        min_reserve: i128,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_factory(&env)?;
+
+        if max_reserve < 0 || min_reserve < 0 || (max_reserve > 0 && min_reserve >= max_reserve) {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+
+        set_reserve_bounds(&env, max_reserve, min_reserve);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Require a request and delay for large withdrawals
+    /// Only factory can call (which requires admin auth)
+    ///
+    /// Withdrawals of more than `threshold_bps` of the reserves must first be
+    /// announced with `request_withdrawal`, and can only be executed between
+    /// `delay_seconds` and `delay_seconds + window_seconds` after the request.
+    /// Unrequested withdrawals are totalled per period of `delay_seconds`, per
+    /// LP and across all LPs, and the threshold applies to both totals, so a
+    /// large exit cannot be split into small ones. Delay and window are at
+    /// most 30 days each. A `threshold_bps` of 0 disables the mode.
+    pub fn set_large_withdrawal(
+        env: Env,
+        threshold_bps: u32,
+        delay_seconds: u64,
+        window_seconds: u64,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_factory(&env)?;
+
+        if threshold_bps > BPS_DENOMINATOR
+            || (threshold_bps > 0 && (delay_seconds == 0 || window_seconds == 0))
+            || delay_seconds > Self::MAX_WITHDRAWAL_DELAY
+            || window_seconds > Self::MAX_WITHDRAWAL_WINDOW
+        {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+
+        set_large_withdrawal(
+            &env,
+            &LargeWithdrawalConfig {
+                threshold_bps,
+                delay_seconds,
+                window_seconds,
+            },
+        );
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Check `shares` against the large-withdrawal mode, consuming the
+    /// user's request if the withdrawal needs one
+    ///
+    /// Without a request, the user's and all LPs' withdrawals in the current
+    /// period must each stay within the threshold.
+    fn take_withdrawal_request(
+        env: &Env,
+        user: &Address,
+        shares: i128,
+        total_supply: i128,
+    ) -> Result<(), AstroSwapError> {
+        let config = get_large_withdrawal(env);
+        if config.threshold_bps == 0 {
+            return Ok(());
+        }
+
+        let now = env.ledger().timestamp();
+        let period_start = now - now % config.delay_seconds;
+        let add = |withdrawn: WithdrawnShares| -> Result<WithdrawnShares, AstroSwapError> {
+            let earlier = if withdrawn.period_start == period_start {
+                withdrawn.shares
+            } else {
+                0
+            };
+            Ok(WithdrawnShares {
+                period_start,
+                shares: safe_add(earlier, shares)?,
+            })
+        };
+        let user_withdrawn = add(get_withdrawn(env, Some(user)))?;
+        let total_withdrawn = add(get_withdrawn(env, None))?;
+
+        let limit = safe_mul(total_supply, i128::from(config.threshold_bps))?;
+        let bps = i128::from(BPS_DENOMINATOR);
+        if safe_mul(user_withdrawn.shares, bps)? <= limit
+            && safe_mul(total_withdrawn.shares, bps)? <= limit
+        {
+            set_withdrawn(env, Some(user), &user_withdrawn);
+            set_withdrawn(env, None, &total_withdrawn);
+            return Ok(());
+        }
+
+        let request = match get_withdrawal_request(env, user) {
+            Some(request) if request.shares >= shares => request,
+            _ => return Err(AstroSwapError::WithdrawalNotRequested),
+        };
+        let now = env.ledger().timestamp();
+        if now < request.ready_at {
+            return Err(AstroSwapError::WithdrawalDelayActive);
+        }
+        if now > request.expires_at {
+            return Err(AstroSwapError::WithdrawalRequestExpired);
+        }
+
+        remove_withdrawal_request(env, user);
+        Ok(())
+    }
+
+    /// Reject a reserve above the configured cap
+    fn require_below_cap(env: &Env, reserve: i128) -> Result<(), AstroSwapError> {
+        let (max_reserve, _) = get_reserve_bounds(env);
+        if max_reserve > 0 && reserve > max_reserve {
+            return Err(AstroSwapError::ReserveCapExceeded);
+        }
+        Ok(())
+    }
+
+    /// Reject a swap leaving the output reserve below the configured floor
+    fn require_above_floor(env: &Env, reserve_out: i128) -> Result<(), AstroSwapError> {
+        let (_, min_reserve) = get_reserve_bounds(env);
+        if reserve_out < min_reserve {
+            return Err(AstroSwapError::ReserveBelowMinimum);
+        }
+        Ok(())
+    }
+
+    /// Send the balance of `token` above `reserve` to the treasury
+    ///
+    /// Reserves only move through internal accounting, so a surplus can only
+    /// come from direct transfers. Without a treasury the surplus stays in the
+    /// pair, outside the reserves, until the factory skims or syncs it.
+    fn skim_surplus(env: &Env, token: &Address, reserve: i128) -> Result<(), AstroSwapError> {
+        let Some(treasury) = get_treasury(env) else {
+            return Ok(());
+        };
+        Self::transfer_surplus(env, token, reserve, &treasury)
+    }
+
+    /// Send the balance of `token` above `reserve` to `treasury`
+    fn transfer_surplus(
+        env: &Env,
+        token: &Address,
+        reserve: i128,
+        treasury: &Address,
+    ) -> Result<(), AstroSwapError> {
+        let token_client = token::Client::new(env, token);
+        let balance = token_client.balance(&env.current_contract_address());
+        if balance > reserve {
+            let amount = safe_sub(balance, reserve)?;
+            token_client.transfer(&env.current_contract_address(), treasury, &amount);
+            SurplusSkimmed {
+                token: token.clone(),
+                treasury: treasury.clone(),
+                amount,
+            }
+            .publish(env);
+        }
+        Ok(())
+    }
+
+    /// Report a swap to the analytics contract, if one is set
+    fn report_swap(
+        env: &Env,
+        token_0: &Address,
+        token_1: &Address,
+        token_in: &Address,
+        amount_in: i128,
+        fee: i128,
+    ) {
+        if let Some(analytics) = get_analytics(env) {
+            AnalyticsClient::new(env, &analytics)
+                .report_swap(token_0, token_1, token_in, amount_in, fee);
+        }
+    }
+
     /// Deposit liquidity and receive LP tokens
     ///
     /// # Arguments
@@ -112,6 +373,8 @@ impl AstroSwapPair {
     /// * `amount_1_desired` - Desired amount of token_1 to deposit
     /// * `amount_0_min` - Minimum amount of token_0 (slippage protection)
     /// * `amount_1_min` - Minimum amount of token_1 (slippage protection)
+    /// * `deadline` - Transaction deadline timestamp, so a stale deposit
+    ///   cannot execute at a ratio far from the one it was signed at
     ///
     /// # Returns
     /// * Tuple of (amount_0_used, amount_1_used, shares_minted)
@@ -125,100 +388,104 @@ impl AstroSwapPair {
         amount_1_desired: i128,
         amount_0_min: i128,
         amount_1_min: i128,
+        deadline: u64,
     ) -> Result<(i128, i128, i128), AstroSwapError> {
         // Verify contract is initialized
         Self::require_initialized(&env)?;
         // Check pause status
-        Self::require_not_paused(&env)?;
+        require_not_paused(&env, &DataKey::Paused)?;
 
-        // Reentrancy guard
-        Self::acquire_lock(&env)?;
+        // Check deadline
+        require_deadline(&env, deadline)?;
 
-        user.require_auth();
+        // Reentrancy guard
+        with_ledger_lock(&env, &DataKey::LockLedger, || {
+            user.require_auth();
 
-        if amount_0_desired <= 0 || amount_1_desired <= 0 {
-            Self::release_lock(&env);
-            return Err(AstroSwapError::InvalidAmount);
-        }
+            require_positive(amount_0_desired)?;
+            require_positive(amount_1_desired)?;
 
-        let (reserve_0, reserve_1) = get_reserves(&env);
-        let total_supply = get_total_supply(&env);
+            let (reserve_0, reserve_1) = get_reserves(&env);
+            let total_supply = get_total_supply(&env);
 
-        // Calculate optimal amounts
-        let (amount_0, amount_1) = if total_supply == 0 {
-            // First deposit - use desired amounts
-            (amount_0_desired, amount_1_desired)
-        } else {
-            // Calculate optimal based on current ratio
-            let amount_1_optimal = astroswap_shared::quote(amount_0_desired, reserve_0, reserve_1)?;
+            // Donations never count towards the reserves or LP share value
+            let token_0 = get_token_0(&env);
+            let token_1 = get_token_1(&env);
+            Self::skim_surplus(&env, &token_0, reserve_0)?;
+            Self::skim_surplus(&env, &token_1, reserve_1)?;
 
-            if amount_1_optimal <= amount_1_desired {
-                if amount_1_optimal < amount_1_min {
-                    Self::release_lock(&env);
-                    return Err(AstroSwapError::MinimumNotMet);
-                }
-                (amount_0_desired, amount_1_optimal)
+            // Calculate optimal amounts
+            let (amount_0, amount_1) = if total_supply == 0 {
+                // First deposit - use desired amounts
+                (amount_0_desired, amount_1_desired)
             } else {
-                let amount_0_optimal =
-                    astroswap_shared::quote(amount_1_desired, reserve_1, reserve_0)?;
-                if amount_0_optimal > amount_0_desired || amount_0_optimal < amount_0_min {
-                    Self::release_lock(&env);
-                    return Err(AstroSwapError::MinimumNotMet);
+                // Calculate optimal based on current ratio
+                let amount_1_optimal =
+                    astroswap_shared::quote(amount_0_desired, reserve_0, reserve_1)?;
+
+                if amount_1_optimal <= amount_1_desired {
+                    if amount_1_optimal < amount_1_min {
+                        return Err(AstroSwapError::MinimumNotMet);
+                    }
+                    (amount_0_desired, amount_1_optimal)
+                } else {
+                    let amount_0_optimal =
+                        astroswap_shared::quote(amount_1_desired, reserve_1, reserve_0)?;
+                    if amount_0_optimal > amount_0_desired || amount_0_optimal < amount_0_min {
+                        return Err(AstroSwapError::MinimumNotMet);
+                    }
+                    (amount_0_optimal, amount_1_desired)
                 }
-                (amount_0_optimal, amount_1_desired)
-            }
-        };
-
-        // Calculate LP tokens to mint
-        let shares =
-            calculate_liquidity_tokens(amount_0, amount_1, reserve_0, reserve_1, total_supply)?;
-
-        if shares <= 0 {
-            return Err(AstroSwapError::InsufficientLiquidity);
-        }
-
-        // Transfer tokens from user to pool
-        let token_0 = get_token_0(&env);
-        let token_1 = get_token_1(&env);
-
-        let token_0_client = token::Client::new(&env, &token_0);
-        let token_1_client = token::Client::new(&env, &token_1);
-
-        token_0_client.transfer(&user, env.current_contract_address(), &amount_0);
-        token_1_client.transfer(&user, env.current_contract_address(), &amount_1);
+            };
 
-        // Mint LP tokens
-        if total_supply == 0 {
-            // Lock minimum liquidity forever to prevent attacks
-            lp_token::mint(&env, &env.current_contract_address(), MINIMUM_LIQUIDITY)?;
-        }
-        lp_token::mint(&env, &user, shares)?;
+            // Calculate LP tokens to mint
+            let shares =
+                calculate_liquidity_tokens(amount_0, amount_1, reserve_0, reserve_1, total_supply)?;
 
-        // Update reserves (with overflow protection)
-        let (new_reserve_0, new_reserve_1) =
-            update_reserves_add(reserve_0, reserve_1, amount_0, amount_1)?;
-        set_reserves(&env, new_reserve_0, new_reserve_1);
+            if shares <= 0 {
+                return Err(AstroSwapError::InsufficientLiquidity);
+            }
 
-        // Update k_last for protocol fee (with overflow protection)
-        let k = calculate_k(new_reserve_0, new_reserve_1)?;
-        set_k_last(&env, k);
+            // Transfer tokens from user to pool
+            let token_0_client = token::Client::new(&env, &token_0);
+            let token_1_client = token::Client::new(&env, &token_1);
 
-        // Emit event
-        emit_deposit(
-            &env,
-            &user,
-            &env.current_contract_address(),
-            amount_0,
-            amount_1,
-            shares,
-        );
+            token_0_client.transfer(&user, env.current_contract_address(), &amount_0);
+            token_1_client.transfer(&user, env.current_contract_address(), &amount_1);
 
-        extend_instance_ttl(&env);
+            // Mint LP tokens
+            if total_supply == 0 {
+                // Lock minimum liquidity forever to prevent attacks
+                lp_token::mint(&env, &env.current_contract_address(), MINIMUM_LIQUIDITY)?;
+            }
+            lp_token::mint(&env, &user, shares)?;
+
+            // Update reserves (with overflow protection)
+            let (new_reserve_0, new_reserve_1) =
+                update_reserves_add(reserve_0, reserve_1, amount_0, amount_1)?;
+            Self::require_below_cap(&env, new_reserve_0)?;
+            Self::require_below_cap(&env, new_reserve_1)?;
+            observations::record(&env)?;
+            set_reserves(&env, new_reserve_0, new_reserve_1);
+
+            // Update k_last for protocol fee (with overflow protection)
+            let k = calculate_k(new_reserve_0, new_reserve_1)?;
+            set_k_last(&env, k);
+
+            // Emit event
+            emit_deposit(
+                &env,
+                &user,
+                &env.current_contract_address(),
+                amount_0,
+                amount_1,
+                shares,
+            );
 
-        // Release reentrancy lock
-        Self::release_lock(&env);
+            extend_instance_ttl(&env);
 
-        Ok((amount_0, amount_1, shares))
+            Ok((amount_0, amount_1, shares))
+        })
     }
 
     /// Withdraw liquidity by burning LP tokens
@@ -228,6 +495,7 @@ impl AstroSwapPair {
     /// * `shares` - Amount of LP tokens to burn
     /// * `amount_0_min` - Minimum amount of token_0 to receive
     /// * `amount_1_min` - Minimum amount of token_1 to receive
+    /// * `deadline` - Transaction deadline timestamp
     ///
     /// # Returns
     /// * Tuple of (amount_0_received, amount_1_received)
@@ -240,79 +508,151 @@ impl AstroSwapPair {
         shares: i128,
         amount_0_min: i128,
         amount_1_min: i128,
+        deadline: u64,
     ) -> Result<(i128, i128), AstroSwapError> {
         // Verify contract is initialized
         Self::require_initialized(&env)?;
         // Check pause status
-        Self::require_not_paused(&env)?;
+        require_not_paused(&env, &DataKey::Paused)?;
+
+        // Check deadline
+        require_deadline(&env, deadline)?;
 
         // Reentrancy guard
-        Self::acquire_lock(&env)?;
+        with_ledger_lock(&env, &DataKey::LockLedger, || {
+            user.require_auth();
 
-        user.require_auth();
+            require_positive(shares)?;
 
-        if shares <= 0 {
-            Self::release_lock(&env);
-            return Err(AstroSwapError::InvalidAmount);
-        }
+            let user_balance = get_balance(&env, &user);
+            if user_balance < shares {
+                return Err(AstroSwapError::InsufficientBalance);
+            }
 
-        let user_balance = get_balance(&env, &user);
-        if user_balance < shares {
-            Self::release_lock(&env);
-            return Err(AstroSwapError::InsufficientBalance);
-        }
+            let (reserve_0, reserve_1) = get_reserves(&env);
+            let total_supply = get_total_supply(&env);
 
-        let (reserve_0, reserve_1) = get_reserves(&env);
-        let total_supply = get_total_supply(&env);
+            // Large withdrawals must have been announced
+            Self::take_withdrawal_request(&env, &user, shares, total_supply)?;
 
-        // Calculate amounts to return
-        let (amount_0, amount_1) =
-            calculate_withdrawal_amounts(shares, reserve_0, reserve_1, total_supply)?;
+            // Calculate amounts to return
+            let (amount_0, amount_1) =
+                calculate_withdrawal_amounts(shares, reserve_0, reserve_1, total_supply)?;
 
-        // Check minimums (slippage protection)
-        if amount_0 < amount_0_min || amount_1 < amount_1_min {
-            Self::release_lock(&env);
-            return Err(AstroSwapError::MinimumNotMet);
-        }
+            // Check minimums (slippage protection)
+            if amount_0 < amount_0_min || amount_1 < amount_1_min {
+                return Err(AstroSwapError::MinimumNotMet);
+            }
 
-        // Burn LP tokens
-        lp_token::burn(&env, &user, shares)?;
+            // Burn LP tokens
+            lp_token::burn(&env, &user, shares)?;
 
-        // Transfer tokens to user
-        let token_0 = get_token_0(&env);
-        let token_1 = get_token_1(&env);
+            // Transfer tokens to user
+            let token_0 = get_token_0(&env);
+            let token_1 = get_token_1(&env);
 
-        let token_0_client = token::Client::new(&env, &token_0);
-        let token_1_client = token::Client::new(&env, &token_1);
+            let token_0_client = token::Client::new(&env, &token_0);
+            let token_1_client = token::Client::new(&env, &token_1);
 
-        token_0_client.transfer(&env.current_contract_address(), &user, &amount_0);
-        token_1_client.transfer(&env.current_contract_address(), &user, &amount_1);
+            token_0_client.transfer(&env.current_contract_address(), &user, &amount_0);
+            token_1_client.transfer(&env.current_contract_address(), &user, &amount_1);
 
-        // Update reserves (with underflow protection)
-        let (new_reserve_0, new_reserve_1) =
-            update_reserves_sub(reserve_0, reserve_1, amount_0, amount_1)?;
-        set_reserves(&env, new_reserve_0, new_reserve_1);
+            // Update reserves (with underflow protection)
+            let (new_reserve_0, new_reserve_1) =
+                update_reserves_sub(reserve_0, reserve_1, amount_0, amount_1)?;
+            observations::record(&env)?;
+            set_reserves(&env, new_reserve_0, new_reserve_1);
 
-        // Update k_last (with overflow protection)
-        let k = calculate_k(new_reserve_0, new_reserve_1)?;
-        set_k_last(&env, k);
+            // Update k_last (with overflow protection)
+            let k = calculate_k(new_reserve_0, new_reserve_1)?;
+            set_k_last(&env, k);
 
-        // Emit event
-        emit_withdraw(
-            &env,
-            &user,
-            &env.current_contract_address(),
+            // Emit event
+            emit_withdraw(
+                &env,
+                &user,
+                &env.current_contract_address(),
+                shares,
+                amount_0,
+                amount_1,
+            );
+
+            extend_instance_ttl(&env);
+
+            Ok((amount_0, amount_1))
+        })
+    }
+
+    /// Announce a large withdrawal
+    ///
+    /// Required while the large-withdrawal mode is on for withdrawals above
+    /// its threshold. The request covers a later `withdraw` of up to `shares`
+    /// within its window and replaces any earlier request of the user.
+    ///
+    /// # Returns
+    /// * The request, with the window it can be executed in
+    pub fn request_withdrawal(
+        env: Env,
+        user: Address,
+        shares: i128,
+    ) -> Result<WithdrawalRequest, AstroSwapError> {
+        Self::require_initialized(&env)?;
+        user.require_auth();
+        require_positive(shares)?;
+
+        let config = get_large_withdrawal(&env);
+        if config.threshold_bps == 0 {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+        if get_balance(&env, &user) < shares {
+            return Err(AstroSwapError::InsufficientBalance);
+        }
+
+        let ready_at = env
+            .ledger()
+            .timestamp()
+            .checked_add(config.delay_seconds)
+            .ok_or(AstroSwapError::Overflow)?;
+        let request = WithdrawalRequest {
             shares,
-            amount_0,
-            amount_1,
-        );
+            ready_at,
+            expires_at: ready_at
+                .checked_add(config.window_seconds)
+                .ok_or(AstroSwapError::Overflow)?,
+        };
+        set_withdrawal_request(&env, &user, &request);
+
+        // At most the whole supply, so it fits in u32
+        let share_bps =
+            mul_div_down(shares, i128::from(BPS_DENOMINATOR), get_total_supply(&env))? as u32;
+        WithdrawalRequested {
+            user,
+            shares,
+            share_bps,
+            ready_at,
+            expires_at: request.expires_at,
+        }
+        .publish(&env);
 
         extend_instance_ttl(&env);
+        Ok(request)
+    }
+
+    /// Cancel the user's pending large withdrawal
+    pub fn cancel_withdrawal_request(env: Env, user: Address) -> Result<(), AstroSwapError> {
+        user.require_auth();
+        let request =
+            get_withdrawal_request(&env, &user).ok_or(AstroSwapError::WithdrawalNotRequested)?;
+        remove_withdrawal_request(&env, &user);
 
-        // Release reentrancy lock
-        Self::release_lock(&env);
+        WithdrawalRequestCancelled {
+            user,
+            shares: request.shares,
+        }
+        .publish(&env);
 
-        Ok((amount_0, amount_1))
+        extend_instance_ttl(&env);
+        Ok(())
     }
 
     /// Execute a swap
@@ -339,96 +679,156 @@ impl AstroSwapPair {
         // Verify contract is initialized
         Self::require_initialized(&env)?;
         // Check pause status
-        Self::require_not_paused(&env)?;
+        require_not_paused(&env, &DataKey::Paused)?;
 
         // Check deadline (MEV protection)
-        if env.ledger().timestamp() > deadline {
-            return Err(AstroSwapError::DeadlineExpired);
-        }
+        require_deadline(&env, deadline)?;
 
         // Reentrancy guard
-        Self::acquire_lock(&env)?;
+        with_ledger_lock(&env, &DataKey::LockLedger, || {
+            user.require_auth();
 
-        user.require_auth();
+            // Validate amount (must be positive and meet minimum trade amount)
+            require_positive(amount_in)?;
 
-        // Validate amount (must be positive and meet minimum trade amount)
-        if amount_in <= 0 {
-            Self::release_lock(&env);
-            return Err(AstroSwapError::InvalidAmount);
-        }
+            // Minimum trade amount to prevent dust attacks (0.1 XLM = 1_000_000 stroops)
+            // Uses shared constant from astroswap_shared for consistency
+            if amount_in < MIN_TRADE_AMOUNT {
+                return Err(AstroSwapError::AmountBelowMinimum);
+            }
 
-        // Minimum trade amount to prevent dust attacks (0.1 XLM = 1_000_000 stroops)
-        // Uses shared constant from astroswap_shared for consistency
-        if amount_in < MIN_TRADE_AMOUNT {
-            Self::release_lock(&env);
-            return Err(AstroSwapError::AmountBelowMinimum);
-        }
+            let token_0 = get_token_0(&env);
+            let token_1 = get_token_1(&env);
 
-        let token_0 = get_token_0(&env);
-        let token_1 = get_token_1(&env);
+            // Determine swap direction
+            let (reserve_in, reserve_out, token_out, is_token_0_in) = if token_in == token_0 {
+                let (r0, r1) = get_reserves(&env);
+                (r0, r1, token_1.clone(), true)
+            } else if token_in == token_1 {
+                let (r0, r1) = get_reserves(&env);
+                (r1, r0, token_0.clone(), false)
+            } else {
+                return Err(AstroSwapError::InvalidToken);
+            };
 
-        // Determine swap direction
-        let (reserve_in, reserve_out, token_out, is_token_0_in) = if token_in == token_0 {
-            let (r0, r1) = get_reserves(&env);
-            (r0, r1, token_1.clone(), true)
-        } else if token_in == token_1 {
-            let (r0, r1) = get_reserves(&env);
-            (r1, r0, token_0.clone(), false)
-        } else {
-            Self::release_lock(&env);
-            return Err(AstroSwapError::InvalidToken);
-        };
+            // Donations never count towards the reserves
+            Self::skim_surplus(&env, &token_in, reserve_in)?;
+            Self::skim_surplus(&env, &token_out, reserve_out)?;
 
-        // Calculate output amount
-        let fee_bps = get_fee_bps(&env);
-        let amount_out = match get_amount_out(amount_in, reserve_in, reserve_out, fee_bps) {
-            Ok(out) => out,
-            Err(e) => {
-                Self::release_lock(&env);
-                return Err(e);
+            // Calculate output amount
+            let fee_bps = get_fee_bps(&env);
+            let amount_out = get_amount_out(amount_in, reserve_in, reserve_out, fee_bps)?;
+
+            // Check slippage
+            if amount_out < min_out {
+                return Err(AstroSwapError::SlippageExceeded);
             }
-        };
 
-        // Check slippage
-        if amount_out < min_out {
-            Self::release_lock(&env);
-            return Err(AstroSwapError::SlippageExceeded);
-        }
+            // Check reserve bounds
+            Self::require_below_cap(&env, safe_add(reserve_in, amount_in)?)?;
+            Self::require_above_floor(&env, safe_sub(reserve_out, amount_out)?)?;
 
-        // Transfer input tokens from user
-        let token_in_client = token::Client::new(&env, &token_in);
-        token_in_client.transfer(&user, env.current_contract_address(), &amount_in);
+            // Transfer input tokens from user
+            let token_in_client = token::Client::new(&env, &token_in);
+            token_in_client.transfer(&user, env.current_contract_address(), &amount_in);
 
-        // Transfer output tokens to user
-        let token_out_client = token::Client::new(&env, &token_out);
-        token_out_client.transfer(&env.current_contract_address(), &user, &amount_out);
+            // Transfer output tokens to user
+            let token_out_client = token::Client::new(&env, &token_out);
+            token_out_client.transfer(&env.current_contract_address(), &user, &amount_out);
 
-        // Update reserves (with overflow/underflow protection)
-        let (new_reserve_0, new_reserve_1) =
-            update_reserves_swap(reserve_in, reserve_out, amount_in, amount_out, is_token_0_in)?;
-        set_reserves(&env, new_reserve_0, new_reserve_1);
+            // Update reserves (with overflow/underflow protection)
+            let (new_reserve_0, new_reserve_1) =
+                update_reserves_swap(reserve_in, reserve_out, amount_in, amount_out, is_token_0_in)?;
+            observations::record(&env)?;
+            set_reserves(&env, new_reserve_0, new_reserve_1);
 
-        // Verify k invariant (should increase slightly due to fees)
-        // Get original reserves for k comparison
-        let (orig_reserve_0, orig_reserve_1) = if is_token_0_in {
-            (reserve_in, reserve_out)
-        } else {
-            (reserve_out, reserve_in)
-        };
-        if !verify_k_invariant(new_reserve_0, new_reserve_1, orig_reserve_0, orig_reserve_1)? {
-            Self::release_lock(&env);
-            return Err(AstroSwapError::InvalidAmount);
-        }
+            // Verify k invariant (should increase slightly due to fees)
+            // Get original reserves for k comparison
+            let (orig_reserve_0, orig_reserve_1) = if is_token_0_in {
+                (reserve_in, reserve_out)
+            } else {
+                (reserve_out, reserve_in)
+            };
+            if !verify_k_invariant(new_reserve_0, new_reserve_1, orig_reserve_0, orig_reserve_1)? {
+                return Err(AstroSwapError::InvalidAmount);
+            }
 
-        // Emit event
-        emit_swap(&env, &user, &token_in, &token_out, amount_in, amount_out);
+            // Emit event with the fee split for LP APR / protocol revenue analytics
+            let fees = split_swap_fee(amount_in, fee_bps)?;
+            emit_swap(&env, &user, &token_in, &token_out, amount_in, amount_out, &fees);
+            Self::report_swap(&env, &token_0, &token_1, &token_in, amount_in, fees.fee_amount);
 
-        extend_instance_ttl(&env);
+            extend_instance_ttl(&env);
+
+            Ok(amount_out)
+        })
+    }
+
+    /// Swap guarded by the oracle TWAP
+    /// Like `swap`, but first rejects the trade if its execution price deviates
+    /// from the oracle TWAP price by more than `max_twap_deviation_bps`
+    ///
+    /// # Arguments
+    /// * `user` - The address executing the swap
+    /// * `token_in` - Address of the token being swapped in
+    /// * `amount_in` - Amount of token_in to swap
+    /// * `min_out` - Minimum amount of output token (slippage protection)
+    /// * `max_twap_deviation_bps` - Maximum deviation of the output from the
+    ///   TWAP-implied output, in basis points (the pair fee counts towards it)
+    /// * `oracle` - AstroSwap oracle providing TWAPs for both pair tokens
+    /// * `deadline` - Timestamp after which the transaction reverts
+    ///
+    /// # Returns
+    /// * Amount of output token received
+    ///
+    /// # Security
+    /// Both TWAPs must be quoted with the same decimals. A missing or
+    /// insufficient TWAP fails with `QuoteStale` rather than skipping the guard.
+    #[allow(clippy::too_many_arguments)]
+    pub fn swap_with_twap_guard(
+        env: Env,
+        user: Address,
+        token_in: Address,
+        amount_in: i128,
+        min_out: i128,
+        max_twap_deviation_bps: u32,
+        oracle: Address,
+        deadline: u64,
+    ) -> Result<i128, AstroSwapError> {
+        Self::require_initialized(&env)?;
+
+        if max_twap_deviation_bps > BPS_DENOMINATOR {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+
+        let token_out = if token_in == get_token_0(&env) {
+            get_token_1(&env)
+        } else if token_in == get_token_1(&env) {
+            get_token_0(&env)
+        } else {
+            return Err(AstroSwapError::InvalidToken);
+        };
 
-        // Release reentrancy lock
-        Self::release_lock(&env);
+        // Output implied by the TWAPs vs. the output this swap would execute at,
+        // both at 18 decimals since the TWAPs price whole tokens
+        let oracle_client = OracleClient::new(&env, &oracle);
+        let twap_in = oracle_client.try_get_twap(&token_in, Self::TWAP_GUARD_WINDOW)?;
+        let twap_out = oracle_client.try_get_twap(&token_out, Self::TWAP_GUARD_WINDOW)?;
+        if twap_in <= 0 || twap_out <= 0 {
+            return Err(AstroSwapError::QuoteStale);
+        }
+        let decimals_in = token::Client::new(&env, &token_in).decimals();
+        let decimals_out = token::Client::new(&env, &token_out).decimals();
+        let twap_amount_out =
+            mul_div_down(to_precision(amount_in, decimals_in)?, twap_in, twap_out)?;
+        let amount_out = Self::get_amount_out(env.clone(), amount_in, token_in.clone())?;
+        let amount_out = to_precision(amount_out, decimals_out)?;
+
+        if calculate_deviation_bps(amount_out, twap_amount_out)? > max_twap_deviation_bps {
+            return Err(AstroSwapError::TwapDeviationExceeded);
+        }
 
-        Ok(amount_out)
+        Self::swap(env, user, token_in, amount_in, min_out, deadline)
     }
 
     /// Low-level swap for router (tokens already in contract)
@@ -455,102 +855,83 @@ impl AstroSwapPair {
         // Verify contract is initialized
         Self::require_initialized(&env)?;
         // Check pause status
-        Self::require_not_paused(&env)?;
+        require_not_paused(&env, &DataKey::Paused)?;
 
         // Check deadline (MEV protection)
-        if env.ledger().timestamp() > deadline {
-            return Err(AstroSwapError::DeadlineExpired);
-        }
+        require_deadline(&env, deadline)?;
 
         // Reentrancy guard
-        Self::acquire_lock(&env)?;
-
-        let token_0 = get_token_0(&env);
-        let token_1 = get_token_1(&env);
-        let (reserve_0, reserve_1) = get_reserves(&env);
+        with_ledger_lock(&env, &DataKey::LockLedger, || {
+            let token_0 = get_token_0(&env);
+            let token_1 = get_token_1(&env);
+            let (reserve_0, reserve_1) = get_reserves(&env);
+
+            // Determine swap direction
+            let (reserve_in, reserve_out, token_out, is_token_0_in) = if token_in == token_0 {
+                (reserve_0, reserve_1, token_1.clone(), true)
+            } else if token_in == token_1 {
+                (reserve_1, reserve_0, token_0.clone(), false)
+            } else {
+                return Err(AstroSwapError::InvalidToken);
+            };
 
-        // Get current balances
-        let token_0_client = token::Client::new(&env, &token_0);
-        let token_1_client = token::Client::new(&env, &token_1);
-        let balance_0 = token_0_client.balance(&env.current_contract_address());
-        let balance_1 = token_1_client.balance(&env.current_contract_address());
+            // Output-side donations never count towards the reserves. On the
+            // input side the balance above the reserve is the pre-transferred input.
+            Self::skim_surplus(&env, &token_out, reserve_out)?;
 
-        // Determine swap direction and calculate amount_in from balance diff (with underflow protection)
-        let (amount_in, reserve_in, reserve_out, token_out, is_token_0_in) = if token_in == token_0 {
-            let amount_in = match safe_sub(balance_0, reserve_0) {
-                Ok(amt) if amt > 0 => amt,
-                _ => {
-                    Self::release_lock(&env);
-                    return Err(AstroSwapError::InvalidAmount);
-                }
-            };
-            (amount_in, reserve_0, reserve_1, token_1.clone(), true)
-        } else if token_in == token_1 {
-            let amount_in = match safe_sub(balance_1, reserve_1) {
+            // Calculate amount_in from the balance diff (with underflow protection)
+            let token_in_client = token::Client::new(&env, &token_in);
+            let balance_in = token_in_client.balance(&env.current_contract_address());
+            let amount_in = match safe_sub(balance_in, reserve_in) {
                 Ok(amt) if amt > 0 => amt,
                 _ => {
-                    Self::release_lock(&env);
                     return Err(AstroSwapError::InvalidAmount);
                 }
             };
-            (amount_in, reserve_1, reserve_0, token_0.clone(), false)
-        } else {
-            Self::release_lock(&env);
-            return Err(AstroSwapError::InvalidToken);
-        };
 
-        // SECURITY: Validate minimum trade amount to prevent dust attacks
-        // This check was missing before, allowing small trades to bypass minimum validation
-        if amount_in < MIN_TRADE_AMOUNT {
-            Self::release_lock(&env);
-            return Err(AstroSwapError::AmountBelowMinimum);
-        }
-
-        // Calculate output amount
-        let fee_bps = get_fee_bps(&env);
-        let amount_out = match get_amount_out(amount_in, reserve_in, reserve_out, fee_bps) {
-            Ok(out) => out,
-            Err(e) => {
-                Self::release_lock(&env);
-                return Err(e);
+            // SECURITY: Validate minimum trade amount to prevent dust attacks
+            // This check was missing before, allowing small trades to bypass minimum validation
+            if amount_in < MIN_TRADE_AMOUNT {
+                return Err(AstroSwapError::AmountBelowMinimum);
             }
-        };
 
-        // Check slippage
-        if amount_out < min_out {
-            Self::release_lock(&env);
-            return Err(AstroSwapError::SlippageExceeded);
-        }
+            // Calculate output amount
+            let fee_bps = get_fee_bps(&env);
+            let amount_out = get_amount_out(amount_in, reserve_in, reserve_out, fee_bps)?;
 
-        // Transfer output tokens to recipient
-        let token_out_client = token::Client::new(&env, &token_out);
-        token_out_client.transfer(&env.current_contract_address(), &to, &amount_out);
+            // Check slippage
+            if amount_out < min_out {
+                return Err(AstroSwapError::SlippageExceeded);
+            }
 
-        // Update reserves based on actual balances after output transfer (with underflow protection)
-        let (new_balance_0, new_balance_1) = if is_token_0_in {
-            let new_b1 = safe_sub(balance_1, amount_out)?;
-            (balance_0, new_b1)
-        } else {
-            let new_b0 = safe_sub(balance_0, amount_out)?;
-            (new_b0, balance_1)
-        };
-        set_reserves(&env, new_balance_0, new_balance_1);
+            // Check reserve bounds
+            Self::require_below_cap(&env, safe_add(reserve_in, amount_in)?)?;
+            Self::require_above_floor(&env, safe_sub(reserve_out, amount_out)?)?;
 
-        // Verify k invariant (with overflow protection)
-        if !verify_k_invariant(new_balance_0, new_balance_1, reserve_0, reserve_1)? {
-            Self::release_lock(&env);
-            return Err(AstroSwapError::InvalidAmount);
-        }
+            // Transfer output tokens to recipient
+            let token_out_client = token::Client::new(&env, &token_out);
+            token_out_client.transfer(&env.current_contract_address(), &to, &amount_out);
 
-        // Emit event
-        emit_swap(&env, &to, &token_in, &token_out, amount_in, amount_out);
+            // Update reserves from internal accounting (with overflow/underflow protection)
+            let (new_reserve_0, new_reserve_1) =
+                update_reserves_swap(reserve_in, reserve_out, amount_in, amount_out, is_token_0_in)?;
+            observations::record(&env)?;
+            set_reserves(&env, new_reserve_0, new_reserve_1);
 
-        extend_instance_ttl(&env);
+            // Verify k invariant (with overflow protection)
+            if !verify_k_invariant(new_reserve_0, new_reserve_1, reserve_0, reserve_1)? {
+                return Err(AstroSwapError::InvalidAmount);
+            }
+
+            // Emit event with the fee split for LP APR / protocol revenue analytics
+            let fees = split_swap_fee(amount_in, fee_bps)?;
+            emit_swap(&env, &to, &token_in, &token_out, amount_in, amount_out, &fees);
+            Self::report_swap(&env, &token_0, &token_1, &token_in, amount_in, fees.fee_amount);
 
-        // Release reentrancy lock
-        Self::release_lock(&env);
+            extend_instance_ttl(&env);
 
-        Ok((amount_in, amount_out))
+            Ok((amount_in, amount_out))
+        })
     }
 
     /// Force reserves to match actual token balances
@@ -575,6 +956,7 @@ impl AstroSwapPair {
         let balance_0 = token_0_client.balance(&env.current_contract_address());
         let balance_1 = token_1_client.balance(&env.current_contract_address());
 
+        observations::record(&env)?;
         set_reserves(&env, balance_0, balance_1);
 
         extend_instance_ttl(&env);
@@ -596,40 +978,59 @@ impl AstroSwapPair {
         let factory = get_factory(&env);
         factory.require_auth();
 
-        Self::acquire_lock(&env)?;
-
-        let token_0 = get_token_0(&env);
-        let token_1 = get_token_1(&env);
-        let (reserve_0, reserve_1) = get_reserves(&env);
-
-        let token_0_client = token::Client::new(&env, &token_0);
-        let token_1_client = token::Client::new(&env, &token_1);
-
-        let balance_0 = token_0_client.balance(&env.current_contract_address());
-        let balance_1 = token_1_client.balance(&env.current_contract_address());
+        with_ledger_lock(&env, &DataKey::LockLedger, || {
+            let token_0 = get_token_0(&env);
+            let token_1 = get_token_1(&env);
+            let (reserve_0, reserve_1) = get_reserves(&env);
+
+            let token_0_client = token::Client::new(&env, &token_0);
+            let token_1_client = token::Client::new(&env, &token_1);
+
+            let balance_0 = token_0_client.balance(&env.current_contract_address());
+            let balance_1 = token_1_client.balance(&env.current_contract_address());
+
+            // Transfer excess (using safe_sub for consistency even though we check balance > reserve)
+            if balance_0 > reserve_0 {
+                let excess_0 = safe_sub(balance_0, reserve_0)?;
+                token_0_client.transfer(
+                    &env.current_contract_address(),
+                    &to,
+                    &excess_0,
+                );
+            }
+            if balance_1 > reserve_1 {
+                let excess_1 = safe_sub(balance_1, reserve_1)?;
+                token_1_client.transfer(
+                    &env.current_contract_address(),
+                    &to,
+                    &excess_1,
+                );
+            }
 
-        // Transfer excess (using safe_sub for consistency even though we check balance > reserve)
-        if balance_0 > reserve_0 {
-            let excess_0 = safe_sub(balance_0, reserve_0)?;
-            token_0_client.transfer(
-                &env.current_contract_address(),
-                &to,
-                &excess_0,
-            );
-        }
-        if balance_1 > reserve_1 {
-            let excess_1 = safe_sub(balance_1, reserve_1)?;
-            token_1_client.transfer(
-                &env.current_contract_address(),
-                &to,
-                &excess_1,
-            );
-        }
+            extend_instance_ttl(&env);
 
-        Self::release_lock(&env);
-        extend_instance_ttl(&env);
+            Ok(())
+        })
+    }
 
-        Ok(())
+    /// Transfer excess tokens to the factory's fee recipient
+    ///
+    /// Unlike `skim`, anyone can call this (e.g. keepers): the destination is
+    /// fixed by the factory, so there is nothing for the caller to extract.
+    pub fn skim_to_treasury(env: Env) -> Result<(), AstroSwapError> {
+        let fee_to = FactoryClient::new(&env, &get_factory(&env))
+            .fee_to()
+            .ok_or(AstroSwapError::FeeRecipientNotSet)?;
+
+        with_ledger_lock(&env, &DataKey::LockLedger, || {
+            let (reserve_0, reserve_1) = get_reserves(&env);
+            Self::transfer_surplus(&env, &get_token_0(&env), reserve_0, &fee_to)?;
+            Self::transfer_surplus(&env, &get_token_1(&env), reserve_1, &fee_to)?;
+
+            extend_instance_ttl(&env);
+
+            Ok(())
+        })
     }
 
     // ==================== View Functions ====================
@@ -668,6 +1069,33 @@ impl AstroSwapPair {
         get_reserves(&env)
     }
 
+    /// Get an LP's position at the current reserves
+    ///
+    /// # Returns
+    /// * Tuple of (lp_balance, share_bps, amount_0, amount_1): the owner's LP
+    ///   balance, its share of the total supply and the underlying tokens a
+    ///   full withdrawal would return, all rounded down
+    pub fn position_of(
+        env: Env,
+        owner: Address,
+    ) -> Result<(i128, u32, i128, i128), AstroSwapError> {
+        extend_instance_ttl(&env);
+
+        let lp_balance = get_balance(&env, &owner);
+        let total_supply = get_total_supply(&env);
+        if lp_balance <= 0 || total_supply <= 0 {
+            return Ok((lp_balance.max(0), 0, 0, 0));
+        }
+
+        let (reserve_0, reserve_1) = get_reserves(&env);
+        let (amount_0, amount_1) =
+            calculate_withdrawal_amounts(lp_balance, reserve_0, reserve_1, total_supply)?;
+        // At most the whole supply, so it fits in u32
+        let share_bps = mul_div_down(lp_balance, i128::from(BPS_DENOMINATOR), total_supply)? as u32;
+
+        Ok((lp_balance, share_bps, amount_0, amount_1))
+    }
+
     /// Get factory address
     pub fn factory(env: Env) -> Address {
         extend_instance_ttl(&env);
@@ -686,6 +1114,75 @@ impl AstroSwapPair {
         get_k_last(&env)
     }
 
+    /// Get the treasury that receives skimmed surplus, if set
+    pub fn treasury(env: Env) -> Option<Address> {
+        extend_instance_ttl(&env);
+        get_treasury(&env)
+    }
+
+    /// Get the analytics contract swaps are reported to
+    pub fn analytics(env: Env) -> Option<Address> {
+        extend_instance_ttl(&env);
+        get_analytics(&env)
+    }
+
+    /// Get the reserve cap and floor as (max_reserve, min_reserve), 0 if unset
+    pub fn reserve_bounds(env: Env) -> (i128, i128) {
+        extend_instance_ttl(&env);
+        get_reserve_bounds(&env)
+    }
+
+    /// Get the large-withdrawal mode (threshold 0 if disabled)
+    pub fn large_withdrawal(env: Env) -> LargeWithdrawalConfig {
+        extend_instance_ttl(&env);
+        get_large_withdrawal(&env)
+    }
+
+    /// Get an LP's pending large withdrawal, if any
+    pub fn withdrawal_request(env: Env, user: Address) -> Option<WithdrawalRequest> {
+        get_withdrawal_request(&env, &user)
+    }
+
+    // ==================== Price Observations ====================
+
+    /// Grow the price observation buffer to `cardinality` slots
+    ///
+    /// Permissionless: whoever needs a longer TWAP history pays for it. The
+    /// new slots fill as the buffer next wraps; asking for no more than the
+    /// current size is a no-op. At most `MAX_OBSERVATION_CARDINALITY` slots.
+    pub fn increase_observation_cardinality(
+        env: Env,
+        cardinality: u32,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_initialized(&env)?;
+        let (cardinality_old, cardinality_new) = observations::grow(&env, cardinality)?;
+        if cardinality_new != cardinality_old {
+            ObservationCardinalityIncreased {
+                cardinality_old,
+                cardinality_new,
+            }
+            .publish(&env);
+        }
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Get the observation buffer position and size, if observations started
+    pub fn observation_state(env: Env) -> Option<ObservationState> {
+        get_observation_state(&env)
+    }
+
+    /// Price cumulatives `seconds_ago` seconds before now, for each entry
+    ///
+    /// The TWAP of token_0 in token_1 between two points is the difference of
+    /// their `price_0_cumulative` (with `wrapping_sub`) divided by the seconds
+    /// between them, e.g. `observe([1800, 0])` for the last 30 minutes. Fails
+    /// with `QuoteStale` for a point older than the oldest observation.
+    pub fn observe(env: Env, seconds_agos: Vec<u64>) -> Result<Vec<Observation>, AstroSwapError> {
+        Self::require_initialized(&env)?;
+        observations::observe(&env, &seconds_agos)
+    }
+
     // ==================== Quote Functions ====================
 
     /// Get expected output amount for a swap
@@ -732,6 +1229,73 @@ impl AstroSwapPair {
         get_amount_in(amount_out, reserve_in, reserve_out, fee)
     }
 
+    /// Quote a ladder of trade sizes in both directions in one call
+    ///
+    /// Each entry of `levels_bps` sizes a trade as a share of the input
+    /// reserve (e.g. 10, 50, 100, 500 for 0.1%, 0.5%, 1% and 5%). Levels come
+    /// back in the order given; a size that rounds to nothing quotes zeros.
+    pub fn quote_depth(env: Env, levels_bps: Vec<u32>) -> Result<Vec<DepthLevel>, AstroSwapError> {
+        if levels_bps.is_empty() || levels_bps.len() > Self::MAX_DEPTH_LEVELS {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+        let (reserve_0, reserve_1) = get_reserves(&env);
+        if reserve_0 <= 0 || reserve_1 <= 0 {
+            return Err(AstroSwapError::InsufficientLiquidity);
+        }
+        let fee = get_fee_bps(&env);
+        let decimals_0 = token::Client::new(&env, &get_token_0(&env)).decimals();
+        let decimals_1 = token::Client::new(&env, &get_token_1(&env)).decimals();
+
+        let mut levels = Vec::new(&env);
+        for size_bps in levels_bps.iter() {
+            if size_bps == 0 || size_bps > BPS_DENOMINATOR {
+                return Err(AstroSwapError::InvalidArgument);
+            }
+            let side_0 = (reserve_0, decimals_0);
+            let side_1 = (reserve_1, decimals_1);
+            levels.push_back(DepthLevel {
+                size_bps,
+                sell_0: Self::depth_quote(size_bps, side_0, side_1, fee)?,
+                sell_1: Self::depth_quote(size_bps, side_1, side_0, fee)?,
+            });
+        }
+
+        extend_instance_ttl(&env);
+        Ok(levels)
+    }
+
+    /// Quote selling `size_bps` of the input reserve; sides are
+    /// (reserve, decimals)
+    fn depth_quote(
+        size_bps: u32,
+        (reserve_in, decimals_in): (i128, u32),
+        (reserve_out, decimals_out): (i128, u32),
+        fee: u32,
+    ) -> Result<DepthQuote, AstroSwapError> {
+        let amount_in = mul_div_down(
+            reserve_in,
+            i128::from(size_bps),
+            i128::from(BPS_DENOMINATOR),
+        )?;
+        if amount_in == 0 {
+            return Ok(DepthQuote::default());
+        }
+
+        let amount_out = get_amount_out(amount_in, reserve_in, reserve_out, fee)?;
+        Ok(DepthQuote {
+            amount_in,
+            amount_out,
+            price: calculate_price(
+                amount_in,
+                decimals_in,
+                amount_out,
+                decimals_out,
+                CANONICAL_PRICE_DECIMALS,
+            )?,
+            price_impact_bps: calculate_price_impact(amount_in, reserve_in, reserve_out, 0)?,
+        })
+    }
+
     // ==================== LP Token Interface (SEP-41) ====================
 
     /// Get LP token name
@@ -1,10 +1,15 @@
 #![no_std]
 
 mod contract;
+mod observations;
 mod storage;
 mod token;
 
 #[cfg(test)]
 mod tests;
 
-pub use contract::AstroSwapPair;
+pub use contract::{AstroSwapPair, AstroSwapPairClient};
+pub use observations::{MAX_OBSERVATION_CARDINALITY, MAX_OBSERVE_POINTS};
+pub use storage::{
+    DepthLevel, DepthQuote, LargeWithdrawalConfig, Observation, ObservationState, WithdrawalRequest,
+};
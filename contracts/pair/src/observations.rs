@@ -0,0 +1,195 @@
+//! Price observation ring buffer (Uniswap v3 style)
+//!
+//! Before the reserves change, the pair accumulates both spot prices over the
+//! time since the last observation and writes the result into a ring buffer of
+//! `cardinality` slots, at most once per timestamp. `observe` reads the
+//! cumulatives back at any point the buffer covers, so a TWAP over an
+//! arbitrary recent window takes a single call.
+
+use astroswap_shared::{mul_div_down, AstroSwapError, CANONICAL_PRICE_DECIMALS};
+use soroban_sdk::{Env, Vec};
+
+use crate::storage::{
+    get_observation, get_observation_state, get_reserves, set_observation, set_observation_state,
+    Observation, ObservationState,
+};
+
+/// Most slots the ring buffer can grow to
+pub const MAX_OBSERVATION_CARDINALITY: u32 = 1_024;
+
+/// Most points `observe` reads in one call
+pub const MAX_OBSERVE_POINTS: u32 = 20;
+
+/// Spot price of one whole unit of the base reserve in the quote reserve
+/// (7 decimals), 0 without liquidity
+fn spot_price(reserve_base: i128, reserve_quote: i128) -> Result<i128, AstroSwapError> {
+    if reserve_base <= 0 {
+        return Ok(0);
+    }
+    mul_div_down(
+        reserve_quote,
+        10i128.pow(CANONICAL_PRICE_DECIMALS),
+        reserve_base,
+    )
+}
+
+/// Carry `last` forward to `timestamp` at the prices of the given reserves
+fn transform(
+    last: &Observation,
+    timestamp: u64,
+    reserves: (i128, i128),
+) -> Result<Observation, AstroSwapError> {
+    let elapsed = i128::from(timestamp - last.timestamp);
+    let price_0 = spot_price(reserves.0, reserves.1)?;
+    let price_1 = spot_price(reserves.1, reserves.0)?;
+    Ok(Observation {
+        timestamp,
+        price_0_cumulative: last
+            .price_0_cumulative
+            .wrapping_add(price_0.wrapping_mul(elapsed)),
+        price_1_cumulative: last
+            .price_1_cumulative
+            .wrapping_add(price_1.wrapping_mul(elapsed)),
+    })
+}
+
+/// Start the buffer with a single zero observation at the current time
+pub fn initialize(env: &Env) -> ObservationState {
+    let state = ObservationState {
+        index: 0,
+        cardinality: 1,
+        cardinality_next: 1,
+    };
+    let observation = Observation {
+        timestamp: env.ledger().timestamp(),
+        ..Observation::default()
+    };
+    set_observation(env, 0, &observation);
+    set_observation_state(env, &state);
+    state
+}
+
+/// Record the prices of the current reserves up to now
+///
+/// Call before the reserves change. A second change within the same
+/// timestamp is not recorded: the prices it leaves behind are accumulated
+/// by the next observation.
+pub fn record(env: &Env) -> Result<(), AstroSwapError> {
+    let Some(mut state) = get_observation_state(env) else {
+        initialize(env);
+        return Ok(());
+    };
+    let now = env.ledger().timestamp();
+    let last = get_observation(env, state.index).unwrap_or_default();
+    if last.timestamp == now {
+        return Ok(());
+    }
+
+    let observation = transform(&last, now, get_reserves(env))?;
+
+    // Grow into the new slots once the buffer reaches its current end
+    if state.cardinality_next > state.cardinality && state.index == state.cardinality - 1 {
+        state.cardinality = state.cardinality_next;
+    }
+    state.index = (state.index + 1) % state.cardinality;
+    set_observation(env, state.index, &observation);
+    set_observation_state(env, &state);
+    Ok(())
+}
+
+/// Grow the buffer to `cardinality` slots, taking effect once it next wraps
+///
+/// Returns the previous and the new `cardinality_next`. Never shrinks.
+pub fn grow(env: &Env, cardinality: u32) -> Result<(u32, u32), AstroSwapError> {
+    if cardinality == 0 || cardinality > MAX_OBSERVATION_CARDINALITY {
+        return Err(AstroSwapError::InvalidArgument);
+    }
+    let mut state = get_observation_state(env).unwrap_or_else(|| initialize(env));
+    let previous = state.cardinality_next;
+    if cardinality > previous {
+        state.cardinality_next = cardinality;
+        set_observation_state(env, &state);
+    }
+    Ok((previous, state.cardinality_next))
+}
+
+/// Cumulatives `seconds_ago` seconds before now for each entry
+///
+/// Points between two observations are interpolated; points after the
+/// latest one are extrapolated at the current reserves. A point older than
+/// the oldest observation fails with `QuoteStale`.
+pub fn observe(env: &Env, seconds_agos: &Vec<u64>) -> Result<Vec<Observation>, AstroSwapError> {
+    if seconds_agos.is_empty() || seconds_agos.len() > MAX_OBSERVE_POINTS {
+        return Err(AstroSwapError::InvalidArgument);
+    }
+    let state = get_observation_state(env).ok_or(AstroSwapError::QuoteStale)?;
+    let now = env.ledger().timestamp();
+    let reserves = get_reserves(env);
+
+    let mut observations = Vec::new(env);
+    for seconds_ago in seconds_agos.iter() {
+        let target = now
+            .checked_sub(seconds_ago)
+            .ok_or(AstroSwapError::InvalidArgument)?;
+        observations.push_back(observe_at(env, &state, target, reserves)?);
+    }
+    Ok(observations)
+}
+
+/// Cumulatives at `target`, which is at or before now
+fn observe_at(
+    env: &Env,
+    state: &ObservationState,
+    target: u64,
+    reserves: (i128, i128),
+) -> Result<Observation, AstroSwapError> {
+    let last = get_observation(env, state.index).ok_or(AstroSwapError::QuoteStale)?;
+    if target >= last.timestamp {
+        return transform(&last, target, reserves);
+    }
+
+    // Until the buffer has wrapped once, the slot after the latest one is
+    // still empty and the oldest observation sits in slot 0
+    let next = (state.index + 1) % state.cardinality;
+    let (oldest_index, len) = match get_observation(env, next) {
+        Some(_) => (next, state.cardinality),
+        None => (0, state.index + 1),
+    };
+    let at = |position: u32| {
+        get_observation(env, (oldest_index + position) % state.cardinality)
+            .ok_or(AstroSwapError::QuoteStale)
+    };
+    if target < at(0)?.timestamp {
+        return Err(AstroSwapError::QuoteStale);
+    }
+
+    // Binary search for the observations around `target`:
+    // at(low).timestamp <= target < at(high).timestamp
+    let (mut low, mut high) = (0, len - 1);
+    while high - low > 1 {
+        let mid = low + (high - low) / 2;
+        if at(mid)?.timestamp <= target {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    let before = at(low)?;
+    if before.timestamp == target {
+        return Ok(before);
+    }
+    let after = at(high)?;
+
+    // Prices are constant between two observations, so the cumulatives grow
+    // linearly from one to the next
+    let span = i128::from(after.timestamp - before.timestamp);
+    let elapsed = i128::from(target - before.timestamp);
+    let interpolate = |from: i128, to: i128| {
+        from.wrapping_add((to.wrapping_sub(from) / span).wrapping_mul(elapsed))
+    };
+    Ok(Observation {
+        timestamp: target,
+        price_0_cumulative: interpolate(before.price_0_cumulative, after.price_0_cumulative),
+        price_1_cumulative: interpolate(before.price_1_cumulative, after.price_1_cumulative),
+    })
+}
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address, Env};
+use soroban_sdk::{contracttype, Address, Env, String};
 
 /// Storage keys for the pair contract
 #[contracttype]
@@ -14,27 +14,113 @@ pub enum DataKey {
     KLast, // k = reserve0 * reserve1, for protocol fee calculation
     FeeBps,
     Initialized,
-    Locked, // Reentrancy lock for extra security
-    Paused, // Emergency pause mechanism
+    LockLedger,       // Ledger sequence the reentrancy lock was taken in
+    Paused,           // Emergency pause mechanism
+    LpName,           // Per-pair LP token name
+    LpSymbol,         // Per-pair LP token symbol
+    Treasury,         // Recipient of balances donated outside the reserves
+    MaxReserve,       // Cap on each reserve (0 = uncapped)
+    MinReserve,       // Floor swaps may not drain a reserve below (0 = none)
+    LargeWithdrawal,  // Request-and-delay mode for large withdrawals
+    Analytics,        // Analytics contract swaps are reported to
+    ObservationState, // Ring buffer position of the price observations
 
     // Persistent storage (user data)
     Balance(Address),
     Allowance(Address, Address),
+    WithdrawalRequest(Address), // Pending large withdrawal of an LP
+    Withdrawn(Address),         // Unrequested withdrawals of an LP in the current period
+    TotalWithdrawn,             // Unrequested withdrawals of all LPs in the current period
+    Observation(u32),           // Price observation ring buffer slot
 }
 
-// ==================== Reentrancy Lock ====================
+/// Request-and-delay mode for withdrawals of a large share of the reserves
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LargeWithdrawalConfig {
+    /// Withdrawals above this share of the reserves need a request
+    /// (basis points, 0 = mode disabled)
+    pub threshold_bps: u32,
+    /// Time between a request and the earliest withdrawal
+    pub delay_seconds: u64,
+    /// Time after the delay during which the request can be executed
+    pub window_seconds: u64,
+}
 
-/// Check if the contract is locked (reentrancy protection)
-pub fn is_locked(env: &Env) -> bool {
-    env.storage()
-        .instance()
-        .get::<DataKey, bool>(&DataKey::Locked)
-        .unwrap_or(false)
+/// Announced large withdrawal
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WithdrawalRequest {
+    /// LP shares the request covers
+    pub shares: i128,
+    /// Earliest withdrawal time
+    pub ready_at: u64,
+    /// Latest withdrawal time
+    pub expires_at: u64,
+}
+
+/// LP shares withdrawn without a request in one large-withdrawal period
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct WithdrawnShares {
+    /// Start of the period
+    pub period_start: u64,
+    /// Shares withdrawn since `period_start`
+    pub shares: i128,
+}
+
+/// Quote for selling a share of one reserve into the pair
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DepthQuote {
+    pub amount_in: i128,
+    /// Output after the swap fee
+    pub amount_out: i128,
+    /// Average price of one whole input token in output tokens over the
+    /// trade, fee included (7 decimals)
+    pub price: i128,
+    /// Price impact of the trade, excluding the fee (basis points)
+    pub price_impact_bps: u32,
 }
 
-/// Set the lock state
-pub fn set_locked(env: &Env, locked: bool) {
-    env.storage().instance().set(&DataKey::Locked, &locked);
+/// One rung of a depth ladder, in both directions
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DepthLevel {
+    /// Trade size as a share of the input reserve (basis points)
+    pub size_bps: u32,
+    /// token_0 sold for token_1
+    pub sell_0: DepthQuote,
+    /// token_1 sold for token_0
+    pub sell_1: DepthQuote,
+}
+
+/// Price accumulators at a point in time
+///
+/// Each cumulative is the sum of the spot price (raw reserve ratio, 7
+/// decimals) times the seconds it held. Cumulatives wrap on overflow, so
+/// subtract them with `wrapping_sub`; the TWAP between two observations is
+/// the difference divided by the seconds between them.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Observation {
+    pub timestamp: u64,
+    /// Accumulated price of token_0 in token_1
+    pub price_0_cumulative: i128,
+    /// Accumulated price of token_1 in token_0
+    pub price_1_cumulative: i128,
+}
+
+/// Position and size of the observation ring buffer
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ObservationState {
+    /// Slot holding the most recent observation
+    pub index: u32,
+    /// Slots in use
+    pub cardinality: u32,
+    /// Slots to use once the buffer next wraps
+    pub cardinality_next: u32,
 }
 
 // ==================== Pause Mechanism ====================
@@ -154,6 +240,25 @@ pub fn set_total_supply(env: &Env, supply: i128) {
     env.storage().instance().set(&DataKey::TotalSupply, &supply);
 }
 
+/// Get the LP token name and symbol set at initialization
+pub fn get_lp_metadata(env: &Env) -> Option<(String, String)> {
+    let name = env
+        .storage()
+        .instance()
+        .get::<DataKey, String>(&DataKey::LpName)?;
+    let symbol = env
+        .storage()
+        .instance()
+        .get::<DataKey, String>(&DataKey::LpSymbol)?;
+    Some((name, symbol))
+}
+
+/// Set the LP token name and symbol
+pub fn set_lp_metadata(env: &Env, name: &String, symbol: &String) {
+    env.storage().instance().set(&DataKey::LpName, name);
+    env.storage().instance().set(&DataKey::LpSymbol, symbol);
+}
+
 /// Get k_last (for protocol fee calculation)
 pub fn get_k_last(env: &Env) -> i128 {
     env.storage()
@@ -180,6 +285,157 @@ pub fn set_fee_bps(env: &Env, fee: u32) {
     env.storage().instance().set(&DataKey::FeeBps, &fee);
 }
 
+/// Get the treasury that receives skimmed surplus, if set
+pub fn get_treasury(env: &Env) -> Option<Address> {
+    env.storage()
+        .instance()
+        .get::<DataKey, Address>(&DataKey::Treasury)
+}
+
+/// Set the treasury that receives skimmed surplus
+pub fn set_treasury(env: &Env, treasury: &Address) {
+    env.storage().instance().set(&DataKey::Treasury, treasury);
+}
+
+/// Get the analytics contract swaps are reported to
+pub fn get_analytics(env: &Env) -> Option<Address> {
+    env.storage()
+        .instance()
+        .get::<DataKey, Address>(&DataKey::Analytics)
+}
+
+/// Set or clear the analytics contract swaps are reported to
+pub fn set_analytics(env: &Env, analytics: &Option<Address>) {
+    match analytics {
+        Some(analytics) => env.storage().instance().set(&DataKey::Analytics, analytics),
+        None => env.storage().instance().remove(&DataKey::Analytics),
+    }
+}
+
+/// Get the reserve cap and floor (0 = unset)
+pub fn get_reserve_bounds(env: &Env) -> (i128, i128) {
+    let max_reserve = env
+        .storage()
+        .instance()
+        .get::<DataKey, i128>(&DataKey::MaxReserve)
+        .unwrap_or(0);
+    let min_reserve = env
+        .storage()
+        .instance()
+        .get::<DataKey, i128>(&DataKey::MinReserve)
+        .unwrap_or(0);
+    (max_reserve, min_reserve)
+}
+
+/// Set the reserve cap and floor
+pub fn set_reserve_bounds(env: &Env, max_reserve: i128, min_reserve: i128) {
+    env.storage()
+        .instance()
+        .set(&DataKey::MaxReserve, &max_reserve);
+    env.storage()
+        .instance()
+        .set(&DataKey::MinReserve, &min_reserve);
+}
+
+// ==================== Large Withdrawal Storage ====================
+
+/// Get the large-withdrawal mode (disabled unless set)
+pub fn get_large_withdrawal(env: &Env) -> LargeWithdrawalConfig {
+    env.storage()
+        .instance()
+        .get::<DataKey, LargeWithdrawalConfig>(&DataKey::LargeWithdrawal)
+        .unwrap_or_default()
+}
+
+/// Set the large-withdrawal mode
+pub fn set_large_withdrawal(env: &Env, config: &LargeWithdrawalConfig) {
+    env.storage()
+        .instance()
+        .set(&DataKey::LargeWithdrawal, config);
+}
+
+/// Get an LP's pending large withdrawal
+pub fn get_withdrawal_request(env: &Env, user: &Address) -> Option<WithdrawalRequest> {
+    env.storage()
+        .persistent()
+        .get::<DataKey, WithdrawalRequest>(&DataKey::WithdrawalRequest(user.clone()))
+}
+
+/// Record an LP's pending large withdrawal
+pub fn set_withdrawal_request(env: &Env, user: &Address, request: &WithdrawalRequest) {
+    let key = DataKey::WithdrawalRequest(user.clone());
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, request);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+/// Remove an LP's pending large withdrawal
+pub fn remove_withdrawal_request(env: &Env, user: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::WithdrawalRequest(user.clone()));
+}
+
+/// Get the shares withdrawn without a request, by `user` or by all LPs
+pub fn get_withdrawn(env: &Env, user: Option<&Address>) -> WithdrawnShares {
+    let key = match user {
+        Some(user) => DataKey::Withdrawn(user.clone()),
+        None => DataKey::TotalWithdrawn,
+    };
+    env.storage()
+        .persistent()
+        .get::<DataKey, WithdrawnShares>(&key)
+        .unwrap_or_default()
+}
+
+/// Record the shares withdrawn without a request, by `user` or by all LPs
+pub fn set_withdrawn(env: &Env, user: Option<&Address>, withdrawn: &WithdrawnShares) {
+    let key = match user {
+        Some(user) => DataKey::Withdrawn(user.clone()),
+        None => DataKey::TotalWithdrawn,
+    };
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, withdrawn);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+// ==================== Observation Storage ====================
+
+/// Get the observation ring buffer position, if observations were started
+pub fn get_observation_state(env: &Env) -> Option<ObservationState> {
+    env.storage()
+        .instance()
+        .get::<DataKey, ObservationState>(&DataKey::ObservationState)
+}
+
+/// Set the observation ring buffer position
+pub fn set_observation_state(env: &Env, state: &ObservationState) {
+    env.storage()
+        .instance()
+        .set(&DataKey::ObservationState, state);
+}
+
+/// Get the observation in a ring buffer slot, if it was ever written
+pub fn get_observation(env: &Env, index: u32) -> Option<Observation> {
+    env.storage()
+        .persistent()
+        .get::<DataKey, Observation>(&DataKey::Observation(index))
+}
+
+/// Write a ring buffer slot
+pub fn set_observation(env: &Env, index: u32, observation: &Observation) {
+    let key = DataKey::Observation(index);
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, observation);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
 // ==================== LP Token Storage ====================
 
 /// Get LP token balance for an address
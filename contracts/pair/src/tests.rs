@@ -1,8 +1,15 @@
 use crate::contract::{AstroSwapPair, AstroSwapPairClient};
+use crate::observations::MAX_OBSERVATION_CARDINALITY;
+use crate::storage::{DataKey, Observation};
+use crate::token::{truncate_symbol, Approve, Burn, Mint, Transfer};
+use astroswap_shared::{AstroSwapError, MINIMUM_LIQUIDITY};
 use soroban_sdk::{
-    testutils::Address as _,
+    contract, contractimpl,
+    testutils::{Address as _, Events, Ledger},
     token::{Client as TokenClient, StellarAssetClient},
-    Address, Env,
+    vec,
+    xdr::{ContractEventBody, ScSymbol, ScVal},
+    Address, Env, Event, String,
 };
 
 // Future deadline for swap tests (very far in the future)
@@ -118,6 +125,64 @@ fn test_double_initialize_fails() {
     assert!(result.is_err());
 }
 
+// ==================== LP Metadata Tests ====================
+
+#[test]
+fn test_lp_metadata_from_underlying_symbols() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (pair_client, token_0_client, token_1_client, _, _, _) = setup_pair_with_liquidity(&env);
+
+    let mut symbol_0 = [0u8; 32];
+    let mut symbol_1 = [0u8; 32];
+    let len_0 = token_0_client.symbol().len() as usize;
+    let len_1 = token_1_client.symbol().len() as usize;
+    token_0_client.symbol().copy_into_slice(&mut symbol_0[..len_0]);
+    token_1_client.symbol().copy_into_slice(&mut symbol_1[..len_1]);
+
+    let mut expected = [0u8; 64];
+    let mut len = 0;
+    for part in [
+        b"ASTRO-LP ".as_slice(),
+        truncate_symbol(&symbol_0[..len_0]),
+        b"/",
+        truncate_symbol(&symbol_1[..len_1]),
+    ] {
+        expected[len..len + part.len()].copy_from_slice(part);
+        len += part.len();
+    }
+    assert_eq!(
+        pair_client.symbol(),
+        String::from_bytes(&env, &expected[..len])
+    );
+}
+
+#[test]
+fn test_lp_metadata_without_token_symbols() {
+    let env = Env::default();
+
+    // Plain addresses have no token contract to ask
+    let contract_id = env.register(AstroSwapPair, ());
+    let client = AstroSwapPairClient::new(&env, &contract_id);
+    client.initialize(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &Address::generate(&env),
+    );
+
+    assert_eq!(client.name(), String::from_str(&env, "AstroSwap LP Token ?/?"));
+    assert_eq!(client.symbol(), String::from_str(&env, "ASTRO-LP ?/?"));
+}
+
+#[test]
+fn test_truncate_symbol() {
+    assert_eq!(truncate_symbol(b"XLM"), b"XLM");
+    assert_eq!(truncate_symbol(b"SHIBAINUX"), b"SHIBAINU");
+    // Never splits a multi-byte character: "ABCDEFG" + "é" is 9 bytes
+    assert_eq!(truncate_symbol("ABCDEFGé".as_bytes()), b"ABCDEFG");
+}
+
 // ==================== Deposit Tests ====================
 
 #[test]
@@ -131,7 +196,7 @@ fn test_first_deposit_success() {
     let amount_0 = 100_0000000i128; // 100 tokens
     let amount_1 = 100_0000000i128;
 
-    let result = pair_client.deposit(&user, &amount_0, &amount_1, &0, &0);
+    let result = pair_client.deposit(&user, &amount_0, &amount_1, &0, &0, &FAR_FUTURE_DEADLINE);
 
     // First deposit uses all amounts
     assert_eq!(result.0, amount_0);
@@ -156,10 +221,10 @@ fn test_subsequent_deposit_maintains_ratio() {
     let (pair_client, _, _, _, _, user) = setup_pair_with_liquidity(&env);
 
     // First deposit
-    pair_client.deposit(&user, &100_0000000, &200_0000000, &0, &0);
+    pair_client.deposit(&user, &100_0000000, &200_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
 
     // Second deposit - should maintain 1:2 ratio
-    let result = pair_client.deposit(&user, &50_0000000, &200_0000000, &0, &0);
+    let result = pair_client.deposit(&user, &50_0000000, &200_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
 
     // Should get optimal amounts based on ratio
     // With 1:2 ratio, 50 token_0 should pair with 100 token_1
@@ -174,7 +239,7 @@ fn test_deposit_with_zero_amount_fails() {
 
     let (pair_client, _, _, _, _, user) = setup_pair_with_liquidity(&env);
 
-    let result = pair_client.try_deposit(&user, &0, &100_0000000, &0, &0);
+    let result = pair_client.try_deposit(&user, &0, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
     assert!(result.is_err());
 }
 
@@ -185,7 +250,7 @@ fn test_deposit_with_negative_amount_fails() {
 
     let (pair_client, _, _, _, _, user) = setup_pair_with_liquidity(&env);
 
-    let result = pair_client.try_deposit(&user, &-100, &100_0000000, &0, &0);
+    let result = pair_client.try_deposit(&user, &-100, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
     assert!(result.is_err());
 }
 
@@ -197,7 +262,7 @@ fn test_deposit_below_minimum_fails() {
     let (pair_client, _, _, _, _, user) = setup_pair_with_liquidity(&env);
 
     // First deposit
-    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
 
     // Second deposit with minimum too high
     let result = pair_client.try_deposit(
@@ -206,6 +271,7 @@ fn test_deposit_below_minimum_fails() {
         &50_0000000,
         &50_0000000, // min_0 = desired_0, OK
         &100_0000000, // min_1 much higher than optimal
+        &FAR_FUTURE_DEADLINE,
     );
     assert!(result.is_err());
 }
@@ -221,13 +287,13 @@ fn test_withdraw_success() {
         setup_pair_with_liquidity(&env);
 
     // First deposit
-    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
 
     let user_lp = pair_client.balance(&user);
     let half_lp = user_lp / 2;
 
     // Withdraw half
-    let result = pair_client.withdraw(&user, &half_lp, &0, &0);
+    let result = pair_client.withdraw(&user, &half_lp, &0, &0, &FAR_FUTURE_DEADLINE);
 
     assert!(result.0 > 0);
     assert!(result.1 > 0);
@@ -244,12 +310,12 @@ fn test_withdraw_all() {
     let (pair_client, _, _, _, _, user) = setup_pair_with_liquidity(&env);
 
     // Deposit
-    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
 
     let user_lp = pair_client.balance(&user);
 
     // Withdraw all
-    pair_client.withdraw(&user, &user_lp, &0, &0);
+    pair_client.withdraw(&user, &user_lp, &0, &0, &FAR_FUTURE_DEADLINE);
 
     // User should have 0 LP tokens
     assert_eq!(pair_client.balance(&user), 0);
@@ -262,9 +328,9 @@ fn test_withdraw_zero_fails() {
 
     let (pair_client, _, _, _, _, user) = setup_pair_with_liquidity(&env);
 
-    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
 
-    let result = pair_client.try_withdraw(&user, &0, &0, &0);
+    let result = pair_client.try_withdraw(&user, &0, &0, &0, &FAR_FUTURE_DEADLINE);
     assert!(result.is_err());
 }
 
@@ -275,11 +341,11 @@ fn test_withdraw_more_than_balance_fails() {
 
     let (pair_client, _, _, _, _, user) = setup_pair_with_liquidity(&env);
 
-    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
 
     let user_lp = pair_client.balance(&user);
 
-    let result = pair_client.try_withdraw(&user, &(user_lp + 1), &0, &0);
+    let result = pair_client.try_withdraw(&user, &(user_lp + 1), &0, &0, &FAR_FUTURE_DEADLINE);
     assert!(result.is_err());
 }
 
@@ -290,15 +356,41 @@ fn test_withdraw_below_minimum_fails() {
 
     let (pair_client, _, _, _, _, user) = setup_pair_with_liquidity(&env);
 
-    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
 
     let user_lp = pair_client.balance(&user);
 
     // Set minimum higher than what we'd receive
-    let result = pair_client.try_withdraw(&user, &user_lp, &200_0000000, &0);
+    let result = pair_client.try_withdraw(&user, &user_lp, &200_0000000, &0, &FAR_FUTURE_DEADLINE);
     assert!(result.is_err());
 }
 
+#[test]
+fn test_deposit_and_withdraw_respect_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (pair_client, _, _, _, _, user) = setup_pair_with_liquidity(&env);
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+    // A deadline equal to the current timestamp is still valid
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &1_000);
+    let user_lp = pair_client.balance(&user);
+
+    assert_eq!(
+        pair_client.try_deposit(&user, &10_0000000, &10_0000000, &0, &0, &999),
+        Err(Ok(AstroSwapError::DeadlineExpired))
+    );
+    assert_eq!(
+        pair_client.try_withdraw(&user, &user_lp, &0, &0, &999),
+        Err(Ok(AstroSwapError::DeadlineExpired))
+    );
+    assert_eq!(pair_client.balance(&user), user_lp);
+
+    pair_client.withdraw(&user, &user_lp, &0, &0, &1_000);
+    assert_eq!(pair_client.balance(&user), 0);
+}
+
 // ==================== Swap Tests ====================
 
 #[test]
@@ -309,7 +401,7 @@ fn test_swap_success() {
     let (pair_client, _, _, token_0_addr, _, user) = setup_pair_with_liquidity(&env);
 
     // Add liquidity first
-    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
 
     // Swap
     let amount_in = 10_0000000i128;
@@ -327,7 +419,7 @@ fn test_swap_respects_slippage() {
 
     let (pair_client, _, _, token_0_addr, _, user) = setup_pair_with_liquidity(&env);
 
-    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
 
     // Try swap with very high minimum - should fail
     let result = pair_client.try_swap(&user, &token_0_addr, &10_0000000, &50_0000000, &FAR_FUTURE_DEADLINE);
@@ -341,7 +433,7 @@ fn test_swap_zero_amount_fails() {
 
     let (pair_client, _, _, token_0_addr, _, user) = setup_pair_with_liquidity(&env);
 
-    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
 
     let result = pair_client.try_swap(&user, &token_0_addr, &0, &0, &FAR_FUTURE_DEADLINE);
     assert!(result.is_err());
@@ -354,7 +446,7 @@ fn test_swap_invalid_token_fails() {
 
     let (pair_client, _, _, _, _, user) = setup_pair_with_liquidity(&env);
 
-    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
 
     let invalid_token = Address::generate(&env);
     let result = pair_client.try_swap(&user, &invalid_token, &10_0000000, &0, &FAR_FUTURE_DEADLINE);
@@ -368,7 +460,7 @@ fn test_swap_maintains_k_invariant() {
 
     let (pair_client, _, _, token_0_addr, _, user) = setup_pair_with_liquidity(&env);
 
-    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
 
     let k_before = pair_client.k_last();
 
@@ -380,6 +472,213 @@ fn test_swap_maintains_k_invariant() {
     assert!(k_after >= k_before);
 }
 
+// ==================== TWAP Guard Tests ====================
+
+// Oracle stub serving fixed TWAPs; panics for tokens without one
+#[contract]
+struct MockTwapOracle;
+
+#[contractimpl]
+impl MockTwapOracle {
+    pub fn set_twap(env: Env, token: Address, price: i128) {
+        env.storage().instance().set(&token, &price);
+    }
+
+    pub fn get_twap(env: Env, token: Address, _window: u64) -> i128 {
+        env.storage().instance().get(&token).unwrap()
+    }
+}
+
+#[test]
+fn test_swap_with_twap_guard() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (pair_client, _, _, token_0_addr, token_1_addr, user) = setup_pair_with_liquidity(&env);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
+
+    let oracle = env.register(MockTwapOracle, ());
+    let oracle_client = MockTwapOracleClient::new(&env, &oracle);
+    oracle_client.set_twap(&token_0_addr, &1_0000000);
+    oracle_client.set_twap(&token_1_addr, &1_0000000);
+
+    // 1% of the pool: ~1.3% below the 1:1 TWAP (fee + price impact)
+    let amount_in = 1_0000000i128;
+    let result = pair_client.try_swap_with_twap_guard(
+        &user,
+        &token_0_addr,
+        &amount_in,
+        &0,
+        &50,
+        &oracle,
+        &FAR_FUTURE_DEADLINE,
+    );
+    assert_eq!(result, Err(Ok(AstroSwapError::TwapDeviationExceeded)));
+
+    let out = pair_client.swap_with_twap_guard(
+        &user,
+        &token_0_addr,
+        &amount_in,
+        &0,
+        &200,
+        &oracle,
+        &FAR_FUTURE_DEADLINE,
+    );
+    assert!(out > 0 && out < amount_in);
+}
+
+#[test]
+fn test_swap_with_twap_guard_rejects_off_oracle_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (pair_client, _, _, token_0_addr, token_1_addr, user) = setup_pair_with_liquidity(&env);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
+
+    let oracle = env.register(MockTwapOracle, ());
+    let oracle_client = MockTwapOracleClient::new(&env, &oracle);
+
+    // No TWAP for token 1 yet: the guard fails closed
+    oracle_client.set_twap(&token_0_addr, &2_0000000);
+    let result = pair_client.try_swap_with_twap_guard(
+        &user,
+        &token_0_addr,
+        &1_0000000,
+        &0,
+        &1_000,
+        &oracle,
+        &FAR_FUTURE_DEADLINE,
+    );
+    assert_eq!(result, Err(Ok(AstroSwapError::QuoteStale)));
+
+    // TWAP says token 0 is worth 2x token 1 while the pool trades 1:1
+    oracle_client.set_twap(&token_1_addr, &1_0000000);
+    let result = pair_client.try_swap_with_twap_guard(
+        &user,
+        &token_0_addr,
+        &1_0000000,
+        &0,
+        &1_000,
+        &oracle,
+        &FAR_FUTURE_DEADLINE,
+    );
+    assert_eq!(result, Err(Ok(AstroSwapError::TwapDeviationExceeded)));
+}
+
+// Minimal 18-decimal token (Stellar assets have 7)
+#[contract]
+struct MockToken18;
+
+#[contractimpl]
+impl MockToken18 {
+    pub fn mint(env: Env, to: Address, amount: i128) {
+        let balance = Self::balance(env.clone(), to.clone());
+        env.storage().instance().set(&to, &(balance + amount));
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage().instance().get(&id).unwrap_or(0)
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        from.require_auth();
+        let balance = Self::balance(env.clone(), from.clone());
+        env.storage().instance().set(&from, &(balance - amount));
+        Self::mint(env, to, amount);
+    }
+
+    pub fn decimals(_env: Env) -> u32 {
+        18
+    }
+}
+
+#[test]
+fn test_swap_with_twap_guard_mixed_decimals() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let (_, token_7) = create_token(&env, &admin);
+    let token_18 = env.register(MockToken18, ());
+    mint_token(&env, &token_7, &admin, &user, 1_000_0000000);
+    MockToken18Client::new(&env, &token_18).mint(&user, &1_000_000_000_000_000_000_000);
+
+    let pair_client = AstroSwapPairClient::new(&env, &env.register(AstroSwapPair, ()));
+    pair_client.initialize(&Address::generate(&env), &token_7, &token_18);
+    pair_client.deposit(
+        &user,
+        &100_0000000,
+        &100_000_000_000_000_000_000,
+        &0,
+        &0,
+        &FAR_FUTURE_DEADLINE,
+    );
+
+    // Both tokens are worth the same, as is 1 whole unit of each in the pool
+    let oracle = env.register(MockTwapOracle, ());
+    let oracle_client = MockTwapOracleClient::new(&env, &oracle);
+    oracle_client.set_twap(&token_7, &1_0000000);
+    oracle_client.set_twap(&token_18, &1_0000000);
+
+    // 1 token each way lands 0.5-2% off the TWAP (fee + price impact)
+    for (token_in, amount_in) in [
+        (&token_7, 1_0000000i128),
+        (&token_18, 1_000_000_000_000_000_000),
+    ] {
+        let result = pair_client.try_swap_with_twap_guard(
+            &user,
+            token_in,
+            &amount_in,
+            &0,
+            &50,
+            &oracle,
+            &FAR_FUTURE_DEADLINE,
+        );
+        assert_eq!(result, Err(Ok(AstroSwapError::TwapDeviationExceeded)));
+
+        let out = pair_client.swap_with_twap_guard(
+            &user,
+            token_in,
+            &amount_in,
+            &0,
+            &200,
+            &oracle,
+            &FAR_FUTURE_DEADLINE,
+        );
+        assert!(out > 0);
+    }
+}
+
+// ==================== Reentrancy Guard Tests ====================
+
+#[test]
+fn test_reentrancy_lock_expires_with_ledger() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (pair_client, _, _, token_0_addr, _, user) = setup_pair_with_liquidity(&env);
+    pair_client.deposit(&user, &10_000_000_000, &10_000_000_000, &0, &0, &FAR_FUTURE_DEADLINE);
+
+    // A lock held in the current ledger rejects the call with `Reentrancy`
+    let sequence = env.ledger().sequence();
+    env.as_contract(&pair_client.address, || {
+        env.storage()
+            .instance()
+            .set(&DataKey::LockLedger, &sequence);
+    });
+    let result = pair_client.try_swap(&user, &token_0_addr, &10_0000000, &0, &FAR_FUTURE_DEADLINE);
+    assert_eq!(result, Err(Ok(AstroSwapError::Reentrancy)));
+
+    // A lock left over from an earlier ledger is treated as released
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+    let out = pair_client.swap(&user, &token_0_addr, &10_0000000, &0, &FAR_FUTURE_DEADLINE);
+    assert!(out > 0);
+    env.as_contract(&pair_client.address, || {
+        assert!(!env.storage().instance().has(&DataKey::LockLedger));
+    });
+}
+
 // ==================== Pause Tests ====================
 
 #[test]
@@ -394,7 +693,7 @@ fn test_pause_blocks_deposit() {
     assert!(pair_client.is_paused());
 
     // Deposit should fail
-    let result = pair_client.try_deposit(&user, &100_0000000, &100_0000000, &0, &0);
+    let result = pair_client.try_deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
     assert!(result.is_err());
 }
 
@@ -406,7 +705,7 @@ fn test_pause_blocks_swap() {
     let (pair_client, _, _, token_0_addr, _, user) = setup_pair_with_liquidity(&env);
 
     // Deposit before pausing
-    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
 
     // Pause
     pair_client.set_paused(&true);
@@ -424,7 +723,7 @@ fn test_unpause_restores_functionality() {
     let (pair_client, _, _, token_0_addr, _, user) = setup_pair_with_liquidity(&env);
 
     // Deposit
-    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
 
     // Pause then unpause
     pair_client.set_paused(&true);
@@ -445,7 +744,7 @@ fn test_lp_token_transfer() {
 
     let (pair_client, _, _, _, _, user) = setup_pair_with_liquidity(&env);
 
-    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
 
     let user_lp = pair_client.balance(&user);
     let recipient = Address::generate(&env);
@@ -463,7 +762,7 @@ fn test_lp_token_approve_and_transfer_from() {
 
     let (pair_client, _, _, _, _, user) = setup_pair_with_liquidity(&env);
 
-    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
 
     let user_lp = pair_client.balance(&user);
     let spender = Address::generate(&env);
@@ -478,6 +777,90 @@ fn test_lp_token_approve_and_transfer_from() {
     assert_eq!(pair_client.balance(&recipient), user_lp);
 }
 
+#[test]
+fn test_lp_token_sep41_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (pair_client, _, _, _, _, user) = setup_pair_with_liquidity(&env);
+    let pair = pair_client.address.clone();
+
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
+    let deposit_events = env.events().all().filter_by_contract(&pair);
+    let user_lp = pair_client.balance(&user);
+    let spender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    // Topics ["mint", to], data amount; the first deposit also locks the
+    // minimum liquidity in the pair
+    let mint_topic = ScVal::Symbol(ScSymbol("mint".try_into().unwrap()));
+    let mut mints = deposit_events
+        .events()
+        .iter()
+        .filter(|event| match &event.body {
+            ContractEventBody::V0(body) => body.topics.first() == Some(&mint_topic),
+        });
+    let locked = Mint {
+        to: pair.clone(),
+        amount: MINIMUM_LIQUIDITY,
+    };
+    let minted = Mint {
+        to: user.clone(),
+        amount: user_lp,
+    };
+    assert_eq!(mints.next(), Some(&locked.to_xdr(&env, &pair)));
+    assert_eq!(mints.next(), Some(&minted.to_xdr(&env, &pair)));
+    assert_eq!(mints.next(), None);
+
+    // Topics ["transfer", from, to], data amount
+    pair_client.transfer(&user, &recipient, &100);
+    assert_eq!(
+        env.events().all().filter_by_contract(&pair),
+        [Transfer {
+            from: user.clone(),
+            to: recipient.clone(),
+            amount: 100,
+        }
+        .to_xdr(&env, &pair)]
+    );
+
+    // Topics ["approve", from, spender], data [amount, live_until_ledger]
+    pair_client.approve(&user, &spender, &user_lp);
+    assert_eq!(
+        env.events().all().filter_by_contract(&pair),
+        [Approve {
+            from: user.clone(),
+            spender: spender.clone(),
+            amount: user_lp,
+            live_until_ledger: u32::MAX,
+        }
+        .to_xdr(&env, &pair)]
+    );
+
+    // Spending an allowance is an ordinary transfer from the owner
+    pair_client.transfer_from(&spender, &user, &recipient, &200);
+    assert_eq!(
+        env.events().all().filter_by_contract(&pair),
+        [Transfer {
+            from: user.clone(),
+            to: recipient.clone(),
+            amount: 200,
+        }
+        .to_xdr(&env, &pair)]
+    );
+
+    // Topics ["burn", from], data amount
+    pair_client.burn(&recipient, &300);
+    assert_eq!(
+        env.events().all().filter_by_contract(&pair),
+        [Burn {
+            from: recipient.clone(),
+            amount: 300,
+        }
+        .to_xdr(&env, &pair)]
+    );
+}
+
 // ==================== View Function Tests ====================
 
 #[test]
@@ -487,7 +870,7 @@ fn test_get_amount_out() {
 
     let (pair_client, _, _, token_0_addr, _, user) = setup_pair_with_liquidity(&env);
 
-    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
 
     // Use try_get_amount_out which returns Result
     let amount_out = pair_client.try_get_amount_out(&10_0000000, &token_0_addr);
@@ -504,7 +887,7 @@ fn test_get_amount_in() {
 
     let (pair_client, _, _, _, token_1_addr, user) = setup_pair_with_liquidity(&env);
 
-    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
 
     // Use try_get_amount_in which returns Result
     let amount_in = pair_client.try_get_amount_in(&10_0000000, &token_1_addr);
@@ -514,6 +897,72 @@ fn test_get_amount_in() {
     assert!(amount_in.unwrap().unwrap() > 0);
 }
 
+#[test]
+fn test_quote_depth() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (pair_client, _, _, token_0_addr, token_1_addr, user) = setup_pair_with_liquidity(&env);
+
+    // Nothing to quote against yet
+    let levels = vec![&env, 10u32, 50, 100, 500];
+    assert_eq!(
+        pair_client.try_quote_depth(&levels),
+        Err(Ok(AstroSwapError::InsufficientLiquidity))
+    );
+
+    // 1 token_0 = 2 token_1
+    pair_client.deposit(&user, &100_0000000, &200_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
+    let depth = pair_client.quote_depth(&levels);
+    assert_eq!(depth.len(), 4);
+
+    let mut last_price = i128::MAX;
+    for (level, size_bps) in depth.iter().zip(levels.iter()) {
+        assert_eq!(level.size_bps, size_bps);
+
+        // Matches get_amount_out in both directions
+        let sell_0 = level.sell_0;
+        assert_eq!(
+            sell_0.amount_in,
+            100_0000000 * i128::from(size_bps) / 10_000
+        );
+        assert_eq!(
+            sell_0.amount_out,
+            pair_client.get_amount_out(&sell_0.amount_in, &token_0_addr)
+        );
+        let sell_1 = level.sell_1;
+        assert_eq!(
+            sell_1.amount_in,
+            200_0000000 * i128::from(size_bps) / 10_000
+        );
+        assert_eq!(
+            sell_1.amount_out,
+            pair_client.get_amount_out(&sell_1.amount_in, &token_1_addr)
+        );
+
+        // Larger trades execute at worse prices, below the 2.0 spot price
+        assert!(sell_0.price < 2_0000000 && sell_0.price < last_price);
+        assert!(sell_1.price < 5000000);
+        last_price = sell_0.price;
+    }
+    assert_eq!(depth.get(0).unwrap().sell_0.price_impact_bps, 9);
+    assert_eq!(depth.get(3).unwrap().sell_0.price_impact_bps, 476);
+
+    // Sizes must be within (0, 100%] and the ladder bounded
+    assert_eq!(
+        pair_client.try_quote_depth(&vec![&env, 0u32]),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+    assert_eq!(
+        pair_client.try_quote_depth(&vec![&env, 10_001u32]),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+    assert_eq!(
+        pair_client.try_quote_depth(&soroban_sdk::Vec::new(&env)),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+}
+
 #[test]
 fn test_get_info() {
     let env = Env::default();
@@ -521,7 +970,7 @@ fn test_get_info() {
 
     let (pair_client, _, _, token_0_addr, token_1_addr, user) = setup_pair_with_liquidity(&env);
 
-    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
 
     let info = pair_client.get_info();
 
@@ -533,6 +982,37 @@ fn test_get_info() {
     assert_eq!(info.fee_bps, 30);
 }
 
+#[test]
+fn test_position_of() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (pair_client, _, _, _, _, user) = setup_pair_with_liquidity(&env);
+    let other = Address::generate(&env);
+
+    assert_eq!(pair_client.position_of(&user), (0, 0, 0, 0));
+
+    pair_client.deposit(&user, &100_0000000, &200_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
+    let user_lp = pair_client.balance(&user);
+    pair_client.transfer(&user, &other, &(user_lp / 4));
+
+    let (lp_balance, share_bps, amount_0, amount_1) = pair_client.position_of(&user);
+    assert_eq!(lp_balance, user_lp - user_lp / 4);
+    assert_eq!(
+        share_bps,
+        (lp_balance * 10_000 / pair_client.total_supply()) as u32
+    );
+    // Just under 75% since the minimum liquidity is locked
+    assert!(share_bps > 7_400 && share_bps < 7_500);
+
+    // Matches what withdrawing the whole balance returns
+    assert_eq!(
+        pair_client.withdraw(&user, &lp_balance, &0, &0, &FAR_FUTURE_DEADLINE),
+        (amount_0, amount_1)
+    );
+    assert_eq!(pair_client.position_of(&user), (0, 0, 0, 0));
+}
+
 // ==================== Sync and Skim Tests ====================
 
 #[test]
@@ -542,7 +1022,7 @@ fn test_sync_updates_reserves() {
 
     let (pair_client, _, _, _, _, user) = setup_pair_with_liquidity(&env);
 
-    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
 
     // Sync should not change anything if balances match reserves
     pair_client.sync();
@@ -552,6 +1032,348 @@ fn test_sync_updates_reserves() {
     assert_eq!(reserve_1, 100_0000000);
 }
 
+#[test]
+fn test_donations_excluded_from_reserves_and_skimmed_to_treasury() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (pair_client, token_0_client, token_1_client, token_0_addr, token_1_addr, user) =
+        setup_pair_with_liquidity(&env);
+    let treasury = Address::generate(&env);
+
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
+    let quote_before = pair_client.get_amount_out(&10_0000000, &token_0_addr);
+
+    // A direct transfer moves neither the reserves nor the price
+    mint_token(&env, &token_0_addr, &user, &pair_client.address, 50_0000000);
+    assert_eq!(pair_client.get_reserves(), (100_0000000, 100_0000000));
+    assert_eq!(
+        pair_client.get_amount_out(&10_0000000, &token_0_addr),
+        quote_before
+    );
+
+    // Without a treasury the surplus stays in the pair, outside the reserves
+    let out = pair_client.swap(&user, &token_0_addr, &10_0000000, &0, &FAR_FUTURE_DEADLINE);
+    assert_eq!(out, quote_before);
+    assert_eq!(pair_client.get_reserves(), (110_0000000, 100_0000000 - out));
+    assert_eq!(token_0_client.balance(&pair_client.address), 160_0000000);
+
+    // With a treasury the next deposit or swap skims it
+    assert_eq!(pair_client.treasury(), None);
+    pair_client.set_treasury(&treasury);
+    assert_eq!(pair_client.treasury(), Some(treasury.clone()));
+
+    pair_client.swap(&user, &token_1_addr, &1_0000000, &0, &FAR_FUTURE_DEADLINE);
+    assert_eq!(token_0_client.balance(&treasury), 50_0000000);
+    let (reserve_0, reserve_1) = pair_client.get_reserves();
+    assert_eq!(token_0_client.balance(&pair_client.address), reserve_0);
+    assert_eq!(token_1_client.balance(&pair_client.address), reserve_1);
+
+    // Donations ahead of a deposit do not inflate LP share value either
+    mint_token(&env, &token_1_addr, &user, &pair_client.address, 5_0000000);
+    pair_client.deposit(&user, &10_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
+    assert_eq!(token_1_client.balance(&treasury), 5_0000000);
+    let (reserve_0, reserve_1) = pair_client.get_reserves();
+    assert_eq!(token_0_client.balance(&pair_client.address), reserve_0);
+    assert_eq!(token_1_client.balance(&pair_client.address), reserve_1);
+}
+
+#[test]
+fn test_swap_from_balance_ignores_output_side_donation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (pair_client, token_0_client, token_1_client, token_0_addr, token_1_addr, user) =
+        setup_pair_with_liquidity(&env);
+
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
+    let expected_out = pair_client.get_amount_out(&10_0000000, &token_0_addr);
+
+    // Donate the output token, then pre-transfer the input like the router does
+    mint_token(&env, &token_1_addr, &user, &pair_client.address, 20_0000000);
+    token_0_client.transfer(&user, &pair_client.address, &10_0000000);
+
+    let (amount_in, amount_out) =
+        pair_client.swap_from_balance(&user, &token_0_addr, &0, &FAR_FUTURE_DEADLINE);
+    assert_eq!(amount_in, 10_0000000);
+    assert_eq!(amount_out, expected_out);
+
+    // The donation stays outside the reserves
+    assert_eq!(
+        pair_client.get_reserves(),
+        (110_0000000, 100_0000000 - amount_out)
+    );
+    assert_eq!(
+        token_1_client.balance(&pair_client.address),
+        100_0000000 - amount_out + 20_0000000
+    );
+}
+
+#[test]
+fn test_reserve_cap_and_floor() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (pair_client, _, _, token_0_addr, token_1_addr, user) = setup_pair_with_liquidity(&env);
+
+    // Bounds must be non-negative with the floor below the cap
+    assert_eq!(pair_client.reserve_bounds(), (0, 0));
+    assert_eq!(
+        pair_client.try_set_reserve_bounds(&100_0000000, &100_0000000),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+    assert_eq!(
+        pair_client.try_set_reserve_bounds(&0, &-1),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+
+    // Cap each reserve at 200 tokens, keep at least 60 tokens in each
+    pair_client.set_reserve_bounds(&200_0000000, &60_0000000);
+    assert_eq!(pair_client.reserve_bounds(), (200_0000000, 60_0000000));
+
+    assert_eq!(
+        pair_client.try_deposit(&user, &250_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE),
+        Err(Ok(AstroSwapError::ReserveCapExceeded))
+    );
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
+
+    // Draining the output reserve below the floor is rejected
+    assert_eq!(
+        pair_client.try_swap(&user, &token_0_addr, &100_0000000, &0, &FAR_FUTURE_DEADLINE),
+        Err(Ok(AstroSwapError::ReserveBelowMinimum))
+    );
+    let out = pair_client.swap(&user, &token_0_addr, &50_0000000, &0, &FAR_FUTURE_DEADLINE);
+    assert_eq!(pair_client.get_reserves(), (150_0000000, 100_0000000 - out));
+
+    // So is pushing the input reserve above the cap
+    assert_eq!(
+        pair_client.try_swap(&user, &token_0_addr, &60_0000000, &0, &FAR_FUTURE_DEADLINE),
+        Err(Ok(AstroSwapError::ReserveCapExceeded))
+    );
+
+    // Swapping back towards balance is always allowed, and 0 lifts the bounds
+    pair_client.swap(&user, &token_1_addr, &10_0000000, &0, &FAR_FUTURE_DEADLINE);
+    pair_client.set_reserve_bounds(&0, &0);
+    pair_client.swap(&user, &token_0_addr, &100_0000000, &0, &FAR_FUTURE_DEADLINE);
+}
+
+#[test]
+fn test_large_withdrawal_request_and_delay() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (pair_client, _, _, _, _, user) = setup_pair_with_liquidity(&env);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
+    let advance = |seconds: u64| env.ledger().with_mut(|li| li.timestamp += seconds);
+
+    // The threshold is bounded and needs a delay and a window
+    assert_eq!(pair_client.large_withdrawal().threshold_bps, 0);
+    assert_eq!(
+        pair_client.try_set_large_withdrawal(&10_001, &3600, &600),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+    assert_eq!(
+        pair_client.try_set_large_withdrawal(&2_000, &0, &600),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+    assert_eq!(
+        pair_client.try_set_large_withdrawal(&2_000, &(30 * 86_400 + 1), &600),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+    assert_eq!(
+        pair_client.try_set_large_withdrawal(&2_000, &3600, &(30 * 86_400 + 1)),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+
+    // Withdrawals above 20% of the reserves per hour need a request an hour ahead
+    pair_client.set_large_withdrawal(&2_000, &3600, &600);
+    let other = Address::generate(&env);
+    pair_client.transfer(&user, &other, &1_000);
+    let total_supply = pair_client.total_supply();
+    pair_client.withdraw(&user, &(total_supply / 5), &0, &0, &FAR_FUTURE_DEADLINE);
+
+    // Further small withdrawals in the same hour count towards the threshold,
+    // from the same LP or any other
+    assert_eq!(
+        pair_client.try_withdraw(&user, &1_000, &0, &0, &FAR_FUTURE_DEADLINE),
+        Err(Ok(AstroSwapError::WithdrawalNotRequested))
+    );
+    assert_eq!(
+        pair_client.try_withdraw(&other, &1_000, &0, &0, &FAR_FUTURE_DEADLINE),
+        Err(Ok(AstroSwapError::WithdrawalNotRequested))
+    );
+
+    let shares = pair_client.balance(&user) / 2;
+    assert_eq!(
+        pair_client.try_withdraw(&user, &shares, &0, &0, &FAR_FUTURE_DEADLINE),
+        Err(Ok(AstroSwapError::WithdrawalNotRequested))
+    );
+
+    let now = env.ledger().timestamp();
+    let request = pair_client.request_withdrawal(&user, &shares);
+    assert_eq!(request.ready_at, now + 3600);
+    assert_eq!(request.expires_at, now + 4200);
+    assert_eq!(pair_client.withdrawal_request(&user), Some(request));
+    assert_eq!(
+        pair_client.try_withdraw(&user, &shares, &0, &0, &FAR_FUTURE_DEADLINE),
+        Err(Ok(AstroSwapError::WithdrawalDelayActive))
+    );
+
+    // Once ready, up to the requested shares can be withdrawn, once
+    advance(3600);
+    assert_eq!(
+        pair_client.try_withdraw(&user, &(shares + 1), &0, &0, &FAR_FUTURE_DEADLINE),
+        Err(Ok(AstroSwapError::WithdrawalNotRequested))
+    );
+    pair_client.withdraw(&user, &shares, &0, &0, &FAR_FUTURE_DEADLINE);
+    assert_eq!(pair_client.withdrawal_request(&user), None);
+
+    // A new hour starts a new total
+    pair_client.withdraw(&other, &1_000, &0, &0, &FAR_FUTURE_DEADLINE);
+
+    // Requests lapse after their window, and can be cancelled
+    let shares = pair_client.balance(&user) / 2;
+    pair_client.request_withdrawal(&user, &shares);
+    advance(4201);
+    assert_eq!(
+        pair_client.try_withdraw(&user, &shares, &0, &0, &FAR_FUTURE_DEADLINE),
+        Err(Ok(AstroSwapError::WithdrawalRequestExpired))
+    );
+    pair_client.cancel_withdrawal_request(&user);
+    assert_eq!(
+        pair_client.try_cancel_withdrawal_request(&user),
+        Err(Ok(AstroSwapError::WithdrawalNotRequested))
+    );
+
+    // Disabling the mode lifts the requirement
+    pair_client.set_large_withdrawal(&0, &0, &0);
+    assert_eq!(
+        pair_client.try_request_withdrawal(&user, &shares),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+    pair_client.withdraw(&user, &shares, &0, &0, &FAR_FUTURE_DEADLINE);
+}
+
+// ==================== Price Observation Tests ====================
+
+#[test]
+fn test_observe_price_cumulatives() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    // Observations start at initialization (t = 0)
+    let (pair_client, _, _, token_0, _, user) = setup_pair_with_liquidity(&env);
+    let set_time = |timestamp: u64| env.ledger().with_mut(|li| li.timestamp = timestamp);
+    pair_client.increase_observation_cardinality(&8);
+    let state = pair_client.observation_state().unwrap();
+    assert_eq!((state.cardinality, state.cardinality_next), (1, 8));
+    assert_eq!(
+        pair_client.try_increase_observation_cardinality(&(MAX_OBSERVATION_CARDINALITY + 1)),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+
+    // No liquidity until t = 100, then a price of 1.0 until the swap at t = 1_100
+    set_time(100);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
+    set_time(1_100);
+    pair_client.swap(&user, &token_0, &10_0000000, &0, &FAR_FUTURE_DEADLINE);
+    let (reserve_0, reserve_1) = pair_client.get_reserves();
+    let price_0 = reserve_1 * 10_000_000 / reserve_0;
+    let price_1 = reserve_0 * 10_000_000 / reserve_1;
+
+    set_time(2_100);
+    let observation = |timestamp, price_0_cumulative, price_1_cumulative| Observation {
+        timestamp,
+        price_0_cumulative,
+        price_1_cumulative,
+    };
+    assert_eq!(
+        pair_client.observe(&vec![&env, 2_000, 1_500, 1_000, 500, 0]),
+        vec![
+            &env,
+            // Recorded by the deposit
+            observation(100, 0, 0),
+            // Interpolated halfway to the swap
+            observation(600, 5_000_000_000, 5_000_000_000),
+            // Recorded by the swap
+            observation(1_100, 10_000_000_000, 10_000_000_000),
+            // Extrapolated at the current reserves
+            observation(
+                1_600,
+                10_000_000_000 + price_0 * 500,
+                10_000_000_000 + price_1 * 500
+            ),
+            observation(
+                2_100,
+                10_000_000_000 + price_0 * 1_000,
+                10_000_000_000 + price_1 * 1_000
+            ),
+        ]
+    );
+
+    // TWAP over the last 1_000 seconds is the post-swap price
+    let window = pair_client.observe(&vec![&env, 1_000, 0]);
+    let (start, end) = (window.get(0).unwrap(), window.get(1).unwrap());
+    assert_eq!(
+        end.price_0_cumulative
+            .wrapping_sub(start.price_0_cumulative)
+            / 1_000,
+        price_0
+    );
+
+    // Points before t = 0 and empty requests are rejected
+    assert_eq!(
+        pair_client.try_observe(&vec![&env, 0, 2_101]),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+    assert_eq!(
+        pair_client.try_observe(&vec![&env]),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+}
+
+#[test]
+fn test_observation_buffer_wraps_at_cardinality() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (pair_client, _, _, token_0, _, user) = setup_pair_with_liquidity(&env);
+    let set_time = |timestamp: u64| env.ledger().with_mut(|li| li.timestamp = timestamp);
+    let observed_at = |seconds_ago: u64| {
+        let observations = pair_client.observe(&vec![&env, seconds_ago]);
+        observations.get(0).unwrap().timestamp
+    };
+    set_time(100);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
+
+    // A single slot only keeps the latest observation
+    set_time(200);
+    pair_client.swap(&user, &token_0, &1_0000000, &0, &FAR_FUTURE_DEADLINE);
+    set_time(300);
+    assert_eq!(
+        pair_client.try_observe(&vec![&env, 150]),
+        Err(Ok(AstroSwapError::QuoteStale))
+    );
+    assert_eq!(observed_at(100), 200);
+
+    // Three slots keep the last three, once the buffer has wrapped into them
+    pair_client.increase_observation_cardinality(&3);
+    for timestamp in [300, 400, 500, 600] {
+        set_time(timestamp);
+        // Only the first change within a timestamp is recorded
+        pair_client.swap(&user, &token_0, &1_0000000, &0, &FAR_FUTURE_DEADLINE);
+        pair_client.swap(&user, &token_0, &1_0000000, &0, &FAR_FUTURE_DEADLINE);
+    }
+    let state = pair_client.observation_state().unwrap();
+    assert_eq!((state.index, state.cardinality), (1, 3));
+
+    assert_eq!(observed_at(200), 400);
+    assert_eq!(
+        pair_client.try_observe(&vec![&env, 201]),
+        Err(Ok(AstroSwapError::QuoteStale))
+    );
+}
+
 // ==================== Edge Cases ====================
 
 #[test]
@@ -562,7 +1384,7 @@ fn test_large_swap_high_price_impact() {
     let (pair_client, _, _, token_0_addr, _, user) = setup_pair_with_liquidity(&env);
 
     // Small pool
-    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0);
+    pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
 
     // Large swap (50% of pool)
     let amount_in = 50_0000000i128;
@@ -581,7 +1403,7 @@ fn test_multiple_swaps_in_same_direction() {
 
     let (pair_client, _, _, token_0_addr, _, user) = setup_pair_with_liquidity(&env);
 
-    pair_client.deposit(&user, &10_000_000_000, &10_000_000_000, &0, &0);
+    pair_client.deposit(&user, &10_000_000_000, &10_000_000_000, &0, &0, &FAR_FUTURE_DEADLINE);
 
     // Multiple small swaps
     let mut total_out = 0i128;
@@ -607,7 +1429,7 @@ fn test_minimum_liquidity_locked() {
     let (pair_client, _, _, _, _, user) = setup_pair_with_liquidity(&env);
 
     // First deposit
-    let _result = pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0);
+    let _result = pair_client.deposit(&user, &100_0000000, &100_0000000, &0, &0, &FAR_FUTURE_DEADLINE);
 
     // Total supply should be more than user's balance (MINIMUM_LIQUIDITY locked)
     let total_supply = pair_client.total_supply();
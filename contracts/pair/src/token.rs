@@ -4,11 +4,11 @@
 //! Using modern #[contractevent] macro for type-safe event emission
 
 use astroswap_shared::AstroSwapError;
-use soroban_sdk::{contractevent, Address, Env, String};
+use soroban_sdk::{contractevent, token, Address, Env, String};
 
 use crate::storage::{
-    extend_balance_ttl, get_allowance, get_balance, get_total_supply, set_allowance, set_balance,
-    set_total_supply,
+    extend_balance_ttl, get_allowance, get_balance, get_lp_metadata, get_total_supply,
+    set_allowance, set_balance, set_lp_metadata, set_total_supply,
 };
 
 /// LP Token name prefix
@@ -20,52 +20,140 @@ const LP_TOKEN_SYMBOL: &str = "ASTRO-LP";
 /// Decimals (same as most tokens)
 const DECIMALS: u32 = 7;
 
+/// Longest underlying symbol kept in LP metadata, in bytes
+const MAX_UNDERLYING_SYMBOL_LEN: usize = 8;
+
+/// Longest underlying symbol read from a token; longer ones count as unknown
+const MAX_SYMBOL_READ_LEN: usize = 32;
+
+/// Placeholder for tokens without a readable symbol
+const UNKNOWN_SYMBOL: &[u8] = b"?";
+
+/// Room for "AstroSwap LP Token" + " " + two symbols + "/"
+const METADATA_BUFFER_LEN: usize = 64;
+
+/// `live_until_ledger` reported in approve events: LP allowances do not expire
+const ALLOWANCE_NEVER_EXPIRES: u32 = u32::MAX;
+
 // ==================== Event Structs ====================
+// Topics and data follow SEP-41 so wallets and indexers can track LP balances
 
-/// Transfer event
-#[contractevent]
+/// Transfer event: topics `["transfer", from, to]`, data `amount`
+#[contractevent(topics = ["transfer"], data_format = "single-value")]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Transfer {
+    #[topic]
     pub from: Address,
+    #[topic]
     pub to: Address,
     pub amount: i128,
 }
 
-/// Approval event
-#[contractevent]
+/// Approve event: topics `["approve", from, spender]`, data
+/// `[amount, live_until_ledger]`
+#[contractevent(topics = ["approve"], data_format = "vec")]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct Approval {
-    pub owner: Address,
+pub struct Approve {
+    #[topic]
+    pub from: Address,
+    #[topic]
     pub spender: Address,
     pub amount: i128,
+    pub live_until_ledger: u32,
 }
 
-/// Mint event
-#[contractevent]
+/// Mint event: topics `["mint", to]`, data `amount`
+#[contractevent(topics = ["mint"], data_format = "single-value")]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Mint {
+    #[topic]
     pub to: Address,
     pub amount: i128,
 }
 
-/// Burn event
-#[contractevent]
+/// Burn event: topics `["burn", from]`, data `amount`
+#[contractevent(topics = ["burn"], data_format = "single-value")]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Burn {
+    #[topic]
     pub from: Address,
     pub amount: i128,
 }
 
 // ==================== Token Metadata ====================
 
+/// Truncate an underlying symbol to MAX_UNDERLYING_SYMBOL_LEN bytes without
+/// splitting a UTF-8 character
+pub fn truncate_symbol(symbol: &[u8]) -> &[u8] {
+    if symbol.len() <= MAX_UNDERLYING_SYMBOL_LEN {
+        return symbol;
+    }
+    let mut end = MAX_UNDERLYING_SYMBOL_LEN;
+    // Back off continuation bytes (0b10xx_xxxx)
+    while end > 0 && symbol[end] & 0xC0 == 0x80 {
+        end -= 1;
+    }
+    &symbol[..end]
+}
+
+/// Read a token's SEP-41 symbol into `buf`, returning its length
+/// Tokens that fail to report a symbol, or report an empty or overlong one,
+/// get UNKNOWN_SYMBOL
+fn read_symbol(env: &Env, token: &Address, buf: &mut [u8; MAX_SYMBOL_READ_LEN]) -> usize {
+    let symbol = match token::Client::new(env, token).try_symbol() {
+        Ok(Ok(symbol)) => symbol,
+        _ => String::from_str(env, ""),
+    };
+    let len = symbol.len() as usize;
+    if len == 0 || len > MAX_SYMBOL_READ_LEN {
+        buf[..UNKNOWN_SYMBOL.len()].copy_from_slice(UNKNOWN_SYMBOL);
+        return UNKNOWN_SYMBOL.len();
+    }
+    symbol.copy_into_slice(&mut buf[..len]);
+    len
+}
+
+/// `prefix` + " " + `symbol_0` + "/" + `symbol_1`
+fn pair_label(env: &Env, prefix: &str, symbol_0: &[u8], symbol_1: &[u8]) -> String {
+    let mut buf = [0u8; METADATA_BUFFER_LEN];
+    let mut len = 0;
+    for part in [prefix.as_bytes(), b" ", symbol_0, b"/", symbol_1] {
+        buf[len..len + part.len()].copy_from_slice(part);
+        len += part.len();
+    }
+    String::from_bytes(env, &buf[..len])
+}
+
+/// Derive the LP token name and symbol from the underlying tokens' symbols,
+/// e.g. "AstroSwap LP Token SHIBA/XLM" and "ASTRO-LP SHIBA/XLM"
+pub fn init_metadata(env: &Env, token_0: &Address, token_1: &Address) {
+    let mut buf_0 = [0u8; MAX_SYMBOL_READ_LEN];
+    let mut buf_1 = [0u8; MAX_SYMBOL_READ_LEN];
+    let len_0 = read_symbol(env, token_0, &mut buf_0);
+    let len_1 = read_symbol(env, token_1, &mut buf_1);
+    let symbol_0 = truncate_symbol(&buf_0[..len_0]);
+    let symbol_1 = truncate_symbol(&buf_1[..len_1]);
+
+    set_lp_metadata(
+        env,
+        &pair_label(env, LP_TOKEN_NAME, symbol_0, symbol_1),
+        &pair_label(env, LP_TOKEN_SYMBOL, symbol_0, symbol_1),
+    );
+}
+
 /// Get the token name
-pub fn name(_env: &Env) -> String {
-    String::from_str(_env, LP_TOKEN_NAME)
+/// Pairs initialized before per-pair metadata use the generic name
+pub fn name(env: &Env) -> String {
+    get_lp_metadata(env)
+        .map(|(name, _)| name)
+        .unwrap_or_else(|| String::from_str(env, LP_TOKEN_NAME))
 }
 
 /// Get the token symbol
-pub fn symbol(_env: &Env) -> String {
-    String::from_str(_env, LP_TOKEN_SYMBOL)
+pub fn symbol(env: &Env) -> String {
+    get_lp_metadata(env)
+        .map(|(_, symbol)| symbol)
+        .unwrap_or_else(|| String::from_str(env, LP_TOKEN_SYMBOL))
 }
 
 /// Get the decimals
@@ -194,10 +282,11 @@ pub fn approve(
     set_allowance(env, owner, spender, amount);
 
     // Emit approval event
-    Approval {
-        owner: owner.clone(),
+    Approve {
+        from: owner.clone(),
         spender: spender.clone(),
         amount,
+        live_until_ledger: ALLOWANCE_NEVER_EXPIRES,
     }
     .publish(env);
 
@@ -0,0 +1,272 @@
+//! AstroSwap Registry Contract
+//!
+//! Maps well-known protocol components (factory, router, staking, ...) to
+//! their current addresses, so contracts and frontends can discover them
+//! instead of hardcoding them. Every update bumps the component's version;
+//! the addresses of earlier versions remain readable.
+
+use astroswap_shared::{AstroSwapError, ComponentEntry, ProtocolComponent};
+use soroban_sdk::{contract, contractevent, contractimpl, Address, Env, Vec};
+
+use crate::storage::{
+    extend_instance_ttl, get_admin, get_entry, get_entry_at, is_initialized, set_admin, set_entry,
+    set_initialized,
+};
+
+/// Every component the registry knows, in listing order
+pub const COMPONENTS: [ProtocolComponent; 9] = [
+    ProtocolComponent::Factory,
+    ProtocolComponent::Router,
+    ProtocolComponent::Staking,
+    ProtocolComponent::Oracle,
+    ProtocolComponent::Aggregator,
+    ProtocolComponent::Treasury,
+    ProtocolComponent::Bridge,
+    ProtocolComponent::Governance,
+    ProtocolComponent::Analytics,
+];
+
+/// Component address registered or updated event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ComponentUpdated {
+    #[topic]
+    pub component: ProtocolComponent,
+    pub address: Address,
+    pub version: u32,
+    pub previous: Option<Address>,
+}
+
+#[contract]
+pub struct AstroSwapRegistry;
+
+#[contractimpl]
+impl AstroSwapRegistry {
+    /// Initialize the registry contract
+    ///
+    /// # Arguments
+    /// * `admin` - Registers and updates component addresses
+    pub fn initialize(env: Env, admin: Address) -> Result<(), AstroSwapError> {
+        if is_initialized(&env) {
+            return Err(AstroSwapError::AlreadyInitialized);
+        }
+
+        set_admin(&env, &admin);
+        set_initialized(&env);
+
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    // ==================== Admin Functions ====================
+
+    /// Register the address of a component, or point it to a new one
+    /// Only admin can call
+    ///
+    /// Returns the new version. Re-registering the current address fails
+    /// with `InvalidArgument`, so versions only change with the address.
+    pub fn set_component(
+        env: Env,
+        admin: Address,
+        component: ProtocolComponent,
+        address: Address,
+    ) -> Result<u32, AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+
+        let previous = get_entry(&env, component);
+        if previous
+            .as_ref()
+            .is_some_and(|entry| entry.address == address)
+        {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+
+        let entry = ComponentEntry {
+            address: address.clone(),
+            version: previous.as_ref().map_or(1, |entry| entry.version + 1),
+            updated_at: env.ledger().timestamp(),
+        };
+        set_entry(&env, component, &entry);
+
+        ComponentUpdated {
+            component,
+            address,
+            version: entry.version,
+            previous: previous.map(|entry| entry.address),
+        }
+        .publish(&env);
+
+        extend_instance_ttl(&env);
+        Ok(entry.version)
+    }
+
+    /// Transfer admin rights
+    /// Only admin can call
+    pub fn set_admin(env: Env, admin: Address, new_admin: Address) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+        set_admin(&env, &new_admin);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    // ==================== View Functions ====================
+
+    /// Current address of a component, or `ComponentNotRegistered`
+    pub fn get_address(env: Env, component: ProtocolComponent) -> Result<Address, AstroSwapError> {
+        get_entry(&env, component)
+            .map(|entry| entry.address)
+            .ok_or(AstroSwapError::ComponentNotRegistered)
+    }
+
+    /// Current entry of a component, if registered
+    pub fn entry(env: Env, component: ProtocolComponent) -> Option<ComponentEntry> {
+        get_entry(&env, component)
+    }
+
+    /// Entry a component had at `version`, if it ever reached it
+    pub fn entry_at(
+        env: Env,
+        component: ProtocolComponent,
+        version: u32,
+    ) -> Option<ComponentEntry> {
+        get_entry_at(&env, component, version)
+    }
+
+    /// Current entries of all registered components
+    pub fn components(env: Env) -> Vec<(ProtocolComponent, ComponentEntry)> {
+        let mut entries = Vec::new(&env);
+        for component in COMPONENTS {
+            if let Some(entry) = get_entry(&env, component) {
+                entries.push_back((component, entry));
+            }
+        }
+        entries
+    }
+
+    /// Get admin address
+    pub fn admin(env: Env) -> Address {
+        get_admin(&env)
+    }
+
+    // ==================== Internal Functions ====================
+
+    /// Verify caller is admin
+    fn require_admin(env: &Env, caller: &Address) -> Result<(), AstroSwapError> {
+        if !is_initialized(env) {
+            return Err(AstroSwapError::NotInitialized);
+        }
+        caller.require_auth();
+
+        if *caller != get_admin(env) {
+            return Err(AstroSwapError::Unauthorized);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, Ledger};
+
+    fn setup(env: &Env) -> (AstroSwapRegistryClient<'_>, Address) {
+        env.mock_all_auths();
+
+        let contract_id = env.register(AstroSwapRegistry, ());
+        let client = AstroSwapRegistryClient::new(env, &contract_id);
+        let admin = Address::generate(env);
+        client.initialize(&admin);
+
+        (client, admin)
+    }
+
+    #[test]
+    fn test_initialize() {
+        let env = Env::default();
+        let (client, admin) = setup(&env);
+
+        assert_eq!(client.admin(), admin);
+        assert_eq!(
+            client.try_initialize(&admin),
+            Err(Ok(AstroSwapError::AlreadyInitialized))
+        );
+        assert_eq!(
+            client.try_get_address(&ProtocolComponent::Router),
+            Err(Ok(AstroSwapError::ComponentNotRegistered))
+        );
+        assert_eq!(client.components().len(), 0);
+    }
+
+    #[test]
+    fn test_component_versions() {
+        let env = Env::default();
+        let (client, admin) = setup(&env);
+        let router_v1 = Address::generate(&env);
+        let router_v2 = Address::generate(&env);
+        let factory = Address::generate(&env);
+
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        assert_eq!(
+            client.set_component(&admin, &ProtocolComponent::Router, &router_v1),
+            1
+        );
+        client.set_component(&admin, &ProtocolComponent::Factory, &factory);
+
+        env.ledger().with_mut(|li| li.timestamp = 2_000);
+        assert_eq!(
+            client.set_component(&admin, &ProtocolComponent::Router, &router_v2),
+            2
+        );
+        assert_eq!(client.get_address(&ProtocolComponent::Router), router_v2);
+        assert_eq!(
+            client.entry(&ProtocolComponent::Router),
+            Some(ComponentEntry {
+                address: router_v2.clone(),
+                version: 2,
+                updated_at: 2_000,
+            })
+        );
+
+        // Earlier versions stay readable
+        assert_eq!(
+            client
+                .entry_at(&ProtocolComponent::Router, &1)
+                .map(|entry| (entry.address, entry.updated_at)),
+            Some((router_v1, 1_000))
+        );
+        assert_eq!(client.entry_at(&ProtocolComponent::Router, &3), None);
+
+        // Listed in registry order, whatever the registration order
+        let components = client.components();
+        assert_eq!(components.len(), 2);
+        assert_eq!(components.get_unchecked(0).0, ProtocolComponent::Factory);
+        assert_eq!(components.get_unchecked(1).1.address, router_v2);
+
+        // Same address again is not a new version
+        assert_eq!(
+            client.try_set_component(&admin, &ProtocolComponent::Router, &router_v2),
+            Err(Ok(AstroSwapError::InvalidArgument))
+        );
+    }
+
+    #[test]
+    fn test_only_admin_registers() {
+        let env = Env::default();
+        let (client, admin) = setup(&env);
+        let other = Address::generate(&env);
+
+        assert_eq!(
+            client.try_set_component(&other, &ProtocolComponent::Treasury, &other),
+            Err(Ok(AstroSwapError::Unauthorized))
+        );
+
+        client.set_admin(&admin, &other);
+        client.set_component(&other, &ProtocolComponent::Treasury, &admin);
+        assert_eq!(client.get_address(&ProtocolComponent::Treasury), admin);
+        assert_eq!(
+            client.try_set_component(&admin, &ProtocolComponent::Treasury, &other),
+            Err(Ok(AstroSwapError::Unauthorized))
+        );
+    }
+}
@@ -0,0 +1,6 @@
+#![no_std]
+
+mod contract;
+mod storage;
+
+pub use contract::{AstroSwapRegistry, AstroSwapRegistryClient, ComponentUpdated, COMPONENTS};
@@ -0,0 +1,84 @@
+//! Storage module for AstroSwap Registry
+//!
+//! The current entry of each component is kept in instance storage, which
+//! every lookup loads anyway. Each entry is also written to persistent
+//! storage under its version, so earlier addresses stay discoverable.
+
+use astroswap_shared::{ComponentEntry, ProtocolComponent};
+use soroban_sdk::{contracttype, Address, Env};
+
+/// Storage keys for the registry contract
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Admin,
+    Initialized,
+    /// Current entry of a component
+    Component(ProtocolComponent),
+    /// (component, version)
+    ComponentVersion(ProtocolComponent, u32),
+}
+
+/// Check if the contract is initialized
+pub fn is_initialized(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get::<DataKey, bool>(&DataKey::Initialized)
+        .unwrap_or(false)
+}
+
+/// Set initialized flag
+pub fn set_initialized(env: &Env) {
+    env.storage().instance().set(&DataKey::Initialized, &true);
+}
+
+/// Get the admin address
+pub fn get_admin(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get::<DataKey, Address>(&DataKey::Admin)
+        .expect("Admin not set")
+}
+
+/// Set the admin address
+pub fn set_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&DataKey::Admin, admin);
+}
+
+/// Get the current entry of a component
+pub fn get_entry(env: &Env, component: ProtocolComponent) -> Option<ComponentEntry> {
+    env.storage()
+        .instance()
+        .get::<DataKey, ComponentEntry>(&DataKey::Component(component))
+}
+
+/// Get the entry a component had at `version`
+pub fn get_entry_at(
+    env: &Env,
+    component: ProtocolComponent,
+    version: u32,
+) -> Option<ComponentEntry> {
+    env.storage()
+        .persistent()
+        .get::<DataKey, ComponentEntry>(&DataKey::ComponentVersion(component, version))
+}
+
+/// Make `entry` the current one of a component and record its version
+pub fn set_entry(env: &Env, component: ProtocolComponent, entry: &ComponentEntry) {
+    env.storage()
+        .instance()
+        .set(&DataKey::Component(component), entry);
+
+    let key = DataKey::ComponentVersion(component, entry.version);
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, entry);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+/// Extend TTL for instance storage
+pub fn extend_instance_ttl(env: &Env) {
+    let max_ttl = env.storage().max_ttl();
+    env.storage().instance().extend_ttl(max_ttl - 1000, max_ttl);
+}
@@ -1,15 +1,31 @@
 #![allow(clippy::too_many_arguments)]
 
 use astroswap_shared::{
-    get_amount_in, get_amount_out, AstroSwapError, FactoryClient, PairClient, MIN_TRADE_AMOUNT,
+    apply_bps, calculate_balancing_swap_amount, calculate_price, emit_route_executed,
+    emit_router_add_liquidity, emit_router_remove_liquidity, emit_router_swap, get_amount_in,
+    get_amount_out, quote, require_deadline, safe_add, safe_sub, AstroSwapError, FactoryClient,
+    PairClient, BPS_DENOMINATOR, CANONICAL_PRICE_DECIMALS, MIN_TRADE_AMOUNT,
+};
+use soroban_sdk::{
+    contract, contractevent, contractimpl, symbol_short, token, Address, Env, Symbol, Vec,
 };
-use soroban_sdk::{contract, contractimpl, token, Address, Env, Vec};
 
 use crate::storage::{
-    extend_instance_ttl, get_admin, get_factory, is_initialized, set_admin, set_factory,
-    set_initialized,
+    extend_instance_ttl, get_admin, get_factory, get_min_reserve_depth, get_recovery,
+    is_initialized, remove_recovery, set_admin, set_factory, set_initialized,
+    set_min_reserve_depth, set_recovery, TokenRecovery,
 };
 
+/// Stranded tokens recovered by the admin event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenRecovered {
+    #[topic]
+    pub token: Address,
+    pub to: Address,
+    pub amount: i128,
+}
+
 #[contract]
 pub struct AstroSwapRouter;
 
@@ -33,6 +49,15 @@ impl AstroSwapRouter {
     // Maximum path length to prevent excessive gas consumption
     const MAX_PATH_LENGTH: u32 = 5;
 
+    // Client tag of router events for calls made without one
+    const DEFAULT_CLIENT_TAG: Symbol = symbol_short!("untagged");
+
+    // Decimals of spot prices returned by `quote_price`
+    const PRICE_DECIMALS: u32 = CANONICAL_PRICE_DECIMALS;
+
+    // Delay between requesting and executing a token recovery (48 hours)
+    const RECOVERY_DELAY: u64 = 48 * 60 * 60;
+
     /// Verify contract is initialized (prevents storage panics)
     fn require_initialized(env: &Env) -> Result<(), AstroSwapError> {
         if !is_initialized(env) {
@@ -60,6 +85,60 @@ impl AstroSwapRouter {
         amount_out_min: i128,
         path: Vec<Address>,
         deadline: u64,
+    ) -> Result<Vec<i128>, AstroSwapError> {
+        Self::swap_exact_in_with_hop_minimums(
+            env,
+            user,
+            amount_in,
+            amount_out_min,
+            path,
+            None,
+            deadline,
+        )
+    }
+
+    /// Swap exact tokens for tokens with a minimum output for every hop
+    ///
+    /// Same as `swap_exact_tokens_for_tokens`, but `hop_minimums` (one entry
+    /// per hop, `path.len() - 1` in total) bounds what each pool in the route
+    /// may return, so a thin intermediate pool cannot absorb the slippage
+    /// budget of the whole route. A route quoting any hop below its minimum
+    /// fails with `SlippageExceeded` before any transfer. With or without
+    /// `hop_minimums`, every hop must return at least its quoted amount.
+    pub fn swap_exact_in_with_hop_minimums(
+        env: Env,
+        user: Address,
+        amount_in: i128,
+        amount_out_min: i128,
+        path: Vec<Address>,
+        hop_minimums: Option<Vec<i128>>,
+        deadline: u64,
+    ) -> Result<Vec<i128>, AstroSwapError> {
+        Self::swap_exact_in_tagged(
+            env,
+            user,
+            amount_in,
+            amount_out_min,
+            path,
+            hop_minimums,
+            Self::DEFAULT_CLIENT_TAG,
+            deadline,
+        )
+    }
+
+    /// Exact-input swap attributed to a client
+    ///
+    /// Same as `swap_exact_in_with_hop_minimums`; `client_tag` identifies the
+    /// integrator behind the call in the router's `router_swap` event.
+    pub fn swap_exact_in_tagged(
+        env: Env,
+        user: Address,
+        amount_in: i128,
+        amount_out_min: i128,
+        path: Vec<Address>,
+        hop_minimums: Option<Vec<i128>>,
+        client_tag: Symbol,
+        deadline: u64,
     ) -> Result<Vec<i128>, AstroSwapError> {
         // Verify contract is initialized
         Self::require_initialized(&env)?;
@@ -67,7 +146,7 @@ impl AstroSwapRouter {
         user.require_auth();
 
         // Check deadline
-        Self::check_deadline(&env, deadline)?;
+        require_deadline(&env, deadline)?;
 
         // Validate minimum trade amount (dust attack prevention)
         if amount_in < MIN_TRADE_AMOUNT {
@@ -76,9 +155,80 @@ impl AstroSwapRouter {
 
         // Validate path
         Self::validate_path(&path)?;
+        if let Some(minimums) = &hop_minimums {
+            if minimums.len() != path.len() - 1 {
+                return Err(AstroSwapError::InvalidArgument);
+            }
+        }
+
+        Self::execute_exact_in(
+            &env,
+            &user,
+            amount_in,
+            amount_out_min,
+            &path,
+            hop_minimums.as_ref(),
+            &client_tag,
+            deadline,
+        )
+    }
+
+    /// Swap exact tokens around a cycle back into the input token
+    ///
+    /// For triangular rebalancing and arbitrage: `path` starts and ends with
+    /// the same token and visits at least two other distinct tokens
+    /// (A → B → C → A), so every hop goes through a different pair. The swap
+    /// fails with `SlippageExceeded` unless it returns at least `amount_in +
+    /// min_profit`. Other entry points still reject any repeated token.
+    pub fn swap_cyclic(
+        env: Env,
+        user: Address,
+        amount_in: i128,
+        min_profit: i128,
+        path: Vec<Address>,
+        deadline: u64,
+    ) -> Result<Vec<i128>, AstroSwapError> {
+        Self::require_initialized(&env)?;
 
-        // Calculate amounts for the entire path
-        let amounts = Self::get_amounts_out(&env, amount_in, &path)?;
+        user.require_auth();
+
+        require_deadline(&env, deadline)?;
+
+        if amount_in < MIN_TRADE_AMOUNT {
+            return Err(AstroSwapError::MinimumNotMet);
+        }
+        if min_profit <= 0 {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+
+        Self::validate_cyclic_path(&path)?;
+
+        let amount_out_min = safe_add(amount_in, min_profit)?;
+        Self::execute_exact_in(
+            &env,
+            &user,
+            amount_in,
+            amount_out_min,
+            &path,
+            None,
+            &Self::DEFAULT_CLIENT_TAG,
+            deadline,
+        )
+    }
+
+    /// Quote and execute a validated exact-input route, returning the amounts
+    fn execute_exact_in(
+        env: &Env,
+        user: &Address,
+        amount_in: i128,
+        amount_out_min: i128,
+        path: &Vec<Address>,
+        hop_minimums: Option<&Vec<i128>>,
+        client_tag: &Symbol,
+        deadline: u64,
+    ) -> Result<Vec<i128>, AstroSwapError> {
+        // Calculate amounts for the entire path, on the best fee tier per hop
+        let (amounts, pairs) = Self::get_amounts_out(env, amount_in, path)?;
 
         // Check slippage - use ok_or for safe indexing
         let final_amount = amounts
@@ -88,32 +238,52 @@ impl AstroSwapRouter {
             return Err(AstroSwapError::SlippageExceeded);
         }
 
-        // Get factory and first pair
-        let factory = get_factory(&env);
-        let factory_client = FactoryClient::new(&env, &factory);
+        // Every hop executes at its quote at least, so checking the quotes
+        // enforces the per-hop minimums
+        if let Some(minimums) = hop_minimums {
+            for (i, hop_min) in minimums.iter().enumerate() {
+                let quoted = amounts
+                    .get(i as u32 + 1)
+                    .ok_or(AstroSwapError::InvalidPath)?;
+                if quoted < hop_min {
+                    return Err(AstroSwapError::SlippageExceeded);
+                }
+            }
+        }
 
         let token_in = path.get(0).ok_or(AstroSwapError::InvalidPath)?;
-        let token_out = path.get(1).ok_or(AstroSwapError::InvalidPath)?;
-
-        // Get pair address
-        let pair_address = factory_client
-            .get_pair(&token_in, &token_out)
-            .ok_or(AstroSwapError::PairNotFound)?;
+        let pair_address = pairs.get(0).ok_or(AstroSwapError::InvalidPath)?;
 
         // Transfer input tokens from user to first pair
-        let token_in_client = token::Client::new(&env, &token_in);
-        token_in_client.transfer(&user, &pair_address, &amount_in);
+        let token_in_client = token::Client::new(env, &token_in);
+        token_in_client.transfer(user, &pair_address, &amount_in);
 
         // Execute swaps along the path
-        Self::execute_swaps(&env, &factory, &path, &amounts, &user, deadline)?;
+        Self::execute_swaps(env, path, &pairs, &amounts, user, deadline)?;
+
+        let path_out = path.get(path.len() - 1).ok_or(AstroSwapError::InvalidPath)?;
+        emit_route_executed(
+            env,
+            user,
+            &token_in,
+            &path_out,
+            amount_in,
+            final_amount,
+            path.len() - 1,
+        );
+        emit_router_swap(env, user, client_tag, path, &amounts, false);
 
-        extend_instance_ttl(&env);
+        extend_instance_ttl(env);
 
         Ok(amounts)
     }
 
     /// Swap tokens for exact tokens
     /// Swaps as few input tokens as possible for a fixed amount of output tokens
+    ///
+    /// Returns the amounts actually swapped at each hop: the first entry is the
+    /// input charged to the user, the last is at least `amount_out`. Any output
+    /// above `amount_out` left by per-hop rounding is credited to the user.
     pub fn swap_tokens_for_exact_tokens(
         env: Env,
         user: Address,
@@ -121,6 +291,30 @@ impl AstroSwapRouter {
         amount_in_max: i128,
         path: Vec<Address>,
         deadline: u64,
+    ) -> Result<Vec<i128>, AstroSwapError> {
+        Self::swap_exact_out_tagged(
+            env,
+            user,
+            amount_out,
+            amount_in_max,
+            path,
+            Self::DEFAULT_CLIENT_TAG,
+            deadline,
+        )
+    }
+
+    /// Exact-output swap attributed to a client
+    ///
+    /// Same as `swap_tokens_for_exact_tokens`; `client_tag` identifies the
+    /// integrator behind the call in the router's `router_swap` event.
+    pub fn swap_exact_out_tagged(
+        env: Env,
+        user: Address,
+        amount_out: i128,
+        amount_in_max: i128,
+        path: Vec<Address>,
+        client_tag: Symbol,
+        deadline: u64,
     ) -> Result<Vec<i128>, AstroSwapError> {
         // Verify contract is initialized
         Self::require_initialized(&env)?;
@@ -128,7 +322,7 @@ impl AstroSwapRouter {
         user.require_auth();
 
         // Check deadline
-        Self::check_deadline(&env, deadline)?;
+        require_deadline(&env, deadline)?;
 
         // Validate minimum trade amount (dust attack prevention)
         if amount_out < MIN_TRADE_AMOUNT {
@@ -139,7 +333,7 @@ impl AstroSwapRouter {
         Self::validate_path(&path)?;
 
         // Calculate amounts for the entire path (reverse calculation)
-        let amounts = Self::get_amounts_in(&env, amount_out, &path)?;
+        let (amounts, pairs) = Self::get_amounts_in(&env, amount_out, &path)?;
 
         // Check slippage - use ok_or for safe indexing
         let required_amount = amounts.get(0).ok_or(AstroSwapError::InvalidPath)?;
@@ -152,24 +346,36 @@ impl AstroSwapRouter {
             return Err(AstroSwapError::MinimumNotMet);
         }
 
-        // Get factory and first pair
-        let factory = get_factory(&env);
-        let factory_client = FactoryClient::new(&env, &factory);
+        // Drop the rounding surplus so it stays with the user, and execute
+        // against the forward amounts the pairs will actually deliver
+        let spent_amount =
+            Self::trim_exact_input(&env, &path, &pairs, required_amount, amount_out)?;
+        let amounts = Self::amounts_along(&env, spent_amount, &path, &pairs)?;
+        let final_amount = amounts
+            .get(amounts.len() - 1)
+            .ok_or(AstroSwapError::InvalidPath)?;
 
         let token_in = path.get(0).ok_or(AstroSwapError::InvalidPath)?;
-        let token_out = path.get(1).ok_or(AstroSwapError::InvalidPath)?;
-
-        // Get pair address
-        let pair_address = factory_client
-            .get_pair(&token_in, &token_out)
-            .ok_or(AstroSwapError::PairNotFound)?;
+        let pair_address = pairs.get(0).ok_or(AstroSwapError::InvalidPath)?;
 
         // Transfer input tokens from user to first pair
         let token_in_client = token::Client::new(&env, &token_in);
-        token_in_client.transfer(&user, &pair_address, &required_amount);
+        token_in_client.transfer(&user, &pair_address, &spent_amount);
 
         // Execute swaps along the path
-        Self::execute_swaps(&env, &factory, &path, &amounts, &user, deadline)?;
+        Self::execute_swaps(&env, &path, &pairs, &amounts, &user, deadline)?;
+
+        let path_out = path.get(path.len() - 1).ok_or(AstroSwapError::InvalidPath)?;
+        emit_route_executed(
+            &env,
+            &user,
+            &token_in,
+            &path_out,
+            spent_amount,
+            final_amount,
+            path.len() - 1,
+        );
+        emit_router_swap(&env, &user, &client_tag, &path, &amounts, true);
 
         extend_instance_ttl(&env);
 
@@ -177,6 +383,11 @@ impl AstroSwapRouter {
     }
 
     /// Add liquidity to a pair
+    ///
+    /// Creates the pair if it does not exist yet.
+    ///
+    /// # Returns
+    /// * Tuple of (amount_a_deposited, amount_b_deposited, shares_minted, pair)
     #[allow(clippy::too_many_arguments)]
     pub fn add_liquidity(
         env: Env,
@@ -188,14 +399,89 @@ impl AstroSwapRouter {
         amount_a_min: i128,
         amount_b_min: i128,
         deadline: u64,
-    ) -> Result<(i128, i128, i128), AstroSwapError> {
+    ) -> Result<(i128, i128, i128, Address), AstroSwapError> {
+        Self::add_liquidity_with_options(
+            env,
+            user,
+            token_a,
+            token_b,
+            amount_a_desired,
+            amount_b_desired,
+            amount_a_min,
+            amount_b_min,
+            false,
+            0,
+            deadline,
+        )
+    }
+
+    /// Add liquidity to a pair, optionally balancing the amounts first
+    ///
+    /// With `auto_balance`, the excess of the over-supplied token is swapped
+    /// through the pair so that nearly all provided value is deposited, instead
+    /// of leaving the excess with the user. The swap must not lose more than
+    /// `max_swap_slippage_bps` against the pre-swap spot price (fee included).
+    /// `amount_a_min` / `amount_b_min` apply to the amounts actually deposited.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_liquidity_with_options(
+        env: Env,
+        user: Address,
+        token_a: Address,
+        token_b: Address,
+        amount_a_desired: i128,
+        amount_b_desired: i128,
+        amount_a_min: i128,
+        amount_b_min: i128,
+        auto_balance: bool,
+        max_swap_slippage_bps: u32,
+        deadline: u64,
+    ) -> Result<(i128, i128, i128, Address), AstroSwapError> {
+        Self::add_liquidity_tagged(
+            env,
+            user,
+            token_a,
+            token_b,
+            amount_a_desired,
+            amount_b_desired,
+            amount_a_min,
+            amount_b_min,
+            auto_balance,
+            max_swap_slippage_bps,
+            Self::DEFAULT_CLIENT_TAG,
+            deadline,
+        )
+    }
+
+    /// Add liquidity attributed to a client
+    ///
+    /// Same as `add_liquidity_with_options`; `client_tag` identifies the
+    /// integrator behind the call in the router's `router_add_liquidity` event.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_liquidity_tagged(
+        env: Env,
+        user: Address,
+        token_a: Address,
+        token_b: Address,
+        amount_a_desired: i128,
+        amount_b_desired: i128,
+        amount_a_min: i128,
+        amount_b_min: i128,
+        auto_balance: bool,
+        max_swap_slippage_bps: u32,
+        client_tag: Symbol,
+        deadline: u64,
+    ) -> Result<(i128, i128, i128, Address), AstroSwapError> {
         // Verify contract is initialized
         Self::require_initialized(&env)?;
 
         user.require_auth();
 
         // Check deadline
-        Self::check_deadline(&env, deadline)?;
+        require_deadline(&env, deadline)?;
+
+        if max_swap_slippage_bps > BPS_DENOMINATOR {
+            return Err(AstroSwapError::InvalidArgument);
+        }
 
         // Get factory and pair
         let factory = get_factory(&env);
@@ -204,7 +490,11 @@ impl AstroSwapRouter {
         // Get or create pair
         let pair_address = match factory_client.get_pair(&token_a, &token_b) {
             Some(addr) => addr,
-            None => factory_client.create_pair(&token_a, &token_b)?,
+            None => factory_client.create_pair_for(
+                &env.current_contract_address(),
+                &token_a,
+                &token_b,
+            )?,
         };
 
         // Call pair's deposit function
@@ -230,6 +520,20 @@ impl AstroSwapRouter {
             )
         };
 
+        let (amount_0_desired, amount_1_desired) = if auto_balance {
+            Self::balance_deposit(
+                &env,
+                &user,
+                &pair_address,
+                amount_0_desired,
+                amount_1_desired,
+                max_swap_slippage_bps,
+                deadline,
+            )?
+        } else {
+            (amount_0_desired, amount_1_desired)
+        };
+
         // Deposit into pair
         let result = pair_client.deposit(
             &user,
@@ -237,16 +541,31 @@ impl AstroSwapRouter {
             amount_1_desired,
             amount_0_min,
             amount_1_min,
+            deadline,
         );
 
         extend_instance_ttl(&env);
 
         // Reorder result to match input token order
-        if token_a == token_0 {
-            Ok(result)
+        let (amount_a, amount_b, shares) = if token_a == token_0 {
+            result
         } else {
-            Ok((result.1, result.0, result.2))
-        }
+            (result.1, result.0, result.2)
+        };
+
+        emit_router_add_liquidity(
+            &env,
+            &user,
+            &client_tag,
+            &pair_address,
+            &token_a,
+            &token_b,
+            amount_a,
+            amount_b,
+            shares,
+        );
+
+        Ok((amount_a, amount_b, shares, pair_address))
     }
 
     /// Remove liquidity from a pair
@@ -260,6 +579,35 @@ impl AstroSwapRouter {
         amount_a_min: i128,
         amount_b_min: i128,
         deadline: u64,
+    ) -> Result<(i128, i128), AstroSwapError> {
+        Self::remove_liquidity_tagged(
+            env,
+            user,
+            token_a,
+            token_b,
+            liquidity,
+            amount_a_min,
+            amount_b_min,
+            Self::DEFAULT_CLIENT_TAG,
+            deadline,
+        )
+    }
+
+    /// Remove liquidity attributed to a client
+    ///
+    /// Same as `remove_liquidity`; `client_tag` identifies the integrator
+    /// behind the call in the router's `router_remove_liquidity` event.
+    #[allow(clippy::too_many_arguments)]
+    pub fn remove_liquidity_tagged(
+        env: Env,
+        user: Address,
+        token_a: Address,
+        token_b: Address,
+        liquidity: i128,
+        amount_a_min: i128,
+        amount_b_min: i128,
+        client_tag: Symbol,
+        deadline: u64,
     ) -> Result<(i128, i128), AstroSwapError> {
         // Verify contract is initialized
         Self::require_initialized(&env)?;
@@ -267,7 +615,7 @@ impl AstroSwapRouter {
         user.require_auth();
 
         // Check deadline
-        Self::check_deadline(&env, deadline)?;
+        require_deadline(&env, deadline)?;
 
         // Get factory and pair
         let factory = get_factory(&env);
@@ -289,57 +637,331 @@ impl AstroSwapRouter {
         };
 
         // Call withdraw
-        let result = pair_client.withdraw(&user, liquidity, min_0, min_1);
+        let result = pair_client.withdraw(&user, liquidity, min_0, min_1, deadline);
 
         extend_instance_ttl(&env);
 
         // Reorder result
-        if token_a == token_0 {
-            Ok(result)
+        let (amount_a, amount_b) = if token_a == token_0 {
+            result
         } else {
-            Ok((result.1, result.0))
+            (result.1, result.0)
+        };
+
+        emit_router_remove_liquidity(
+            &env,
+            &user,
+            &client_tag,
+            &pair_address,
+            &token_a,
+            &token_b,
+            liquidity,
+            amount_a,
+            amount_b,
+        );
+
+        Ok((amount_a, amount_b))
+    }
+
+    /// Move liquidity from one pool to another holding the same tokens
+    ///
+    /// Burns `liquidity` LP tokens of `from_pair` and deposits the withdrawn
+    /// amounts into `to_pair` in one transaction, e.g. when moving to a new
+    /// fee tier or pool version. Both pools must be factory pairs of the same
+    /// tokens. If the pools' ratios differ, the excess leg is swapped through
+    /// `to_pair` first; that swap must not lose more than `max_swap_slippage_bps`
+    /// against the pre-swap spot price (fee included). `amount_0_min` /
+    /// `amount_1_min` (in the pools' token order) bound what is finally
+    /// deposited. Dust the deposit cannot use stays with the user.
+    ///
+    /// # Returns
+    /// * Tuple of (amount_0_deposited, amount_1_deposited, shares_minted)
+    #[allow(clippy::too_many_arguments)]
+    pub fn migrate_liquidity(
+        env: Env,
+        user: Address,
+        from_pair: Address,
+        to_pair: Address,
+        liquidity: i128,
+        amount_0_min: i128,
+        amount_1_min: i128,
+        max_swap_slippage_bps: u32,
+        deadline: u64,
+    ) -> Result<(i128, i128, i128), AstroSwapError> {
+        // Verify contract is initialized
+        Self::require_initialized(&env)?;
+
+        user.require_auth();
+
+        // Check deadline
+        require_deadline(&env, deadline)?;
+
+        if from_pair == to_pair || max_swap_slippage_bps > BPS_DENOMINATOR {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+
+        // Both pools must hold the same tokens in the same order
+        let from_client = PairClient::new(&env, &from_pair);
+        let to_client = PairClient::new(&env, &to_pair);
+        let token_0 = from_client.token_0();
+        let token_1 = from_client.token_1();
+        if to_client.token_0() != token_0 || to_client.token_1() != token_1 {
+            return Err(AstroSwapError::InvalidToken);
+        }
+
+        // ...and be pairs the factory deployed for them
+        let factory_client = FactoryClient::new(&env, &get_factory(&env));
+        let pairs = factory_client.get_pairs_all_tiers(&token_0, &token_1);
+        let registered = |pair: &Address| pairs.iter().any(|(address, _)| address == *pair);
+        if !registered(&from_pair) || !registered(&to_pair) {
+            return Err(AstroSwapError::PairNotFound);
         }
+
+        // Withdraw to the user, then balance and deposit from there
+        let (withdrawn_0, withdrawn_1) = from_client.withdraw(&user, liquidity, 0, 0, deadline);
+        emit_router_remove_liquidity(
+            &env,
+            &user,
+            &Self::DEFAULT_CLIENT_TAG,
+            &from_pair,
+            &token_0,
+            &token_1,
+            liquidity,
+            withdrawn_0,
+            withdrawn_1,
+        );
+
+        let (amount_0_desired, amount_1_desired) = Self::balance_deposit(
+            &env,
+            &user,
+            &to_pair,
+            withdrawn_0,
+            withdrawn_1,
+            max_swap_slippage_bps,
+            deadline,
+        )?;
+        let (amount_0, amount_1, shares) = to_client.deposit(
+            &user,
+            amount_0_desired,
+            amount_1_desired,
+            amount_0_min,
+            amount_1_min,
+            deadline,
+        );
+        emit_router_add_liquidity(
+            &env,
+            &user,
+            &Self::DEFAULT_CLIENT_TAG,
+            &to_pair,
+            &token_0,
+            &token_1,
+            amount_0,
+            amount_1,
+            shares,
+        );
+
+        extend_instance_ttl(&env);
+
+        Ok((amount_0, amount_1, shares))
+    }
+
+    // ==================== Admin Functions ====================
+
+    /// Set the reserve both sides of a pool need for swaps to route through it
+    /// Only admin can call
+    ///
+    /// A nearly empty pool burns the input in price impact. Fee tiers of a hop
+    /// with either reserve below `min_reserve` are skipped; a hop left without
+    /// a deep enough pool fails with `InsufficientLiquidity`. 0 disables the
+    /// check.
+    pub fn set_min_reserve_depth(
+        env: Env,
+        admin: Address,
+        min_reserve: i128,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env, &admin)?;
+        if min_reserve < 0 {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+
+        set_min_reserve_depth(&env, min_reserve);
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Request the recovery of tokens stranded in the router
+    /// Only admin can call
+    ///
+    /// The router never holds funds between calls, but a failed external hop
+    /// could leave some behind. `recover_token` can send them to `to` once
+    /// `RECOVERY_DELAY` has passed. A new request replaces the pending one.
+    ///
+    /// # Returns
+    /// * Earliest time the recovery can be executed
+    pub fn request_recovery(
+        env: Env,
+        admin: Address,
+        token: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<u64, AstroSwapError> {
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env, &admin)?;
+        if amount <= 0 {
+            return Err(AstroSwapError::InvalidAmount);
+        }
+
+        let ready_at = env
+            .ledger()
+            .timestamp()
+            .checked_add(Self::RECOVERY_DELAY)
+            .ok_or(AstroSwapError::Overflow)?;
+        set_recovery(
+            &env,
+            &token,
+            &TokenRecovery {
+                to,
+                amount,
+                ready_at,
+            },
+        );
+
+        extend_instance_ttl(&env);
+        Ok(ready_at)
+    }
+
+    /// Send stranded tokens as requested with `request_recovery`
+    /// Only admin can call, once the recovery delay has passed
+    ///
+    /// `to` and `amount` must match the pending request.
+    pub fn recover_token(
+        env: Env,
+        admin: Address,
+        token: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env, &admin)?;
+
+        let recovery = get_recovery(&env, &token).ok_or(AstroSwapError::RecoveryNotRequested)?;
+        if recovery.to != to || recovery.amount != amount {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+        if env.ledger().timestamp() < recovery.ready_at {
+            return Err(AstroSwapError::TimelockNotExpired);
+        }
+
+        let token_client = token::Client::new(&env, &token);
+        if token_client.balance(&env.current_contract_address()) < amount {
+            return Err(AstroSwapError::InsufficientBalance);
+        }
+
+        remove_recovery(&env, &token);
+        token_client.transfer(&env.current_contract_address(), &to, &amount);
+
+        TokenRecovered { token, to, amount }.publish(&env);
+
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Cancel the pending recovery of a token
+    /// Only admin can call
+    pub fn cancel_recovery(env: Env, admin: Address, token: Address) -> Result<(), AstroSwapError> {
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env, &admin)?;
+
+        if get_recovery(&env, &token).is_none() {
+            return Err(AstroSwapError::RecoveryNotRequested);
+        }
+        remove_recovery(&env, &token);
+
+        extend_instance_ttl(&env);
+        Ok(())
     }
 
     // ==================== View Functions ====================
 
     /// Get expected output amounts for a swap path
+    ///
+    /// Each hop uses the fee tier of its token pair that yields the most
+    /// output, among those at least `min_reserve_depth` deep. Returns the
+    /// amounts and the pair chosen for each hop.
     fn get_amounts_out(
         env: &Env,
         amount_in: i128,
         path: &Vec<Address>,
-    ) -> Result<Vec<i128>, AstroSwapError> {
+    ) -> Result<(Vec<i128>, Vec<Address>), AstroSwapError> {
         if path.len() < 2 {
             return Err(AstroSwapError::InvalidPath);
         }
 
         let factory = get_factory(env);
         let factory_client = FactoryClient::new(env, &factory);
+        let min_depth = get_min_reserve_depth(env);
 
         let mut amounts = Vec::new(env);
+        let mut pairs = Vec::new(env);
         amounts.push_back(amount_in);
 
         for i in 0..(path.len() - 1) {
             // Safe indexing with proper error handling
             let token_in = path.get(i).ok_or(AstroSwapError::InvalidPath)?;
             let token_out = path.get(i + 1).ok_or(AstroSwapError::InvalidPath)?;
+            let current_amount = amounts.get(i).ok_or(AstroSwapError::InvalidPath)?;
 
-            // Get pair
-            let pair_address = factory_client
-                .get_pair(&token_in, &token_out)
-                .ok_or(AstroSwapError::PairNotFound)?;
+            // Best output over every fee tier of the pair
+            let mut best: Result<(i128, Address), AstroSwapError> =
+                Err(AstroSwapError::PairNotFound);
+            for (pair_address, _) in factory_client
+                .get_pairs_all_tiers(&token_in, &token_out)
+                .iter()
+            {
+                let (reserve_in, reserve_out, fee_bps) =
+                    Self::hop_reserves(env, &pair_address, &token_in);
+                if reserve_in.min(reserve_out) < min_depth {
+                    if best.is_err() {
+                        best = Err(AstroSwapError::InsufficientLiquidity);
+                    }
+                    continue;
+                }
+                match get_amount_out(current_amount, reserve_in, reserve_out, fee_bps) {
+                    Ok(amount_out) => {
+                        if !matches!(best, Ok((best_out, _)) if best_out >= amount_out) {
+                            best = Ok((amount_out, pair_address));
+                        }
+                    }
+                    Err(e) if best.is_err() => best = Err(e),
+                    Err(_) => {}
+                }
+            }
 
-            let pair_client = PairClient::new(env, &pair_address);
-            let (reserve_0, reserve_1) = pair_client.get_reserves();
-            let fee_bps = pair_client.fee_bps();
+            let (amount_out_calc, pair_address) = best?;
+            amounts.push_back(amount_out_calc);
+            pairs.push_back(pair_address);
+        }
 
-            // Determine reserves based on token order
-            let token_0 = pair_client.token_0();
-            let (reserve_in, reserve_out) = if token_in == token_0 {
-                (reserve_0, reserve_1)
-            } else {
-                (reserve_1, reserve_0)
-            };
+        Ok((amounts, pairs))
+    }
+
+    /// Expected output amounts for a swap path through the given pairs
+    fn amounts_along(
+        env: &Env,
+        amount_in: i128,
+        path: &Vec<Address>,
+        pairs: &Vec<Address>,
+    ) -> Result<Vec<i128>, AstroSwapError> {
+        let mut amounts = Vec::new(env);
+        amounts.push_back(amount_in);
+
+        for i in 0..pairs.len() {
+            let token_in = path.get(i).ok_or(AstroSwapError::InvalidPath)?;
+            let pair_address = pairs.get(i).ok_or(AstroSwapError::InvalidPath)?;
+            let (reserve_in, reserve_out, fee_bps) =
+                Self::hop_reserves(env, &pair_address, &token_in);
 
             let current_amount = amounts.get(i).ok_or(AstroSwapError::InvalidPath)?;
             let amount_out_calc = get_amount_out(current_amount, reserve_in, reserve_out, fee_bps)?;
@@ -349,21 +971,61 @@ impl AstroSwapRouter {
         Ok(amounts)
     }
 
+    /// Smallest input, starting from `amount_in`, that still delivers `amount_out`
+    /// through `pairs`
+    ///
+    /// `get_amounts_in` rounds every hop up, so its first amount can exceed the
+    /// input the path needs by a few stroops. Without trimming, that surplus is
+    /// swapped through the first pair instead of staying with the user.
+    fn trim_exact_input(
+        env: &Env,
+        path: &Vec<Address>,
+        pairs: &Vec<Address>,
+        amount_in: i128,
+        amount_out: i128,
+    ) -> Result<i128, AstroSwapError> {
+        let mut trimmed = amount_in;
+
+        // Rounding costs about one stroop per hop, so a few steps suffice
+        for _ in 0..(path.len() - 1) {
+            let candidate = trimmed - 1;
+            if candidate < MIN_TRADE_AMOUNT {
+                break;
+            }
+            let amounts = Self::amounts_along(env, candidate, path, pairs)?;
+            let delivered = amounts
+                .get(amounts.len() - 1)
+                .ok_or(AstroSwapError::InvalidPath)?;
+            if delivered < amount_out {
+                break;
+            }
+            trimmed = candidate;
+        }
+
+        Ok(trimmed)
+    }
+
     /// Get required input amounts for a swap path
+    ///
+    /// Each hop uses the fee tier of its token pair that needs the least
+    /// input, among those at least `min_reserve_depth` deep. Returns the
+    /// amounts and the pair chosen for each hop.
     fn get_amounts_in(
         env: &Env,
         amount_out: i128,
         path: &Vec<Address>,
-    ) -> Result<Vec<i128>, AstroSwapError> {
+    ) -> Result<(Vec<i128>, Vec<Address>), AstroSwapError> {
         if path.len() < 2 {
             return Err(AstroSwapError::InvalidPath);
         }
 
         let factory = get_factory(env);
         let factory_client = FactoryClient::new(env, &factory);
+        let min_depth = get_min_reserve_depth(env);
 
         let path_len = path.len();
         let mut amounts = Vec::new(env);
+        let mut pairs = Vec::new(env);
 
         // Pre-fill with zeros
         for _ in 0..path_len {
@@ -377,31 +1039,54 @@ impl AstroSwapRouter {
         for i in (0..path_len - 1).rev() {
             let token_in = path.get(i).ok_or(AstroSwapError::InvalidPath)?;
             let token_out = path.get(i + 1).ok_or(AstroSwapError::InvalidPath)?;
+            let current_amount_out = amounts.get(i + 1).ok_or(AstroSwapError::InvalidPath)?;
 
-            // Get pair
-            let pair_address = factory_client
-                .get_pair(&token_in, &token_out)
-                .ok_or(AstroSwapError::PairNotFound)?;
-
-            let pair_client = PairClient::new(env, &pair_address);
-            let (reserve_0, reserve_1) = pair_client.get_reserves();
-            let fee_bps = pair_client.fee_bps();
-
-            // Determine reserves based on token order
-            let token_0 = pair_client.token_0();
-            let (reserve_in, reserve_out) = if token_in == token_0 {
-                (reserve_0, reserve_1)
-            } else {
-                (reserve_1, reserve_0)
-            };
+            // Cheapest input over every fee tier of the pair
+            let mut best: Result<(i128, Address), AstroSwapError> =
+                Err(AstroSwapError::PairNotFound);
+            for (pair_address, _) in factory_client
+                .get_pairs_all_tiers(&token_in, &token_out)
+                .iter()
+            {
+                let (reserve_in, reserve_out, fee_bps) =
+                    Self::hop_reserves(env, &pair_address, &token_in);
+                if reserve_in.min(reserve_out) < min_depth {
+                    if best.is_err() {
+                        best = Err(AstroSwapError::InsufficientLiquidity);
+                    }
+                    continue;
+                }
+                match get_amount_in(current_amount_out, reserve_in, reserve_out, fee_bps) {
+                    Ok(amount_in) => {
+                        if !matches!(best, Ok((best_in, _)) if best_in <= amount_in) {
+                            best = Ok((amount_in, pair_address));
+                        }
+                    }
+                    Err(e) if best.is_err() => best = Err(e),
+                    Err(_) => {}
+                }
+            }
 
-            let current_amount_out = amounts.get(i + 1).ok_or(AstroSwapError::InvalidPath)?;
-            let amount_in_calc =
-                get_amount_in(current_amount_out, reserve_in, reserve_out, fee_bps)?;
+            let (amount_in_calc, pair_address) = best?;
             amounts.set(i, amount_in_calc);
+            pairs.push_front(pair_address);
         }
 
-        Ok(amounts)
+        Ok((amounts, pairs))
+    }
+
+    /// Reserves of `pair` as (reserve_in, reserve_out) for `token_in`, and its fee
+    fn hop_reserves(env: &Env, pair: &Address, token_in: &Address) -> (i128, i128, u32) {
+        let pair_client = PairClient::new(env, pair);
+        let (reserve_0, reserve_1) = pair_client.get_reserves();
+        let fee_bps = pair_client.fee_bps();
+
+        // Determine reserves based on token order
+        if *token_in == pair_client.token_0() {
+            (reserve_0, reserve_1, fee_bps)
+        } else {
+            (reserve_1, reserve_0, fee_bps)
+        }
     }
 
     /// Quote: given some amount of token A, calculate optimal amount of token B
@@ -414,6 +1099,45 @@ impl AstroSwapRouter {
         astroswap_shared::quote(amount_a, reserve_a, reserve_b)
     }
 
+    /// Spot price of one whole `token_a` in `token_b`, with 7 decimals
+    ///
+    /// Reserves are scaled by each token's decimals, so the price is
+    /// meaningful when a 7-decimal Stellar asset is paired with an
+    /// 18-decimal bridged asset.
+    pub fn quote_price(
+        env: Env,
+        token_a: Address,
+        token_b: Address,
+    ) -> Result<i128, AstroSwapError> {
+        Self::require_initialized(&env)?;
+
+        let factory_client = FactoryClient::new(&env, &get_factory(&env));
+        let pair_address = factory_client
+            .get_pair(&token_a, &token_b)
+            .ok_or(AstroSwapError::PairNotFound)?;
+
+        let pair_client = PairClient::new(&env, &pair_address);
+        let (reserve_0, reserve_1) = pair_client.get_reserves();
+        let (reserve_a, reserve_b) = if token_a == pair_client.token_0() {
+            (reserve_0, reserve_1)
+        } else {
+            (reserve_1, reserve_0)
+        };
+        if reserve_a == 0 {
+            return Err(AstroSwapError::InsufficientLiquidity);
+        }
+
+        extend_instance_ttl(&env);
+
+        calculate_price(
+            reserve_a,
+            token::Client::new(&env, &token_a).decimals(),
+            reserve_b,
+            token::Client::new(&env, &token_b).decimals(),
+            Self::PRICE_DECIMALS,
+        )
+    }
+
     /// Get factory address
     pub fn factory(env: Env) -> Address {
         extend_instance_ttl(&env);
@@ -426,12 +1150,38 @@ impl AstroSwapRouter {
         get_admin(&env)
     }
 
+    /// Get the minimum reserve depth of a routed pool (0 if unset)
+    pub fn min_reserve_depth(env: Env) -> i128 {
+        extend_instance_ttl(&env);
+        get_min_reserve_depth(&env)
+    }
+
+    /// Get the pending recovery of a token
+    pub fn pending_recovery(env: Env, token: Address) -> Option<TokenRecovery> {
+        extend_instance_ttl(&env);
+        get_recovery(&env, &token)
+    }
+
+    /// Get the router's balance of each token, in order
+    /// Anything above zero is stranded and can be recovered by the admin
+    pub fn token_balances(env: Env, tokens: Vec<Address>) -> Vec<i128> {
+        extend_instance_ttl(&env);
+        let router = env.current_contract_address();
+        let mut balances = Vec::new(&env);
+        for token in tokens.iter() {
+            balances.push_back(token::Client::new(&env, &token).balance(&router));
+        }
+        balances
+    }
+
     // ==================== Internal Functions ====================
 
-    /// Check if deadline has passed
-    fn check_deadline(env: &Env, deadline: u64) -> Result<(), AstroSwapError> {
-        if env.ledger().timestamp() > deadline {
-            return Err(AstroSwapError::DeadlineExpired);
+    /// Verify caller is admin
+    fn require_admin(env: &Env, caller: &Address) -> Result<(), AstroSwapError> {
+        caller.require_auth();
+
+        if *caller != get_admin(env) {
+            return Err(AstroSwapError::Unauthorized);
         }
         Ok(())
     }
@@ -450,7 +1200,7 @@ impl AstroSwapRouter {
 
         // Path must not exceed maximum length (prevents excessive gas)
         if len > Self::MAX_PATH_LENGTH {
-            return Err(AstroSwapError::InvalidPath);
+            return Err(AstroSwapError::RouteTooLong);
         }
 
         // Check for duplicate tokens in path (would indicate a loop)
@@ -474,29 +1224,113 @@ impl AstroSwapRouter {
         Ok(())
     }
 
-    /// Execute swaps along the path using low-level swap_from_balance
+    /// Validate a cyclic swap path
+    /// - Must start and end with the same token
+    /// - Must visit at least two other tokens, without repeating any
+    /// - Must not exceed maximum length
+    fn validate_cyclic_path(path: &Vec<Address>) -> Result<(), AstroSwapError> {
+        let len = path.len();
+        if len > Self::MAX_PATH_LENGTH {
+            return Err(AstroSwapError::RouteTooLong);
+        }
+        if len < 4 || path.first() != path.last() {
+            return Err(AstroSwapError::InvalidPath);
+        }
+
+        // Without the closing token, the usual rules apply
+        Self::validate_path(&path.slice(0..len - 1))
+    }
+
+    /// Swap the excess of the over-supplied token so the amounts match the
+    /// post-swap pool ratio. Returns the balanced (amount_0, amount_1).
+    /// Empty pools are left alone: the first deposit sets the price.
+    fn balance_deposit(
+        env: &Env,
+        user: &Address,
+        pair_address: &Address,
+        amount_0: i128,
+        amount_1: i128,
+        max_swap_slippage_bps: u32,
+        deadline: u64,
+    ) -> Result<(i128, i128), AstroSwapError> {
+        let pair_client = PairClient::new(env, pair_address);
+        let (reserve_0, reserve_1) = pair_client.get_reserves();
+        if reserve_0 == 0 || reserve_1 == 0 {
+            return Ok((amount_0, amount_1));
+        }
+
+        // Token 0 is over-supplied if it would need more token 1 than provided
+        let zero_for_one = quote(amount_0, reserve_0, reserve_1)? > amount_1;
+        let (amount_in, amount_other, reserve_in, reserve_out) = if zero_for_one {
+            (amount_0, amount_1, reserve_0, reserve_1)
+        } else {
+            (amount_1, amount_0, reserve_1, reserve_0)
+        };
+
+        let fee_bps = pair_client.fee_bps();
+        let swap_amount = calculate_balancing_swap_amount(
+            amount_in,
+            amount_other,
+            reserve_in,
+            reserve_out,
+            fee_bps,
+        )?;
+        if swap_amount <= 0 {
+            return Ok((amount_0, amount_1));
+        }
+        let expected_out = get_amount_out(swap_amount, reserve_in, reserve_out, fee_bps)?;
+        if expected_out <= 0 {
+            return Ok((amount_0, amount_1));
+        }
+
+        // Bound the value lost to fee and price impact
+        let spot_out = quote(swap_amount, reserve_in, reserve_out)?;
+        let min_out = apply_bps(spot_out, BPS_DENOMINATOR - max_swap_slippage_bps)?;
+        if expected_out < min_out {
+            return Err(AstroSwapError::SlippageExceeded);
+        }
+
+        let token_in = if zero_for_one {
+            pair_client.token_0()
+        } else {
+            pair_client.token_1()
+        };
+        token::Client::new(env, &token_in).transfer(user, pair_address, &swap_amount);
+        let (_, amount_out) = pair_client.swap_from_balance(user, &token_in, min_out, deadline)?;
+
+        if zero_for_one {
+            Ok((
+                safe_sub(amount_0, swap_amount)?,
+                safe_add(amount_1, amount_out)?,
+            ))
+        } else {
+            Ok((
+                safe_add(amount_0, amount_out)?,
+                safe_sub(amount_1, swap_amount)?,
+            ))
+        }
+    }
+
+    /// Execute swaps along the path through `pairs` using low-level swap_from_balance
     /// Tokens must be pre-transferred to the first pair
+    ///
+    /// Each hop must return at least its quoted amount.
+    /// `pairs` come from `get_amounts_out`/`get_amounts_in`, so every hop
+    /// already meets the minimum reserve depth.
     fn execute_swaps(
         env: &Env,
-        factory: &Address,
         path: &Vec<Address>,
+        pairs: &Vec<Address>,
         amounts: &Vec<i128>,
         recipient: &Address,
         deadline: u64,
     ) -> Result<(), AstroSwapError> {
-        let factory_client = FactoryClient::new(env, factory);
-
         for i in 0..(path.len() - 1) {
             // Safe indexing with proper error handling
             let token_in = path.get(i).ok_or(AstroSwapError::InvalidPath)?;
-            let token_out = path.get(i + 1).ok_or(AstroSwapError::InvalidPath)?;
             let min_out = amounts.get(i + 1).ok_or(AstroSwapError::InvalidPath)?;
 
-            // Get pair
-            let pair_address = factory_client
-                .get_pair(&token_in, &token_out)
-                .ok_or(AstroSwapError::PairNotFound)?;
-
+            let pair_address = pairs.get(i).ok_or(AstroSwapError::InvalidPath)?;
             let pair_client = PairClient::new(env, &pair_address);
 
             // Determine if this is the last swap
@@ -506,12 +1340,8 @@ impl AstroSwapRouter {
             let swap_recipient = if is_last {
                 recipient.clone()
             } else {
-                // Get next pair address - output goes directly to next pair
-                let next_token_in = path.get(i + 1).ok_or(AstroSwapError::InvalidPath)?;
-                let next_token_out = path.get(i + 2).ok_or(AstroSwapError::InvalidPath)?;
-                factory_client
-                    .get_pair(&next_token_in, &next_token_out)
-                    .ok_or(AstroSwapError::PairNotFound)?
+                // Output goes directly to the pair chosen for the next hop
+                pairs.get(i + 1).ok_or(AstroSwapError::InvalidPath)?
             };
 
             // Execute low-level swap (tokens already in pair from previous transfer/swap)
@@ -3,4 +3,5 @@
 mod contract;
 mod storage;
 
-pub use contract::{AstroSwapRouter, AstroSwapRouterClient};
+pub use contract::{AstroSwapRouter, AstroSwapRouterClient, TokenRecovered};
+pub use storage::TokenRecovery;
@@ -7,6 +7,20 @@ pub enum DataKey {
     Factory,
     Admin,
     Initialized,
+    MinReserveDepth,   // Reserve each side of a hop needs to be routed through
+    Recovery(Address), // Pending recovery of a stranded token
+}
+
+/// Admin recovery of a stranded token, executable once `ready_at` is reached
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenRecovery {
+    /// Recipient of the recovered tokens
+    pub to: Address,
+    /// Amount to recover
+    pub amount: i128,
+    /// Earliest execution time
+    pub ready_at: u64,
 }
 
 /// Check if the contract is initialized
@@ -48,6 +62,45 @@ pub fn set_admin(env: &Env, admin: &Address) {
     env.storage().instance().set(&DataKey::Admin, admin);
 }
 
+/// Get the minimum reserve depth of a routed hop (0 = no minimum)
+pub fn get_min_reserve_depth(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get::<DataKey, i128>(&DataKey::MinReserveDepth)
+        .unwrap_or(0)
+}
+
+/// Set the minimum reserve depth of a routed hop
+pub fn set_min_reserve_depth(env: &Env, min_reserve: i128) {
+    env.storage()
+        .instance()
+        .set(&DataKey::MinReserveDepth, &min_reserve);
+}
+
+/// Get the pending recovery of a token
+pub fn get_recovery(env: &Env, token: &Address) -> Option<TokenRecovery> {
+    env.storage()
+        .persistent()
+        .get::<DataKey, TokenRecovery>(&DataKey::Recovery(token.clone()))
+}
+
+/// Set the pending recovery of a token
+pub fn set_recovery(env: &Env, token: &Address, recovery: &TokenRecovery) {
+    let key = DataKey::Recovery(token.clone());
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, recovery);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+/// Remove the pending recovery of a token
+pub fn remove_recovery(env: &Env, token: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Recovery(token.clone()));
+}
+
 /// Extend TTL for instance storage
 pub fn extend_instance_ttl(env: &Env) {
     let max_ttl = env.storage().max_ttl();
@@ -1,3 +1,25 @@
+//! Error code registry for AstroSwap contracts
+//!
+//! Every contract error code is unique across the protocol so a code alone
+//! identifies the failure. Codes are grouped in reserved ranges:
+//!
+//! | Range   | Owner                   | Type                         |
+//! |---------|-------------------------|------------------------------|
+//! | 1-99    | General (all contracts) | `AstroSwapError`             |
+//! | 100-199 | Token handling          | `AstroSwapError`             |
+//! | 200-299 | Pair / liquidity        | `AstroSwapError`             |
+//! | 300-399 | Swap / router           | `AstroSwapError`             |
+//! | 400-499 | Staking                 | `AstroSwapError`             |
+//! | 500-599 | Admin / fees            | `AstroSwapError`             |
+//! | 600-699 | Aggregator              | `AstroSwapError`             |
+//! | 700-799 | Bridge                  | `AstroSwapError`             |
+//! | 800-899 | Oracle                  | `OracleError` (oracle crate) |
+//! | 900-999 | Governance              | `AstroSwapError`             |
+//!
+//! New variants go in their owner's range; existing codes are never reused.
+//! Reentrancy guards in every contract (pair, staking, aggregator, bridge)
+//! report `Reentrancy`.
+
 use astro_core_shared::types::SharedError;
 use soroban_sdk::contracterror;
 
@@ -15,6 +37,7 @@ pub enum AstroSwapError {
     Underflow = 6,
     DivisionByZero = 7,
     Reentrancy = 8,
+    InvalidCursor = 9,
 
     // Token errors (100-199)
     InvalidToken = 100,
@@ -31,6 +54,11 @@ pub enum AstroSwapError {
     PoolNotFound = 204,
     PairExists = 205,
     PairNotFound = 206,
+    ReserveCapExceeded = 207,
+    ReserveBelowMinimum = 208,
+    WithdrawalNotRequested = 209,
+    WithdrawalDelayActive = 210,
+    WithdrawalRequestExpired = 211,
 
     // Swap errors (300-399)
     SlippageExceeded = 300,
@@ -40,6 +68,8 @@ pub enum AstroSwapError {
     InvalidPath = 304,
     PriceImpactTooHigh = 305,
     AmountBelowMinimum = 306,
+    RouteTooLong = 307,
+    TwapDeviationExceeded = 308,
 
     // Staking errors (400-499)
     StakingPoolNotFound = 400,
@@ -49,6 +79,18 @@ pub enum AstroSwapError {
     NoRewardsAvailable = 404,
     InvalidStakingPeriod = 405,
     StakeNotFound = 406,
+    CompoundNotSupported = 407,
+    CheckpointNotFound = 408,
+    CooldownActive = 409,
+    PoolMigrated = 410,
+    EmissionRateTooHigh = 411,
+    RewardsUnfunded = 412,
+    ClaimPeriodExpired = 413,
+    ClaimPeriodActive = 414,
+    RetroDropNotFound = 415,
+    InvalidMerkleProof = 416,
+    RetroRewardClaimed = 417,
+    RetroDropClosed = 418,
 
     // Admin errors (500-599)
     InvalidFee = 500,
@@ -56,12 +98,19 @@ pub enum AstroSwapError {
     TimelockNotExpired = 502,
     InvalidAdmin = 503,
     ContractPaused = 504,
+    RecoveryNotRequested = 505,
+    FeeRecipientNotSet = 506,
+    ComponentNotRegistered = 507,
 
     // Aggregator errors (600-699)
     ProtocolNotFound = 600,
     InvalidRoute = 601,
     RouteNotFound = 602,
     AdapterError = 603,
+    QuoteStale = 604,
+    ProtocolExists = 605,
+    ProtocolInactive = 606,
+    PoolPaused = 607,
 
     // Bridge errors (700-799)
     TokenNotGraduated = 700,
@@ -69,6 +118,19 @@ pub enum AstroSwapError {
     InvalidLaunchpad = 702,
     GraduationFailed = 703,
     InvalidPair = 704,
+    QuoteTokenNotSet = 705,
+    GraduationNotFound = 706,
+    InitialPriceOutOfBand = 707,
+    LbpNotFound = 708,
+    LbpActive = 709,
+    LbpEnded = 710,
+    ClawbackEnabled = 711,
+
+    // Governance errors (900-999)
+    ProposalNotFound = 900,
+    InvalidProposalState = 901,
+    AlreadyVoted = 902,
+    BelowProposalThreshold = 903,
 }
 
 /// Convert SharedError from astro-core-shared to AstroSwapError
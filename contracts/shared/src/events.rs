@@ -1,24 +1,65 @@
 //! Events for AstroSwap contracts
 //!
 //! Using modern #[contractevent] macro for type-safe event emission
+//!
+//! # Schema (version 1)
+//!
+//! Every event is published with the topics `[NAMESPACE, <event topic>, <indexed
+//! field>]` and a map payload carrying a `version` field
+//! (`EVENT_SCHEMA_VERSION`). Indexers can filter on the first two topics and
+//! decode the payload by version. Fields are only ever appended within a version;
+//! removing or retyping a field bumps `EVENT_SCHEMA_VERSION`.
+
+use crate::math::SwapFeeBreakdown;
+use soroban_sdk::{contractevent, Address, Env, Symbol, Vec};
+
+/// Version of the event payload layout
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
 
-use soroban_sdk::{contractevent, Address, Env};
+/// Topic constants for AstroSwap events (must match the `#[contractevent]` topics)
+pub mod topics {
+    /// First topic of every AstroSwap event
+    pub const NAMESPACE: &str = "astroswap";
+
+    pub const SWAP: &str = "swap";
+    pub const DEPOSIT: &str = "deposit";
+    pub const WITHDRAW: &str = "withdraw";
+    pub const PAIR_CREATED: &str = "pair_created";
+    pub const STAKE: &str = "stake";
+    pub const UNSTAKE: &str = "unstake";
+    pub const CLAIM: &str = "claim";
+    pub const GRADUATION: &str = "graduation";
+    pub const ROUTE_EXECUTED: &str = "route_executed";
+    pub const ROUTER_SWAP: &str = "router_swap";
+    pub const ROUTER_ADD_LIQUIDITY: &str = "router_add_liquidity";
+    pub const ROUTER_REMOVE_LIQUIDITY: &str = "router_remove_liquidity";
+}
 
 /// Swap event - emitted when tokens are swapped
-#[contractevent]
+#[contractevent(topics = ["astroswap", "swap"])]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Swap {
+    pub version: u32,
+    #[topic]
     pub user: Address,
     pub token_in: Address,
     pub token_out: Address,
     pub amount_in: i128,
     pub amount_out: i128,
+    /// Total fee charged on amount_in
+    pub fee_amount: i128,
+    /// Portion of fee_amount kept by liquidity providers
+    pub lp_fee: i128,
+    /// Portion of fee_amount attributed to the protocol
+    pub protocol_fee: i128,
 }
 
 /// Deposit event - emitted when liquidity is added
-#[contractevent]
+#[contractevent(topics = ["astroswap", "deposit"])]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Deposit {
+    pub version: u32,
+    #[topic]
     pub user: Address,
     pub pair: Address,
     pub amount_a: i128,
@@ -27,9 +68,11 @@ pub struct Deposit {
 }
 
 /// Withdraw event - emitted when liquidity is removed
-#[contractevent]
+#[contractevent(topics = ["astroswap", "withdraw"])]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Withdraw {
+    pub version: u32,
+    #[topic]
     pub user: Address,
     pub pair: Address,
     pub shares_burned: i128,
@@ -38,52 +81,125 @@ pub struct Withdraw {
 }
 
 /// PairCreated event - emitted when a new pair is created
-#[contractevent]
+#[contractevent(topics = ["astroswap", "pair_created"])]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PairCreated {
+    pub version: u32,
     pub token_a: Address,
     pub token_b: Address,
+    #[topic]
     pub pair: Address,
     pub pair_count: u32,
 }
 
 /// Stake event - emitted when LP tokens are staked
-#[contractevent]
+#[contractevent(topics = ["astroswap", "stake"])]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Stake {
+    pub version: u32,
+    #[topic]
     pub user: Address,
     pub pool_id: u32,
     pub amount: i128,
 }
 
 /// Unstake event - emitted when LP tokens are unstaked
-#[contractevent]
+#[contractevent(topics = ["astroswap", "unstake"])]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Unstake {
+    pub version: u32,
+    #[topic]
     pub user: Address,
     pub pool_id: u32,
     pub amount: i128,
 }
 
 /// Claim event - emitted when staking rewards are claimed
-#[contractevent]
+#[contractevent(topics = ["astroswap", "claim"])]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Claim {
+    pub version: u32,
+    #[topic]
     pub user: Address,
     pub pool_id: u32,
     pub reward_amount: i128,
 }
 
 /// Graduation event - emitted when a token graduates from Astro-Shiba
-#[contractevent]
+#[contractevent(topics = ["astroswap", "graduation"])]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Graduation {
+    pub version: u32,
+    #[topic]
     pub token: Address,
     pub pair: Address,
     pub initial_price: i128,
     pub timestamp: u64,
 }
 
+/// RouteExecuted event - emitted when a multi-hop route completes (router, aggregator)
+#[contractevent(topics = ["astroswap", "route_executed"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RouteExecuted {
+    pub version: u32,
+    #[topic]
+    pub user: Address,
+    pub token_in: Address,
+    pub token_out: Address,
+    pub amount_in: i128,
+    pub amount_out: i128,
+    pub hops: u32,
+}
+
+/// RouterSwap event - emitted by the router for every swap it executes
+#[contractevent(topics = ["astroswap", "router_swap"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RouterSwap {
+    pub version: u32,
+    #[topic]
+    pub user: Address,
+    /// Tag identifying the client (frontend, bot, integrator) behind the call
+    pub client_tag: Symbol,
+    /// Full token path [token_in, ..., token_out]
+    pub path: Vec<Address>,
+    /// Amount entering each hop, followed by the final output
+    pub amounts: Vec<i128>,
+    /// Whether the swap was exact-output (`swap_tokens_for_exact_tokens`)
+    pub exact_output: bool,
+}
+
+/// RouterAddLiquidity event - emitted by the router when liquidity is added
+#[contractevent(topics = ["astroswap", "router_add_liquidity"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RouterAddLiquidity {
+    pub version: u32,
+    #[topic]
+    pub user: Address,
+    pub client_tag: Symbol,
+    pub pair: Address,
+    pub token_a: Address,
+    pub token_b: Address,
+    pub amount_a: i128,
+    pub amount_b: i128,
+    pub shares_minted: i128,
+}
+
+/// RouterRemoveLiquidity event - emitted by the router when liquidity is removed
+#[contractevent(topics = ["astroswap", "router_remove_liquidity"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RouterRemoveLiquidity {
+    pub version: u32,
+    #[topic]
+    pub user: Address,
+    pub client_tag: Symbol,
+    pub pair: Address,
+    pub token_a: Address,
+    pub token_b: Address,
+    pub shares_burned: i128,
+    pub amount_a: i128,
+    pub amount_b: i128,
+}
+
 /// Emit a swap event
 pub fn emit_swap(
     env: &Env,
@@ -92,13 +208,18 @@ pub fn emit_swap(
     token_out: &Address,
     amount_in: i128,
     amount_out: i128,
+    fees: &SwapFeeBreakdown,
 ) {
     Swap {
+        version: EVENT_SCHEMA_VERSION,
         user: user.clone(),
         token_in: token_in.clone(),
         token_out: token_out.clone(),
         amount_in,
         amount_out,
+        fee_amount: fees.fee_amount,
+        lp_fee: fees.lp_fee,
+        protocol_fee: fees.protocol_fee,
     }
     .publish(env);
 }
@@ -113,6 +234,7 @@ pub fn emit_deposit(
     shares_minted: i128,
 ) {
     Deposit {
+        version: EVENT_SCHEMA_VERSION,
         user: user.clone(),
         pair: pair.clone(),
         amount_a,
@@ -132,6 +254,7 @@ pub fn emit_withdraw(
     amount_b: i128,
 ) {
     Withdraw {
+        version: EVENT_SCHEMA_VERSION,
         user: user.clone(),
         pair: pair.clone(),
         shares_burned,
@@ -150,6 +273,7 @@ pub fn emit_pair_created(
     pair_count: u32,
 ) {
     PairCreated {
+        version: EVENT_SCHEMA_VERSION,
         token_a: token_a.clone(),
         token_b: token_b.clone(),
         pair: pair.clone(),
@@ -161,6 +285,7 @@ pub fn emit_pair_created(
 /// Emit a stake event
 pub fn emit_stake(env: &Env, user: &Address, pool_id: u32, amount: i128) {
     Stake {
+        version: EVENT_SCHEMA_VERSION,
         user: user.clone(),
         pool_id,
         amount,
@@ -171,6 +296,7 @@ pub fn emit_stake(env: &Env, user: &Address, pool_id: u32, amount: i128) {
 /// Emit an unstake event
 pub fn emit_unstake(env: &Env, user: &Address, pool_id: u32, amount: i128) {
     Unstake {
+        version: EVENT_SCHEMA_VERSION,
         user: user.clone(),
         pool_id,
         amount,
@@ -181,6 +307,7 @@ pub fn emit_unstake(env: &Env, user: &Address, pool_id: u32, amount: i128) {
 /// Emit a claim rewards event
 pub fn emit_claim(env: &Env, user: &Address, pool_id: u32, reward_amount: i128) {
     Claim {
+        version: EVENT_SCHEMA_VERSION,
         user: user.clone(),
         pool_id,
         reward_amount,
@@ -192,6 +319,7 @@ pub fn emit_claim(env: &Env, user: &Address, pool_id: u32, reward_amount: i128)
 pub fn emit_graduation(env: &Env, token: &Address, pair: &Address, initial_price: i128) {
     let timestamp = env.ledger().timestamp();
     Graduation {
+        version: EVENT_SCHEMA_VERSION,
         token: token.clone(),
         pair: pair.clone(),
         initial_price,
@@ -199,3 +327,99 @@ pub fn emit_graduation(env: &Env, token: &Address, pair: &Address, initial_price
     }
     .publish(env);
 }
+
+/// Emit a route executed event for a completed multi-hop swap
+pub fn emit_route_executed(
+    env: &Env,
+    user: &Address,
+    token_in: &Address,
+    token_out: &Address,
+    amount_in: i128,
+    amount_out: i128,
+    hops: u32,
+) {
+    RouteExecuted {
+        version: EVENT_SCHEMA_VERSION,
+        user: user.clone(),
+        token_in: token_in.clone(),
+        token_out: token_out.clone(),
+        amount_in,
+        amount_out,
+        hops,
+    }
+    .publish(env);
+}
+
+/// Emit a router swap event
+pub fn emit_router_swap(
+    env: &Env,
+    user: &Address,
+    client_tag: &Symbol,
+    path: &Vec<Address>,
+    amounts: &Vec<i128>,
+    exact_output: bool,
+) {
+    RouterSwap {
+        version: EVENT_SCHEMA_VERSION,
+        user: user.clone(),
+        client_tag: client_tag.clone(),
+        path: path.clone(),
+        amounts: amounts.clone(),
+        exact_output,
+    }
+    .publish(env);
+}
+
+/// Emit a router add liquidity event
+#[allow(clippy::too_many_arguments)]
+pub fn emit_router_add_liquidity(
+    env: &Env,
+    user: &Address,
+    client_tag: &Symbol,
+    pair: &Address,
+    token_a: &Address,
+    token_b: &Address,
+    amount_a: i128,
+    amount_b: i128,
+    shares_minted: i128,
+) {
+    RouterAddLiquidity {
+        version: EVENT_SCHEMA_VERSION,
+        user: user.clone(),
+        client_tag: client_tag.clone(),
+        pair: pair.clone(),
+        token_a: token_a.clone(),
+        token_b: token_b.clone(),
+        amount_a,
+        amount_b,
+        shares_minted,
+    }
+    .publish(env);
+}
+
+/// Emit a router remove liquidity event
+#[allow(clippy::too_many_arguments)]
+pub fn emit_router_remove_liquidity(
+    env: &Env,
+    user: &Address,
+    client_tag: &Symbol,
+    pair: &Address,
+    token_a: &Address,
+    token_b: &Address,
+    shares_burned: i128,
+    amount_a: i128,
+    amount_b: i128,
+) {
+    RouterRemoveLiquidity {
+        version: EVENT_SCHEMA_VERSION,
+        user: user.clone(),
+        client_tag: client_tag.clone(),
+        pair: pair.clone(),
+        token_a: token_a.clone(),
+        token_b: token_b.clone(),
+        shares_burned,
+        amount_a,
+        amount_b,
+    }
+    .publish(env);
+}
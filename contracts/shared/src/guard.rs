@@ -0,0 +1,161 @@
+//! Reentrancy guard and pause checks
+//!
+//! Contracts keep their lock and pause flags as `bool`s in instance storage
+//! under their own `DataKey` variants; these helpers take that key so the
+//! storage layout of each contract is unchanged. `with_ledger_lock` stores
+//! the ledger sequence instead, for locks that must expire on their own.
+
+use crate::AstroSwapError;
+use soroban_sdk::{Env, IntoVal, Val};
+
+/// Run `f` while holding the reentrancy lock stored under `lock_key`.
+///
+/// Fails with `Reentrancy` if the lock is already held. The lock is released
+/// after `f` returns, whether it succeeded or not, so early returns inside `f`
+/// cannot leave the contract locked.
+pub fn with_lock<K, T, F>(env: &Env, lock_key: &K, f: F) -> Result<T, AstroSwapError>
+where
+    K: IntoVal<Env, Val>,
+    F: FnOnce() -> Result<T, AstroSwapError>,
+{
+    let storage = env.storage().instance();
+    if storage.get::<K, bool>(lock_key).unwrap_or(false) {
+        return Err(AstroSwapError::Reentrancy);
+    }
+    storage.set(lock_key, &true);
+
+    let result = f();
+
+    storage.set(lock_key, &false);
+    result
+}
+
+/// Run `f` while holding a reentrancy lock that expires with the ledger.
+///
+/// Like `with_lock`, but the lock stores the ledger sequence it was taken in
+/// instead of a `bool`. A lock left behind by an earlier ledger is treated as
+/// released, so a crashed invocation can never lock the contract for good.
+pub fn with_ledger_lock<K, T, F>(env: &Env, lock_key: &K, f: F) -> Result<T, AstroSwapError>
+where
+    K: IntoVal<Env, Val>,
+    F: FnOnce() -> Result<T, AstroSwapError>,
+{
+    let storage = env.storage().instance();
+    let sequence = env.ledger().sequence();
+    if storage.get::<K, u32>(lock_key) == Some(sequence) {
+        return Err(AstroSwapError::Reentrancy);
+    }
+    storage.set(lock_key, &sequence);
+
+    let result = f();
+
+    storage.remove(lock_key);
+    result
+}
+
+/// Fail with `ContractPaused` if the pause flag stored under `paused_key` is set
+pub fn require_not_paused<K>(env: &Env, paused_key: &K) -> Result<(), AstroSwapError>
+where
+    K: IntoVal<Env, Val>,
+{
+    if env
+        .storage()
+        .instance()
+        .get::<K, bool>(paused_key)
+        .unwrap_or(false)
+    {
+        return Err(AstroSwapError::ContractPaused);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::testutils::Ledger;
+    use soroban_sdk::{contract, symbol_short, Symbol};
+
+    #[contract]
+    struct GuardHost;
+
+    const LOCKED: Symbol = symbol_short!("LOCKED");
+    const PAUSED: Symbol = symbol_short!("PAUSED");
+
+    fn is_locked(env: &Env) -> bool {
+        env.storage()
+            .instance()
+            .get::<Symbol, bool>(&LOCKED)
+            .unwrap_or(false)
+    }
+
+    #[test]
+    fn test_lock_released_on_error() {
+        let env = Env::default();
+        let host = env.register(GuardHost, ());
+
+        env.as_contract(&host, || {
+            let result: Result<(), AstroSwapError> = with_lock(&env, &LOCKED, || {
+                assert!(is_locked(&env));
+                Err(AstroSwapError::InvalidAmount)
+            });
+            assert_eq!(result, Err(AstroSwapError::InvalidAmount));
+            assert!(!is_locked(&env));
+
+            assert_eq!(with_lock(&env, &LOCKED, || Ok(7)), Ok(7));
+            assert!(!is_locked(&env));
+        });
+    }
+
+    #[test]
+    fn test_nested_lock_rejected() {
+        let env = Env::default();
+        let host = env.register(GuardHost, ());
+
+        env.as_contract(&host, || {
+            let result = with_lock(&env, &LOCKED, || with_lock(&env, &LOCKED, || Ok(())));
+            assert_eq!(result, Err(AstroSwapError::Reentrancy));
+            assert!(!is_locked(&env));
+        });
+    }
+
+    #[test]
+    fn test_ledger_lock_expires() {
+        let env = Env::default();
+        let host = env.register(GuardHost, ());
+
+        env.as_contract(&host, || {
+            let nested =
+                with_ledger_lock(&env, &LOCKED, || with_ledger_lock(&env, &LOCKED, || Ok(())));
+            assert_eq!(nested, Err(AstroSwapError::Reentrancy));
+            assert!(!env.storage().instance().has(&LOCKED));
+
+            // A lock left set in this ledger still blocks
+            let sequence = env.ledger().sequence();
+            env.storage().instance().set(&LOCKED, &sequence);
+            assert_eq!(
+                with_ledger_lock(&env, &LOCKED, || Ok(())),
+                Err(AstroSwapError::Reentrancy)
+            );
+
+            // ...but is released once the ledger closes
+            env.ledger().with_mut(|li| li.sequence_number += 1);
+            assert_eq!(with_ledger_lock(&env, &LOCKED, || Ok(7)), Ok(7));
+            assert!(!env.storage().instance().has(&LOCKED));
+        });
+    }
+
+    #[test]
+    fn test_require_not_paused() {
+        let env = Env::default();
+        let host = env.register(GuardHost, ());
+
+        env.as_contract(&host, || {
+            assert_eq!(require_not_paused(&env, &PAUSED), Ok(()));
+            env.storage().instance().set(&PAUSED, &true);
+            assert_eq!(
+                require_not_paused(&env, &PAUSED),
+                Err(AstroSwapError::ContractPaused)
+            );
+        });
+    }
+}
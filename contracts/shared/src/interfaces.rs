@@ -4,8 +4,25 @@
 //! without requiring WASM imports at compile time. This approach is more modular
 //! and allows contracts to be built independently.
 
-use crate::AstroSwapError;
-use soroban_sdk::{Address, Env, IntoVal, Symbol, Vec};
+use crate::{mul_div_down, AstroSwapError, ComponentEntry, GraduatedToken, PairInfo, PoolMetadata, PriceData, ProtocolComponent, StakingPool, SwapRoute, TokenMetadata, UserStake};
+use soroban_sdk::{Address, Env, InvokeError, IntoVal, Symbol, Val, Vec};
+
+/// Flatten the result of `try_invoke_contract` into an `AstroSwapError`.
+///
+/// `AstroSwapError`s raised by the callee are passed through unchanged. Codes
+/// outside `AstroSwapError` and host failures become `fallback`; a return value
+/// that fails to decode becomes `InvalidArgument`.
+fn flatten<T, C>(
+    result: Result<Result<T, C>, Result<AstroSwapError, InvokeError>>,
+    fallback: AstroSwapError,
+) -> Result<T, AstroSwapError> {
+    match result {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(_)) => Err(AstroSwapError::InvalidArgument),
+        Err(Ok(err)) => Err(err),
+        Err(Err(_)) => Err(fallback),
+    }
+}
 
 /// Factory contract interface
 /// Provides methods to interact with the AstroSwap Factory contract
@@ -32,6 +49,16 @@ impl<'a> FactoryClient<'a> {
         result
     }
 
+    /// Get the pairs of two tokens in every fee tier as (pair, fee_bps),
+    /// default tier first
+    pub fn get_pairs_all_tiers(&self, token_a: &Address, token_b: &Address) -> Vec<(Address, u32)> {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "get_pairs_all_tiers"),
+            Vec::from_array(self.env, [token_a.to_val(), token_b.to_val()]),
+        )
+    }
+
     /// Create a new trading pair
     pub fn create_pair(
         &self,
@@ -46,6 +73,25 @@ impl<'a> FactoryClient<'a> {
         Ok(result)
     }
 
+    /// Create a new trading pair, recording `creator` in the pair metadata
+    /// The creator must authorize the call (implicit for the invoking contract)
+    pub fn create_pair_for(
+        &self,
+        creator: &Address,
+        token_a: &Address,
+        token_b: &Address,
+    ) -> Result<Address, AstroSwapError> {
+        let result: Address = self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "create_pair_for"),
+            Vec::from_array(
+                self.env,
+                [creator.to_val(), token_a.to_val(), token_b.to_val()],
+            ),
+        );
+        Ok(result)
+    }
+
     /// Get admin address
     pub fn admin(&self) -> Address {
         self.env.invoke_contract(
@@ -64,6 +110,15 @@ impl<'a> FactoryClient<'a> {
         )
     }
 
+    /// Get the protocol fee recipient, if set
+    pub fn fee_to(&self) -> Option<Address> {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "fee_to"),
+            Vec::new(self.env),
+        )
+    }
+
     /// Check if factory is paused
     pub fn is_paused(&self) -> bool {
         self.env.invoke_contract(
@@ -76,6 +131,10 @@ impl<'a> FactoryClient<'a> {
 
 /// Pair contract interface
 /// Provides methods to interact with AstroSwap Pair contracts
+///
+/// `try_` variants return the callee's error instead of trapping, so callers
+/// can tell e.g. `SlippageExceeded` from `ContractPaused` and fall back;
+/// failures that are not an `AstroSwapError` are reported as `AdapterError`.
 pub struct PairClient<'a> {
     env: &'a Env,
     contract_id: Address,
@@ -89,6 +148,90 @@ impl<'a> PairClient<'a> {
         }
     }
 
+    fn swap_args(
+        &self,
+        user: &Address,
+        token_in: &Address,
+        amount_in: i128,
+        min_out: i128,
+        deadline: u64,
+    ) -> Vec<Val> {
+        Vec::from_array(
+            self.env,
+            [
+                user.to_val(),
+                token_in.to_val(),
+                amount_in.into_val(self.env),
+                min_out.into_val(self.env),
+                deadline.into_val(self.env),
+            ],
+        )
+    }
+
+    fn deposit_args(
+        &self,
+        user: &Address,
+        amount_0_desired: i128,
+        amount_1_desired: i128,
+        amount_0_min: i128,
+        amount_1_min: i128,
+        deadline: u64,
+    ) -> Vec<Val> {
+        Vec::from_array(
+            self.env,
+            [
+                user.to_val(),
+                amount_0_desired.into_val(self.env),
+                amount_1_desired.into_val(self.env),
+                amount_0_min.into_val(self.env),
+                amount_1_min.into_val(self.env),
+                deadline.into_val(self.env),
+            ],
+        )
+    }
+
+    fn withdraw_args(
+        &self,
+        user: &Address,
+        shares: i128,
+        amount_0_min: i128,
+        amount_1_min: i128,
+        deadline: u64,
+    ) -> Vec<Val> {
+        Vec::from_array(
+            self.env,
+            [
+                user.to_val(),
+                shares.into_val(self.env),
+                amount_0_min.into_val(self.env),
+                amount_1_min.into_val(self.env),
+                deadline.into_val(self.env),
+            ],
+        )
+    }
+
+    fn amount_token_args(&self, amount: i128, token: &Address) -> Vec<Val> {
+        Vec::from_array(self.env, [amount.into_val(self.env), token.to_val()])
+    }
+
+    fn swap_from_balance_args(
+        &self,
+        to: &Address,
+        token_in: &Address,
+        min_out: i128,
+        deadline: u64,
+    ) -> Vec<Val> {
+        Vec::from_array(
+            self.env,
+            [
+                to.to_val(),
+                token_in.to_val(),
+                min_out.into_val(self.env),
+                deadline.into_val(self.env),
+            ],
+        )
+    }
+
     /// Get token 0 address
     pub fn token_0(&self) -> Address {
         self.env.invoke_contract(
@@ -116,6 +259,27 @@ impl<'a> PairClient<'a> {
         )
     }
 
+    /// Get reserves, returning the pair error on failure
+    pub fn try_get_reserves(&self) -> Result<(i128, i128), AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<(i128, i128), AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "get_reserves"),
+                Vec::new(self.env),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+
+    /// Get tokens, reserves, LP supply and fee in one call
+    pub fn get_info(&self) -> PairInfo {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "get_info"),
+            Vec::new(self.env),
+        )
+    }
+
     /// Get fee in basis points
     pub fn fee_bps(&self) -> u32 {
         self.env.invoke_contract(
@@ -125,6 +289,24 @@ impl<'a> PairClient<'a> {
         )
     }
 
+    /// Get k_last (product of reserves at last interaction)
+    pub fn k_last(&self) -> i128 {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "k_last"),
+            Vec::new(self.env),
+        )
+    }
+
+    /// Check if the pair is paused
+    pub fn is_paused(&self) -> bool {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "is_paused"),
+            Vec::new(self.env),
+        )
+    }
+
     /// Execute a swap
     pub fn swap(
         &self,
@@ -132,23 +314,35 @@ impl<'a> PairClient<'a> {
         token_in: &Address,
         amount_in: i128,
         min_out: i128,
+        deadline: u64,
     ) -> Result<i128, AstroSwapError> {
         let result: i128 = self.env.invoke_contract(
             &self.contract_id,
             &Symbol::new(self.env, "swap"),
-            Vec::from_array(
-                self.env,
-                [
-                    user.to_val(),
-                    token_in.to_val(),
-                    amount_in.into_val(self.env),
-                    min_out.into_val(self.env),
-                ],
-            ),
+            self.swap_args(user, token_in, amount_in, min_out, deadline),
         );
         Ok(result)
     }
 
+    /// Execute a swap, returning the pair error on failure
+    pub fn try_swap(
+        &self,
+        user: &Address,
+        token_in: &Address,
+        amount_in: i128,
+        min_out: i128,
+        deadline: u64,
+    ) -> Result<i128, AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<i128, AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "swap"),
+                self.swap_args(user, token_in, amount_in, min_out, deadline),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+
     /// Deposit liquidity
     pub fn deposit(
         &self,
@@ -157,20 +351,46 @@ impl<'a> PairClient<'a> {
         amount_1_desired: i128,
         amount_0_min: i128,
         amount_1_min: i128,
+        deadline: u64,
     ) -> (i128, i128, i128) {
         self.env.invoke_contract(
             &self.contract_id,
             &Symbol::new(self.env, "deposit"),
-            Vec::from_array(
-                self.env,
-                [
-                    user.to_val(),
-                    amount_0_desired.into_val(self.env),
-                    amount_1_desired.into_val(self.env),
-                    amount_0_min.into_val(self.env),
-                    amount_1_min.into_val(self.env),
-                ],
+            self.deposit_args(
+                user,
+                amount_0_desired,
+                amount_1_desired,
+                amount_0_min,
+                amount_1_min,
+                deadline,
+            ),
+        )
+    }
+
+    /// Deposit liquidity, returning the pair error on failure
+    pub fn try_deposit(
+        &self,
+        user: &Address,
+        amount_0_desired: i128,
+        amount_1_desired: i128,
+        amount_0_min: i128,
+        amount_1_min: i128,
+        deadline: u64,
+    ) -> Result<(i128, i128, i128), AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<(i128, i128, i128), AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "deposit"),
+                self.deposit_args(
+                    user,
+                    amount_0_desired,
+                    amount_1_desired,
+                    amount_0_min,
+                    amount_1_min,
+                    deadline,
+                ),
             ),
+            AstroSwapError::AdapterError,
         )
     }
 
@@ -181,19 +401,31 @@ impl<'a> PairClient<'a> {
         shares: i128,
         amount_0_min: i128,
         amount_1_min: i128,
+        deadline: u64,
     ) -> (i128, i128) {
         self.env.invoke_contract(
             &self.contract_id,
             &Symbol::new(self.env, "withdraw"),
-            Vec::from_array(
-                self.env,
-                [
-                    user.to_val(),
-                    shares.into_val(self.env),
-                    amount_0_min.into_val(self.env),
-                    amount_1_min.into_val(self.env),
-                ],
+            self.withdraw_args(user, shares, amount_0_min, amount_1_min, deadline),
+        )
+    }
+
+    /// Withdraw liquidity, returning the pair error on failure
+    pub fn try_withdraw(
+        &self,
+        user: &Address,
+        shares: i128,
+        amount_0_min: i128,
+        amount_1_min: i128,
+        deadline: u64,
+    ) -> Result<(i128, i128), AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<(i128, i128), AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "withdraw"),
+                self.withdraw_args(user, shares, amount_0_min, amount_1_min, deadline),
             ),
+            AstroSwapError::AdapterError,
         )
     }
 
@@ -206,11 +438,54 @@ impl<'a> PairClient<'a> {
         let result: i128 = self.env.invoke_contract(
             &self.contract_id,
             &Symbol::new(self.env, "get_amount_out"),
-            Vec::from_array(self.env, [amount_in.into_val(self.env), token_in.to_val()]),
+            self.amount_token_args(amount_in, token_in),
         );
         Ok(result)
     }
 
+    /// Get expected output amount for a swap, returning the pair error on
+    /// failure
+    pub fn try_get_amount_out(
+        &self,
+        amount_in: i128,
+        token_in: &Address,
+    ) -> Result<i128, AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<i128, AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "get_amount_out"),
+                self.amount_token_args(amount_in, token_in),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+
+    /// Get required input amount for a specific output
+    pub fn get_amount_in(&self, amount_out: i128, token_out: &Address) -> i128 {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "get_amount_in"),
+            self.amount_token_args(amount_out, token_out),
+        )
+    }
+
+    /// Get required input amount for a specific output, returning the pair
+    /// error on failure
+    pub fn try_get_amount_in(
+        &self,
+        amount_out: i128,
+        token_out: &Address,
+    ) -> Result<i128, AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<i128, AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "get_amount_in"),
+                self.amount_token_args(amount_out, token_out),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+
     /// Low-level swap for router (tokens already in contract)
     /// Returns (amount_in, amount_out)
     pub fn swap_from_balance(
@@ -223,19 +498,73 @@ impl<'a> PairClient<'a> {
         let result: (i128, i128) = self.env.invoke_contract(
             &self.contract_id,
             &Symbol::new(self.env, "swap_from_balance"),
-            Vec::from_array(
-                self.env,
-                [
-                    to.to_val(),
-                    token_in.to_val(),
-                    min_out.into_val(self.env),
-                    deadline.into_val(self.env),
-                ],
-            ),
+            self.swap_from_balance_args(to, token_in, min_out, deadline),
         );
         Ok(result)
     }
 
+    /// Low-level swap for router, returning the pair error on failure
+    pub fn try_swap_from_balance(
+        &self,
+        to: &Address,
+        token_in: &Address,
+        min_out: i128,
+        deadline: u64,
+    ) -> Result<(i128, i128), AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<(i128, i128), AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "swap_from_balance"),
+                self.swap_from_balance_args(to, token_in, min_out, deadline),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+
+    /// Match reserves to the pair's balances
+    pub fn sync(&self) {
+        self.env.invoke_contract::<()>(
+            &self.contract_id,
+            &Symbol::new(self.env, "sync"),
+            Vec::new(self.env),
+        );
+    }
+
+    /// Match reserves to the pair's balances, returning the pair error on
+    /// failure
+    pub fn try_sync(&self) -> Result<(), AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<(), AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "sync"),
+                Vec::new(self.env),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+
+    /// Send balances above the reserves to `to`
+    pub fn skim(&self, to: &Address) {
+        self.env.invoke_contract::<()>(
+            &self.contract_id,
+            &Symbol::new(self.env, "skim"),
+            Vec::from_array(self.env, [to.to_val()]),
+        );
+    }
+
+    /// Send balances above the reserves to `to`, returning the pair error on
+    /// failure
+    pub fn try_skim(&self, to: &Address) -> Result<(), AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<(), AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "skim"),
+                Vec::from_array(self.env, [to.to_val()]),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+
     /// Get LP token balance
     pub fn balance(&self, owner: &Address) -> i128 {
         self.env.invoke_contract(
@@ -268,9 +597,25 @@ impl<'a> PairClient<'a> {
         );
         Ok(())
     }
+
+    /// Burn LP tokens, returning the pair error on failure
+    pub fn try_burn(&self, from: &Address, amount: i128) -> Result<(), AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<(), AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "burn"),
+                Vec::from_array(self.env, [from.to_val(), amount.into_val(self.env)]),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
 }
 
 /// Staking contract interface
+/// Provides methods to interact with the AstroSwap Staking contract
+///
+/// `try_` variants return the callee's error instead of trapping; failures
+/// that are not an `AstroSwapError` are reported as `AdapterError`.
 pub struct StakingClient<'a> {
     env: &'a Env,
     contract_id: Address,
@@ -284,23 +629,147 @@ impl<'a> StakingClient<'a> {
         }
     }
 
+    fn create_pool_args(
+        &self,
+        creator: &Address,
+        lp_token: &Address,
+        reward_per_second: i128,
+        start_time: u64,
+        end_time: u64,
+    ) -> Vec<Val> {
+        Vec::from_array(
+            self.env,
+            [
+                creator.to_val(),
+                lp_token.to_val(),
+                reward_per_second.into_val(self.env),
+                start_time.into_val(self.env),
+                end_time.into_val(self.env),
+            ],
+        )
+    }
+
+    fn stake_args(&self, user: &Address, pool_id: u32, amount: i128) -> Vec<Val> {
+        Vec::from_array(
+            self.env,
+            [
+                user.to_val(),
+                pool_id.into_val(self.env),
+                amount.into_val(self.env),
+            ],
+        )
+    }
+
+    fn user_pool_args(&self, user: &Address, pool_id: u32) -> Vec<Val> {
+        Vec::from_array(self.env, [user.to_val(), pool_id.into_val(self.env)])
+    }
+
+    /// Create a staking pool, returning its id
+    pub fn create_pool(
+        &self,
+        creator: &Address,
+        lp_token: &Address,
+        reward_per_second: i128,
+        start_time: u64,
+        end_time: u64,
+    ) -> u32 {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "create_pool"),
+            self.create_pool_args(creator, lp_token, reward_per_second, start_time, end_time),
+        )
+    }
+
+    /// Create a staking pool, returning the staking error on failure
+    pub fn try_create_pool(
+        &self,
+        creator: &Address,
+        lp_token: &Address,
+        reward_per_second: i128,
+        start_time: u64,
+        end_time: u64,
+    ) -> Result<u32, AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<u32, AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "create_pool"),
+                self.create_pool_args(creator, lp_token, reward_per_second, start_time, end_time),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+
+    /// Create a staking pool with metadata, returning the staking error on
+    /// failure
+    pub fn try_create_pool_with_metadata(
+        &self,
+        creator: &Address,
+        lp_token: &Address,
+        reward_per_second: i128,
+        start_time: u64,
+        end_time: u64,
+        metadata: &PoolMetadata,
+    ) -> Result<u32, AstroSwapError> {
+        let mut args =
+            self.create_pool_args(creator, lp_token, reward_per_second, start_time, end_time);
+        args.push_back(metadata.into_val(self.env));
+        flatten(
+            self.env.try_invoke_contract::<u32, AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "create_pool_with_metadata"),
+                args,
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+
+    /// Fund a pool's rewards in its reward token, returning the staking
+    /// error on failure
+    pub fn try_fund_pool(
+        &self,
+        funder: &Address,
+        pool_id: u32,
+        amount: i128,
+    ) -> Result<(), AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<(), AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "fund_pool"),
+                Vec::from_array(
+                    self.env,
+                    [
+                        funder.to_val(),
+                        pool_id.into_val(self.env),
+                        amount.into_val(self.env),
+                    ],
+                ),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+
     /// Stake LP tokens
     pub fn stake(&self, user: &Address, pool_id: u32, amount: i128) -> Result<(), AstroSwapError> {
         self.env.invoke_contract::<()>(
             &self.contract_id,
             &Symbol::new(self.env, "stake"),
-            Vec::from_array(
-                self.env,
-                [
-                    user.to_val(),
-                    pool_id.into_val(self.env),
-                    amount.into_val(self.env),
-                ],
-            ),
+            self.stake_args(user, pool_id, amount),
         );
         Ok(())
     }
 
+    /// Stake LP tokens, returning the staking error on failure
+    pub fn try_stake(&self, user: &Address, pool_id: u32, amount: i128) -> Result<(), AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<(), AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "stake"),
+                self.stake_args(user, pool_id, amount),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+
     /// Unstake LP tokens
     pub fn unstake(
         &self,
@@ -311,35 +780,746 @@ impl<'a> StakingClient<'a> {
         self.env.invoke_contract::<()>(
             &self.contract_id,
             &Symbol::new(self.env, "unstake"),
-            Vec::from_array(
-                self.env,
-                [
-                    user.to_val(),
-                    pool_id.into_val(self.env),
-                    amount.into_val(self.env),
-                ],
-            ),
+            self.stake_args(user, pool_id, amount),
         );
         Ok(())
     }
 
+    /// Unstake LP tokens, returning the staking error on failure
+    pub fn try_unstake(
+        &self,
+        user: &Address,
+        pool_id: u32,
+        amount: i128,
+    ) -> Result<(), AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<(), AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "unstake"),
+                self.stake_args(user, pool_id, amount),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+
     /// Claim rewards
     pub fn claim_rewards(&self, user: &Address, pool_id: u32) -> Result<i128, AstroSwapError> {
         let result: i128 = self.env.invoke_contract(
             &self.contract_id,
             &Symbol::new(self.env, "claim_rewards"),
-            Vec::from_array(self.env, [user.to_val(), pool_id.into_val(self.env)]),
+            self.user_pool_args(user, pool_id),
         );
         Ok(result)
     }
 
-    /// Get pending rewards
-    pub fn pending_rewards(&self, user: &Address, pool_id: u32) -> Result<i128, AstroSwapError> {
+    /// Claim rewards, returning the staking error on failure
+    pub fn try_claim_rewards(&self, user: &Address, pool_id: u32) -> Result<i128, AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<i128, AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "claim_rewards"),
+                self.user_pool_args(user, pool_id),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+
+    /// Compound rewards back into the pool
+    pub fn compound(&self, user: &Address, pool_id: u32) -> i128 {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "compound"),
+            self.user_pool_args(user, pool_id),
+        )
+    }
+
+    /// Compound rewards, returning the staking error on failure
+    pub fn try_compound(&self, user: &Address, pool_id: u32) -> Result<i128, AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<i128, AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "compound"),
+                self.user_pool_args(user, pool_id),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+
+    /// Get pending rewards
+    pub fn pending_rewards(&self, user: &Address, pool_id: u32) -> Result<i128, AstroSwapError> {
         let result: i128 = self.env.invoke_contract(
             &self.contract_id,
             &Symbol::new(self.env, "pending_rewards"),
-            Vec::from_array(self.env, [user.to_val(), pool_id.into_val(self.env)]),
+            self.user_pool_args(user, pool_id),
         );
         Ok(result)
     }
+
+    /// Get pending rewards, returning the staking error on failure
+    pub fn try_pending_rewards(&self, user: &Address, pool_id: u32) -> Result<i128, AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<i128, AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "pending_rewards"),
+                self.user_pool_args(user, pool_id),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+
+    /// Get pool information
+    pub fn pool_info(&self, pool_id: u32) -> StakingPool {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "pool_info"),
+            Vec::from_array(self.env, [pool_id.into_val(self.env)]),
+        )
+    }
+
+    /// Get pool information, returning `StakingPoolNotFound` for unknown pools
+    pub fn try_pool_info(&self, pool_id: u32) -> Result<StakingPool, AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<StakingPool, AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "pool_info"),
+                Vec::from_array(self.env, [pool_id.into_val(self.env)]),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+
+    /// Get the token new pools pay rewards in, if set
+    pub fn reward_token(&self) -> Option<Address> {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "reward_token"),
+            Vec::new(self.env),
+        )
+    }
+
+    /// Get a user's stake in a pool
+    pub fn user_info(&self, user: &Address, pool_id: u32) -> UserStake {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "user_info"),
+            self.user_pool_args(user, pool_id),
+        )
+    }
+
+    /// Get a user's stake, returning the staking error on failure
+    pub fn try_user_info(&self, user: &Address, pool_id: u32) -> Result<UserStake, AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<UserStake, AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "user_info"),
+                self.user_pool_args(user, pool_id),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+
+    /// Get number of staking pools
+    pub fn pool_count(&self) -> u32 {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "pool_count"),
+            Vec::new(self.env),
+        )
+    }
+
+    /// Checkpoint a pool for the current epoch (keeper entry point)
+    pub fn try_checkpoint_pool(&self, pool_id: u32) -> Result<u64, AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<u64, AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "checkpoint_pool"),
+                Vec::from_array(self.env, [pool_id.into_val(self.env)]),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+}
+
+/// Bridge contract interface
+/// Provides methods to interact with the AstroSwap Bridge contract
+///
+/// `try_` variants return the callee's error instead of trapping; failures
+/// that are not an `AstroSwapError` are reported as `AdapterError`.
+pub struct BridgeClient<'a> {
+    env: &'a Env,
+    contract_id: Address,
+}
+
+impl<'a> BridgeClient<'a> {
+    pub fn new(env: &'a Env, contract_id: &Address) -> Self {
+        Self {
+            env,
+            contract_id: contract_id.clone(),
+        }
+    }
+
+    fn graduate_args(
+        &self,
+        caller: &Address,
+        token: &Address,
+        token_amount: i128,
+        quote_amount: i128,
+        metadata: &TokenMetadata,
+    ) -> Vec<Val> {
+        Vec::from_array(
+            self.env,
+            [
+                caller.to_val(),
+                token.to_val(),
+                token_amount.into_val(self.env),
+                quote_amount.into_val(self.env),
+                metadata.into_val(self.env),
+            ],
+        )
+    }
+
+    /// Graduate a launchpad token to AstroSwap
+    pub fn graduate_token(
+        &self,
+        caller: &Address,
+        token: &Address,
+        token_amount: i128,
+        quote_amount: i128,
+        metadata: &TokenMetadata,
+    ) -> GraduatedToken {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "graduate_token"),
+            self.graduate_args(caller, token, token_amount, quote_amount, metadata),
+        )
+    }
+
+    /// Graduate a token, returning the bridge error on failure
+    pub fn try_graduate_token(
+        &self,
+        caller: &Address,
+        token: &Address,
+        token_amount: i128,
+        quote_amount: i128,
+        metadata: &TokenMetadata,
+    ) -> Result<GraduatedToken, AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<GraduatedToken, AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "graduate_token"),
+                self.graduate_args(caller, token, token_amount, quote_amount, metadata),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+
+    /// Get graduation info for a token
+    pub fn get_graduated_token(&self, token: &Address) -> GraduatedToken {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "get_graduated_token"),
+            Vec::from_array(self.env, [token.to_val()]),
+        )
+    }
+
+    /// Get graduation info, returning `TokenNotGraduated` for unknown tokens
+    pub fn try_get_graduated_token(&self, token: &Address) -> Result<GraduatedToken, AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<GraduatedToken, AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "get_graduated_token"),
+                Vec::from_array(self.env, [token.to_val()]),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+
+    /// Check if a token has graduated
+    pub fn is_graduated(&self, token: &Address) -> bool {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "is_graduated"),
+            Vec::from_array(self.env, [token.to_val()]),
+        )
+    }
+
+    /// Get number of graduated tokens
+    pub fn graduation_count(&self) -> u32 {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "graduation_count"),
+            Vec::new(self.env),
+        )
+    }
+
+    /// Check if bridge is paused
+    pub fn is_paused(&self) -> bool {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "is_paused"),
+            Vec::new(self.env),
+        )
+    }
+}
+
+/// Largest price decimals `OracleClient::try_get_price_scaled` rescales
+pub const MAX_PRICE_DECIMALS: u32 = 18;
+
+/// Oracle contract interface
+/// Provides methods to interact with the AstroSwap Oracle contract
+///
+/// The oracle reports `OracleError` codes (800-899), which this crate cannot
+/// name; `try_` variants report any oracle failure as `QuoteStale`.
+pub struct OracleClient<'a> {
+    env: &'a Env,
+    contract_id: Address,
+}
+
+impl<'a> OracleClient<'a> {
+    pub fn new(env: &'a Env, contract_id: &Address) -> Self {
+        Self {
+            env,
+            contract_id: contract_id.clone(),
+        }
+    }
+
+    /// Get the current (fresh) price for a token
+    pub fn get_price(&self, token: &Address) -> PriceData {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "get_price"),
+            Vec::from_array(self.env, [token.to_val()]),
+        )
+    }
+
+    /// Get the current price, or `QuoteStale` if it is missing or stale
+    pub fn try_get_price(&self, token: &Address) -> Result<PriceData, AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<PriceData, AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "get_price"),
+                Vec::from_array(self.env, [token.to_val()]),
+            ),
+            AstroSwapError::QuoteStale,
+        )
+    }
+
+    /// Get the current price rescaled to `decimals`, or `QuoteStale` if it is
+    /// missing, stale or not positive
+    pub fn try_get_price_scaled(
+        &self,
+        token: &Address,
+        decimals: u32,
+    ) -> Result<i128, AstroSwapError> {
+        let price = self.try_get_price(token)?;
+        if price.price <= 0 || price.decimals.max(decimals) > MAX_PRICE_DECIMALS {
+            return Err(AstroSwapError::QuoteStale);
+        }
+        let (scale_up, scale_down) = (10i128.pow(decimals), 10i128.pow(price.decimals));
+        mul_div_down(price.price, scale_up, scale_down)
+    }
+
+    /// Get the time-weighted average price over `window` seconds
+    pub fn get_twap(&self, token: &Address, window: u64) -> i128 {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "get_twap"),
+            Vec::from_array(self.env, [token.to_val(), window.into_val(self.env)]),
+        )
+    }
+
+    /// Get the TWAP, or `QuoteStale` if there are not enough observations
+    pub fn try_get_twap(&self, token: &Address, window: u64) -> Result<i128, AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<i128, AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "get_twap"),
+                Vec::from_array(self.env, [token.to_val(), window.into_val(self.env)]),
+            ),
+            AstroSwapError::QuoteStale,
+        )
+    }
+
+    /// Get the fair price of one whole LP share of a pair, manipulation
+    /// resistant (`CANONICAL_PRICE_DECIMALS`)
+    pub fn get_lp_price(&self, pair: &Address) -> i128 {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "get_lp_price"),
+            Vec::from_array(self.env, [pair.to_val()]),
+        )
+    }
+
+    /// Get the fair LP share price, or `QuoteStale` if either token's price
+    /// is missing or stale or the pair has no liquidity
+    pub fn try_get_lp_price(&self, pair: &Address) -> Result<i128, AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<i128, AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "get_lp_price"),
+                Vec::from_array(self.env, [pair.to_val()]),
+            ),
+            AstroSwapError::QuoteStale,
+        )
+    }
+
+    /// Check if a token's price is fresh
+    pub fn is_price_fresh(&self, token: &Address) -> bool {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "is_price_fresh"),
+            Vec::from_array(self.env, [token.to_val()]),
+        )
+    }
+
+    /// Get staleness threshold in seconds
+    pub fn get_staleness_threshold(&self) -> u64 {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "get_staleness_threshold"),
+            Vec::new(self.env),
+        )
+    }
+}
+
+/// Aggregator contract interface
+/// Provides methods to interact with the AstroSwap Aggregator contract
+///
+/// `try_` variants return the callee's error instead of trapping; failures
+/// that are not an `AstroSwapError` are reported as `AdapterError`.
+pub struct AggregatorClient<'a> {
+    env: &'a Env,
+    contract_id: Address,
+}
+
+impl<'a> AggregatorClient<'a> {
+    pub fn new(env: &'a Env, contract_id: &Address) -> Self {
+        Self {
+            env,
+            contract_id: contract_id.clone(),
+        }
+    }
+
+    fn swap_args(
+        &self,
+        user: &Address,
+        token_in: &Address,
+        token_out: &Address,
+        amount_in: i128,
+        min_out: i128,
+        deadline: u64,
+    ) -> Vec<Val> {
+        Vec::from_array(
+            self.env,
+            [
+                user.to_val(),
+                token_in.to_val(),
+                token_out.to_val(),
+                amount_in.into_val(self.env),
+                min_out.into_val(self.env),
+                deadline.into_val(self.env),
+            ],
+        )
+    }
+
+    fn swap_with_route_args(
+        &self,
+        user: &Address,
+        route: &SwapRoute,
+        amount_in: i128,
+        min_out: i128,
+        deadline: u64,
+    ) -> Vec<Val> {
+        Vec::from_array(
+            self.env,
+            [
+                user.to_val(),
+                route.into_val(self.env),
+                amount_in.into_val(self.env),
+                min_out.into_val(self.env),
+                deadline.into_val(self.env),
+            ],
+        )
+    }
+
+    fn quote_args(&self, token_in: &Address, token_out: &Address, amount_in: i128) -> Vec<Val> {
+        Vec::from_array(
+            self.env,
+            [
+                token_in.to_val(),
+                token_out.to_val(),
+                amount_in.into_val(self.env),
+            ],
+        )
+    }
+
+    /// Swap along the best route found across protocols
+    pub fn swap(
+        &self,
+        user: &Address,
+        token_in: &Address,
+        token_out: &Address,
+        amount_in: i128,
+        min_out: i128,
+        deadline: u64,
+    ) -> i128 {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "swap"),
+            self.swap_args(user, token_in, token_out, amount_in, min_out, deadline),
+        )
+    }
+
+    /// Swap along the best route, returning the aggregator error on failure
+    pub fn try_swap(
+        &self,
+        user: &Address,
+        token_in: &Address,
+        token_out: &Address,
+        amount_in: i128,
+        min_out: i128,
+        deadline: u64,
+    ) -> Result<i128, AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<i128, AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "swap"),
+                self.swap_args(user, token_in, token_out, amount_in, min_out, deadline),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+
+    /// Swap along the best route, pulling the input from an allowance
+    /// granted to the aggregator
+    pub fn swap_with_allowance(
+        &self,
+        user: &Address,
+        token_in: &Address,
+        token_out: &Address,
+        amount_in: i128,
+        min_out: i128,
+        deadline: u64,
+    ) -> i128 {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "swap_with_allowance"),
+            self.swap_args(user, token_in, token_out, amount_in, min_out, deadline),
+        )
+    }
+
+    /// Allowance-funded swap, returning the aggregator error on failure
+    pub fn try_swap_with_allowance(
+        &self,
+        user: &Address,
+        token_in: &Address,
+        token_out: &Address,
+        amount_in: i128,
+        min_out: i128,
+        deadline: u64,
+    ) -> Result<i128, AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<i128, AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "swap_with_allowance"),
+                self.swap_args(user, token_in, token_out, amount_in, min_out, deadline),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+
+    /// Swap along a pre-computed route
+    pub fn swap_with_route(
+        &self,
+        user: &Address,
+        route: &SwapRoute,
+        amount_in: i128,
+        min_out: i128,
+        deadline: u64,
+    ) -> i128 {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "swap_with_route"),
+            self.swap_with_route_args(user, route, amount_in, min_out, deadline),
+        )
+    }
+
+    /// Swap along a pre-computed route, returning the aggregator error on failure
+    pub fn try_swap_with_route(
+        &self,
+        user: &Address,
+        route: &SwapRoute,
+        amount_in: i128,
+        min_out: i128,
+        deadline: u64,
+    ) -> Result<i128, AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<i128, AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "swap_with_route"),
+                self.swap_with_route_args(user, route, amount_in, min_out, deadline),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+
+    /// Find the best route across registered protocols
+    pub fn find_best_route(&self, token_in: &Address, token_out: &Address, amount_in: i128) -> SwapRoute {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "find_best_route"),
+            self.quote_args(token_in, token_out, amount_in),
+        )
+    }
+
+    /// Find the best route, returning the aggregator error on failure
+    pub fn try_find_best_route(
+        &self,
+        token_in: &Address,
+        token_out: &Address,
+        amount_in: i128,
+    ) -> Result<SwapRoute, AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<SwapRoute, AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "find_best_route"),
+                self.quote_args(token_in, token_out, amount_in),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+
+    /// Get (protocol_id, expected_output) quotes from all active protocols
+    pub fn get_all_quotes(
+        &self,
+        token_in: &Address,
+        token_out: &Address,
+        amount_in: i128,
+    ) -> Vec<(u32, i128)> {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "get_all_quotes"),
+            self.quote_args(token_in, token_out, amount_in),
+        )
+    }
+
+    /// Check if aggregator is paused
+    pub fn is_paused(&self) -> bool {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "is_paused"),
+            Vec::new(self.env),
+        )
+    }
+}
+
+/// Analytics contract interface
+/// Provides methods to report swaps to the AstroSwap Analytics contract
+///
+/// Reports are fire-and-forget: a failing or missing analytics contract never
+/// fails the swap being reported.
+pub struct AnalyticsClient<'a> {
+    env: &'a Env,
+    contract_id: Address,
+}
+
+impl<'a> AnalyticsClient<'a> {
+    pub fn new(env: &'a Env, contract_id: &Address) -> Self {
+        Self {
+            env,
+            contract_id: contract_id.clone(),
+        }
+    }
+
+    /// Report a swap executed by the calling pair
+    pub fn report_swap(
+        &self,
+        token_0: &Address,
+        token_1: &Address,
+        token_in: &Address,
+        amount_in: i128,
+        fee: i128,
+    ) {
+        let _ = self.env.try_invoke_contract::<(), AstroSwapError>(
+            &self.contract_id,
+            &Symbol::new(self.env, "record_swap"),
+            Vec::from_array(
+                self.env,
+                [
+                    self.env.current_contract_address().to_val(),
+                    token_0.to_val(),
+                    token_1.to_val(),
+                    token_in.to_val(),
+                    amount_in.into_val(self.env),
+                    fee.into_val(self.env),
+                ],
+            ),
+        );
+    }
+
+    /// Report a swap routed by the calling contract, with its own fee
+    pub fn report_routed_swap(&self, token_in: &Address, amount_in: i128, fee: i128) {
+        let _ = self.env.try_invoke_contract::<(), AstroSwapError>(
+            &self.contract_id,
+            &Symbol::new(self.env, "record_routed_swap"),
+            Vec::from_array(
+                self.env,
+                [
+                    self.env.current_contract_address().to_val(),
+                    token_in.to_val(),
+                    amount_in.into_val(self.env),
+                    fee.into_val(self.env),
+                ],
+            ),
+        );
+    }
+}
+
+/// Registry contract interface
+/// Provides methods to look up protocol components in the AstroSwap Registry
+///
+/// `try_` variants return the callee's error instead of trapping; failures
+/// that are not an `AstroSwapError` are reported as `AdapterError`.
+pub struct RegistryClient<'a> {
+    env: &'a Env,
+    contract_id: Address,
+}
+
+impl<'a> RegistryClient<'a> {
+    pub fn new(env: &'a Env, contract_id: &Address) -> Self {
+        Self {
+            env,
+            contract_id: contract_id.clone(),
+        }
+    }
+
+    /// Get the current address of a component
+    pub fn get_address(&self, component: ProtocolComponent) -> Address {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "get_address"),
+            Vec::from_array(self.env, [component.into_val(self.env)]),
+        )
+    }
+
+    /// Get the current address of a component, or `ComponentNotRegistered`
+    pub fn try_get_address(&self, component: ProtocolComponent) -> Result<Address, AstroSwapError> {
+        flatten(
+            self.env.try_invoke_contract::<Address, AstroSwapError>(
+                &self.contract_id,
+                &Symbol::new(self.env, "get_address"),
+                Vec::from_array(self.env, [component.into_val(self.env)]),
+            ),
+            AstroSwapError::AdapterError,
+        )
+    }
+
+    /// Get the current entry of a component, if registered
+    pub fn entry(&self, component: ProtocolComponent) -> Option<ComponentEntry> {
+        self.env.invoke_contract(
+            &self.contract_id,
+            &Symbol::new(self.env, "entry"),
+            Vec::from_array(self.env, [component.into_val(self.env)]),
+        )
+    }
 }
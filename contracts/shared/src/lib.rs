@@ -2,12 +2,20 @@
 
 pub mod error;
 pub mod events;
+pub mod guard;
 pub mod interfaces;
 pub mod math;
+pub mod pagination;
+pub mod route;
 pub mod types;
+pub mod validation;
 
 pub use error::*;
 pub use events::*;
+pub use guard::*;
 pub use interfaces::*;
 pub use math::*;
+pub use pagination::*;
+pub use route::*;
 pub use types::*;
+pub use validation::*;
@@ -173,6 +173,97 @@ pub fn quote(amount_a: i128, reserve_a: i128, reserve_b: i128) -> Result<i128, A
     astro_core_shared::math::quote(amount_a, reserve_a, reserve_b).map_err(Into::into)
 }
 
+// ==================== Decimals Functions ====================
+
+/// Largest token decimals amounts can be normalized between
+pub const MAX_TOKEN_DECIMALS: u32 = 18;
+
+/// Rescale `amount` of a token with `from_decimals` to `to_decimals` - rounds DOWN
+/// e.g. 1.5 of an 18-decimal asset (15 * 10^17) is 15_000_000 at 7 decimals
+pub fn normalize_amount(
+    amount: i128,
+    from_decimals: u32,
+    to_decimals: u32,
+) -> Result<i128, AstroSwapError> {
+    if from_decimals.max(to_decimals) > MAX_TOKEN_DECIMALS {
+        return Err(AstroSwapError::InvalidArgument);
+    }
+    if from_decimals >= to_decimals {
+        mul_div_down(amount, 1, 10i128.pow(from_decimals - to_decimals))
+    } else {
+        safe_mul(amount, 10i128.pow(to_decimals - from_decimals))
+    }
+}
+
+/// Rescale `amount` of a token with `from_decimals` to `to_decimals` - rounds UP
+pub fn normalize_amount_up(
+    amount: i128,
+    from_decimals: u32,
+    to_decimals: u32,
+) -> Result<i128, AstroSwapError> {
+    if from_decimals.max(to_decimals) > MAX_TOKEN_DECIMALS {
+        return Err(AstroSwapError::InvalidArgument);
+    }
+    if from_decimals >= to_decimals {
+        mul_div_up(amount, 1, 10i128.pow(from_decimals - to_decimals))
+    } else {
+        safe_mul(amount, 10i128.pow(to_decimals - from_decimals))
+    }
+}
+
+/// Decimals of `PRECISION` (1e18) fixed-point values
+pub const PRECISION_DECIMALS: u32 = 18;
+
+/// Decimals prices and USD values are exchanged in across the protocol
+/// (those of Stellar assets)
+pub const CANONICAL_PRICE_DECIMALS: u32 = 7;
+
+/// Rounding direction of a conversion that can lose precision
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Rounding {
+    /// Floor - favors the protocol when paying out
+    Down,
+    /// Ceiling - favors the protocol when charging
+    Up,
+}
+
+/// Scale `amount` of a token with `decimals` to `PRECISION` fixed point
+/// e.g. 1.5 of a 7-decimal token (15_000_000) is 1.5 * 10^18 - lossless
+pub fn to_precision(amount: i128, decimals: u32) -> Result<i128, AstroSwapError> {
+    normalize_amount(amount, decimals, PRECISION_DECIMALS)
+}
+
+/// Scale a `PRECISION` fixed-point `value` to an amount with `decimals`
+pub fn from_precision(
+    value: i128,
+    decimals: u32,
+    rounding: Rounding,
+) -> Result<i128, AstroSwapError> {
+    match rounding {
+        Rounding::Down => normalize_amount(value, PRECISION_DECIMALS, decimals),
+        Rounding::Up => normalize_amount_up(value, PRECISION_DECIMALS, decimals),
+    }
+}
+
+/// Price of one whole base token in quote tokens, at `price_decimals`,
+/// from raw amounts of each token - rounds DOWN
+pub fn calculate_price(
+    base_amount: i128,
+    base_decimals: u32,
+    quote_amount: i128,
+    quote_decimals: u32,
+    price_decimals: u32,
+) -> Result<i128, AstroSwapError> {
+    if base_amount <= 0 {
+        return Err(AstroSwapError::InvalidAmount);
+    }
+    if base_decimals > MAX_TOKEN_DECIMALS {
+        return Err(AstroSwapError::InvalidArgument);
+    }
+    let quote_amount = normalize_amount(quote_amount, quote_decimals, price_decimals)?;
+    mul_div_down(quote_amount, 10i128.pow(base_decimals), base_amount)
+}
+
 // ==================== Basis Points Functions ====================
 
 /// Apply basis points (percentage) to an amount - rounds DOWN
@@ -278,6 +369,151 @@ pub fn calculate_price_impact(
     Ok(impact as u32)
 }
 
+/// Absolute deviation of `actual` from `reference`, in basis points of `reference`
+/// Saturates at u32::MAX; rounds DOWN
+pub fn calculate_deviation_bps(actual: i128, reference: i128) -> Result<u32, AstroSwapError> {
+    if reference <= 0 {
+        return Err(AstroSwapError::InvalidArgument);
+    }
+    let diff = safe_sub(actual, reference)?.abs();
+    let deviation = mul_div_down(diff, i128::from(BPS_DENOMINATOR), reference)?;
+    Ok(u32::try_from(deviation).unwrap_or(u32::MAX))
+}
+
+/// Average of `values` weighted by `weights`: sum(value * weight) / sum(weight)
+/// Exact without phantom overflow: each value is split into its multiple of the
+/// total weight and a remainder, so no product exceeds 128 bits. The total
+/// weight must fit in a u64. Rounds DOWN (towards negative infinity)
+pub fn weighted_avg<V, W>(values: V, weights: W) -> Result<i128, AstroSwapError>
+where
+    V: IntoIterator<Item = i128>,
+    W: IntoIterator<Item = u64>,
+    W::IntoIter: Clone,
+{
+    let weights = weights.into_iter();
+    let total_weight = weights
+        .clone()
+        .try_fold(0u64, |total, weight| total.checked_add(weight))
+        .ok_or(AstroSwapError::Overflow)?;
+    if total_weight == 0 {
+        return Err(AstroSwapError::DivisionByZero);
+    }
+    let total = i128::from(total_weight);
+    let total_u = u128::from(total_weight);
+
+    let mut values = values.into_iter();
+    let mut average: i128 = 0;
+    // Fractional part of the average, in units of 1 / total_weight; kept below total
+    let mut remainder: u128 = 0;
+    for weight in weights {
+        let value = values.next().ok_or(AstroSwapError::InvalidArgument)?;
+        // value * weight / total = (value div total) * weight + (value mod total) * weight / total
+        let whole = safe_mul(value.div_euclid(total), i128::from(weight))?;
+        // rem_euclid is in [0, total), so the product stays below 2^128
+        let part = value.rem_euclid(total) as u128 * u128::from(weight);
+        average = safe_add(average, whole)?;
+        average = safe_add(average, (part / total_u) as i128)?;
+
+        remainder += part % total_u;
+        if remainder >= total_u {
+            remainder -= total_u;
+            average = safe_add(average, 1)?;
+        }
+    }
+    if values.next().is_some() {
+        return Err(AstroSwapError::InvalidArgument);
+    }
+
+    Ok(average)
+}
+
+/// Amount of the over-supplied `amount_in` to swap so that the remaining
+/// `amount_in` and `amount_other` plus the swap output match the pool ratio
+/// after the swap. `amount_other = 0` is a single-sided zap-in.
+///
+/// Solves `(amount_other + out(s)) / (amount_in - s) = (reserve_out - out(s)) / (reserve_in + s)`,
+/// which reduces to the single-sided case for the effective amount
+/// `a = (amount_in * reserve_out - amount_other * reserve_in) / (reserve_out + amount_other)`:
+/// `s = r (sqrt((2 - f)^2 + 4 (1 - f) a / r) - (2 - f)) / (2 (1 - f))` with `r = reserve_in`.
+/// Rounds DOWN, leaving dust on the over-supplied token rather than overshooting.
+pub fn calculate_balancing_swap_amount(
+    amount_in: i128,
+    amount_other: i128,
+    reserve_in: i128,
+    reserve_out: i128,
+    fee_bps: u32,
+) -> Result<i128, AstroSwapError> {
+    if amount_in <= 0 || amount_other < 0 || reserve_in <= 0 || reserve_out <= 0 {
+        return Ok(0);
+    }
+    if fee_bps >= BPS_DENOMINATOR {
+        return Err(AstroSwapError::FeeTooHigh);
+    }
+
+    let excess = safe_sub(amount_in, quote(amount_other, reserve_out, reserve_in)?)?;
+    if excess <= 0 {
+        return Ok(0);
+    }
+    let amount_in = mul_div_down(excess, reserve_out, safe_add(reserve_out, amount_other)?)?;
+
+    // Solved for s / r in 1e18 fixed point so the square does not grow with the reserve:
+    // s = r (sqrt((2 - f)^2 + 4 (1 - f) a / r) - (2 - f)) / (2 (1 - f))
+    let one = 10i128.pow(PRECISION_DECIMALS);
+    let bps = i128::from(BPS_DENOMINATOR);
+    let fee_factor = bps - i128::from(fee_bps);
+    let two_minus_f = mul_div_down(one, bps + fee_factor, bps)?;
+    let one_minus_f = mul_div_down(one, fee_factor, bps)?;
+    let ratio = mul_div_down(amount_in, one, reserve_in)?;
+
+    // Discriminant at 36 decimals so its root has 18; past ~40x the reserve it
+    // no longer fits and is taken at 18 decimals, where the root is large enough
+    // for the lost digits not to matter
+    let root = match safe_mul(4 * one_minus_f, ratio)
+        .and_then(|term| safe_add(safe_mul(two_minus_f, two_minus_f)?, term))
+    {
+        Ok(discriminant) => sqrt(discriminant),
+        Err(_) => {
+            let discriminant = safe_add(
+                mul_div_down(two_minus_f, two_minus_f, one)?,
+                mul_div_down(4 * one_minus_f, ratio, one)?,
+            )?;
+            safe_mul(sqrt(discriminant), 10i128.pow(PRECISION_DECIMALS / 2))?
+        }
+    };
+
+    let numerator = safe_sub(root, two_minus_f)?;
+    let swap_amount = mul_div_down(reserve_in, numerator, 2 * one_minus_f)?;
+    Ok(swap_amount.clamp(0, amount_in))
+}
+
+/// Fee taken from a swap input, split between LPs and the protocol
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SwapFeeBreakdown {
+    /// Total fee charged on the input amount
+    pub fee_amount: i128,
+    /// Portion that stays in the pool for liquidity providers
+    pub lp_fee: i128,
+    /// Portion attributed to the protocol
+    pub protocol_fee: i128,
+}
+
+/// Split the fee on `amount_in` at `fee_bps` into LP and protocol portions
+/// The protocol takes PROTOCOL_FEE_BPS out of every PROTOCOL_FEE_BPS + LP_FEE_BPS
+/// of fee (1/6), whatever the pair's fee tier; rounding favors LPs
+pub fn split_swap_fee(amount_in: i128, fee_bps: u32) -> Result<SwapFeeBreakdown, AstroSwapError> {
+    let fee_amount = apply_bps(amount_in, fee_bps)?;
+    let protocol_fee = mul_div_down(
+        fee_amount,
+        i128::from(PROTOCOL_FEE_BPS),
+        i128::from(PROTOCOL_FEE_BPS + LP_FEE_BPS),
+    )?;
+    Ok(SwapFeeBreakdown {
+        fee_amount,
+        lp_fee: safe_sub(fee_amount, protocol_fee)?,
+        protocol_fee,
+    })
+}
+
 /// Calculate staking multiplier based on time staked
 /// Progressive rewards: starts at 1x, increases to 1.3x over 60 days
 pub fn calculate_staking_multiplier(stake_duration_seconds: u64) -> u32 {
@@ -332,6 +568,89 @@ mod tests {
         assert_eq!(tokens, 900);
     }
 
+    #[test]
+    fn test_normalize_amount() {
+        // 18-decimal bridged asset down to 7 decimals and back
+        assert_eq!(
+            normalize_amount(1_500_000_000_000_000_000, 18, 7).unwrap(),
+            15_000_000
+        );
+        assert_eq!(
+            normalize_amount(15_000_000, 7, 18).unwrap(),
+            1_500_000_000_000_000_000
+        );
+        assert_eq!(normalize_amount(15_000_000, 7, 7).unwrap(), 15_000_000);
+
+        // Dust below the target precision
+        assert_eq!(normalize_amount(1, 18, 7).unwrap(), 0);
+        assert_eq!(normalize_amount_up(1, 18, 7).unwrap(), 1);
+
+        assert_eq!(
+            normalize_amount(1, 19, 7),
+            Err(AstroSwapError::InvalidArgument)
+        );
+        assert_eq!(
+            normalize_amount(i128::MAX, 0, 18),
+            Err(AstroSwapError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_precision_conversion() {
+        assert_eq!(
+            to_precision(15_000_000, 7).unwrap(),
+            1_500_000_000_000_000_000
+        );
+        assert_eq!(to_precision(1, PRECISION_DECIMALS).unwrap(), 1);
+        assert_eq!(
+            from_precision(1_500_000_000_000_000_000, 7, Rounding::Down).unwrap(),
+            15_000_000
+        );
+
+        // Amounts survive a round trip; values between two amounts round as asked
+        let value = to_precision(123_4567890, 7).unwrap();
+        assert_eq!(from_precision(value, 7, Rounding::Up).unwrap(), 123_4567890);
+        assert_eq!(
+            from_precision(value + 1, 7, Rounding::Down).unwrap(),
+            123_4567890
+        );
+        assert_eq!(
+            from_precision(value + 1, 7, Rounding::Up).unwrap(),
+            123_4567891
+        );
+
+        assert_eq!(
+            to_precision(i128::MAX / 1_000, 7),
+            Err(AstroSwapError::Overflow)
+        );
+        assert_eq!(
+            from_precision(1, 19, Rounding::Down),
+            Err(AstroSwapError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn test_calculate_price() {
+        // 2 quote (7 decimals) per token, whatever the token's decimals
+        assert_eq!(
+            calculate_price(10_000_000, 7, 20_000_000, 7, 7).unwrap(),
+            20_000_000
+        );
+        assert_eq!(
+            calculate_price(1_000_000_000_000_000_000, 18, 20_000_000, 7, 7).unwrap(),
+            20_000_000
+        );
+        // 0.5 quote (18 decimals) per 7-decimal token
+        assert_eq!(
+            calculate_price(10_000_000, 7, 500_000_000_000_000_000, 18, 7).unwrap(),
+            5_000_000
+        );
+        assert_eq!(
+            calculate_price(0, 7, 1, 7, 7),
+            Err(AstroSwapError::InvalidAmount)
+        );
+    }
+
     #[test]
     fn test_staking_multiplier() {
         assert_eq!(calculate_staking_multiplier(0), 10_000);
@@ -367,6 +686,124 @@ mod tests {
         assert_eq!(MIN_TRADE_AMOUNT, 1_000_000);
     }
 
+    #[test]
+    fn test_split_swap_fee() {
+        // 0.3% of 1_000_000 = 3000, of which 5/30 to the protocol
+        let fees = split_swap_fee(1_000_000, DEFAULT_SWAP_FEE_BPS).unwrap();
+        assert_eq!(fees.fee_amount, 3000);
+        assert_eq!(fees.protocol_fee, 500);
+        assert_eq!(fees.lp_fee, 2500);
+
+        // Rounding leftovers go to LPs
+        let fees = split_swap_fee(1_000, DEFAULT_SWAP_FEE_BPS).unwrap();
+        assert_eq!(fees.fee_amount, 3);
+        assert_eq!(fees.protocol_fee, 0);
+        assert_eq!(fees.lp_fee, 3);
+
+        assert_eq!(
+            split_swap_fee(1_000_000, 0).unwrap(),
+            SwapFeeBreakdown::default()
+        );
+    }
+
+    #[test]
+    fn test_calculate_deviation_bps() {
+        assert_eq!(calculate_deviation_bps(1_000, 1_000).unwrap(), 0);
+        assert_eq!(calculate_deviation_bps(1_050, 1_000).unwrap(), 500);
+        assert_eq!(calculate_deviation_bps(950, 1_000).unwrap(), 500);
+        assert_eq!(calculate_deviation_bps(1_000_000_000_000_000, 1).unwrap(), u32::MAX);
+        assert_eq!(
+            calculate_deviation_bps(1_000, 0),
+            Err(AstroSwapError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn test_weighted_avg() {
+        let avg = |values: &[i128], weights: &[u64]| {
+            weighted_avg(values.iter().copied(), weights.iter().copied())
+        };
+        assert_eq!(avg(&[100, 200], &[1, 3]).unwrap(), 175);
+        assert_eq!(avg(&[100, 101], &[1, 1]).unwrap(), 100);
+        assert_eq!(avg(&[-1, 0], &[1, 1]).unwrap(), -1);
+        assert_eq!(avg(&[7, 1_000], &[5, 0]).unwrap(), 7);
+
+        // value * weight overflows i128 but the average does not
+        let half = u64::MAX / 2;
+        assert_eq!(
+            avg(&[i128::MAX, i128::MAX - 2], &[half, half]).unwrap(),
+            i128::MAX - 1
+        );
+        assert_eq!(
+            avg(&[i128::MIN, i128::MIN + 2], &[half, half]).unwrap(),
+            i128::MIN + 1
+        );
+
+        assert_eq!(avg(&[], &[]), Err(AstroSwapError::DivisionByZero));
+        assert_eq!(avg(&[1, 2], &[0, 0]), Err(AstroSwapError::DivisionByZero));
+        assert_eq!(avg(&[1, 2], &[1]), Err(AstroSwapError::InvalidArgument));
+        assert_eq!(avg(&[1], &[1, 1]), Err(AstroSwapError::InvalidArgument));
+        assert_eq!(avg(&[1, 2], &[u64::MAX, 1]), Err(AstroSwapError::Overflow));
+    }
+
+    #[test]
+    fn test_calculate_balancing_swap_amount() {
+        let (reserve_in, reserve_out) = (20_000_0000000i128, 10_000_0000000i128);
+        let fee = DEFAULT_SWAP_FEE_BPS;
+
+        // Single-sided and 3000:1000 against a 2:1 pool
+        for (amount_in, amount_other) in
+            [(1_000_0000000i128, 0i128), (3_000_0000000, 1_000_0000000)]
+        {
+            let swapped = calculate_balancing_swap_amount(
+                amount_in,
+                amount_other,
+                reserve_in,
+                reserve_out,
+                fee,
+            )
+            .unwrap();
+            let out = get_amount_out(swapped, reserve_in, reserve_out, fee).unwrap();
+
+            // Deposited amounts are in the post-swap pool ratio
+            let lhs = (amount_in - swapped) * (reserve_out - out);
+            let rhs = (amount_other + out) * (reserve_in + swapped);
+            assert!((lhs - rhs).abs() * 1_000_000 < rhs);
+        }
+
+        // 18-decimal reserves far past the point where squaring them overflows,
+        // and a zap of 50x the reserve
+        let (e18, big_out) = (10i128.pow(18), 5_000_0000000i128);
+        for (big_in, amount_in, amount_other) in [
+            (1_000_000 * e18, 100_000 * e18, 0i128),
+            (1_000_000 * e18, 100_000 * e18, 100_0000000),
+            (100 * e18, 5_000 * e18, 0),
+        ] {
+            let swapped =
+                calculate_balancing_swap_amount(amount_in, amount_other, big_in, big_out, fee)
+                    .unwrap();
+            let out = get_amount_out(swapped, big_in, big_out, fee).unwrap();
+
+            let lhs = (amount_in - swapped) * (big_out - out);
+            let rhs = (amount_other + out) * (big_in + swapped);
+            assert!((lhs - rhs).abs() * 1_000_000 < rhs);
+        }
+
+        // Already balanced or under-supplied: nothing to swap
+        let balanced = calculate_balancing_swap_amount(2_000, 1_000, reserve_in, reserve_out, fee);
+        assert_eq!(balanced.unwrap(), 0);
+        let under = calculate_balancing_swap_amount(1_000, 1_000, reserve_in, reserve_out, fee);
+        assert_eq!(under.unwrap(), 0);
+        assert_eq!(
+            calculate_balancing_swap_amount(1_000, 0, 0, reserve_out, fee).unwrap(),
+            0
+        );
+        assert_eq!(
+            calculate_balancing_swap_amount(1_000, 0, reserve_in, reserve_out, BPS_DENOMINATOR),
+            Err(AstroSwapError::FeeTooHigh)
+        );
+    }
+
     #[test]
     fn test_k_invariant_after_swap() {
         let reserve_0 = 1_000_000i128;
@@ -0,0 +1,158 @@
+//! Cursor pagination shared by AstroSwap listing APIs
+//!
+//! A listing takes a `cursor` (index of the first item, 0 for the first
+//! page) and a `limit`, and returns a page holding the items, the cursor of
+//! the next page (`None` on the last one) and the total item count. The
+//! cursor is an index into an append-only list, so pages stay stable while
+//! new entries are added at the end.
+//!
+//! Contract types cannot be generic, so each listing declares its concrete
+//! page type with [`contract_page!`](crate::contract_page), e.g.
+//! `contract_page!(PairPage, Address);`, and fills it with its `load`.
+
+use crate::error::AstroSwapError;
+use soroban_sdk::{Env, IntoVal, Val, Vec};
+
+/// Largest page any listing returns
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+/// Index range `[start, end)` of the page at `cursor` over `total` items
+///
+/// Fails with `InvalidArgument` unless `limit` is in `1..=MAX_PAGE_SIZE`, and
+/// with `InvalidCursor` if `cursor` is past the end. A cursor equal to
+/// `total` is valid and yields an empty last page.
+pub fn page_range(cursor: u32, limit: u32, total: u32) -> Result<(u32, u32), AstroSwapError> {
+    if limit == 0 || limit > MAX_PAGE_SIZE {
+        return Err(AstroSwapError::InvalidArgument);
+    }
+    if cursor > total {
+        return Err(AstroSwapError::InvalidCursor);
+    }
+    Ok((cursor, cursor.saturating_add(limit).min(total)))
+}
+
+/// Cursor of the page after one ending at `end`, `None` on the last page
+#[inline]
+pub fn next_cursor(end: u32, total: u32) -> Option<u32> {
+    if end < total {
+        Some(end)
+    } else {
+        None
+    }
+}
+
+/// Collect the page at `cursor` with `get(index)`, skipping missing indexes
+///
+/// Returns the items and the next cursor; see [`page_range`] for the errors.
+pub fn collect_page<T, F>(
+    env: &Env,
+    cursor: u32,
+    limit: u32,
+    total: u32,
+    get: F,
+) -> Result<(Vec<T>, Option<u32>), AstroSwapError>
+where
+    T: IntoVal<Env, Val>,
+    F: Fn(u32) -> Option<T>,
+{
+    let (start, end) = page_range(cursor, limit, total)?;
+    let mut items = Vec::new(env);
+    for index in start..end {
+        if let Some(item) = get(index) {
+            items.push_back(item);
+        }
+    }
+    Ok((items, next_cursor(end, total)))
+}
+
+/// Declare `$name` as the contract page type of a listing of `$item`s
+///
+/// The type has the shared page layout, `{ items, next_cursor, total }`, and
+/// a `load` constructor wrapping [`collect_page`].
+#[macro_export]
+macro_rules! contract_page {
+    ($name:ident, $item:ty) => {
+        #[soroban_sdk::contracttype]
+        #[derive(Clone, Debug, Eq, PartialEq)]
+        pub struct $name {
+            pub items: soroban_sdk::Vec<$item>,
+            /// Cursor of the next page, `None` on the last page
+            pub next_cursor: Option<u32>,
+            /// Items in the whole listing
+            pub total: u32,
+        }
+
+        impl $name {
+            /// Load the page at `cursor` with `get(index)`
+            pub fn load<F>(
+                env: &soroban_sdk::Env,
+                cursor: u32,
+                limit: u32,
+                total: u32,
+                get: F,
+            ) -> Result<Self, $crate::AstroSwapError>
+            where
+                F: Fn(u32) -> Option<$item>,
+            {
+                let (items, next_cursor) = $crate::collect_page(env, cursor, limit, total, get)?;
+                Ok(Self {
+                    items,
+                    next_cursor,
+                    total,
+                })
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    contract_page!(NumberPage, u32);
+
+    #[test]
+    fn test_page_range() {
+        assert_eq!(page_range(0, 10, 25), Ok((0, 10)));
+        assert_eq!(page_range(20, 10, 25), Ok((20, 25)));
+        // The end of the listing is an empty last page
+        assert_eq!(page_range(25, 10, 25), Ok((25, 25)));
+        assert_eq!(page_range(26, 10, 25), Err(AstroSwapError::InvalidCursor));
+        assert_eq!(page_range(0, 0, 25), Err(AstroSwapError::InvalidArgument));
+        assert_eq!(
+            page_range(0, MAX_PAGE_SIZE + 1, 25),
+            Err(AstroSwapError::InvalidArgument)
+        );
+        assert_eq!(
+            page_range(u32::MAX, MAX_PAGE_SIZE, u32::MAX),
+            Ok((u32::MAX, u32::MAX))
+        );
+    }
+
+    #[test]
+    fn test_contract_page_walks_listing() {
+        let env = Env::default();
+        // Odd indexes are missing, as with removed entries
+        let get = |index: u32| {
+            if index % 2 == 0 {
+                Some(index * 10)
+            } else {
+                None
+            }
+        };
+
+        let first = NumberPage::load(&env, 0, 4, 6, get).unwrap();
+        assert_eq!(first.items, Vec::from_array(&env, [0, 20]));
+        assert_eq!(first.next_cursor, Some(4));
+        assert_eq!(first.total, 6);
+
+        let last = NumberPage::load(&env, 4, 4, 6, get).unwrap();
+        assert_eq!(last.items, Vec::from_array(&env, [40]));
+        assert_eq!(last.next_cursor, None);
+
+        assert_eq!(
+            NumberPage::load(&env, 7, 4, 6, get),
+            Err(AstroSwapError::InvalidCursor)
+        );
+    }
+}
@@ -0,0 +1,140 @@
+//! Swap route helpers
+//!
+//! Routes handed to the aggregator by users are untrusted input;
+//! `validate_route` checks their shape before any tokens move.
+
+use crate::error::AstroSwapError;
+use crate::types::{RouteStep, SwapRoute};
+use soroban_sdk::{Address, Env, Vec};
+
+/// First input token and last output token of a route
+pub fn route_endpoints(route: &SwapRoute) -> Result<(Address, Address), AstroSwapError> {
+    let first = route.steps.first().ok_or(AstroSwapError::InvalidPath)?;
+    let last = route.steps.last().ok_or(AstroSwapError::InvalidPath)?;
+    Ok((first.token_in, last.token_out))
+}
+
+/// Token path of a route: the input token followed by each step's output
+pub fn route_path(env: &Env, route: &SwapRoute) -> Vec<Address> {
+    let mut path = Vec::new(env);
+    if let Some(first) = route.steps.first() {
+        path.push_back(first.token_in);
+    }
+    for step in route.steps.iter() {
+        path.push_back(step.token_out);
+    }
+    path
+}
+
+/// Build a route from its steps; the expected output is the last step's
+pub fn route_from_steps(steps: Vec<RouteStep>, total_fee_bps: u32) -> SwapRoute {
+    let expected_output = steps.last().map(|s| s.expected_out).unwrap_or(0);
+    SwapRoute {
+        steps,
+        expected_output,
+        total_fee_bps,
+    }
+}
+
+/// Validate a user-supplied route
+///
+/// - at least one step (`InvalidPath`) and at most `max_hops` (`RouteTooLong`)
+/// - every step uses a protocol accepted by `is_active_protocol` (`ProtocolNotFound`)
+/// - every step swaps between two different tokens and expects a positive output,
+///   and each step starts with the token the previous one produced (`InvalidRoute`)
+pub fn validate_route<F>(
+    route: &SwapRoute,
+    max_hops: u32,
+    is_active_protocol: F,
+) -> Result<(), AstroSwapError>
+where
+    F: Fn(u32) -> bool,
+{
+    if route.steps.is_empty() {
+        return Err(AstroSwapError::InvalidPath);
+    }
+    if route.steps.len() > max_hops {
+        return Err(AstroSwapError::RouteTooLong);
+    }
+
+    let mut previous_out: Option<Address> = None;
+    for step in route.steps.iter() {
+        if !is_active_protocol(step.protocol_id) {
+            return Err(AstroSwapError::ProtocolNotFound);
+        }
+        if step.token_in == step.token_out || step.expected_out <= 0 {
+            return Err(AstroSwapError::InvalidRoute);
+        }
+        if let Some(previous) = previous_out {
+            if previous != step.token_in {
+                return Err(AstroSwapError::InvalidRoute);
+            }
+        }
+        previous_out = Some(step.token_out);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+
+    fn step(env: &Env, token_in: &Address, token_out: &Address, expected_out: i128) -> RouteStep {
+        RouteStep {
+            protocol_id: 0,
+            pool_address: Address::generate(env),
+            token_in: token_in.clone(),
+            token_out: token_out.clone(),
+            amount_in: 1_000,
+            expected_out,
+        }
+    }
+
+    fn route(env: &Env, steps: &[RouteStep]) -> SwapRoute {
+        let mut vec = Vec::new(env);
+        for s in steps {
+            vec.push_back(s.clone());
+        }
+        route_from_steps(vec, 30)
+    }
+
+    #[test]
+    fn test_valid_route_helpers() {
+        let env = Env::default();
+        let (a, b, c) = (Address::generate(&env), Address::generate(&env), Address::generate(&env));
+        let route = route(&env, &[step(&env, &a, &b, 900), step(&env, &b, &c, 800)]);
+
+        assert_eq!(validate_route(&route, 3, |_| true), Ok(()));
+        assert_eq!(route.expected_output, 800);
+        assert_eq!(route_endpoints(&route), Ok((a.clone(), c.clone())));
+        assert_eq!(route_path(&env, &route), Vec::from_array(&env, [a, b, c]));
+    }
+
+    #[test]
+    fn test_invalid_routes() {
+        let env = Env::default();
+        let (a, b, c) = (Address::generate(&env), Address::generate(&env), Address::generate(&env));
+
+        let empty = route(&env, &[]);
+        assert_eq!(validate_route(&empty, 3, |_| true), Err(AstroSwapError::InvalidPath));
+        assert_eq!(route_endpoints(&empty), Err(AstroSwapError::InvalidPath));
+
+        let two_hops = route(&env, &[step(&env, &a, &b, 900), step(&env, &b, &c, 800)]);
+        assert_eq!(validate_route(&two_hops, 1, |_| true), Err(AstroSwapError::RouteTooLong));
+        assert_eq!(
+            validate_route(&two_hops, 3, |id| id != 0),
+            Err(AstroSwapError::ProtocolNotFound)
+        );
+
+        let broken = route(&env, &[step(&env, &a, &b, 900), step(&env, &a, &c, 800)]);
+        assert_eq!(validate_route(&broken, 3, |_| true), Err(AstroSwapError::InvalidRoute));
+
+        let zero_out = route(&env, &[step(&env, &a, &b, 0)]);
+        assert_eq!(validate_route(&zero_out, 3, |_| true), Err(AstroSwapError::InvalidRoute));
+
+        let same_token = route(&env, &[step(&env, &a, &a, 900)]);
+        assert_eq!(validate_route(&same_token, 3, |_| true), Err(AstroSwapError::InvalidRoute));
+    }
+}
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address, String, Vec};
+use soroban_sdk::{contracttype, Address, BytesN, String, Symbol, Vec};
 
 /// Token metadata for graduated tokens from Astro-Shiba
 #[contracttype]
@@ -48,6 +48,23 @@ pub struct StakingPool {
     pub end_time: u64,
     pub last_update_time: u64,
     pub acc_reward_per_share: i128,
+    /// Set by the pool's creator or the admin, for frontends
+    pub metadata: Option<PoolMetadata>,
+}
+
+/// Longest staking pool name, in bytes
+pub const MAX_POOL_NAME_LENGTH: u32 = 32;
+
+/// Descriptive staking pool metadata
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolMetadata {
+    /// Short name or symbol of the staked LP token
+    pub name: String,
+    /// Category, e.g. "graduated"
+    pub tag: Symbol,
+    /// Hash of an informational URL, resolved off-chain
+    pub url_hash: Option<BytesN<32>>,
 }
 
 /// User's staking information
@@ -92,6 +109,31 @@ pub enum Protocol {
     Aqua = 3,
 }
 
+/// Well-known protocol components resolved through the registry
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProtocolComponent {
+    Factory,
+    Router,
+    Staking,
+    Oracle,
+    Aggregator,
+    Treasury,
+    Bridge,
+    Governance,
+    Analytics,
+}
+
+/// Registered address of a protocol component
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ComponentEntry {
+    pub address: Address,
+    /// 1 for the first address registered, incremented on every update
+    pub version: u32,
+    pub updated_at: u64,
+}
+
 /// Graduation status for tokens from Astro-Shiba
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -102,4 +144,21 @@ pub struct GraduatedToken {
     pub initial_price: i128,
     pub graduation_time: u64,
     pub metadata: TokenMetadata,
+    /// The token still had an admin able to claw back or freeze balances
+    /// when it graduated
+    pub clawback_risk: bool,
+}
+
+/// Oracle price data
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    /// Price value (scaled by decimals)
+    pub price: i128,
+    /// Timestamp when price was last updated
+    pub timestamp: u64,
+    /// Number of decimals for the price (e.g., 8 for USD prices)
+    pub decimals: u32,
+    /// Price source/feed identifier
+    pub source: String,
 }
@@ -0,0 +1,77 @@
+//! Input validation shared by AstroSwap contracts
+//!
+//! Each check maps to a single error code so the same bad input is reported
+//! the same way by every contract.
+
+use crate::error::AstroSwapError;
+use crate::math::BPS_DENOMINATOR;
+use soroban_sdk::Env;
+
+/// Maximum fee any contract accepts, in basis points (1%)
+pub const MAX_FEE_BPS: u32 = 100;
+
+/// Fail with `DeadlineExpired` once the ledger time is past `deadline`
+#[inline]
+pub fn require_deadline(env: &Env, deadline: u64) -> Result<(), AstroSwapError> {
+    if env.ledger().timestamp() > deadline {
+        return Err(AstroSwapError::DeadlineExpired);
+    }
+    Ok(())
+}
+
+/// Fail with `InvalidAmount` unless `amount` is strictly positive
+#[inline]
+pub fn require_positive(amount: i128) -> Result<(), AstroSwapError> {
+    if amount <= 0 {
+        return Err(AstroSwapError::InvalidAmount);
+    }
+    Ok(())
+}
+
+/// Fail with `FeeTooHigh` if `bps` exceeds `max_bps` (or 100%)
+#[inline]
+pub fn require_bps_range(bps: u32, max_bps: u32) -> Result<(), AstroSwapError> {
+    if bps > max_bps || bps > BPS_DENOMINATOR {
+        return Err(AstroSwapError::FeeTooHigh);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::testutils::Ledger;
+
+    #[test]
+    fn test_require_deadline() {
+        let env = Env::default();
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+
+        assert_eq!(require_deadline(&env, 1_001), Ok(()));
+        // The deadline itself is still valid
+        assert_eq!(require_deadline(&env, 1_000), Ok(()));
+        assert_eq!(require_deadline(&env, 999), Err(AstroSwapError::DeadlineExpired));
+    }
+
+    #[test]
+    fn test_require_positive() {
+        assert_eq!(require_positive(1), Ok(()));
+        assert_eq!(require_positive(0), Err(AstroSwapError::InvalidAmount));
+        assert_eq!(require_positive(-5), Err(AstroSwapError::InvalidAmount));
+    }
+
+    #[test]
+    fn test_require_bps_range() {
+        assert_eq!(require_bps_range(0, MAX_FEE_BPS), Ok(()));
+        assert_eq!(require_bps_range(MAX_FEE_BPS, MAX_FEE_BPS), Ok(()));
+        assert_eq!(
+            require_bps_range(MAX_FEE_BPS + 1, MAX_FEE_BPS),
+            Err(AstroSwapError::FeeTooHigh)
+        );
+        // Never more than 100%, whatever the caller's limit
+        assert_eq!(
+            require_bps_range(BPS_DENOMINATOR + 1, u32::MAX),
+            Err(AstroSwapError::FeeTooHigh)
+        );
+    }
+}
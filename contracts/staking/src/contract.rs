@@ -1,19 +1,133 @@
 use astroswap_shared::{
-    calculate_staking_multiplier, emit_claim, emit_stake, emit_unstake, safe_add, safe_div,
-    safe_mul, safe_sub, AstroSwapError, StakingPool, UserStake, BPS_DENOMINATOR,
+    apply_bps_round_up, calculate_staking_multiplier, emit_claim, emit_stake, emit_unstake,
+    mul_div_down, require_bps_range, require_not_paused, require_positive, safe_add, safe_div,
+    safe_mul, safe_sub, with_lock, AstroSwapError, OracleClient, PoolMetadata, StakingPool,
+    UserStake, BPS_DENOMINATOR, CANONICAL_PRICE_DECIMALS, MAX_FEE_BPS, MAX_POOL_NAME_LENGTH,
+};
+use soroban_sdk::{contract, contractevent, contractimpl, token, Address, BytesN, Env, Vec};
+
+use crate::retro::{
+    retro_leaf, verify_proof, RetroDropClosed, RetroDropPublished, RetroRewardClaimed,
+    MAX_PROOF_LENGTH,
 };
-use soroban_sdk::{contract, contractimpl, token, Address, Env};
 
 use crate::storage::{
-    extend_instance_ttl, extend_pool_ttl, extend_user_stake_ttl, get_admin, get_pool,
-    get_pool_count, get_reward_token, get_user_stake, increment_pool_count, is_initialized,
-    is_locked, is_paused, set_admin, set_initialized, set_locked, set_paused, set_pool,
-    set_reward_token, set_user_stake,
+    extend_instance_ttl, extend_pool_ttl, extend_user_stake_ttl, get_admin, get_checkpoint,
+    get_claim_deadline, get_claimer, get_last_deposit, get_migration, get_pool, get_pool_count,
+    get_pool_funding, get_pool_generation, get_pool_options, get_retro_drop, get_retro_drop_count,
+    get_retro_reserved, get_reward_ledger, get_reward_precision, get_reward_token,
+    get_settled_rewards, get_token_staked, get_treasury, get_user_generation, get_user_stake,
+    has_checkpoint, increment_pool_count, increment_retro_drop_count, is_initialized, is_paused,
+    is_pool_creator, is_retro_claimed, remove_claimer, set_admin, set_checkpoint,
+    set_claim_deadline, set_claimer, set_initialized, set_last_deposit, set_migration, set_paused,
+    set_pool, set_pool_creator, set_pool_funding, set_pool_generation, set_pool_options,
+    set_retro_claimed, set_retro_drop, set_retro_reserved, set_reward_ledger, set_reward_precision,
+    set_reward_token, set_settled_rewards, set_token_staked, set_treasury, set_user_generation,
+    set_user_stake, DataKey, MigrationParams, MigrationRecord, PoolCheckpoint, PoolOptions,
+    RetroDrop, RewardLedger,
 };
 
-/// Precision for reward calculations
+/// Precision for reward calculations, until a migration sets a pool's own
 const REWARD_PRECISION: i128 = 1_000_000_000_000;
 
+/// Length of a reward checkpoint epoch in seconds (1 day)
+pub const CHECKPOINT_EPOCH_SECONDS: u64 = 86_400;
+
+/// Seconds per year, for APR annualization
+const SECONDS_PER_YEAR: i128 = 31_536_000;
+
+/// Decimals of USD prices and values in the analytics views
+const PRICE_DECIMALS: u32 = CANONICAL_PRICE_DECIMALS;
+
+/// One USD at `PRICE_DECIMALS`
+const PRICE_PRECISION: i128 = 10i128.pow(PRICE_DECIMALS);
+
+/// Longest withdrawal cooldown a pool can be created with (30 days)
+pub const MAX_COOLDOWN_SECONDS: u64 = 30 * 86_400;
+
+/// Highest reward rate a single pool can emit (1M tokens per second at 7
+/// decimals). Enforced per pool, not across pools.
+pub const MAX_POOL_REWARD_PER_SECOND: i128 = 1_000_000_0000000;
+
+/// Shortest time stakers get to claim after a pool ends, or after its claim
+/// deadline is set, before its rewards can expire (90 days)
+pub const MIN_CLAIM_GRACE_SECONDS: u64 = 90 * 86_400;
+
+/// Pool migrated to new reward parameters event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolMigrated {
+    #[topic]
+    pub pool_id: u32,
+    /// Generation the pool enters
+    pub generation: u32,
+    pub old_reward_token: Address,
+    pub new_reward_token: Address,
+    /// Final accumulator of the closed generation
+    pub settled_acc_reward_per_share: i128,
+    pub reward_per_second: i128,
+    pub end_time: u64,
+    pub reward_precision: i128,
+}
+
+/// Rewards of a closed pool generation settled to a user's ledger event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RewardsSettled {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub pool_id: u32,
+    pub generation: u32,
+    pub reward_token: Address,
+    pub amount: i128,
+}
+
+/// Settled rewards claimed event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SettledRewardsClaimed {
+    #[topic]
+    pub user: Address,
+    pub reward_token: Address,
+    pub amount: i128,
+}
+
+/// Keeper allowed to claim and compound a user's rewards changed event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimerUpdated {
+    #[topic]
+    pub user: Address,
+    #[topic]
+    pub pool_id: u32,
+    /// New claimer, None once revoked
+    pub claimer: Option<Address>,
+}
+
+/// Pool claim deadline set or removed event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimDeadlineSet {
+    #[topic]
+    pub pool_id: u32,
+    /// Time from which unclaimed rewards expire, 0 once removed
+    pub claim_deadline: u64,
+}
+
+/// Unclaimed rewards of a pool past its claim deadline sent to the treasury event
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExpiredRewardsSwept {
+    #[topic]
+    pub pool_id: u32,
+    #[topic]
+    pub treasury: Address,
+    pub reward_token: Address,
+    pub amount: i128,
+    pub claim_deadline: u64,
+}
+
 #[contract]
 pub struct AstroSwapStaking;
 
@@ -41,27 +155,122 @@ impl AstroSwapStaking {
     /// Create a new staking pool
     ///
     /// # Arguments
-    /// * `admin` - Admin address (must match contract admin)
+    /// * `creator` - Contract admin or an allowlisted pool creator
     /// * `lp_token` - LP token address to stake
     /// * `reward_per_second` - Rewards distributed per second
     /// * `start_time` - When rewards start accruing
     /// * `end_time` - When rewards stop accruing
+    ///
+    /// The rate is capped at `MAX_POOL_REWARD_PER_SECOND` and its emission over
+    /// the whole period must fit in an `i128`. Rewards are not escrowed at
+    /// creation: claims fail with `RewardsUnfunded` until `fund_rewards`
+    /// covers them.
     pub fn create_pool(
         env: Env,
-        admin: Address,
+        creator: Address,
         lp_token: Address,
         reward_per_second: i128,
         start_time: u64,
         end_time: u64,
     ) -> Result<u32, AstroSwapError> {
-        Self::require_admin(&env, &admin)?;
+        Self::create_pool_with_options(
+            env,
+            creator,
+            lp_token,
+            reward_per_second,
+            start_time,
+            end_time,
+            0,
+            0,
+        )
+    }
+
+    /// Create a new staking pool with a deposit fee and withdrawal cooldown
+    ///
+    /// Both deter yield hopping into pools that attract short-term capital:
+    /// `deposit_fee_bps` (at most 1%) of every stake is sent to the admin,
+    /// and a user cannot unstake until `cooldown_seconds` (at most 30 days)
+    /// have passed since their latest stake. Zero disables either option.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_pool_with_options(
+        env: Env,
+        creator: Address,
+        lp_token: Address,
+        reward_per_second: i128,
+        start_time: u64,
+        end_time: u64,
+        deposit_fee_bps: u32,
+        cooldown_seconds: u64,
+    ) -> Result<u32, AstroSwapError> {
+        Self::create_pool_internal(
+            &env,
+            &creator,
+            lp_token,
+            reward_per_second,
+            start_time,
+            end_time,
+            &PoolOptions {
+                deposit_fee_bps,
+                cooldown_seconds,
+            },
+            None,
+        )
+    }
+
+    /// Create a new staking pool described by `metadata`
+    ///
+    /// Same as `create_pool`, with the name, tag and URL hash frontends show
+    /// for the pool in `pool_info`. The name must be 1 to
+    /// `MAX_POOL_NAME_LENGTH` bytes long.
+    pub fn create_pool_with_metadata(
+        env: Env,
+        creator: Address,
+        lp_token: Address,
+        reward_per_second: i128,
+        start_time: u64,
+        end_time: u64,
+        metadata: PoolMetadata,
+    ) -> Result<u32, AstroSwapError> {
+        Self::create_pool_internal(
+            &env,
+            &creator,
+            lp_token,
+            reward_per_second,
+            start_time,
+            end_time,
+            &PoolOptions::default(),
+            Some(metadata),
+        )
+    }
+
+    /// Validate the parameters of a new pool and store it
+    #[allow(clippy::too_many_arguments)]
+    fn create_pool_internal(
+        env: &Env,
+        creator: &Address,
+        lp_token: Address,
+        reward_per_second: i128,
+        start_time: u64,
+        end_time: u64,
+        options: &PoolOptions,
+        metadata: Option<PoolMetadata>,
+    ) -> Result<u32, AstroSwapError> {
+        Self::require_pool_creator(env, creator)?;
 
         if start_time >= end_time {
             return Err(AstroSwapError::InvalidStakingPeriod);
         }
+        Self::total_emission(reward_per_second, start_time, end_time)?;
+        require_bps_range(options.deposit_fee_bps, MAX_FEE_BPS)?;
+        if options.cooldown_seconds > MAX_COOLDOWN_SECONDS {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+        if let Some(metadata) = &metadata {
+            Self::require_valid_metadata(metadata)?;
+        }
 
-        let pool_id = increment_pool_count(&env);
-        let reward_token = get_reward_token(&env).ok_or(AstroSwapError::NotInitialized)?;
+        let pool_id = increment_pool_count(env);
+        let reward_token = get_reward_token(env).ok_or(AstroSwapError::NotInitialized)?;
 
         let pool = StakingPool {
             pool_id,
@@ -73,29 +282,17 @@ impl AstroSwapStaking {
             end_time,
             last_update_time: start_time,
             acc_reward_per_share: 0,
+            metadata,
         };
 
-        set_pool(&env, pool_id, &pool);
-        extend_instance_ttl(&env);
-        extend_pool_ttl(&env, pool_id);
-
-        Ok(pool_id)
-    }
-
-    // ==================== Reentrancy Guard ====================
-
-    /// Internal function to acquire reentrancy lock
-    fn acquire_lock(env: &Env) -> Result<(), AstroSwapError> {
-        if is_locked(env) {
-            return Err(AstroSwapError::Reentrancy);
+        set_pool(env, pool_id, &pool);
+        if *options != PoolOptions::default() {
+            set_pool_options(env, pool_id, options);
         }
-        set_locked(env, true);
-        Ok(())
-    }
+        extend_instance_ttl(env);
+        extend_pool_ttl(env, pool_id);
 
-    /// Internal function to release reentrancy lock
-    fn release_lock(env: &Env) {
-        set_locked(env, false);
+        Ok(pool_id)
     }
 
     /// Stake LP tokens in a pool
@@ -103,7 +300,10 @@ impl AstroSwapStaking {
     /// # Arguments
     /// * `user` - User address
     /// * `pool_id` - Pool to stake in
-    /// * `amount` - Amount of LP tokens to stake
+    /// * `amount` - Amount of LP tokens to stake, including the pool's
+    ///   deposit fee if it has one
+    ///
+    /// Staking (re)starts the pool's withdrawal cooldown for the user.
     ///
     /// # Security
     /// Uses reentrancy guard to prevent flash loan attacks
@@ -114,69 +314,78 @@ impl AstroSwapStaking {
         amount: i128,
     ) -> Result<(), AstroSwapError> {
         user.require_auth();
-        Self::require_not_paused(&env)?;
-        Self::acquire_lock(&env)?;
-
-        if amount <= 0 {
-            Self::release_lock(&env);
-            return Err(AstroSwapError::InvalidAmount);
-        }
-
-        let mut pool = get_pool(&env, pool_id).ok_or(AstroSwapError::StakingPoolNotFound)?;
-
-        // Update pool rewards
-        Self::update_pool(&env, &mut pool)?;
-
-        // Get or create user stake
-        let mut user_stake = get_user_stake(&env, &user, pool_id).unwrap_or(UserStake {
-            amount: 0,
-            reward_debt: 0,
-            stake_time: env.ledger().timestamp(),
-            multiplier: BPS_DENOMINATOR, // 1x
-        });
-
-        // Claim any pending rewards first
-        if user_stake.amount > 0 {
-            let pending = Self::calculate_pending_rewards(&pool, &user_stake)?;
-            if pending > 0 {
-                Self::transfer_rewards(&env, &pool.reward_token, &user, pending)?;
-                emit_claim(&env, &user, pool_id, pending);
+        require_not_paused(&env, &DataKey::Paused)?;
+        with_lock(&env, &DataKey::Locked, || {
+            require_positive(amount)?;
+
+            let mut pool = get_pool(&env, pool_id).ok_or(AstroSwapError::StakingPoolNotFound)?;
+
+            // Update pool rewards
+            Self::update_pool(&env, &mut pool)?;
+
+            // Get or create user stake
+            let mut user_stake = get_user_stake(&env, &user, pool_id).unwrap_or(UserStake {
+                amount: 0,
+                reward_debt: 0,
+                stake_time: env.ledger().timestamp(),
+                multiplier: BPS_DENOMINATOR, // 1x
+            });
+            Self::settle_migrations(&env, &user, pool_id, &mut user_stake)?;
+
+            // Claim any pending rewards first (forfeited past the claim deadline)
+            if user_stake.amount > 0 && !Self::claim_period_expired(&env, pool_id) {
+                let pending = Self::calculate_pending_rewards(&env, &pool, &user_stake)?;
+                if pending > 0 {
+                    Self::transfer_rewards(&env, &pool.reward_token, &user, pending)?;
+                    Self::record_paid(&env, pool_id, pending, pending)?;
+                    emit_claim(&env, &user, pool_id, pending);
+                }
             }
-        }
 
-        // Transfer LP tokens from user
-        let lp_client = token::Client::new(&env, &pool.lp_token);
-        lp_client.transfer(&user, env.current_contract_address(), &amount);
+            // Transfer LP tokens from user, the deposit fee going to the admin
+            let options = get_pool_options(&env, pool_id);
+            let fee = apply_bps_round_up(amount, options.deposit_fee_bps)?;
+            let amount = safe_sub(amount, fee)?;
+            require_positive(amount)?;
 
-        // Update user stake
-        user_stake.amount = safe_add(user_stake.amount, amount)?;
-        if user_stake.stake_time == 0 {
-            user_stake.stake_time = env.ledger().timestamp();
-        }
+            let lp_client = token::Client::new(&env, &pool.lp_token);
+            if fee > 0 {
+                lp_client.transfer(&user, &get_admin(&env), &fee);
+            }
+            lp_client.transfer(&user, env.current_contract_address(), &amount);
 
-        // Update reward debt
-        user_stake.reward_debt = safe_div(
-            safe_mul(user_stake.amount, pool.acc_reward_per_share)?,
-            REWARD_PRECISION,
-        )?;
+            // Update user stake
+            user_stake.amount = safe_add(user_stake.amount, amount)?;
+            if user_stake.stake_time == 0 {
+                user_stake.stake_time = env.ledger().timestamp();
+            }
 
-        // Update pool total
-        pool.total_staked = safe_add(pool.total_staked, amount)?;
+            // Update reward debt
+            user_stake.reward_debt = safe_div(
+                safe_mul(user_stake.amount, pool.acc_reward_per_share)?,
+                Self::reward_precision(&env, pool_id),
+            )?;
 
-        // Save state
-        set_pool(&env, pool_id, &pool);
-        set_user_stake(&env, &user, pool_id, &user_stake);
+            // Update pool total
+            pool.total_staked = safe_add(pool.total_staked, amount)?;
+            Self::track_staked(&env, &pool.lp_token, amount)?;
 
-        // Emit event
-        emit_stake(&env, &user, pool_id, amount);
+            // Save state
+            set_pool(&env, pool_id, &pool);
+            set_user_stake(&env, &user, pool_id, &user_stake);
+            if options.cooldown_seconds > 0 {
+                set_last_deposit(&env, &user, pool_id, env.ledger().timestamp());
+            }
 
-        extend_instance_ttl(&env);
-        extend_pool_ttl(&env, pool_id);
-        extend_user_stake_ttl(&env, &user, pool_id);
+            // Emit event
+            emit_stake(&env, &user, pool_id, amount);
 
-        Self::release_lock(&env);
+            extend_instance_ttl(&env);
+            extend_pool_ttl(&env, pool_id);
+            extend_user_stake_ttl(&env, &user, pool_id);
 
-        Ok(())
+            Ok(())
+        })
     }
 
     /// Unstake LP tokens from a pool
@@ -186,6 +395,10 @@ impl AstroSwapStaking {
     /// * `pool_id` - Pool to unstake from
     /// * `amount` - Amount of LP tokens to unstake
     ///
+    /// Fails with `CooldownActive` while the pool's withdrawal cooldown since
+    /// the user's latest stake is running. Past the pool's claim deadline the
+    /// LP tokens are returned but pending rewards are forfeited.
+    ///
     /// # Security
     /// Uses reentrancy guard to prevent flash loan attacks
     pub fn unstake(
@@ -195,149 +408,96 @@ impl AstroSwapStaking {
         amount: i128,
     ) -> Result<(), AstroSwapError> {
         user.require_auth();
-        Self::acquire_lock(&env)?;
+        with_lock(&env, &DataKey::Locked, || {
+            require_positive(amount)?;
 
-        if amount <= 0 {
-            Self::release_lock(&env);
-            return Err(AstroSwapError::InvalidAmount);
-        }
+            let mut pool = get_pool(&env, pool_id).ok_or(AstroSwapError::StakingPoolNotFound)?;
+            let mut user_stake =
+                get_user_stake(&env, &user, pool_id).ok_or(AstroSwapError::StakeNotFound)?;
 
-        let mut pool = match get_pool(&env, pool_id) {
-            Some(p) => p,
-            None => {
-                Self::release_lock(&env);
-                return Err(AstroSwapError::StakingPoolNotFound);
+            if user_stake.amount < amount {
+                return Err(AstroSwapError::InsufficientStake);
             }
-        };
-        let mut user_stake = match get_user_stake(&env, &user, pool_id) {
-            Some(s) => s,
-            None => {
-                Self::release_lock(&env);
-                return Err(AstroSwapError::StakeNotFound);
+            if env.ledger().timestamp() < Self::cooldown_end_time(&env, &user, pool_id) {
+                return Err(AstroSwapError::CooldownActive);
+            }
+            Self::settle_migrations(&env, &user, pool_id, &mut user_stake)?;
+
+            // Update pool rewards
+            Self::update_pool(&env, &mut pool)?;
+
+            // Calculate and transfer pending rewards
+            let pending = Self::calculate_pending_rewards(&env, &pool, &user_stake)?;
+            if pending > 0 && !Self::claim_period_expired(&env, pool_id) {
+                // Apply multiplier (multiplier is u32, safely fits in i128)
+                let multiplier = Self::get_current_multiplier(&env, &user_stake);
+                let boosted_reward = safe_div(
+                    safe_mul(pending, i128::from(multiplier))?,
+                    i128::from(BPS_DENOMINATOR),
+                )?;
+                Self::transfer_rewards(&env, &pool.reward_token, &user, boosted_reward)?;
+                Self::record_paid(&env, pool_id, pending, boosted_reward)?;
+                emit_claim(&env, &user, pool_id, boosted_reward);
             }
-        };
-
-        if user_stake.amount < amount {
-            Self::release_lock(&env);
-            return Err(AstroSwapError::InsufficientStake);
-        }
-
-        // Update pool rewards
-        Self::update_pool(&env, &mut pool)?;
 
-        // Calculate and transfer pending rewards
-        let pending = Self::calculate_pending_rewards(&pool, &user_stake)?;
-        if pending > 0 {
-            // Apply multiplier (multiplier is u32, safely fits in i128)
-            let multiplier = Self::get_current_multiplier(&env, &user_stake);
-            let boosted_reward = safe_div(
-                safe_mul(pending, i128::from(multiplier))?,
-                i128::from(BPS_DENOMINATOR),
+            // Update user stake
+            user_stake.amount = safe_sub(user_stake.amount, amount)?;
+            user_stake.reward_debt = safe_div(
+                safe_mul(user_stake.amount, pool.acc_reward_per_share)?,
+                Self::reward_precision(&env, pool_id),
             )?;
-            Self::transfer_rewards(&env, &pool.reward_token, &user, boosted_reward)?;
-            emit_claim(&env, &user, pool_id, boosted_reward);
-        }
-
-        // Update user stake
-        user_stake.amount = safe_sub(user_stake.amount, amount)?;
-        user_stake.reward_debt = safe_div(
-            safe_mul(user_stake.amount, pool.acc_reward_per_share)?,
-            REWARD_PRECISION,
-        )?;
 
-        // Reset stake time if fully unstaked
-        if user_stake.amount == 0 {
-            user_stake.stake_time = 0;
-            user_stake.multiplier = BPS_DENOMINATOR;
-        }
-
-        // Update pool total
-        pool.total_staked = safe_sub(pool.total_staked, amount)?;
+            // Reset stake time if fully unstaked
+            if user_stake.amount == 0 {
+                user_stake.stake_time = 0;
+                user_stake.multiplier = BPS_DENOMINATOR;
+            }
 
-        // Transfer LP tokens back to user
-        let lp_client = token::Client::new(&env, &pool.lp_token);
-        lp_client.transfer(&env.current_contract_address(), &user, &amount);
+            // Update pool total
+            pool.total_staked = safe_sub(pool.total_staked, amount)?;
+            Self::track_staked(&env, &pool.lp_token, -amount)?;
 
-        // Save state
-        set_pool(&env, pool_id, &pool);
-        set_user_stake(&env, &user, pool_id, &user_stake);
+            // Transfer LP tokens back to user
+            let lp_client = token::Client::new(&env, &pool.lp_token);
+            lp_client.transfer(&env.current_contract_address(), &user, &amount);
 
-        // Emit event
-        emit_unstake(&env, &user, pool_id, amount);
+            // Save state
+            set_pool(&env, pool_id, &pool);
+            set_user_stake(&env, &user, pool_id, &user_stake);
 
-        extend_instance_ttl(&env);
-        extend_pool_ttl(&env, pool_id);
-        extend_user_stake_ttl(&env, &user, pool_id);
+            // Emit event
+            emit_unstake(&env, &user, pool_id, amount);
 
-        Self::release_lock(&env);
+            extend_instance_ttl(&env);
+            extend_pool_ttl(&env, pool_id);
+            extend_user_stake_ttl(&env, &user, pool_id);
 
-        Ok(())
+            Ok(())
+        })
     }
 
     /// Claim pending rewards without unstaking
     ///
+    /// Fails with `ClaimPeriodExpired` from the pool's claim deadline on.
+    ///
     /// # Security
     /// Uses reentrancy guard to prevent flash loan attacks
     pub fn claim_rewards(env: Env, user: Address, pool_id: u32) -> Result<i128, AstroSwapError> {
         user.require_auth();
-        Self::acquire_lock(&env)?;
-
-        let mut pool = match get_pool(&env, pool_id) {
-            Some(p) => p,
-            None => {
-                Self::release_lock(&env);
-                return Err(AstroSwapError::StakingPoolNotFound);
-            }
-        };
-        let mut user_stake = match get_user_stake(&env, &user, pool_id) {
-            Some(s) => s,
-            None => {
-                Self::release_lock(&env);
-                return Err(AstroSwapError::StakeNotFound);
-            }
-        };
-
-        // Update pool rewards
-        Self::update_pool(&env, &mut pool)?;
-
-        // Calculate pending rewards
-        let pending = Self::calculate_pending_rewards(&pool, &user_stake)?;
-
-        if pending == 0 {
-            Self::release_lock(&env);
-            return Err(AstroSwapError::NoRewardsAvailable);
-        }
-
-        // Apply multiplier (multiplier is u32, safely fits in i128)
-        let multiplier = Self::get_current_multiplier(&env, &user_stake);
-        let boosted_reward = safe_div(
-            safe_mul(pending, i128::from(multiplier))?,
-            i128::from(BPS_DENOMINATOR),
-        )?;
-
-        // Transfer rewards
-        Self::transfer_rewards(&env, &pool.reward_token, &user, boosted_reward)?;
-
-        // Update reward debt
-        user_stake.reward_debt = safe_div(
-            safe_mul(user_stake.amount, pool.acc_reward_per_share)?,
-            REWARD_PRECISION,
-        )?;
-
-        // Save state
-        set_pool(&env, pool_id, &pool);
-        set_user_stake(&env, &user, pool_id, &user_stake);
-
-        // Emit event
-        emit_claim(&env, &user, pool_id, boosted_reward);
-
-        extend_instance_ttl(&env);
-        extend_pool_ttl(&env, pool_id);
-        extend_user_stake_ttl(&env, &user, pool_id);
-
-        Self::release_lock(&env);
+        Self::claim_rewards_internal(&env, &user, pool_id)
+    }
 
-        Ok(boosted_reward)
+    /// Claim a user's pending rewards as their approved claimer
+    ///
+    /// Rewards are always sent to `user`; see `approve_claimer`.
+    pub fn claim_rewards_for(
+        env: Env,
+        claimer: Address,
+        user: Address,
+        pool_id: u32,
+    ) -> Result<i128, AstroSwapError> {
+        Self::require_claimer(&env, &claimer, &user, pool_id)?;
+        Self::claim_rewards_internal(&env, &user, pool_id)
     }
 
     /// Compound rewards back into stake (if reward token == LP token)
@@ -348,7 +508,7 @@ impl AstroSwapStaking {
 
         // Can only compound if reward token is the LP token
         if pool.reward_token != pool.lp_token {
-            return Err(AstroSwapError::InvalidArgument);
+            return Err(AstroSwapError::CompoundNotSupported);
         }
 
         // Claim rewards
@@ -360,93 +520,715 @@ impl AstroSwapStaking {
         Ok(rewards)
     }
 
-    // ==================== Admin Functions ====================
-
-    /// Update pool reward rate
-    pub fn update_pool_rewards(
+    /// Compound a user's rewards back into their stake as their approved
+    /// claimer (if reward token == LP token)
+    ///
+    /// The rewards never leave the contract: they are added to the user's
+    /// stake, less the pool's deposit fee. Unlike a user's own stake, this
+    /// does not restart the withdrawal cooldown, so a claimer cannot keep a
+    /// user's LP tokens locked. Returns the rewards compounded, fee included.
+    ///
+    /// # Security
+    /// Uses reentrancy guard to prevent flash loan attacks
+    pub fn compound_for(
         env: Env,
-        admin: Address,
+        claimer: Address,
+        user: Address,
         pool_id: u32,
-        reward_per_second: i128,
-    ) -> Result<(), AstroSwapError> {
-        Self::require_admin(&env, &admin)?;
-
-        let mut pool = get_pool(&env, pool_id).ok_or(AstroSwapError::StakingPoolNotFound)?;
+    ) -> Result<i128, AstroSwapError> {
+        Self::require_claimer(&env, &claimer, &user, pool_id)?;
+        require_not_paused(&env, &DataKey::Paused)?;
+        with_lock(&env, &DataKey::Locked, || {
+            let mut pool = get_pool(&env, pool_id).ok_or(AstroSwapError::StakingPoolNotFound)?;
+            if pool.reward_token != pool.lp_token {
+                return Err(AstroSwapError::CompoundNotSupported);
+            }
+            if Self::claim_period_expired(&env, pool_id) {
+                return Err(AstroSwapError::ClaimPeriodExpired);
+            }
+            let mut user_stake =
+                get_user_stake(&env, &user, pool_id).ok_or(AstroSwapError::StakeNotFound)?;
+            Self::settle_migrations(&env, &user, pool_id, &mut user_stake)?;
 
-        // Update pool first to settle current rewards
-        Self::update_pool(&env, &mut pool)?;
+            // Update pool rewards
+            Self::update_pool(&env, &mut pool)?;
 
-        pool.reward_per_second = reward_per_second;
-        set_pool(&env, pool_id, &pool);
+            let pending = Self::calculate_pending_rewards(&env, &pool, &user_stake)?;
+            if pending == 0 {
+                return Err(AstroSwapError::NoRewardsAvailable);
+            }
+            let multiplier = Self::get_current_multiplier(&env, &user_stake);
+            let rewards = safe_div(
+                safe_mul(pending, i128::from(multiplier))?,
+                i128::from(BPS_DENOMINATOR),
+            )?;
 
-        extend_instance_ttl(&env);
-        extend_pool_ttl(&env, pool_id);
+            // Same funding check as a claim: never paid out of staked principal
+            if Self::available_rewards(&env, &pool.reward_token) < rewards {
+                return Err(AstroSwapError::RewardsUnfunded);
+            }
 
-        Ok(())
-    }
+            // The deposit fee goes to the admin, the rest is staked
+            let options = get_pool_options(&env, pool_id);
+            let fee = apply_bps_round_up(rewards, options.deposit_fee_bps)?;
+            if fee > 0 {
+                token::Client::new(&env, &pool.lp_token).transfer(
+                    &env.current_contract_address(),
+                    &get_admin(&env),
+                    &fee,
+                );
+            }
+            let amount = safe_sub(rewards, fee)?;
+            Self::record_paid(&env, pool_id, pending, rewards)?;
 
-    /// Fund the reward pool
-    pub fn fund_rewards(env: Env, funder: Address, amount: i128) -> Result<(), AstroSwapError> {
-        funder.require_auth();
+            user_stake.amount = safe_add(user_stake.amount, amount)?;
+            user_stake.reward_debt = safe_div(
+                safe_mul(user_stake.amount, pool.acc_reward_per_share)?,
+                Self::reward_precision(&env, pool_id),
+            )?;
+            pool.total_staked = safe_add(pool.total_staked, amount)?;
+            Self::track_staked(&env, &pool.lp_token, amount)?;
 
-        let reward_token = get_reward_token(&env).ok_or(AstroSwapError::NotInitialized)?;
-        let token_client = token::Client::new(&env, &reward_token);
+            // Save state
+            set_pool(&env, pool_id, &pool);
+            set_user_stake(&env, &user, pool_id, &user_stake);
 
-        token_client.transfer(&funder, env.current_contract_address(), &amount);
+            emit_claim(&env, &user, pool_id, rewards);
+            emit_stake(&env, &user, pool_id, amount);
 
-        extend_instance_ttl(&env);
+            extend_instance_ttl(&env);
+            extend_pool_ttl(&env, pool_id);
+            extend_user_stake_ttl(&env, &user, pool_id);
 
-        Ok(())
+            Ok(rewards)
+        })
     }
 
-    /// Pause/unpause the contract
-    pub fn set_paused(env: Env, admin: Address, paused: bool) -> Result<(), AstroSwapError> {
-        Self::require_admin(&env, &admin)?;
-        set_paused(&env, paused);
+    /// Let `claimer` (e.g. an auto-compound keeper) call `claim_rewards_for`
+    /// and `compound_for` on the user's stake in a pool
+    ///
+    /// Rewards always go to the user and the claimer can never move their LP
+    /// tokens. Replaces any previous claimer for the pool.
+    pub fn approve_claimer(
+        env: Env,
+        user: Address,
+        pool_id: u32,
+        claimer: Address,
+    ) -> Result<(), AstroSwapError> {
+        user.require_auth();
+        get_pool(&env, pool_id).ok_or(AstroSwapError::StakingPoolNotFound)?;
+
+        set_claimer(&env, &user, pool_id, &claimer);
+        ClaimerUpdated {
+            user,
+            pool_id,
+            claimer: Some(claimer),
+        }
+        .publish(&env);
+
         extend_instance_ttl(&env);
         Ok(())
     }
 
-    /// Transfer admin role
-    pub fn set_admin(env: Env, admin: Address, new_admin: Address) -> Result<(), AstroSwapError> {
-        Self::require_admin(&env, &admin)?;
-        set_admin(&env, &new_admin);
+    /// Revoke the user's claimer for a pool (no-op if none is approved)
+    pub fn revoke_claimer(env: Env, user: Address, pool_id: u32) {
+        user.require_auth();
+        if get_claimer(&env, &user, pool_id).is_some() {
+            remove_claimer(&env, &user, pool_id);
+            ClaimerUpdated {
+                user,
+                pool_id,
+                claimer: None,
+            }
+            .publish(&env);
+        }
         extend_instance_ttl(&env);
-        Ok(())
     }
 
-    // ==================== View Functions ====================
+    /// Credit a user's rewards from migrated pool generations to their claimable ledger
+    ///
+    /// Callable by anyone (e.g. a keeper); stakes are also settled on the
+    /// user's next stake, unstake or claim in the pool. Returns the total
+    /// credited (0 if the stake is already settled).
+    pub fn settle_rewards(env: Env, user: Address, pool_id: u32) -> Result<i128, AstroSwapError> {
+        with_lock(&env, &DataKey::Locked, || {
+            get_pool(&env, pool_id).ok_or(AstroSwapError::StakingPoolNotFound)?;
+            let mut user_stake =
+                get_user_stake(&env, &user, pool_id).ok_or(AstroSwapError::StakeNotFound)?;
+
+            let settled = Self::settle_migrations(&env, &user, pool_id, &mut user_stake)?;
+            set_user_stake(&env, &user, pool_id, &user_stake);
+
+            extend_instance_ttl(&env);
+            extend_user_stake_ttl(&env, &user, pool_id);
+
+            Ok(settled)
+        })
+    }
 
-    /// Get pending rewards for a user
-    pub fn pending_rewards(env: Env, user: Address, pool_id: u32) -> Result<i128, AstroSwapError> {
-        let pool = get_pool(&env, pool_id).ok_or(AstroSwapError::StakingPoolNotFound)?;
-        let user_stake =
-            get_user_stake(&env, &user, pool_id).ok_or(AstroSwapError::StakeNotFound)?;
+    /// Claim rewards settled by pool migrations in `reward_token`
+    ///
+    /// # Security
+    /// Uses reentrancy guard to prevent flash loan attacks
+    pub fn claim_settled_rewards(
+        env: Env,
+        user: Address,
+        reward_token: Address,
+    ) -> Result<i128, AstroSwapError> {
+        user.require_auth();
+        with_lock(&env, &DataKey::Locked, || {
+            let amount = get_settled_rewards(&env, &user, &reward_token);
+            if amount == 0 {
+                return Err(AstroSwapError::NoRewardsAvailable);
+            }
 
-        // Calculate pending with current acc_reward_per_share
-        let mut simulated_pool = pool.clone();
-        Self::update_pool_internal(&env, &mut simulated_pool)?;
+            set_settled_rewards(&env, &user, &reward_token, 0);
+            Self::transfer_rewards(&env, &reward_token, &user, amount)?;
 
-        let pending = Self::calculate_pending_rewards(&simulated_pool, &user_stake)?;
+            SettledRewardsClaimed {
+                user: user.clone(),
+                reward_token: reward_token.clone(),
+                amount,
+            }
+            .publish(&env);
 
-        // Apply multiplier (multiplier is u32, safely fits in i128)
-        let multiplier = Self::get_current_multiplier(&env, &user_stake);
-        safe_div(
-            safe_mul(pending, i128::from(multiplier))?,
-            i128::from(BPS_DENOMINATOR),
-        )
-    }
+            extend_instance_ttl(&env);
 
-    /// Get pool information
-    pub fn pool_info(env: Env, pool_id: u32) -> Result<StakingPool, AstroSwapError> {
-        extend_instance_ttl(&env);
-        extend_pool_ttl(&env, pool_id);
-        get_pool(&env, pool_id).ok_or(AstroSwapError::StakingPoolNotFound)
+            Ok(amount)
+        })
     }
 
-    /// Get user stake information
-    pub fn user_info(env: Env, user: Address, pool_id: u32) -> Result<UserStake, AstroSwapError> {
-        extend_instance_ttl(&env);
+    /// Publish retroactive rewards as the merkle root of `(user, amount)`
+    /// leaves, see the `retro` module for the tree layout
+    ///
+    /// The admin funds the drop with `total_amount` of `reward_token` up
+    /// front; claims from the drop never pay out more. The funds stay
+    /// reserved for the drop's claimants until it is closed: pool rewards
+    /// cannot be paid out of them. Returns the drop ID.
+    pub fn publish_retro_drop(
+        env: Env,
+        admin: Address,
+        merkle_root: BytesN<32>,
+        reward_token: Address,
+        total_amount: i128,
+    ) -> Result<u32, AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+        require_positive(total_amount)?;
+
+        token::Client::new(&env, &reward_token).transfer(
+            &admin,
+            env.current_contract_address(),
+            &total_amount,
+        );
+
+        let reserved = get_retro_reserved(&env, &reward_token);
+        set_retro_reserved(&env, &reward_token, safe_add(reserved, total_amount)?);
+
+        let drop_id = increment_retro_drop_count(&env);
+        let retro_drop = RetroDrop {
+            merkle_root: merkle_root.clone(),
+            reward_token: reward_token.clone(),
+            total_amount,
+            claimed: 0,
+            published_at: env.ledger().timestamp(),
+            closed: false,
+        };
+        set_retro_drop(&env, drop_id, &retro_drop);
+
+        RetroDropPublished {
+            drop_id,
+            merkle_root,
+            reward_token,
+            total_amount,
+        }
+        .publish(&env);
+
+        extend_instance_ttl(&env);
+        Ok(drop_id)
+    }
+
+    /// Claim `amount` from a retroactive drop with a merkle `proof` of the
+    /// user's leaf
+    ///
+    /// Each user claims once per drop. Fails with `InvalidMerkleProof` if the
+    /// proof does not lead to the drop's root, `RetroRewardClaimed` on a
+    /// second claim and `RetroDropClosed` once the drop is closed.
+    ///
+    /// # Security
+    /// Uses reentrancy guard to prevent flash loan attacks
+    pub fn claim_retro_reward(
+        env: Env,
+        user: Address,
+        drop_id: u32,
+        amount: i128,
+        proof: Vec<BytesN<32>>,
+    ) -> Result<i128, AstroSwapError> {
+        user.require_auth();
+        with_lock(&env, &DataKey::Locked, || {
+            let mut retro_drop =
+                get_retro_drop(&env, drop_id).ok_or(AstroSwapError::RetroDropNotFound)?;
+            if retro_drop.closed {
+                return Err(AstroSwapError::RetroDropClosed);
+            }
+            if is_retro_claimed(&env, drop_id, &user) {
+                return Err(AstroSwapError::RetroRewardClaimed);
+            }
+            require_positive(amount)?;
+            let leaf = retro_leaf(&env, &user, amount);
+            if proof.len() > MAX_PROOF_LENGTH
+                || !verify_proof(&env, leaf, &proof, &retro_drop.merkle_root)
+            {
+                return Err(AstroSwapError::InvalidMerkleProof);
+            }
+
+            // A root summing past the funded total cannot drain other rewards
+            retro_drop.claimed = safe_add(retro_drop.claimed, amount)?;
+            if retro_drop.claimed > retro_drop.total_amount {
+                return Err(AstroSwapError::RewardsUnfunded);
+            }
+            set_retro_drop(&env, drop_id, &retro_drop);
+            set_retro_claimed(&env, drop_id, &user);
+            Self::release_retro_reserve(&env, &retro_drop.reward_token, amount)?;
+            Self::transfer_rewards(&env, &retro_drop.reward_token, &user, amount)?;
+
+            RetroRewardClaimed {
+                user: user.clone(),
+                drop_id,
+                reward_token: retro_drop.reward_token.clone(),
+                amount,
+            }
+            .publish(&env);
+
+            extend_instance_ttl(&env);
+
+            Ok(amount)
+        })
+    }
+
+    /// Close a retroactive drop and refund its unclaimed remainder to the
+    /// admin
+    ///
+    /// Claims from a closed drop fail with `RetroDropClosed`. Returns the
+    /// amount refunded.
+    ///
+    /// # Security
+    /// Uses reentrancy guard to prevent flash loan attacks
+    pub fn close_retro_drop(
+        env: Env,
+        admin: Address,
+        drop_id: u32,
+    ) -> Result<i128, AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+        with_lock(&env, &DataKey::Locked, || {
+            let mut retro_drop =
+                get_retro_drop(&env, drop_id).ok_or(AstroSwapError::RetroDropNotFound)?;
+            if retro_drop.closed {
+                return Err(AstroSwapError::RetroDropClosed);
+            }
+
+            let refunded = safe_sub(retro_drop.total_amount, retro_drop.claimed)?;
+            retro_drop.closed = true;
+            set_retro_drop(&env, drop_id, &retro_drop);
+            Self::release_retro_reserve(&env, &retro_drop.reward_token, refunded)?;
+            Self::transfer_rewards(&env, &retro_drop.reward_token, &admin, refunded)?;
+
+            RetroDropClosed {
+                drop_id,
+                reward_token: retro_drop.reward_token,
+                refunded,
+            }
+            .publish(&env);
+
+            extend_instance_ttl(&env);
+
+            Ok(refunded)
+        })
+    }
+
+    /// Settle a pool's rewards and snapshot it for the current epoch
+    ///
+    /// Callable by anyone (e.g. a keeper). Pools are also checkpointed on the
+    /// first interaction of each epoch; calling this keeps the lazy update
+    /// short for pools that see no interactions. Returns the epoch.
+    pub fn checkpoint_pool(env: Env, pool_id: u32) -> Result<u64, AstroSwapError> {
+        with_lock(&env, &DataKey::Locked, || {
+            let mut pool = get_pool(&env, pool_id).ok_or(AstroSwapError::StakingPoolNotFound)?;
+
+            Self::update_pool_internal(&env, &mut pool)?;
+            set_pool(&env, pool_id, &pool);
+            let epoch = Self::write_checkpoint(&env, &pool);
+
+            extend_instance_ttl(&env);
+            extend_pool_ttl(&env, pool_id);
+
+            Ok(epoch)
+        })
+    }
+
+    // ==================== Admin Functions ====================
+
+    /// Update pool reward rate
+    pub fn update_pool_rewards(
+        env: Env,
+        admin: Address,
+        pool_id: u32,
+        reward_per_second: i128,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+
+        let mut pool = get_pool(&env, pool_id).ok_or(AstroSwapError::StakingPoolNotFound)?;
+        Self::total_emission(reward_per_second, pool.start_time, pool.end_time)?;
+
+        // Update pool first to settle current rewards
+        Self::update_pool(&env, &mut pool)?;
+
+        pool.reward_per_second = reward_per_second;
+        set_pool(&env, pool_id, &pool);
+
+        extend_instance_ttl(&env);
+        extend_pool_ttl(&env, pool_id);
+
+        Ok(())
+    }
+
+    /// Move a pool to new reward parameters
+    ///
+    /// Changing the reward token or precision in place would corrupt every
+    /// stake's reward debt. Instead the pool is settled under its current
+    /// parameters and the final accumulator is recorded for the closed
+    /// generation; the accumulator then restarts from zero. Each stake's
+    /// rewards from the closed generation are credited to the user's
+    /// claimable ledger (`claim_settled_rewards`) the next time the stake is
+    /// touched or `settle_rewards` is called. Returns the new generation.
+    ///
+    /// Checkpoints from different generations cannot be compared:
+    /// `historical_apr_bps` across a migration fails with `PoolMigrated`.
+    /// A pool past its claim deadline cannot be migrated, and the new
+    /// `end_time` must leave `MIN_CLAIM_GRACE_SECONDS` before the deadline.
+    /// Changing the reward token clears the pool's `fund_pool` funding; fund
+    /// it again in the new token.
+    pub fn migrate_pool(
+        env: Env,
+        admin: Address,
+        pool_id: u32,
+        new_params: MigrationParams,
+    ) -> Result<u32, AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+        with_lock(&env, &DataKey::Locked, || {
+            let mut pool = get_pool(&env, pool_id).ok_or(AstroSwapError::StakingPoolNotFound)?;
+
+            if new_params.reward_precision <= 0 {
+                return Err(AstroSwapError::InvalidArgument);
+            }
+            if new_params.end_time <= pool.start_time {
+                return Err(AstroSwapError::InvalidStakingPeriod);
+            }
+            if Self::claim_period_expired(&env, pool_id) {
+                return Err(AstroSwapError::ClaimPeriodExpired);
+            }
+            if let Some(claim_deadline) = get_claim_deadline(&env, pool_id) {
+                if new_params.end_time.saturating_add(MIN_CLAIM_GRACE_SECONDS) > claim_deadline {
+                    return Err(AstroSwapError::InvalidStakingPeriod);
+                }
+            }
+            Self::total_emission(
+                new_params.reward_per_second,
+                pool.start_time,
+                new_params.end_time,
+            )?;
+
+            // Settle rewards accrued under the old parameters
+            Self::update_pool(&env, &mut pool)?;
+
+            let generation = get_pool_generation(&env, pool_id);
+            set_migration(
+                &env,
+                pool_id,
+                &MigrationRecord {
+                    generation,
+                    timestamp: env.ledger().timestamp(),
+                    reward_token: pool.reward_token.clone(),
+                    acc_reward_per_share: pool.acc_reward_per_share,
+                    reward_precision: Self::reward_precision(&env, pool_id),
+                },
+            );
+
+            // Re-base: every stake's debt is zero against the fresh accumulator
+            let old_reward_token = pool.reward_token.clone();
+            let settled_acc_reward_per_share = pool.acc_reward_per_share;
+            pool.acc_reward_per_share = 0;
+            pool.reward_token = new_params.reward_token.clone();
+            pool.reward_per_second = new_params.reward_per_second;
+            pool.end_time = new_params.end_time;
+
+            set_pool(&env, pool_id, &pool);
+            set_reward_precision(&env, pool_id, new_params.reward_precision);
+            set_pool_generation(&env, pool_id, generation + 1);
+            // Rewards of the closed generation move to the settled ledger,
+            // which never expires
+            set_reward_ledger(&env, pool_id, &RewardLedger::default());
+            // Funding was counted in the old reward token
+            if old_reward_token != new_params.reward_token {
+                set_pool_funding(&env, pool_id, 0);
+            }
+
+            PoolMigrated {
+                pool_id,
+                generation: generation + 1,
+                old_reward_token,
+                new_reward_token: new_params.reward_token,
+                settled_acc_reward_per_share,
+                reward_per_second: new_params.reward_per_second,
+                end_time: new_params.end_time,
+                reward_precision: new_params.reward_precision,
+            }
+            .publish(&env);
+
+            extend_instance_ttl(&env);
+            extend_pool_ttl(&env, pool_id);
+
+            Ok(generation + 1)
+        })
+    }
+
+    /// Fund the reward pool
+    pub fn fund_rewards(env: Env, funder: Address, amount: i128) -> Result<(), AstroSwapError> {
+        funder.require_auth();
+
+        let reward_token = get_reward_token(&env).ok_or(AstroSwapError::NotInitialized)?;
+        let token_client = token::Client::new(&env, &reward_token);
+
+        token_client.transfer(&funder, env.current_contract_address(), &amount);
+
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Fund a pool's rewards in its reward token
+    ///
+    /// Unlike `fund_rewards`, the funds are earmarked for the pool: they cap
+    /// what `sweep_expired_rewards` can take from it.
+    pub fn fund_pool(
+        env: Env,
+        funder: Address,
+        pool_id: u32,
+        amount: i128,
+    ) -> Result<(), AstroSwapError> {
+        funder.require_auth();
+        require_positive(amount)?;
+
+        let pool = get_pool(&env, pool_id).ok_or(AstroSwapError::StakingPoolNotFound)?;
+        token::Client::new(&env, &pool.reward_token).transfer(
+            &funder,
+            env.current_contract_address(),
+            &amount,
+        );
+        let funding = get_pool_funding(&env, pool_id);
+        set_pool_funding(&env, pool_id, safe_add(funding, amount)?);
+
+        extend_instance_ttl(&env);
+        extend_pool_ttl(&env, pool_id);
+
+        Ok(())
+    }
+
+    /// Set or remove the time from which a pool's unclaimed rewards expire
+    ///
+    /// From `claim_deadline` on, claims fail with `ClaimPeriodExpired`,
+    /// unstaking forfeits pending rewards and anyone can sweep the pool's
+    /// unclaimed rewards to the treasury with `sweep_expired_rewards`. The
+    /// deadline must be at least `MIN_CLAIM_GRACE_SECONDS` after both the
+    /// pool's end and now, so stakers always get a grace period to claim.
+    /// 0 removes the deadline. Fails with `ClaimPeriodExpired` once the
+    /// current deadline has passed.
+    pub fn set_claim_deadline(
+        env: Env,
+        admin: Address,
+        pool_id: u32,
+        claim_deadline: u64,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+
+        let pool = get_pool(&env, pool_id).ok_or(AstroSwapError::StakingPoolNotFound)?;
+        if Self::claim_period_expired(&env, pool_id) {
+            return Err(AstroSwapError::ClaimPeriodExpired);
+        }
+        let earliest = pool
+            .end_time
+            .max(env.ledger().timestamp())
+            .saturating_add(MIN_CLAIM_GRACE_SECONDS);
+        if claim_deadline != 0 && claim_deadline < earliest {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+
+        set_claim_deadline(&env, pool_id, claim_deadline);
+        ClaimDeadlineSet {
+            pool_id,
+            claim_deadline,
+        }
+        .publish(&env);
+
+        extend_instance_ttl(&env);
+        extend_pool_ttl(&env, pool_id);
+        Ok(())
+    }
+
+    /// Set the treasury receiving expired rewards
+    pub fn set_treasury(env: Env, admin: Address, treasury: Address) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+        set_treasury(&env, &treasury);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Send a pool's unclaimed rewards to the treasury once its claim
+    /// deadline has passed
+    ///
+    /// Callable by anyone. Sweeps everything the pool emitted in its current
+    /// generation and has not paid out (before multipliers), capped at the
+    /// pool's own remaining funding (`pool_funding`): funds of other pools,
+    /// settled rewards and retroactive drops are never swept. Fails with
+    /// `ClaimPeriodActive` before the deadline (or without one) and
+    /// `FeeRecipientNotSet` without a treasury. Returns the amount swept.
+    ///
+    /// # Security
+    /// Uses reentrancy guard to prevent flash loan attacks
+    pub fn sweep_expired_rewards(env: Env, pool_id: u32) -> Result<i128, AstroSwapError> {
+        with_lock(&env, &DataKey::Locked, || {
+            let mut pool = get_pool(&env, pool_id).ok_or(AstroSwapError::StakingPoolNotFound)?;
+            let claim_deadline = get_claim_deadline(&env, pool_id)
+                .filter(|deadline| env.ledger().timestamp() >= *deadline)
+                .ok_or(AstroSwapError::ClaimPeriodActive)?;
+            let treasury = get_treasury(&env).ok_or(AstroSwapError::FeeRecipientNotSet)?;
+
+            // Accrue up to the pool's end before reading the ledger
+            Self::update_pool(&env, &mut pool)?;
+
+            let mut ledger = get_reward_ledger(&env, pool_id);
+            let unclaimed = safe_sub(ledger.accrued, ledger.paid)?;
+            let funding = get_pool_funding(&env, pool_id);
+            let amount = unclaimed
+                .min(funding)
+                .min(Self::available_rewards(&env, &pool.reward_token));
+            if amount <= 0 {
+                return Err(AstroSwapError::NoRewardsAvailable);
+            }
+
+            // Whatever the funding did not cover is written off as well
+            ledger.paid = ledger.accrued;
+            set_reward_ledger(&env, pool_id, &ledger);
+            set_pool_funding(&env, pool_id, safe_sub(funding, amount)?);
+            token::Client::new(&env, &pool.reward_token).transfer(
+                &env.current_contract_address(),
+                &treasury,
+                &amount,
+            );
+
+            ExpiredRewardsSwept {
+                pool_id,
+                treasury,
+                reward_token: pool.reward_token.clone(),
+                amount,
+                claim_deadline,
+            }
+            .publish(&env);
+
+            extend_instance_ttl(&env);
+            extend_pool_ttl(&env, pool_id);
+
+            Ok(amount)
+        })
+    }
+
+    /// Pause/unpause the contract
+    pub fn set_paused(env: Env, admin: Address, paused: bool) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+        set_paused(&env, paused);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Allow or disallow an address to create pools without being admin
+    /// Only admin can call
+    ///
+    /// Lets the bridge create pools for graduated tokens without holding
+    /// admin power over the staking contract.
+    pub fn set_pool_creator(
+        env: Env,
+        admin: Address,
+        creator: Address,
+        allowed: bool,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+        set_pool_creator(&env, &creator, allowed);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set or clear a pool's metadata
+    /// Only admin can call
+    pub fn set_pool_metadata(
+        env: Env,
+        admin: Address,
+        pool_id: u32,
+        metadata: Option<PoolMetadata>,
+    ) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+        if let Some(metadata) = &metadata {
+            Self::require_valid_metadata(metadata)?;
+        }
+
+        let mut pool = get_pool(&env, pool_id).ok_or(AstroSwapError::StakingPoolNotFound)?;
+        pool.metadata = metadata;
+        set_pool(&env, pool_id, &pool);
+
+        extend_instance_ttl(&env);
+        extend_pool_ttl(&env, pool_id);
+        Ok(())
+    }
+
+    /// Transfer admin role
+    pub fn set_admin(env: Env, admin: Address, new_admin: Address) -> Result<(), AstroSwapError> {
+        Self::require_admin(&env, &admin)?;
+        set_admin(&env, &new_admin);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    // ==================== View Functions ====================
+
+    /// Get pending rewards for a user
+    pub fn pending_rewards(env: Env, user: Address, pool_id: u32) -> Result<i128, AstroSwapError> {
+        Self::rewards_at(&env, &user, pool_id, env.ledger().timestamp())
+    }
+
+    /// Get the rewards a user would have pending at a future time
+    ///
+    /// Advances the pool's accumulator to `at_timestamp` (emission stops at
+    /// the pool's `end_time`) assuming the pool's stake and rate stay as they
+    /// are, and applies the duration multiplier the stake will have reached
+    /// by then. Past timestamps give the current pending rewards.
+    pub fn projected_rewards(
+        env: Env,
+        user: Address,
+        pool_id: u32,
+        at_timestamp: u64,
+    ) -> Result<i128, AstroSwapError> {
+        let at_timestamp = at_timestamp.max(env.ledger().timestamp());
+        Self::rewards_at(&env, &user, pool_id, at_timestamp)
+    }
+
+    /// Get pool information
+    pub fn pool_info(env: Env, pool_id: u32) -> Result<StakingPool, AstroSwapError> {
+        extend_instance_ttl(&env);
+        extend_pool_ttl(&env, pool_id);
+        get_pool(&env, pool_id).ok_or(AstroSwapError::StakingPoolNotFound)
+    }
+
+    /// Get user stake information
+    pub fn user_info(env: Env, user: Address, pool_id: u32) -> Result<UserStake, AstroSwapError> {
+        extend_instance_ttl(&env);
         extend_user_stake_ttl(&env, &user, pool_id);
         get_user_stake(&env, &user, pool_id).ok_or(AstroSwapError::StakeNotFound)
     }
@@ -458,6 +1240,178 @@ impl AstroSwapStaking {
         Ok(Self::get_current_multiplier(&env, &user_stake))
     }
 
+    /// Get the current checkpoint epoch
+    pub fn current_epoch(env: Env) -> u64 {
+        env.ledger().timestamp() / CHECKPOINT_EPOCH_SECONDS
+    }
+
+    /// Get a pool's checkpoint for an epoch
+    pub fn pool_checkpoint(
+        env: Env,
+        pool_id: u32,
+        epoch: u64,
+    ) -> Result<PoolCheckpoint, AstroSwapError> {
+        get_checkpoint(&env, pool_id, epoch).ok_or(AstroSwapError::CheckpointNotFound)
+    }
+
+    /// Get a pool's generation (number of migrations)
+    pub fn pool_generation(env: Env, pool_id: u32) -> u32 {
+        get_pool_generation(&env, pool_id)
+    }
+
+    /// Get the accounting a migration settled for a closed pool generation
+    pub fn pool_migration(
+        env: Env,
+        pool_id: u32,
+        generation: u32,
+    ) -> Result<MigrationRecord, AstroSwapError> {
+        get_migration(&env, pool_id, generation).ok_or(AstroSwapError::InvalidArgument)
+    }
+
+    /// Get the claimer a user approved for a pool, if any
+    pub fn claimer(env: Env, user: Address, pool_id: u32) -> Option<Address> {
+        get_claimer(&env, &user, pool_id)
+    }
+
+    /// Get a user's settled, unclaimed rewards in a reward token
+    pub fn settled_rewards(env: Env, user: Address, reward_token: Address) -> i128 {
+        get_settled_rewards(&env, &user, &reward_token)
+    }
+
+    /// Historical APR of a pool between two checkpointed epochs, in basis points
+    ///
+    /// Measured in reward tokens per staked LP token (unpriced), annualized.
+    /// Fails with `PoolMigrated` if the checkpoints straddle a migration.
+    pub fn historical_apr_bps(
+        env: Env,
+        pool_id: u32,
+        from_epoch: u64,
+        to_epoch: u64,
+    ) -> Result<i128, AstroSwapError> {
+        if from_epoch >= to_epoch {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+        let from = get_checkpoint(&env, pool_id, from_epoch)
+            .ok_or(AstroSwapError::CheckpointNotFound)?;
+        let to =
+            get_checkpoint(&env, pool_id, to_epoch).ok_or(AstroSwapError::CheckpointNotFound)?;
+
+        if from.generation != to.generation {
+            return Err(AstroSwapError::PoolMigrated);
+        }
+
+        let elapsed = i128::from(to.timestamp.saturating_sub(from.timestamp));
+        if elapsed == 0 {
+            return Ok(0);
+        }
+        let precision = match get_migration(&env, pool_id, to.generation) {
+            Some(record) => record.reward_precision,
+            None => Self::reward_precision(&env, pool_id),
+        };
+        let reward_per_share = safe_sub(to.acc_reward_per_share, from.acc_reward_per_share)?;
+        mul_div_down(
+            reward_per_share,
+            safe_mul(i128::from(BPS_DENOMINATOR), SECONDS_PER_YEAR)?,
+            safe_mul(precision, elapsed)?,
+        )
+    }
+
+    /// USD value of a pool's staked LP tokens, 7 decimals
+    ///
+    /// `lp_price` is the USD price of one LP token, 7 decimals.
+    pub fn pool_tvl(env: Env, pool_id: u32, lp_price: i128) -> Result<i128, AstroSwapError> {
+        let pool = get_pool(&env, pool_id).ok_or(AstroSwapError::StakingPoolNotFound)?;
+        Self::tvl(&pool, lp_price)
+    }
+
+    /// Current APR of a pool in basis points at the given USD prices (7 decimals)
+    ///
+    /// Annualizes the pool's emission rate over its staked value. Zero when
+    /// nothing is staked or the pool has ended.
+    pub fn pool_apr(
+        env: Env,
+        pool_id: u32,
+        lp_price: i128,
+        reward_price: i128,
+    ) -> Result<i128, AstroSwapError> {
+        let pool = get_pool(&env, pool_id).ok_or(AstroSwapError::StakingPoolNotFound)?;
+        Self::apr(&env, &pool, lp_price, reward_price)
+    }
+
+    /// `pool_tvl` with the LP token priced by `oracle`
+    pub fn pool_tvl_from_oracle(
+        env: Env,
+        pool_id: u32,
+        oracle: Address,
+    ) -> Result<i128, AstroSwapError> {
+        let pool = get_pool(&env, pool_id).ok_or(AstroSwapError::StakingPoolNotFound)?;
+        let lp_price = OracleClient::new(&env, &oracle)
+            .try_get_price_scaled(&pool.lp_token, PRICE_DECIMALS)?;
+        Self::tvl(&pool, lp_price)
+    }
+
+    /// `pool_apr` with the LP and reward tokens priced by `oracle`
+    pub fn pool_apr_from_oracle(
+        env: Env,
+        pool_id: u32,
+        oracle: Address,
+    ) -> Result<i128, AstroSwapError> {
+        let pool = get_pool(&env, pool_id).ok_or(AstroSwapError::StakingPoolNotFound)?;
+        let oracle = OracleClient::new(&env, &oracle);
+        let lp_price = oracle.try_get_price_scaled(&pool.lp_token, PRICE_DECIMALS)?;
+        let reward_price = oracle.try_get_price_scaled(&pool.reward_token, PRICE_DECIMALS)?;
+        Self::apr(&env, &pool, lp_price, reward_price)
+    }
+
+    /// Get a pool's deposit fee and withdrawal cooldown
+    pub fn pool_options(env: Env, pool_id: u32) -> Result<PoolOptions, AstroSwapError> {
+        get_pool(&env, pool_id).ok_or(AstroSwapError::StakingPoolNotFound)?;
+        Ok(get_pool_options(&env, pool_id))
+    }
+
+    /// Get the time from which a pool's unclaimed rewards expire, if set
+    pub fn claim_deadline(env: Env, pool_id: u32) -> Option<u64> {
+        get_claim_deadline(&env, pool_id)
+    }
+
+    /// Get the rewards a pool emitted and paid in its current generation
+    ///
+    /// Accrued up to the pool's last update.
+    pub fn reward_ledger(env: Env, pool_id: u32) -> RewardLedger {
+        get_reward_ledger(&env, pool_id)
+    }
+
+    /// Get the rewards funded for a pool with `fund_pool` and not yet paid,
+    /// settled or swept
+    pub fn pool_funding(env: Env, pool_id: u32) -> i128 {
+        get_pool_funding(&env, pool_id)
+    }
+
+    /// Time from which `user` can unstake from a pool (0 if not in cooldown)
+    pub fn cooldown_end(env: Env, user: Address, pool_id: u32) -> u64 {
+        Self::cooldown_end_time(&env, &user, pool_id)
+    }
+
+    /// Get a retroactive drop
+    pub fn retro_drop(env: Env, drop_id: u32) -> Option<RetroDrop> {
+        get_retro_drop(&env, drop_id)
+    }
+
+    /// Get the number of published retroactive drops
+    pub fn retro_drop_count(env: Env) -> u32 {
+        get_retro_drop_count(&env)
+    }
+
+    /// Check if a user claimed their reward from a retroactive drop
+    pub fn retro_claimed(env: Env, drop_id: u32, user: Address) -> bool {
+        is_retro_claimed(&env, drop_id, &user)
+    }
+
+    /// Get the funds of `reward_token` held for open retroactive drops
+    pub fn retro_reserved(env: Env, reward_token: Address) -> i128 {
+        get_retro_reserved(&env, &reward_token)
+    }
+
     /// Get pool count
     pub fn pool_count(env: Env) -> u32 {
         extend_instance_ttl(&env);
@@ -476,11 +1430,23 @@ impl AstroSwapStaking {
         get_reward_token(&env)
     }
 
+    /// Get the treasury receiving expired rewards
+    pub fn treasury(env: Env) -> Option<Address> {
+        extend_instance_ttl(&env);
+        get_treasury(&env)
+    }
+
     /// Check if contract is paused
     pub fn is_paused(env: Env) -> bool {
         is_paused(&env)
     }
 
+    /// Check if an address may create pools without being admin
+    pub fn is_pool_creator(env: Env, creator: Address) -> bool {
+        extend_instance_ttl(&env);
+        is_pool_creator(&env, &creator)
+    }
+
     // ==================== Internal Functions ====================
 
     /// Verify caller is admin
@@ -492,32 +1458,251 @@ impl AstroSwapStaking {
         Ok(())
     }
 
-    /// Verify contract is not paused
-    fn require_not_paused(env: &Env) -> Result<(), AstroSwapError> {
-        if is_paused(env) {
-            return Err(AstroSwapError::ContractPaused);
+    /// Verify caller is admin or an allowlisted pool creator
+    fn require_pool_creator(env: &Env, caller: &Address) -> Result<(), AstroSwapError> {
+        caller.require_auth();
+        if *caller != get_admin(env) && !is_pool_creator(env, caller) {
+            return Err(AstroSwapError::Unauthorized);
         }
         Ok(())
     }
 
+    /// Reject an empty or overlong pool name
+    fn require_valid_metadata(metadata: &PoolMetadata) -> Result<(), AstroSwapError> {
+        if metadata.name.is_empty() || metadata.name.len() > MAX_POOL_NAME_LENGTH {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+        Ok(())
+    }
+
+    /// Pay a user's pending rewards to them (shared by `claim_rewards` and
+    /// `claim_rewards_for`)
+    fn claim_rewards_internal(
+        env: &Env,
+        user: &Address,
+        pool_id: u32,
+    ) -> Result<i128, AstroSwapError> {
+        with_lock(env, &DataKey::Locked, || {
+            let mut pool = get_pool(env, pool_id).ok_or(AstroSwapError::StakingPoolNotFound)?;
+            if Self::claim_period_expired(env, pool_id) {
+                return Err(AstroSwapError::ClaimPeriodExpired);
+            }
+            let mut user_stake =
+                get_user_stake(env, user, pool_id).ok_or(AstroSwapError::StakeNotFound)?;
+            Self::settle_migrations(env, user, pool_id, &mut user_stake)?;
+
+            // Update pool rewards
+            Self::update_pool(env, &mut pool)?;
+
+            // Calculate pending rewards
+            let pending = Self::calculate_pending_rewards(env, &pool, &user_stake)?;
+
+            if pending == 0 {
+                return Err(AstroSwapError::NoRewardsAvailable);
+            }
+
+            // Apply multiplier (multiplier is u32, safely fits in i128)
+            let multiplier = Self::get_current_multiplier(env, &user_stake);
+            let boosted_reward = safe_div(
+                safe_mul(pending, i128::from(multiplier))?,
+                i128::from(BPS_DENOMINATOR),
+            )?;
+
+            // Transfer rewards
+            Self::transfer_rewards(env, &pool.reward_token, user, boosted_reward)?;
+            Self::record_paid(env, pool_id, pending, boosted_reward)?;
+
+            // Update reward debt
+            user_stake.reward_debt = safe_div(
+                safe_mul(user_stake.amount, pool.acc_reward_per_share)?,
+                Self::reward_precision(env, pool_id),
+            )?;
+
+            // Save state
+            set_pool(env, pool_id, &pool);
+            set_user_stake(env, user, pool_id, &user_stake);
+
+            // Emit event
+            emit_claim(env, user, pool_id, boosted_reward);
+
+            extend_instance_ttl(env);
+            extend_pool_ttl(env, pool_id);
+            extend_user_stake_ttl(env, user, pool_id);
+
+            Ok(boosted_reward)
+        })
+    }
+
+    /// Verify `claimer` is authorized and approved for the user's stake
+    fn require_claimer(
+        env: &Env,
+        claimer: &Address,
+        user: &Address,
+        pool_id: u32,
+    ) -> Result<(), AstroSwapError> {
+        claimer.require_auth();
+        if get_claimer(env, user, pool_id).as_ref() != Some(claimer) {
+            return Err(AstroSwapError::Unauthorized);
+        }
+        Ok(())
+    }
+
+    /// Whether a pool's claim deadline has passed
+    fn claim_period_expired(env: &Env, pool_id: u32) -> bool {
+        get_claim_deadline(env, pool_id)
+            .is_some_and(|deadline| env.ledger().timestamp() >= deadline)
+    }
+
+    /// Record rewards paid out of a pool's current generation: `pending`
+    /// before the multiplier, `paid_out` the amount that left the pool
+    fn record_paid(
+        env: &Env,
+        pool_id: u32,
+        pending: i128,
+        paid_out: i128,
+    ) -> Result<(), AstroSwapError> {
+        let mut ledger = get_reward_ledger(env, pool_id);
+        ledger.paid = safe_add(ledger.paid, pending)?;
+        set_reward_ledger(env, pool_id, &ledger);
+        Self::spend_pool_funding(env, pool_id, paid_out);
+        Ok(())
+    }
+
+    /// Take `amount` paid or settled by a pool off its funding; pools paid
+    /// from `fund_rewards` run past their own funding, which stops at zero
+    fn spend_pool_funding(env: &Env, pool_id: u32, amount: i128) {
+        let funding = get_pool_funding(env, pool_id);
+        if funding > 0 {
+            set_pool_funding(env, pool_id, funding.saturating_sub(amount).max(0));
+        }
+    }
+
+    /// End of the withdrawal cooldown started by the user's latest stake
+    fn cooldown_end_time(env: &Env, user: &Address, pool_id: u32) -> u64 {
+        let cooldown_seconds = get_pool_options(env, pool_id).cooldown_seconds;
+        match get_last_deposit(env, user, pool_id) {
+            Some(last_deposit) if cooldown_seconds > 0 => last_deposit + cooldown_seconds,
+            _ => 0,
+        }
+    }
+
+    /// USD value (7 decimals) of a pool's stake at `lp_price`
+    fn tvl(pool: &StakingPool, lp_price: i128) -> Result<i128, AstroSwapError> {
+        if lp_price <= 0 {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+        mul_div_down(pool.total_staked, lp_price, PRICE_PRECISION)
+    }
+
+    /// Annualized emissions over staked value, in basis points
+    fn apr(
+        env: &Env,
+        pool: &StakingPool,
+        lp_price: i128,
+        reward_price: i128,
+    ) -> Result<i128, AstroSwapError> {
+        if lp_price <= 0 || reward_price <= 0 {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+        if pool.total_staked == 0 || env.ledger().timestamp() >= pool.end_time {
+            return Ok(0);
+        }
+
+        let yearly_rewards = safe_mul(pool.reward_per_second, SECONDS_PER_YEAR)?;
+        mul_div_down(
+            safe_mul(yearly_rewards, reward_price)?,
+            i128::from(BPS_DENOMINATOR),
+            safe_mul(pool.total_staked, lp_price)?,
+        )
+    }
+
     /// Update pool's accumulated rewards
+    /// The first update of each epoch also writes the epoch's checkpoint
     fn update_pool(env: &Env, pool: &mut StakingPool) -> Result<(), AstroSwapError> {
         Self::update_pool_internal(env, pool)?;
         set_pool(env, pool.pool_id, pool);
+        let epoch = env.ledger().timestamp() / CHECKPOINT_EPOCH_SECONDS;
+        if !has_checkpoint(env, pool.pool_id, epoch) {
+            Self::write_checkpoint(env, pool);
+        }
         Ok(())
     }
 
-    /// Internal pool update (doesn't save)
+    /// Snapshot an updated pool for the current epoch, returning the epoch
+    fn write_checkpoint(env: &Env, pool: &StakingPool) -> u64 {
+        let epoch = env.ledger().timestamp() / CHECKPOINT_EPOCH_SECONDS;
+        set_checkpoint(
+            env,
+            pool.pool_id,
+            &PoolCheckpoint {
+                epoch,
+                generation: get_pool_generation(env, pool.pool_id),
+                timestamp: pool.last_update_time,
+                acc_reward_per_share: pool.acc_reward_per_share,
+                total_staked: pool.total_staked,
+                reward_per_second: pool.reward_per_second,
+            },
+        );
+        epoch
+    }
+
+    /// Pending rewards of a stake at `timestamp`, multiplier applied
+    fn rewards_at(
+        env: &Env,
+        user: &Address,
+        pool_id: u32,
+        timestamp: u64,
+    ) -> Result<i128, AstroSwapError> {
+        let pool = get_pool(env, pool_id).ok_or(AstroSwapError::StakingPoolNotFound)?;
+        let mut user_stake =
+            get_user_stake(env, user, pool_id).ok_or(AstroSwapError::StakeNotFound)?;
+
+        // Rewards of closed generations go to the settled ledger; the debt
+        // of an unsettled stake is zero against the re-based accumulator
+        if get_user_generation(env, user, pool_id) < get_pool_generation(env, pool_id) {
+            user_stake.reward_debt = 0;
+        }
+
+        // Calculate pending with the accumulator as of `timestamp`
+        let mut simulated_pool = pool.clone();
+        Self::advance_pool(env, &mut simulated_pool, timestamp)?;
+
+        let pending = Self::calculate_pending_rewards(env, &simulated_pool, &user_stake)?;
+
+        // Apply multiplier (multiplier is u32, safely fits in i128)
+        let multiplier = Self::multiplier_at(&user_stake, timestamp);
+        safe_div(
+            safe_mul(pending, i128::from(multiplier))?,
+            i128::from(BPS_DENOMINATOR),
+        )
+    }
+
+    /// Internal pool update (doesn't save the pool, records the emission in
+    /// its reward ledger)
     fn update_pool_internal(env: &Env, pool: &mut StakingPool) -> Result<(), AstroSwapError> {
-        let current_time = env.ledger().timestamp();
+        let emitted = Self::advance_pool(env, pool, env.ledger().timestamp())?;
+        if emitted > 0 {
+            let mut ledger = get_reward_ledger(env, pool.pool_id);
+            ledger.accrued = safe_add(ledger.accrued, emitted)?;
+            set_reward_ledger(env, pool.pool_id, &ledger);
+        }
+        Ok(())
+    }
 
+    /// Accrue a pool's rewards up to `current_time` (doesn't save),
+    /// returning the rewards emitted
+    fn advance_pool(
+        env: &Env,
+        pool: &mut StakingPool,
+        current_time: u64,
+    ) -> Result<i128, AstroSwapError> {
         if current_time <= pool.last_update_time {
-            return Ok(());
+            return Ok(0);
         }
 
         if pool.total_staked == 0 {
             pool.last_update_time = current_time;
-            return Ok(());
+            return Ok(0);
         }
 
         // Calculate time elapsed (capped at end_time)
@@ -528,47 +1713,158 @@ impl AstroSwapStaking {
         };
 
         if effective_time <= pool.last_update_time {
-            return Ok(());
+            return Ok(0);
         }
 
         let time_elapsed = effective_time - pool.last_update_time;
 
         // Calculate rewards (time_elapsed is u64, safely fits in i128)
         let reward = safe_mul(pool.reward_per_second, i128::from(time_elapsed))?;
-        let reward_per_share_increase =
-            safe_div(safe_mul(reward, REWARD_PRECISION)?, pool.total_staked)?;
+        let reward_per_share_increase = safe_div(
+            safe_mul(reward, Self::reward_precision(env, pool.pool_id))?,
+            pool.total_staked,
+        )?;
 
         pool.acc_reward_per_share = safe_add(pool.acc_reward_per_share, reward_per_share_increase)?;
         pool.last_update_time = current_time;
 
-        Ok(())
+        Ok(reward)
     }
 
     /// Calculate pending rewards for a user
     fn calculate_pending_rewards(
+        env: &Env,
         pool: &StakingPool,
         user_stake: &UserStake,
     ) -> Result<i128, AstroSwapError> {
         let accumulated = safe_div(
             safe_mul(user_stake.amount, pool.acc_reward_per_share)?,
-            REWARD_PRECISION,
+            Self::reward_precision(env, pool.pool_id),
         )?;
 
         safe_sub(accumulated, user_stake.reward_debt)
     }
 
+    /// Scale of a pool's `acc_reward_per_share`
+    fn reward_precision(env: &Env, pool_id: u32) -> i128 {
+        get_reward_precision(env, pool_id, REWARD_PRECISION)
+    }
+
+    /// Credit a stake's rewards from closed pool generations to the user's
+    /// claimable ledger and zero its reward debt for the current accumulator
+    ///
+    /// The caller saves `user_stake`. Returns the total credited.
+    fn settle_migrations(
+        env: &Env,
+        user: &Address,
+        pool_id: u32,
+        user_stake: &mut UserStake,
+    ) -> Result<i128, AstroSwapError> {
+        let pool_generation = get_pool_generation(env, pool_id);
+        let mut generation = get_user_generation(env, user, pool_id);
+        if generation >= pool_generation {
+            return Ok(0);
+        }
+
+        let multiplier = Self::get_current_multiplier(env, user_stake);
+        let mut total = 0;
+        while generation < pool_generation {
+            let record = get_migration(env, pool_id, generation)
+                .ok_or(AstroSwapError::StakingPoolNotFound)?;
+            let accumulated = safe_div(
+                safe_mul(user_stake.amount, record.acc_reward_per_share)?,
+                record.reward_precision,
+            )?;
+            let pending = safe_sub(accumulated, user_stake.reward_debt)?;
+
+            if pending > 0 {
+                let amount = safe_div(
+                    safe_mul(pending, i128::from(multiplier))?,
+                    i128::from(BPS_DENOMINATOR),
+                )?;
+                let settled = get_settled_rewards(env, user, &record.reward_token);
+                set_settled_rewards(env, user, &record.reward_token, safe_add(settled, amount)?);
+                Self::spend_pool_funding(env, pool_id, amount);
+                total = safe_add(total, amount)?;
+
+                RewardsSettled {
+                    user: user.clone(),
+                    pool_id,
+                    generation,
+                    reward_token: record.reward_token,
+                    amount,
+                }
+                .publish(env);
+            }
+
+            user_stake.reward_debt = 0;
+            generation += 1;
+        }
+
+        set_user_generation(env, user, pool_id, pool_generation);
+        Ok(total)
+    }
+
     /// Get current multiplier based on stake duration
     fn get_current_multiplier(env: &Env, user_stake: &UserStake) -> u32 {
+        Self::multiplier_at(user_stake, env.ledger().timestamp())
+    }
+
+    /// Multiplier a stake has reached at `timestamp`
+    fn multiplier_at(user_stake: &UserStake, timestamp: u64) -> u32 {
         if user_stake.stake_time == 0 {
             return BPS_DENOMINATOR;
         }
 
-        let current_time = env.ledger().timestamp();
-        let stake_duration = current_time.saturating_sub(user_stake.stake_time);
-
+        let stake_duration = timestamp.saturating_sub(user_stake.stake_time);
         calculate_staking_multiplier(stake_duration)
     }
 
+    /// Rewards a pool emits at `reward_per_second` from `start_time` to
+    /// `end_time`, rejecting negative rates and rates above
+    /// `MAX_POOL_REWARD_PER_SECOND`
+    fn total_emission(
+        reward_per_second: i128,
+        start_time: u64,
+        end_time: u64,
+    ) -> Result<i128, AstroSwapError> {
+        if reward_per_second < 0 {
+            return Err(AstroSwapError::InvalidArgument);
+        }
+        if reward_per_second > MAX_POOL_REWARD_PER_SECOND {
+            return Err(AstroSwapError::EmissionRateTooHigh);
+        }
+        safe_mul(reward_per_second, i128::from(end_time - start_time))
+    }
+
+    /// Balance of `reward_token` rewards can be paid from: neither reserved
+    /// for retroactive drops nor staked in a pool staking that token
+    fn available_rewards(env: &Env, reward_token: &Address) -> i128 {
+        let balance =
+            token::Client::new(env, reward_token).balance(&env.current_contract_address());
+        balance
+            .saturating_sub(get_retro_reserved(env, reward_token))
+            .saturating_sub(get_token_staked(env, reward_token))
+    }
+
+    /// Add `delta` to the principal of `lp_token` staked across all pools
+    fn track_staked(env: &Env, lp_token: &Address, delta: i128) -> Result<(), AstroSwapError> {
+        let staked = safe_add(get_token_staked(env, lp_token), delta)?;
+        set_token_staked(env, lp_token, staked.max(0));
+        Ok(())
+    }
+
+    /// Release `amount` of a retroactive drop's escrow before paying it out
+    fn release_retro_reserve(
+        env: &Env,
+        reward_token: &Address,
+        amount: i128,
+    ) -> Result<(), AstroSwapError> {
+        let reserved = get_retro_reserved(env, reward_token);
+        set_retro_reserved(env, reward_token, safe_sub(reserved, amount)?);
+        Ok(())
+    }
+
     /// Transfer rewards to user
     fn transfer_rewards(
         env: &Env,
@@ -581,10 +1877,8 @@ impl AstroSwapStaking {
         }
 
         let token_client = token::Client::new(env, reward_token);
-        let contract_balance = token_client.balance(&env.current_contract_address());
-
-        if contract_balance < amount {
-            return Err(AstroSwapError::InsufficientBalance);
+        if Self::available_rewards(env, reward_token) < amount {
+            return Err(AstroSwapError::RewardsUnfunded);
         }
 
         token_client.transfer(&env.current_contract_address(), to, &amount);
@@ -1,6 +1,11 @@
 #![no_std]
 
 mod contract;
+mod retro;
 mod storage;
 
-pub use contract::{AstroSwapStaking, AstroSwapStakingClient};
+pub use contract::{AstroSwapStaking, AstroSwapStakingClient, MIN_CLAIM_GRACE_SECONDS};
+pub use retro::{
+    retro_leaf, RetroDropClosed, RetroDropPublished, RetroRewardClaimed, MAX_PROOF_LENGTH,
+};
+pub use storage::{MigrationParams, MigrationRecord, RetroDrop, RewardLedger};
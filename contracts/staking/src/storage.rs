@@ -1,5 +1,5 @@
 use astroswap_shared::{StakingPool, UserStake};
-use soroban_sdk::{contracttype, Address, Env};
+use soroban_sdk::{contracttype, Address, BytesN, Env};
 
 /// Storage keys for the staking contract
 #[contracttype]
@@ -12,11 +12,104 @@ pub enum DataKey {
     Locked, // Reentrancy guard
     PoolCount,
     RewardToken,
+    Treasury, // Receives rewards expired past their pool's claim deadline
+    RetroDropCount,
 
     // Persistent storage
     Pool(u32),
     UserStake(Address, u32),
     UserRewardDebt(Address, u32),
+    PoolCheckpoint(u32, u64), // (pool_id, epoch)
+    PoolOptions(u32),
+    LastDeposit(Address, u32), // (user, pool_id) -> timestamp of the latest stake
+    RewardPrecision(u32),      // Scale of acc_reward_per_share, set by migrations
+    PoolGeneration(u32),       // Number of migrations a pool went through
+    PoolMigration(u32, u32),   // (pool_id, generation) -> settled accounting
+    UserGeneration(Address, u32), // (user, pool_id) -> generation settled to
+    SettledRewards(Address, Address), // (user, reward_token) -> claimable amount
+    Claimer(Address, u32),     // (user, pool_id) -> keeper allowed to claim/compound
+    PoolCreator(Address),      // Non-admin allowed to create pools (e.g. the bridge)
+    ClaimDeadline(u32),        // Time after which a pool's unclaimed rewards expire
+    RewardLedger(u32),         // Rewards a pool emitted and paid in its current generation
+    RetroDrop(u32),            // Published merkle root of retroactive rewards
+    RetroClaimed(u32, Address), // (drop_id, user) -> retroactive reward claimed
+    RetroReserved(Address),    // reward_token -> escrow of open retroactive drops
+    PoolFunding(u32),          // Rewards funded for a pool and not yet paid, settled or swept
+    TokenStaked(Address),      // lp_token -> principal staked across all pools
+}
+
+/// Optional per-pool deposit fee and withdrawal cooldown
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PoolOptions {
+    /// Share of each stake sent to the admin (basis points)
+    pub deposit_fee_bps: u32,
+    /// Time after a user's latest stake before they can unstake
+    pub cooldown_seconds: u64,
+}
+
+/// Rewards a pool emitted and paid out in its current generation
+///
+/// Amounts are before the duration multiplier. What has been emitted but not
+/// paid is what the pool's stakers can still claim.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RewardLedger {
+    pub accrued: i128,
+    pub paid: i128,
+}
+
+/// New reward parameters applied by a pool migration
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MigrationParams {
+    pub reward_token: Address,
+    pub reward_per_second: i128,
+    pub end_time: u64,
+    /// Scale of `acc_reward_per_share` from the migration on
+    pub reward_precision: i128,
+}
+
+/// Pool accounting settled by a migration, kept until every staker is settled
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MigrationRecord {
+    /// Generation the record closes (the pool's generation before the migration)
+    pub generation: u32,
+    pub timestamp: u64,
+    /// Reward token owed for the closed generation
+    pub reward_token: Address,
+    /// Final accumulator of the closed generation
+    pub acc_reward_per_share: i128,
+    pub reward_precision: i128,
+}
+
+/// Retroactive rewards published as a merkle root of (user, amount) leaves
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetroDrop {
+    pub merkle_root: BytesN<32>,
+    pub reward_token: Address,
+    /// Funded by the admin on publication; claims never pay out more
+    pub total_amount: i128,
+    pub claimed: i128,
+    pub published_at: u64,
+    /// Closed by the admin; the unclaimed remainder was refunded
+    pub closed: bool,
+}
+
+/// Snapshot of a pool's reward accounting at the start of an epoch
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolCheckpoint {
+    pub epoch: u64,
+    /// Pool generation (number of migrations) the accumulator belongs to
+    pub generation: u32,
+    /// Pool `last_update_time` when the snapshot was taken
+    pub timestamp: u64,
+    pub acc_reward_per_share: i128,
+    pub total_staked: i128,
+    pub reward_per_second: i128,
 }
 
 /// Check if the contract is initialized
@@ -58,19 +151,6 @@ pub fn set_paused(env: &Env, paused: bool) {
     env.storage().instance().set(&DataKey::Paused, &paused);
 }
 
-/// Check if contract is locked (reentrancy guard)
-pub fn is_locked(env: &Env) -> bool {
-    env.storage()
-        .instance()
-        .get::<DataKey, bool>(&DataKey::Locked)
-        .unwrap_or(false)
-}
-
-/// Set lock state (reentrancy guard)
-pub fn set_locked(env: &Env, locked: bool) {
-    env.storage().instance().set(&DataKey::Locked, &locked);
-}
-
 /// Get the total number of pools
 pub fn get_pool_count(env: &Env) -> u32 {
     env.storage()
@@ -98,6 +178,18 @@ pub fn set_reward_token(env: &Env, token: &Address) {
     env.storage().instance().set(&DataKey::RewardToken, token);
 }
 
+/// Get the treasury receiving expired rewards
+pub fn get_treasury(env: &Env) -> Option<Address> {
+    env.storage()
+        .instance()
+        .get::<DataKey, Address>(&DataKey::Treasury)
+}
+
+/// Set the treasury receiving expired rewards
+pub fn set_treasury(env: &Env, treasury: &Address) {
+    env.storage().instance().set(&DataKey::Treasury, treasury);
+}
+
 // ==================== Pool Storage ====================
 
 /// Get a staking pool by ID
@@ -120,6 +212,351 @@ pub fn pool_exists(env: &Env, pool_id: u32) -> bool {
     env.storage().persistent().has(&DataKey::Pool(pool_id))
 }
 
+/// Get a pool's options; pools created without options have none
+pub fn get_pool_options(env: &Env, pool_id: u32) -> PoolOptions {
+    env.storage()
+        .persistent()
+        .get::<DataKey, PoolOptions>(&DataKey::PoolOptions(pool_id))
+        .unwrap_or_default()
+}
+
+/// Set a pool's options
+pub fn set_pool_options(env: &Env, pool_id: u32, options: &PoolOptions) {
+    let key = DataKey::PoolOptions(pool_id);
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, options);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+/// Get a pool's reward precision, `default` until a migration sets one
+pub fn get_reward_precision(env: &Env, pool_id: u32, default: i128) -> i128 {
+    env.storage()
+        .persistent()
+        .get::<DataKey, i128>(&DataKey::RewardPrecision(pool_id))
+        .unwrap_or(default)
+}
+
+/// Set a pool's reward precision
+pub fn set_reward_precision(env: &Env, pool_id: u32, precision: i128) {
+    let key = DataKey::RewardPrecision(pool_id);
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, &precision);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+// ==================== Retroactive Drop Storage ====================
+
+/// Get the number of published retroactive drops
+pub fn get_retro_drop_count(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get::<DataKey, u32>(&DataKey::RetroDropCount)
+        .unwrap_or(0)
+}
+
+/// Increment the retroactive drop count and return the new count
+pub fn increment_retro_drop_count(env: &Env) -> u32 {
+    let count = get_retro_drop_count(env) + 1;
+    env.storage()
+        .instance()
+        .set(&DataKey::RetroDropCount, &count);
+    count
+}
+
+/// Get a retroactive drop by ID
+pub fn get_retro_drop(env: &Env, drop_id: u32) -> Option<RetroDrop> {
+    env.storage()
+        .persistent()
+        .get::<DataKey, RetroDrop>(&DataKey::RetroDrop(drop_id))
+}
+
+/// Set a retroactive drop
+pub fn set_retro_drop(env: &Env, drop_id: u32, drop: &RetroDrop) {
+    let key = DataKey::RetroDrop(drop_id);
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, drop);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+/// Check if a user claimed their reward from a retroactive drop
+pub fn is_retro_claimed(env: &Env, drop_id: u32, user: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::RetroClaimed(drop_id, user.clone()))
+}
+
+/// Record a user's claim from a retroactive drop
+pub fn set_retro_claimed(env: &Env, drop_id: u32, user: &Address) {
+    let key = DataKey::RetroClaimed(drop_id, user.clone());
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, &true);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+/// Get the funds of `reward_token` held for unclaimed retroactive rewards
+pub fn get_retro_reserved(env: &Env, reward_token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get::<DataKey, i128>(&DataKey::RetroReserved(reward_token.clone()))
+        .unwrap_or(0)
+}
+
+/// Set the funds of `reward_token` held for unclaimed retroactive rewards
+pub fn set_retro_reserved(env: &Env, reward_token: &Address, amount: i128) {
+    let key = DataKey::RetroReserved(reward_token.clone());
+    if amount == 0 {
+        env.storage().persistent().remove(&key);
+        return;
+    }
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, &amount);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+/// Get the principal of `lp_token` staked across all pools
+pub fn get_token_staked(env: &Env, lp_token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get::<DataKey, i128>(&DataKey::TokenStaked(lp_token.clone()))
+        .unwrap_or(0)
+}
+
+/// Set the principal of `lp_token` staked across all pools
+pub fn set_token_staked(env: &Env, lp_token: &Address, amount: i128) {
+    let key = DataKey::TokenStaked(lp_token.clone());
+    if amount == 0 {
+        env.storage().persistent().remove(&key);
+        return;
+    }
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, &amount);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+// ==================== Claim Deadline Storage ====================
+
+/// Get a pool's claim deadline, if it has one
+pub fn get_claim_deadline(env: &Env, pool_id: u32) -> Option<u64> {
+    env.storage()
+        .persistent()
+        .get::<DataKey, u64>(&DataKey::ClaimDeadline(pool_id))
+}
+
+/// Set a pool's claim deadline (0 removes it)
+pub fn set_claim_deadline(env: &Env, pool_id: u32, claim_deadline: u64) {
+    let key = DataKey::ClaimDeadline(pool_id);
+    if claim_deadline == 0 {
+        env.storage().persistent().remove(&key);
+        return;
+    }
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, &claim_deadline);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+/// Get the rewards a pool emitted and paid in its current generation
+pub fn get_reward_ledger(env: &Env, pool_id: u32) -> RewardLedger {
+    env.storage()
+        .persistent()
+        .get::<DataKey, RewardLedger>(&DataKey::RewardLedger(pool_id))
+        .unwrap_or_default()
+}
+
+/// Set the rewards a pool emitted and paid in its current generation
+pub fn set_reward_ledger(env: &Env, pool_id: u32, ledger: &RewardLedger) {
+    let key = DataKey::RewardLedger(pool_id);
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, ledger);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+/// Get the rewards funded for a pool and not yet paid, settled or swept
+pub fn get_pool_funding(env: &Env, pool_id: u32) -> i128 {
+    env.storage()
+        .persistent()
+        .get::<DataKey, i128>(&DataKey::PoolFunding(pool_id))
+        .unwrap_or(0)
+}
+
+/// Set the rewards funded for a pool and not yet paid, settled or swept
+pub fn set_pool_funding(env: &Env, pool_id: u32, amount: i128) {
+    let key = DataKey::PoolFunding(pool_id);
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, &amount);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+// ==================== Migration Storage ====================
+
+/// Get the number of migrations a pool went through
+pub fn get_pool_generation(env: &Env, pool_id: u32) -> u32 {
+    env.storage()
+        .persistent()
+        .get::<DataKey, u32>(&DataKey::PoolGeneration(pool_id))
+        .unwrap_or(0)
+}
+
+/// Set a pool's generation
+pub fn set_pool_generation(env: &Env, pool_id: u32, generation: u32) {
+    let key = DataKey::PoolGeneration(pool_id);
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, &generation);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+/// Get the accounting a migration settled for a pool generation
+pub fn get_migration(env: &Env, pool_id: u32, generation: u32) -> Option<MigrationRecord> {
+    env.storage()
+        .persistent()
+        .get::<DataKey, MigrationRecord>(&DataKey::PoolMigration(pool_id, generation))
+}
+
+/// Record the accounting a migration settled
+pub fn set_migration(env: &Env, pool_id: u32, record: &MigrationRecord) {
+    let key = DataKey::PoolMigration(pool_id, record.generation);
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, record);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+/// Get the pool generation a user's stake is settled to
+pub fn get_user_generation(env: &Env, user: &Address, pool_id: u32) -> u32 {
+    env.storage()
+        .persistent()
+        .get::<DataKey, u32>(&DataKey::UserGeneration(user.clone(), pool_id))
+        .unwrap_or(0)
+}
+
+/// Set the pool generation a user's stake is settled to
+pub fn set_user_generation(env: &Env, user: &Address, pool_id: u32, generation: u32) {
+    let key = DataKey::UserGeneration(user.clone(), pool_id);
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, &generation);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+/// Get a user's settled, unclaimed rewards in a reward token
+pub fn get_settled_rewards(env: &Env, user: &Address, reward_token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get::<DataKey, i128>(&DataKey::SettledRewards(user.clone(), reward_token.clone()))
+        .unwrap_or(0)
+}
+
+/// Set a user's settled, unclaimed rewards in a reward token
+pub fn set_settled_rewards(env: &Env, user: &Address, reward_token: &Address, amount: i128) {
+    let key = DataKey::SettledRewards(user.clone(), reward_token.clone());
+    if amount == 0 {
+        env.storage().persistent().remove(&key);
+        return;
+    }
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, &amount);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+// ==================== Claimer Storage ====================
+
+/// Get the claimer a user approved for a pool
+pub fn get_claimer(env: &Env, user: &Address, pool_id: u32) -> Option<Address> {
+    env.storage()
+        .persistent()
+        .get::<DataKey, Address>(&DataKey::Claimer(user.clone(), pool_id))
+}
+
+/// Approve a claimer for a user's stake in a pool
+pub fn set_claimer(env: &Env, user: &Address, pool_id: u32, claimer: &Address) {
+    let key = DataKey::Claimer(user.clone(), pool_id);
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, claimer);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+/// Revoke a user's claimer for a pool
+pub fn remove_claimer(env: &Env, user: &Address, pool_id: u32) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Claimer(user.clone(), pool_id));
+}
+
+// ==================== Pool Creator Storage ====================
+
+/// Check if an address may create pools without being admin
+pub fn is_pool_creator(env: &Env, creator: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .get::<DataKey, bool>(&DataKey::PoolCreator(creator.clone()))
+        .unwrap_or(false)
+}
+
+/// Allow or disallow an address to create pools
+pub fn set_pool_creator(env: &Env, creator: &Address, allowed: bool) {
+    let key = DataKey::PoolCreator(creator.clone());
+    if !allowed {
+        env.storage().persistent().remove(&key);
+        return;
+    }
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, &true);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
+// ==================== Checkpoint Storage ====================
+
+/// Get a pool's checkpoint for an epoch
+pub fn get_checkpoint(env: &Env, pool_id: u32, epoch: u64) -> Option<PoolCheckpoint> {
+    env.storage()
+        .persistent()
+        .get::<DataKey, PoolCheckpoint>(&DataKey::PoolCheckpoint(pool_id, epoch))
+}
+
+/// Check if a pool has a checkpoint for an epoch
+pub fn has_checkpoint(env: &Env, pool_id: u32, epoch: u64) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::PoolCheckpoint(pool_id, epoch))
+}
+
+/// Set a pool's checkpoint for an epoch
+pub fn set_checkpoint(env: &Env, pool_id: u32, checkpoint: &PoolCheckpoint) {
+    let key = DataKey::PoolCheckpoint(pool_id, checkpoint.epoch);
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, checkpoint);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
 // ==================== User Stake Storage ====================
 
 /// Get user's stake in a pool
@@ -136,6 +573,23 @@ pub fn set_user_stake(env: &Env, user: &Address, pool_id: u32, stake: &UserStake
         .set(&DataKey::UserStake(user.clone(), pool_id), stake);
 }
 
+/// Get the time of a user's latest stake in a pool
+pub fn get_last_deposit(env: &Env, user: &Address, pool_id: u32) -> Option<u64> {
+    env.storage()
+        .persistent()
+        .get::<DataKey, u64>(&DataKey::LastDeposit(user.clone(), pool_id))
+}
+
+/// Record the time of a user's latest stake in a pool
+pub fn set_last_deposit(env: &Env, user: &Address, pool_id: u32, timestamp: u64) {
+    let key = DataKey::LastDeposit(user.clone(), pool_id);
+    let max_ttl = env.storage().max_ttl();
+    env.storage().persistent().set(&key, &timestamp);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, max_ttl - 1000, max_ttl);
+}
+
 /// Remove user's stake
 #[allow(dead_code)]
 pub fn remove_user_stake(env: &Env, user: &Address, pool_id: u32) {
@@ -12,6 +12,7 @@
 //! - Multi-hop swaps across multiple pairs
 //! - Staking and reward distribution
 //! - Aggregator with multiple protocols
+//! - Swap reporting to the analytics contract
 //! - Bridge graduation from Astro-Shiba launchpad
 //!
 //! These tests ensure all contracts work together correctly and handle
@@ -24,8 +25,11 @@ pub mod pair_wasm {
     pub const WASM: &[u8] = include_bytes!("../../../target/wasm32v1-none/release/astroswap_pair.wasm");
 }
 
+mod mock_oracle;
 mod mock_token;
+mod scenario;
 mod test_aggregator;
+mod test_analytics;
 mod test_bridge;
 mod test_full_swap;
 mod test_multi_hop;
@@ -0,0 +1,42 @@
+//! Mock Oracle Contract for Integration Tests
+//!
+//! Serves fixed USD prices and TWAPs with the oracle's `get_price` and
+//! `get_twap` signatures; tokens without a price trap like a missing feed.
+
+use astroswap_shared::PriceData;
+use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env, String};
+
+#[contract]
+pub struct MockOracle;
+
+#[contractimpl]
+impl MockOracle {
+    /// Set a token's USD price (8 decimals) at the current ledger time
+    pub fn set_price(env: Env, token: Address, price: i128) {
+        let data = PriceData {
+            price,
+            timestamp: env.ledger().timestamp(),
+            decimals: 8,
+            source: String::from_str(&env, "mock"),
+        };
+        env.storage().instance().set(&token, &data);
+    }
+
+    pub fn get_price(env: Env, token: Address) -> PriceData {
+        env.storage().instance().get(&token).unwrap()
+    }
+
+    /// Set a token's TWAP, served for any window
+    pub fn set_twap(env: Env, token: Address, price: i128) {
+        env.storage()
+            .instance()
+            .set(&(symbol_short!("twap"), token), &price);
+    }
+
+    pub fn get_twap(env: Env, token: Address, _window: u64) -> i128 {
+        env.storage()
+            .instance()
+            .get(&(symbol_short!("twap"), token))
+            .unwrap()
+    }
+}
@@ -0,0 +1,271 @@
+//! Scenario Builder
+//!
+//! Chainable setup on top of `TestContext`, so a test states the market it
+//! needs instead of repeating deployment and funding boilerplate:
+//!
+//! ```ignore
+//! let ctx = Scenario::new()
+//!     .with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000))
+//!     .with_staking_pool(10_0000000, 86_400)
+//!     .graduate("GRAD");
+//! ```
+//!
+//! `Scenario` dereferences to `TestContext`, so clients, users and helpers
+//! stay reachable as `ctx.router`, `ctx.user1`, `ctx.advance_time(..)`.
+
+use crate::mock_oracle::{MockOracle, MockOracleClient};
+use crate::test_utils::TestContext;
+use astroswap_shared::{GraduatedToken, PairClient, TokenMetadata};
+use core::ops::Deref;
+use soroban_sdk::{token::StellarAssetClient, Address, String};
+use std::vec::Vec;
+
+/// Supply minted to the launchpad for each launched token
+pub const LAUNCH_SUPPLY: i128 = 1_000_000_0000000;
+
+/// Tokens paired at graduation (50% of the supply)
+pub const GRADUATION_TOKEN_AMOUNT: i128 = 500_000_0000000;
+
+/// XLM paired at graduation and sent to the launchpad for each launched token
+pub const GRADUATION_QUOTE_AMOUNT: i128 = 69_000_0000000;
+
+/// Tokens deployed by `TestContext`
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Token {
+    A,
+    B,
+    C,
+    Xlm,
+}
+
+/// Test context plus the pairs, pools, oracle and graduations set up so far
+pub struct Scenario {
+    ctx: TestContext,
+    pairs: Vec<(Address, Token, Token)>,
+    pools: Vec<u32>,
+    graduations: Vec<GraduatedToken>,
+    oracle: Option<Address>,
+}
+
+impl Scenario {
+    /// Deploy all contracts with funded users and no markets
+    pub fn new() -> Self {
+        Self {
+            ctx: TestContext::new(),
+            pairs: Vec::new(),
+            pools: Vec::new(),
+            graduations: Vec::new(),
+            oracle: None,
+        }
+    }
+
+    // ==================== Builder Steps ====================
+
+    /// Create a pair seeded by the admin with `liquidity` of (`token_a`, `token_b`)
+    pub fn with_pair(mut self, token_a: Token, token_b: Token, liquidity: (i128, i128)) -> Self {
+        let pair = self.ctx.setup_pair(
+            &self.token(token_a),
+            &self.token(token_b),
+            liquidity.0,
+            liquidity.1,
+        );
+        self.pairs.push((pair, token_a, token_b));
+        self
+    }
+
+    /// Create an XLM reward pool for the latest pair, starting now and
+    /// funded by the admin for its whole `duration`
+    pub fn with_staking_pool(mut self, reward_per_second: i128, duration: u64) -> Self {
+        let start_time = self.ctx.timestamp();
+        let pool_id = self.ctx.staking.create_pool(
+            &self.ctx.admin,
+            &self.pair(),
+            &reward_per_second,
+            &start_time,
+            &(start_time + duration),
+        );
+        self.ctx.staking.fund_pool(
+            &self.ctx.admin,
+            &pool_id,
+            &(reward_per_second * duration as i128),
+        );
+        self.pools.push(pool_id);
+        self
+    }
+
+    /// Register a mock oracle (see `oracle`)
+    pub fn with_oracle(mut self) -> Self {
+        self.oracle = Some(self.ctx.env.register(MockOracle, ()));
+        self
+    }
+
+    /// Launch a token and graduate it through the bridge at the default
+    /// amounts (`GRADUATION_TOKEN_AMOUNT` against `GRADUATION_QUOTE_AMOUNT`)
+    pub fn graduate(mut self, symbol: &str) -> Self {
+        let token = self.launch_token();
+        let launchpad = self.launchpad();
+        let graduation = self.ctx.bridge.graduate_token(
+            &launchpad,
+            &token,
+            &GRADUATION_TOKEN_AMOUNT,
+            &GRADUATION_QUOTE_AMOUNT,
+            &self.token_metadata(symbol),
+        );
+        self.graduations.push(graduation);
+        self
+    }
+
+    // ==================== Accessors ====================
+
+    /// Address of a `TestContext` token
+    pub fn token(&self, token: Token) -> Address {
+        match token {
+            Token::A => self.ctx.token_a_address.clone(),
+            Token::B => self.ctx.token_b_address.clone(),
+            Token::C => self.ctx.token_c_address.clone(),
+            Token::Xlm => self.ctx.xlm_address.clone(),
+        }
+    }
+
+    /// Latest pair
+    pub fn pair(&self) -> Address {
+        self.pairs.last().expect("no pair in scenario").0.clone()
+    }
+
+    /// Pair created by the `index`-th `with_pair`
+    pub fn pair_at(&self, index: usize) -> Address {
+        self.pairs[index].0.clone()
+    }
+
+    /// Latest staking pool
+    pub fn pool(&self) -> u32 {
+        *self.pools.last().expect("no staking pool in scenario")
+    }
+
+    /// Latest graduation
+    pub fn graduation(&self) -> GraduatedToken {
+        self.graduations
+            .last()
+            .expect("no graduation in scenario")
+            .clone()
+    }
+
+    /// Mock oracle registered by `with_oracle`
+    pub fn oracle(&self) -> MockOracleClient<'static> {
+        MockOracleClient::new(&self.ctx.env, &self.oracle_address())
+    }
+
+    /// Address of the mock oracle registered by `with_oracle`
+    pub fn oracle_address(&self) -> Address {
+        self.oracle.clone().expect("no oracle in scenario")
+    }
+
+    /// Launchpad allowed to graduate tokens through the bridge
+    pub fn launchpad(&self) -> Address {
+        self.ctx.bridge.launchpad().unwrap()
+    }
+
+    // ==================== Actions ====================
+
+    /// Add `liquidity` to the latest pair for `user`, in the token order the
+    /// pair was created with, returning the LP tokens minted
+    pub fn add_liquidity(&self, user: &Address, liquidity: (i128, i128)) -> i128 {
+        let (_, token_a, token_b) = self.pairs.last().expect("no pair in scenario");
+        let (_, _, lp_tokens, _) = self.ctx.router.add_liquidity(
+            user,
+            &self.token(*token_a),
+            &self.token(*token_b),
+            &liquidity.0,
+            &liquidity.1,
+            &0,
+            &0,
+            &self.ctx.deadline(),
+        );
+        lp_tokens
+    }
+
+    /// Stake all of `user`'s LP tokens in the latest pool, returning the amount
+    pub fn stake_all(&self, user: &Address) -> i128 {
+        let pool_id = self.pool();
+        let lp_token = self.ctx.staking.pool_info(&pool_id).lp_token;
+        let amount = PairClient::new(&self.ctx.env, &lp_token).balance(user);
+        self.ctx.staking.stake(user, &pool_id, &amount);
+        amount
+    }
+
+    /// Deploy a token administered by the launchpad, mint `LAUNCH_SUPPLY` to
+    /// the launchpad and send it `GRADUATION_QUOTE_AMOUNT` of XLM
+    pub fn launch_token(&self) -> Address {
+        let launchpad = self.launchpad();
+        let token = self
+            .ctx
+            .env
+            .register_stellar_asset_contract_v2(launchpad.clone())
+            .address();
+        StellarAssetClient::new(&self.ctx.env, &token).mint(&launchpad, &LAUNCH_SUPPLY);
+        self.ctx
+            .xlm
+            .transfer(&self.ctx.admin, &launchpad, &GRADUATION_QUOTE_AMOUNT);
+        token
+    }
+
+    /// Graduation metadata of a launched token
+    pub fn token_metadata(&self, symbol: &str) -> TokenMetadata {
+        TokenMetadata {
+            name: String::from_str(&self.ctx.env, "Test Token"),
+            symbol: String::from_str(&self.ctx.env, symbol),
+            decimals: 7,
+            total_supply: LAUNCH_SUPPLY,
+            creator: self.launchpad(),
+            graduation_time: self.ctx.timestamp(),
+        }
+    }
+}
+
+impl Deref for Scenario {
+    type Target = TestContext;
+
+    fn deref(&self) -> &TestContext {
+        &self.ctx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scenario_builder() {
+        let ctx = Scenario::new()
+            .with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000))
+            .with_pair(Token::B, Token::C, (20_000_0000000, 40_000_0000000))
+            .with_staking_pool(10_0000000, 86_400)
+            .graduate("GRAD");
+
+        assert_eq!(
+            ctx.factory
+                .get_pair(&ctx.token_a_address, &ctx.token_b_address),
+            Some(ctx.pair_at(0))
+        );
+        assert_eq!(ctx.pair(), ctx.pair_at(1));
+
+        // The pool belongs to the latest pair and is fully funded
+        assert_eq!(ctx.staking.pool_info(&ctx.pool()).lp_token, ctx.pair());
+        assert_eq!(ctx.xlm.balance(&ctx.staking_address), 10_0000000 * 86_400);
+
+        let lp_tokens = ctx.add_liquidity(&ctx.user1, (1_000_0000000, 2_000_0000000));
+        assert!(lp_tokens > 0);
+        assert_eq!(ctx.stake_all(&ctx.user1), lp_tokens);
+        assert_eq!(
+            ctx.staking.user_info(&ctx.user1, &ctx.pool()).amount,
+            lp_tokens
+        );
+
+        let graduation = ctx.graduation();
+        assert!(ctx.bridge.is_graduated(&graduation.token));
+        assert_eq!(
+            graduation.metadata.symbol,
+            String::from_str(&ctx.env, "GRAD")
+        );
+    }
+}
@@ -6,13 +6,149 @@
 //! - Execute aggregated swap
 //! - Verify best route selection
 
-use crate::test_utils::{assert_approx_eq, TestContext};
-use astroswap_shared::Protocol;
-use soroban_sdk::testutils::Address as _;
+use crate::mock_oracle::{MockOracle, MockOracleClient};
+use crate::scenario::{Scenario, Token};
+use crate::test_utils::assert_approx_eq;
+use astroswap_pair::AstroSwapPairClient;
+use astroswap_shared::{get_amount_in, get_amount_out, AstroSwapError, Protocol};
+use soroban_sdk::{contract, contractimpl, testutils::Address as _, token, Address, Env};
+
+// External DEX stub quoting a fixed output for any swap
+#[contract]
+pub struct MockExternalDex;
+
+#[contractimpl]
+impl MockExternalDex {
+    pub fn set_quote(env: Env, amount_out: i128) {
+        env.storage().instance().set(&0u32, &amount_out);
+    }
+
+    pub fn get_amounts_out(
+        env: Env,
+        _amount_in: i128,
+        _token_in: Address,
+        _token_out: Address,
+    ) -> i128 {
+        env.storage().instance().get(&0u32).unwrap()
+    }
+}
+
+// External DEX stub with one constant-product pool (0.3% fee) that swaps
+// whatever input was sent to it since the last swap
+#[contract]
+pub struct MockPoolDex;
+
+#[contractimpl]
+impl MockPoolDex {
+    /// Open the pool with the balances already sent to it as reserves
+    pub fn init(env: Env, token_0: Address, token_1: Address) {
+        env.storage().instance().set(&0u32, &(token_0, token_1));
+        Self::sync(&env);
+    }
+
+    pub fn get_amounts_out(
+        env: Env,
+        amount_in: i128,
+        token_in: Address,
+        _token_out: Address,
+    ) -> i128 {
+        let (_, reserve_in, reserve_out) = Self::pool(&env, &token_in);
+        get_amount_out(amount_in, reserve_in, reserve_out, 30).unwrap()
+    }
+
+    pub fn get_amounts_in(
+        env: Env,
+        amount_out: i128,
+        token_in: Address,
+        _token_out: Address,
+    ) -> i128 {
+        let (_, reserve_in, reserve_out) = Self::pool(&env, &token_in);
+        get_amount_in(amount_out, reserve_in, reserve_out, 30).unwrap()
+    }
+
+    pub fn swap(env: Env, recipient: Address, token_in: Address, min_out: i128) -> i128 {
+        let (token_out, reserve_in, reserve_out) = Self::pool(&env, &token_in);
+        let this = env.current_contract_address();
+        let amount_in = token::Client::new(&env, &token_in).balance(&this) - reserve_in;
+        let amount_out = get_amount_out(amount_in, reserve_in, reserve_out, 30).unwrap();
+        assert!(amount_out >= min_out);
+        token::Client::new(&env, &token_out).transfer(&this, &recipient, &amount_out);
+        Self::sync(&env);
+        amount_out
+    }
+}
+
+// External DEX stub with a deadline-taking `swap` paying a fixed output from
+// its balance and recording the deadline it was given
+#[contract]
+pub struct MockDeadlineDex;
+
+#[contractimpl]
+impl MockDeadlineDex {
+    pub fn init(env: Env, token_out: Address, amount_out: i128) {
+        env.storage()
+            .instance()
+            .set(&0u32, &(token_out, amount_out));
+    }
+
+    pub fn get_amounts_out(
+        env: Env,
+        _amount_in: i128,
+        _token_in: Address,
+        _token_out: Address,
+    ) -> i128 {
+        let (_, amount_out): (Address, i128) = env.storage().instance().get(&0u32).unwrap();
+        amount_out
+    }
+
+    pub fn swap(
+        env: Env,
+        recipient: Address,
+        _token_in: Address,
+        min_out: i128,
+        deadline: u64,
+    ) -> i128 {
+        assert!(env.ledger().timestamp() <= deadline);
+        let (token_out, amount_out): (Address, i128) = env.storage().instance().get(&0u32).unwrap();
+        assert!(amount_out >= min_out);
+        let this = env.current_contract_address();
+        token::Client::new(&env, &token_out).transfer(&this, &recipient, &amount_out);
+        env.storage().instance().set(&1u32, &deadline);
+        amount_out
+    }
+
+    pub fn last_deadline(env: Env) -> Option<u64> {
+        env.storage().instance().get(&1u32)
+    }
+}
+
+impl MockPoolDex {
+    /// Store the current balances as reserves
+    fn sync(env: &Env) {
+        let (token_0, token_1): (Address, Address) = env.storage().instance().get(&0u32).unwrap();
+        let this = env.current_contract_address();
+        let reserves = (
+            token::Client::new(env, &token_0).balance(&this),
+            token::Client::new(env, &token_1).balance(&this),
+        );
+        env.storage().instance().set(&1u32, &reserves);
+    }
+
+    /// (token_out, reserve_in, reserve_out) for a swap selling `token_in`
+    fn pool(env: &Env, token_in: &Address) -> (Address, i128, i128) {
+        let (token_0, token_1): (Address, Address) = env.storage().instance().get(&0u32).unwrap();
+        let (reserve_0, reserve_1): (i128, i128) = env.storage().instance().get(&1u32).unwrap();
+        if *token_in == token_0 {
+            (token_1, reserve_0, reserve_1)
+        } else {
+            (token_0, reserve_1, reserve_0)
+        }
+    }
+}
 
 #[test]
 fn test_aggregator_initialization() {
-    let ctx = TestContext::new();
+    let ctx = Scenario::new();
 
     // Aggregator should be initialized with AstroSwap as Protocol 0
     assert_eq!(ctx.aggregator.admin(), ctx.admin);
@@ -33,15 +169,8 @@ fn test_aggregator_initialization() {
 
 #[test]
 fn test_aggregator_single_protocol_swap() {
-    let ctx = TestContext::new();
-
     // Setup AstroSwap pair
-    ctx.setup_pair(
-        &ctx.token_a_address,
-        &ctx.token_b_address,
-        10_000_0000000,
-        20_000_0000000,
-    );
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
 
     let swap_amount = 1_000_0000000i128;
 
@@ -93,7 +222,7 @@ fn test_aggregator_single_protocol_swap() {
 
 #[test]
 fn test_register_multiple_protocols() {
-    let ctx = TestContext::new();
+    let ctx = Scenario::new();
 
     // Register a mock Soroswap protocol
     let soroswap_factory = soroban_sdk::Address::generate(&ctx.env);
@@ -124,7 +253,7 @@ fn test_register_multiple_protocols() {
 
 #[test]
 fn test_disable_protocol() {
-    let ctx = TestContext::new();
+    let ctx = Scenario::new();
 
     // AstroSwap should be active initially
     let info = ctx
@@ -156,7 +285,7 @@ fn test_disable_protocol() {
 
 #[test]
 fn test_aggregator_config_update() {
-    let ctx = TestContext::new();
+    let ctx = Scenario::new();
 
     let config = ctx.aggregator.config();
     assert_eq!(config.max_hops, 3);
@@ -165,7 +294,7 @@ fn test_aggregator_config_update() {
 
     // Update config
     ctx.aggregator
-        .set_config(&ctx.admin, &2, &1, &10);
+        .set_config(&ctx.admin, &2, &1, &10, &100);
 
     let new_config = ctx.aggregator.config();
     assert_eq!(new_config.max_hops, 2);
@@ -175,25 +304,18 @@ fn test_aggregator_config_update() {
 
 #[test]
 fn test_aggregator_fee_too_high_rejected() {
-    let ctx = TestContext::new();
+    let ctx = Scenario::new();
 
     // Try to set fee > 1%
-    let result = ctx.aggregator.try_set_config(&ctx.admin, &3, &2, &150);
+    let result = ctx.aggregator.try_set_config(&ctx.admin, &3, &2, &150, &100);
 
     assert!(result.is_err(), "Should reject fee > 1%");
 }
 
 #[test]
 fn test_aggregator_pause() {
-    let ctx = TestContext::new();
-
     // Setup pair
-    ctx.setup_pair(
-        &ctx.token_a_address,
-        &ctx.token_b_address,
-        10_000_0000000,
-        20_000_0000000,
-    );
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
 
     // Pause aggregator
     ctx.aggregator
@@ -232,15 +354,8 @@ fn test_aggregator_pause() {
 
 #[test]
 fn test_get_all_quotes() {
-    let ctx = TestContext::new();
-
     // Setup AstroSwap pair
-    ctx.setup_pair(
-        &ctx.token_a_address,
-        &ctx.token_b_address,
-        10_000_0000000,
-        20_000_0000000,
-    );
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
 
     let swap_amount = 1_000_0000000i128;
 
@@ -259,7 +374,7 @@ fn test_get_all_quotes() {
 
 #[test]
 fn test_aggregator_fee_recipient() {
-    let ctx = TestContext::new();
+    let ctx = Scenario::new();
 
     // Initially no fee recipient
     assert!(ctx.aggregator.fee_recipient().is_none());
@@ -304,15 +419,8 @@ fn test_aggregator_fee_recipient() {
 
 #[test]
 fn test_swap_with_precomputed_route() {
-    let ctx = TestContext::new();
-
     // Setup pair
-    ctx.setup_pair(
-        &ctx.token_a_address,
-        &ctx.token_b_address,
-        10_000_0000000,
-        20_000_0000000,
-    );
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
 
     let swap_amount = 1_000_0000000i128;
 
@@ -344,9 +452,137 @@ fn test_swap_with_precomputed_route() {
     assert_eq!(final_b - initial_b, actual_output);
 }
 
+#[test]
+fn test_simulate_route_preview() {
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+
+    let swap_amount = 1_000_0000000i128;
+    let route = ctx
+        .aggregator
+        .find_best_route(&ctx.token_a_address, &ctx.token_b_address, &swap_amount);
+
+    let simulation = ctx.aggregator.simulate_route(&route, &swap_amount);
+
+    // 0.05% aggregator fee comes off the input before the first hop
+    let aggregator_fee = swap_amount * 5 / 10_000;
+    assert_eq!(simulation.amount_in, swap_amount);
+    assert_eq!(simulation.aggregator_fee, aggregator_fee);
+    assert_eq!(simulation.total_fee_bps, route.total_fee_bps);
+
+    assert_eq!(simulation.hops.len(), 1);
+    let hop = simulation.hops.get(0).unwrap();
+    assert_eq!(hop.pool_address, route.steps.get(0).unwrap().pool_address);
+    assert_eq!(hop.amount_in, swap_amount - aggregator_fee);
+    assert_eq!(hop.fee_bps, 30);
+    assert_eq!(hop.fee_amount, hop.amount_in * 30 / 10_000);
+    assert_eq!(hop.amount_out, simulation.amount_out);
+
+    // ~999.5 A into a 10k A pool moves the price by ~9.1%, fee excluded
+    assert!(
+        (900..=915).contains(&hop.price_impact_bps),
+        "unexpected price impact: {}",
+        hop.price_impact_bps
+    );
+
+    // Simulating does not touch the pool: executing the route matches the preview
+    let initial_b = ctx.token_b.balance(&ctx.user1);
+    let actual_output = ctx.aggregator.swap_with_route(
+        &ctx.user1,
+        &route,
+        &swap_amount,
+        &simulation.amount_out,
+        &ctx.deadline(),
+    );
+    assert_eq!(actual_output, simulation.amount_out);
+    assert_eq!(ctx.token_b.balance(&ctx.user1) - initial_b, actual_output);
+
+    // A larger trade on the moved pool has a higher impact
+    let larger = ctx.aggregator.simulate_route(&route, &(swap_amount * 2));
+    assert!(larger.hops.get(0).unwrap().price_impact_bps > hop.price_impact_bps);
+
+    // Invalid inputs are rejected
+    assert_eq!(
+        ctx.aggregator.try_simulate_route(&route, &0),
+        Err(Ok(AstroSwapError::InvalidAmount))
+    );
+}
+
+#[test]
+fn test_swap_with_tampered_route_rejected() {
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+
+    let swap_amount = 1_000_0000000i128;
+    let route = ctx
+        .aggregator
+        .find_best_route(&ctx.token_a_address, &ctx.token_b_address, &swap_amount);
+    let initial_a = ctx.token_a.balance(&ctx.user1);
+
+    // Unregistered protocol
+    let mut unknown_protocol = route.clone();
+    let mut step = unknown_protocol.steps.get(0).unwrap();
+    step.protocol_id = 42;
+    unknown_protocol.steps.set(0, step);
+    let result = ctx.aggregator.try_swap_with_route(
+        &ctx.user1,
+        &unknown_protocol,
+        &swap_amount,
+        &0,
+        &ctx.deadline(),
+    );
+    assert!(result.is_err(), "Should reject route through unknown protocol");
+
+    // Second hop does not continue from the first hop's output
+    let mut broken = route.clone();
+    let mut second = broken.steps.get(0).unwrap();
+    second.token_in = ctx.token_c_address.clone();
+    broken.steps.push_back(second);
+    let result = ctx.aggregator.try_swap_with_route(
+        &ctx.user1,
+        &broken,
+        &swap_amount,
+        &0,
+        &ctx.deadline(),
+    );
+    assert!(result.is_err(), "Should reject discontinuous route");
+
+    // No tokens moved
+    assert_eq!(ctx.token_a.balance(&ctx.user1), initial_a);
+}
+
+#[test]
+fn test_stale_route_fails_before_moving_funds() {
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+
+    let swap_amount = 1_000_0000000i128;
+    let route =
+        ctx.aggregator
+            .find_best_route(&ctx.token_a_address, &ctx.token_b_address, &swap_amount);
+    let initial_a = ctx.token_a.balance(&ctx.user1);
+    let swap_with_route = || {
+        ctx.aggregator
+            .try_swap_with_route(&ctx.user1, &route, &swap_amount, &0, &ctx.deadline())
+    };
+
+    // Protocol deactivated after the route was quoted
+    ctx.aggregator
+        .set_protocol_active(&ctx.admin, &Protocol::AstroSwap, &false);
+    assert_eq!(swap_with_route(), Err(Ok(AstroSwapError::ProtocolInactive)));
+    ctx.aggregator
+        .set_protocol_active(&ctx.admin, &Protocol::AstroSwap, &true);
+
+    // Pool paused after the route was quoted
+    let pair = AstroSwapPairClient::new(&ctx.env, &ctx.pair());
+    pair.set_paused(&true);
+    assert_eq!(swap_with_route(), Err(Ok(AstroSwapError::PoolPaused)));
+    assert_eq!(ctx.token_a.balance(&ctx.user1), initial_a);
+
+    pair.set_paused(&false);
+    assert!(swap_with_route().is_ok());
+}
+
 #[test]
 fn test_route_not_found_for_missing_pair() {
-    let ctx = TestContext::new();
+    let ctx = Scenario::new();
 
     // Try to find route for pair that doesn't exist (use try_ for error testing)
     let result = ctx
@@ -358,7 +594,7 @@ fn test_route_not_found_for_missing_pair() {
 
 #[test]
 fn test_aggregator_admin_transfer() {
-    let ctx = TestContext::new();
+    let ctx = Scenario::new();
 
     let new_admin = soroban_sdk::Address::generate(&ctx.env);
 
@@ -377,3 +613,848 @@ fn test_aggregator_admin_transfer() {
 
     assert!(ctx.aggregator.is_paused());
 }
+
+#[test]
+fn test_swap_auth_shapes_and_allowance_pull() {
+    use astroswap_aggregator::PullMode;
+    use soroban_sdk::testutils::{AuthorizedFunction, Ledger};
+    use soroban_sdk::Symbol;
+
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+    let swap_amount = 100_0000000i128;
+
+    // Transfer pull: the user signs the swap with a single token sub-invocation
+    ctx.aggregator.swap(
+        &ctx.user1,
+        &ctx.token_a_address,
+        &ctx.token_b_address,
+        &swap_amount,
+        &0,
+        &ctx.deadline(),
+    );
+    let auths = ctx.env.auths();
+    let (_, invocation) = auths.iter().find(|(addr, _)| *addr == ctx.user1).unwrap();
+    assert_eq!(invocation.sub_invocations.len(), 1);
+    match &invocation.sub_invocations[0].function {
+        AuthorizedFunction::Contract((contract, function, _)) => {
+            assert_eq!(*contract, ctx.token_a_address);
+            assert_eq!(*function, Symbol::new(&ctx.env, "transfer"));
+        }
+        _ => panic!("expected a token transfer sub-invocation"),
+    }
+
+    let transfer_auth = ctx.aggregator.pull_authorization(
+        &ctx.user1,
+        &ctx.token_a_address,
+        &swap_amount,
+        &PullMode::Transfer,
+    );
+    assert_eq!(transfer_auth.spender, ctx.aggregator_address);
+    assert_eq!(transfer_auth.allowance_shortfall, 0);
+
+    // Allowance pull without an approval is rejected before tokens move
+    let needed = ctx.aggregator.pull_authorization(
+        &ctx.user1,
+        &ctx.token_a_address,
+        &swap_amount,
+        &PullMode::Allowance,
+    );
+    assert_eq!(needed.allowance_shortfall, swap_amount);
+    let result = ctx.aggregator.try_swap_with_allowance(
+        &ctx.user1,
+        &ctx.token_a_address,
+        &ctx.token_b_address,
+        &swap_amount,
+        &0,
+        &ctx.deadline(),
+    );
+    assert!(result.is_err(), "Should require an allowance");
+
+    // Approve, then the swap needs no token authorization from the user
+    let expiration = ctx.env.ledger().sequence() + 1_000;
+    ctx.token_a.approve(
+        &ctx.user1,
+        &ctx.aggregator_address,
+        &swap_amount,
+        &expiration,
+    );
+    let ready = ctx.aggregator.pull_authorization(
+        &ctx.user1,
+        &ctx.token_a_address,
+        &swap_amount,
+        &PullMode::Allowance,
+    );
+    assert_eq!(ready.allowance_shortfall, 0);
+
+    let initial_a = ctx.token_a.balance(&ctx.user1);
+    let initial_b = ctx.token_b.balance(&ctx.user1);
+    let output = ctx.aggregator.swap_with_allowance(
+        &ctx.user1,
+        &ctx.token_a_address,
+        &ctx.token_b_address,
+        &swap_amount,
+        &0,
+        &ctx.deadline(),
+    );
+    let auths = ctx.env.auths();
+    let (_, invocation) = auths.iter().find(|(addr, _)| *addr == ctx.user1).unwrap();
+    assert!(invocation.sub_invocations.is_empty());
+
+    assert!(output > 0);
+    assert_eq!(initial_a - ctx.token_a.balance(&ctx.user1), swap_amount);
+    assert_eq!(ctx.token_b.balance(&ctx.user1) - initial_b, output);
+    assert_eq!(ctx.token_a.allowance(&ctx.user1, &ctx.aggregator_address), 0);
+}
+
+#[test]
+fn test_external_quotes_checked_against_twap() {
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+    let swap_amount = 100_0000000i128;
+
+    // An external protocol reporting twice the market output
+    let dex = ctx.env.register(MockExternalDex, ());
+    let dex_client = MockExternalDexClient::new(&ctx.env, &dex);
+    dex_client.set_quote(&400_0000000);
+    ctx.aggregator
+        .register_protocol(&ctx.admin, &Protocol::Soroswap, &dex, &30);
+
+    // Unchecked, the inflated quote wins route selection
+    let route =
+        ctx.aggregator
+            .find_best_route(&ctx.token_a_address, &ctx.token_b_address, &swap_amount);
+    assert_eq!(route.steps.get(0).unwrap().protocol_id, 1);
+
+    // TWAPs say 1 A = 2 B, so 100 A should give about 200 B
+    let oracle = ctx.env.register(MockOracle, ());
+    let oracle_client = MockOracleClient::new(&ctx.env, &oracle);
+    oracle_client.set_twap(&ctx.token_a_address, &2_0000000);
+    oracle_client.set_twap(&ctx.token_b_address, &1_0000000);
+
+    assert_eq!(
+        ctx.aggregator
+            .try_set_quote_guard(&ctx.admin, &oracle, &0, &500),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+    ctx.aggregator
+        .set_quote_guard(&ctx.admin, &oracle, &3600, &500);
+    assert_eq!(ctx.aggregator.quote_guard().unwrap().max_deviation_bps, 500);
+
+    // The inflated quote is dropped; AstroSwap's own quote is never checked
+    let quotes =
+        ctx.aggregator
+            .get_all_quotes(&ctx.token_a_address, &ctx.token_b_address, &swap_amount);
+    assert_eq!(quotes.len(), 1);
+    assert_eq!(quotes.get(0).unwrap().0, 0);
+    let route =
+        ctx.aggregator
+            .find_best_route(&ctx.token_a_address, &ctx.token_b_address, &swap_amount);
+    assert_eq!(route.steps.get(0).unwrap().protocol_id, 0);
+
+    // A quote within the band still competes, and wins when it is better
+    dex_client.set_quote(&201_0000000);
+    let route =
+        ctx.aggregator
+            .find_best_route(&ctx.token_a_address, &ctx.token_b_address, &swap_amount);
+    assert_eq!(route.steps.get(0).unwrap().protocol_id, 1);
+    assert_eq!(route.expected_output, 201_0000000);
+
+    // Quotes the oracle cannot check are dropped too
+    let empty_oracle = ctx.env.register(MockOracle, ());
+    ctx.aggregator
+        .set_quote_guard(&ctx.admin, &empty_oracle, &3600, &500);
+    let quotes =
+        ctx.aggregator
+            .get_all_quotes(&ctx.token_a_address, &ctx.token_b_address, &swap_amount);
+    assert_eq!(quotes.len(), 1);
+
+    // Removing the guard restores unchecked quotes
+    ctx.aggregator.remove_quote_guard(&ctx.admin);
+    assert!(ctx.aggregator.quote_guard().is_none());
+    let quotes =
+        ctx.aggregator
+            .get_all_quotes(&ctx.token_a_address, &ctx.token_b_address, &swap_amount);
+    assert_eq!(quotes.len(), 2);
+}
+
+// Token stub reporting 18 decimals (Stellar assets have 7)
+#[contract]
+pub struct MockToken18;
+
+#[contractimpl]
+impl MockToken18 {
+    pub fn decimals(_env: Env) -> u32 {
+        18
+    }
+}
+
+#[test]
+fn test_quote_guard_with_mixed_decimals() {
+    let ctx = Scenario::new();
+    let token_x = ctx.env.register(MockToken18, ());
+    let one_x = 1_000_000_000_000_000_000i128;
+
+    let dex = ctx.env.register(MockExternalDex, ());
+    let dex_client = MockExternalDexClient::new(&ctx.env, &dex);
+    ctx.aggregator
+        .register_protocol(&ctx.admin, &Protocol::Soroswap, &dex, &30);
+
+    // TWAPs say 1 A = 2 X
+    let oracle = ctx.env.register(MockOracle, ());
+    let oracle_client = MockOracleClient::new(&ctx.env, &oracle);
+    oracle_client.set_twap(&ctx.token_a_address, &2_0000000);
+    oracle_client.set_twap(&token_x, &1_0000000);
+    ctx.aggregator
+        .set_quote_guard(&ctx.admin, &oracle, &3600, &500);
+
+    // 100 A (7 decimals) is fairly 200 X (18 decimals)
+    dex_client.set_quote(&(199 * one_x));
+    let quotes = ctx
+        .aggregator
+        .get_all_quotes(&ctx.token_a_address, &token_x, &100_0000000);
+    assert_eq!(quotes.len(), 1);
+    dex_client.set_quote(&(400 * one_x));
+    let quotes = ctx
+        .aggregator
+        .get_all_quotes(&ctx.token_a_address, &token_x, &100_0000000);
+    assert_eq!(quotes.len(), 0);
+
+    // And 200 X is fairly 100 A
+    dex_client.set_quote(&99_0000000);
+    let quotes = ctx
+        .aggregator
+        .get_all_quotes(&token_x, &ctx.token_a_address, &(200 * one_x));
+    assert_eq!(quotes.len(), 1);
+    dex_client.set_quote(&50_0000000);
+    let quotes = ctx
+        .aggregator
+        .get_all_quotes(&token_x, &ctx.token_a_address, &(200 * one_x));
+    assert_eq!(quotes.len(), 0);
+}
+
+#[test]
+fn test_protocol_priority_filter_and_early_stop() {
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+    let swap_amount = 100_0000000i128;
+    let ids = |quotes: soroban_sdk::Vec<(u32, i128)>| -> std::vec::Vec<u32> {
+        quotes.iter().map(|(protocol_id, _)| protocol_id).collect()
+    };
+
+    // Two external protocols quoting close to the ~197 B AstroSwap gives
+    let soroswap = ctx.env.register(MockExternalDex, ());
+    let soroswap_client = MockExternalDexClient::new(&ctx.env, &soroswap);
+    soroswap_client.set_quote(&199_0000000);
+    ctx.aggregator
+        .register_protocol(&ctx.admin, &Protocol::Soroswap, &soroswap, &30);
+    let phoenix = ctx.env.register(MockExternalDex, ());
+    let phoenix_client = MockExternalDexClient::new(&ctx.env, &phoenix);
+    phoenix_client.set_quote(&199_5000000);
+    ctx.aggregator
+        .register_protocol(&ctx.admin, &Protocol::Phoenix, &phoenix, &30);
+
+    let quotes =
+        ctx.aggregator
+            .get_all_quotes(&ctx.token_a_address, &ctx.token_b_address, &swap_amount);
+    assert_eq!(ids(quotes), [0, 1, 2]);
+
+    // Prioritized protocols are quoted first, the rest in ID order
+    assert_eq!(
+        ctx.aggregator
+            .try_set_protocol_priority(&ctx.admin, &soroban_sdk::vec![&ctx.env, 1, 1]),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+    assert_eq!(
+        ctx.aggregator
+            .try_set_protocol_priority(&ctx.admin, &soroban_sdk::vec![&ctx.env, 3]),
+        Err(Ok(AstroSwapError::ProtocolNotFound))
+    );
+    let priority = soroban_sdk::vec![&ctx.env, 2, 1];
+    ctx.aggregator.set_protocol_priority(&ctx.admin, &priority);
+    assert_eq!(ctx.aggregator.protocol_priority(), priority);
+    let quotes =
+        ctx.aggregator
+            .get_all_quotes(&ctx.token_a_address, &ctx.token_b_address, &swap_amount);
+    assert_eq!(ids(quotes), [2, 1, 0]);
+
+    // A per-call filter quotes only the listed protocols
+    let quotes = ctx.aggregator.get_quotes(
+        &ctx.token_a_address,
+        &ctx.token_b_address,
+        &swap_amount,
+        &Some(soroban_sdk::vec![&ctx.env, 0, 2]),
+    );
+    assert_eq!(ids(quotes), [2, 0]);
+
+    // Early stop needs the quote guard's oracle: 1 A = 2 B, so stop at 198 B
+    assert_eq!(
+        ctx.aggregator.try_set_early_stop_bps(&ctx.admin, &10_001),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+    ctx.aggregator.set_early_stop_bps(&ctx.admin, &100);
+    assert_eq!(ctx.aggregator.early_stop_bps(), 100);
+    let quotes =
+        ctx.aggregator
+            .get_all_quotes(&ctx.token_a_address, &ctx.token_b_address, &swap_amount);
+    assert_eq!(quotes.len(), 3);
+
+    let oracle = ctx.env.register(MockOracle, ());
+    let oracle_client = MockOracleClient::new(&ctx.env, &oracle);
+    oracle_client.set_twap(&ctx.token_a_address, &2_0000000);
+    oracle_client.set_twap(&ctx.token_b_address, &1_0000000);
+    ctx.aggregator
+        .set_quote_guard(&ctx.admin, &oracle, &3600, &500);
+
+    // The first quote within 1% of fair ends the search
+    let quotes =
+        ctx.aggregator
+            .get_all_quotes(&ctx.token_a_address, &ctx.token_b_address, &swap_amount);
+    assert_eq!(ids(quotes), [2]);
+    let route =
+        ctx.aggregator
+            .find_best_route(&ctx.token_a_address, &ctx.token_b_address, &swap_amount);
+    assert_eq!(route.steps.get(0).unwrap().protocol_id, 2);
+
+    // A quote short of the mark keeps the search going
+    phoenix_client.set_quote(&195_0000000);
+    let quotes =
+        ctx.aggregator
+            .get_all_quotes(&ctx.token_a_address, &ctx.token_b_address, &swap_amount);
+    assert_eq!(ids(quotes), [2, 1]);
+    let route =
+        ctx.aggregator
+            .find_best_route(&ctx.token_a_address, &ctx.token_b_address, &swap_amount);
+    assert_eq!(route.steps.get(0).unwrap().protocol_id, 1);
+
+    // Disabling early stop quotes everything again
+    ctx.aggregator.set_early_stop_bps(&ctx.admin, &0);
+    let quotes =
+        ctx.aggregator
+            .get_all_quotes(&ctx.token_a_address, &ctx.token_b_address, &swap_amount);
+    assert_eq!(quotes.len(), 3);
+}
+
+#[test]
+fn test_route_statistics() {
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+    let swap_amount = 100_0000000i128;
+    assert_eq!(ctx.aggregator.best_protocol(), None);
+
+    // An external protocol quoting 1% below AstroSwap
+    let native_quote = ctx.aggregator.get_protocol_quote(
+        &Protocol::AstroSwap,
+        &ctx.token_a_address,
+        &ctx.token_b_address,
+        &swap_amount,
+    );
+    let external_quote = native_quote * 99 / 100;
+    let dex = ctx.env.register(MockExternalDex, ());
+    MockExternalDexClient::new(&ctx.env, &dex).set_quote(&external_quote);
+    ctx.aggregator
+        .register_protocol(&ctx.admin, &Protocol::Soroswap, &dex, &30);
+
+    // A best-route swap counts a contested win and a hop for AstroSwap
+    ctx.aggregator.swap(
+        &ctx.user1,
+        &ctx.token_a_address,
+        &ctx.token_b_address,
+        &swap_amount,
+        &0,
+        &ctx.deadline(),
+    );
+    let improvement = (native_quote - external_quote) * 10_000 / external_quote;
+    let hop_amount = swap_amount - swap_amount * 5 / 10_000;
+    let stats = ctx.aggregator.protocol_stats(&0);
+    assert_eq!(stats.win_count, 1);
+    assert_eq!(stats.contested_wins, 1);
+    assert_eq!(stats.total_improvement_bps, improvement as u64);
+    assert_eq!(stats.hop_count, 1);
+    assert_eq!(stats.routed_volume, hop_amount);
+    assert_eq!(ctx.aggregator.avg_improvement_bps(&0), improvement as u32);
+
+    // A pre-computed route counts the hop but no win
+    let route =
+        ctx.aggregator
+            .find_best_route(&ctx.token_a_address, &ctx.token_b_address, &swap_amount);
+    ctx.aggregator
+        .swap_with_route(&ctx.user1, &route, &swap_amount, &0, &ctx.deadline());
+    let stats = ctx.aggregator.protocol_stats(&0);
+    assert_eq!(stats.win_count, 1);
+    assert_eq!(stats.hop_count, 2);
+    assert_eq!(stats.routed_volume, hop_amount * 2);
+
+    // The losing protocol is listed with empty statistics
+    let all = ctx.aggregator.all_protocol_stats();
+    assert_eq!(all.len(), 2);
+    assert_eq!(all.get(1).unwrap().0, 1);
+    assert_eq!(all.get(1).unwrap().1.win_count, 0);
+    assert_eq!(ctx.aggregator.avg_improvement_bps(&1), 0);
+    assert_eq!(ctx.aggregator.best_protocol(), Some(0));
+}
+
+#[test]
+fn test_exact_output_split_across_protocols() {
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+
+    // An external pool as deep as the AstroSwap pair
+    let dex = ctx.env.register(MockPoolDex, ());
+    ctx.token_a.transfer(&ctx.admin, &dex, &10_000_0000000);
+    ctx.token_b.transfer(&ctx.admin, &dex, &20_000_0000000);
+    MockPoolDexClient::new(&ctx.env, &dex).init(&ctx.token_a_address, &ctx.token_b_address);
+    ctx.aggregator
+        .register_protocol(&ctx.admin, &Protocol::Soroswap, &dex, &30);
+
+    // Equal pools take half of the order each
+    let amount_out = 2_000_0000000i128;
+    let quote = ctx.aggregator.quote_exact_output_split(
+        &ctx.token_a_address,
+        &ctx.token_b_address,
+        &amount_out,
+    );
+    assert_eq!(quote.legs.len(), 2);
+    assert_eq!(quote.legs.get(0).unwrap().protocol_id, 0);
+    assert_eq!(quote.legs.get(0).unwrap().amount_out, 1_000_0000000);
+    assert_eq!(quote.legs.get(1).unwrap().pool_address, dex);
+    assert_eq!(quote.legs.get(1).unwrap().amount_out, 1_000_0000000);
+
+    // Deducting 0.05% from the total leaves at least what the legs need
+    let total_in = quote.total_in();
+    assert!(total_in - total_in * 5 / 10_000 >= quote.amount_in);
+
+    // Buying everything from one pool costs more
+    ctx.aggregator.set_config(&ctx.admin, &3, &1, &5, &100);
+    let single = ctx.aggregator.quote_exact_output_split(
+        &ctx.token_a_address,
+        &ctx.token_b_address,
+        &amount_out,
+    );
+    assert_eq!(single.legs.len(), 1);
+    assert!(single.amount_in > quote.amount_in);
+    ctx.aggregator.set_config(&ctx.admin, &3, &2, &5, &100);
+
+    assert_eq!(
+        ctx.aggregator.try_swap_exact_output_split(
+            &ctx.user1,
+            &ctx.token_a_address,
+            &ctx.token_b_address,
+            &amount_out,
+            &(quote.total_in() - 1),
+            &ctx.deadline(),
+        ),
+        Err(Ok(AstroSwapError::ExcessiveInputAmount))
+    );
+
+    let initial_a = ctx.token_a.balance(&ctx.user1);
+    let initial_b = ctx.token_b.balance(&ctx.user1);
+    let paid = ctx.aggregator.swap_exact_output_split(
+        &ctx.user1,
+        &ctx.token_a_address,
+        &ctx.token_b_address,
+        &amount_out,
+        &quote.total_in(),
+        &ctx.deadline(),
+    );
+
+    // Rounding up each leg's input can only buy slightly more than asked
+    assert_eq!(paid, quote.total_in());
+    assert_eq!(initial_a - ctx.token_a.balance(&ctx.user1), paid);
+    let bought = ctx.token_b.balance(&ctx.user1) - initial_b;
+    assert!(bought >= amount_out && bought - amount_out <= 2 * quote.legs.len() as i128);
+    assert_eq!(
+        ctx.token_a.balance(&ctx.aggregator_address),
+        quote.aggregator_fee
+    );
+    assert_eq!(
+        ctx.aggregator.protocol_stats(&1).routed_volume,
+        quote.legs.get(1).unwrap().amount_in
+    );
+}
+
+#[test]
+fn test_external_adapter_receives_deadline() {
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+    let swap_amount = 100_0000000i128;
+
+    // An external pool beating AstroSwap's ~197 B for 100 A
+    let dex = ctx.env.register(MockDeadlineDex, ());
+    let dex_client = MockDeadlineDexClient::new(&ctx.env, &dex);
+    dex_client.init(&ctx.token_b_address, &250_0000000);
+    ctx.token_b.transfer(&ctx.admin, &dex, &1_000_0000000);
+    ctx.aggregator
+        .register_protocol(&ctx.admin, &Protocol::Soroswap, &dex, &30);
+
+    let swap = || {
+        ctx.aggregator.try_swap(
+            &ctx.user1,
+            &ctx.token_a_address,
+            &ctx.token_b_address,
+            &swap_amount,
+            &0,
+            &ctx.deadline(),
+        )
+    };
+
+    // Called without a deadline, the adapter's swap cannot be invoked
+    assert!(swap().is_err());
+
+    assert_eq!(
+        ctx.aggregator
+            .try_set_protocol_deadline(&ctx.user1, &Protocol::Soroswap, &true),
+        Err(Ok(AstroSwapError::Unauthorized))
+    );
+    assert_eq!(
+        ctx.aggregator
+            .try_set_protocol_deadline(&ctx.admin, &Protocol::Phoenix, &true),
+        Err(Ok(AstroSwapError::ProtocolNotFound))
+    );
+    ctx.aggregator
+        .set_protocol_deadline(&ctx.admin, &Protocol::Soroswap, &true);
+    assert!(ctx.aggregator.protocol_takes_deadline(&Protocol::Soroswap));
+
+    // The route's deadline reaches the adapter
+    assert_eq!(swap().unwrap().unwrap(), 250_0000000);
+    assert_eq!(dex_client.last_deadline(), Some(ctx.deadline()));
+
+    // Deregistering forgets the convention
+    ctx.aggregator
+        .deregister_protocol(&ctx.admin, &Protocol::Soroswap);
+    assert!(!ctx.aggregator.protocol_takes_deadline(&Protocol::Soroswap));
+}
+
+#[test]
+fn test_partial_fill_within_price_bounds() {
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+
+    // 2,000 A cannot buy 3,800 B (1.9 B per A) against ~2 B per A spot
+    let amount_in = 2_000_0000000i128;
+    let min_out = 3_800_0000000i128;
+    assert_eq!(
+        ctx.aggregator.try_swap(
+            &ctx.user1,
+            &ctx.token_a_address,
+            &ctx.token_b_address,
+            &amount_in,
+            &min_out,
+            &ctx.deadline(),
+        ),
+        Err(Ok(AstroSwapError::SlippageExceeded))
+    );
+
+    // Opting in fills the part that still gets 1.9 B per A
+    let initial_a = ctx.token_a.balance(&ctx.user1);
+    let initial_b = ctx.token_b.balance(&ctx.user1);
+    let fill = ctx.aggregator.swap_partial(
+        &ctx.user1,
+        &ctx.token_a_address,
+        &ctx.token_b_address,
+        &amount_in,
+        &min_out,
+        &500,
+        &ctx.deadline(),
+    );
+    assert!(fill.amount_in > 400_0000000 && fill.amount_in < 550_0000000);
+    assert_eq!(fill.unfilled, amount_in - fill.amount_in);
+    assert!(fill.amount_out * amount_in >= min_out * fill.amount_in);
+
+    // Only the fill left the user's account
+    assert_eq!(initial_a - ctx.token_a.balance(&ctx.user1), fill.amount_in);
+    assert_eq!(ctx.token_b.balance(&ctx.user1) - initial_b, fill.amount_out);
+
+    // Without a limit price the impact bound decides: ~1% of the reserve
+    // moves the price by ~1%
+    let fill = ctx.aggregator.swap_partial(
+        &ctx.user1,
+        &ctx.token_a_address,
+        &ctx.token_b_address,
+        &amount_in,
+        &0,
+        &100,
+        &ctx.deadline(),
+    );
+    assert!(fill.amount_in > 80_0000000 && fill.amount_in < 150_0000000);
+
+    // Orders within both bounds fill completely
+    let fill = ctx.aggregator.swap_partial(
+        &ctx.user1,
+        &ctx.token_a_address,
+        &ctx.token_b_address,
+        &10_0000000,
+        &0,
+        &500,
+        &ctx.deadline(),
+    );
+    assert_eq!(fill.amount_in, 10_0000000);
+    assert_eq!(fill.unfilled, 0);
+
+    // A limit price above spot fills nothing
+    assert_eq!(
+        ctx.aggregator.try_swap_partial(
+            &ctx.user1,
+            &ctx.token_a_address,
+            &ctx.token_b_address,
+            &amount_in,
+            &(amount_in * 3),
+            &500,
+            &ctx.deadline(),
+        ),
+        Err(Ok(AstroSwapError::SlippageExceeded))
+    );
+    assert_eq!(
+        ctx.aggregator.try_swap_partial(
+            &ctx.user1,
+            &ctx.token_a_address,
+            &ctx.token_b_address,
+            &amount_in,
+            &0,
+            &10_001,
+            &ctx.deadline(),
+        ),
+        Err(Ok(AstroSwapError::FeeTooHigh))
+    );
+}
+
+#[test]
+fn test_connector_tokens_for_two_hop_routes() {
+    // A and C only trade against XLM
+    let ctx = Scenario::new()
+        .with_pair(Token::A, Token::Xlm, (10_000_0000000, 20_000_0000000))
+        .with_pair(Token::Xlm, Token::C, (20_000_0000000, 10_000_0000000));
+    let amount_in = 100_0000000i128;
+
+    // Without connectors only direct pools are searched
+    assert!(ctx.aggregator.connectors().is_empty());
+    assert_eq!(
+        ctx.aggregator
+            .try_find_best_route(&ctx.token_a_address, &ctx.token_c_address, &amount_in),
+        Err(Ok(AstroSwapError::RouteNotFound))
+    );
+
+    // Admin-only, no duplicates
+    assert!(ctx
+        .aggregator
+        .try_add_connector(&ctx.user1, &ctx.xlm_address)
+        .is_err());
+    ctx.aggregator.add_connector(&ctx.admin, &ctx.xlm_address);
+    assert_eq!(
+        ctx.aggregator
+            .try_add_connector(&ctx.admin, &ctx.xlm_address),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+    assert_eq!(ctx.aggregator.connectors().len(), 1);
+
+    // A -> XLM -> C, each hop quoted on its pool
+    let route =
+        ctx.aggregator
+            .find_best_route(&ctx.token_a_address, &ctx.token_c_address, &amount_in);
+    assert_eq!(route.steps.len(), 2);
+    let first = route.steps.get(0).unwrap();
+    let second = route.steps.get(1).unwrap();
+    assert_eq!(first.token_out, ctx.xlm_address);
+    assert_eq!(second.token_in, ctx.xlm_address);
+    let xlm_out = get_amount_out(amount_in, 10_000_0000000, 20_000_0000000, 30).unwrap();
+    let expected = get_amount_out(xlm_out, 20_000_0000000, 10_000_0000000, 30).unwrap();
+    assert_eq!(route.expected_output, expected);
+
+    // Executes through both pools
+    let initial_c = ctx.token_c.balance(&ctx.user1);
+    let amount_out = ctx.aggregator.swap(
+        &ctx.user1,
+        &ctx.token_a_address,
+        &ctx.token_c_address,
+        &amount_in,
+        &(expected * 99 / 100),
+        &ctx.deadline(),
+    );
+    assert_eq!(ctx.token_c.balance(&ctx.user1) - initial_c, amount_out);
+
+    // Single-hop configs skip connectors
+    let config = ctx.aggregator.config();
+    ctx.aggregator.set_config(
+        &ctx.admin,
+        &1,
+        &config.max_splits,
+        &config.aggregator_fee_bps,
+        &config.hop_slippage_bps,
+    );
+    assert!(ctx
+        .aggregator
+        .try_find_best_route(&ctx.token_a_address, &ctx.token_c_address, &amount_in)
+        .is_err());
+    ctx.aggregator.set_config(
+        &ctx.admin,
+        &2,
+        &config.max_splits,
+        &config.aggregator_fee_bps,
+        &config.hop_slippage_bps,
+    );
+
+    // Removed connectors are no longer routed through
+    ctx.aggregator
+        .remove_connector(&ctx.admin, &ctx.xlm_address);
+    assert!(ctx.aggregator.connectors().is_empty());
+    assert_eq!(
+        ctx.aggregator
+            .try_remove_connector(&ctx.admin, &ctx.xlm_address),
+        Err(Ok(AstroSwapError::InvalidToken))
+    );
+    assert!(ctx
+        .aggregator
+        .try_find_best_route(&ctx.token_a_address, &ctx.token_c_address, &amount_in)
+        .is_err());
+}
+
+#[test]
+fn test_slippage_preference_applies_to_zero_min_out() {
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+    let amount_in = 100_0000000i128;
+
+    assert_eq!(ctx.aggregator.slippage_preference(&ctx.user1), None);
+    assert_eq!(
+        ctx.aggregator
+            .try_set_slippage_preference(&ctx.user1, &10_001),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+    ctx.aggregator.set_slippage_preference(&ctx.user1, &10);
+    assert_eq!(ctx.aggregator.slippage_preference(&ctx.user1), Some(10));
+
+    // On an unchanged pool the preference leaves room for the aggregator fee
+    let initial_b = ctx.token_b.balance(&ctx.user1);
+    let amount_out = ctx.aggregator.swap(
+        &ctx.user1,
+        &ctx.token_a_address,
+        &ctx.token_b_address,
+        &amount_in,
+        &0,
+        &ctx.deadline(),
+    );
+    assert_eq!(ctx.token_b.balance(&ctx.user1) - initial_b, amount_out);
+
+    // A trade after the route was quoted moves the price ~0.5%: within the
+    // per-hop tolerance, beyond the 0.1% preference
+    let route =
+        ctx.aggregator
+            .find_best_route(&ctx.token_a_address, &ctx.token_b_address, &amount_in);
+    let path = soroban_sdk::vec![
+        &ctx.env,
+        ctx.token_a_address.clone(),
+        ctx.token_b_address.clone()
+    ];
+    ctx.router
+        .swap_exact_tokens_for_tokens(&ctx.user2, &25_0000000, &0, &path, &ctx.deadline());
+    let result =
+        ctx.aggregator
+            .try_swap_with_route(&ctx.user1, &route, &amount_in, &0, &ctx.deadline());
+    assert_eq!(result, Err(Ok(AstroSwapError::SlippageExceeded)));
+
+    // An explicit min_out overrides the preference
+    assert!(ctx
+        .aggregator
+        .try_swap_with_route(&ctx.user1, &route, &amount_in, &1, &ctx.deadline())
+        .is_ok());
+
+    ctx.aggregator.set_slippage_preference(&ctx.user1, &0);
+    assert_eq!(ctx.aggregator.slippage_preference(&ctx.user1), None);
+}
+
+#[test]
+fn test_swap_batch_policies() {
+    use astroswap_aggregator::{BatchPolicy, SwapRequest};
+
+    let ctx = Scenario::new()
+        .with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000))
+        .with_pair(Token::B, Token::C, (20_000_0000000, 40_000_0000000));
+    let amount_in = 100_0000000i128;
+    let request = |token_in: &Address, token_out: &Address, min_out: i128| SwapRequest {
+        token_in: token_in.clone(),
+        token_out: token_out.clone(),
+        amount_in,
+        min_out,
+    };
+
+    // Two independent swaps, each along its own route
+    let initial_a = ctx.token_a.balance(&ctx.user1);
+    let initial_b = ctx.token_b.balance(&ctx.user1);
+    let initial_c = ctx.token_c.balance(&ctx.user1);
+    let requests = soroban_sdk::vec![
+        &ctx.env,
+        request(&ctx.token_a_address, &ctx.token_b_address, 1),
+        request(&ctx.token_b_address, &ctx.token_c_address, 1),
+    ];
+    let fill = ctx.aggregator.swap_batch(
+        &ctx.user1,
+        &requests,
+        &ctx.deadline(),
+        &BatchPolicy::AllOrNothing,
+    );
+    assert_eq!(fill.executed, 2);
+    let out_b = fill.amounts_out.get(0).unwrap();
+    let out_c = fill.amounts_out.get(1).unwrap();
+
+    // Within the aggregator fee of each pool's quote
+    let quote_b = get_amount_out(amount_in, 10_000_0000000, 20_000_0000000, 30).unwrap();
+    let quote_c = get_amount_out(amount_in, 20_000_0000000, 40_000_0000000, 30).unwrap();
+    assert_approx_eq(out_b, quote_b, 10);
+    assert_approx_eq(out_c, quote_c, 10);
+    assert_eq!(initial_a - ctx.token_a.balance(&ctx.user1), amount_in);
+    let b_gained = ctx.token_b.balance(&ctx.user1) - initial_b;
+    assert_eq!(b_gained, out_b - amount_in);
+    assert_eq!(ctx.token_c.balance(&ctx.user1) - initial_c, out_c);
+
+    // An unmeetable min_out and a missing pair...
+    let requests = soroban_sdk::vec![
+        &ctx.env,
+        request(&ctx.token_a_address, &ctx.token_b_address, amount_in * 3),
+        request(&ctx.token_a_address, &ctx.xlm_address, 0),
+        request(&ctx.token_b_address, &ctx.token_c_address, 1),
+    ];
+
+    // ...revert the whole batch
+    let initial_c = ctx.token_c.balance(&ctx.user1);
+    assert_eq!(
+        ctx.aggregator.try_swap_batch(
+            &ctx.user1,
+            &requests,
+            &ctx.deadline(),
+            &BatchPolicy::AllOrNothing,
+        ),
+        Err(Ok(AstroSwapError::SlippageExceeded))
+    );
+    assert_eq!(ctx.token_c.balance(&ctx.user1), initial_c);
+
+    // ...or are skipped without moving their tokens
+    let initial_a = ctx.token_a.balance(&ctx.user1);
+    let fill = ctx.aggregator.swap_batch(
+        &ctx.user1,
+        &requests,
+        &ctx.deadline(),
+        &BatchPolicy::BestEffort,
+    );
+    assert_eq!(fill.executed, 1);
+    assert_eq!(fill.amounts_out.get(0), Some(0));
+    assert_eq!(fill.amounts_out.get(1), Some(0));
+    assert!(fill.amounts_out.get(2).unwrap() > 0);
+    assert_eq!(ctx.token_a.balance(&ctx.user1), initial_a);
+
+    // Batches hold 1 to 5 requests
+    let empty = soroban_sdk::Vec::new(&ctx.env);
+    assert_eq!(
+        ctx.aggregator.try_swap_batch(
+            &ctx.user1,
+            &empty,
+            &ctx.deadline(),
+            &BatchPolicy::BestEffort
+        ),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+    let mut oversized = soroban_sdk::Vec::new(&ctx.env);
+    for _ in 0..6 {
+        oversized.push_back(request(&ctx.token_a_address, &ctx.token_b_address, 1));
+    }
+    assert_eq!(
+        ctx.aggregator.try_swap_batch(
+            &ctx.user1,
+            &oversized,
+            &ctx.deadline(),
+            &BatchPolicy::BestEffort
+        ),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+}
@@ -0,0 +1,118 @@
+//! Analytics Integration Tests
+//!
+//! Tests swap reporting to the analytics contract:
+//! - Pairs report every swap, whether it came through the router or the aggregator
+//! - The aggregator reports routed swaps and its own fee
+//! - Reports never fail a swap
+
+use crate::scenario::{Scenario, Token};
+use astroswap_analytics::{AstroSwapAnalytics, AstroSwapAnalyticsClient, VolumeStats};
+use astroswap_shared::{split_swap_fee, AstroSwapError};
+
+#[test]
+fn test_swaps_reported_to_analytics() {
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 10_000_0000000));
+    let pair = ctx.pair();
+
+    let analytics_address = ctx.env.register(AstroSwapAnalytics, ());
+    let analytics = AstroSwapAnalyticsClient::new(&ctx.env, &analytics_address);
+    analytics.initialize(&ctx.admin, &ctx.factory_address);
+    analytics.set_reporter(&ctx.admin, &ctx.aggregator_address, &true);
+
+    // Admin only, and only for pairs the factory deployed
+    assert_eq!(
+        ctx.factory
+            .try_set_pair_analytics(&ctx.user1, &pair, &Some(analytics_address.clone())),
+        Err(Ok(AstroSwapError::Unauthorized))
+    );
+    assert_eq!(
+        ctx.factory.try_set_pair_analytics(
+            &ctx.admin,
+            &ctx.token_a_address,
+            &Some(analytics_address.clone())
+        ),
+        Err(Ok(AstroSwapError::PairNotFound))
+    );
+    ctx.factory
+        .set_pair_analytics(&ctx.admin, &pair, &Some(analytics_address.clone()));
+    ctx.aggregator
+        .set_analytics(&ctx.admin, &Some(analytics_address.clone()));
+
+    let swap_amount = 100_0000000i128;
+    let path = soroban_sdk::vec![
+        &ctx.env,
+        ctx.token_a_address.clone(),
+        ctx.token_b_address.clone()
+    ];
+    let router_swap = || {
+        ctx.router.swap_exact_tokens_for_tokens(
+            &ctx.user1,
+            &swap_amount,
+            &0,
+            &path,
+            &ctx.deadline(),
+        )
+    };
+    let aggregator_swap = || {
+        ctx.aggregator.swap(
+            &ctx.user1,
+            &ctx.token_a_address,
+            &ctx.token_b_address,
+            &swap_amount,
+            &0,
+            &ctx.deadline(),
+        )
+    };
+
+    // The pair reports both swaps; the aggregator also reports its route
+    let aggregator_fee = swap_amount * i128::from(ctx.aggregator.user_fee_bps(&ctx.user1)) / 10_000;
+    router_swap();
+    aggregator_swap();
+
+    let hop_in = swap_amount - aggregator_fee;
+    let day = analytics.current_day();
+    let pair_day = analytics.pair_day(&pair, &ctx.token_a_address, &day);
+    assert_eq!(
+        pair_day,
+        VolumeStats {
+            swaps: 2,
+            volume: swap_amount + hop_in,
+            fees: split_swap_fee(swap_amount, 30).unwrap().fee_amount
+                + split_swap_fee(hop_in, 30).unwrap().fee_amount,
+        }
+    );
+    assert_eq!(analytics.token_day(&ctx.token_a_address, &day), pair_day);
+    assert_eq!(
+        analytics.reporter_day(&ctx.aggregator_address, &ctx.token_a_address, &day),
+        VolumeStats {
+            swaps: 1,
+            volume: swap_amount,
+            fees: aggregator_fee,
+        }
+    );
+
+    // Daily counters start over, cumulative ones keep counting
+    ctx.advance_time(86_400);
+    router_swap();
+    let today = analytics.current_day();
+    assert_eq!(today, day + 1);
+    assert_eq!(
+        analytics
+            .pair_day(&pair, &ctx.token_a_address, &today)
+            .swaps,
+        1
+    );
+    assert_eq!(analytics.pair_total(&pair, &ctx.token_a_address).swaps, 3);
+
+    // A rejected report does not fail the swap
+    analytics.set_reporter(&ctx.admin, &ctx.aggregator_address, &false);
+    assert!(aggregator_swap() > 0);
+    let totals = analytics.totals();
+    assert_eq!(totals.pair_swaps, 4);
+    assert_eq!(totals.routed_swaps, 1);
+
+    // Clearing the analytics contract stops the reports
+    ctx.factory.set_pair_analytics(&ctx.admin, &pair, &None);
+    router_swap();
+    assert_eq!(analytics.totals().pair_swaps, 4);
+}
@@ -6,13 +6,21 @@
 //! - Verify LP tokens burned
 //! - Verify staking pool created
 
-use crate::test_utils::TestContext;
-use astroswap_shared::{PairClient, TokenMetadata};
+use crate::scenario::{Scenario, GRADUATION_QUOTE_AMOUNT, GRADUATION_TOKEN_AMOUNT};
+use astroswap_shared::{
+    from_precision, mul_div_down, to_precision, AstroSwapError, PairClient, Rounding, TokenMetadata,
+};
 use soroban_sdk::{testutils::Address as _, String};
 
+/// Quote per whole token at 7 decimals, for two 7-decimal tokens
+fn initial_price(token_amount: i128, quote_amount: i128) -> i128 {
+    let ratio = mul_div_down(quote_amount, to_precision(1, 0).unwrap(), token_amount).unwrap();
+    from_precision(ratio, 7, Rounding::Down).unwrap()
+}
+
 #[test]
 fn test_bridge_initialization() {
-    let ctx = TestContext::new();
+    let ctx = Scenario::new();
 
     assert_eq!(ctx.bridge.admin(), ctx.admin);
     // Note: These view methods may return values or Options depending on implementation
@@ -24,7 +32,7 @@ fn test_bridge_initialization() {
 
 #[test]
 fn test_complete_graduation_flow() {
-    let ctx = TestContext::new();
+    let ctx = Scenario::new();
 
     // Get launchpad address (was set during bridge initialization)
     let launchpad = ctx.bridge.launchpad().unwrap();
@@ -89,6 +97,10 @@ fn test_complete_graduation_flow() {
             || (token_0 == ctx.xlm_address && token_1 == graduated_token_address)
     );
 
+    // The factory records the bridge as the pair's creator
+    let metadata = ctx.factory.pair_metadata(&pair_address).unwrap();
+    assert_eq!(metadata.creator, Some(ctx.bridge_address.clone()));
+
     // Verify LP tokens were BURNED (not just locked in bridge)
     // After the security fix, LP tokens are permanently burned to reduce total supply
     // Bridge balance should be 0 since tokens are burned, not held
@@ -116,6 +128,14 @@ fn test_complete_graduation_flow() {
 
     assert_eq!(pool_info.lp_token, pair_address);
 
+    // Named after the token and tagged as graduated
+    let pool_metadata = pool_info.metadata.unwrap();
+    assert_eq!(pool_metadata.name, metadata.symbol);
+    assert_eq!(
+        pool_metadata.tag,
+        soroban_sdk::Symbol::new(&ctx.env, "graduated")
+    );
+
     // Verify graduation is recorded
     assert!(ctx.bridge.is_graduated(&graduated_token_address));
     assert_eq!(ctx.bridge.graduation_count(), 1);
@@ -131,32 +151,12 @@ fn test_complete_graduation_flow() {
 
 #[test]
 fn test_cannot_graduate_twice() {
-    let ctx = TestContext::new();
-
-    let launchpad = ctx.bridge.launchpad().unwrap();
-
-    // Create token
-    let token_admin = soroban_sdk::Address::generate(&ctx.env);
-    let token_address = ctx.env.register_stellar_asset_contract_v2(token_admin.clone()).address();
-    let token = soroban_sdk::token::StellarAssetClient::new(&ctx.env, &token_address);
-
-    token.mint(&launchpad, &1_000_000_0000000);
-
-    ctx.xlm
-        .transfer(&ctx.admin, &launchpad, &69_000_0000000);
-
-    let metadata = TokenMetadata {
-        name: String::from_str(&ctx.env, "Test Token"),
-        symbol: String::from_str(&ctx.env, "TEST"),
-        decimals: 7,
-        total_supply: 1_000_000_0000000,
-        creator: launchpad.clone(),
-        graduation_time: ctx.timestamp(),
-    };
-
-    // First graduation (returns GraduatedToken directly)
-    ctx.bridge
-        .graduate_token(&launchpad, &token_address, &500_000_0000000i128, &69_000_0000000i128, &metadata);
+    // First graduation
+    let ctx = Scenario::new().graduate("TEST");
+    let launchpad = ctx.launchpad();
+    let graduation = ctx.graduation();
+    let token_address = graduation.token;
+    let metadata = graduation.metadata;
 
     // Try to graduate again
     ctx.xlm
@@ -175,7 +175,7 @@ fn test_cannot_graduate_twice() {
 
 #[test]
 fn test_only_launchpad_can_graduate() {
-    let ctx = TestContext::new();
+    let ctx = Scenario::new();
 
     let launchpad = ctx.bridge.launchpad().unwrap();
 
@@ -207,16 +207,293 @@ fn test_only_launchpad_can_graduate() {
     assert!(result.is_err(), "Only launchpad should be able to graduate");
 }
 
+#[test]
+fn test_bridge_creates_pools_as_pool_creator() {
+    use crate::scenario::{GRADUATION_QUOTE_AMOUNT, GRADUATION_TOKEN_AMOUNT};
+
+    // The bridge is an allowlisted pool creator, not the staking admin
+    let ctx = Scenario::new().graduate("TEST");
+    assert!(ctx.staking.is_pool_creator(&ctx.bridge_address));
+    assert_ne!(ctx.staking.admin(), ctx.bridge_address);
+    let graduation = ctx.graduation();
+    assert_eq!(
+        ctx.staking.pool_info(&graduation.staking_pool_id).lp_token,
+        graduation.pair
+    );
+
+    // Admin only; other addresses cannot create pools
+    assert_eq!(
+        ctx.staking
+            .try_set_pool_creator(&ctx.user1, &ctx.user1, &true),
+        Err(Ok(AstroSwapError::Unauthorized))
+    );
+    assert_eq!(
+        ctx.staking.try_create_pool(
+            &ctx.user1,
+            &graduation.pair,
+            &0,
+            &ctx.timestamp(),
+            &(ctx.timestamp() + 3600)
+        ),
+        Err(Ok(AstroSwapError::Unauthorized))
+    );
+
+    // Without the role, graduation cannot create its pool
+    ctx.staking
+        .set_pool_creator(&ctx.admin, &ctx.bridge_address, &false);
+    assert!(!ctx.staking.is_pool_creator(&ctx.bridge_address));
+    let token = ctx.launch_token();
+    assert!(ctx
+        .bridge
+        .try_graduate_token(
+            &ctx.launchpad(),
+            &token,
+            &GRADUATION_TOKEN_AMOUNT,
+            &GRADUATION_QUOTE_AMOUNT,
+            &ctx.token_metadata("NEXT"),
+        )
+        .is_err());
+}
+
 #[test]
 fn test_graduation_with_zero_liquidity_rejected() {
-    let ctx = TestContext::new();
+    let ctx = Scenario::new();
+
+    let launchpad = ctx.launchpad();
+    let token_address = ctx.launch_token();
+    let metadata = ctx.token_metadata("TEST");
+
+    // Try to graduate with zero amounts
+    let result = ctx.bridge.try_graduate_token(
+        &launchpad,
+        &token_address,
+        &0i128, // Zero token amount
+        &69_000_0000000i128,
+        &metadata,
+    );
+
+    assert!(result.is_err(), "Should reject zero liquidity");
+
+    let result = ctx.bridge.try_graduate_token(
+        &launchpad,
+        &token_address,
+        &500_000_0000000i128,
+        &0i128, // Zero XLM amount
+        &metadata,
+    );
+
+    assert!(result.is_err(), "Should reject zero liquidity");
+}
+
+#[test]
+fn test_graduation_market_depth_validation() {
+    let ctx = Scenario::new().with_oracle();
+
+    let launchpad = ctx.launchpad();
+    let quote_token = ctx.bridge.quote_token().unwrap();
+
+    let token_address = ctx.launch_token();
+    let metadata = ctx.token_metadata("TEST");
+
+    // XLM at $0.10; at least $5,000 of quote liquidity, 10% price band
+    let oracle = ctx.oracle_address();
+    let oracle_client = ctx.oracle();
+    oracle_client.set_price(&quote_token, &10_000_000);
+    ctx.bridge
+        .set_depth_config(&ctx.admin, &oracle, &5_000_0000000, &1_000);
+    assert_eq!(ctx.bridge.depth_config().unwrap().oracle, oracle);
+
+    // 10k XLM = $1,000: too thin
+    let result = ctx.bridge.try_graduate_token(
+        &launchpad,
+        &token_address,
+        &500_000_0000000i128,
+        &10_000_0000000i128,
+        &metadata,
+    );
+    assert_eq!(result, Err(Ok(AstroSwapError::InsufficientLiquidity)));
+
+    // 69k XLM = $6,900 for 500k tokens: ~$0.0138 per token, oracle says $0.05
+    oracle_client.set_price(&token_address, &5_000_000);
+    let result = ctx.bridge.try_graduate_token(
+        &launchpad,
+        &token_address,
+        &500_000_0000000i128,
+        &69_000_0000000i128,
+        &metadata,
+    );
+    assert_eq!(result, Err(Ok(AstroSwapError::InitialPriceOutOfBand)));
+
+    // Oracle agrees with the implied price
+    oracle_client.set_price(&token_address, &1_380_000);
+    let info = ctx.bridge.graduate_token(
+        &launchpad,
+        &token_address,
+        &500_000_0000000i128,
+        &69_000_0000000i128,
+        &metadata,
+    );
+    assert_eq!(info.token, token_address);
+}
+
+#[test]
+fn test_graduation_clawback_authority() {
+    use crate::scenario::{GRADUATION_QUOTE_AMOUNT, GRADUATION_TOKEN_AMOUNT, LAUNCH_SUPPLY};
+
+    let ctx = Scenario::new();
+    let launchpad = ctx.launchpad();
+
+    // Launched tokens keep the launchpad as their asset admin: flagged only
+    assert!(!ctx.bridge.rejects_clawback());
+    let flagged = ctx.launch_token();
+    let info = ctx.bridge.graduate_token(
+        &launchpad,
+        &flagged,
+        &GRADUATION_TOKEN_AMOUNT,
+        &GRADUATION_QUOTE_AMOUNT,
+        &ctx.token_metadata("FLAG"),
+    );
+    assert!(info.clawback_risk);
+    assert!(ctx.bridge.get_graduated_token(&flagged).clawback_risk);
+
+    // Only the admin can enable rejection
+    assert!(ctx
+        .bridge
+        .try_set_reject_clawback(&ctx.user1, &true)
+        .is_err());
+    ctx.bridge.set_reject_clawback(&ctx.admin, &true);
+    assert!(ctx.bridge.rejects_clawback());
+
+    // Both graduation paths refuse the token before taking its liquidity
+    let token = ctx.launch_token();
+    let metadata = ctx.token_metadata("RUG");
+    let result = ctx.bridge.try_graduate_token(
+        &launchpad,
+        &token,
+        &GRADUATION_TOKEN_AMOUNT,
+        &GRADUATION_QUOTE_AMOUNT,
+        &metadata,
+    );
+    assert_eq!(result, Err(Ok(AstroSwapError::ClawbackEnabled)));
+    let result = ctx.bridge.try_graduate_token_lbp(
+        &launchpad,
+        &token,
+        &GRADUATION_TOKEN_AMOUNT,
+        &GRADUATION_QUOTE_AMOUNT,
+        &metadata,
+        &(48 * 3600),
+        &9_000,
+    );
+    assert_eq!(result, Err(Ok(AstroSwapError::ClawbackEnabled)));
+    assert!(!ctx.bridge.is_graduated(&token));
+    let token_client = soroban_sdk::token::TokenClient::new(&ctx.env, &token);
+    assert_eq!(token_client.balance(&launchpad), LAUNCH_SUPPLY);
+}
+
+#[test]
+fn test_graduation_bootstraps_staking_rewards() {
+    use crate::scenario::{GRADUATION_QUOTE_AMOUNT, GRADUATION_TOKEN_AMOUNT};
+    use astroswap_bridge::RewardBootstrap;
+
+    let ctx = Scenario::new();
+    let staking_balance = || ctx.xlm.balance(&ctx.staking_address);
+    let graduate = |token: &soroban_sdk::Address, symbol: &str| {
+        let info = ctx.bridge.graduate_token(
+            &ctx.launchpad(),
+            token,
+            &GRADUATION_TOKEN_AMOUNT,
+            &GRADUATION_QUOTE_AMOUNT,
+            &ctx.token_metadata(symbol),
+        );
+        (
+            info.staking_pool_id,
+            ctx.staking.pool_info(&info.staking_pool_id),
+        )
+    };
+
+    // 1,000 XLM of rewards over 30 days for every graduated pool
+    let duration = 30 * 86_400u64;
+    let bootstrap = RewardBootstrap {
+        funder: ctx.admin.clone(),
+        amount: 1_000_0000000,
+        duration,
+    };
+    let reward_per_second = 1_000_0000000 / i128::from(duration);
+
+    // Admin only, with a schedule that emits at least a stroop per second
+    assert_eq!(
+        ctx.bridge
+            .try_set_reward_bootstrap(&ctx.user1, &Some(bootstrap.clone())),
+        Err(Ok(AstroSwapError::Unauthorized))
+    );
+    for invalid in [
+        RewardBootstrap {
+            duration: 0,
+            ..bootstrap.clone()
+        },
+        RewardBootstrap {
+            amount: i128::from(duration) - 1,
+            ..bootstrap.clone()
+        },
+    ] {
+        assert_eq!(
+            ctx.bridge
+                .try_set_reward_bootstrap(&ctx.admin, &Some(invalid)),
+            Err(Ok(AstroSwapError::InvalidArgument))
+        );
+    }
+    ctx.bridge
+        .set_reward_bootstrap(&ctx.admin, &Some(bootstrap.clone()));
+    assert_eq!(ctx.bridge.reward_bootstrap(), Some(bootstrap));
+
+    // Without an allowance the pool starts unfunded, as before
+    let token = ctx.launch_token();
+    let before = staking_balance();
+    let (pool_id, pool) = graduate(&token, "NOFUND");
+    assert_eq!(pool.reward_per_second, 0);
+    assert_eq!(pool.end_time, ctx.timestamp() + 365 * 86_400);
+    assert_eq!(staking_balance(), before);
+    assert_eq!(ctx.staking.pool_funding(&pool_id), 0);
+
+    // With one, the rewards are pulled and emitted over the schedule
+    let expiration_ledger = ctx.env.ledger().sequence() + 10_000;
+    ctx.xlm.approve(
+        &ctx.admin,
+        &ctx.bridge_address,
+        &2_000_0000000,
+        &expiration_ledger,
+    );
+    let token = ctx.launch_token();
+    let funder_before = ctx.xlm.balance(&ctx.admin);
+    let before = staking_balance();
+    let (pool_id, pool) = graduate(&token, "FUNDED");
+    let emission = reward_per_second * i128::from(duration);
+    assert_eq!(pool.reward_per_second, reward_per_second);
+    assert_eq!(pool.end_time, ctx.timestamp() + duration);
+    assert_eq!(staking_balance() - before, emission);
+    assert_eq!(funder_before - ctx.xlm.balance(&ctx.admin), emission);
+    // Earmarked for the new pool, not the shared reward fund
+    assert_eq!(ctx.staking.pool_funding(&pool_id), emission);
+    assert_eq!(ctx.xlm.balance(&ctx.bridge_address), 0);
+
+    // Disabling it leaves new pools unfunded again
+    ctx.bridge.set_reward_bootstrap(&ctx.admin, &None);
+    assert_eq!(ctx.bridge.reward_bootstrap(), None);
+    let token = ctx.launch_token();
+    assert_eq!(graduate(&token, "OFF").1.reward_per_second, 0);
+}
+
+#[test]
+fn test_lbp_graduation_flow() {
+    let ctx = Scenario::new();
 
     let launchpad = ctx.bridge.launchpad().unwrap();
 
     let token_address = ctx.env.register_stellar_asset_contract_v2(launchpad.clone()).address();
     let token = soroban_sdk::token::StellarAssetClient::new(&ctx.env, &token_address);
-
     token.mint(&launchpad, &1_000_000_0000000);
+    ctx.xlm.transfer(&ctx.admin, &launchpad, &10_000_0000000);
+    ctx.xlm.transfer(&ctx.admin, &ctx.user1, &1_000_0000000);
 
     let metadata = TokenMetadata {
         name: String::from_str(&ctx.env, "Test Token"),
@@ -227,31 +504,253 @@ fn test_graduation_with_zero_liquidity_rejected() {
         graduation_time: ctx.timestamp(),
     };
 
-    // Try to graduate with zero amounts
-    let result = ctx.bridge.try_graduate_token(
+    // Auction parameters are bounded
+    let result = ctx.bridge.try_graduate_token_lbp(
         &launchpad,
         &token_address,
-        &0i128, // Zero token amount
-        &69_000_0000000i128,
+        &1_000_000_0000000i128,
+        &10_000_0000000i128,
         &metadata,
+        &3_600,
+        &9_000,
     );
+    assert_eq!(result, Err(Ok(AstroSwapError::InvalidArgument)));
 
-    assert!(result.is_err(), "Should reject zero liquidity");
+    // 1M tokens against 10k XLM, 90/10 falling to 50/50 over 48 hours
+    let pool = ctx.bridge.graduate_token_lbp(
+        &launchpad,
+        &token_address,
+        &1_000_000_0000000i128,
+        &10_000_0000000i128,
+        &metadata,
+        &(48 * 3600),
+        &9_000,
+    );
+    assert_eq!(pool.end_time, ctx.timestamp() + 48 * 3600);
+    assert_eq!(ctx.bridge.lbp_weights(&token_address), (9_000, 1_000));
+    let opening_price = ctx.bridge.lbp_spot_price(&token_address);
+    assert_eq!(opening_price, 900_000);
 
+    // The running auction blocks a fixed-price graduation
     let result = ctx.bridge.try_graduate_token(
         &launchpad,
         &token_address,
-        &500_000_0000000i128,
-        &0i128, // Zero XLM amount
+        &1_000_000_0000000i128,
+        &10_000_0000000i128,
         &metadata,
     );
+    assert_eq!(result, Err(Ok(AstroSwapError::LbpActive)));
+
+    // Without buyers the price decays
+    ctx.advance_time(24 * 3600);
+    assert_eq!(ctx.bridge.lbp_weights(&token_address), (7_000, 3_000));
+    assert!(ctx.bridge.lbp_spot_price(&token_address) < opening_price);
+
+    // Buy with 1,000 XLM
+    let amount_in = 1_000_0000000i128;
+    let quoted = ctx.bridge.lbp_quote(&token_address, &ctx.xlm_address, &amount_in);
+    let result = ctx.bridge.try_lbp_swap(
+        &ctx.user1,
+        &token_address,
+        &ctx.xlm_address,
+        &amount_in,
+        &(quoted + 1),
+        &ctx.deadline(),
+    );
+    assert_eq!(result, Err(Ok(AstroSwapError::SlippageExceeded)));
 
-    assert!(result.is_err(), "Should reject zero liquidity");
+    let bought = ctx.bridge.lbp_swap(
+        &ctx.user1,
+        &token_address,
+        &ctx.xlm_address,
+        &amount_in,
+        &quoted,
+        &ctx.deadline(),
+    );
+    assert_eq!(bought, quoted);
+    assert_eq!(token.balance(&ctx.user1), bought);
+
+    let pool = ctx.bridge.get_lbp(&token_address);
+    assert_eq!(pool.quote_balance, 11_000_0000000);
+    assert_eq!(pool.token_balance, 1_000_000_0000000 - bought);
+
+    // Too early to convert
+    let result = ctx.bridge.try_finalize_lbp(&token_address);
+    assert_eq!(result, Err(Ok(AstroSwapError::LbpActive)));
+
+    // Auction over: no more swaps, anyone can finalize
+    ctx.advance_time(24 * 3600);
+    let result = ctx.bridge.try_lbp_swap(
+        &ctx.user1,
+        &token_address,
+        &ctx.xlm_address,
+        &amount_in,
+        &0,
+        &ctx.deadline(),
+    );
+    assert_eq!(result, Err(Ok(AstroSwapError::LbpEnded)));
+
+    let graduation = ctx.bridge.finalize_lbp(&token_address);
+    assert!(ctx.bridge.is_graduated(&token_address));
+    assert_eq!(
+        ctx.bridge.try_get_lbp(&token_address),
+        Err(Ok(AstroSwapError::LbpNotFound))
+    );
+
+    // The pair opens at the auction's closing (50/50) price
+    let pair_client = PairClient::new(&ctx.env, &graduation.pair);
+    let (reserve_0, reserve_1) = pair_client.get_reserves();
+    let (token_reserve, quote_reserve) = if pair_client.token_0() == token_address {
+        (reserve_0, reserve_1)
+    } else {
+        (reserve_1, reserve_0)
+    };
+    assert_eq!(token_reserve, pool.token_balance);
+    assert_eq!(quote_reserve, pool.quote_balance);
+    assert_eq!(
+        graduation.initial_price,
+        initial_price(pool.token_balance, pool.quote_balance)
+    );
+    assert_eq!(pair_client.balance(&ctx.bridge_address), 0);
+}
+
+#[test]
+fn test_lbp_finalization_guards_and_recovery() {
+    use astroswap_bridge::LBP_RECOVERY_DELAY;
+
+    let ctx = Scenario::new().with_oracle();
+    let launchpad = ctx.launchpad();
+    let start_lbp = |token: &soroban_sdk::Address| {
+        ctx.bridge.graduate_token_lbp(
+            &launchpad,
+            token,
+            &GRADUATION_TOKEN_AMOUNT,
+            &GRADUATION_QUOTE_AMOUNT,
+            &ctx.token_metadata("LBP"),
+            &(24 * 3600),
+            &9_000,
+        )
+    };
+
+    // During the auctions, someone opens an empty pair for the first token,
+    // a mispriced one for the second and one at the auction's price for the
+    // third
+    let seeded_token = ctx.launch_token();
+    start_lbp(&seeded_token);
+    let empty_pair = ctx.factory.create_pair(&seeded_token, &ctx.xlm_address);
+
+    let blocked_token = ctx.launch_token();
+    start_lbp(&blocked_token);
+    let blocked_pair = ctx.factory.create_pair(&blocked_token, &ctx.xlm_address);
+    soroban_sdk::token::Client::new(&ctx.env, &blocked_token).transfer(
+        &launchpad,
+        &ctx.user1,
+        &100_0000000,
+    );
+    ctx.xlm.transfer(&ctx.admin, &ctx.user1, &100_0000000);
+    PairClient::new(&ctx.env, &blocked_pair).deposit(
+        &ctx.user1,
+        100_0000000,
+        100_0000000,
+        0,
+        0,
+        ctx.deadline(),
+    );
+
+    let joined_token = ctx.launch_token();
+    start_lbp(&joined_token);
+    let joined_pair = ctx.factory.create_pair(&joined_token, &ctx.xlm_address);
+    let joined_client = PairClient::new(&ctx.env, &joined_pair);
+    let (existing_token, existing_quote) =
+        (GRADUATION_TOKEN_AMOUNT / 100, GRADUATION_QUOTE_AMOUNT / 100);
+    soroban_sdk::token::Client::new(&ctx.env, &joined_token).transfer(
+        &launchpad,
+        &ctx.user1,
+        &existing_token,
+    );
+    ctx.xlm.transfer(&ctx.admin, &ctx.user1, &existing_quote);
+    let (amount_0, amount_1) = if joined_client.token_0() == joined_token {
+        (existing_token, existing_quote)
+    } else {
+        (existing_quote, existing_token)
+    };
+    joined_client.deposit(&ctx.user1, amount_0, amount_1, 0, 0, ctx.deadline());
+    ctx.advance_time(24 * 3600);
+
+    // The final balances face the same depth floor as `graduate_token`:
+    // 69k XLM at $0.10 is short of $10,000
+    let oracle_client = ctx.oracle();
+    oracle_client.set_price(&ctx.xlm_address, &10_000_000);
+    ctx.bridge
+        .set_depth_config(&ctx.admin, &ctx.oracle_address(), &10_000_0000000, &1_000);
+    assert_eq!(
+        ctx.bridge.try_finalize_lbp(&seeded_token),
+        Err(Ok(AstroSwapError::InsufficientLiquidity))
+    );
+
+    // An empty pair is seeded instead of blocking the graduation
+    ctx.bridge
+        .set_depth_config(&ctx.admin, &ctx.oracle_address(), &5_000_0000000, &1_000);
+    let graduation = ctx.bridge.finalize_lbp(&seeded_token);
+    assert_eq!(graduation.pair, empty_pair);
+    let (reserve_0, reserve_1) = PairClient::new(&ctx.env, &empty_pair).get_reserves();
+    assert_eq!(
+        reserve_0 + reserve_1,
+        GRADUATION_TOKEN_AMOUNT + GRADUATION_QUOTE_AMOUNT
+    );
+
+    // A pair with liquidity at the closing price is joined
+    let launchpad_before = ctx.xlm.balance(&launchpad);
+    let graduation = ctx.bridge.finalize_lbp(&joined_token);
+    assert_eq!(graduation.pair, joined_pair);
+    let (reserve_0, reserve_1) = joined_client.get_reserves();
+    assert_eq!(
+        reserve_0 + reserve_1,
+        existing_token + existing_quote + GRADUATION_TOKEN_AMOUNT + GRADUATION_QUOTE_AMOUNT
+    );
+    assert_eq!(ctx.xlm.balance(&launchpad), launchpad_before);
+    assert_eq!(ctx.xlm.balance(&ctx.bridge_address), 0);
+
+    // One priced more than `MAX_LISTING_SLIPPAGE_BPS` away is not; the pool
+    // is refunded to the launchpad after the recovery delay instead
+    assert_eq!(
+        ctx.bridge.try_finalize_lbp(&blocked_token),
+        Err(Ok(AstroSwapError::MinimumNotMet))
+    );
+    assert_eq!(
+        ctx.bridge.try_recover_lbp(&launchpad, &blocked_token),
+        Err(Ok(AstroSwapError::LbpActive))
+    );
+    ctx.advance_time(LBP_RECOVERY_DELAY);
+    assert_eq!(
+        ctx.bridge.try_recover_lbp(&ctx.user1, &blocked_token),
+        Err(Ok(AstroSwapError::Unauthorized))
+    );
+    let token_client = soroban_sdk::token::Client::new(&ctx.env, &blocked_token);
+    let token_before = token_client.balance(&launchpad);
+    let quote_before = ctx.xlm.balance(&launchpad);
+    assert_eq!(
+        ctx.bridge.recover_lbp(&launchpad, &blocked_token),
+        (GRADUATION_TOKEN_AMOUNT, GRADUATION_QUOTE_AMOUNT)
+    );
+    assert_eq!(
+        token_client.balance(&launchpad) - token_before,
+        GRADUATION_TOKEN_AMOUNT
+    );
+    assert_eq!(
+        ctx.xlm.balance(&launchpad) - quote_before,
+        GRADUATION_QUOTE_AMOUNT
+    );
+    assert_eq!(
+        ctx.bridge.try_get_lbp(&blocked_token),
+        Err(Ok(AstroSwapError::LbpNotFound))
+    );
+    assert!(!ctx.bridge.is_graduated(&blocked_token));
 }
 
 #[test]
 fn test_get_graduation_by_index() {
-    let ctx = TestContext::new();
+    let ctx = Scenario::new();
 
     let launchpad = ctx.bridge.launchpad().unwrap();
 
@@ -305,7 +804,7 @@ fn test_get_graduation_by_index() {
 
 #[test]
 fn test_bridge_pause() {
-    let ctx = TestContext::new();
+    let ctx = Scenario::new();
 
     // Pause bridge
     ctx.bridge
@@ -361,7 +860,7 @@ fn test_bridge_pause() {
 
 #[test]
 fn test_update_launchpad_address() {
-    let ctx = TestContext::new();
+    let ctx = Scenario::new();
 
     let old_launchpad = ctx.bridge.launchpad().unwrap();
     let new_launchpad = soroban_sdk::Address::generate(&ctx.env);
@@ -404,7 +903,7 @@ fn test_update_launchpad_address() {
 
 #[test]
 fn test_initial_price_calculation() {
-    let ctx = TestContext::new();
+    let ctx = Scenario::new();
 
     let launchpad = ctx.bridge.launchpad().unwrap();
 
@@ -434,7 +933,7 @@ fn test_initial_price_calculation() {
 
     // Initial price should be XLM / Token ratio
     // Price = (xlm_amount / token_amount) * 10^7 (for 7 decimal precision)
-    let expected_price = (xlm_amount * 10_000_000) / token_amount;
+    let expected_price = initial_price(token_amount, xlm_amount);
 
     assert_eq!(graduation.initial_price, expected_price);
 
@@ -445,7 +944,7 @@ fn test_initial_price_calculation() {
 
 #[test]
 fn test_bridge_admin_transfer() {
-    let ctx = TestContext::new();
+    let ctx = Scenario::new();
 
     let new_admin = soroban_sdk::Address::generate(&ctx.env);
 
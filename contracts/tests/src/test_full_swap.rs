@@ -8,12 +8,13 @@
 //! 5. Verify balances and reserves
 //! 6. Remove liquidity
 
-use crate::test_utils::{assert_approx_eq, calculate_output_amount, TestContext};
+use crate::scenario::{Scenario, Token};
+use crate::test_utils::{assert_approx_eq, calculate_output_amount};
 use astroswap_shared::PairClient;
 
 #[test]
 fn test_full_swap_flow() {
-    let ctx = TestContext::new();
+    let ctx = Scenario::new();
 
     // Step 1: Create pair (returns Address directly)
     let pair_address = ctx
@@ -34,7 +35,7 @@ fn test_full_swap_flow() {
     let initial_a = ctx.token_a.balance(&ctx.user1);
     let initial_b = ctx.token_b.balance(&ctx.user1);
 
-    let (amount_a, amount_b, shares) = ctx
+    let (amount_a, amount_b, shares, pair) = ctx
         .router
         .add_liquidity(
             &ctx.user1,
@@ -50,6 +51,7 @@ fn test_full_swap_flow() {
     assert_eq!(amount_a, liquidity_a);
     assert_eq!(amount_b, liquidity_b);
     assert!(shares > 0);
+    assert_eq!(pair, pair_address);
 
     // Verify tokens were transferred
     assert_eq!(ctx.token_a.balance(&ctx.user1), initial_a - liquidity_a);
@@ -160,15 +162,9 @@ fn test_full_swap_flow() {
 
 #[test]
 fn test_swap_with_slippage_protection() {
-    let ctx = TestContext::new();
-
     // Setup pair
-    let pair_address = ctx.setup_pair(
-        &ctx.token_a_address,
-        &ctx.token_b_address,
-        10_000_0000000,
-        20_000_0000000,
-    );
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+    let pair_address = ctx.pair();
 
     let pair_client = PairClient::new(&ctx.env, &pair_address);
     let (reserve_0, reserve_1) = pair_client.get_reserves();
@@ -214,20 +210,13 @@ fn test_swap_with_slippage_protection() {
 
 #[test]
 fn test_add_liquidity_with_ratio_adjustment() {
-    let ctx = TestContext::new();
-
     // Create pair and add initial liquidity
-    ctx.setup_pair(
-        &ctx.token_a_address,
-        &ctx.token_b_address,
-        10_000_0000000,
-        20_000_0000000,
-    );
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
 
     // Now try to add liquidity with different ratio
     // Pool ratio is 1:2 (A:B)
     // User wants to add 1000:3000 but should be adjusted to 1000:2000
-    let (amount_a, amount_b, _) = ctx
+    let (amount_a, amount_b, _, _) = ctx
         .router
         .add_liquidity(
             &ctx.user2,
@@ -245,9 +234,58 @@ fn test_add_liquidity_with_ratio_adjustment() {
     assert_eq!(amount_b, 2_000_0000000); // Adjusted to 1:2 ratio
 }
 
+#[test]
+fn test_add_liquidity_auto_balance() {
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+
+    // Same 1000:3000 as above, but the excess B is swapped instead of left over
+    let initial_a = ctx.token_a.balance(&ctx.user2);
+    let initial_b = ctx.token_b.balance(&ctx.user2);
+    let (amount_a, amount_b, shares, _) = ctx.router.add_liquidity_with_options(
+        &ctx.user2,
+        &ctx.token_a_address,
+        &ctx.token_b_address,
+        &1_000_0000000i128,
+        &3_000_0000000i128,
+        &0,
+        &0,
+        &true,
+        &500, // 5% max swap slippage
+        &ctx.deadline(),
+    );
+
+    assert!(shares > 0);
+    assert!(
+        amount_a > 1_000_0000000,
+        "Swap output should be deposited too"
+    );
+    assert!(amount_b > 2_000_0000000 && amount_b < 3_000_0000000);
+
+    // Only dust of the provided value is left with the user
+    let spent_a = initial_a - ctx.token_a.balance(&ctx.user2);
+    let spent_b = initial_b - ctx.token_b.balance(&ctx.user2);
+    assert_approx_eq(spent_a, 1_000_0000000, 1);
+    assert_approx_eq(spent_b, 3_000_0000000, 1);
+
+    // A bound tighter than the pool fee rejects the balancing swap
+    let result = ctx.router.try_add_liquidity_with_options(
+        &ctx.user2,
+        &ctx.token_a_address,
+        &ctx.token_b_address,
+        &1_000_0000000i128,
+        &3_000_0000000i128,
+        &0,
+        &0,
+        &true,
+        &10,
+        &ctx.deadline(),
+    );
+    assert!(result.is_err());
+}
+
 #[test]
 fn test_cannot_create_duplicate_pair() {
-    let ctx = TestContext::new();
+    let ctx = Scenario::new();
 
     // Create first pair (returns Address directly)
     let pair1 = ctx
@@ -270,15 +308,8 @@ fn test_cannot_create_duplicate_pair() {
 
 #[test]
 fn test_swap_tokens_for_exact_tokens() {
-    let ctx = TestContext::new();
-
     // Setup pair with reserves
-    ctx.setup_pair(
-        &ctx.token_a_address,
-        &ctx.token_b_address,
-        10_000_0000000,
-        20_000_0000000,
-    );
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
 
     // User wants exactly 1000 token B
     let exact_output = 1_000_0000000i128;
@@ -319,14 +350,7 @@ fn test_swap_tokens_for_exact_tokens() {
 
 #[test]
 fn test_expired_deadline_rejected() {
-    let ctx = TestContext::new();
-
-    ctx.setup_pair(
-        &ctx.token_a_address,
-        &ctx.token_b_address,
-        10_000_0000000,
-        20_000_0000000,
-    );
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
 
     let path = soroban_sdk::vec![
         &ctx.env,
@@ -353,7 +377,7 @@ fn test_expired_deadline_rejected() {
 
 #[test]
 fn test_minimum_liquidity_lock() {
-    let ctx = TestContext::new();
+    let ctx = Scenario::new();
 
     // Create pair (returns Address directly)
     let pair_address = ctx
@@ -363,7 +387,7 @@ fn test_minimum_liquidity_lock() {
     let pair_client = PairClient::new(&ctx.env, &pair_address);
 
     // Add initial liquidity (returns tuple directly)
-    let (_, _, shares) = ctx
+    let (_, _, shares, _) = ctx
         .router
         .add_liquidity(
             &ctx.user1,
@@ -388,3 +412,678 @@ fn test_minimum_liquidity_lock() {
     let locked = pair_client.balance(&pair_address);
     assert_eq!(locked, expected_locked);
 }
+
+#[test]
+fn test_tagged_router_operations() {
+    let ctx = Scenario::new();
+    let tag = soroban_sdk::Symbol::new(&ctx.env, "mobile_app");
+
+    // Tokens passed in reverse order: results still follow the caller's order
+    let (amount_b, amount_a, shares, _) = ctx.router.add_liquidity_tagged(
+        &ctx.user1,
+        &ctx.token_b_address,
+        &ctx.token_a_address,
+        &20_000_0000000,
+        &10_000_0000000,
+        &0,
+        &0,
+        &false,
+        &0,
+        &tag,
+        &ctx.deadline(),
+    );
+    assert_eq!(amount_a, 10_000_0000000);
+    assert_eq!(amount_b, 20_000_0000000);
+    assert!(shares > 0);
+
+    let path = soroban_sdk::vec![
+        &ctx.env,
+        ctx.token_a_address.clone(),
+        ctx.token_b_address.clone()
+    ];
+    let swap_amount = 100_0000000i128;
+    let expected_output = calculate_output_amount(swap_amount, amount_a, amount_b);
+    let amounts = ctx.router.swap_exact_in_tagged(
+        &ctx.user2,
+        &swap_amount,
+        &(expected_output - 1_0000000),
+        &path,
+        &None,
+        &tag,
+        &ctx.deadline(),
+    );
+    assert_eq!(amounts.get(0).unwrap(), swap_amount);
+    assert_approx_eq(amounts.get(1).unwrap(), expected_output, 100);
+
+    let balance_b = ctx.token_b.balance(&ctx.user2);
+    let amounts = ctx.router.swap_exact_out_tagged(
+        &ctx.user2,
+        &50_0000000,
+        &100_0000000,
+        &path,
+        &tag,
+        &ctx.deadline(),
+    );
+    assert!(amounts.get(1).unwrap() >= 50_0000000);
+    assert_eq!(
+        ctx.token_b.balance(&ctx.user2),
+        balance_b + amounts.get(1).unwrap()
+    );
+
+    let (removed_a, removed_b) = ctx.router.remove_liquidity_tagged(
+        &ctx.user1,
+        &ctx.token_a_address,
+        &ctx.token_b_address,
+        &(shares / 2),
+        &0,
+        &0,
+        &tag,
+        &ctx.deadline(),
+    );
+    assert!(removed_a > 0);
+    assert!(removed_b > 0);
+}
+
+#[test]
+fn test_pair_creation_metadata() {
+    let ctx = Scenario::new();
+
+    // Anonymous factory call
+    let direct_pair = ctx
+        .factory
+        .create_pair(&ctx.token_a_address, &ctx.token_b_address);
+    let metadata = ctx.factory.pair_metadata(&direct_pair).unwrap();
+    assert_eq!(metadata.creator, None);
+    assert_eq!(metadata.created_at, ctx.timestamp());
+    assert_eq!(metadata.fee_bps, 30);
+    assert!(!metadata.graduated);
+
+    // Created by the router on first liquidity
+    ctx.advance_time(600);
+    let (_, _, _, router_pair) = ctx.router.add_liquidity(
+        &ctx.user1,
+        &ctx.token_b_address,
+        &ctx.token_c_address,
+        &1_000_0000000,
+        &1_000_0000000,
+        &0,
+        &0,
+        &ctx.deadline(),
+    );
+    assert_eq!(
+        ctx.factory.get_pair(&ctx.token_b_address, &ctx.token_c_address),
+        Some(router_pair.clone())
+    );
+    let metadata = ctx.factory.pair_metadata(&router_pair).unwrap();
+    assert_eq!(metadata.creator, Some(ctx.router_address.clone()));
+    assert_eq!(metadata.created_at, ctx.timestamp());
+
+    // Unknown pairs have no metadata
+    assert_eq!(ctx.factory.pair_metadata(&ctx.token_a_address), None);
+}
+
+#[test]
+fn test_factory_sets_pair_reserve_bounds() {
+    use astroswap_pair::AstroSwapPairClient;
+    use astroswap_shared::AstroSwapError;
+
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (1_000_0000000, 1_000_0000000));
+    let pair = AstroSwapPairClient::new(&ctx.env, &ctx.pair());
+
+    // Admin only, and only for pairs the factory deployed
+    assert_eq!(
+        ctx.factory
+            .try_set_pair_reserve_bounds(&ctx.user1, &pair.address, &0, &0),
+        Err(Ok(AstroSwapError::Unauthorized))
+    );
+    assert_eq!(
+        ctx.factory
+            .try_set_pair_reserve_bounds(&ctx.admin, &ctx.token_a_address, &0, &0),
+        Err(Ok(AstroSwapError::PairNotFound))
+    );
+
+    // Cap each reserve at 1,500 tokens, keep at least 800 in each
+    ctx.factory
+        .set_pair_reserve_bounds(&ctx.admin, &pair.address, &1_500_0000000, &800_0000000);
+    assert_eq!(pair.reserve_bounds(), (1_500_0000000, 800_0000000));
+
+    let path = soroban_sdk::vec![
+        &ctx.env,
+        ctx.token_a_address.clone(),
+        ctx.token_b_address.clone()
+    ];
+    let swap = |amount: i128| {
+        ctx.router
+            .try_swap_exact_tokens_for_tokens(&ctx.user1, &amount, &0, &path, &ctx.deadline())
+    };
+
+    // 300 in drains the output reserve to ~770, below the floor
+    assert!(swap(300_0000000).is_err());
+    assert!(swap(100_0000000).is_ok());
+
+    // Liquidity pushing a reserve above the cap is rejected
+    let result = ctx.router.try_add_liquidity(
+        &ctx.user1,
+        &ctx.token_a_address,
+        &ctx.token_b_address,
+        &500_0000000,
+        &500_0000000,
+        &0,
+        &0,
+        &ctx.deadline(),
+    );
+    assert!(result.is_err());
+
+    // Lifting the bounds restores both
+    ctx.factory
+        .set_pair_reserve_bounds(&ctx.admin, &pair.address, &0, &0);
+    assert!(swap(300_0000000).is_ok());
+}
+
+#[test]
+fn test_factory_sets_pair_large_withdrawal() {
+    use astroswap_pair::AstroSwapPairClient;
+    use astroswap_shared::AstroSwapError;
+
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (1_000_0000000, 1_000_0000000));
+    let pair = AstroSwapPairClient::new(&ctx.env, &ctx.pair());
+    let shares = ctx.add_liquidity(&ctx.user1, (1_000_0000000, 1_000_0000000));
+
+    // Admin only, and only for pairs the factory deployed
+    assert_eq!(
+        ctx.factory
+            .try_set_pair_large_withdrawal(&ctx.user1, &pair.address, &2_500, &3600, &600),
+        Err(Ok(AstroSwapError::Unauthorized))
+    );
+    assert_eq!(
+        ctx.factory.try_set_pair_large_withdrawal(
+            &ctx.admin,
+            &ctx.token_a_address,
+            &2_500,
+            &3600,
+            &600
+        ),
+        Err(Ok(AstroSwapError::PairNotFound))
+    );
+
+    // Withdrawals above 25% of the reserves need a request an hour ahead
+    ctx.factory
+        .set_pair_large_withdrawal(&ctx.admin, &pair.address, &2_500, &3600, &600);
+    assert_eq!(pair.large_withdrawal().delay_seconds, 3600);
+
+    let remove = |liquidity: i128| {
+        ctx.router.try_remove_liquidity(
+            &ctx.user1,
+            &ctx.token_a_address,
+            &ctx.token_b_address,
+            &liquidity,
+            &0,
+            &0,
+            &ctx.deadline(),
+        )
+    };
+
+    // Half the pool cannot leave through the router unannounced
+    assert!(remove(shares).is_err());
+    pair.request_withdrawal(&ctx.user1, &shares);
+    assert!(remove(shares).is_err());
+
+    ctx.advance_time(3600);
+    assert!(remove(shares).is_ok());
+    assert_eq!(pair.balance(&ctx.user1), 0);
+}
+
+#[test]
+fn test_router_picks_best_fee_tier() {
+    use astroswap_pair::AstroSwapPairClient;
+    use astroswap_shared::{get_amount_in, get_amount_out, AstroSwapError};
+
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (1_000_0000000, 1_000_0000000));
+    let default_pair = PairClient::new(&ctx.env, &ctx.pair());
+
+    // Only admin-enabled tiers can be created, by anyone
+    assert_eq!(
+        ctx.factory.try_create_pair_with_fee(
+            &ctx.user1,
+            &ctx.token_a_address,
+            &ctx.token_b_address,
+            &5
+        ),
+        Err(Ok(AstroSwapError::InvalidFee))
+    );
+    assert_eq!(
+        ctx.factory.try_set_fee_tier(&ctx.user1, &5, &true),
+        Err(Ok(AstroSwapError::Unauthorized))
+    );
+    ctx.factory.set_fee_tier(&ctx.admin, &5, &true);
+    assert_eq!(ctx.factory.fee_tiers(), soroban_sdk::vec![&ctx.env, 5u32]);
+
+    let tier_pair = AstroSwapPairClient::new(
+        &ctx.env,
+        &ctx.factory.create_pair_with_fee(
+            &ctx.user1,
+            &ctx.token_a_address,
+            &ctx.token_b_address,
+            &5,
+        ),
+    );
+    assert_eq!(
+        ctx.factory.try_create_pair_with_fee(
+            &ctx.user1,
+            &ctx.token_b_address,
+            &ctx.token_a_address,
+            &5
+        ),
+        Err(Ok(AstroSwapError::PairExists))
+    );
+    assert_eq!(
+        ctx.factory
+            .get_pairs_all_tiers(&ctx.token_b_address, &ctx.token_a_address),
+        soroban_sdk::vec![
+            &ctx.env,
+            (default_pair.address.clone(), 30u32),
+            (tier_pair.address.clone(), 5u32)
+        ]
+    );
+    assert_eq!(tier_pair.fee_bps(), 5);
+    let metadata = ctx.factory.pair_metadata(&tier_pair.address).unwrap();
+    assert_eq!(metadata.fee_bps, 5);
+    assert_eq!(metadata.creator, Some(ctx.user1.clone()));
+
+    // The default pair keeps serving get_pair
+    assert_eq!(
+        ctx.factory
+            .get_pair(&ctx.token_a_address, &ctx.token_b_address),
+        Some(default_pair.address.clone())
+    );
+
+    // The 5 bps pool is deeper and cheaper, so both swap directions go there
+    tier_pair.deposit(&ctx.admin, &10_000_0000000, &10_000_0000000, &0, &0, &ctx.deadline());
+    let path = soroban_sdk::vec![
+        &ctx.env,
+        ctx.token_a_address.clone(),
+        ctx.token_b_address.clone()
+    ];
+    let amount_in = 100_0000000i128;
+    let expected_out = get_amount_out(amount_in, 10_000_0000000, 10_000_0000000, 5).unwrap();
+    let amounts =
+        ctx.router
+            .swap_exact_tokens_for_tokens(&ctx.user1, &amount_in, &0, &path, &ctx.deadline());
+    assert_eq!(amounts.get(1).unwrap(), expected_out);
+    assert_eq!(default_pair.get_reserves(), (1_000_0000000, 1_000_0000000));
+
+    let (reserve_0, reserve_1) = tier_pair.get_reserves();
+    let (reserve_a, reserve_b) = if tier_pair.token_0() == ctx.token_a_address {
+        (reserve_0, reserve_1)
+    } else {
+        (reserve_1, reserve_0)
+    };
+    let amount_out = 50_0000000i128;
+    let max_in = get_amount_in(amount_out, reserve_a, reserve_b, 5).unwrap();
+    let amounts = ctx.router.swap_tokens_for_exact_tokens(
+        &ctx.user1,
+        &amount_out,
+        &max_in,
+        &path,
+        &ctx.deadline(),
+    );
+    assert!(amounts.get(0).unwrap() <= max_in);
+    assert_eq!(default_pair.get_reserves(), (1_000_0000000, 1_000_0000000));
+}
+
+#[test]
+fn test_router_skips_pools_below_min_reserve_depth() {
+    use astroswap_pair::AstroSwapPairClient;
+    use astroswap_shared::AstroSwapError;
+
+    // A shallow pool quoting 1 A = 2 B, and a deep 5 bps pool quoting 1 A = 1 B
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (100_0000000, 200_0000000));
+    let shallow_pair = PairClient::new(&ctx.env, &ctx.pair());
+    ctx.factory.set_fee_tier(&ctx.admin, &5, &true);
+    let deep_pair = AstroSwapPairClient::new(
+        &ctx.env,
+        &ctx.factory.create_pair_with_fee(
+            &ctx.user1,
+            &ctx.token_a_address,
+            &ctx.token_b_address,
+            &5,
+        ),
+    );
+    deep_pair.deposit(&ctx.admin, &10_000_0000000, &10_000_0000000, &0, &0, &ctx.deadline());
+
+    // Only the admin sets the depth, and never below zero
+    assert_eq!(ctx.router.min_reserve_depth(), 0);
+    assert_eq!(
+        ctx.router
+            .try_set_min_reserve_depth(&ctx.user1, &1_000_0000000),
+        Err(Ok(AstroSwapError::Unauthorized))
+    );
+    assert_eq!(
+        ctx.router.try_set_min_reserve_depth(&ctx.admin, &-1),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+
+    // Without a minimum, the better-priced shallow pool wins
+    let path = soroban_sdk::vec![
+        &ctx.env,
+        ctx.token_a_address.clone(),
+        ctx.token_b_address.clone()
+    ];
+    let shallow_reserves = shallow_pair.get_reserves();
+    ctx.router
+        .swap_exact_tokens_for_tokens(&ctx.user1, &1_0000000, &0, &path, &ctx.deadline());
+    assert_ne!(shallow_pair.get_reserves(), shallow_reserves);
+
+    // With one, both swap directions skip it for the deep pool
+    ctx.router.set_min_reserve_depth(&ctx.admin, &1_000_0000000);
+    assert_eq!(ctx.router.min_reserve_depth(), 1_000_0000000);
+    let shallow_reserves = shallow_pair.get_reserves();
+    let deep_reserves = deep_pair.get_reserves();
+    ctx.router
+        .swap_exact_tokens_for_tokens(&ctx.user1, &1_0000000, &0, &path, &ctx.deadline());
+    ctx.router.swap_tokens_for_exact_tokens(
+        &ctx.user1,
+        &1_0000000,
+        &2_0000000,
+        &path,
+        &ctx.deadline(),
+    );
+    assert_eq!(shallow_pair.get_reserves(), shallow_reserves);
+    assert_ne!(deep_pair.get_reserves(), deep_reserves);
+
+    // A hop without any deep enough pool fails
+    ctx.router
+        .set_min_reserve_depth(&ctx.admin, &20_000_0000000);
+    assert_eq!(
+        ctx.router.try_swap_exact_tokens_for_tokens(
+            &ctx.user1,
+            &1_0000000,
+            &0,
+            &path,
+            &ctx.deadline()
+        ),
+        Err(Ok(AstroSwapError::InsufficientLiquidity))
+    );
+    assert_eq!(
+        ctx.router.try_swap_tokens_for_exact_tokens(
+            &ctx.user1,
+            &1_0000000,
+            &2_0000000,
+            &path,
+            &ctx.deadline(),
+        ),
+        Err(Ok(AstroSwapError::InsufficientLiquidity))
+    );
+}
+
+#[test]
+fn test_migrate_liquidity_between_pools() {
+    use astroswap_pair::{AstroSwapPair, AstroSwapPairClient};
+    use astroswap_shared::AstroSwapError;
+    use soroban_sdk::Address;
+
+    let ctx = Scenario::new()
+        .with_pair(Token::A, Token::C, (1_000_0000000, 1_000_0000000))
+        .with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+    let old_pair = PairClient::new(&ctx.env, &ctx.pair());
+    let liquidity = ctx.add_liquidity(&ctx.user1, (1_000_0000000, 2_000_0000000));
+
+    // A 5 bps pool for the same tokens, priced at 1 A = 1.8 B
+    ctx.factory.set_fee_tier(&ctx.admin, &5, &true);
+    let new_pair = AstroSwapPairClient::new(
+        &ctx.env,
+        &ctx.factory.create_pair_with_fee(
+            &ctx.admin,
+            &ctx.token_a_address,
+            &ctx.token_b_address,
+            &5,
+        ),
+    );
+    let (token_0, token_1) = (old_pair.token_0(), old_pair.token_1());
+    let (seed_0, seed_1) = if token_0 == ctx.token_a_address {
+        (10_000_0000000, 18_000_0000000)
+    } else {
+        (18_000_0000000, 10_000_0000000)
+    };
+    new_pair.deposit(&ctx.admin, &seed_0, &seed_1, &0, &0, &ctx.deadline());
+
+    // Pools must differ, hold the same tokens and come from the factory
+    let migrate = |from: &Address, to: &Address, min: i128, max_slippage_bps: u32| {
+        ctx.router.try_migrate_liquidity(
+            &ctx.user1,
+            from,
+            to,
+            &liquidity,
+            &min,
+            &min,
+            &max_slippage_bps,
+            &ctx.deadline(),
+        )
+    };
+    assert_eq!(
+        migrate(&old_pair.address, &old_pair.address, 0, 100),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+    assert_eq!(
+        migrate(&old_pair.address, &new_pair.address, 0, 10_001),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+    assert_eq!(
+        migrate(&old_pair.address, &ctx.pair_at(0), 0, 100),
+        Err(Ok(AstroSwapError::InvalidToken))
+    );
+    let rogue_pair = AstroSwapPairClient::new(&ctx.env, &ctx.env.register(AstroSwapPair, ()));
+    rogue_pair.initialize(&ctx.factory_address, &token_0, &token_1);
+    assert_eq!(
+        migrate(&old_pair.address, &rogue_pair.address, 0, 100),
+        Err(Ok(AstroSwapError::PairNotFound))
+    );
+    assert_eq!(
+        migrate(&rogue_pair.address, &new_pair.address, 0, 100),
+        Err(Ok(AstroSwapError::PairNotFound))
+    );
+
+    // The balancing swap loses ~0.6% to price impact and fee, and unreachable
+    // minimums revert the whole migration
+    assert_eq!(
+        migrate(&old_pair.address, &new_pair.address, 0, 10),
+        Err(Ok(AstroSwapError::SlippageExceeded))
+    );
+    assert!(migrate(&old_pair.address, &new_pair.address, 2_000_0000000, 100).is_err());
+    assert_eq!(old_pair.balance(&ctx.user1), liquidity);
+
+    let balance_a = ctx.token_a.balance(&ctx.user1);
+    let balance_b = ctx.token_b.balance(&ctx.user1);
+    let (amount_0, amount_1, shares) = ctx.router.migrate_liquidity(
+        &ctx.user1,
+        &old_pair.address,
+        &new_pair.address,
+        &liquidity,
+        &0,
+        &0,
+        &100,
+        &ctx.deadline(),
+    );
+
+    // The LP position moved, and the excess B was swapped rather than left behind
+    assert_eq!(old_pair.balance(&ctx.user1), 0);
+    assert_eq!(new_pair.balance(&ctx.user1), shares);
+    assert!(shares > 0 && amount_0 > 0 && amount_1 > 0);
+    let dust_a = ctx.token_a.balance(&ctx.user1) - balance_a;
+    let dust_b = ctx.token_b.balance(&ctx.user1) - balance_b;
+    assert!(dust_a >= 0 && dust_a < 1_0000000);
+    assert!(dust_b >= 0 && dust_b < 1_0000000);
+}
+
+#[test]
+fn test_router_quote_price() {
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+
+    // One whole A buys 2 B, one whole B buys 0.5 A (7 decimals)
+    assert_eq!(
+        ctx.router
+            .quote_price(&ctx.token_a_address, &ctx.token_b_address),
+        2_0000000
+    );
+    assert_eq!(
+        ctx.router
+            .quote_price(&ctx.token_b_address, &ctx.token_a_address),
+        5_000000
+    );
+    assert!(ctx
+        .router
+        .try_quote_price(&ctx.token_a_address, &ctx.token_c_address)
+        .is_err());
+}
+
+#[test]
+fn test_router_recovers_stranded_tokens() {
+    use astroswap_shared::AstroSwapError;
+
+    let ctx = Scenario::new();
+    let stranded = 25_0000000i128;
+    ctx.token_a
+        .transfer(&ctx.user1, &ctx.router_address, &stranded);
+    let tokens = soroban_sdk::vec![
+        &ctx.env,
+        ctx.token_a_address.clone(),
+        ctx.token_b_address.clone()
+    ];
+    assert_eq!(
+        ctx.router.token_balances(&tokens),
+        soroban_sdk::vec![&ctx.env, stranded, 0]
+    );
+
+    // Admin only, and only after a request and the 48 hour delay
+    assert_eq!(
+        ctx.router
+            .try_request_recovery(&ctx.user1, &ctx.token_a_address, &ctx.user1, &stranded),
+        Err(Ok(AstroSwapError::Unauthorized))
+    );
+    assert_eq!(
+        ctx.router
+            .try_recover_token(&ctx.admin, &ctx.token_a_address, &ctx.user2, &stranded),
+        Err(Ok(AstroSwapError::RecoveryNotRequested))
+    );
+    let ready_at =
+        ctx.router
+            .request_recovery(&ctx.admin, &ctx.token_a_address, &ctx.user2, &stranded);
+    assert_eq!(ready_at, ctx.timestamp() + 48 * 3600);
+    assert_eq!(
+        ctx.router
+            .pending_recovery(&ctx.token_a_address)
+            .unwrap()
+            .amount,
+        stranded
+    );
+    assert_eq!(
+        ctx.router
+            .try_recover_token(&ctx.admin, &ctx.token_a_address, &ctx.user2, &stranded),
+        Err(Ok(AstroSwapError::TimelockNotExpired))
+    );
+
+    // The executed recovery must match the request
+    ctx.advance_time(48 * 3600);
+    assert_eq!(
+        ctx.router
+            .try_recover_token(&ctx.admin, &ctx.token_a_address, &ctx.admin, &stranded),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+    let balance = ctx.token_a.balance(&ctx.user2);
+    ctx.router
+        .recover_token(&ctx.admin, &ctx.token_a_address, &ctx.user2, &stranded);
+    assert_eq!(ctx.token_a.balance(&ctx.user2), balance + stranded);
+    assert_eq!(ctx.router.token_balances(&tokens).get(0), Some(0));
+    assert_eq!(ctx.router.pending_recovery(&ctx.token_a_address), None);
+
+    // Requests can be withdrawn
+    ctx.router
+        .request_recovery(&ctx.admin, &ctx.token_b_address, &ctx.user2, &1);
+    ctx.router.cancel_recovery(&ctx.admin, &ctx.token_b_address);
+    assert_eq!(
+        ctx.router
+            .try_cancel_recovery(&ctx.admin, &ctx.token_b_address),
+        Err(Ok(AstroSwapError::RecoveryNotRequested))
+    );
+
+    // A delay running past the end of time is rejected, not wrapped
+    ctx.advance_time(u64::MAX - ctx.timestamp());
+    assert_eq!(
+        ctx.router
+            .try_request_recovery(&ctx.admin, &ctx.token_b_address, &ctx.user2, &1),
+        Err(Ok(AstroSwapError::Overflow))
+    );
+}
+
+#[test]
+fn test_shared_pair_client_surfaces_errors() {
+    use astroswap_shared::AstroSwapError;
+
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+    let client = PairClient::new(&ctx.env, &ctx.pair());
+    let token_in = client.token_0();
+    let amount_in = 100_0000000i128;
+    let quote = client.try_get_amount_out(amount_in, &token_in).unwrap();
+
+    // Each failure comes back as its own error instead of trapping
+    assert_eq!(
+        client.try_swap(&ctx.user1, &token_in, amount_in, quote + 1, ctx.deadline()),
+        Err(AstroSwapError::SlippageExceeded)
+    );
+    let expired = ctx.timestamp();
+    ctx.advance_time(1);
+    assert_eq!(
+        client.try_swap(&ctx.user1, &token_in, amount_in, 0, expired),
+        Err(AstroSwapError::DeadlineExpired)
+    );
+    assert_eq!(
+        client.try_get_amount_out(amount_in, &ctx.token_c_address),
+        Err(AstroSwapError::InvalidToken)
+    );
+    assert_eq!(
+        client.try_get_amount_in(amount_in, &ctx.token_c_address),
+        Err(AstroSwapError::InvalidToken)
+    );
+
+    // Successful calls return the pair's result
+    assert_eq!(
+        client.try_swap(&ctx.user1, &token_in, amount_in, quote, ctx.deadline()),
+        Ok(quote)
+    );
+    assert_eq!(client.try_get_reserves(), Ok(client.get_reserves()));
+    let info = client.get_info();
+    assert_eq!(
+        (info.reserve_a, info.reserve_b),
+        client.try_get_reserves().unwrap()
+    );
+    assert_eq!(client.try_sync(), Ok(()));
+}
+
+#[test]
+fn test_pair_skims_to_factory_fee_recipient() {
+    use astroswap_pair::AstroSwapPairClient;
+    use astroswap_shared::AstroSwapError;
+
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (1_000_0000000, 1_000_0000000));
+    let pair = AstroSwapPairClient::new(&ctx.env, &ctx.pair());
+    let treasury = &ctx.user2;
+
+    // A donation sits in the pair outside the reserves
+    ctx.token_a.transfer(&ctx.user1, &pair.address, &50_0000000);
+    assert_eq!(
+        pair.try_skim_to_treasury(),
+        Err(Ok(AstroSwapError::FeeRecipientNotSet))
+    );
+
+    // Once the factory has a fee recipient, anyone can sweep it there
+    ctx.factory.set_fee_to(&ctx.admin, treasury);
+    let treasury_before = ctx.token_a.balance(treasury);
+    pair.skim_to_treasury();
+    assert_eq!(ctx.token_a.balance(treasury), treasury_before + 50_0000000);
+    assert_eq!(ctx.token_a.balance(&pair.address), 1_000_0000000);
+    assert_eq!(ctx.token_b.balance(&pair.address), 1_000_0000000);
+
+    // Nothing left to sweep
+    pair.skim_to_treasury();
+    assert_eq!(ctx.token_a.balance(treasury), treasury_before + 50_0000000);
+}
@@ -6,28 +6,19 @@
 //! - Verify intermediate amounts
 //! - Test multi-hop slippage protection
 
-use crate::test_utils::{assert_approx_eq, calculate_output_amount, TestContext};
-use astroswap_shared::PairClient;
+use crate::scenario::{Scenario, Token};
+use crate::test_utils::{assert_approx_eq, calculate_output_amount};
+use astroswap_shared::{AstroSwapError, PairClient};
 
 #[test]
 fn test_two_hop_swap() {
-    let ctx = TestContext::new();
-
-    // Create pair A/B with 10k A and 20k B
-    let pair_ab = ctx.setup_pair(
-        &ctx.token_a_address,
-        &ctx.token_b_address,
-        10_000_0000000,
-        20_000_0000000,
-    );
-
-    // Create pair B/C with 20k B and 40k C
-    let pair_bc = ctx.setup_pair(
-        &ctx.token_b_address,
-        &ctx.token_c_address,
-        20_000_0000000,
-        40_000_0000000,
-    );
+    let ctx = Scenario::new()
+        // Create pair A/B with 10k A and 20k B
+        .with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000))
+        // Create pair B/C with 20k B and 40k C
+        .with_pair(Token::B, Token::C, (20_000_0000000, 40_000_0000000));
+    let pair_ab = ctx.pair_at(0);
+    let pair_bc = ctx.pair_at(1);
 
     let pair_ab_client = PairClient::new(&ctx.env, &pair_ab);
     let pair_bc_client = PairClient::new(&ctx.env, &pair_bc);
@@ -102,29 +93,11 @@ fn test_two_hop_swap() {
 
 #[test]
 fn test_three_hop_swap() {
-    let ctx = TestContext::new();
-
-    // Deploy 4 tokens and create chain: A → B → C → XLM
-    ctx.setup_pair(
-        &ctx.token_a_address,
-        &ctx.token_b_address,
-        10_000_0000000,
-        20_000_0000000,
-    );
-
-    ctx.setup_pair(
-        &ctx.token_b_address,
-        &ctx.token_c_address,
-        20_000_0000000,
-        40_000_0000000,
-    );
-
-    ctx.setup_pair(
-        &ctx.token_c_address,
-        &ctx.xlm_address,
-        40_000_0000000,
-        80_000_0000000,
-    );
+    let ctx = Scenario::new()
+        // Deploy 4 tokens and create chain: A → B → C → XLM
+        .with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000))
+        .with_pair(Token::B, Token::C, (20_000_0000000, 40_000_0000000))
+        .with_pair(Token::C, Token::Xlm, (40_000_0000000, 80_000_0000000));
 
     let swap_amount = 500_0000000i128;
 
@@ -164,22 +137,10 @@ fn test_three_hop_swap() {
 
 #[test]
 fn test_multi_hop_slippage_protection() {
-    let ctx = TestContext::new();
-
-    // Setup pairs
-    ctx.setup_pair(
-        &ctx.token_a_address,
-        &ctx.token_b_address,
-        10_000_0000000,
-        20_000_0000000,
-    );
-
-    ctx.setup_pair(
-        &ctx.token_b_address,
-        &ctx.token_c_address,
-        20_000_0000000,
-        40_000_0000000,
-    );
+    let ctx = Scenario::new()
+        // Setup pairs
+        .with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000))
+        .with_pair(Token::B, Token::C, (20_000_0000000, 40_000_0000000));
 
     let path = soroban_sdk::vec![
         &ctx.env,
@@ -220,22 +181,12 @@ fn test_multi_hop_slippage_protection() {
 
 #[test]
 fn test_reverse_path_gives_different_rate() {
-    let ctx = TestContext::new();
-
-    // Create asymmetric pairs to test that A→B→C ≠ C→B→A
-    ctx.setup_pair(
-        &ctx.token_a_address,
-        &ctx.token_b_address,
-        10_000_0000000,
-        30_000_0000000, // 1:3 ratio
-    );
-
-    ctx.setup_pair(
-        &ctx.token_b_address,
-        &ctx.token_c_address,
-        30_000_0000000,
-        60_000_0000000, // 1:2 ratio
-    );
+    let ctx = Scenario::new()
+        // Create asymmetric pairs to test that A→B→C ≠ C→B→A
+        // 1:3 ratio
+        .with_pair(Token::A, Token::B, (10_000_0000000, 30_000_0000000))
+        // 1:2 ratio
+        .with_pair(Token::B, Token::C, (30_000_0000000, 60_000_0000000));
 
     let swap_amount = 1_000_0000000i128;
 
@@ -295,15 +246,8 @@ fn test_reverse_path_gives_different_rate() {
 
 #[test]
 fn test_invalid_path_rejected() {
-    let ctx = TestContext::new();
-
     // Create only A/B pair
-    ctx.setup_pair(
-        &ctx.token_a_address,
-        &ctx.token_b_address,
-        10_000_0000000,
-        20_000_0000000,
-    );
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
 
     // Try path A → C (no pair exists)
     let invalid_path = soroban_sdk::vec![
@@ -338,22 +282,10 @@ fn test_invalid_path_rejected() {
 
 #[test]
 fn test_price_impact_increases_with_amount() {
-    let ctx = TestContext::new();
-
-    // Setup pairs
-    ctx.setup_pair(
-        &ctx.token_a_address,
-        &ctx.token_b_address,
-        10_000_0000000,
-        20_000_0000000,
-    );
-
-    ctx.setup_pair(
-        &ctx.token_b_address,
-        &ctx.token_c_address,
-        20_000_0000000,
-        40_000_0000000,
-    );
+    let ctx = Scenario::new()
+        // Setup pairs
+        .with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000))
+        .with_pair(Token::B, Token::C, (20_000_0000000, 40_000_0000000));
 
     let path = soroban_sdk::vec![
         &ctx.env,
@@ -396,3 +328,211 @@ fn test_price_impact_increases_with_amount() {
         rate_diff_pct
     );
 }
+
+#[test]
+fn test_exact_output_multi_hop_charges_minimal_input() {
+    let ctx = Scenario::new()
+        .with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000))
+        .with_pair(Token::B, Token::C, (20_000_0000000, 40_000_0000000));
+    let pair_ab = ctx.pair_at(0);
+    let pair_bc = ctx.pair_at(1);
+    let pair_ab_client = PairClient::new(&ctx.env, &pair_ab);
+    let pair_bc_client = PairClient::new(&ctx.env, &pair_bc);
+
+    let reserves = |client: &PairClient, token_in: &soroban_sdk::Address| {
+        let (reserve_0, reserve_1) = client.get_reserves();
+        if *token_in == client.token_0() {
+            (reserve_0, reserve_1)
+        } else {
+            (reserve_1, reserve_0)
+        }
+    };
+    let (reserve_a, reserve_b_in_ab) = reserves(&pair_ab_client, &ctx.token_a_address);
+    let (reserve_b_in_bc, reserve_c) = reserves(&pair_bc_client, &ctx.token_b_address);
+
+    // Odd target so every hop has to round
+    let exact_output = 1_234_5678901i128;
+    let path = soroban_sdk::vec![
+        &ctx.env,
+        ctx.token_a_address.clone(),
+        ctx.token_b_address.clone(),
+        ctx.token_c_address.clone()
+    ];
+
+    let initial_a = ctx.token_a.balance(&ctx.user1);
+    let initial_c = ctx.token_c.balance(&ctx.user1);
+
+    let amounts = ctx.router.swap_tokens_for_exact_tokens(
+        &ctx.user1,
+        &exact_output,
+        &2_000_0000000i128,
+        &path,
+        &ctx.deadline(),
+    );
+    let input_amount = amounts.get(0).unwrap();
+    let output_amount = amounts.get(2).unwrap();
+
+    // The user pays exactly the reported input and receives the reported output
+    assert_eq!(initial_a - ctx.token_a.balance(&ctx.user1), input_amount);
+    assert_eq!(ctx.token_c.balance(&ctx.user1) - initial_c, output_amount);
+    assert!(output_amount >= exact_output);
+
+    // One stroop less would not have reached the target
+    let short_b = calculate_output_amount(input_amount - 1, reserve_a, reserve_b_in_ab);
+    let short_c = calculate_output_amount(short_b, reserve_b_in_bc, reserve_c);
+    assert!(short_c < exact_output);
+
+    // Nothing is left unswapped in the first pair
+    let (reserve_a_after, _) = reserves(&pair_ab_client, &ctx.token_a_address);
+    assert_eq!(ctx.token_a.balance(&pair_ab), reserve_a_after);
+}
+
+#[test]
+fn test_exact_input_per_hop_minimums() {
+    let ctx = Scenario::new()
+        .with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000))
+        // Thin intermediate pool
+        .with_pair(Token::B, Token::C, (2_000_0000000, 4_000_0000000));
+    let pair_ab = ctx.pair_at(0);
+    let pair_bc = ctx.pair_at(1);
+
+    let reserves = |pair: &soroban_sdk::Address, token_in: &soroban_sdk::Address| {
+        let client = PairClient::new(&ctx.env, pair);
+        let (reserve_0, reserve_1) = client.get_reserves();
+        if *token_in == client.token_0() {
+            (reserve_0, reserve_1)
+        } else {
+            (reserve_1, reserve_0)
+        }
+    };
+    let (reserve_a, reserve_b_in_ab) = reserves(&pair_ab, &ctx.token_a_address);
+    let (reserve_b_in_bc, reserve_c) = reserves(&pair_bc, &ctx.token_b_address);
+
+    let path = soroban_sdk::vec![
+        &ctx.env,
+        ctx.token_a_address.clone(),
+        ctx.token_b_address.clone(),
+        ctx.token_c_address.clone()
+    ];
+    let swap_amount = 1_000_0000000i128;
+    let expected_b = calculate_output_amount(swap_amount, reserve_a, reserve_b_in_ab);
+    let expected_c = calculate_output_amount(expected_b, reserve_b_in_bc, reserve_c);
+
+    // One minimum per hop
+    let result = ctx.router.try_swap_exact_in_with_hop_minimums(
+        &ctx.user1,
+        &swap_amount,
+        &0i128,
+        &path,
+        &Some(soroban_sdk::vec![&ctx.env, expected_b]),
+        &ctx.deadline(),
+    );
+    assert_eq!(result, Err(Ok(AstroSwapError::InvalidArgument)));
+
+    // The final minimum alone would pass, the first hop's does not
+    let result = ctx.router.try_swap_exact_in_with_hop_minimums(
+        &ctx.user1,
+        &swap_amount,
+        &expected_c,
+        &path,
+        &Some(soroban_sdk::vec![&ctx.env, expected_b + 1, 0]),
+        &ctx.deadline(),
+    );
+    assert_eq!(result, Err(Ok(AstroSwapError::SlippageExceeded)));
+
+    let result = ctx.router.try_swap_exact_in_with_hop_minimums(
+        &ctx.user1,
+        &swap_amount,
+        &0i128,
+        &path,
+        &Some(soroban_sdk::vec![&ctx.env, 0, expected_c + 1]),
+        &ctx.deadline(),
+    );
+    assert_eq!(result, Err(Ok(AstroSwapError::SlippageExceeded)));
+
+    // Minimums met exactly
+    let initial_c = ctx.token_c.balance(&ctx.user1);
+    let amounts = ctx.router.swap_exact_in_with_hop_minimums(
+        &ctx.user1,
+        &swap_amount,
+        &expected_c,
+        &path,
+        &Some(soroban_sdk::vec![&ctx.env, expected_b, expected_c]),
+        &ctx.deadline(),
+    );
+    assert_eq!(amounts.get(1).unwrap(), expected_b);
+    assert_eq!(amounts.get(2).unwrap(), expected_c);
+    assert_eq!(ctx.token_c.balance(&ctx.user1) - initial_c, expected_c);
+}
+
+#[test]
+fn test_cyclic_swap_requires_profit() {
+    let ctx = Scenario::new()
+        .with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000))
+        .with_pair(Token::B, Token::C, (20_000_0000000, 40_000_0000000))
+        // C is overpriced against A here: 1 C = 1 A instead of 0.25
+        .with_pair(Token::C, Token::A, (40_000_0000000, 40_000_0000000));
+    let (a, b, c) = (
+        ctx.token_a_address.clone(),
+        ctx.token_b_address.clone(),
+        ctx.token_c_address.clone(),
+    );
+
+    let swap_amount = 100_0000000i128;
+    let expected_b = calculate_output_amount(swap_amount, 10_000_0000000, 20_000_0000000);
+    let expected_c = calculate_output_amount(expected_b, 20_000_0000000, 40_000_0000000);
+    let expected_a = calculate_output_amount(expected_c, 40_000_0000000, 40_000_0000000);
+    let profit = expected_a - swap_amount;
+    assert!(profit > 0);
+
+    // The default entry points keep rejecting repeated tokens
+    let cycle = soroban_sdk::vec![&ctx.env, a.clone(), b.clone(), c.clone(), a.clone()];
+    let result = ctx.router.try_swap_exact_tokens_for_tokens(
+        &ctx.user1,
+        &swap_amount,
+        &0i128,
+        &cycle,
+        &ctx.deadline(),
+    );
+    assert_eq!(result, Err(Ok(AstroSwapError::InvalidPath)));
+
+    // Only closed cycles through at least two other distinct tokens
+    for path in [
+        soroban_sdk::vec![&ctx.env, a.clone(), b.clone(), c.clone()],
+        soroban_sdk::vec![&ctx.env, a.clone(), b.clone(), a.clone()],
+        soroban_sdk::vec![
+            &ctx.env,
+            a.clone(),
+            b.clone(),
+            c.clone(),
+            b.clone(),
+            a.clone()
+        ],
+    ] {
+        let result =
+            ctx.router
+                .try_swap_cyclic(&ctx.user1, &swap_amount, &1i128, &path, &ctx.deadline());
+        assert_eq!(result, Err(Ok(AstroSwapError::InvalidPath)));
+    }
+
+    // A profit threshold is required and enforced
+    let result =
+        ctx.router
+            .try_swap_cyclic(&ctx.user1, &swap_amount, &0i128, &cycle, &ctx.deadline());
+    assert_eq!(result, Err(Ok(AstroSwapError::InvalidArgument)));
+    let result = ctx.router.try_swap_cyclic(
+        &ctx.user1,
+        &swap_amount,
+        &(profit + 1),
+        &cycle,
+        &ctx.deadline(),
+    );
+    assert_eq!(result, Err(Ok(AstroSwapError::SlippageExceeded)));
+
+    let initial_a = ctx.token_a.balance(&ctx.user1);
+    let amounts =
+        ctx.router
+            .swap_cyclic(&ctx.user1, &swap_amount, &profit, &cycle, &ctx.deadline());
+    assert_eq!(amounts.get(3).unwrap(), expected_a);
+    assert_eq!(ctx.token_a.balance(&ctx.user1) - initial_a, profit);
+}
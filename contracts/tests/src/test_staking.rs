@@ -8,25 +8,21 @@
 //! - Verify reward calculations
 //! - Test multipliers and compounding
 
-use crate::test_utils::{assert_approx_eq, TestContext};
-use astroswap_shared::PairClient;
+use crate::scenario::{Scenario, Token};
+use crate::test_utils::assert_approx_eq;
+use astroswap_shared::{AstroSwapError, PairClient, PoolMetadata, StakingClient};
+use soroban_sdk::{testutils::Address as _, Address, Bytes, BytesN, String, Symbol, Vec};
 
 #[test]
 fn test_complete_staking_flow() {
-    let ctx = TestContext::new();
-
     // Step 1: Create pair and add liquidity
-    let pair_address = ctx.setup_pair(
-        &ctx.token_a_address,
-        &ctx.token_b_address,
-        10_000_0000000,
-        20_000_0000000,
-    );
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+    let pair_address = ctx.pair();
 
     let pair_client = PairClient::new(&ctx.env, &pair_address);
 
     // User1 adds more liquidity to get LP tokens
-    let (_, _, lp_tokens) = ctx
+    let (_, _, lp_tokens, _) = ctx
         .router
         .add_liquidity(
             &ctx.user1,
@@ -137,72 +133,21 @@ fn test_complete_staking_flow() {
 
 #[test]
 fn test_multiple_stakers_share_rewards() {
-    let ctx = TestContext::new();
-
-    // Setup pair
-    let pair_address = ctx.setup_pair(
-        &ctx.token_a_address,
-        &ctx.token_b_address,
-        10_000_0000000,
-        20_000_0000000,
-    );
-
-    let pair_client = PairClient::new(&ctx.env, &pair_address);
-
-    // User1 adds liquidity for 100 LP
-    let (_, _, lp1) = ctx
-        .router
-        .add_liquidity(
-            &ctx.user1,
-            &ctx.token_a_address,
-            &ctx.token_b_address,
-            &1_000_0000000i128,
-            &2_000_0000000i128,
-            &0,
-            &0,
-            &ctx.deadline(),
-        );
-
-    // User2 adds liquidity for 200 LP (2x more)
-    let (_, _, lp2) = ctx
-        .router
-        .add_liquidity(
-            &ctx.user2,
-            &ctx.token_a_address,
-            &ctx.token_b_address,
-            &2_000_0000000i128,
-            &4_000_0000000i128,
-            &0,
-            &0,
-            &ctx.deadline(),
-        );
-
-    // Create pool
+    // Setup pair and a 1 hour pool
     let reward_per_second = 30_0000000i128;
-    let start_time = ctx.timestamp();
-    let end_time = start_time + 3600;
-
-    let pool_id = ctx
-        .staking
-        .create_pool(
-            &ctx.admin,
-            &pair_address,
-            &reward_per_second,
-            &start_time,
-            &end_time,
-        );
-
-    // Fund rewards
     let total_rewards = reward_per_second * 3600;
-    ctx.xlm
-        .transfer(&ctx.admin, &ctx.staking_address, &total_rewards);
+    let ctx = Scenario::new()
+        .with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000))
+        .with_staking_pool(reward_per_second, 3600);
+    let pool_id = ctx.pool();
 
-    // Both stake at the same time
-    ctx.staking
-        .stake(&ctx.user1, &pool_id, &lp1);
+    // User1 adds liquidity for 100 LP, User2 for 200 LP (2x more)
+    let lp1 = ctx.add_liquidity(&ctx.user1, (1_000_0000000, 2_000_0000000));
+    let lp2 = ctx.add_liquidity(&ctx.user2, (2_000_0000000, 4_000_0000000));
 
-    ctx.staking
-        .stake(&ctx.user2, &pool_id, &lp2);
+    // Both stake at the same time
+    assert_eq!(ctx.stake_all(&ctx.user1), lp1);
+    assert_eq!(ctx.stake_all(&ctx.user2), lp2);
 
     // Advance time
     ctx.advance_time(3600);
@@ -233,71 +178,24 @@ fn test_multiple_stakers_share_rewards() {
 
 #[test]
 fn test_staking_after_pool_starts() {
-    let ctx = TestContext::new();
-
-    // Setup pair and liquidity
-    let pair_address = ctx.setup_pair(
-        &ctx.token_a_address,
-        &ctx.token_b_address,
-        10_000_0000000,
-        20_000_0000000,
-    );
-
-    let (_, _, lp1) = ctx
-        .router
-        .add_liquidity(
-            &ctx.user1,
-            &ctx.token_a_address,
-            &ctx.token_b_address,
-            &1_000_0000000i128,
-            &2_000_0000000i128,
-            &0,
-            &0,
-            &ctx.deadline(),
-        );
-
-    let (_, _, lp2) = ctx
-        .router
-        .add_liquidity(
-            &ctx.user2,
-            &ctx.token_a_address,
-            &ctx.token_b_address,
-            &1_000_0000000i128,
-            &2_000_0000000i128,
-            &0,
-            &0,
-            &ctx.deadline(),
-        );
-
-    // Create pool
+    // Setup pair, liquidity and a 2 hour pool
     let reward_per_second = 10_0000000i128;
-    let start_time = ctx.timestamp();
-    let end_time = start_time + 7200; // 2 hours
+    let ctx = Scenario::new()
+        .with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000))
+        .with_staking_pool(reward_per_second, 7200);
+    let pool_id = ctx.pool();
 
-    let pool_id = ctx
-        .staking
-        .create_pool(
-            &ctx.admin,
-            &pair_address,
-            &reward_per_second,
-            &start_time,
-            &end_time,
-        );
-
-    let rewards_amount = reward_per_second * 7200;
-    ctx.xlm
-        .transfer(&ctx.admin, &ctx.staking_address, &rewards_amount);
+    let lp1 = ctx.add_liquidity(&ctx.user1, (1_000_0000000, 2_000_0000000));
+    let lp2 = ctx.add_liquidity(&ctx.user2, (1_000_0000000, 2_000_0000000));
 
     // User1 stakes immediately
-    ctx.staking
-        .stake(&ctx.user1, &pool_id, &lp1);
+    ctx.stake_all(&ctx.user1);
 
     // Advance 1 hour
     ctx.advance_time(3600);
 
     // User2 stakes after 1 hour
-    ctx.staking
-        .stake(&ctx.user2, &pool_id, &lp2);
+    ctx.stake_all(&ctx.user2);
 
     // Advance another hour
     ctx.advance_time(3600);
@@ -330,24 +228,10 @@ fn test_staking_after_pool_starts() {
 
 #[test]
 fn test_cannot_stake_zero() {
-    let ctx = TestContext::new();
-
-    let pair_address = ctx.setup_pair(
-        &ctx.token_a_address,
-        &ctx.token_b_address,
-        10_000_0000000,
-        20_000_0000000,
-    );
-
-    let pool_id = ctx
-        .staking
-        .create_pool(
-            &ctx.admin,
-            &pair_address,
-            &10_0000000i128,
-            &ctx.timestamp(),
-            &(ctx.timestamp() + 3600),
-        );
+    let ctx = Scenario::new()
+        .with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000))
+        .with_staking_pool(10_0000000, 3600);
+    let pool_id = ctx.pool();
 
     let result = ctx.staking.try_stake(&ctx.user1, &pool_id, &0i128);
 
@@ -356,24 +240,10 @@ fn test_cannot_stake_zero() {
 
 #[test]
 fn test_cannot_claim_with_no_stake() {
-    let ctx = TestContext::new();
-
-    let pair_address = ctx.setup_pair(
-        &ctx.token_a_address,
-        &ctx.token_b_address,
-        10_000_0000000,
-        20_000_0000000,
-    );
-
-    let pool_id = ctx
-        .staking
-        .create_pool(
-            &ctx.admin,
-            &pair_address,
-            &10_0000000i128,
-            &ctx.timestamp(),
-            &(ctx.timestamp() + 3600),
-        );
+    let ctx = Scenario::new()
+        .with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000))
+        .with_staking_pool(10_0000000, 3600);
+    let pool_id = ctx.pool();
 
     // Try to claim without staking
     let result = ctx.staking.try_claim_rewards(&ctx.user1, &pool_id);
@@ -382,45 +252,49 @@ fn test_cannot_claim_with_no_stake() {
 }
 
 #[test]
-fn test_rewards_stop_at_end_time() {
-    let ctx = TestContext::new();
+fn test_shared_staking_client_surfaces_errors() {
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+    let pair_address = ctx.pair();
 
-    // Setup
-    let pair_address = ctx.setup_pair(
-        &ctx.token_a_address,
-        &ctx.token_b_address,
-        10_000_0000000,
-        20_000_0000000,
+    let client = StakingClient::new(&ctx.env, &ctx.staking_address);
+    let pool_id = client
+        .try_create_pool(
+            &ctx.admin,
+            &pair_address,
+            10_0000000,
+            ctx.timestamp(),
+            ctx.timestamp() + 3600,
+        )
+        .unwrap();
+    assert_eq!(client.pool_count(), pool_id);
+    assert_eq!(client.pool_info(pool_id).lp_token, pair_address);
+
+    assert_eq!(
+        client.try_stake(&ctx.user1, pool_id, 0),
+        Err(AstroSwapError::InvalidAmount)
     );
+    assert_eq!(
+        client.try_pool_info(pool_id + 1),
+        Err(AstroSwapError::StakingPoolNotFound)
+    );
+    assert_eq!(
+        client.try_create_pool(&ctx.admin, &pair_address, 1, 10, 10),
+        Err(AstroSwapError::InvalidStakingPeriod)
+    );
+}
 
-    let (_, _, lp_tokens) = ctx
-        .router
-        .add_liquidity(
-            &ctx.user1,
-            &ctx.token_a_address,
-            &ctx.token_b_address,
-            &1_000_0000000i128,
-            &2_000_0000000i128,
-            &0,
-            &0,
-            &ctx.deadline(),
-        );
-
+#[test]
+fn test_rewards_stop_at_end_time() {
+    // Setup a 1 hour pool
     let reward_per_second = 10_0000000i128;
-    let start_time = ctx.timestamp();
     let duration = 3600u64; // 1 hour
-    let end_time = start_time + duration;
+    let ctx = Scenario::new()
+        .with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000))
+        .with_staking_pool(reward_per_second, duration);
+    let pool_id = ctx.pool();
 
-    let pool_id = ctx
-        .staking
-        .create_pool(&ctx.admin, &pair_address, &reward_per_second, &start_time, &end_time);
-
-    let total_fund = reward_per_second * duration as i128;
-    ctx.xlm
-        .transfer(&ctx.admin, &ctx.staking_address, &total_fund);
-
-    ctx.staking
-        .stake(&ctx.user1, &pool_id, &lp_tokens);
+    ctx.add_liquidity(&ctx.user1, (1_000_0000000, 2_000_0000000));
+    ctx.stake_all(&ctx.user1);
 
     // Advance past end time (2 hours instead of 1)
     ctx.advance_time(7200);
@@ -445,47 +319,262 @@ fn test_rewards_stop_at_end_time() {
 }
 
 #[test]
-fn test_partial_unstake() {
-    let ctx = TestContext::new();
+fn test_projected_rewards_at_future_time() {
+    // Setup a 10 day pool with a single staker
+    let reward_per_second = 1_0000000i128;
+    let day = 86_400u64;
+    let ctx = Scenario::new()
+        .with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000))
+        .with_staking_pool(reward_per_second, 10 * day);
+    let pool_id = ctx.pool();
+
+    ctx.add_liquidity(&ctx.user1, (1_000_0000000, 2_000_0000000));
+    ctx.stake_all(&ctx.user1);
+    let now = ctx.timestamp();
+
+    // Past timestamps give the current pending rewards
+    assert_eq!(
+        ctx.staking
+            .projected_rewards(&ctx.user1, &pool_id, &(now - 1)),
+        ctx.staking.pending_rewards(&ctx.user1, &pool_id)
+    );
+
+    // Within the first week the multiplier is 1x
+    let day_one = ctx
+        .staking
+        .projected_rewards(&ctx.user1, &pool_id, &(now + day));
+    assert_approx_eq(day_one, reward_per_second * day as i128, 10);
+
+    // After a week the stake has reached the 1.1x multiplier
+    let day_eight = ctx
+        .staking
+        .projected_rewards(&ctx.user1, &pool_id, &(now + 8 * day));
+    assert_approx_eq(
+        day_eight,
+        reward_per_second * (8 * day) as i128 * 11 / 10,
+        10,
+    );
+
+    // Emission stops at end time, the multiplier keeps growing (1.2x at day 20)
+    let day_twenty = ctx
+        .staking
+        .projected_rewards(&ctx.user1, &pool_id, &(now + 20 * day));
+    assert_approx_eq(
+        day_twenty,
+        reward_per_second * (10 * day) as i128 * 12 / 10,
+        10,
+    );
+
+    // The projection is what becomes pending once the time has passed
+    ctx.advance_time(8 * day);
+    assert_eq!(ctx.staking.pending_rewards(&ctx.user1, &pool_id), day_eight);
+
+    // Unknown pools and stakers are rejected
+    assert_eq!(
+        ctx.staking
+            .try_projected_rewards(&ctx.user1, &(pool_id + 1), &now),
+        Err(Ok(AstroSwapError::StakingPoolNotFound))
+    );
+    assert_eq!(
+        ctx.staking
+            .try_projected_rewards(&Address::generate(&ctx.env), &pool_id, &now),
+        Err(Ok(AstroSwapError::StakeNotFound))
+    );
+}
 
-    let pair_address = ctx.setup_pair(
+#[test]
+fn test_pool_checkpoints_and_historical_apr() {
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+    let pair_address = ctx.pair();
+    let (_, _, lp_tokens, _) = ctx.router.add_liquidity(
+        &ctx.user1,
         &ctx.token_a_address,
         &ctx.token_b_address,
-        10_000_0000000,
-        20_000_0000000,
+        &1_000_0000000i128,
+        &2_000_0000000i128,
+        &0,
+        &0,
+        &ctx.deadline(),
     );
 
-    let (_, _, lp_tokens) = ctx
-        .router
-        .add_liquidity(
-            &ctx.user1,
+    let reward_per_second = 10_0000000i128;
+    let start_time = ctx.timestamp();
+    let end_time = start_time + 30 * 86_400;
+    let pool_id = ctx.staking.create_pool(
+        &ctx.admin,
+        &pair_address,
+        &reward_per_second,
+        &start_time,
+        &end_time,
+    );
+
+    // The first interaction of an epoch checkpoints the pool
+    ctx.staking.stake(&ctx.user1, &pool_id, &lp_tokens);
+    let first_epoch = ctx.staking.current_epoch();
+    let first = ctx.staking.pool_checkpoint(&pool_id, &first_epoch);
+    assert_eq!(first.total_staked, 0);
+    assert_eq!(first.acc_reward_per_share, 0);
+
+    // A keeper checkpoints the next epoch without any user interaction
+    ctx.advance_time(86_400);
+    let second_epoch = ctx.staking.checkpoint_pool(&pool_id);
+    assert_eq!(second_epoch, first_epoch + 1);
+    let second = ctx.staking.pool_checkpoint(&pool_id, &second_epoch);
+    assert_eq!(second.total_staked, lp_tokens);
+    assert!(second.acc_reward_per_share > 0);
+
+    // Annualized reward tokens per staked LP token
+    let apr = ctx
+        .staking
+        .historical_apr_bps(&pool_id, &first_epoch, &second_epoch);
+    let expected = reward_per_second * 31_536_000 * 10_000 / lp_tokens;
+    assert_approx_eq(apr, expected, 1);
+
+    assert!(ctx
+        .staking
+        .try_pool_checkpoint(&pool_id, &(second_epoch + 1))
+        .is_err());
+    assert!(ctx
+        .staking
+        .try_historical_apr_bps(&pool_id, &second_epoch, &first_epoch)
+        .is_err());
+}
+
+#[test]
+fn test_pool_migration_settles_rewards() {
+    use astroswap_staking::MigrationParams;
+
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+    let pair_address = ctx.pair();
+    let mut lp_tokens = [0i128; 2];
+    for (i, user) in [&ctx.user1, &ctx.user2].into_iter().enumerate() {
+        let (_, _, lp, _) = ctx.router.add_liquidity(
+            user,
             &ctx.token_a_address,
             &ctx.token_b_address,
-            &5_000_0000000i128,
-            &10_000_0000000i128,
+            &1_000_0000000i128,
+            &2_000_0000000i128,
             &0,
             &0,
             &ctx.deadline(),
         );
+        lp_tokens[i] = lp;
+    }
 
     let start_time = ctx.timestamp();
-    let end_time = start_time + 3600;
-    let pool_id = ctx
-        .staking
-        .create_pool(
-            &ctx.admin,
-            &pair_address,
-            &10_0000000i128,
-            &start_time,
-            &end_time,
-        );
-
-    let fund_amount = 10_0000000i128 * 3600;
+    let end_time = start_time + 30 * 86_400;
+    let pool_id = ctx.staking.create_pool(
+        &ctx.admin,
+        &pair_address,
+        &10_0000000i128,
+        &start_time,
+        &end_time,
+    );
     ctx.xlm
-        .transfer(&ctx.admin, &ctx.staking_address, &fund_amount);
+        .transfer(&ctx.admin, &ctx.staking_address, &100_000_0000000);
+    ctx.token_c
+        .transfer(&ctx.admin, &ctx.staking_address, &500_000_0000000);
 
-    ctx.staking
-        .stake(&ctx.user1, &pool_id, &lp_tokens);
+    ctx.staking.stake(&ctx.user1, &pool_id, &lp_tokens[0]);
+    ctx.staking.stake(&ctx.user2, &pool_id, &lp_tokens[1]);
+    let first_epoch = ctx.staking.current_epoch();
+
+    ctx.advance_time(3600);
+    let pending_1 = ctx.staking.pending_rewards(&ctx.user1, &pool_id);
+    let pending_2 = ctx.staking.pending_rewards(&ctx.user2, &pool_id);
+    assert!(pending_1 > 0 && pending_2 > 0);
+
+    // Switch the pool to a new reward token and a finer precision
+    let params = MigrationParams {
+        reward_token: ctx.token_c_address.clone(),
+        reward_per_second: 5_0000000,
+        end_time,
+        reward_precision: 1_000_000_000_000_000_000,
+    };
+    assert_eq!(
+        ctx.staking.try_migrate_pool(&ctx.user1, &pool_id, &params),
+        Err(Ok(AstroSwapError::Unauthorized))
+    );
+    let invalid = MigrationParams {
+        reward_precision: 0,
+        ..params.clone()
+    };
+    assert_eq!(
+        ctx.staking.try_migrate_pool(&ctx.admin, &pool_id, &invalid),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+    assert_eq!(ctx.staking.migrate_pool(&ctx.admin, &pool_id, &params), 1);
+
+    let pool = ctx.staking.pool_info(&pool_id);
+    assert_eq!(pool.reward_token, ctx.token_c_address);
+    assert_eq!(pool.acc_reward_per_share, 0);
+    assert_eq!(ctx.staking.pool_generation(&pool_id), 1);
+    let record = ctx.staking.pool_migration(&pool_id, &0);
+    assert_eq!(record.reward_token, ctx.xlm_address);
+    assert!(record.acc_reward_per_share > 0);
+
+    // Old-generation rewards are no longer pending against the new accumulator
+    assert_eq!(ctx.staking.pending_rewards(&ctx.user1, &pool_id), 0);
+
+    // Anyone can settle a stake into the claimable ledger, once
+    assert_eq!(ctx.staking.settle_rewards(&ctx.user2, &pool_id), pending_2);
+    assert_eq!(ctx.staking.settle_rewards(&ctx.user2, &pool_id), 0);
+    assert_eq!(
+        ctx.staking.settled_rewards(&ctx.user2, &ctx.xlm_address),
+        pending_2
+    );
+
+    // New-generation rewards accrue in the new token
+    ctx.advance_time(86_400);
+    let second_epoch = ctx.staking.checkpoint_pool(&pool_id);
+    let pending_c = ctx.staking.pending_rewards(&ctx.user1, &pool_id);
+    assert_approx_eq(
+        pending_c,
+        5_0000000 * 86_400 * lp_tokens[0] / (lp_tokens[0] + lp_tokens[1]),
+        1,
+    );
+
+    // Claiming settles user1's old generation and pays the new one in token C
+    let c_before = ctx.token_c.balance(&ctx.user1);
+    let claimed = ctx.staking.claim_rewards(&ctx.user1, &pool_id);
+    assert_eq!(claimed, pending_c);
+    assert_eq!(ctx.token_c.balance(&ctx.user1) - c_before, claimed);
+    assert_eq!(
+        ctx.staking.settled_rewards(&ctx.user1, &ctx.xlm_address),
+        pending_1
+    );
+
+    // Settled rewards are paid in the old reward token
+    let xlm_before = ctx.xlm.balance(&ctx.user1);
+    assert_eq!(
+        ctx.staking
+            .claim_settled_rewards(&ctx.user1, &ctx.xlm_address),
+        pending_1
+    );
+    assert_eq!(ctx.xlm.balance(&ctx.user1) - xlm_before, pending_1);
+    assert_eq!(
+        ctx.staking
+            .try_claim_settled_rewards(&ctx.user1, &ctx.xlm_address),
+        Err(Ok(AstroSwapError::NoRewardsAvailable))
+    );
+
+    // Checkpoints of different generations are not comparable
+    assert_eq!(
+        ctx.staking
+            .try_historical_apr_bps(&pool_id, &first_epoch, &second_epoch),
+        Err(Ok(AstroSwapError::PoolMigrated))
+    );
+}
+
+#[test]
+fn test_partial_unstake() {
+    let ctx = Scenario::new()
+        .with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000))
+        .with_staking_pool(10_0000000, 3600);
+    let pool_id = ctx.pool();
+
+    let lp_tokens = ctx.add_liquidity(&ctx.user1, (5_000_0000000, 10_000_0000000));
+    ctx.stake_all(&ctx.user1);
 
     // Advance time
     ctx.advance_time(1800); // 30 minutes
@@ -524,3 +613,794 @@ fn test_partial_unstake() {
         max_expected
     );
 }
+
+#[test]
+fn test_pool_apr_and_tvl_views() {
+    use crate::mock_oracle::{MockOracle, MockOracleClient};
+
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+    let pair_address = ctx.pair();
+    let (_, _, lp_tokens, _) = ctx.router.add_liquidity(
+        &ctx.user1,
+        &ctx.token_a_address,
+        &ctx.token_b_address,
+        &1_000_0000000i128,
+        &2_000_0000000i128,
+        &0,
+        &0,
+        &ctx.deadline(),
+    );
+
+    let reward_per_second = 10_0000000i128;
+    let start_time = ctx.timestamp();
+    let end_time = start_time + 30 * 86_400;
+    let pool_id = ctx.staking.create_pool(
+        &ctx.admin,
+        &pair_address,
+        &reward_per_second,
+        &start_time,
+        &end_time,
+    );
+
+    // $2 per LP token, $0.50 per reward token (7 decimals)
+    let lp_price = 2_0000000i128;
+    let reward_price = 5000000i128;
+
+    // Nothing staked yet
+    assert_eq!(ctx.staking.pool_tvl(&pool_id, &lp_price), 0);
+    assert_eq!(ctx.staking.pool_apr(&pool_id, &lp_price, &reward_price), 0);
+
+    ctx.staking.stake(&ctx.user1, &pool_id, &lp_tokens);
+
+    let tvl = ctx.staking.pool_tvl(&pool_id, &lp_price);
+    assert_eq!(tvl, lp_tokens * 2);
+    let apr = ctx.staking.pool_apr(&pool_id, &lp_price, &reward_price);
+    let yearly_rewards_usd = reward_per_second * 31_536_000 / 2;
+    assert_approx_eq(apr, yearly_rewards_usd * 10_000 / tvl, 1);
+
+    // Oracle prices (8 decimals) give the same figures
+    let oracle = ctx.env.register(MockOracle, ());
+    let oracle_client = MockOracleClient::new(&ctx.env, &oracle);
+    oracle_client.set_price(&pair_address, &2_00000000);
+    let reward_token = ctx.staking.pool_info(&pool_id).reward_token;
+    assert!(ctx
+        .staking
+        .try_pool_apr_from_oracle(&pool_id, &oracle)
+        .is_err());
+    oracle_client.set_price(&reward_token, &50000000);
+    assert_eq!(ctx.staking.pool_tvl_from_oracle(&pool_id, &oracle), tvl);
+    assert_eq!(ctx.staking.pool_apr_from_oracle(&pool_id, &oracle), apr);
+
+    // Prices must be positive and the pool must exist
+    assert!(ctx.staking.try_pool_tvl(&pool_id, &0).is_err());
+    assert!(ctx.staking.try_pool_apr(&pool_id, &lp_price, &-1).is_err());
+    assert!(ctx.staking.try_pool_tvl(&(pool_id + 1), &lp_price).is_err());
+
+    // No emissions once the pool has ended
+    ctx.advance_time(31 * 86_400);
+    assert_eq!(ctx.staking.pool_apr(&pool_id, &lp_price, &reward_price), 0);
+    assert_eq!(ctx.staking.pool_tvl(&pool_id, &lp_price), tvl);
+}
+
+#[test]
+fn test_pool_deposit_fee_and_cooldown() {
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+    let pair_address = ctx.pair();
+    let (_, _, lp_tokens, _) = ctx.router.add_liquidity(
+        &ctx.user1,
+        &ctx.token_a_address,
+        &ctx.token_b_address,
+        &1_000_0000000i128,
+        &2_000_0000000i128,
+        &0,
+        &0,
+        &ctx.deadline(),
+    );
+    let lp = PairClient::new(&ctx.env, &pair_address);
+
+    let start_time = ctx.timestamp();
+    let end_time = start_time + 30 * 86_400;
+
+    // Options are bounded
+    assert_eq!(
+        ctx.staking.try_create_pool_with_options(
+            &ctx.admin,
+            &pair_address,
+            &0i128,
+            &start_time,
+            &end_time,
+            &101,
+            &0,
+        ),
+        Err(Ok(AstroSwapError::FeeTooHigh))
+    );
+    assert_eq!(
+        ctx.staking.try_create_pool_with_options(
+            &ctx.admin,
+            &pair_address,
+            &0i128,
+            &start_time,
+            &end_time,
+            &0,
+            &(31 * 86_400),
+        ),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+
+    // 0.5% deposit fee, 6 hour cooldown
+    let pool_id = ctx.staking.create_pool_with_options(
+        &ctx.admin,
+        &pair_address,
+        &0i128,
+        &start_time,
+        &end_time,
+        &50,
+        &(6 * 3600),
+    );
+    let options = ctx.staking.pool_options(&pool_id);
+    assert_eq!(options.deposit_fee_bps, 50);
+    assert_eq!(options.cooldown_seconds, 6 * 3600);
+
+    let admin_lp_before = lp.balance(&ctx.admin);
+    let first = lp_tokens / 2;
+    ctx.staking.stake(&ctx.user1, &pool_id, &first);
+
+    // The fee (rounded up) goes to the admin, the rest is staked
+    let fee = (first * 50 + 9_999) / 10_000;
+    assert_eq!(lp.balance(&ctx.admin) - admin_lp_before, fee);
+    assert_eq!(
+        ctx.staking.user_info(&ctx.user1, &pool_id).amount,
+        first - fee
+    );
+    assert_eq!(ctx.staking.pool_info(&pool_id).total_staked, first - fee);
+    assert_eq!(
+        ctx.staking.cooldown_end(&ctx.user1, &pool_id),
+        ctx.timestamp() + 6 * 3600
+    );
+
+    // No withdrawal during the cooldown
+    ctx.advance_time(5 * 3600);
+    assert_eq!(
+        ctx.staking.try_unstake(&ctx.user1, &pool_id, &1),
+        Err(Ok(AstroSwapError::CooldownActive))
+    );
+
+    // A new stake restarts it
+    ctx.staking
+        .stake(&ctx.user1, &pool_id, &(lp_tokens - first));
+    ctx.advance_time(3600);
+    assert_eq!(
+        ctx.staking.try_unstake(&ctx.user1, &pool_id, &1),
+        Err(Ok(AstroSwapError::CooldownActive))
+    );
+
+    ctx.advance_time(5 * 3600);
+    let staked = ctx.staking.user_info(&ctx.user1, &pool_id).amount;
+    ctx.staking.unstake(&ctx.user1, &pool_id, &staked);
+    assert_eq!(lp.balance(&ctx.user1), staked);
+
+    // Pools created without options charge nothing and have no cooldown
+    let plain_pool =
+        ctx.staking
+            .create_pool(&ctx.admin, &pair_address, &0i128, &start_time, &end_time);
+    assert_eq!(ctx.staking.pool_options(&plain_pool).deposit_fee_bps, 0);
+    ctx.staking.stake(&ctx.user1, &plain_pool, &staked);
+    assert_eq!(ctx.staking.cooldown_end(&ctx.user1, &plain_pool), 0);
+    ctx.staking.unstake(&ctx.user1, &plain_pool, &staked);
+    assert_eq!(lp.balance(&ctx.user1), staked);
+}
+
+#[test]
+fn test_pool_metadata() {
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+    let pair_address = ctx.pair();
+    let start_time = ctx.timestamp();
+    let end_time = start_time + 30 * 86_400;
+    let metadata = PoolMetadata {
+        name: String::from_str(&ctx.env, "TKA-TKB"),
+        tag: Symbol::new(&ctx.env, "featured"),
+        url_hash: Some(BytesN::from_array(&ctx.env, &[7; 32])),
+    };
+
+    // Names are 1 to 32 bytes
+    for name in ["", "A-VERY-LONG-LP-TOKEN-NAME-OVER-32"] {
+        let invalid = PoolMetadata {
+            name: String::from_str(&ctx.env, name),
+            ..metadata.clone()
+        };
+        assert_eq!(
+            ctx.staking.try_create_pool_with_metadata(
+                &ctx.admin,
+                &pair_address,
+                &0i128,
+                &start_time,
+                &end_time,
+                &invalid,
+            ),
+            Err(Ok(AstroSwapError::InvalidArgument))
+        );
+    }
+
+    // Returned with the pool; pools created without it have none
+    let pool_id = ctx.staking.create_pool_with_metadata(
+        &ctx.admin,
+        &pair_address,
+        &0i128,
+        &start_time,
+        &end_time,
+        &metadata,
+    );
+    assert_eq!(
+        ctx.staking.pool_info(&pool_id).metadata,
+        Some(metadata.clone())
+    );
+    let plain_pool =
+        ctx.staking
+            .create_pool(&ctx.admin, &pair_address, &0i128, &start_time, &end_time);
+    assert_eq!(ctx.staking.pool_info(&plain_pool).metadata, None);
+
+    // Only the admin edits it after creation
+    assert_eq!(
+        ctx.staking
+            .try_set_pool_metadata(&ctx.user1, &plain_pool, &Some(metadata.clone())),
+        Err(Ok(AstroSwapError::Unauthorized))
+    );
+    ctx.staking
+        .set_pool_metadata(&ctx.admin, &plain_pool, &Some(metadata.clone()));
+    assert_eq!(ctx.staking.pool_info(&plain_pool).metadata, Some(metadata));
+    ctx.staking.set_pool_metadata(&ctx.admin, &pool_id, &None);
+    assert_eq!(ctx.staking.pool_info(&pool_id).metadata, None);
+    assert_eq!(
+        ctx.staking.try_set_pool_metadata(&ctx.admin, &99, &None),
+        Err(Ok(AstroSwapError::StakingPoolNotFound))
+    );
+}
+
+#[test]
+fn test_pool_emission_limits_and_unfunded_claims() {
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+    let pair_address = ctx.pair();
+    let start_time = ctx.timestamp();
+    let end_time = start_time + 3600;
+
+    // Rates are bounded by the per-pool emission cap
+    assert_eq!(
+        ctx.staking.try_create_pool(
+            &ctx.admin,
+            &pair_address,
+            &(1_000_000_0000000i128 + 1),
+            &start_time,
+            &end_time,
+        ),
+        Err(Ok(AstroSwapError::EmissionRateTooHigh))
+    );
+    assert_eq!(
+        ctx.staking
+            .try_create_pool(&ctx.admin, &pair_address, &-1i128, &start_time, &end_time),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+
+    // Funded for half of its emission
+    let reward_per_second = 10_0000000i128;
+    let pool_id = ctx.staking.create_pool(
+        &ctx.admin,
+        &pair_address,
+        &reward_per_second,
+        &start_time,
+        &end_time,
+    );
+    assert_eq!(
+        ctx.staking
+            .try_update_pool_rewards(&ctx.admin, &pool_id, &(1_000_000_0000000i128 + 1)),
+        Err(Ok(AstroSwapError::EmissionRateTooHigh))
+    );
+    ctx.xlm.transfer(
+        &ctx.admin,
+        &ctx.staking_address,
+        &(reward_per_second * 1800),
+    );
+
+    ctx.add_liquidity(&ctx.user1, (1_000_0000000, 2_000_0000000));
+    let lp = PairClient::new(&ctx.env, &pair_address);
+    ctx.staking
+        .stake(&ctx.user1, &pool_id, &lp.balance(&ctx.user1));
+    ctx.advance_time(3600);
+
+    assert_eq!(
+        ctx.staking.try_claim_rewards(&ctx.user1, &pool_id),
+        Err(Ok(AstroSwapError::RewardsUnfunded))
+    );
+
+    // Topping up the pool makes the claim go through
+    ctx.xlm.transfer(
+        &ctx.admin,
+        &ctx.staking_address,
+        &(reward_per_second * 3600),
+    );
+    let claimed = ctx.staking.claim_rewards(&ctx.user1, &pool_id);
+    assert!(claimed > reward_per_second * 1800);
+}
+
+#[test]
+fn test_keeper_claims_and_compounds_for_user() {
+    use astroswap_staking::MigrationParams;
+
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+    let pair_address = ctx.pair();
+    let lp = PairClient::new(&ctx.env, &pair_address);
+    ctx.add_liquidity(&ctx.user1, (1_000_0000000, 2_000_0000000));
+    ctx.add_liquidity(&ctx.user2, (1_000_0000000, 2_000_0000000));
+    let keeper = Address::generate(&ctx.env);
+
+    let start_time = ctx.timestamp();
+    let end_time = start_time + 30 * 86_400;
+    let pool_id = ctx.staking.create_pool(
+        &ctx.admin,
+        &pair_address,
+        &10_0000000i128,
+        &start_time,
+        &end_time,
+    );
+    ctx.xlm
+        .transfer(&ctx.admin, &ctx.staking_address, &100_000_0000000);
+    let staked = lp.balance(&ctx.user1);
+    ctx.staking.stake(&ctx.user1, &pool_id, &staked);
+    ctx.advance_time(3600);
+
+    // Only an approved claimer can claim for the user
+    assert_eq!(
+        ctx.staking
+            .try_claim_rewards_for(&keeper, &ctx.user1, &pool_id),
+        Err(Ok(AstroSwapError::Unauthorized))
+    );
+    ctx.staking.approve_claimer(&ctx.user1, &pool_id, &keeper);
+    assert_eq!(
+        ctx.staking.claimer(&ctx.user1, &pool_id),
+        Some(keeper.clone())
+    );
+    assert_eq!(
+        ctx.staking
+            .try_claim_rewards_for(&ctx.user2, &ctx.user1, &pool_id),
+        Err(Ok(AstroSwapError::Unauthorized))
+    );
+
+    // Rewards go to the user, never to the keeper
+    let user_xlm_before = ctx.xlm.balance(&ctx.user1);
+    let claimed = ctx.staking.claim_rewards_for(&keeper, &ctx.user1, &pool_id);
+    assert!(claimed > 0);
+    assert_eq!(ctx.xlm.balance(&ctx.user1) - user_xlm_before, claimed);
+    assert_eq!(ctx.xlm.balance(&keeper), 0);
+    assert_eq!(
+        ctx.staking.try_compound_for(&keeper, &ctx.user1, &pool_id),
+        Err(Ok(AstroSwapError::CompoundNotSupported))
+    );
+
+    // Once the pool pays out in its own LP token, the keeper can compound
+    ctx.staking.migrate_pool(
+        &ctx.admin,
+        &pool_id,
+        &MigrationParams {
+            reward_token: pair_address.clone(),
+            reward_per_second: 1_0000,
+            end_time,
+            reward_precision: 1_000_000_000_000,
+        },
+    );
+    lp.transfer(&ctx.user2, &ctx.staking_address, &100_0000000);
+    ctx.advance_time(3600);
+
+    let compounded = ctx.staking.compound_for(&keeper, &ctx.user1, &pool_id);
+    assert!(compounded > 0);
+    assert_eq!(
+        ctx.staking.user_info(&ctx.user1, &pool_id).amount,
+        staked + compounded
+    );
+    assert_eq!(
+        ctx.staking.pool_info(&pool_id).total_staked,
+        staked + compounded
+    );
+    assert_eq!(lp.balance(&ctx.user1), 0);
+    assert_eq!(lp.balance(&keeper), 0);
+
+    // Revoking cuts the keeper off
+    ctx.staking.revoke_claimer(&ctx.user1, &pool_id);
+    assert_eq!(ctx.staking.claimer(&ctx.user1, &pool_id), None);
+    ctx.advance_time(3600);
+    assert_eq!(
+        ctx.staking.try_compound_for(&keeper, &ctx.user1, &pool_id),
+        Err(Ok(AstroSwapError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_expired_rewards_swept_to_treasury() {
+    use astroswap_staking::MIN_CLAIM_GRACE_SECONDS;
+
+    let reward_per_second = 10_0000000i128;
+    let duration = 3600u64;
+    let ctx = Scenario::new()
+        .with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000))
+        .with_staking_pool(reward_per_second, duration);
+    let pool_id = ctx.pool();
+    let end_time = ctx.timestamp() + duration;
+    let treasury = Address::generate(&ctx.env);
+
+    ctx.add_liquidity(&ctx.user1, (1_000_0000000, 2_000_0000000));
+    ctx.add_liquidity(&ctx.user2, (1_000_0000000, 2_000_0000000));
+    ctx.stake_all(&ctx.user1);
+    ctx.stake_all(&ctx.user2);
+    ctx.advance_time(duration);
+    ctx.staking.claim_rewards(&ctx.user1, &pool_id);
+
+    // Stakers always get the grace period after the pool's end
+    assert_eq!(
+        ctx.staking.try_set_claim_deadline(
+            &ctx.admin,
+            &pool_id,
+            &(end_time + MIN_CLAIM_GRACE_SECONDS - 1)
+        ),
+        Err(Ok(AstroSwapError::InvalidArgument))
+    );
+    let claim_deadline = end_time + MIN_CLAIM_GRACE_SECONDS;
+    ctx.staking
+        .set_claim_deadline(&ctx.admin, &pool_id, &claim_deadline);
+    assert_eq!(ctx.staking.claim_deadline(&pool_id), Some(claim_deadline));
+
+    // Nothing can be swept before the deadline, nor without a treasury
+    assert_eq!(
+        ctx.staking.try_sweep_expired_rewards(&pool_id),
+        Err(Ok(AstroSwapError::ClaimPeriodActive))
+    );
+    ctx.advance_time(MIN_CLAIM_GRACE_SECONDS);
+    assert_eq!(
+        ctx.staking.try_sweep_expired_rewards(&pool_id),
+        Err(Ok(AstroSwapError::FeeRecipientNotSet))
+    );
+    ctx.staking.set_treasury(&ctx.admin, &treasury);
+
+    // user2 never claimed: their half of the emission expired
+    assert_eq!(
+        ctx.staking.try_claim_rewards(&ctx.user2, &pool_id),
+        Err(Ok(AstroSwapError::ClaimPeriodExpired))
+    );
+    let swept = ctx.staking.sweep_expired_rewards(&pool_id);
+    assert_approx_eq(swept, reward_per_second * duration as i128 / 2, 10);
+    assert_eq!(ctx.xlm.balance(&treasury), swept);
+    let ledger = ctx.staking.reward_ledger(&pool_id);
+    assert_eq!(ledger.accrued, ledger.paid);
+
+    // A pool is swept once and its deadline can no longer move
+    assert_eq!(
+        ctx.staking.try_sweep_expired_rewards(&pool_id),
+        Err(Ok(AstroSwapError::NoRewardsAvailable))
+    );
+    assert_eq!(
+        ctx.staking.try_set_claim_deadline(&ctx.admin, &pool_id, &0),
+        Err(Ok(AstroSwapError::ClaimPeriodExpired))
+    );
+
+    // LP tokens are still returned, without rewards
+    let lp = PairClient::new(&ctx.env, &ctx.pair());
+    let staked = ctx.staking.user_info(&ctx.user2, &pool_id).amount;
+    ctx.staking.unstake(&ctx.user2, &pool_id, &staked);
+    assert_eq!(lp.balance(&ctx.user2), staked);
+    assert_eq!(ctx.xlm.balance(&treasury), swept);
+}
+
+#[test]
+fn test_expired_sweep_capped_at_pool_funding() {
+    use astroswap_staking::MIN_CLAIM_GRACE_SECONDS;
+
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+    let pair_address = ctx.pair();
+    let start_time = ctx.timestamp();
+    let end_time = start_time + 3600;
+    let reward_per_second = 1_0000000i128;
+    let emission = reward_per_second * 3600;
+    let treasury = Address::generate(&ctx.env);
+
+    // Two pools paying the same token: A funded for half its emission, B
+    // enough for its emission at the top multiplier
+    let pool_a = ctx.staking.create_pool(
+        &ctx.admin,
+        &pair_address,
+        &reward_per_second,
+        &start_time,
+        &end_time,
+    );
+    let pool_b = ctx.staking.create_pool(
+        &ctx.admin,
+        &pair_address,
+        &reward_per_second,
+        &start_time,
+        &end_time,
+    );
+    ctx.staking.fund_pool(&ctx.admin, &pool_a, &(emission / 2));
+    ctx.staking
+        .fund_pool(&ctx.admin, &pool_b, &(emission * 13 / 10));
+    assert_eq!(ctx.staking.pool_funding(&pool_a), emission / 2);
+
+    let lp = PairClient::new(&ctx.env, &pair_address);
+    ctx.add_liquidity(&ctx.user1, (1_000_0000000, 2_000_0000000));
+    ctx.add_liquidity(&ctx.user2, (1_000_0000000, 2_000_0000000));
+    ctx.staking
+        .stake(&ctx.user1, &pool_a, &lp.balance(&ctx.user1));
+    ctx.staking
+        .stake(&ctx.user2, &pool_b, &lp.balance(&ctx.user2));
+    ctx.advance_time(3600);
+
+    ctx.staking
+        .set_claim_deadline(&ctx.admin, &pool_a, &(end_time + MIN_CLAIM_GRACE_SECONDS));
+    ctx.staking.set_treasury(&ctx.admin, &treasury);
+    ctx.advance_time(MIN_CLAIM_GRACE_SECONDS);
+
+    // All of A's emission went unclaimed, but only its own funds are swept
+    let swept = ctx.staking.sweep_expired_rewards(&pool_a);
+    assert_eq!(swept, emission / 2);
+    assert_eq!(ctx.xlm.balance(&treasury), swept);
+    assert_eq!(ctx.staking.pool_funding(&pool_a), 0);
+    assert_eq!(ctx.staking.pool_funding(&pool_b), emission * 13 / 10);
+
+    // B's staker is still paid in full
+    let pending = ctx.staking.pending_rewards(&ctx.user2, &pool_b);
+    assert_approx_eq(pending, emission * 13 / 10, 10);
+    assert_eq!(ctx.staking.claim_rewards(&ctx.user2, &pool_b), pending);
+}
+
+#[test]
+fn test_reward_token_migration_clears_pool_funding() {
+    use astroswap_staking::MigrationParams;
+
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+    let pair_address = ctx.pair();
+    let start_time = ctx.timestamp();
+    let end_time = start_time + 30 * 86_400;
+    let pool_id = ctx.staking.create_pool(
+        &ctx.admin,
+        &pair_address,
+        &10_0000000i128,
+        &start_time,
+        &end_time,
+    );
+    ctx.staking.fund_pool(&ctx.admin, &pool_id, &1_000_0000000);
+
+    // Same reward token: the funding carries over
+    ctx.staking.migrate_pool(
+        &ctx.admin,
+        &pool_id,
+        &MigrationParams {
+            reward_token: ctx.xlm_address.clone(),
+            reward_per_second: 5_0000000,
+            end_time,
+            reward_precision: 1_000_000_000_000,
+        },
+    );
+    assert_eq!(ctx.staking.pool_funding(&pool_id), 1_000_0000000);
+
+    // New reward token: XLM funding is not a balance of the LP token
+    ctx.staking.migrate_pool(
+        &ctx.admin,
+        &pool_id,
+        &MigrationParams {
+            reward_token: pair_address.clone(),
+            reward_per_second: 1_0000,
+            end_time,
+            reward_precision: 1_000_000_000_000,
+        },
+    );
+    assert_eq!(ctx.staking.pool_funding(&pool_id), 0);
+}
+
+#[test]
+fn test_retro_drop_claims_with_merkle_proofs() {
+    use astroswap_staking::retro_leaf;
+
+    let ctx = Scenario::new();
+    let env = &ctx.env;
+    let parent = |a: &BytesN<32>, b: &BytesN<32>| {
+        let (first, second) = if a.to_array() <= b.to_array() {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        let mut data = Bytes::from(first.clone());
+        data.append(&Bytes::from(second.clone()));
+        env.crypto().sha256(&data).to_bytes()
+    };
+
+    // Off-chain tree over three compensated users
+    let user3 = Address::generate(env);
+    let leaf_1 = retro_leaf(env, &ctx.user1, 500_0000000);
+    let leaf_2 = retro_leaf(env, &ctx.user2, 300_0000000);
+    let leaf_3 = retro_leaf(env, &user3, 200_0000000);
+    let node_12 = parent(&leaf_1, &leaf_2);
+    let root = parent(&node_12, &leaf_3);
+
+    // Publishing pulls the funding from the admin
+    let staking_before = ctx.token_c.balance(&ctx.staking_address);
+    let drop_id =
+        ctx.staking
+            .publish_retro_drop(&ctx.admin, &root, &ctx.token_c_address, &1_000_0000000);
+    assert_eq!(drop_id, 1);
+    assert_eq!(ctx.staking.retro_drop_count(), 1);
+    assert_eq!(
+        ctx.token_c.balance(&ctx.staking_address) - staking_before,
+        1_000_0000000
+    );
+
+    let user1_before = ctx.token_c.balance(&ctx.user1);
+    let proof_1 = Vec::from_array(env, [leaf_2.clone(), leaf_3.clone()]);
+    let claimed = ctx
+        .staking
+        .claim_retro_reward(&ctx.user1, &drop_id, &500_0000000, &proof_1);
+    assert_eq!(claimed, 500_0000000);
+    assert_eq!(ctx.token_c.balance(&ctx.user1) - user1_before, 500_0000000);
+    assert!(ctx.staking.retro_claimed(&drop_id, &ctx.user1));
+
+    // Each leaf pays once
+    assert_eq!(
+        ctx.staking
+            .try_claim_retro_reward(&ctx.user1, &drop_id, &500_0000000, &proof_1),
+        Err(Ok(AstroSwapError::RetroRewardClaimed))
+    );
+
+    // Another amount, or someone else's proof, does not verify
+    let proof_2 = Vec::from_array(env, [leaf_1.clone(), leaf_3.clone()]);
+    assert_eq!(
+        ctx.staking
+            .try_claim_retro_reward(&ctx.user2, &drop_id, &400_0000000, &proof_2),
+        Err(Ok(AstroSwapError::InvalidMerkleProof))
+    );
+    assert_eq!(
+        ctx.staking
+            .try_claim_retro_reward(&user3, &drop_id, &200_0000000, &proof_2),
+        Err(Ok(AstroSwapError::InvalidMerkleProof))
+    );
+    assert_eq!(
+        ctx.staking
+            .try_claim_retro_reward(&ctx.user2, &(drop_id + 1), &300_0000000, &proof_2),
+        Err(Ok(AstroSwapError::RetroDropNotFound))
+    );
+
+    ctx.staking
+        .claim_retro_reward(&ctx.user2, &drop_id, &300_0000000, &proof_2);
+    let proof_3 = Vec::from_array(env, [node_12]);
+    ctx.staking
+        .claim_retro_reward(&user3, &drop_id, &200_0000000, &proof_3);
+    assert_eq!(ctx.token_c.balance(&user3), 200_0000000);
+
+    let retro_drop = ctx.staking.retro_drop(&drop_id).unwrap();
+    assert_eq!(retro_drop.claimed, retro_drop.total_amount);
+    assert_eq!(ctx.token_c.balance(&ctx.staking_address), staking_before);
+}
+
+#[test]
+fn test_lp_reward_pool_never_pays_from_staked_principal() {
+    use astroswap_staking::MigrationParams;
+
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+    let pair_address = ctx.pair();
+    let lp = PairClient::new(&ctx.env, &pair_address);
+    ctx.add_liquidity(&ctx.user1, (1_000_0000000, 2_000_0000000));
+    ctx.add_liquidity(&ctx.user2, (1_000_0000000, 2_000_0000000));
+
+    // Two pools of the same LP token, the first paying rewards in it
+    let start_time = ctx.timestamp();
+    let end_time = start_time + 30 * 86_400;
+    let lp_rewards = ctx.staking.create_pool(
+        &ctx.admin,
+        &pair_address,
+        &10_0000000i128,
+        &start_time,
+        &end_time,
+    );
+    let other_pool = ctx.staking.create_pool(
+        &ctx.admin,
+        &pair_address,
+        &10_0000000i128,
+        &start_time,
+        &end_time,
+    );
+    ctx.staking.migrate_pool(
+        &ctx.admin,
+        &lp_rewards,
+        &MigrationParams {
+            reward_token: pair_address.clone(),
+            reward_per_second: 1_0000,
+            end_time,
+            reward_precision: 1_000_000_000_000,
+        },
+    );
+    let staked_1 = lp.balance(&ctx.user1);
+    let staked_2 = lp.balance(&ctx.user2) / 2;
+    ctx.staking.stake(&ctx.user1, &lp_rewards, &staked_1);
+    ctx.staking.stake(&ctx.user2, &other_pool, &staked_2);
+    ctx.advance_time(3600);
+
+    // The contract holds only staked principal, which is not a reward fund
+    assert_eq!(
+        ctx.staking.try_claim_rewards(&ctx.user1, &lp_rewards),
+        Err(Ok(AstroSwapError::RewardsUnfunded))
+    );
+    let keeper = Address::generate(&ctx.env);
+    ctx.staking
+        .approve_claimer(&ctx.user1, &lp_rewards, &keeper);
+    assert_eq!(
+        ctx.staking
+            .try_compound_for(&keeper, &ctx.user1, &lp_rewards),
+        Err(Ok(AstroSwapError::RewardsUnfunded))
+    );
+
+    // Funded on top of the principal, rewards are paid and stakes stay whole
+    lp.transfer(&ctx.user2, &ctx.staking_address, &100_0000000);
+    let claimed = ctx.staking.claim_rewards(&ctx.user1, &lp_rewards);
+    assert!(claimed > 0);
+    assert_eq!(lp.balance(&ctx.user1), claimed);
+    ctx.staking.unstake(&ctx.user2, &other_pool, &staked_2);
+    ctx.staking.unstake(&ctx.user1, &lp_rewards, &staked_1);
+    assert_eq!(lp.balance(&ctx.user1), claimed + staked_1);
+}
+
+#[test]
+fn test_retro_drop_escrow_not_spent_on_pool_rewards() {
+    use astroswap_staking::retro_leaf;
+
+    let ctx = Scenario::new().with_pair(Token::A, Token::B, (10_000_0000000, 20_000_0000000));
+    let env = &ctx.env;
+    let pair_address = ctx.pair();
+    let start_time = ctx.timestamp();
+
+    // A pool paying in the reward token, never funded
+    let pool_id = ctx.staking.create_pool(
+        &ctx.admin,
+        &pair_address,
+        &10_0000000i128,
+        &start_time,
+        &(start_time + 3600),
+    );
+    ctx.add_liquidity(&ctx.user1, (1_000_0000000, 2_000_0000000));
+    let lp = PairClient::new(env, &pair_address);
+    ctx.staking
+        .stake(&ctx.user1, &pool_id, &lp.balance(&ctx.user1));
+    ctx.advance_time(3600);
+
+    // A drop in the same token; single-leaf trees need no proof
+    let root = retro_leaf(env, &ctx.user2, 100_0000000);
+    let drop_id = ctx
+        .staking
+        .publish_retro_drop(&ctx.admin, &root, &ctx.xlm_address, &100_0000000);
+    assert_eq!(ctx.staking.retro_reserved(&ctx.xlm_address), 100_0000000);
+
+    // The pool cannot be paid out of the drop's escrow
+    assert_eq!(
+        ctx.staking.try_claim_rewards(&ctx.user1, &pool_id),
+        Err(Ok(AstroSwapError::RewardsUnfunded))
+    );
+    ctx.staking
+        .claim_retro_reward(&ctx.user2, &drop_id, &100_0000000, &Vec::new(env));
+    assert_eq!(ctx.staking.retro_reserved(&ctx.xlm_address), 0);
+
+    // Closing refunds the unclaimed remainder and releases its escrow
+    let user3 = Address::generate(env);
+    let root = retro_leaf(env, &user3, 50_0000000);
+    let drop_id = ctx
+        .staking
+        .publish_retro_drop(&ctx.admin, &root, &ctx.xlm_address, &80_0000000);
+    let admin_before = ctx.xlm.balance(&ctx.admin);
+    assert_eq!(
+        ctx.staking.close_retro_drop(&ctx.admin, &drop_id),
+        80_0000000
+    );
+    assert_eq!(ctx.xlm.balance(&ctx.admin) - admin_before, 80_0000000);
+    assert_eq!(ctx.staking.retro_reserved(&ctx.xlm_address), 0);
+    assert!(ctx.staking.retro_drop(&drop_id).unwrap().closed);
+    assert_eq!(
+        ctx.staking
+            .try_claim_retro_reward(&user3, &drop_id, &50_0000000, &Vec::new(env)),
+        Err(Ok(AstroSwapError::RetroDropClosed))
+    );
+    assert_eq!(
+        ctx.staking.try_close_retro_drop(&ctx.admin, &drop_id),
+        Err(Ok(AstroSwapError::RetroDropClosed))
+    );
+}
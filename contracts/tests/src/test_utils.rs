@@ -111,6 +111,9 @@ impl TestContext {
             &xlm_address,
         );
 
+        // Let the bridge create staking pools for graduated tokens
+        staking.set_pool_creator(&admin, &bridge_address, &true);
+
         Self {
             env,
             admin,
@@ -168,7 +171,7 @@ impl TestContext {
             .create_pair(token_a, token_b);
 
         // Add liquidity via router (returns tuple directly)
-        let (_amount_a, _amount_b, _liquidity) = self
+        let (_amount_a, _amount_b, _liquidity, _pair) = self
             .router
             .add_liquidity(
                 &self.admin,
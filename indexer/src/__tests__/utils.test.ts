@@ -0,0 +1,138 @@
+/**
+ * Unit tests for indexer event parsing
+ */
+
+import { describe, it, expect } from 'vitest';
+import { Address, nativeToScVal, xdr } from '@stellar/stellar-sdk';
+import { parseSorobanEvent, EVENT_NAMESPACE, EVENT_SCHEMA_VERSION } from '../utils';
+import { EventParseError } from '../types';
+
+const USER = 'GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H';
+const TOKEN_IN = 'CAAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQC526';
+const TOKEN_OUT = 'CABAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAFNSZ';
+
+/**
+ * Build a contract event as the RPC returns it, in base64 XDR
+ */
+function eventXdr(topics: xdr.ScVal[], fields: Record<string, xdr.ScVal>): string {
+  const data = xdr.ScVal.scvMap(
+    Object.entries(fields).map(
+      ([key, val]) => new xdr.ScMapEntry({ key: xdr.ScVal.scvSymbol(key), val })
+    )
+  );
+  const event = new xdr.ContractEvent({
+    ext: new xdr.ExtensionPoint(0),
+    contractId: null,
+    type: xdr.ContractEventType.contract(),
+    body: new xdr.ContractEventBody(0, new xdr.ContractEventV0({ topics, data })),
+  });
+  return new xdr.DiagnosticEvent({ inSuccessfulContractCall: true, event }).toXDR('base64');
+}
+
+function parse(topics: xdr.ScVal[], fields: Record<string, xdr.ScVal>) {
+  return parseSorobanEvent('CPAIR', eventXdr(topics, fields), 42, '2026-01-01T00:00:00Z', 'tx');
+}
+
+const i128 = (value: bigint) => nativeToScVal(value, { type: 'i128' });
+
+describe('parseSorobanEvent', () => {
+  const swapTopics = [
+    xdr.ScVal.scvSymbol(EVENT_NAMESPACE),
+    xdr.ScVal.scvSymbol('swap'),
+    Address.fromString(USER).toScVal(),
+  ];
+  const swapFields = {
+    version: xdr.ScVal.scvU32(EVENT_SCHEMA_VERSION),
+    token_in: Address.fromString(TOKEN_IN).toScVal(),
+    token_out: Address.fromString(TOKEN_OUT).toScVal(),
+    amount_in: i128(1_000n),
+    amount_out: i128(990n),
+    fee_amount: i128(3n),
+    lp_fee: i128(2n),
+    protocol_fee: i128(1n),
+  };
+
+  it('reads the name from the second topic and indexed fields after it', () => {
+    const event = parse(swapTopics, swapFields);
+
+    expect(event.type).toBe('swap');
+    expect(event.version).toBe(EVENT_SCHEMA_VERSION);
+    expect(event.data).toEqual({
+      sender: USER,
+      tokenIn: TOKEN_IN,
+      tokenOut: TOKEN_OUT,
+      amountIn: 1_000n,
+      amountOut: 990n,
+      feeAmount: 3n,
+      lpFee: 2n,
+      protocolFee: 1n,
+    });
+  });
+
+  it('takes the pair of pair_created from its indexed topic', () => {
+    const event = parse(
+      [
+        xdr.ScVal.scvSymbol(EVENT_NAMESPACE),
+        xdr.ScVal.scvSymbol('pair_created'),
+        Address.fromString(TOKEN_OUT).toScVal(),
+      ],
+      {
+        version: xdr.ScVal.scvU32(EVENT_SCHEMA_VERSION),
+        token_a: Address.fromString(TOKEN_IN).toScVal(),
+        token_b: Address.fromString(USER).toScVal(),
+        pair_count: xdr.ScVal.scvU32(7),
+      }
+    );
+
+    expect(event.type).toBe('pair_created');
+    expect(event.data).toEqual({
+      token0: TOKEN_IN,
+      token1: USER,
+      pair: TOKEN_OUT,
+      pairCount: 7n,
+    });
+  });
+
+  it('maps liquidity payloads to amounts and shares', () => {
+    const event = parse(
+      [
+        xdr.ScVal.scvSymbol(EVENT_NAMESPACE),
+        xdr.ScVal.scvSymbol('withdraw'),
+        Address.fromString(USER).toScVal(),
+      ],
+      {
+        version: xdr.ScVal.scvU32(EVENT_SCHEMA_VERSION),
+        pair: Address.fromString(TOKEN_OUT).toScVal(),
+        shares_burned: i128(500n),
+        amount_a: i128(100n),
+        amount_b: i128(200n),
+      }
+    );
+
+    expect(event.data).toEqual({
+      sender: USER,
+      pair: TOKEN_OUT,
+      amount0: 100n,
+      amount1: 200n,
+      liquidity: 500n,
+    });
+  });
+
+  it('rejects events outside the AstroSwap namespace', () => {
+    // Shape of the LP token's own SEP-41 events
+    const transfer = [
+      xdr.ScVal.scvSymbol('transfer'),
+      Address.fromString(USER).toScVal(),
+      Address.fromString(TOKEN_IN).toScVal(),
+    ];
+
+    expect(() => parse(transfer, swapFields)).toThrow(EventParseError);
+    expect(() => parse(transfer, swapFields)).toThrow('Not an AstroSwap event');
+  });
+
+  it('rejects unknown schema versions', () => {
+    const fields = { ...swapFields, version: xdr.ScVal.scvU32(EVENT_SCHEMA_VERSION + 1) };
+
+    expect(() => parse(swapTopics, fields)).toThrow('Unsupported event schema version');
+  });
+});
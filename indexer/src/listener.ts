@@ -232,11 +232,9 @@ export class EventListener {
             await this.handleSwap(event, pairAddress);
             break;
           case 'deposit':
-          case 'mint':
             await this.handleDeposit(event, pairAddress);
             break;
           case 'withdraw':
-          case 'burn':
             await this.handleWithdraw(event, pairAddress);
             break;
           case 'sync':
@@ -303,7 +301,7 @@ export class EventListener {
             events.push(parsed);
           } catch (error) {
             if (error instanceof EventParseError) {
-              logger.debug({ error: error.message }, 'Skipping non-contract event');
+              logger.debug({ error: error.message }, 'Skipping non-AstroSwap event');
             } else {
               throw error;
             }
@@ -364,8 +362,7 @@ export class EventListener {
     event: ParsedSorobanEvent,
     pairAddress: string
   ): Promise<void> {
-    const { sender, to, amount0In, amount1In, amount0Out, amount1Out } =
-      event.data;
+    const { sender, tokenIn, amountIn, amountOut } = event.data;
 
     // Get pair from database
     const pair = await this.prisma.pair.findUnique({
@@ -377,17 +374,21 @@ export class EventListener {
       return;
     }
 
+    // The event names the tokens; store the amounts on the pair's sides
+    const zeroForOne = tokenIn === pair.token0;
+
     // Create swap record
     await this.prisma.swap.create({
       data: {
         pairId: pair.id,
         txHash: event.txHash,
         sender,
-        recipient: to,
-        amount0In: bigIntToString(amount0In),
-        amount1In: bigIntToString(amount1In),
-        amount0Out: bigIntToString(amount0Out),
-        amount1Out: bigIntToString(amount1Out),
+        // The swap event's user is the recipient of the output
+        recipient: sender,
+        amount0In: bigIntToString(zeroForOne ? amountIn : 0n),
+        amount1In: bigIntToString(zeroForOne ? 0n : amountIn),
+        amount0Out: bigIntToString(zeroForOne ? 0n : amountOut),
+        amount1Out: bigIntToString(zeroForOne ? amountOut : 0n),
         timestamp: new Date(event.ledgerClosedAt),
         blockNumber: BigInt(event.ledger),
       },
@@ -13,15 +13,18 @@ export interface PairCreatedEvent {
 
 export interface SwapEvent {
   sender: string;
-  to: string;
-  amount0In: bigint;
-  amount1In: bigint;
-  amount0Out: bigint;
-  amount1Out: bigint;
+  tokenIn: string;
+  tokenOut: string;
+  amountIn: bigint;
+  amountOut: bigint;
+  feeAmount: bigint;
+  lpFee: bigint;
+  protocolFee: bigint;
 }
 
 export interface DepositEvent {
   sender: string;
+  pair: string;
   amount0: bigint;
   amount1: bigint;
   liquidity: bigint;
@@ -29,6 +32,7 @@ export interface DepositEvent {
 
 export interface WithdrawEvent {
   sender: string;
+  pair: string;
   amount0: bigint;
   amount1: bigint;
   liquidity: bigint;
@@ -46,6 +50,7 @@ export interface SyncEvent {
 export interface ParsedSorobanEvent {
   contractId: string;
   type: string;
+  version: number;
   ledger: number;
   ledgerClosedAt: string;
   txHash: string;
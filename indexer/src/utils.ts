@@ -7,8 +7,24 @@ import { logger } from './logger';
 // Event Parsing Utilities
 // ============================================================================
 
+/**
+ * First topic of every AstroSwap event
+ */
+export const EVENT_NAMESPACE = 'astroswap';
+
+/**
+ * Payload layout version the parser understands (`EVENT_SCHEMA_VERSION` in
+ * contracts/shared/src/events.rs)
+ */
+export const EVENT_SCHEMA_VERSION = 1;
+
 /**
  * Parse Soroban event from XDR
+ *
+ * AstroSwap events are published with the topics
+ * `[EVENT_NAMESPACE, <event name>, ...indexed fields]` and a map payload
+ * carrying a `version` field. Events from other namespaces (such as the LP
+ * token's own `transfer`/`mint`/`burn`) are rejected with `EventParseError`.
  */
 export function parseSorobanEvent(
   contractId: string,
@@ -30,23 +46,35 @@ export function parseSorobanEvent(
       throw new EventParseError('No event body found');
     }
 
-    // Parse topics
+    // Parse topics: [namespace, name, ...indexed]
     const topics = body.topics().map((topic: any) => scValToNative(topic));
-    const eventType = topics[0] as string;
+    if (topics[0] !== EVENT_NAMESPACE) {
+      throw new EventParseError('Not an AstroSwap event');
+    }
+    const eventType = topics[1] as string;
+    const indexed = topics.slice(2);
 
     // Parse data
     const dataValue = body.data();
     const data = scValToNative(dataValue);
+    const version = Number(data?.version);
+    if (version !== EVENT_SCHEMA_VERSION) {
+      throw new EventParseError('Unsupported event schema version', { version });
+    }
 
     return {
       contractId,
       type: eventType,
+      version,
       ledger,
       ledgerClosedAt,
       txHash,
-      data: parseEventData(eventType, topics, data),
+      data: parseEventData(eventType, indexed, data),
     };
   } catch (error) {
+    if (error instanceof EventParseError) {
+      throw error;
+    }
     throw new EventParseError('Failed to parse event', {
       contractId,
       txHash,
@@ -57,47 +85,51 @@ export function parseSorobanEvent(
 
 /**
  * Parse event data based on event type
+ *
+ * `indexed` holds the topics after the event name, `data` the payload map.
  */
 function parseEventData(
   eventType: string,
-  topics: any[],
+  indexed: any[],
   data: any
 ): Record<string, any> {
   switch (eventType) {
     case 'pair_created':
       return {
-        token0: topics[1],
-        token1: topics[2],
-        pair: data.pair,
+        token0: data.token_a,
+        token1: data.token_b,
+        pair: indexed[0],
         pairCount: BigInt(data.pair_count),
       };
 
     case 'swap':
       return {
-        sender: topics[1],
-        to: data.to,
-        amount0In: BigInt(data.amount0_in || 0),
-        amount1In: BigInt(data.amount1_in || 0),
-        amount0Out: BigInt(data.amount0_out || 0),
-        amount1Out: BigInt(data.amount1_out || 0),
+        sender: indexed[0],
+        tokenIn: data.token_in,
+        tokenOut: data.token_out,
+        amountIn: BigInt(data.amount_in),
+        amountOut: BigInt(data.amount_out),
+        feeAmount: BigInt(data.fee_amount),
+        lpFee: BigInt(data.lp_fee),
+        protocolFee: BigInt(data.protocol_fee),
       };
 
     case 'deposit':
-    case 'mint':
       return {
-        sender: topics[1],
-        amount0: BigInt(data.amount0),
-        amount1: BigInt(data.amount1),
-        liquidity: BigInt(data.liquidity),
+        sender: indexed[0],
+        pair: data.pair,
+        amount0: BigInt(data.amount_a),
+        amount1: BigInt(data.amount_b),
+        liquidity: BigInt(data.shares_minted),
       };
 
     case 'withdraw':
-    case 'burn':
       return {
-        sender: topics[1],
-        amount0: BigInt(data.amount0),
-        amount1: BigInt(data.amount1),
-        liquidity: BigInt(data.liquidity),
+        sender: indexed[0],
+        pair: data.pair,
+        amount0: BigInt(data.amount_a),
+        amount1: BigInt(data.amount_b),
+        liquidity: BigInt(data.shares_burned),
       };
 
     case 'sync':
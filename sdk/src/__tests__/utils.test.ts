@@ -37,12 +37,16 @@ import {
   // ScVal utilities
   scValToNative,
   nativeToScValTyped,
+  // Event utilities
+  decodeEvent,
   // Retry utilities
   retry,
   // Constants
   BPS_DENOMINATOR,
   MINIMUM_LIQUIDITY,
   DEFAULT_DEADLINE_SECONDS,
+  EVENT_NAMESPACE,
+  EVENT_SCHEMA_VERSION,
 } from '../utils';
 import { Address, xdr } from '@stellar/stellar-sdk';
 
@@ -908,6 +912,62 @@ describe('ScVal Utilities', () => {
   });
 });
 
+// ==================== Event Decoding Tests ====================
+
+describe('Event Decoding', () => {
+  const user = 'GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H';
+  const payload = (version: number) =>
+    xdr.ScVal.scvMap([
+      new xdr.ScMapEntry({
+        key: xdr.ScVal.scvSymbol('amount'),
+        val: nativeToScValTyped(500n, 'i128'),
+      }),
+      new xdr.ScMapEntry({
+        key: xdr.ScVal.scvSymbol('pool_id'),
+        val: xdr.ScVal.scvU32(3),
+      }),
+      new xdr.ScMapEntry({
+        key: xdr.ScVal.scvSymbol('version'),
+        val: xdr.ScVal.scvU32(version),
+      }),
+    ]);
+
+  it('should split namespace, name and indexed topics', () => {
+    const event = decodeEvent(
+      [
+        xdr.ScVal.scvSymbol(EVENT_NAMESPACE),
+        xdr.ScVal.scvSymbol('stake'),
+        nativeToScValTyped(user, 'address'),
+      ],
+      payload(EVENT_SCHEMA_VERSION)
+    );
+
+    expect(event).toEqual({
+      name: 'stake',
+      version: EVENT_SCHEMA_VERSION,
+      indexed: [user],
+      data: { amount: 500n, pool_id: 3, version: EVENT_SCHEMA_VERSION },
+    });
+  });
+
+  it('should skip events outside the namespace', () => {
+    const event = decodeEvent(
+      [xdr.ScVal.scvSymbol('transfer'), nativeToScValTyped(user, 'address')],
+      nativeToScValTyped(500n, 'i128')
+    );
+
+    expect(event).toBeNull();
+  });
+
+  it('should reject unknown schema versions', () => {
+    const topics = [xdr.ScVal.scvSymbol(EVENT_NAMESPACE), xdr.ScVal.scvSymbol('stake')];
+
+    expect(() => decodeEvent(topics, payload(EVENT_SCHEMA_VERSION + 1))).toThrow(
+      'Unsupported event schema version'
+    );
+  });
+});
+
 // ==================== Retry Utility Tests ====================
 
 describe('Retry Utility', () => {
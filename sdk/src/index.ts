@@ -81,10 +81,14 @@ export {
   // ScVal conversion
   scValToNative,
   nativeToScValTyped,
+  // Events
+  decodeEvent,
   // Retry
   retry,
   // Constants
   BPS_DENOMINATOR,
   MINIMUM_LIQUIDITY,
   DEFAULT_DEADLINE_SECONDS,
+  EVENT_NAMESPACE,
+  EVENT_SCHEMA_VERSION,
 } from './utils';
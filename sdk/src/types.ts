@@ -269,6 +269,21 @@ export interface SimulationResult<T = unknown> {
 
 // ==================== Event Types ====================
 
+/**
+ * An AstroSwap contract event in the versioned layout: topics
+ * `["astroswap", name, ...indexed]` and a map payload with a `version` field
+ */
+export interface AstroSwapEvent {
+  /** Event name (second topic), e.g. `swap` */
+  name: string;
+  /** Payload layout version */
+  version: number;
+  /** Indexed fields: the topics after the name */
+  indexed: unknown[];
+  /** Payload fields, keyed by their contract names */
+  data: Record<string, unknown>;
+}
+
 export interface SwapEvent {
   sender: string;
   amountIn: bigint;
@@ -5,12 +5,15 @@
  */
 
 import { Address, xdr, scValToBigInt, nativeToScVal } from '@stellar/stellar-sdk';
+import type { AstroSwapEvent } from './types';
 
 // ==================== Constants ====================
 
 export const BPS_DENOMINATOR = 10_000n;
 export const MINIMUM_LIQUIDITY = 1000n;
 export const DEFAULT_DEADLINE_SECONDS = 30 * 60; // 30 minutes
+export const EVENT_NAMESPACE = 'astroswap'; // First topic of every AstroSwap event
+export const EVENT_SCHEMA_VERSION = 1; // Event payload layout decoded by `decodeEvent`
 
 // ==================== Safe Math Utilities ====================
 
@@ -391,6 +394,30 @@ export function nativeToScValTyped(
   }
 }
 
+// ==================== Event Decoding ====================
+
+/**
+ * Decode an AstroSwap contract event from its topics and payload
+ *
+ * Returns `null` for events outside the `astroswap` namespace (such as
+ * SEP-41 token events). Throws on a payload version newer than
+ * `EVENT_SCHEMA_VERSION`.
+ */
+export function decodeEvent(topics: xdr.ScVal[], data: xdr.ScVal): AstroSwapEvent | null {
+  const [namespace, name, ...indexed] = topics.map(scValToNative);
+  if (namespace !== EVENT_NAMESPACE || typeof name !== 'string') {
+    return null;
+  }
+
+  const payload = scValToNative(data) as Record<string, unknown>;
+  const version = Number(payload.version);
+  if (version !== EVENT_SCHEMA_VERSION) {
+    throw new Error(`Unsupported event schema version: ${payload.version}`);
+  }
+
+  return { name, version, indexed, data: payload };
+}
+
 // ==================== Slippage Utilities ====================
 
 /**
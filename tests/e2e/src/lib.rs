@@ -1 +1,5 @@
-// Dummy lib file for e2e test package
+//! AstroSwap E2E Test Harness
+//!
+//! Fixtures shared by the end-to-end suites in `tests/`.
+
+pub mod mock_protocols;
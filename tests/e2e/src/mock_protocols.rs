@@ -0,0 +1,420 @@
+//! Mock Soroswap and Phoenix Protocols
+//!
+//! Minimal stand-ins for the external DEXs the aggregator routes through,
+//! implementing the part of each protocol's public interface a swap needs:
+//! - `MockSoroswapRouter`: Soroswap router (`router_get_amounts_out`,
+//!   `swap_exact_tokens_for_tokens`) over constant-product pools
+//! - `MockPhoenixPool`: Phoenix XYK pool (`simulate_swap`, `swap`)
+//!
+//! The aggregator calls external protocols through its generic adapter
+//! interface: `get_amounts_out(amount_in, token_in, token_out)` to quote and
+//! `swap(recipient, token_in, min_out, deadline)` to swap the input already
+//! sent to the adapter. `SoroswapAdapter` and `PhoenixAdapter` translate it
+//! to each protocol's own interface and are what gets registered. That
+//! `swap` names only the input token, so each adapter serves one pair.
+
+use astroswap_shared::get_amount_out;
+use soroban_sdk::{contract, contractimpl, contracttype, token, vec, Address, Env, Vec};
+
+/// Swap fee of the mock Soroswap pools (0.3%)
+pub const SOROSWAP_FEE_BPS: u32 = 30;
+
+/// Storage keys of the mock protocols and adapters
+#[contracttype]
+#[derive(Clone)]
+enum MockKey {
+    /// Soroswap pool reserves, keyed by the sorted token pair
+    Reserves(Address, Address),
+    /// Phoenix pool (token_a, token_b, fee_bps)
+    PoolConfig,
+    /// Phoenix pool (reserve_a, reserve_b)
+    PoolReserves,
+    /// Adapter (protocol contract, token_a, token_b)
+    Adapter,
+}
+
+// ==================== Soroswap ====================
+
+/// Soroswap router over one constant-product pool per token pair
+#[contract]
+pub struct MockSoroswapRouter;
+
+#[contractimpl]
+impl MockSoroswapRouter {
+    /// Add `amount_a` and `amount_b` from `from` to the pool of the pair
+    pub fn seed_pool(
+        env: Env,
+        from: Address,
+        token_a: Address,
+        token_b: Address,
+        amount_a: i128,
+        amount_b: i128,
+    ) {
+        from.require_auth();
+        let this = env.current_contract_address();
+        token::Client::new(&env, &token_a).transfer(&from, &this, &amount_a);
+        token::Client::new(&env, &token_b).transfer(&from, &this, &amount_b);
+
+        let (reserve_a, reserve_b) = Self::reserves(&env, &token_a, &token_b);
+        Self::set_reserves(
+            &env,
+            &token_a,
+            &token_b,
+            reserve_a + amount_a,
+            reserve_b + amount_b,
+        );
+    }
+
+    /// Amounts along `path` for `amount_in` of its first token
+    pub fn router_get_amounts_out(env: Env, amount_in: i128, path: Vec<Address>) -> Vec<i128> {
+        assert!(path.len() >= 2, "invalid path");
+        let mut amounts = vec![&env, amount_in];
+        for i in 0..path.len() - 1 {
+            let (reserve_in, reserve_out) =
+                Self::reserves(&env, &path.get(i).unwrap(), &path.get(i + 1).unwrap());
+            assert!(reserve_in > 0 && reserve_out > 0, "pair not found");
+            let amount_out = get_amount_out(
+                amounts.last().unwrap(),
+                reserve_in,
+                reserve_out,
+                SOROSWAP_FEE_BPS,
+            )
+            .unwrap();
+            amounts.push_back(amount_out);
+        }
+        amounts
+    }
+
+    /// Swap exactly `amount_in` of `path[0]` from `to` for at least
+    /// `amount_out_min` of the last token of `path`, paid to `to`
+    pub fn swap_exact_tokens_for_tokens(
+        env: Env,
+        amount_in: i128,
+        amount_out_min: i128,
+        path: Vec<Address>,
+        to: Address,
+        deadline: u64,
+    ) -> Vec<i128> {
+        to.require_auth();
+        assert!(env.ledger().timestamp() <= deadline, "deadline expired");
+
+        let amounts = Self::router_get_amounts_out(env.clone(), amount_in, path.clone());
+        let amount_out = amounts.last().unwrap();
+        assert!(amount_out >= amount_out_min, "insufficient output amount");
+
+        let this = env.current_contract_address();
+        token::Client::new(&env, &path.get(0).unwrap()).transfer(&to, &this, &amount_in);
+        for i in 0..path.len() - 1 {
+            let (token_in, token_out) = (path.get(i).unwrap(), path.get(i + 1).unwrap());
+            let (reserve_in, reserve_out) = Self::reserves(&env, &token_in, &token_out);
+            Self::set_reserves(
+                &env,
+                &token_in,
+                &token_out,
+                reserve_in + amounts.get(i).unwrap(),
+                reserve_out - amounts.get(i + 1).unwrap(),
+            );
+        }
+        token::Client::new(&env, &path.last().unwrap()).transfer(&this, &to, &amount_out);
+
+        amounts
+    }
+}
+
+impl MockSoroswapRouter {
+    /// Reserves of the pair, in the order of the given tokens
+    fn reserves(env: &Env, token_a: &Address, token_b: &Address) -> (i128, i128) {
+        let (key, flipped) = Self::pair_key(token_a, token_b);
+        let (reserve_0, reserve_1) = env.storage().instance().get(&key).unwrap_or((0, 0));
+        if flipped {
+            (reserve_1, reserve_0)
+        } else {
+            (reserve_0, reserve_1)
+        }
+    }
+
+    fn set_reserves(
+        env: &Env,
+        token_a: &Address,
+        token_b: &Address,
+        reserve_a: i128,
+        reserve_b: i128,
+    ) {
+        let (key, flipped) = Self::pair_key(token_a, token_b);
+        let reserves = if flipped {
+            (reserve_b, reserve_a)
+        } else {
+            (reserve_a, reserve_b)
+        };
+        env.storage().instance().set(&key, &reserves);
+    }
+
+    /// Key of the pair and whether the tokens were given in reverse order
+    fn pair_key(token_a: &Address, token_b: &Address) -> (MockKey, bool) {
+        if token_a < token_b {
+            (MockKey::Reserves(token_a.clone(), token_b.clone()), false)
+        } else {
+            (MockKey::Reserves(token_b.clone(), token_a.clone()), true)
+        }
+    }
+}
+
+// ==================== Phoenix ====================
+
+/// Phoenix `simulate_swap` result
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SimulateSwapResponse {
+    /// Output after the commission
+    pub ask_amount: i128,
+    pub commission_amount: i128,
+    /// Output lost to price impact
+    pub spread_amount: i128,
+    pub total_return: i128,
+}
+
+/// Phoenix XYK pool; the commission is taken from the output
+#[contract]
+pub struct MockPhoenixPool;
+
+#[contractimpl]
+impl MockPhoenixPool {
+    pub fn initialize(env: Env, token_a: Address, token_b: Address, fee_bps: i64) {
+        env.storage()
+            .instance()
+            .set(&MockKey::PoolConfig, &(token_a, token_b, fee_bps));
+        env.storage()
+            .instance()
+            .set(&MockKey::PoolReserves, &(0i128, 0i128));
+    }
+
+    /// Add `amount_a` and `amount_b` from `from` to the pool
+    pub fn seed_pool(env: Env, from: Address, amount_a: i128, amount_b: i128) {
+        from.require_auth();
+        let (token_a, token_b, _) = Self::config(&env);
+        let this = env.current_contract_address();
+        token::Client::new(&env, &token_a).transfer(&from, &this, &amount_a);
+        token::Client::new(&env, &token_b).transfer(&from, &this, &amount_b);
+
+        let (reserve_a, reserve_b) = Self::reserves(&env);
+        env.storage().instance().set(
+            &MockKey::PoolReserves,
+            &(reserve_a + amount_a, reserve_b + amount_b),
+        );
+    }
+
+    pub fn simulate_swap(
+        env: Env,
+        offer_asset: Address,
+        offer_amount: i128,
+    ) -> SimulateSwapResponse {
+        let (_, reserve_offer, reserve_ask) = Self::sides(&env, &offer_asset);
+        let (_, _, fee_bps) = Self::config(&env);
+
+        let return_amount = offer_amount * reserve_ask / (reserve_offer + offer_amount);
+        let commission_amount = return_amount * i128::from(fee_bps) / 10_000;
+        let ideal_return = offer_amount * reserve_ask / reserve_offer;
+        SimulateSwapResponse {
+            ask_amount: return_amount - commission_amount,
+            commission_amount,
+            spread_amount: ideal_return - return_amount,
+            total_return: ideal_return,
+        }
+    }
+
+    /// Swap `offer_amount` of `offer_asset` from `sender`, paying the other
+    /// asset to `sender`
+    #[allow(clippy::too_many_arguments)]
+    pub fn swap(
+        env: Env,
+        sender: Address,
+        offer_asset: Address,
+        offer_amount: i128,
+        ask_asset_min_amount: Option<i128>,
+        max_spread_bps: Option<i64>,
+        deadline: Option<u64>,
+        max_allowed_fee_bps: Option<i64>,
+    ) -> i128 {
+        sender.require_auth();
+        if let Some(deadline) = deadline {
+            assert!(env.ledger().timestamp() <= deadline, "deadline expired");
+        }
+        let (_, _, fee_bps) = Self::config(&env);
+        if let Some(max_fee) = max_allowed_fee_bps {
+            assert!(fee_bps <= max_fee, "fee above the allowed maximum");
+        }
+
+        let simulation = Self::simulate_swap(env.clone(), offer_asset.clone(), offer_amount);
+        if let Some(min) = ask_asset_min_amount {
+            assert!(simulation.ask_amount >= min, "ask amount below the minimum");
+        }
+        if let Some(max_spread) = max_spread_bps {
+            assert!(
+                simulation.spread_amount * 10_000
+                    <= simulation.total_return * i128::from(max_spread),
+                "spread above the allowed maximum"
+            );
+        }
+
+        let (ask_asset, reserve_offer, reserve_ask) = Self::sides(&env, &offer_asset);
+        let this = env.current_contract_address();
+        token::Client::new(&env, &offer_asset).transfer(&sender, &this, &offer_amount);
+        token::Client::new(&env, &ask_asset).transfer(&this, &sender, &simulation.ask_amount);
+
+        // The commission stays in the pool
+        let (token_a, _, _) = Self::config(&env);
+        let (reserve_offer, reserve_ask) = (
+            reserve_offer + offer_amount,
+            reserve_ask - simulation.ask_amount,
+        );
+        let reserves = if offer_asset == token_a {
+            (reserve_offer, reserve_ask)
+        } else {
+            (reserve_ask, reserve_offer)
+        };
+        env.storage()
+            .instance()
+            .set(&MockKey::PoolReserves, &reserves);
+
+        simulation.ask_amount
+    }
+}
+
+impl MockPhoenixPool {
+    fn config(env: &Env) -> (Address, Address, i64) {
+        env.storage().instance().get(&MockKey::PoolConfig).unwrap()
+    }
+
+    fn reserves(env: &Env) -> (i128, i128) {
+        env.storage()
+            .instance()
+            .get(&MockKey::PoolReserves)
+            .unwrap()
+    }
+
+    /// (ask_asset, reserve_offer, reserve_ask) for an offer of `offer_asset`
+    fn sides(env: &Env, offer_asset: &Address) -> (Address, i128, i128) {
+        let (token_a, token_b, _) = Self::config(env);
+        let (reserve_a, reserve_b) = Self::reserves(env);
+        if *offer_asset == token_a {
+            (token_b, reserve_a, reserve_b)
+        } else if *offer_asset == token_b {
+            (token_a, reserve_b, reserve_a)
+        } else {
+            panic!("asset not in pool")
+        }
+    }
+}
+
+// ==================== Adapters ====================
+
+/// Aggregator adapter routing one pair through the Soroswap router
+#[contract]
+pub struct SoroswapAdapter;
+
+#[contractimpl]
+impl SoroswapAdapter {
+    pub fn initialize(env: Env, router: Address, token_a: Address, token_b: Address) {
+        env.storage()
+            .instance()
+            .set(&MockKey::Adapter, &(router, token_a, token_b));
+    }
+
+    pub fn get_amounts_out(
+        env: Env,
+        amount_in: i128,
+        token_in: Address,
+        token_out: Address,
+    ) -> i128 {
+        let (router, _) = adapter_target(&env, &token_in);
+        let path = vec![&env, token_in, token_out];
+        MockSoroswapRouterClient::new(&env, &router)
+            .router_get_amounts_out(&amount_in, &path)
+            .last()
+            .unwrap()
+    }
+
+    /// Swap the adapter's whole `token_in` balance, paying `recipient`
+    pub fn swap(
+        env: Env,
+        recipient: Address,
+        token_in: Address,
+        min_out: i128,
+        deadline: u64,
+    ) -> i128 {
+        let (router, token_out) = adapter_target(&env, &token_in);
+        let this = env.current_contract_address();
+        let amount_in = token::Client::new(&env, &token_in).balance(&this);
+
+        let path = vec![&env, token_in, token_out.clone()];
+        let amount_out = MockSoroswapRouterClient::new(&env, &router)
+            .swap_exact_tokens_for_tokens(&amount_in, &min_out, &path, &this, &deadline)
+            .last()
+            .unwrap();
+        token::Client::new(&env, &token_out).transfer(&this, &recipient, &amount_out);
+        amount_out
+    }
+}
+
+/// Aggregator adapter routing through one Phoenix pool
+#[contract]
+pub struct PhoenixAdapter;
+
+#[contractimpl]
+impl PhoenixAdapter {
+    pub fn initialize(env: Env, pool: Address, token_a: Address, token_b: Address) {
+        env.storage()
+            .instance()
+            .set(&MockKey::Adapter, &(pool, token_a, token_b));
+    }
+
+    pub fn get_amounts_out(
+        env: Env,
+        amount_in: i128,
+        token_in: Address,
+        _token_out: Address,
+    ) -> i128 {
+        let (pool, _) = adapter_target(&env, &token_in);
+        MockPhoenixPoolClient::new(&env, &pool)
+            .simulate_swap(&token_in, &amount_in)
+            .ask_amount
+    }
+
+    /// Swap the adapter's whole `token_in` balance, paying `recipient`
+    pub fn swap(
+        env: Env,
+        recipient: Address,
+        token_in: Address,
+        min_out: i128,
+        deadline: u64,
+    ) -> i128 {
+        let (pool, token_out) = adapter_target(&env, &token_in);
+        let this = env.current_contract_address();
+        let amount_in = token::Client::new(&env, &token_in).balance(&this);
+
+        let amount_out = MockPhoenixPoolClient::new(&env, &pool).swap(
+            &this,
+            &token_in,
+            &amount_in,
+            &Some(min_out),
+            &None,
+            &Some(deadline),
+            &None,
+        );
+        token::Client::new(&env, &token_out).transfer(&this, &recipient, &amount_out);
+        amount_out
+    }
+}
+
+/// (protocol contract, token_out) of an adapter for a swap selling `token_in`
+fn adapter_target(env: &Env, token_in: &Address) -> (Address, Address) {
+    let (target, token_a, token_b): (Address, Address, Address) =
+        env.storage().instance().get(&MockKey::Adapter).unwrap();
+    if *token_in == token_a {
+        (target, token_b)
+    } else if *token_in == token_b {
+        (target, token_a)
+    } else {
+        panic!("token not served by this adapter")
+    }
+}
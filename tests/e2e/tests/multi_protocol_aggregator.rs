@@ -0,0 +1,316 @@
+#![cfg(test)]
+
+//! E2E Test: Multi-Protocol Aggregator Routing
+//!
+//! Deploys AstroSwap next to mock Soroswap and Phoenix contracts (see
+//! `astroswap_e2e_tests::mock_protocols`), registers them into the aggregator
+//! through their adapters, and checks best-route selection and execution
+//! across protocols end-to-end:
+//! - Every protocol is quoted through its own public interface
+//! - The best direct quote wins and the swap settles on that protocol only
+//! - Deactivating the winner falls back to the next best protocol
+//! - Two-hop routes through a connector token can mix protocols
+
+use soroban_sdk::{
+    testutils::{Address as _, Ledger, LedgerInfo},
+    token, vec, Address, Env,
+};
+
+use astroswap_aggregator::{AstroSwapAggregator, AstroSwapAggregatorClient};
+use astroswap_e2e_tests::mock_protocols::{
+    MockPhoenixPool, MockPhoenixPoolClient, MockSoroswapRouter, MockSoroswapRouterClient,
+    PhoenixAdapter, PhoenixAdapterClient, SoroswapAdapter, SoroswapAdapterClient,
+};
+use astroswap_factory::{AstroSwapFactory, AstroSwapFactoryClient};
+use astroswap_pair::AstroSwapPairClient;
+use astroswap_router::{AstroSwapRouter, AstroSwapRouterClient};
+use astroswap_shared::Protocol;
+
+// WASM bytes for pair contract deployment
+mod pair_wasm {
+    pub const WASM: &[u8] =
+        include_bytes!("../../../target/wasm32v1-none/release/astroswap_pair.wasm");
+}
+
+/// Phoenix pool commission in basis points
+const PHOENIX_FEE_BPS: i64 = 30;
+
+/// AstroSwap, the mock Soroswap router and the aggregator over three tokens
+struct Harness<'a> {
+    env: Env,
+    admin: Address,
+    user: Address,
+    token_a: Address,
+    token_b: Address,
+    /// Connector token for two-hop routes
+    token_x: Address,
+    router: AstroSwapRouterClient<'a>,
+    aggregator: AstroSwapAggregatorClient<'a>,
+    soroswap: MockSoroswapRouterClient<'a>,
+}
+
+impl<'a> Harness<'a> {
+    fn new() -> Self {
+        let env = Env::default();
+        // Use mock_all_auths_allowing_non_root_auth for contract-to-contract calls
+        env.mock_all_auths_allowing_non_root_auth();
+
+        // Set initial ledger state (protocol version 23 required for SDK 23)
+        env.ledger().set(LedgerInfo {
+            timestamp: 1700000000,
+            protocol_version: 23,
+            sequence_number: 1000,
+            network_id: [0; 32],
+            base_reserve: 10,
+            min_temp_entry_ttl: 100,
+            min_persistent_entry_ttl: 100,
+            max_entry_ttl: 3110400,
+        });
+
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        // Tokens, minted to the admin (liquidity) and the user (trading)
+        let [token_a, token_b, token_x] = [0, 1, 2].map(|_| {
+            let token = env
+                .register_stellar_asset_contract_v2(admin.clone())
+                .address();
+            let token_admin = token::StellarAssetClient::new(&env, &token);
+            token_admin.mint(&admin, &1_000_000_0000000);
+            token_admin.mint(&user, &10_000_0000000);
+            token
+        });
+
+        // AstroSwap
+        let factory_id = env.register(AstroSwapFactory, ());
+        let factory = AstroSwapFactoryClient::new(&env, &factory_id);
+        let pair_wasm_hash = env.deployer().upload_contract_wasm(pair_wasm::WASM);
+        factory.initialize(&admin, &pair_wasm_hash, &30);
+
+        let router_id = env.register(AstroSwapRouter, ());
+        let router = AstroSwapRouterClient::new(&env, &router_id);
+        router.initialize(&factory_id, &admin);
+
+        // Aggregator, quoting AstroSwap natively
+        let aggregator_id = env.register(AstroSwapAggregator, ());
+        let aggregator = AstroSwapAggregatorClient::new(&env, &aggregator_id);
+        aggregator.initialize(&admin, &factory_id);
+
+        // Soroswap router, registered once its adapter's pair is known
+        let soroswap_id = env.register(MockSoroswapRouter, ());
+        let soroswap = MockSoroswapRouterClient::new(&env, &soroswap_id);
+
+        Self {
+            env,
+            admin,
+            user,
+            token_a,
+            token_b,
+            token_x,
+            router,
+            aggregator,
+            soroswap,
+        }
+    }
+
+    fn deadline(&self) -> u64 {
+        self.env.ledger().timestamp() + 3600
+    }
+
+    fn balance(&self, token: &Address, owner: &Address) -> i128 {
+        token::Client::new(&self.env, token).balance(owner)
+    }
+
+    /// Create an AstroSwap pair with the given reserves
+    fn astroswap_pool(
+        &self,
+        token_a: &Address,
+        token_b: &Address,
+        amounts: (i128, i128),
+    ) -> Address {
+        let (_, _, _, pair) = self.router.add_liquidity(
+            &self.admin,
+            token_a,
+            token_b,
+            &amounts.0,
+            &amounts.1,
+            &0,
+            &0,
+            &self.deadline(),
+        );
+        pair
+    }
+
+    /// Seed a Soroswap pool and register the adapter routing it
+    fn soroswap_pool(&self, token_a: &Address, token_b: &Address, amounts: (i128, i128)) {
+        self.soroswap
+            .seed_pool(&self.admin, token_a, token_b, &amounts.0, &amounts.1);
+
+        let adapter = self.env.register(SoroswapAdapter, ());
+        SoroswapAdapterClient::new(&self.env, &adapter).initialize(
+            &self.soroswap.address,
+            token_a,
+            token_b,
+        );
+        self.register(Protocol::Soroswap, &adapter);
+    }
+
+    /// Deploy and seed a Phoenix pool and register the adapter routing it
+    fn phoenix_pool(
+        &self,
+        token_a: &Address,
+        token_b: &Address,
+        amounts: (i128, i128),
+    ) -> MockPhoenixPoolClient<'a> {
+        let pool = MockPhoenixPoolClient::new(&self.env, &self.env.register(MockPhoenixPool, ()));
+        pool.initialize(token_a, token_b, &PHOENIX_FEE_BPS);
+        pool.seed_pool(&self.admin, &amounts.0, &amounts.1);
+
+        let adapter = self.env.register(PhoenixAdapter, ());
+        PhoenixAdapterClient::new(&self.env, &adapter).initialize(&pool.address, token_a, token_b);
+        self.register(Protocol::Phoenix, &adapter);
+        pool
+    }
+
+    /// Register an adapter; both adapters enforce the route's deadline
+    fn register(&self, protocol: Protocol, adapter: &Address) {
+        self.aggregator
+            .register_protocol(&self.admin, &protocol, adapter, &30);
+        self.aggregator
+            .set_protocol_deadline(&self.admin, &protocol, &true);
+    }
+
+    /// Input reaching the first pool of a route after the aggregator fee
+    fn after_fee(&self, amount_in: i128) -> i128 {
+        let fee_bps = self.aggregator.config().aggregator_fee_bps;
+        amount_in - amount_in * i128::from(fee_bps) / 10_000
+    }
+}
+
+#[test]
+fn test_best_direct_route_across_protocols() {
+    let h = Harness::new();
+    let (a, b) = (h.token_a.clone(), h.token_b.clone());
+    let amount_in = 100_0000000i128;
+
+    // Same depth in A, B priced at 2.0 (AstroSwap), 2.1 (Soroswap) and 2.2 (Phoenix)
+    let pair = h.astroswap_pool(&a, &b, (10_000_0000000, 20_000_0000000));
+    h.soroswap_pool(&a, &b, (10_000_0000000, 21_000_0000000));
+    let phoenix = h.phoenix_pool(&a, &b, (10_000_0000000, 22_000_0000000));
+
+    // Each protocol is quoted through its own interface
+    let astroswap_quote = AstroSwapPairClient::new(&h.env, &pair).get_amount_out(&amount_in, &a);
+    let soroswap_quote = h
+        .soroswap
+        .router_get_amounts_out(&amount_in, &vec![&h.env, a.clone(), b.clone()])
+        .last()
+        .unwrap();
+    let phoenix_quote = phoenix.simulate_swap(&a, &amount_in).ask_amount;
+    let quotes = h.aggregator.get_all_quotes(&a, &b, &amount_in);
+    assert_eq!(quotes.len(), 3);
+    for expected in [
+        (0, astroswap_quote),
+        (1, soroswap_quote),
+        (2, phoenix_quote),
+    ] {
+        assert!(quotes.contains(expected), "missing quote {:?}", expected);
+    }
+    assert!(phoenix_quote > soroswap_quote && soroswap_quote > astroswap_quote);
+
+    // Phoenix wins the direct route
+    let route = h.aggregator.find_best_route(&a, &b, &amount_in);
+    assert_eq!(route.steps.len(), 1);
+    assert_eq!(route.steps.get(0).unwrap().protocol_id, 2);
+    assert_eq!(route.expected_output, phoenix_quote);
+
+    // The swap settles on Phoenix only
+    let expected_out = phoenix
+        .simulate_swap(&a, &h.after_fee(amount_in))
+        .ask_amount;
+    let pair_b = h.balance(&b, &pair);
+    let soroswap_b = h.balance(&b, &h.soroswap.address);
+    let phoenix_b = h.balance(&b, &phoenix.address);
+    let user_b = h.balance(&b, &h.user);
+
+    let amount_out = h.aggregator.swap(
+        &h.user,
+        &a,
+        &b,
+        &amount_in,
+        &(route.expected_output * 99 / 100),
+        &h.deadline(),
+    );
+    assert_eq!(amount_out, expected_out);
+    assert_eq!(h.balance(&b, &h.user) - user_b, amount_out);
+    assert_eq!(phoenix_b - h.balance(&b, &phoenix.address), amount_out);
+    assert_eq!(h.balance(&b, &pair), pair_b);
+    assert_eq!(h.balance(&b, &h.soroswap.address), soroswap_b);
+
+    // With Phoenix switched off, Soroswap is the next best venue
+    h.aggregator
+        .set_protocol_active(&h.admin, &Protocol::Phoenix, &false);
+    let route = h.aggregator.find_best_route(&a, &b, &amount_in);
+    assert_eq!(route.steps.get(0).unwrap().protocol_id, 1);
+
+    let expected_out = h
+        .soroswap
+        .router_get_amounts_out(&h.after_fee(amount_in), &vec![&h.env, a.clone(), b.clone()])
+        .last()
+        .unwrap();
+    let user_b = h.balance(&b, &h.user);
+    let amount_out = h
+        .aggregator
+        .swap(&h.user, &a, &b, &amount_in, &0, &h.deadline());
+    assert_eq!(amount_out, expected_out);
+    assert_eq!(h.balance(&b, &h.user) - user_b, amount_out);
+    assert_eq!(soroswap_b - h.balance(&b, &h.soroswap.address), amount_out);
+    assert_eq!(h.balance(&b, &pair), pair_b);
+}
+
+#[test]
+fn test_two_hop_route_across_protocols() {
+    let h = Harness::new();
+    let (a, b, x) = (h.token_a.clone(), h.token_b.clone(), h.token_x.clone());
+    let amount_in = 100_0000000i128;
+
+    // A only trades on Soroswap and B only on Phoenix, both against X
+    h.soroswap_pool(&a, &x, (10_000_0000000, 20_000_0000000));
+    let phoenix = h.phoenix_pool(&x, &b, (20_000_0000000, 10_000_0000000));
+
+    // Without a connector there is no route
+    assert!(h
+        .aggregator
+        .try_find_best_route(&a, &b, &amount_in)
+        .is_err());
+    h.aggregator.add_connector(&h.admin, &x);
+
+    // A -> X on Soroswap, then X -> B on Phoenix
+    let route = h.aggregator.find_best_route(&a, &b, &amount_in);
+    assert_eq!(route.steps.len(), 2);
+    let (first, second) = (route.steps.get(0).unwrap(), route.steps.get(1).unwrap());
+    assert_eq!((first.protocol_id, second.protocol_id), (1, 2));
+    assert_eq!(
+        (first.token_out.clone(), second.token_in.clone()),
+        (x.clone(), x.clone())
+    );
+    assert_eq!(
+        route.expected_output,
+        phoenix.simulate_swap(&x, &first.expected_out).ask_amount
+    );
+
+    let user_b = h.balance(&b, &h.user);
+    let amount_out = h.aggregator.swap(
+        &h.user,
+        &a,
+        &b,
+        &amount_in,
+        &(route.expected_output * 99 / 100),
+        &h.deadline(),
+    );
+    assert_eq!(h.balance(&b, &h.user) - user_b, amount_out);
+
+    // Only the aggregator fee separates the execution from the quote
+    let fee_bps = i128::from(h.aggregator.config().aggregator_fee_bps);
+    assert!(amount_out < route.expected_output);
+    assert!(amount_out * 10_000 >= route.expected_output * (10_000 - 2 * fee_bps));
+}
@@ -5,18 +5,26 @@
 use astroswap_stress_tests::*;
 use astroswap_stress_tests::scenarios::*;
 use chrono::Utc;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Parser, Debug)]
 #[command(name = "stress-runner")]
 #[command(about = "AstroSwap DEX Stress Test Runner", long_about = None)]
 struct Args {
-    /// Scenario to run (swap-load, pool-stress, router-paths, concurrent, all)
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Scenario to run (swap-load, pool-stress, router-paths, concurrent, storage-growth, oracle-twap, bank-run, graduation-storm, mev-sandwich, router-vs-aggregator, all)
     #[arg(short, long, default_value = "all")]
     scenario: String,
 
+    /// Weighted scenarios run interleaved, e.g. "swap-load:70,pool-stress:20,concurrent:10"
+    /// (overrides --scenario)
+    #[arg(long)]
+    mix: Option<String>,
+
     /// Test duration in seconds
     #[arg(short, long, default_value = "60")]
     duration: u64,
@@ -41,6 +49,90 @@ struct Args {
     #[arg(long, default_value = "4")]
     max_hops: u32,
 
+    /// Number of tokens in the router paths pair graph
+    #[arg(long, default_value = "8")]
+    graph_tokens: u32,
+
+    /// Percent of swap-load swaps concentrated on the hottest pair (0 spreads them uniformly)
+    #[arg(long, default_value = "0")]
+    hot_pair_pct: u32,
+
+    /// Account balance distribution (uniform, pareto, zipf)
+    #[arg(long, default_value = "uniform")]
+    balance_distribution: String,
+
+    /// Disable mid-run refill of depleted accounts
+    #[arg(long)]
+    no_refill: bool,
+
+    /// Comma-separated token classes for swap-load
+    /// (standard, fee-on-transfer, high-decimals, blocklist, pausable)
+    #[arg(long, default_value = "standard")]
+    token_classes: String,
+
+    /// Serve live metrics on this address (Prometheus at /metrics, JSON at /metrics.json)
+    #[arg(long)]
+    metrics_addr: Option<String>,
+
+    /// Reuse set-up environments snapshotted in this directory (created on first run)
+    #[arg(long)]
+    snapshot_dir: Option<String>,
+
+    /// Number of pairs to create in the storage-growth scenario
+    #[arg(long, default_value = "1000")]
+    growth_pairs: u32,
+
+    /// Allowed TWAP divergence from the reference in basis points (oracle-twap)
+    #[arg(long, default_value = "10")]
+    twap_tolerance_bps: u32,
+
+    /// Number of stakers withdrawing at pool end (bank-run)
+    #[arg(long, default_value = "300")]
+    bank_run_stakers: u32,
+
+    /// Share of the pool's emission funded up front in basis points (bank-run)
+    #[arg(long, default_value = "10000")]
+    bank_run_funding_bps: u32,
+
+    /// Swaps to execute on the freshly graduated pair (graduation-storm)
+    #[arg(long, default_value = "5000")]
+    storm_swaps: u32,
+
+    /// Victim routes per slippage tolerance level (mev-sandwich)
+    #[arg(long, default_value = "200")]
+    mev_victims: u32,
+
+    /// Trades run through both venues (router-vs-aggregator)
+    #[arg(long, default_value = "1000")]
+    h2h_trades: u32,
+
+    /// Daily operation volume for the mainnet cost projection
+    #[arg(long, default_value = "100000")]
+    daily_volume: u64,
+
+    /// Reset the budget at the start of every operation and record it for
+    /// failed operations too (adds a reset per operation)
+    #[arg(long)]
+    isolate_budget: bool,
+
+    /// Advance ledger time during scenarios on this schedule (linear, bursty, backwards)
+    #[arg(long)]
+    time_warp: Option<String>,
+
+    /// Simulated seconds per time-warp step
+    #[arg(long, default_value = "5")]
+    warp_step_secs: u64,
+
+    /// Per-error-code failure limits, e.g. "8=0,300=500" (code=max failures).
+    /// Defaults to failing on any reentrancy or arithmetic error
+    #[arg(long)]
+    error_thresholds: Option<String>,
+
+    /// Pass/fail rules replacing the defaults, e.g.
+    /// "success_rate>=0.99,latency_p95_ms<=500@swap,tps>=40#swap-load"
+    #[arg(long)]
+    assert: Option<String>,
+
     /// Output directory for results
     #[arg(short, long, default_value = "results")]
     output: PathBuf,
@@ -49,14 +141,73 @@ struct Args {
     #[arg(short, long, default_value = "both")]
     format: String,
 
+    /// Also write every raw operation metric to a gzip-compressed CSV file
+    #[arg(long)]
+    raw_metrics: bool,
+
+    /// Soak mode: run for this many hours (overrides --duration), writing
+    /// numbered report checkpoints and rotating raw metrics as it goes
+    #[arg(long)]
+    soak_hours: Option<f64>,
+
+    /// Minutes between soak checkpoints
+    #[arg(long, default_value = "15")]
+    checkpoint_minutes: u64,
+
     /// Enable verbose logging
     #[arg(short, long)]
     verbose: bool,
 }
 
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Compare two JSON reports metric by metric instead of running a test
+    Diff {
+        /// Baseline report
+        report_a: PathBuf,
+
+        /// Candidate report
+        report_b: PathBuf,
+
+        /// Highlight TPS changes beyond this percentage
+        #[arg(long, default_value = "10")]
+        tps_threshold: f64,
+
+        /// Highlight latency changes beyond this percentage
+        #[arg(long, default_value = "10")]
+        latency_threshold: f64,
+
+        /// Highlight success rate changes beyond this many percentage points
+        #[arg(long, default_value = "1")]
+        success_threshold: f64,
+
+        /// Also write the diff as markdown to this file
+        #[arg(long)]
+        markdown: Option<PathBuf>,
+    },
+}
+
 fn main() {
     let args = Args::parse();
 
+    if let Some(Command::Diff {
+        report_a,
+        report_b,
+        tps_threshold,
+        latency_threshold,
+        success_threshold,
+        markdown,
+    }) = &args.command
+    {
+        let thresholds = metrics::DiffThresholds {
+            tps_pct: *tps_threshold,
+            latency_pct: *latency_threshold,
+            success_rate_pts: *success_threshold,
+        };
+        diff_reports(report_a, report_b, thresholds, markdown.as_deref());
+        return;
+    }
+
     // Initialize logger
     if args.verbose {
         env_logger::Builder::from_default_env()
@@ -78,8 +229,48 @@ fn main() {
     config.num_accounts = args.accounts;
     config.num_pairs = args.pairs;
     config.output_dir = args.output.to_string_lossy().to_string();
+    config.metrics_addr = args.metrics_addr.clone();
+    config.snapshot_dir = args.snapshot_dir.clone();
     config.concurrent.num_workers = args.workers;
+    config.swap_load.hot_pair_pct = args.hot_pair_pct.min(100);
     config.router_paths.max_hops = args.max_hops;
+    config.router_paths.graph_tokens = args.graph_tokens;
+    config.storage_growth.target_pairs = args.growth_pairs;
+    config.oracle_twap.tolerance_bps = args.twap_tolerance_bps;
+    config.bank_run.num_stakers = args.bank_run_stakers;
+    config.bank_run.funding_bps = args.bank_run_funding_bps;
+    config.graduation_storm.num_swaps = args.storm_swaps;
+    config.mev_sandwich.victims_per_level = args.mev_victims;
+    config.router_vs_aggregator.num_trades = args.h2h_trades;
+    config.cost.daily_operations = args.daily_volume;
+    if args.isolate_budget {
+        config.budget_mode = BudgetMode::Isolated;
+    }
+    if let Some(schedule) = &args.time_warp {
+        config.time_warp.enabled = true;
+        config.time_warp.schedule = TimeSchedule::from_str(schedule).expect("Invalid time-warp schedule");
+        config.time_warp.seconds_per_step = args.warp_step_secs;
+    }
+    if let Some(hours) = args.soak_hours {
+        config.duration_seconds = (hours * 3600.0).round() as u64;
+        config.soak.enabled = true;
+        config.soak.checkpoint_interval_secs = args.checkpoint_minutes.max(1) * 60;
+    }
+    if let Some(thresholds) = &args.error_thresholds {
+        config.error_thresholds =
+            ErrorCodeThreshold::parse_list(thresholds).expect("Invalid error thresholds");
+    }
+    if let Some(rules) = &args.assert {
+        config.assertions = AssertionRule::parse_list(rules).expect("Invalid assertion rules");
+    }
+    config.accounts.distribution = BalanceDistribution::from_str(&args.balance_distribution)
+        .expect("Invalid balance distribution");
+    config.accounts.refill_enabled = !args.no_refill;
+    config.tokens.classes = args
+        .token_classes
+        .split(',')
+        .map(|c| TokenClass::from_str(c.trim()).expect("Invalid token class"))
+        .collect();
 
     // Parse scenario
     let scenarios = if args.scenario.to_lowercase() == "all" {
@@ -88,12 +279,22 @@ fn main() {
             Scenario::PoolStress,
             Scenario::RouterPaths,
             Scenario::Concurrent,
+            Scenario::StorageGrowth,
+            Scenario::OracleTwap,
+            Scenario::BankRun,
+            Scenario::GraduationStorm,
+            Scenario::MevSandwich,
+            Scenario::RouterVsAggregator,
         ]
     } else {
         vec![Scenario::from_str(&args.scenario).expect("Invalid scenario")]
     };
 
     config.scenarios = scenarios.clone();
+    if let Some(mix) = &args.mix {
+        config.mix = WeightedScenario::parse_mix(mix).expect("Invalid scenario mix");
+        config.scenarios = config.mix.iter().map(|w| w.scenario).collect();
+    }
 
     println!("╔═══════════════════════════════════════════════════════╗");
     println!("║      AstroSwap DEX Stress Test Runner v0.1.0        ║");
@@ -104,7 +305,17 @@ fn main() {
     println!("  Target TPS: {}", config.target_tps);
     println!("  Accounts:  {}", config.num_accounts);
     println!("  Pairs:     {}", config.num_pairs);
-    println!("  Scenarios: {}", scenarios.len());
+    if config.mix.is_empty() {
+        println!("  Scenarios: {}", scenarios.len());
+    } else {
+        println!("  Mix:       {} weighted scenarios", config.mix.len());
+    }
+    if config.soak.enabled {
+        println!(
+            "  Soak:      checkpoint every {} minutes",
+            config.soak.checkpoint_interval_secs / 60
+        );
+    }
     println!();
 
     // Generate test ID
@@ -118,9 +329,38 @@ fn main() {
 
     let start_time = Utc::now();
     let collector = MetricsCollector::new();
+    collector.set_budget_mode(config.budget_mode);
+
+    // Optional live metrics endpoint (kept alive until the end of main)
+    let _exporter = config.metrics_addr.as_ref().map(|addr| {
+        let exporter = metrics::MetricsExporter::start(addr, collector.clone())
+            .expect("Failed to start metrics exporter");
+        println!("Live metrics: http://{}/metrics", exporter.local_addr());
+        println!();
+        exporter
+    });
+
+    // Soak runs flush each window to disk instead of holding the whole run
+    let checkpointer = config.soak.enabled.then(|| {
+        let writer = metrics::CheckpointWriter::new(
+            test_id.clone(),
+            config.clone(),
+            &args.output,
+            args.raw_metrics,
+        );
+        metrics::SoakCheckpointer::start(writer, collector.clone())
+    });
+
+    // Run a weighted mix interleaved, or each scenario in turn
+    if !config.mix.is_empty() {
+        println!("═══════════════════════════════════════════════════════");
+        println!("Running: Mixed Workload");
+        MixedWorkloadScenario::new().run(&config, &collector);
+        println!();
+    }
 
-    // Run scenarios
-    for scenario in &scenarios {
+    let sequential: &[Scenario] = if config.mix.is_empty() { &scenarios } else { &[] };
+    for scenario in sequential {
         println!("═══════════════════════════════════════════════════════");
         match scenario {
             Scenario::SwapLoad => {
@@ -143,6 +383,36 @@ fn main() {
                 let scenario = ConcurrentScenario::new();
                 scenario.run(&config, &collector);
             }
+            Scenario::StorageGrowth => {
+                println!("Running: Storage Growth & TTL Test");
+                let scenario = StorageGrowthScenario::new();
+                scenario.run(&config, &collector);
+            }
+            Scenario::OracleTwap => {
+                println!("Running: Oracle TWAP Consistency Test");
+                let scenario = OracleTwapScenario::new();
+                scenario.run(&config, &collector);
+            }
+            Scenario::BankRun => {
+                println!("Running: Staking Bank-Run Test");
+                let scenario = BankRunScenario::new();
+                scenario.run(&config, &collector);
+            }
+            Scenario::GraduationStorm => {
+                println!("Running: Graduation Trading Storm Test");
+                let scenario = GraduationStormScenario::new();
+                scenario.run(&config, &collector);
+            }
+            Scenario::MevSandwich => {
+                println!("Running: MEV Sandwich Simulation");
+                let scenario = MevSandwichScenario::new();
+                scenario.run(&config, &collector);
+            }
+            Scenario::RouterVsAggregator => {
+                println!("Running: Router vs Aggregator Benchmark");
+                let scenario = RouterVsAggregatorScenario::new();
+                scenario.run(&config, &collector);
+            }
             Scenario::All => {
                 // This case is handled above
             }
@@ -150,17 +420,31 @@ fn main() {
         println!();
     }
 
+    let checkpoints = checkpointer.map(|c| c.finish()).unwrap_or_default();
+
     // Generate report
     println!("═══════════════════════════════════════════════════════");
     println!("Generating report...");
 
-    let report = metrics::TestReport::from_metrics(
+    // After checkpoints the collector only holds the window since the last one
+    let report_start = checkpoints.last().map_or(start_time, |c| c.end_time);
+    let mut report = metrics::TestReport::from_metrics(
         test_id.clone(),
         config.clone(),
-        start_time,
+        report_start,
         &collector,
     );
 
+    // A failed checkpoint fails the soak run
+    for checkpoint in checkpoints.iter().filter(|c| !c.test_passed) {
+        report.summary.test_passed = false;
+        report.summary.issues.push(format!(
+            "Checkpoint {} failed, see {}",
+            checkpoint.index,
+            checkpoint.report_path.display()
+        ));
+    }
+
     // Save report
     let json_path = args.output.join(format!("{}.json", test_id));
     let md_path = args.output.join(format!("{}.md", test_id));
@@ -183,6 +467,25 @@ fn main() {
         }
     }
 
+    if args.raw_metrics {
+        let csv_path = args.output.join(format!("{}.metrics.csv.gz", test_id));
+        let rows =
+            metrics::save_raw_csv_gz(&collector, &csv_path).expect("Failed to save raw metrics");
+        println!(
+            "Raw metrics ({} rows) saved to: {}",
+            rows,
+            csv_path.display()
+        );
+    }
+
+    if !checkpoints.is_empty() {
+        println!(
+            "Soak checkpoints: {} ({} operations before the final window)",
+            checkpoints.len(),
+            checkpoints.iter().map(|c| c.total_operations).sum::<usize>()
+        );
+    }
+
     // Print summary
     println!();
     println!("╔═══════════════════════════════════════════════════════╗");
@@ -196,6 +499,30 @@ fn main() {
     println!("  Test Status:         {}", if report.summary.test_passed { "✓ PASSED" } else { "✗ FAILED" });
     println!();
 
+    if !report.cost.per_operation.is_empty() {
+        println!("Projected fees (stroops per operation):");
+        let mut costs: Vec<_> = report.cost.per_operation.iter().collect();
+        costs.sort_by(|a, b| a.0.cmp(b.0));
+        for (operation, cost) in costs {
+            println!("  {:<18} {:>10}", operation, cost.fee_stroops);
+        }
+        println!(
+            "  Daily ({} ops):     {:.4} XLM",
+            report.cost.daily_operations, report.cost.daily_fee_xlm
+        );
+        println!();
+    }
+
+    if let Some(h2h) = &report.head_to_head {
+        println!(
+            "Router vs aggregator: {:+.2} bps per trade, failure rate {:.2}% vs {:.2}%",
+            h2h.avg_output_diff_bps,
+            h2h.router.failure_rate * 100.0,
+            h2h.aggregator.failure_rate * 100.0
+        );
+        println!();
+    }
+
     if !report.summary.issues.is_empty() {
         println!("Issues detected:");
         for issue in &report.summary.issues {
@@ -207,3 +534,21 @@ fn main() {
     // Exit with appropriate code
     std::process::exit(if report.summary.test_passed { 0 } else { 1 });
 }
+
+/// Print the metric deltas between two saved reports, optionally as markdown too
+fn diff_reports(
+    report_a: &Path,
+    report_b: &Path,
+    thresholds: metrics::DiffThresholds,
+    markdown: Option<&Path>,
+) {
+    let before = metrics::TestReport::load_json(report_a).expect("Failed to load baseline report");
+    let after = metrics::TestReport::load_json(report_b).expect("Failed to load candidate report");
+    let diff = metrics::ReportDiff::between(&before, &after, thresholds);
+
+    print!("{}", diff.to_console());
+    if let Some(path) = markdown {
+        fs::write(path, diff.to_markdown()).expect("Failed to save diff");
+        println!("Diff saved to: {}", path.display());
+    }
+}
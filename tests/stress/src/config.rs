@@ -2,6 +2,9 @@
 //!
 //! Defines configuration structures for stress and load testing scenarios.
 
+use crate::metrics::OperationType;
+use crate::utils::BudgetMode;
+use astroswap_shared::AstroSwapError;
 use serde::{Deserialize, Serialize};
 
 /// Network environment for testing
@@ -32,6 +35,18 @@ pub enum Scenario {
     RouterPaths,
     /// Concurrent mixed operations
     Concurrent,
+    /// Ledger-entry growth and TTL extension
+    StorageGrowth,
+    /// Oracle price updates and TWAP consistency
+    OracleTwap,
+    /// Simultaneous staking withdrawals at pool end
+    BankRun,
+    /// Graduation followed by a burst of trading on the new pair
+    GraduationStorm,
+    /// Sandwich attacks around victim swaps at several slippage tolerances
+    MevSandwich,
+    /// The same trade list through the router and the aggregator
+    RouterVsAggregator,
     /// All scenarios combined
     All,
 }
@@ -43,19 +58,248 @@ impl Scenario {
             "pool-stress" | "pool_stress" => Some(Scenario::PoolStress),
             "router-paths" | "router_paths" => Some(Scenario::RouterPaths),
             "concurrent" => Some(Scenario::Concurrent),
+            "storage-growth" | "storage_growth" => Some(Scenario::StorageGrowth),
+            "oracle-twap" | "oracle_twap" => Some(Scenario::OracleTwap),
+            "bank-run" | "bank_run" => Some(Scenario::BankRun),
+            "graduation-storm" | "graduation_storm" => Some(Scenario::GraduationStorm),
+            "mev-sandwich" | "mev_sandwich" => Some(Scenario::MevSandwich),
+            "router-vs-aggregator" | "router_vs_aggregator" => Some(Scenario::RouterVsAggregator),
             "all" => Some(Scenario::All),
             _ => None,
         }
     }
 
+    pub fn as_str(&self) -> &str {
+        match self {
+            Scenario::SwapLoad => "swap-load",
+            Scenario::PoolStress => "pool-stress",
+            Scenario::RouterPaths => "router-paths",
+            Scenario::Concurrent => "concurrent",
+            Scenario::StorageGrowth => "storage-growth",
+            Scenario::OracleTwap => "oracle-twap",
+            Scenario::BankRun => "bank-run",
+            Scenario::GraduationStorm => "graduation-storm",
+            Scenario::MevSandwich => "mev-sandwich",
+            Scenario::RouterVsAggregator => "router-vs-aggregator",
+            Scenario::All => "all",
+        }
+    }
+
     pub fn all() -> Vec<Self> {
         vec![
             Scenario::SwapLoad,
             Scenario::PoolStress,
             Scenario::RouterPaths,
             Scenario::Concurrent,
+            Scenario::StorageGrowth,
+            Scenario::OracleTwap,
+            Scenario::BankRun,
+            Scenario::GraduationStorm,
+            Scenario::MevSandwich,
+            Scenario::RouterVsAggregator,
+        ]
+    }
+}
+
+/// A scenario and its relative weight in a mixed workload
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WeightedScenario {
+    pub scenario: Scenario,
+    pub weight: u32,
+}
+
+impl WeightedScenario {
+    /// Parse a mix such as "swap-load:70,pool-stress:20,concurrent:10".
+    /// A scenario without a weight counts as 1; `all` and zero weights are rejected.
+    pub fn parse_mix(s: &str) -> Option<Vec<Self>> {
+        let mut mix = Vec::new();
+        for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (name, weight) = match part.split_once(':') {
+                Some((name, weight)) => (name.trim(), weight.trim().parse().ok()?),
+                None => (part, 1),
+            };
+            let scenario = Scenario::from_str(name)?;
+            if scenario == Scenario::All || weight == 0 {
+                return None;
+            }
+            mix.push(WeightedScenario { scenario, weight });
+        }
+        if mix.is_empty() {
+            None
+        } else {
+            Some(mix)
+        }
+    }
+}
+
+/// Maximum number of failures allowed for one contract error code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorCodeThreshold {
+    pub code: u32,
+    pub max_count: u64,
+}
+
+impl ErrorCodeThreshold {
+    /// Default thresholds: errors that indicate a contract bug rather than
+    /// load (reentrancy, arithmetic) fail the run on first occurrence
+    pub fn defaults() -> Vec<Self> {
+        [
+            AstroSwapError::Reentrancy,
+            AstroSwapError::Overflow,
+            AstroSwapError::Underflow,
+            AstroSwapError::DivisionByZero,
+        ]
+        .iter()
+        .map(|err| ErrorCodeThreshold { code: *err as u32, max_count: 0 })
+        .collect()
+    }
+
+    /// Parse thresholds such as "8=0,300=100". A code without a limit allows
+    /// no occurrences.
+    pub fn parse_list(s: &str) -> Option<Vec<Self>> {
+        let mut thresholds = Vec::new();
+        for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (code, max_count) = match part.split_once('=') {
+                Some((code, max)) => (code.trim().parse().ok()?, max.trim().parse().ok()?),
+                None => (part.parse().ok()?, 0),
+            };
+            thresholds.push(ErrorCodeThreshold { code, max_count });
+        }
+        Some(thresholds)
+    }
+}
+
+/// Run metric checked by an assertion rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AssertionMetric {
+    /// Share of successful operations (0.0 - 1.0)
+    SuccessRate,
+    LatencyAvgMs,
+    LatencyP50Ms,
+    LatencyP95Ms,
+    LatencyP99Ms,
+    /// Operations per second over the whole run
+    Tps,
+    /// Operations per second as a share of `target_tps`
+    TargetTpsRatio,
+    /// Distinct failure messages
+    DistinctErrors,
+}
+
+impl AssertionMetric {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "success_rate" => Some(AssertionMetric::SuccessRate),
+            "latency_avg_ms" | "avg_latency_ms" => Some(AssertionMetric::LatencyAvgMs),
+            "latency_p50_ms" | "p50_latency_ms" => Some(AssertionMetric::LatencyP50Ms),
+            "latency_p95_ms" | "p95_latency_ms" => Some(AssertionMetric::LatencyP95Ms),
+            "latency_p99_ms" | "p99_latency_ms" => Some(AssertionMetric::LatencyP99Ms),
+            "tps" => Some(AssertionMetric::Tps),
+            "target_tps_ratio" => Some(AssertionMetric::TargetTpsRatio),
+            "distinct_errors" => Some(AssertionMetric::DistinctErrors),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AssertionMetric::SuccessRate => "success_rate",
+            AssertionMetric::LatencyAvgMs => "latency_avg_ms",
+            AssertionMetric::LatencyP50Ms => "latency_p50_ms",
+            AssertionMetric::LatencyP95Ms => "latency_p95_ms",
+            AssertionMetric::LatencyP99Ms => "latency_p99_ms",
+            AssertionMetric::Tps => "tps",
+            AssertionMetric::TargetTpsRatio => "target_tps_ratio",
+            AssertionMetric::DistinctErrors => "distinct_errors",
+        }
+    }
+}
+
+/// Pass/fail rule on a run metric, optionally narrowed to one operation type
+/// and/or scenario
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssertionRule {
+    pub metric: AssertionMetric,
+    /// Violated if the metric is below this value
+    pub min: Option<f64>,
+    /// Violated if the metric is above this value
+    pub max: Option<f64>,
+    /// Only operations of this type; all operations if None
+    pub operation: Option<OperationType>,
+    /// Only this scenario of a mixed run, `*` for each scenario separately;
+    /// the whole run if None
+    pub scenario: Option<String>,
+    /// Violations fail the run; otherwise they are only reported as issues
+    pub enforce: bool,
+}
+
+impl AssertionRule {
+    /// Enforced rule bounding `metric` over the whole run
+    pub fn new(metric: AssertionMetric, min: Option<f64>, max: Option<f64>) -> Self {
+        Self {
+            metric,
+            min,
+            max,
+            operation: None,
+            scenario: None,
+            enforce: true,
+        }
+    }
+
+    /// Default rules: the run fails below 95% success; low success in a
+    /// scenario, low throughput, slow p95 and many distinct errors are reported
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::new(AssertionMetric::SuccessRate, Some(0.95), None),
+            Self {
+                scenario: Some("*".to_string()),
+                enforce: false,
+                ..Self::new(AssertionMetric::SuccessRate, Some(0.95), None)
+            },
+            Self {
+                enforce: false,
+                ..Self::new(AssertionMetric::TargetTpsRatio, Some(0.8), None)
+            },
+            Self {
+                enforce: false,
+                ..Self::new(AssertionMetric::LatencyP95Ms, None, Some(1000.0))
+            },
+            Self {
+                enforce: false,
+                ..Self::new(AssertionMetric::DistinctErrors, None, Some(5.0))
+            },
         ]
     }
+
+    /// Parse enforced rules such as
+    /// "success_rate>=0.99,latency_p95_ms<=500@swap,tps>=40#swap-load":
+    /// `@` narrows a rule to an operation type and `#` to a scenario
+    pub fn parse_list(s: &str) -> Option<Vec<Self>> {
+        let mut rules = Vec::new();
+        for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (part, scenario) = match part.split_once('#') {
+                Some((part, scenario)) => (part, Some(scenario.trim().to_string())),
+                None => (part, None),
+            };
+            let (part, operation) = match part.split_once('@') {
+                Some((part, operation)) => (part, Some(OperationType::from_str(operation.trim())?)),
+                None => (part, None),
+            };
+            let (metric, min, max) = if let Some((metric, value)) = part.split_once(">=") {
+                (metric, Some(value.trim().parse().ok()?), None)
+            } else if let Some((metric, value)) = part.split_once("<=") {
+                (metric, None, Some(value.trim().parse().ok()?))
+            } else {
+                return None;
+            };
+            rules.push(Self {
+                operation,
+                scenario,
+                ..Self::new(AssertionMetric::from_str(metric.trim())?, min, max)
+            });
+        }
+        Some(rules)
+    }
 }
 
 /// Main stress test configuration
@@ -79,14 +323,57 @@ pub struct StressConfig {
     /// Scenarios to run
     pub scenarios: Vec<Scenario>,
 
+    /// Weighted scenarios run interleaved in a single run; empty runs `scenarios`
+    /// one after another
+    pub mix: Vec<WeightedScenario>,
+
     /// Output directory for results
     pub output_dir: String,
 
+    /// Address for the live metrics endpoint (e.g. "127.0.0.1:9184"), disabled if None
+    pub metrics_addr: Option<String>,
+
+    /// Directory for environment snapshots reused across runs, disabled if None
+    pub snapshot_dir: Option<String>,
+
+    /// Test account balance distribution and refill behaviour
+    pub accounts: AccountConfig,
+
+    /// Token classes used when creating test tokens
+    pub tokens: TokenConfig,
+
+    /// Per-contract-error-code failure limits; exceeding one fails the run
+    pub error_thresholds: Vec<ErrorCodeThreshold>,
+
+    /// Pass/fail rules on success rate, latency and throughput
+    pub assertions: Vec<AssertionRule>,
+
+    /// Network fee parameters for mainnet cost projection
+    pub cost: CostConfig,
+
+    /// How the CPU and memory budget is attributed to each operation
+    pub budget_mode: BudgetMode,
+
+    /// Accelerated ledger time during scenarios
+    pub time_warp: TimeWarpConfig,
+
+    /// Flag unbounded ledger-entry or memory growth
+    pub leak_detection: LeakDetectionConfig,
+
+    /// Periodic report checkpoints for multi-hour runs
+    pub soak: SoakConfig,
+
     /// Scenario-specific configurations
     pub swap_load: SwapLoadConfig,
     pub pool_stress: PoolStressConfig,
     pub router_paths: RouterPathsConfig,
     pub concurrent: ConcurrentConfig,
+    pub storage_growth: StorageGrowthConfig,
+    pub oracle_twap: OracleTwapConfig,
+    pub bank_run: BankRunConfig,
+    pub graduation_storm: GraduationStormConfig,
+    pub mev_sandwich: MevSandwichConfig,
+    pub router_vs_aggregator: RouterVsAggregatorConfig,
 }
 
 impl Default for StressConfig {
@@ -98,11 +385,222 @@ impl Default for StressConfig {
             num_accounts: 30,
             num_pairs: 5,
             scenarios: vec![Scenario::All],
+            mix: Vec::new(),
             output_dir: "results".to_string(),
+            metrics_addr: None,
+            snapshot_dir: None,
+            accounts: AccountConfig::default(),
+            tokens: TokenConfig::default(),
+            error_thresholds: ErrorCodeThreshold::defaults(),
+            assertions: AssertionRule::defaults(),
+            cost: CostConfig::default(),
+            budget_mode: BudgetMode::default(),
+            time_warp: TimeWarpConfig::default(),
+            leak_detection: LeakDetectionConfig::default(),
+            soak: SoakConfig::default(),
             swap_load: SwapLoadConfig::default(),
             pool_stress: PoolStressConfig::default(),
             router_paths: RouterPathsConfig::default(),
             concurrent: ConcurrentConfig::default(),
+            storage_growth: StorageGrowthConfig::default(),
+            oracle_twap: OracleTwapConfig::default(),
+            bank_run: BankRunConfig::default(),
+            graduation_storm: GraduationStormConfig::default(),
+            mev_sandwich: MevSandwichConfig::default(),
+            router_vs_aggregator: RouterVsAggregatorConfig::default(),
+        }
+    }
+}
+
+impl StressConfig {
+    /// Copy of this configuration carrying `share` (0.0 - 1.0) of the load, used
+    /// for one component of a mixed workload
+    pub fn scaled(&self, share: f64) -> Self {
+        let share = share.clamp(0.0, 1.0);
+        let scale = |value: u32| ((value as f64 * share).round() as u32).max(1);
+
+        let mut config = self.clone();
+        config.mix = Vec::new();
+        config.target_tps = scale(self.target_tps);
+        config.concurrent.num_workers = scale(self.concurrent.num_workers);
+        config.router_paths.paths_per_second = scale(self.router_paths.paths_per_second);
+        config.oracle_twap.updates_per_second = scale(self.oracle_twap.updates_per_second);
+        config
+    }
+}
+
+/// How token balances are spread across test accounts
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BalanceDistribution {
+    /// Every account holds the same balance
+    Uniform,
+    /// Pareto (power-law) wealth: a few whales, a long tail of small traders.
+    /// Lower `alpha` means heavier concentration.
+    Pareto { alpha: f64 },
+    /// Zipf rank-based wealth: account `k` holds `1 / k^exponent` of the top balance
+    Zipf { exponent: f64 },
+}
+
+impl BalanceDistribution {
+    /// Parse a distribution name (uses default shape parameters)
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "uniform" => Some(BalanceDistribution::Uniform),
+            "pareto" => Some(BalanceDistribution::Pareto { alpha: 1.16 }), // 80/20 rule
+            "zipf" => Some(BalanceDistribution::Zipf { exponent: 1.0 }),
+            _ => None,
+        }
+    }
+
+    /// Relative wealth of each of `n` accounts, wealthiest first.
+    ///
+    /// Pareto weights are taken at evenly spaced quantiles so runs are reproducible.
+    pub fn weights(&self, n: usize) -> Vec<f64> {
+        (0..n)
+            .map(|i| match *self {
+                BalanceDistribution::Uniform => 1.0,
+                BalanceDistribution::Pareto { alpha } => {
+                    let quantile = (i as f64 + 0.5) / n as f64;
+                    quantile.powf(-1.0 / alpha.max(0.01))
+                }
+                BalanceDistribution::Zipf { exponent } => 1.0 / ((i + 1) as f64).powf(exponent),
+            })
+            .collect()
+    }
+}
+
+impl Default for BalanceDistribution {
+    fn default() -> Self {
+        BalanceDistribution::Uniform
+    }
+}
+
+/// Test account configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountConfig {
+    /// Balance distribution across accounts
+    pub distribution: BalanceDistribution,
+
+    /// Top up depleted accounts during the run
+    pub refill_enabled: bool,
+
+    /// Refill when a token balance drops below this share of the target (basis points)
+    pub refill_threshold_bps: u32,
+}
+
+impl Default for AccountConfig {
+    fn default() -> Self {
+        Self {
+            distribution: BalanceDistribution::Uniform,
+            refill_enabled: true,
+            refill_threshold_bps: 1000,          // refill below 10% of target
+        }
+    }
+}
+
+/// Kind of mock token used in a scenario
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TokenClass {
+    /// Stellar Asset Contract, 7 decimals
+    Standard,
+    /// Withholds a fee on every transfer (recipient receives less than sent)
+    FeeOnTransfer,
+    /// 18 decimals, amounts are scaled accordingly
+    HighDecimals,
+    /// Admin can block holders from sending or receiving
+    Blocklist,
+    /// Admin can pause all transfers
+    Pausable,
+}
+
+impl TokenClass {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "standard" => Some(TokenClass::Standard),
+            "fee-on-transfer" | "fee_on_transfer" | "fot" => Some(TokenClass::FeeOnTransfer),
+            "high-decimals" | "high_decimals" => Some(TokenClass::HighDecimals),
+            "blocklist" => Some(TokenClass::Blocklist),
+            "pausable" => Some(TokenClass::Pausable),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            TokenClass::Standard => "standard",
+            TokenClass::FeeOnTransfer => "fee_on_transfer",
+            TokenClass::HighDecimals => "high_decimals",
+            TokenClass::Blocklist => "blocklist",
+            TokenClass::Pausable => "pausable",
+        }
+    }
+
+    /// Token decimals for this class
+    pub fn decimals(&self) -> u32 {
+        match self {
+            TokenClass::HighDecimals => 18,
+            _ => 7,
+        }
+    }
+}
+
+/// Test token configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenConfig {
+    /// Token classes, assigned to created tokens round-robin
+    pub classes: Vec<TokenClass>,
+
+    /// Transfer fee for fee-on-transfer tokens in basis points
+    pub transfer_fee_bps: u32,
+
+    /// Per-iteration probability of toggling pause / a random block (0.0 - 1.0)
+    pub hostile_toggle_probability: f64,
+}
+
+impl Default for TokenConfig {
+    fn default() -> Self {
+        Self {
+            classes: vec![TokenClass::Standard],
+            transfer_fee_bps: 100,               // 1%
+            hostile_toggle_probability: 0.1,
+        }
+    }
+}
+
+/// Network fee parameters used to project mainnet costs from measured budgets.
+///
+/// The budget only covers CPU and memory; ledger reads/writes, events and rent
+/// are folded into `resource_overhead_stroops` when known.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostConfig {
+    /// Inclusion fee paid per transaction, in stroops
+    pub inclusion_fee_stroops: i64,
+
+    /// Resource fee per 10,000 CPU instructions, in stroops
+    pub fee_per_instructions_increment: i64,
+
+    /// Flat per-transaction resource fee for costs the budget doesn't measure
+    pub resource_overhead_stroops: i64,
+
+    /// Per-transaction CPU instruction limit
+    pub tx_max_instructions: u64,
+
+    /// Per-transaction memory limit in bytes
+    pub tx_memory_limit: u64,
+
+    /// Daily operation volume the aggregate projection is made for
+    pub daily_operations: u64,
+}
+
+impl Default for CostConfig {
+    fn default() -> Self {
+        Self {
+            inclusion_fee_stroops: 100,
+            fee_per_instructions_increment: 25,
+            resource_overhead_stroops: 0,
+            tx_max_instructions: 100_000_000,
+            tx_memory_limit: 41_943_040,         // 40 MiB
+            daily_operations: 100_000,
         }
     }
 }
@@ -124,6 +622,10 @@ pub struct SwapLoadConfig {
 
     /// Whether to test both directions (A->B and B->A)
     pub bidirectional: bool,
+
+    /// Percent of swaps sent to the hottest pair (the first one), the rest
+    /// spread uniformly over the others. 0 spreads all swaps uniformly
+    pub hot_pair_pct: u32,
 }
 
 impl Default for SwapLoadConfig {
@@ -134,6 +636,7 @@ impl Default for SwapLoadConfig {
             min_slippage_bps: 10,                // 0.1%
             max_slippage_bps: 500,               // 5%
             bidirectional: true,
+            hot_pair_pct: 0,
         }
     }
 }
@@ -175,9 +678,19 @@ pub struct RouterPathsConfig {
     /// Minimum number of hops in a path
     pub min_hops: u32,
 
-    /// Maximum number of hops in a path
+    /// Maximum number of hops in a path (paths above the router limit of 4
+    /// hops are exercised as expected rejections)
     pub max_hops: u32,
 
+    /// Number of tokens in the randomly generated pair graph
+    pub graph_tokens: u32,
+
+    /// Probability of a pair existing between two non-adjacent tokens (0.0 - 1.0)
+    pub edge_probability: f64,
+
+    /// Upper bound on enumerated paths
+    pub max_paths: u32,
+
     /// Number of different paths to test per second
     pub paths_per_second: u32,
 
@@ -193,6 +706,9 @@ impl Default for RouterPathsConfig {
         Self {
             min_hops: 2,
             max_hops: 4,
+            graph_tokens: 8,
+            edge_probability: 0.3,
+            max_paths: 500,
             paths_per_second: 20,
             test_path_optimization: true,
             max_price_impact_bps: 1000,          // 10%
@@ -235,6 +751,363 @@ impl Default for ConcurrentConfig {
     }
 }
 
+/// Storage growth and TTL test configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageGrowthConfig {
+    /// Number of pairs to create through the factory
+    pub target_pairs: u32,
+
+    /// Number of distinct staking positions to open
+    pub target_stakes: u32,
+
+    /// Number of launchpad graduations through the bridge
+    pub target_graduations: u32,
+
+    /// Number of staking pools positions are spread across
+    pub num_staking_pools: u32,
+
+    /// Operations per batch between storage snapshots
+    pub batch_size: u32,
+
+    /// Ledgers advanced after each batch, so earlier entries age during the run
+    pub ledgers_per_batch: u32,
+
+    /// Ledgers skipped at the end of the run to simulate archival
+    pub archival_jump_ledgers: u32,
+}
+
+impl Default for StorageGrowthConfig {
+    fn default() -> Self {
+        Self {
+            target_pairs: 1000,
+            target_stakes: 2000,
+            target_graduations: 100,
+            num_staking_pools: 10,
+            batch_size: 50,
+            ledgers_per_batch: 100,
+            archival_jump_ledgers: 120_960,      // ~1 week of 5s ledgers
+        }
+    }
+}
+
+/// Oracle update and TWAP consistency test configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OracleTwapConfig {
+    /// Number of tokens with price feeds
+    pub num_tokens: u32,
+
+    /// Price updates pushed per second
+    pub updates_per_second: u32,
+
+    /// Minimum simulated seconds between consecutive updates
+    pub min_update_interval_secs: u64,
+
+    /// Maximum simulated seconds between consecutive updates
+    pub max_update_interval_secs: u64,
+
+    /// Maximum price move per update in basis points (random walk)
+    pub volatility_bps: u32,
+
+    /// TWAP windows queried after every batch, in seconds
+    pub twap_windows: Vec<u64>,
+
+    /// Allowed divergence from the reference TWAP in basis points
+    pub tolerance_bps: u32,
+}
+
+impl Default for OracleTwapConfig {
+    fn default() -> Self {
+        Self {
+            num_tokens: 3,
+            updates_per_second: 50,
+            min_update_interval_secs: 1,
+            max_update_interval_secs: 60,
+            volatility_bps: 200,                 // 2% per update
+            twap_windows: vec![300, 900, 3600],
+            tolerance_bps: 10,                   // 0.1%
+        }
+    }
+}
+
+/// Staking bank-run test configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BankRunConfig {
+    /// Number of stakers withdrawing at pool end
+    pub num_stakers: u32,
+
+    /// Pool reward rate (7 decimals)
+    pub reward_per_second: i128,
+
+    /// Pool duration in seconds
+    pub pool_duration_secs: u64,
+
+    /// Share of the pool's total emission funded up front (basis points)
+    pub funding_bps: u32,
+
+    /// Share of stakers that claim before unstaking, the rest unstake directly
+    /// (basis points)
+    pub claim_first_bps: u32,
+}
+
+impl Default for BankRunConfig {
+    fn default() -> Self {
+        Self {
+            num_stakers: 300,
+            reward_per_second: 10_0000000,
+            pool_duration_secs: 7 * 24 * 60 * 60, // within the 1.0x multiplier tier
+            funding_bps: 10_000,                  // fully funded
+            claim_first_bps: 5_000,
+        }
+    }
+}
+
+/// Graduation trading-storm test configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraduationStormConfig {
+    /// Graduated token liquidity seeded by the graduation (7 decimals)
+    pub token_liquidity: i128,
+
+    /// Quote token liquidity seeded by the graduation (7 decimals)
+    pub quote_liquidity: i128,
+
+    /// Swaps to execute after the graduation
+    pub num_swaps: u32,
+
+    /// Swaps quoted against the same reserves, i.e. landing in one ledger
+    pub swaps_per_ledger: u32,
+
+    /// Share of swaps buying the graduated token (basis points)
+    pub buy_bps: u32,
+
+    /// Minimum swap size in quote token terms (7 decimals)
+    pub min_swap_amount: i128,
+
+    /// Maximum swap size in quote token terms (7 decimals)
+    pub max_swap_amount: i128,
+
+    /// Minimum slippage tolerance in basis points
+    pub min_slippage_bps: u32,
+
+    /// Maximum slippage tolerance in basis points
+    pub max_slippage_bps: u32,
+}
+
+impl Default for GraduationStormConfig {
+    fn default() -> Self {
+        Self {
+            token_liquidity: 1_000_000_0000000,
+            quote_liquidity: 100_000_0000000,
+            num_swaps: 5_000,
+            swaps_per_ledger: 20,
+            buy_bps: 6_000,                      // launch buy pressure
+            min_swap_amount: 10_0000000,
+            max_swap_amount: 2_000_0000000,
+            min_slippage_bps: 50,                // 0.5%
+            max_slippage_bps: 1_000,             // 10%
+        }
+    }
+}
+
+/// MEV sandwich simulation configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MevSandwichConfig {
+    /// Victim routes simulated per tolerance level
+    pub victims_per_level: u32,
+
+    /// Per-hop slippage tolerances to compare (basis points); the aggregator
+    /// hard-codes 100
+    pub tolerance_levels_bps: Vec<u32>,
+
+    /// Hops per victim route
+    pub hops: u32,
+
+    /// Liquidity of each token in every pool (7 decimals)
+    pub pool_liquidity: i128,
+
+    /// Minimum victim trade size (7 decimals)
+    pub min_victim_amount: i128,
+
+    /// Maximum victim trade size (7 decimals)
+    pub max_victim_amount: i128,
+
+    /// Largest front-run the attacker can fund (7 decimals)
+    pub attacker_capital: i128,
+}
+
+impl Default for MevSandwichConfig {
+    fn default() -> Self {
+        Self {
+            victims_per_level: 200,
+            tolerance_levels_bps: vec![10, 50, 100, 300],
+            hops: 2,
+            pool_liquidity: 1_000_000_0000000,
+            min_victim_amount: 100_0000000,
+            max_victim_amount: 20_000_0000000,
+            attacker_capital: 500_000_0000000,
+        }
+    }
+}
+
+/// Router vs aggregator head-to-head configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouterVsAggregatorConfig {
+    /// Trades in the list run through both venues
+    pub num_trades: u32,
+
+    /// Tokens in the pair graph
+    pub graph_tokens: u32,
+
+    /// Probability of a pair between tokens not already chained together
+    pub edge_probability: f64,
+
+    /// Minimum liquidity of each token in a pool (7 decimals)
+    pub min_liquidity: i128,
+
+    /// Maximum liquidity of each token in a pool (7 decimals)
+    pub max_liquidity: i128,
+
+    /// Largest deviation of a pool's price from par (basis points), so
+    /// indirect routes can beat the direct one
+    pub max_price_skew_bps: u32,
+
+    /// Minimum trade size (7 decimals)
+    pub min_trade_amount: i128,
+
+    /// Maximum trade size (7 decimals)
+    pub max_trade_amount: i128,
+
+    /// Slippage tolerance against each venue's own quote (basis points)
+    pub slippage_bps: u32,
+}
+
+impl Default for RouterVsAggregatorConfig {
+    fn default() -> Self {
+        Self {
+            num_trades: 1_000,
+            graph_tokens: 6,
+            edge_probability: 0.5,
+            min_liquidity: 100_000_0000000,
+            max_liquidity: 1_000_000_0000000,
+            max_price_skew_bps: 500,             // 5%
+            min_trade_amount: 100_0000000,
+            max_trade_amount: 10_000_0000000,
+            slippage_bps: 50,                    // 0.5%
+        }
+    }
+}
+
+/// Schedule for advancing the ledger clock
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeSchedule {
+    /// Fixed step every tick
+    Linear,
+    /// Fixed steps with a large jump every `burst_every` ticks
+    Bursty,
+    /// Fixed steps with a rewind every `rewind_every` ticks, to check contracts
+    /// cope with non-monotonic close times
+    Backwards,
+}
+
+impl TimeSchedule {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "linear" => Some(TimeSchedule::Linear),
+            "bursty" => Some(TimeSchedule::Bursty),
+            "backwards" => Some(TimeSchedule::Backwards),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            TimeSchedule::Linear => "linear",
+            TimeSchedule::Bursty => "bursty",
+            TimeSchedule::Backwards => "backwards",
+        }
+    }
+}
+
+/// Accelerated ledger time configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeWarpConfig {
+    /// Advance the ledger clock during scenarios
+    pub enabled: bool,
+
+    /// How the clock moves
+    pub schedule: TimeSchedule,
+
+    /// Simulated seconds per tick
+    pub seconds_per_step: u64,
+
+    /// Simulated seconds per burst (bursty schedule)
+    pub burst_seconds: u64,
+
+    /// Ticks between bursts (bursty schedule)
+    pub burst_every: u64,
+
+    /// Seconds moved back per rewind (backwards schedule)
+    pub rewind_seconds: u64,
+
+    /// Ticks between rewinds (backwards schedule)
+    pub rewind_every: u64,
+}
+
+impl Default for TimeWarpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            schedule: TimeSchedule::Linear,
+            seconds_per_step: 5,                 // one ledger close
+            burst_seconds: 86_400,               // 1 day
+            burst_every: 100,
+            rewind_seconds: 60,
+            rewind_every: 250,
+        }
+    }
+}
+
+/// Ledger-entry and memory leak detection during long runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeakDetectionConfig {
+    /// Sample ledger entries and per-operation memory
+    pub enabled: bool,
+
+    /// Operations per sample window
+    pub window_ops: u64,
+
+    /// Windows needed before growth is judged
+    pub min_windows: usize,
+}
+
+impl Default for LeakDetectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window_ops: 1000,
+            min_windows: 5,
+        }
+    }
+}
+
+/// Long-duration soak run configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoakConfig {
+    /// Flush report checkpoints and rotate raw metrics while the run is in progress
+    pub enabled: bool,
+
+    /// Seconds between checkpoints
+    pub checkpoint_interval_secs: u64,
+}
+
+impl Default for SoakConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            checkpoint_interval_secs: 900,       // 15 minutes
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -245,6 +1118,21 @@ mod tests {
         assert_eq!(Scenario::from_str("pool_stress"), Some(Scenario::PoolStress));
         assert_eq!(Scenario::from_str("router-paths"), Some(Scenario::RouterPaths));
         assert_eq!(Scenario::from_str("concurrent"), Some(Scenario::Concurrent));
+        assert_eq!(Scenario::from_str("storage-growth"), Some(Scenario::StorageGrowth));
+        assert_eq!(Scenario::from_str("oracle_twap"), Some(Scenario::OracleTwap));
+        assert_eq!(Scenario::from_str("bank-run"), Some(Scenario::BankRun));
+        assert_eq!(
+            Scenario::from_str("graduation-storm"),
+            Some(Scenario::GraduationStorm)
+        );
+        assert_eq!(
+            Scenario::from_str("mev_sandwich"),
+            Some(Scenario::MevSandwich)
+        );
+        assert_eq!(
+            Scenario::from_str("router-vs-aggregator"),
+            Some(Scenario::RouterVsAggregator)
+        );
         assert_eq!(Scenario::from_str("all"), Some(Scenario::All));
         assert_eq!(Scenario::from_str("invalid"), None);
     }
@@ -258,13 +1146,115 @@ mod tests {
         assert_eq!(config.num_accounts, 30);
     }
 
+    #[test]
+    fn test_balance_distribution_weights() {
+        assert_eq!(BalanceDistribution::Uniform.weights(3), vec![1.0, 1.0, 1.0]);
+
+        let pareto = BalanceDistribution::Pareto { alpha: 1.16 }.weights(10);
+        assert!(pareto.windows(2).all(|w| w[0] > w[1]));
+
+        let zipf = BalanceDistribution::Zipf { exponent: 1.0 }.weights(4);
+        assert_eq!(zipf, vec![1.0, 0.5, 1.0 / 3.0, 0.25]);
+
+        assert_eq!(BalanceDistribution::from_str("ZIPF"), Some(BalanceDistribution::Zipf { exponent: 1.0 }));
+        assert_eq!(BalanceDistribution::from_str("lognormal"), None);
+    }
+
+    #[test]
+    fn test_token_class_from_str() {
+        assert_eq!(TokenClass::from_str("fot"), Some(TokenClass::FeeOnTransfer));
+        assert_eq!(TokenClass::from_str("high-decimals"), Some(TokenClass::HighDecimals));
+        assert_eq!(TokenClass::from_str("rebasing"), None);
+        assert_eq!(TokenClass::HighDecimals.decimals(), 18);
+        assert_eq!(TokenClass::Blocklist.decimals(), 7);
+    }
+
+    #[test]
+    fn test_time_schedule_from_str() {
+        assert_eq!(TimeSchedule::from_str("bursty"), Some(TimeSchedule::Bursty));
+        assert_eq!(TimeSchedule::from_str("BACKWARDS"), Some(TimeSchedule::Backwards));
+        assert_eq!(TimeSchedule::from_str("realtime"), None);
+        assert_eq!(TimeSchedule::Linear.as_str(), "linear");
+    }
+
+    #[test]
+    fn test_parse_mix() {
+        let mix = WeightedScenario::parse_mix("swap-load:70, pool-stress:20,concurrent").unwrap();
+        assert_eq!(mix.len(), 3);
+        assert_eq!(mix[0], WeightedScenario { scenario: Scenario::SwapLoad, weight: 70 });
+        assert_eq!(mix[2].weight, 1);
+
+        assert!(WeightedScenario::parse_mix("swap-load:0").is_none());
+        assert!(WeightedScenario::parse_mix("all:10").is_none());
+        assert!(WeightedScenario::parse_mix("swap-load:x").is_none());
+        assert!(WeightedScenario::parse_mix("").is_none());
+    }
+
+    #[test]
+    fn test_parse_error_thresholds() {
+        let thresholds = ErrorCodeThreshold::parse_list("8=0, 300=100,5").unwrap();
+        assert_eq!(thresholds.len(), 3);
+        assert_eq!(thresholds[1], ErrorCodeThreshold { code: 300, max_count: 100 });
+        assert_eq!(thresholds[2], ErrorCodeThreshold { code: 5, max_count: 0 });
+        assert!(ErrorCodeThreshold::parse_list("reentrancy").is_none());
+
+        let defaults = ErrorCodeThreshold::defaults();
+        assert!(defaults.contains(&ErrorCodeThreshold { code: AstroSwapError::Reentrancy as u32, max_count: 0 }));
+    }
+
+    #[test]
+    fn test_parse_assertion_rules() {
+        let rules =
+            AssertionRule::parse_list("success_rate>=0.99, p95_latency_ms<=500@swap,tps>=40#swap-load")
+                .unwrap();
+        assert_eq!(rules.len(), 3);
+        assert_eq!(
+            rules[0],
+            AssertionRule::new(AssertionMetric::SuccessRate, Some(0.99), None)
+        );
+        assert_eq!(rules[1].metric, AssertionMetric::LatencyP95Ms);
+        assert_eq!(rules[1].max, Some(500.0));
+        assert_eq!(rules[1].operation, Some(OperationType::Swap));
+        assert_eq!(rules[2].scenario.as_deref(), Some("swap-load"));
+        assert!(rules.iter().all(|rule| rule.enforce));
+
+        assert!(AssertionRule::parse_list("success_rate=0.99").is_none());
+        assert!(AssertionRule::parse_list("throughput>=10").is_none());
+        assert!(AssertionRule::parse_list("tps>=10@teleport").is_none());
+
+        // Only the overall success rate fails a run by default
+        let enforced: Vec<_> = AssertionRule::defaults()
+            .into_iter()
+            .filter(|rule| rule.enforce)
+            .collect();
+        assert_eq!(
+            enforced,
+            vec![AssertionRule::new(AssertionMetric::SuccessRate, Some(0.95), None)]
+        );
+    }
+
+    #[test]
+    fn test_scaled_config() {
+        let config = StressConfig::default();
+        let scaled = config.scaled(0.2);
+        assert_eq!(scaled.target_tps, 10);
+        assert_eq!(scaled.concurrent.num_workers, 4);
+        assert_eq!(config.scaled(0.0).target_tps, 1);
+    }
+
     #[test]
     fn test_scenario_all() {
         let scenarios = Scenario::all();
-        assert_eq!(scenarios.len(), 4);
+        assert_eq!(scenarios.len(), 10);
         assert!(scenarios.contains(&Scenario::SwapLoad));
         assert!(scenarios.contains(&Scenario::PoolStress));
         assert!(scenarios.contains(&Scenario::RouterPaths));
         assert!(scenarios.contains(&Scenario::Concurrent));
+        assert!(scenarios.contains(&Scenario::StorageGrowth));
+        assert!(scenarios.contains(&Scenario::OracleTwap));
+        assert!(scenarios.contains(&Scenario::BankRun));
+        assert!(scenarios.contains(&Scenario::GraduationStorm));
+        assert!(scenarios.contains(&Scenario::MevSandwich));
+        assert!(scenarios.contains(&Scenario::RouterVsAggregator));
     }
 }
@@ -21,7 +21,10 @@ pub mod pair_wasm {
 }
 
 // Re-exports for convenience
-pub use config::{Network, Scenario, StressConfig};
+pub use config::{
+    AssertionMetric, AssertionRule, BalanceDistribution, ErrorCodeThreshold, LeakDetectionConfig,
+    Network, Scenario, SoakConfig, StressConfig, TimeSchedule, TokenClass, WeightedScenario,
+};
 pub use scenarios::StressScenario;
 pub use metrics::{MetricsCollector, TestReport};
-pub use utils::{AccountPool, TokenManager};
+pub use utils::{AccountPool, BudgetMode, TokenManager};
@@ -0,0 +1,216 @@
+//! Assertion Rules
+//!
+//! Evaluates the `StressConfig::assertions` rules against collected metrics.
+//! Each rule bounds one metric, optionally for a single operation type and/or
+//! scenario; enforced violations fail the run, the rest are reported as issues.
+
+use super::collector::{MetricsCollector, OperationMetric};
+use crate::config::{AssertionMetric, AssertionRule};
+
+/// A rule whose bound was not met
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssertionViolation {
+    pub message: String,
+    pub enforced: bool,
+}
+
+/// Value of `metric` over `metrics`, None if there is nothing to measure
+pub fn measure(
+    metric: AssertionMetric,
+    metrics: &[OperationMetric],
+    elapsed_seconds: f64,
+    target_tps: u32,
+) -> Option<f64> {
+    if metrics.is_empty() {
+        return None;
+    }
+    let count = metrics.len() as f64;
+    let tps = if elapsed_seconds > 0.0 {
+        count / elapsed_seconds
+    } else {
+        0.0
+    };
+
+    let value = match metric {
+        AssertionMetric::SuccessRate => metrics.iter().filter(|m| m.success).count() as f64 / count,
+        AssertionMetric::LatencyAvgMs => {
+            metrics.iter().map(|m| m.duration_micros).sum::<u64>() as f64 / count / 1000.0
+        }
+        AssertionMetric::LatencyP50Ms => latency_percentile_ms(metrics, 0.50),
+        AssertionMetric::LatencyP95Ms => latency_percentile_ms(metrics, 0.95),
+        AssertionMetric::LatencyP99Ms => latency_percentile_ms(metrics, 0.99),
+        AssertionMetric::Tps => tps,
+        AssertionMetric::TargetTpsRatio => {
+            if target_tps == 0 {
+                return None;
+            }
+            tps / target_tps as f64
+        }
+        AssertionMetric::DistinctErrors => {
+            let mut errors: Vec<&str> = metrics.iter().filter_map(|m| m.error.as_deref()).collect();
+            errors.sort_unstable();
+            errors.dedup();
+            errors.len() as f64
+        }
+    };
+    Some(value)
+}
+
+/// Violations of every rule in `rules`
+pub fn assertion_violations(
+    collector: &MetricsCollector,
+    rules: &[AssertionRule],
+    target_tps: u32,
+) -> Vec<AssertionViolation> {
+    let elapsed_seconds = collector.elapsed().as_secs_f64();
+    let mut violations = Vec::new();
+
+    for rule in rules {
+        let scopes: Vec<(Option<String>, Vec<OperationMetric>)> = match rule.scenario.as_deref() {
+            None => vec![(None, collector.get_metrics())],
+            Some("*") => collector
+                .scenario_labels()
+                .into_iter()
+                .map(|label| {
+                    let metrics = collector.filtered(&label).get_metrics();
+                    (Some(label), metrics)
+                })
+                .collect(),
+            Some(label) => vec![(
+                Some(label.to_string()),
+                collector.filtered(label).get_metrics(),
+            )],
+        };
+
+        for (scenario, metrics) in scopes {
+            let metrics: Vec<OperationMetric> = match rule.operation {
+                Some(operation) => metrics
+                    .into_iter()
+                    .filter(|m| m.operation == operation)
+                    .collect(),
+                None => metrics,
+            };
+            let Some(value) = measure(rule.metric, &metrics, elapsed_seconds, target_tps) else {
+                continue;
+            };
+
+            let breach = match (rule.min, rule.max) {
+                (Some(min), _) if value < min => Some(format!("below minimum {}", min)),
+                (_, Some(max)) if value > max => Some(format!("above maximum {}", max)),
+                _ => None,
+            };
+            if let Some(breach) = breach {
+                violations.push(AssertionViolation {
+                    message: format!(
+                        "{}{}: {:.4} {}",
+                        rule.metric.as_str(),
+                        describe_scope(rule, scenario.as_deref()),
+                        value,
+                        breach
+                    ),
+                    enforced: rule.enforce,
+                });
+            }
+        }
+    }
+
+    violations
+}
+
+/// " (swap, swap-load)" style suffix naming a rule's operation and scenario
+fn describe_scope(rule: &AssertionRule, scenario: Option<&str>) -> String {
+    let parts: Vec<&str> = rule
+        .operation
+        .as_ref()
+        .map(|operation| operation.as_str())
+        .into_iter()
+        .chain(scenario)
+        .collect();
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", parts.join(", "))
+    }
+}
+
+fn latency_percentile_ms(metrics: &[OperationMetric], percentile: f64) -> f64 {
+    let mut durations: Vec<u64> = metrics.iter().map(|m| m.duration_micros).collect();
+    durations.sort_unstable();
+    let index = ((durations.len() as f64) * percentile) as usize;
+    durations[index.min(durations.len() - 1)] as f64 / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::OperationType;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    fn record(collector: &MetricsCollector, operation: OperationType, micros: u64, success: bool) {
+        let error = (!success).then(|| "Swap failed: SlippageExceeded".to_string());
+        collector.record(
+            operation,
+            Duration::from_micros(micros),
+            success,
+            error,
+            HashMap::new(),
+        );
+    }
+
+    #[test]
+    fn test_assertion_violations() {
+        let collector = MetricsCollector::new();
+        for _ in 0..9 {
+            record(&collector, OperationType::Swap, 2_000, true);
+        }
+        record(&collector, OperationType::Swap, 2_000, false);
+        record(&collector, OperationType::Stake, 500_000, true);
+
+        let rules = AssertionRule::parse_list(
+            "success_rate>=0.95,success_rate>=0.9@swap,latency_p99_ms<=100@stake,distinct_errors<=1",
+        )
+        .unwrap();
+        let violations = assertion_violations(&collector, &rules, 0);
+
+        // 10 of 11 succeed overall (90.9%), swaps at exactly 90%, stake p99 500ms
+        assert_eq!(violations.len(), 2);
+        assert!(violations[0]
+            .message
+            .starts_with("success_rate: 0.9091 below minimum 0.95"));
+        assert_eq!(
+            violations[1].message,
+            "latency_p99_ms (stake): 500.0000 above maximum 100"
+        );
+        assert!(violations.iter().all(|v| v.enforced));
+
+        // Rules for operations that never ran are skipped
+        let unused = AssertionRule::parse_list("success_rate>=1@graduate").unwrap();
+        assert!(assertion_violations(&collector, &unused, 0).is_empty());
+    }
+
+    #[test]
+    fn test_scenario_scoped_rules() {
+        let collector = MetricsCollector::new();
+        let swaps = collector.scoped("swap-load");
+        let pools = collector.scoped("pool-stress");
+        record(&swaps, OperationType::Swap, 1_000, true);
+        record(&pools, OperationType::AddLiquidity, 1_000, false);
+
+        let each = vec![AssertionRule {
+            scenario: Some("*".to_string()),
+            enforce: false,
+            ..AssertionRule::new(AssertionMetric::SuccessRate, Some(0.95), None)
+        }];
+        let violations = assertion_violations(&collector, &each, 0);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].message,
+            "success_rate (pool-stress): 0.0000 below minimum 0.95"
+        );
+        assert!(!violations[0].enforced);
+
+        let named = AssertionRule::parse_list("success_rate>=1#swap-load").unwrap();
+        assert!(assertion_violations(&collector, &named, 0).is_empty());
+    }
+}
@@ -2,8 +2,10 @@
 //!
 //! Real-time collection of stress test metrics including latency, throughput, and errors.
 
+use crate::utils::budget::{record_budget, reset_budget, BudgetMode, CPU_INSTRUCTIONS_KEY};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use soroban_sdk::Env;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
@@ -19,9 +21,29 @@ pub enum OperationType {
     Stake,
     Unstake,
     ClaimRewards,
+    Graduate,
+    OracleUpdate,
+    TwapQuery,
 }
 
 impl OperationType {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "swap" => Some(OperationType::Swap),
+            "add_liquidity" => Some(OperationType::AddLiquidity),
+            "remove_liquidity" => Some(OperationType::RemoveLiquidity),
+            "create_pair" => Some(OperationType::CreatePair),
+            "multi_hop_swap" => Some(OperationType::MultiHopSwap),
+            "stake" => Some(OperationType::Stake),
+            "unstake" => Some(OperationType::Unstake),
+            "claim_rewards" => Some(OperationType::ClaimRewards),
+            "graduate" => Some(OperationType::Graduate),
+            "oracle_update" => Some(OperationType::OracleUpdate),
+            "twap_query" => Some(OperationType::TwapQuery),
+            _ => None,
+        }
+    }
+
     pub fn as_str(&self) -> &str {
         match self {
             OperationType::Swap => "swap",
@@ -32,6 +54,9 @@ impl OperationType {
             OperationType::Stake => "stake",
             OperationType::Unstake => "unstake",
             OperationType::ClaimRewards => "claim_rewards",
+            OperationType::Graduate => "graduate",
+            OperationType::OracleUpdate => "oracle_update",
+            OperationType::TwapQuery => "twap_query",
         }
     }
 }
@@ -47,10 +72,21 @@ pub struct OperationMetric {
     pub metadata: HashMap<String, String>,
 }
 
+/// Ledger entries held by a scenario's `Env` after a given number of operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerEntrySample {
+    /// Operations recorded by the scenario when the sample was taken
+    pub operations: u64,
+    pub ledger_entries: u64,
+    pub scenario: Option<String>,
+}
+
 /// Thread-safe metrics collector
 #[derive(Clone)]
 pub struct MetricsCollector {
     inner: Arc<Mutex<MetricsCollectorInner>>,
+    /// Scenario label added to every metric recorded through this handle
+    scenario: Option<String>,
 }
 
 struct MetricsCollectorInner {
@@ -58,6 +94,12 @@ struct MetricsCollectorInner {
     metrics: Vec<OperationMetric>,
     operation_counts: HashMap<OperationType, u64>,
     error_counts: HashMap<String, u64>,
+    account_balances: HashMap<String, i128>,
+    refill_counts: HashMap<String, u64>,
+    ledger_entry_samples: Vec<LedgerEntrySample>,
+    /// Operations moved out by `rotate`, per scenario label (`None` for all)
+    rotated_operations: HashMap<Option<String>, u64>,
+    budget_mode: BudgetMode,
 }
 
 impl MetricsCollector {
@@ -69,7 +111,118 @@ impl MetricsCollector {
                 metrics: Vec::new(),
                 operation_counts: HashMap::new(),
                 error_counts: HashMap::new(),
+                account_balances: HashMap::new(),
+                refill_counts: HashMap::new(),
+                ledger_entry_samples: Vec::new(),
+                rotated_operations: HashMap::new(),
+                budget_mode: BudgetMode::default(),
             })),
+            scenario: None,
+        }
+    }
+
+    /// Set how timers started with `with_budget` attribute the budget
+    pub fn set_budget_mode(&self, mode: BudgetMode) {
+        self.inner.lock().unwrap().budget_mode = mode;
+    }
+
+    /// How timers started with `with_budget` attribute the budget
+    pub fn budget_mode(&self) -> BudgetMode {
+        self.inner.lock().unwrap().budget_mode
+    }
+
+    /// Handle to the same collector that tags every recorded metric with
+    /// `scenario` metadata, so interleaved scenarios can be reported separately
+    pub fn scoped(&self, scenario: &str) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            scenario: Some(scenario.to_string()),
+        }
+    }
+
+    /// Distinct scenario labels seen in recorded metrics, sorted
+    pub fn scenario_labels(&self) -> Vec<String> {
+        let inner = self.inner.lock().unwrap();
+        let mut labels: Vec<String> = inner
+            .metrics
+            .iter()
+            .filter_map(|m| m.metadata.get("scenario").cloned())
+            .collect();
+        labels.sort();
+        labels.dedup();
+        labels
+    }
+
+    /// Standalone collector holding only the metrics tagged with `scenario`.
+    /// Keeps the original start time so throughput covers the whole run.
+    pub fn filtered(&self, scenario: &str) -> Self {
+        let inner = self.inner.lock().unwrap();
+        let metrics: Vec<OperationMetric> = inner
+            .metrics
+            .iter()
+            .filter(|m| m.metadata.get("scenario").map(String::as_str) == Some(scenario))
+            .cloned()
+            .collect();
+
+        let mut operation_counts = HashMap::new();
+        let mut error_counts = HashMap::new();
+        for metric in &metrics {
+            *operation_counts.entry(metric.operation).or_insert(0) += 1;
+            if let Some(err) = &metric.error {
+                *error_counts.entry(err.clone()).or_insert(0) += 1;
+            }
+        }
+
+        Self {
+            inner: Arc::new(Mutex::new(MetricsCollectorInner {
+                start_time: inner.start_time,
+                metrics,
+                operation_counts,
+                error_counts,
+                account_balances: inner.account_balances.clone(),
+                refill_counts: inner.refill_counts.clone(),
+                ledger_entry_samples: inner
+                    .ledger_entry_samples
+                    .iter()
+                    .filter(|s| s.scenario.as_deref() == Some(scenario))
+                    .cloned()
+                    .collect(),
+                rotated_operations: HashMap::new(),
+                budget_mode: inner.budget_mode,
+            })),
+            scenario: None,
+        }
+    }
+
+    /// Move the recorded metrics into a standalone collector and start a new,
+    /// empty window, so long runs can flush them instead of holding every
+    /// metric in memory. Account state and ledger-entry samples are kept, and
+    /// later samples keep counting the operations rotated out.
+    pub fn rotate(&self) -> Self {
+        let mut inner = self.inner.lock().unwrap();
+        let metrics = std::mem::take(&mut inner.metrics);
+
+        *inner.rotated_operations.entry(None).or_insert(0) += metrics.len() as u64;
+        for scenario in metrics.iter().filter_map(|m| m.metadata.get("scenario")) {
+            *inner
+                .rotated_operations
+                .entry(Some(scenario.clone()))
+                .or_insert(0) += 1;
+        }
+
+        Self {
+            inner: Arc::new(Mutex::new(MetricsCollectorInner {
+                start_time: std::mem::replace(&mut inner.start_time, Instant::now()),
+                metrics,
+                operation_counts: std::mem::take(&mut inner.operation_counts),
+                error_counts: std::mem::take(&mut inner.error_counts),
+                account_balances: inner.account_balances.clone(),
+                refill_counts: inner.refill_counts.clone(),
+                ledger_entry_samples: inner.ledger_entry_samples.clone(),
+                rotated_operations: HashMap::new(),
+                budget_mode: inner.budget_mode,
+            })),
+            scenario: None,
         }
     }
 
@@ -85,8 +238,14 @@ impl MetricsCollector {
         duration: Duration,
         success: bool,
         error: Option<String>,
-        metadata: HashMap<String, String>,
+        mut metadata: HashMap<String, String>,
     ) {
+        if let Some(scenario) = &self.scenario {
+            metadata
+                .entry("scenario".to_string())
+                .or_insert_with(|| scenario.clone());
+        }
+
         let mut inner = self.inner.lock().unwrap();
 
         let metric = OperationMetric {
@@ -129,6 +288,66 @@ impl MetricsCollector {
         self.record(operation, duration, false, Some(error), metadata);
     }
 
+    /// Register an account's initial target balance for per-account reporting
+    pub fn register_account(&self, account: String, target_balance: i128) {
+        self.inner
+            .lock()
+            .unwrap()
+            .account_balances
+            .insert(account, target_balance);
+    }
+
+    /// Record that an account was refilled mid-run
+    pub fn record_refill(&self, account: &str) {
+        *self
+            .inner
+            .lock()
+            .unwrap()
+            .refill_counts
+            .entry(account.to_string())
+            .or_insert(0) += 1;
+    }
+
+    /// Record the number of ledger entries in the scenario's `Env`, against the
+    /// operations recorded through this handle so far
+    pub fn record_ledger_entries(&self, ledger_entries: u64) {
+        let mut inner = self.inner.lock().unwrap();
+        let recorded = match &self.scenario {
+            Some(scenario) => inner
+                .metrics
+                .iter()
+                .filter(|m| m.metadata.get("scenario") == Some(scenario))
+                .count(),
+            None => inner.metrics.len(),
+        } as u64;
+        let rotated = inner
+            .rotated_operations
+            .get(&self.scenario)
+            .copied()
+            .unwrap_or(0);
+        let operations = recorded + rotated;
+        inner.ledger_entry_samples.push(LedgerEntrySample {
+            operations,
+            ledger_entries,
+            scenario: self.scenario.clone(),
+        });
+    }
+
+    /// Get ledger-entry samples in recording order
+    pub fn ledger_entry_samples(&self) -> Vec<LedgerEntrySample> {
+        self.inner.lock().unwrap().ledger_entry_samples.clone()
+    }
+
+    /// Get registered account target balances
+    pub fn account_balances(&self) -> HashMap<String, i128> {
+        self.inner.lock().unwrap().account_balances.clone()
+    }
+
+    /// Get refill counts per account
+    pub fn refill_counts(&self) -> HashMap<String, u64> {
+        self.inner.lock().unwrap().refill_counts.clone()
+    }
+
     /// Get total number of operations
     pub fn total_operations(&self) -> usize {
         self.inner.lock().unwrap().metrics.len()
@@ -197,6 +416,18 @@ impl MetricsCollector {
         self.inner.lock().unwrap().error_counts.clone()
     }
 
+    /// Get counts keyed by (operation name, success)
+    pub fn outcome_counts(&self) -> HashMap<(String, bool), u64> {
+        let inner = self.inner.lock().unwrap();
+        let mut counts = HashMap::new();
+        for metric in &inner.metrics {
+            *counts
+                .entry((metric.operation.as_str().to_string(), metric.success))
+                .or_insert(0) += 1;
+        }
+        counts
+    }
+
     /// Get operation counts
     pub fn operation_counts(&self) -> HashMap<OperationType, u64> {
         self.inner.lock().unwrap().operation_counts.clone()
@@ -207,6 +438,11 @@ impl MetricsCollector {
         self.inner.lock().unwrap().metrics.clone()
     }
 
+    /// Run `f` over all metrics without copying them (for large exports)
+    pub fn with_metrics<R>(&self, f: impl FnOnce(&[OperationMetric]) -> R) -> R {
+        f(&self.inner.lock().unwrap().metrics)
+    }
+
     /// Get metrics for specific operation type
     pub fn get_metrics_for_operation(&self, operation: OperationType) -> Vec<OperationMetric> {
         self.inner
@@ -239,6 +475,10 @@ impl MetricsCollector {
         inner.metrics.clear();
         inner.operation_counts.clear();
         inner.error_counts.clear();
+        inner.account_balances.clear();
+        inner.refill_counts.clear();
+        inner.ledger_entry_samples.clear();
+        inner.rotated_operations.clear();
         inner.start_time = Instant::now();
     }
 }
@@ -253,6 +493,9 @@ impl Default for MetricsCollector {
 pub struct OperationTimer {
     start: Instant,
     collector: MetricsCollector,
+    account: Option<String>,
+    /// Env whose budget is recorded on completion (`BudgetMode::Isolated`)
+    budget_env: Option<Env>,
 }
 
 impl OperationTimer {
@@ -260,18 +503,47 @@ impl OperationTimer {
         Self {
             start: Instant::now(),
             collector,
+            account: None,
+            budget_env: None,
+        }
+    }
+
+    /// Measure the operation's budget on `env` per the collector's
+    /// `BudgetMode`. In isolated mode the tracker is reset here, outside the
+    /// timed span, and the budget is recorded on completion unless the
+    /// scenario already did.
+    pub fn with_budget(mut self, env: &Env) -> Self {
+        if self.collector.budget_mode() == BudgetMode::Isolated {
+            reset_budget(env);
+            self.budget_env = Some(env.clone());
+            self.start = Instant::now();
         }
+        self
+    }
+
+    /// Attribute the operation to an account (recorded as `account` metadata)
+    pub fn for_account(mut self, account: &str) -> Self {
+        self.account = Some(account.to_string());
+        self
     }
 
     /// Complete the operation successfully
-    pub fn success(self, operation: OperationType, metadata: HashMap<String, String>) {
+    pub fn success(self, operation: OperationType, mut metadata: HashMap<String, String>) {
         let duration = self.start.elapsed();
+        self.record_budget(&mut metadata);
+        if let Some(account) = self.account {
+            metadata.insert("account".to_string(), account);
+        }
         self.collector.record_success(operation, duration, metadata);
     }
 
     /// Complete the operation with error
-    pub fn error(self, operation: OperationType, error: String, metadata: HashMap<String, String>) {
+    pub fn error(self, operation: OperationType, error: String, mut metadata: HashMap<String, String>) {
         let duration = self.start.elapsed();
+        self.record_budget(&mut metadata);
+        if let Some(account) = self.account {
+            metadata.insert("account".to_string(), account);
+        }
         self.collector.record_error(operation, duration, error, metadata);
     }
 
@@ -279,6 +551,14 @@ impl OperationTimer {
     pub fn elapsed(&self) -> Duration {
         self.start.elapsed()
     }
+
+    fn record_budget(&self, metadata: &mut HashMap<String, String>) {
+        if let Some(env) = &self.budget_env {
+            if !metadata.contains_key(CPU_INSTRUCTIONS_KEY) {
+                record_budget(env, metadata);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -362,4 +642,114 @@ mod tests {
         assert_eq!(counts.get(&OperationType::Swap), Some(&2));
         assert_eq!(counts.get(&OperationType::AddLiquidity), Some(&1));
     }
+
+    #[test]
+    fn test_account_attribution() {
+        let collector = MetricsCollector::new();
+
+        collector
+            .start_operation()
+            .for_account("account_3")
+            .success(OperationType::Swap, HashMap::new());
+        collector.record_refill("account_3");
+        collector.record_refill("account_3");
+
+        let metrics = collector.get_metrics();
+        assert_eq!(metrics[0].metadata.get("account").map(String::as_str), Some("account_3"));
+        assert_eq!(collector.refill_counts().get("account_3"), Some(&2));
+    }
+
+    #[test]
+    fn test_isolated_budget_recorded_for_failures() {
+        let env = Env::default();
+        let collector = MetricsCollector::new();
+
+        // Shared mode leaves the budget to the scenario
+        collector
+            .start_operation()
+            .with_budget(&env)
+            .error(OperationType::Swap, "Swap failed".to_string(), HashMap::new());
+        assert!(!collector.get_metrics()[0]
+            .metadata
+            .contains_key(CPU_INSTRUCTIONS_KEY));
+
+        // Isolated mode records it on completion, failures included, without
+        // overwriting a reading the scenario took
+        collector.set_budget_mode(BudgetMode::Isolated);
+        let scoped = collector.scoped("swap-load");
+        assert_eq!(scoped.budget_mode(), BudgetMode::Isolated);
+        scoped
+            .start_operation()
+            .with_budget(&env)
+            .error(OperationType::Swap, "Swap failed".to_string(), HashMap::new());
+        let mut metadata = HashMap::new();
+        metadata.insert(CPU_INSTRUCTIONS_KEY.to_string(), "42".to_string());
+        scoped
+            .start_operation()
+            .with_budget(&env)
+            .success(OperationType::Swap, metadata);
+
+        let metrics = collector.get_metrics();
+        assert!(metrics[1].metadata.contains_key(CPU_INSTRUCTIONS_KEY));
+        assert!(metrics[1].metadata.contains_key("memory_bytes"));
+        assert_eq!(
+            metrics[2].metadata.get(CPU_INSTRUCTIONS_KEY).map(String::as_str),
+            Some("42")
+        );
+    }
+
+    #[test]
+    fn test_scoped_collectors() {
+        let collector = MetricsCollector::new();
+        let swaps = collector.scoped("swap-load");
+        let pools = collector.scoped("pool-stress");
+
+        swaps.record_success(OperationType::Swap, Duration::from_millis(1), HashMap::new());
+        swaps.record_success(OperationType::Swap, Duration::from_millis(1), HashMap::new());
+        pools.record_error(
+            OperationType::AddLiquidity,
+            Duration::from_millis(1),
+            "Add liquidity failed".to_string(),
+            HashMap::new(),
+        );
+
+        assert_eq!(collector.total_operations(), 3);
+        assert_eq!(collector.scenario_labels(), vec!["pool-stress", "swap-load"]);
+
+        let swap_only = collector.filtered("swap-load");
+        assert_eq!(swap_only.total_operations(), 2);
+        assert_eq!(swap_only.success_rate(), 1.0);
+        assert_eq!(collector.filtered("pool-stress").error_counts().len(), 1);
+    }
+
+    #[test]
+    fn test_rotate_starts_new_window() {
+        let collector = MetricsCollector::new();
+        let swaps = collector.scoped("swap-load");
+        collector.register_account("account_0".to_string(), 1_000);
+
+        swaps.record_success(OperationType::Swap, Duration::from_millis(1), HashMap::new());
+        swaps.record_error(
+            OperationType::Swap,
+            Duration::from_millis(1),
+            "Swap failed".to_string(),
+            HashMap::new(),
+        );
+        swaps.record_ledger_entries(10);
+
+        let window = collector.rotate();
+        assert_eq!(window.total_operations(), 2);
+        assert_eq!(window.error_counts().get("Swap failed"), Some(&1));
+        assert_eq!(collector.total_operations(), 0);
+        assert!(collector.error_counts().is_empty());
+        assert_eq!(collector.account_balances().len(), 1);
+
+        // Samples keep counting the operations rotated out
+        swaps.record_success(OperationType::Swap, Duration::from_millis(1), HashMap::new());
+        swaps.record_ledger_entries(12);
+        let samples = collector.ledger_entry_samples();
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].operations, 2);
+        assert_eq!(samples[1].operations, 3);
+    }
 }
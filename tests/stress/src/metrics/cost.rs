@@ -0,0 +1,159 @@
+//! Cost Projection
+//!
+//! Converts the Soroban budget measured per operation into projected stroop
+//! fees under the configured network fee parameters, per operation type and
+//! for a target daily volume.
+
+use super::collector::MetricsCollector;
+use crate::config::CostConfig;
+use crate::utils::budget::{CPU_INSTRUCTIONS_KEY, MEMORY_BYTES_KEY};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Stroops per XLM
+pub const STROOPS_PER_XLM: i64 = 10_000_000;
+
+/// Instructions per fee increment
+const INSTRUCTIONS_INCREMENT: i64 = 10_000;
+
+/// Projected cost of one operation type
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OperationCost {
+    /// Operations with a measured budget
+    pub samples: usize,
+    pub avg_cpu_instructions: u64,
+    pub max_cpu_instructions: u64,
+    pub avg_memory_bytes: u64,
+    /// Worst-case share of the per-transaction instruction limit (0.0 - 1.0)
+    pub cpu_limit_utilization: f64,
+    /// Worst-case share of the per-transaction memory limit (0.0 - 1.0)
+    pub memory_limit_utilization: f64,
+    /// Projected fee for the average operation, in stroops
+    pub fee_stroops: i64,
+}
+
+/// Projected costs for a run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CostReport {
+    pub per_operation: HashMap<String, OperationCost>,
+    /// Daily volume, split across operation types by the observed mix
+    pub daily_operations: u64,
+    pub daily_fee_stroops: i64,
+    pub daily_fee_xlm: f64,
+}
+
+/// Fee for a transaction consuming `cpu_instructions`
+pub fn estimate_fee(config: &CostConfig, cpu_instructions: u64) -> i64 {
+    let increments = (cpu_instructions as i64 + INSTRUCTIONS_INCREMENT - 1) / INSTRUCTIONS_INCREMENT;
+    config.inclusion_fee_stroops
+        + increments * config.fee_per_instructions_increment
+        + config.resource_overhead_stroops
+}
+
+impl CostReport {
+    /// Build the projection from successful operations carrying budget metadata
+    pub fn from_collector(collector: &MetricsCollector, config: &CostConfig) -> Self {
+        let mut budgets: HashMap<String, Vec<(u64, u64)>> = HashMap::new();
+        for metric in collector.get_metrics().iter().filter(|m| m.success) {
+            let cpu = metric
+                .metadata
+                .get(CPU_INSTRUCTIONS_KEY)
+                .and_then(|v| v.parse::<u64>().ok());
+            let memory = metric
+                .metadata
+                .get(MEMORY_BYTES_KEY)
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(0);
+            if let Some(cpu) = cpu {
+                budgets
+                    .entry(metric.operation.as_str().to_string())
+                    .or_default()
+                    .push((cpu, memory));
+            }
+        }
+
+        let total_samples: usize = budgets.values().map(Vec::len).sum();
+        let mut report = CostReport {
+            daily_operations: config.daily_operations,
+            ..Default::default()
+        };
+
+        for (operation, samples) in budgets {
+            let count = samples.len() as u64;
+            let avg_cpu = samples.iter().map(|(cpu, _)| cpu).sum::<u64>() / count;
+            let avg_memory = samples.iter().map(|(_, mem)| mem).sum::<u64>() / count;
+            let max_cpu = samples.iter().map(|(cpu, _)| *cpu).max().unwrap_or(0);
+            let max_memory = samples.iter().map(|(_, mem)| *mem).max().unwrap_or(0);
+
+            let cost = OperationCost {
+                samples: samples.len(),
+                avg_cpu_instructions: avg_cpu,
+                max_cpu_instructions: max_cpu,
+                avg_memory_bytes: avg_memory,
+                cpu_limit_utilization: max_cpu as f64 / config.tx_max_instructions.max(1) as f64,
+                memory_limit_utilization: max_memory as f64 / config.tx_memory_limit.max(1) as f64,
+                fee_stroops: estimate_fee(config, avg_cpu),
+            };
+
+            let daily_share = config.daily_operations as f64 * count as f64 / total_samples as f64;
+            report.daily_fee_stroops += (daily_share * cost.fee_stroops as f64).round() as i64;
+            report.per_operation.insert(operation, cost);
+        }
+
+        report.daily_fee_xlm = report.daily_fee_stroops as f64 / STROOPS_PER_XLM as f64;
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::OperationType;
+    use std::time::Duration;
+
+    fn budget(cpu: u64, memory: u64) -> HashMap<String, String> {
+        let mut metadata = HashMap::new();
+        metadata.insert(CPU_INSTRUCTIONS_KEY.to_string(), cpu.to_string());
+        metadata.insert(MEMORY_BYTES_KEY.to_string(), memory.to_string());
+        metadata
+    }
+
+    #[test]
+    fn test_estimate_fee() {
+        let config = CostConfig::default();
+        // 100 inclusion + ceil(1_000_001 / 10_000) = 101 increments * 25
+        assert_eq!(estimate_fee(&config, 1_000_001), 100 + 101 * 25);
+        assert_eq!(estimate_fee(&config, 0), 100);
+    }
+
+    #[test]
+    fn test_cost_report() {
+        let collector = MetricsCollector::new();
+        collector.record_success(OperationType::Swap, Duration::from_millis(1), budget(2_000_000, 1_000));
+        collector.record_success(OperationType::Swap, Duration::from_millis(1), budget(4_000_000, 3_000));
+        collector.record_success(OperationType::Stake, Duration::from_millis(1), budget(1_000_000, 500));
+        // Failures and unmeasured operations don't contribute
+        collector.record_error(
+            OperationType::Swap,
+            Duration::from_millis(1),
+            "Swap failed".to_string(),
+            budget(90_000_000, 0),
+        );
+        collector.record_success(OperationType::AddLiquidity, Duration::from_millis(1), HashMap::new());
+
+        let mut config = CostConfig::default();
+        config.daily_operations = 3_000;
+        let report = CostReport::from_collector(&collector, &config);
+
+        let swap = &report.per_operation["swap"];
+        assert_eq!(swap.samples, 2);
+        assert_eq!(swap.avg_cpu_instructions, 3_000_000);
+        assert_eq!(swap.fee_stroops, 100 + 300 * 25);
+        assert!((swap.cpu_limit_utilization - 0.04).abs() < 1e-9);
+        assert!(!report.per_operation.contains_key("add_liquidity"));
+
+        // 2/3 of the volume swaps, 1/3 stakes
+        let stake_fee = 100 + 100 * 25;
+        assert_eq!(report.daily_fee_stroops, 2_000 * swap.fee_stroops + 1_000 * stake_fee);
+    }
+}
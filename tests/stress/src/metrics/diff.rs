@@ -0,0 +1,460 @@
+//! Report Diffing
+//!
+//! Compares two saved test reports metric by metric: overall TPS, latency and
+//! success rate, the same per scenario, and each scenario's operation
+//! breakdown. Changes beyond the configured thresholds are highlighted as
+//! regressions or improvements.
+
+use super::reporter::{OperationStats, PerformanceMetrics, ScenarioReport, TestReport};
+use serde::{Deserialize, Serialize};
+
+/// Changes at or below these are shown but not highlighted
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DiffThresholds {
+    /// Relative TPS change, in percent
+    pub tps_pct: f64,
+    /// Relative latency change, in percent
+    pub latency_pct: f64,
+    /// Absolute success rate change, in percentage points
+    pub success_rate_pts: f64,
+}
+
+impl Default for DiffThresholds {
+    fn default() -> Self {
+        Self {
+            tps_pct: 10.0,
+            latency_pct: 10.0,
+            success_rate_pts: 1.0,
+        }
+    }
+}
+
+/// Compared metric, which also fixes its better direction and threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiffMetric {
+    Operations,
+    Tps,
+    SuccessRate,
+    LatencyAvg,
+    LatencyP95,
+}
+
+impl DiffMetric {
+    pub fn as_str(&self) -> &str {
+        match self {
+            DiffMetric::Operations => "operations",
+            DiffMetric::Tps => "tps",
+            DiffMetric::SuccessRate => "success_rate",
+            DiffMetric::LatencyAvg => "latency_avg_ms",
+            DiffMetric::LatencyP95 => "latency_p95_ms",
+        }
+    }
+}
+
+/// How a metric moved relative to the thresholds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Verdict {
+    Regressed,
+    Improved,
+    Unchanged,
+    /// Only present in one of the reports
+    Unmatched,
+}
+
+impl Verdict {
+    fn marker(&self) -> &str {
+        match self {
+            Verdict::Regressed => "✗",
+            Verdict::Improved => "✓",
+            Verdict::Unchanged => "",
+            Verdict::Unmatched => "?",
+        }
+    }
+}
+
+/// One metric in both reports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricDelta {
+    /// "overall", a scenario name, or "scenario / operation"
+    pub scope: String,
+    pub metric: DiffMetric,
+    pub before: Option<f64>,
+    pub after: Option<f64>,
+    pub verdict: Verdict,
+}
+
+impl MetricDelta {
+    fn new(
+        scope: &str,
+        metric: DiffMetric,
+        before: Option<f64>,
+        after: Option<f64>,
+        thresholds: &DiffThresholds,
+    ) -> Self {
+        let verdict = match (before, after) {
+            (Some(before), Some(after)) => Self::verdict(metric, before, after, thresholds),
+            _ => Verdict::Unmatched,
+        };
+        Self {
+            scope: scope.to_string(),
+            metric,
+            before,
+            after,
+            verdict,
+        }
+    }
+
+    fn verdict(
+        metric: DiffMetric,
+        before: f64,
+        after: f64,
+        thresholds: &DiffThresholds,
+    ) -> Verdict {
+        // Positive `gain` is an improvement
+        let (gain, threshold) = match metric {
+            DiffMetric::Operations => return Verdict::Unchanged,
+            DiffMetric::Tps => (relative_pct(before, after), thresholds.tps_pct),
+            DiffMetric::SuccessRate => ((after - before) * 100.0, thresholds.success_rate_pts),
+            DiffMetric::LatencyAvg | DiffMetric::LatencyP95 => {
+                (-relative_pct(before, after), thresholds.latency_pct)
+            }
+        };
+        if gain > threshold {
+            Verdict::Improved
+        } else if gain < -threshold {
+            Verdict::Regressed
+        } else {
+            Verdict::Unchanged
+        }
+    }
+
+    /// Change as shown in the tables: points for success rates, percent otherwise
+    pub fn change(&self) -> String {
+        match (self.before, self.after) {
+            (Some(before), Some(after)) if self.metric == DiffMetric::SuccessRate => {
+                format!("{:+.2} pts", (after - before) * 100.0)
+            }
+            (Some(before), Some(after)) if before != 0.0 => {
+                format!("{:+.1}%", relative_pct(before, after))
+            }
+            _ => "-".to_string(),
+        }
+    }
+
+    fn value(&self, value: Option<f64>) -> String {
+        match value {
+            Some(v) if self.metric == DiffMetric::SuccessRate => format!("{:.2}%", v * 100.0),
+            Some(v) if self.metric == DiffMetric::Operations => format!("{:.0}", v),
+            Some(v) => format!("{:.2}", v),
+            None => "-".to_string(),
+        }
+    }
+}
+
+/// Metric deltas between a baseline and a candidate report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportDiff {
+    pub before_id: String,
+    pub after_id: String,
+    pub thresholds: DiffThresholds,
+    pub deltas: Vec<MetricDelta>,
+}
+
+impl ReportDiff {
+    /// Compare `after` against the baseline `before`. Scenarios are matched by
+    /// name and operations by type; unmatched ones are listed with one side.
+    pub fn between(before: &TestReport, after: &TestReport, thresholds: DiffThresholds) -> Self {
+        let mut deltas = Vec::new();
+        let mut push = |scope: &str, metric, before, after| {
+            deltas.push(MetricDelta::new(scope, metric, before, after, &thresholds));
+        };
+
+        let (b, a) = (&before.summary, &after.summary);
+        push(
+            "overall",
+            DiffMetric::Operations,
+            Some(b.total_operations as f64),
+            Some(a.total_operations as f64),
+        );
+        push(
+            "overall",
+            DiffMetric::Tps,
+            Some(b.overall_tps),
+            Some(a.overall_tps),
+        );
+        push(
+            "overall",
+            DiffMetric::SuccessRate,
+            Some(b.overall_success_rate),
+            Some(a.overall_success_rate),
+        );
+        push(
+            "overall",
+            DiffMetric::LatencyAvg,
+            Some(b.overall_latency_ms),
+            Some(a.overall_latency_ms),
+        );
+
+        let mut names: Vec<&str> = before.scenarios.iter().map(|s| s.name.as_str()).collect();
+        for scenario in &after.scenarios {
+            if !names.contains(&scenario.name.as_str()) {
+                names.push(&scenario.name);
+            }
+        }
+
+        for name in names {
+            let (b, a) = (scenario(before, name), scenario(after, name));
+
+            let perf = |s: Option<&ScenarioReport>, f: fn(&PerformanceMetrics) -> f64| {
+                s.map(|s| f(&s.performance))
+            };
+            push(
+                name,
+                DiffMetric::Tps,
+                perf(b, |p| p.operations_per_second),
+                perf(a, |p| p.operations_per_second),
+            );
+            push(
+                name,
+                DiffMetric::SuccessRate,
+                perf(b, |p| p.success_rate),
+                perf(a, |p| p.success_rate),
+            );
+            push(
+                name,
+                DiffMetric::LatencyP95,
+                perf(b, |p| p.latency_p95_ms),
+                perf(a, |p| p.latency_p95_ms),
+            );
+
+            let mut operations: Vec<&String> = b
+                .into_iter()
+                .chain(a)
+                .flat_map(|s| s.operation_breakdown.keys())
+                .collect();
+            operations.sort();
+            operations.dedup();
+
+            for operation in operations {
+                let scope = format!("{} / {}", name, operation);
+                let stats = |s: Option<&ScenarioReport>, f: fn(&OperationStats) -> f64| {
+                    s.and_then(|s| s.operation_breakdown.get(operation)).map(f)
+                };
+                push(
+                    &scope,
+                    DiffMetric::Operations,
+                    stats(b, |o| o.count as f64),
+                    stats(a, |o| o.count as f64),
+                );
+                push(
+                    &scope,
+                    DiffMetric::SuccessRate,
+                    stats(b, |o| o.success_rate),
+                    stats(a, |o| o.success_rate),
+                );
+                push(
+                    &scope,
+                    DiffMetric::LatencyP95,
+                    stats(b, |o| o.p95_latency_ms),
+                    stats(a, |o| o.p95_latency_ms),
+                );
+            }
+        }
+
+        Self {
+            before_id: before.test_id.clone(),
+            after_id: after.test_id.clone(),
+            thresholds,
+            deltas,
+        }
+    }
+
+    /// Deltas worse than their threshold
+    pub fn regressions(&self) -> Vec<&MetricDelta> {
+        self.deltas
+            .iter()
+            .filter(|d| d.verdict == Verdict::Regressed)
+            .collect()
+    }
+
+    /// Plain-text table for the terminal
+    pub fn to_console(&self) -> String {
+        let scope_width = self
+            .deltas
+            .iter()
+            .map(|d| d.scope.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(5);
+
+        let mut out = format!(
+            "Baseline:  {}\nCandidate: {}\n\n",
+            self.before_id, self.after_id
+        );
+        out.push_str(&format!(
+            "  {:<scope_width$}  {:<16} {:>12} {:>12} {:>12}\n",
+            "Scope", "Metric", "Baseline", "Candidate", "Change"
+        ));
+        for delta in &self.deltas {
+            out.push_str(&format!(
+                "  {:<scope_width$}  {:<16} {:>12} {:>12} {:>12} {}\n",
+                delta.scope,
+                delta.metric.as_str(),
+                delta.value(delta.before),
+                delta.value(delta.after),
+                delta.change(),
+                delta.verdict.marker()
+            ));
+        }
+        out.push_str(&self.totals());
+        out
+    }
+
+    /// Markdown table, regressions and improvements in bold
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+        md.push_str(&format!(
+            "# Stress Report Diff: {} → {}\n\n",
+            self.before_id, self.after_id
+        ));
+        md.push_str(&format!(
+            "Thresholds: TPS ±{:.1}%, latency ±{:.1}%, success rate ±{:.2} pts\n\n",
+            self.thresholds.tps_pct, self.thresholds.latency_pct, self.thresholds.success_rate_pts
+        ));
+        md.push_str("| Scope | Metric | Baseline | Candidate | Change | |\n");
+        md.push_str("|-------|--------|----------|-----------|--------|---|\n");
+        for delta in &self.deltas {
+            let change = match delta.verdict {
+                Verdict::Regressed | Verdict::Improved => format!("**{}**", delta.change()),
+                _ => delta.change(),
+            };
+            md.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} |\n",
+                delta.scope,
+                delta.metric.as_str(),
+                delta.value(delta.before),
+                delta.value(delta.after),
+                change,
+                delta.verdict.marker()
+            ));
+        }
+        md.push('\n');
+        md.push_str(&self.totals());
+        md
+    }
+
+    fn totals(&self) -> String {
+        let improved = self
+            .deltas
+            .iter()
+            .filter(|d| d.verdict == Verdict::Improved)
+            .count();
+        format!(
+            "\n{} regressed, {} improved\n",
+            self.regressions().len(),
+            improved
+        )
+    }
+}
+
+/// Scenario of `report` named `name`
+fn scenario<'a>(report: &'a TestReport, name: &str) -> Option<&'a ScenarioReport> {
+    report.scenarios.iter().find(|s| s.name == name)
+}
+
+/// Change from `before` to `after` in percent of `before`, 0 from a zero baseline
+fn relative_pct(before: f64, after: f64) -> f64 {
+    if before == 0.0 {
+        return 0.0;
+    }
+    (after - before) / before * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::StressConfig;
+    use crate::metrics::{MetricsCollector, OperationType};
+    use chrono::Utc;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    fn report(id: &str, latency_ms: u64, failures: usize) -> TestReport {
+        let collector = MetricsCollector::new();
+        for _ in 0..10 {
+            collector.record_success(
+                OperationType::Swap,
+                Duration::from_millis(latency_ms),
+                HashMap::new(),
+            );
+        }
+        for _ in 0..failures {
+            collector.record_error(
+                OperationType::AddLiquidity,
+                Duration::from_millis(1),
+                "Add liquidity failed".to_string(),
+                HashMap::new(),
+            );
+        }
+        TestReport::from_metrics(
+            id.to_string(),
+            StressConfig::default(),
+            Utc::now(),
+            &collector,
+        )
+    }
+
+    fn find<'a>(diff: &'a ReportDiff, scope: &str, metric: DiffMetric) -> &'a MetricDelta {
+        diff.deltas
+            .iter()
+            .find(|d| d.scope == scope && d.metric == metric)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_diff_highlights_beyond_thresholds() {
+        let before = report("before", 10, 0);
+        let after = report("after", 20, 1);
+        let diff = ReportDiff::between(&before, &after, DiffThresholds::default());
+
+        let scenario = &before.scenarios[0].name;
+        let p95 = find(
+            &diff,
+            &format!("{} / swap", scenario),
+            DiffMetric::LatencyP95,
+        );
+        assert_eq!(p95.verdict, Verdict::Regressed);
+        assert_eq!(p95.change(), "+100.0%");
+
+        // Success rate drops from 100% to 10/11
+        let rate = find(&diff, "overall", DiffMetric::SuccessRate);
+        assert_eq!(rate.verdict, Verdict::Regressed);
+        assert_eq!(rate.change(), "-9.09 pts");
+
+        // Operations only seen in the candidate have nothing to compare with
+        let added = find(
+            &diff,
+            &format!("{} / add_liquidity", scenario),
+            DiffMetric::Operations,
+        );
+        assert_eq!(added.verdict, Verdict::Unmatched);
+        assert_eq!(added.before, None);
+
+        // A looser latency threshold keeps the p95 unhighlighted
+        let loose = DiffThresholds {
+            latency_pct: 150.0,
+            ..DiffThresholds::default()
+        };
+        let diff = ReportDiff::between(&before, &after, loose);
+        let p95 = find(
+            &diff,
+            &format!("{} / swap", scenario),
+            DiffMetric::LatencyP95,
+        );
+        assert_eq!(p95.verdict, Verdict::Unchanged);
+
+        let md = diff.to_markdown();
+        assert!(md.contains("| overall | success_rate | 100.00% | 90.91% | **-9.09 pts** | ✗ |"));
+        assert!(diff.to_console().contains("regressed"));
+    }
+}
@@ -0,0 +1,272 @@
+//! Live Metrics Exporter
+//!
+//! Optional HTTP endpoint exposing collector counters while a run is in progress,
+//! so long runs can be watched from a dashboard instead of waiting for the
+//! end-of-run report.
+//!
+//! - `GET /metrics` - Prometheus text exposition format
+//! - `GET /metrics.json` - JSON snapshot
+
+use super::collector::MetricsCollector;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Metric name prefix
+const PREFIX: &str = "astroswap_stress";
+
+/// Point-in-time view of the collector for the JSON endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct LiveSnapshot {
+    pub elapsed_seconds: f64,
+    pub total_operations: usize,
+    pub successful_operations: usize,
+    pub failed_operations: usize,
+    pub success_rate: f64,
+    pub operations_per_second: f64,
+    pub latency_avg_ms: f64,
+    pub latency_p50_ms: f64,
+    pub latency_p95_ms: f64,
+    pub latency_p99_ms: f64,
+    pub operations: HashMap<String, u64>,
+    pub errors: HashMap<String, u64>,
+}
+
+impl LiveSnapshot {
+    pub fn from_collector(collector: &MetricsCollector) -> Self {
+        Self {
+            elapsed_seconds: collector.elapsed().as_secs_f64(),
+            total_operations: collector.total_operations(),
+            successful_operations: collector.successful_operations(),
+            failed_operations: collector.failed_operations(),
+            success_rate: collector.success_rate(),
+            operations_per_second: collector.operations_per_second(),
+            latency_avg_ms: collector.average_latency_micros() as f64 / 1000.0,
+            latency_p50_ms: collector.latency_percentile(0.50) as f64 / 1000.0,
+            latency_p95_ms: collector.latency_percentile(0.95) as f64 / 1000.0,
+            latency_p99_ms: collector.latency_percentile(0.99) as f64 / 1000.0,
+            operations: collector
+                .operation_counts()
+                .into_iter()
+                .map(|(op, count)| (op.as_str().to_string(), count))
+                .collect(),
+            errors: collector.error_counts(),
+        }
+    }
+}
+
+/// Render collector state in Prometheus text exposition format
+pub fn render_prometheus(collector: &MetricsCollector) -> String {
+    let mut out = String::new();
+
+    out.push_str(&format!(
+        "# HELP {p}_operations_total Completed operations by type and result\n\
+         # TYPE {p}_operations_total counter\n",
+        p = PREFIX
+    ));
+    let mut outcomes: Vec<_> = collector.outcome_counts().into_iter().collect();
+    outcomes.sort_by(|a, b| a.0.cmp(&b.0));
+    for ((operation, success), count) in outcomes {
+        out.push_str(&format!(
+            "{}_operations_total{{operation=\"{}\",result=\"{}\"}} {}\n",
+            PREFIX,
+            operation,
+            if success { "success" } else { "error" },
+            count
+        ));
+    }
+
+    out.push_str(&format!(
+        "# HELP {p}_errors_total Failed operations by error message\n\
+         # TYPE {p}_errors_total counter\n",
+        p = PREFIX
+    ));
+    let mut errors: Vec<_> = collector.error_counts().into_iter().collect();
+    errors.sort();
+    for (error, count) in errors {
+        out.push_str(&format!(
+            "{}_errors_total{{error=\"{}\"}} {}\n",
+            PREFIX,
+            escape_label(&error),
+            count
+        ));
+    }
+
+    out.push_str(&format!(
+        "# HELP {p}_latency_microseconds Operation latency quantiles\n\
+         # TYPE {p}_latency_microseconds summary\n",
+        p = PREFIX
+    ));
+    for quantile in [0.5, 0.95, 0.99] {
+        out.push_str(&format!(
+            "{}_latency_microseconds{{quantile=\"{}\"}} {}\n",
+            PREFIX,
+            quantile,
+            collector.latency_percentile(quantile)
+        ));
+    }
+
+    let gauges = [
+        ("operations_per_second", "Current throughput", collector.operations_per_second()),
+        ("success_rate", "Fraction of successful operations", collector.success_rate()),
+        ("elapsed_seconds", "Seconds since the collector started", collector.elapsed().as_secs_f64()),
+        (
+            "account_refills",
+            "Mid-run account refills",
+            collector.refill_counts().values().sum::<u64>() as f64,
+        ),
+    ];
+    for (name, help, value) in gauges {
+        out.push_str(&format!(
+            "# HELP {p}_{n} {h}\n# TYPE {p}_{n} gauge\n{p}_{n} {v}\n",
+            p = PREFIX,
+            n = name,
+            h = help,
+            v = value
+        ));
+    }
+
+    out
+}
+
+/// Escape a Prometheus label value
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Background HTTP server serving live metrics. Stops when dropped.
+pub struct MetricsExporter {
+    addr: SocketAddr,
+    shutdown: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MetricsExporter {
+    /// Bind to `addr` (e.g. "127.0.0.1:9184", port 0 for any) and start serving
+    pub fn start(addr: &str, collector: MetricsCollector) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let stop = shutdown.clone();
+
+        let handle = thread::spawn(move || {
+            while !stop.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        // Scrape failures must never disturb the run
+                        let _ = Self::handle_connection(stream, &collector);
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                        thread::sleep(Duration::from_millis(50));
+                    }
+                    Err(_) => thread::sleep(Duration::from_millis(50)),
+                }
+            }
+        });
+
+        Ok(Self {
+            addr,
+            shutdown,
+            handle: Some(handle),
+        })
+    }
+
+    /// Address the exporter is listening on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    fn handle_connection(mut stream: TcpStream, collector: &MetricsCollector) -> io::Result<()> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+
+        let mut buf = [0u8; 1024];
+        let n = stream.read(&mut buf)?;
+        let request = String::from_utf8_lossy(&buf[..n]);
+        let path = request.split_whitespace().nth(1).unwrap_or("/");
+
+        let (status, content_type, body) = match path {
+            "/metrics" => (
+                "200 OK",
+                "text/plain; version=0.0.4",
+                render_prometheus(collector),
+            ),
+            "/metrics.json" => (
+                "200 OK",
+                "application/json",
+                serde_json::to_string(&LiveSnapshot::from_collector(collector))
+                    .unwrap_or_else(|_| "{}".to_string()),
+            ),
+            _ => ("404 Not Found", "text/plain", "not found\n".to_string()),
+        };
+
+        write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            content_type,
+            body.len(),
+            body
+        )?;
+        stream.flush()
+    }
+}
+
+impl Drop for MetricsExporter {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::OperationType;
+
+    #[test]
+    fn test_render_prometheus() {
+        let collector = MetricsCollector::new();
+        collector.record_success(OperationType::Swap, Duration::from_millis(2), HashMap::new());
+        collector.record_error(
+            OperationType::Swap,
+            Duration::from_millis(1),
+            "Swap failed: \"slippage\"".to_string(),
+            HashMap::new(),
+        );
+
+        let text = render_prometheus(&collector);
+        assert!(text.contains("astroswap_stress_operations_total{operation=\"swap\",result=\"success\"} 1"));
+        assert!(text.contains("astroswap_stress_operations_total{operation=\"swap\",result=\"error\"} 1"));
+        assert!(text.contains("astroswap_stress_errors_total{error=\"Swap failed: \\\"slippage\\\"\"} 1"));
+        assert!(text.contains("# TYPE astroswap_stress_success_rate gauge"));
+    }
+
+    #[test]
+    fn test_exporter_serves_metrics() {
+        let collector = MetricsCollector::new();
+        collector.record_success(OperationType::Stake, Duration::from_millis(1), HashMap::new());
+
+        let exporter = MetricsExporter::start("127.0.0.1:0", collector).unwrap();
+
+        let mut stream = TcpStream::connect(exporter.local_addr()).unwrap();
+        stream.write_all(b"GET /metrics.json HTTP/1.1\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("\"total_operations\":1"));
+    }
+}
@@ -0,0 +1,231 @@
+//! Router vs Aggregator Comparison
+//!
+//! Compares venues that executed the same trade list. Swaps carry a `venue`
+//! (`router` or `aggregator`) and a `trade` index in their metadata; outputs
+//! are compared per trade where both venues filled it, while failure rates
+//! and budget cover every attempt.
+
+use super::collector::MetricsCollector;
+use crate::utils::budget::{CPU_INSTRUCTIONS_KEY, MEMORY_BYTES_KEY};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Metadata key naming the venue a swap went through
+pub const VENUE_KEY: &str = "venue";
+
+/// Metadata key holding the index of a swap in the shared trade list
+pub const TRADE_KEY: &str = "trade";
+
+/// Venue value for swaps through the router
+pub const ROUTER_VENUE: &str = "router";
+
+/// Venue value for swaps through the aggregator
+pub const AGGREGATOR_VENUE: &str = "aggregator";
+
+/// Attempts, failures and budget of one venue
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VenueStats {
+    pub trades: usize,
+    pub failures: usize,
+    pub failure_rate: f64,
+    /// Average hops of filled trades
+    pub avg_hops: f64,
+    pub avg_cpu_instructions: u64,
+    pub avg_memory_bytes: u64,
+}
+
+/// Head-to-head results of the router and the aggregator
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HeadToHeadReport {
+    pub router: VenueStats,
+    pub aggregator: VenueStats,
+    /// Trades both venues filled
+    pub both_filled: usize,
+    pub aggregator_better: usize,
+    pub router_better: usize,
+    pub ties: usize,
+    /// Output summed over trades both venues filled
+    pub router_output: i128,
+    pub aggregator_output: i128,
+    /// Average per-trade output of the aggregator over the router on trades
+    /// both venues filled (bps, positive favours the aggregator)
+    pub avg_output_diff_bps: f64,
+}
+
+/// Attempts of one venue: trade index to (output if filled, hops)
+type Attempts = HashMap<u64, (Option<i128>, Option<u64>)>;
+
+impl HeadToHeadReport {
+    /// Build the comparison, None if the run has no swaps tagged with a venue
+    pub fn from_collector(collector: &MetricsCollector) -> Option<Self> {
+        let mut attempts: HashMap<&str, Attempts> = HashMap::new();
+        let mut budgets: HashMap<&str, (u64, u64, u64)> = HashMap::new();
+
+        collector.with_metrics(|metrics| {
+            for metric in metrics {
+                let venue = match metric.metadata.get(VENUE_KEY).map(String::as_str) {
+                    Some(ROUTER_VENUE) => ROUTER_VENUE,
+                    Some(AGGREGATOR_VENUE) => AGGREGATOR_VENUE,
+                    _ => continue,
+                };
+                let Some(trade) = metric.metadata.get(TRADE_KEY).and_then(|t| t.parse().ok())
+                else {
+                    continue;
+                };
+                let parse =
+                    |key: &str| metric.metadata.get(key).and_then(|v| v.parse::<u64>().ok());
+
+                let output = metric
+                    .metadata
+                    .get("amount_out")
+                    .and_then(|v| v.parse::<i128>().ok())
+                    .filter(|_| metric.success);
+                attempts
+                    .entry(venue)
+                    .or_default()
+                    .insert(trade, (output, parse("hops")));
+
+                if let Some(cpu) = parse(CPU_INSTRUCTIONS_KEY) {
+                    let budget = budgets.entry(venue).or_default();
+                    budget.0 += 1;
+                    budget.1 += cpu;
+                    budget.2 += parse(MEMORY_BYTES_KEY).unwrap_or(0);
+                }
+            }
+        });
+
+        if attempts.is_empty() {
+            return None;
+        }
+
+        let empty = Attempts::new();
+        let router = attempts.get(ROUTER_VENUE).unwrap_or(&empty);
+        let aggregator = attempts.get(AGGREGATOR_VENUE).unwrap_or(&empty);
+
+        let mut report = HeadToHeadReport {
+            router: Self::venue_stats(router, budgets.get(ROUTER_VENUE)),
+            aggregator: Self::venue_stats(aggregator, budgets.get(AGGREGATOR_VENUE)),
+            ..Default::default()
+        };
+
+        let mut diff_bps_sum = 0.0;
+        for (trade, (router_out, _)) in router {
+            let (Some(router_out), Some((Some(aggregator_out), _))) =
+                (router_out, aggregator.get(trade))
+            else {
+                continue;
+            };
+
+            report.both_filled += 1;
+            report.router_output += router_out;
+            report.aggregator_output += aggregator_out;
+            match aggregator_out.cmp(router_out) {
+                std::cmp::Ordering::Greater => report.aggregator_better += 1,
+                std::cmp::Ordering::Less => report.router_better += 1,
+                std::cmp::Ordering::Equal => report.ties += 1,
+            }
+            if *router_out > 0 {
+                diff_bps_sum +=
+                    (aggregator_out - router_out) as f64 * 10_000.0 / *router_out as f64;
+            }
+        }
+        if report.both_filled > 0 {
+            report.avg_output_diff_bps = diff_bps_sum / report.both_filled as f64;
+        }
+
+        Some(report)
+    }
+
+    fn venue_stats(attempts: &Attempts, budget: Option<&(u64, u64, u64)>) -> VenueStats {
+        let trades = attempts.len();
+        let filled_hops: Vec<u64> = attempts
+            .values()
+            .filter(|(output, _)| output.is_some())
+            .filter_map(|(_, hops)| *hops)
+            .collect();
+        let failures = attempts
+            .values()
+            .filter(|(output, _)| output.is_none())
+            .count();
+        let (samples, cpu, memory) = budget.copied().unwrap_or_default();
+
+        VenueStats {
+            trades,
+            failures,
+            failure_rate: if trades > 0 {
+                failures as f64 / trades as f64
+            } else {
+                0.0
+            },
+            avg_hops: if filled_hops.is_empty() {
+                0.0
+            } else {
+                filled_hops.iter().sum::<u64>() as f64 / filled_hops.len() as f64
+            },
+            avg_cpu_instructions: cpu.checked_div(samples).unwrap_or(0),
+            avg_memory_bytes: memory.checked_div(samples).unwrap_or(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::OperationType;
+    use std::time::Duration;
+
+    fn swap(
+        venue: &str,
+        trade: u64,
+        amount_out: Option<i128>,
+        cpu: u64,
+    ) -> HashMap<String, String> {
+        let mut metadata = HashMap::new();
+        metadata.insert(VENUE_KEY.to_string(), venue.to_string());
+        metadata.insert(TRADE_KEY.to_string(), trade.to_string());
+        metadata.insert("hops".to_string(), "1".to_string());
+        metadata.insert(CPU_INSTRUCTIONS_KEY.to_string(), cpu.to_string());
+        if let Some(amount_out) = amount_out {
+            metadata.insert("amount_out".to_string(), amount_out.to_string());
+        }
+        metadata
+    }
+
+    #[test]
+    fn test_head_to_head_report() {
+        let collector = MetricsCollector::new();
+        assert!(HeadToHeadReport::from_collector(&collector).is_none());
+
+        let fill = |venue: &str, trade: u64, out: i128, cpu: u64| {
+            collector.record_success(
+                OperationType::Swap,
+                Duration::from_millis(1),
+                swap(venue, trade, Some(out), cpu),
+            );
+        };
+        fill(ROUTER_VENUE, 0, 10_000, 100);
+        fill(AGGREGATOR_VENUE, 0, 10_100, 300);
+        fill(ROUTER_VENUE, 1, 10_000, 100);
+        fill(AGGREGATOR_VENUE, 1, 10_000, 300);
+        fill(AGGREGATOR_VENUE, 2, 5_000, 300);
+        collector.record_error(
+            OperationType::Swap,
+            Duration::from_millis(1),
+            "Swap failed".to_string(),
+            swap(ROUTER_VENUE, 2, None, 100),
+        );
+
+        let report = HeadToHeadReport::from_collector(&collector).unwrap();
+        assert_eq!(report.router.trades, 3);
+        assert_eq!(report.router.failures, 1);
+        assert_eq!(report.aggregator.failures, 0);
+        assert_eq!(report.aggregator.avg_cpu_instructions, 300);
+        assert_eq!(report.both_filled, 2);
+        assert_eq!(
+            (report.aggregator_better, report.router_better, report.ties),
+            (1, 0, 1)
+        );
+        assert_eq!(report.aggregator_output - report.router_output, 100);
+        assert_eq!(report.avg_output_diff_bps, 50.0);
+    }
+}
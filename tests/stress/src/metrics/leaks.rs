@@ -0,0 +1,217 @@
+//! Leak Detection
+//!
+//! Flags ledger entries or per-operation memory that keep growing for the whole
+//! run instead of levelling off, e.g. orphaned user-stake entries or observation
+//! vectors that are never trimmed. Ledger entries come from the samples taken by
+//! `utils::LedgerEntrySampler`; memory is the average `memory_bytes` budget of
+//! each operation type over consecutive windows of operations.
+
+use super::collector::{MetricsCollector, OperationMetric};
+use crate::config::LeakDetectionConfig;
+use crate::utils::budget::MEMORY_BYTES_KEY;
+use std::collections::BTreeMap;
+
+/// Growth of a series that never went down and did not level off
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrowthTrend {
+    pub first: f64,
+    pub last: f64,
+    /// Operations between the first and last point
+    pub operations: u64,
+    pub windows: usize,
+}
+
+impl GrowthTrend {
+    /// Growth per 1,000 operations
+    pub fn per_1k_ops(&self) -> f64 {
+        if self.operations == 0 {
+            return 0.0;
+        }
+        (self.last - self.first) * 1000.0 / self.operations as f64
+    }
+}
+
+/// Trend of `series` (operations, value) if it grows monotonically without
+/// bound: no point is below the previous one, the last is above the first, and
+/// the second half grows at least half as much as the first half.
+pub fn unbounded_growth(series: &[(u64, f64)], min_windows: usize) -> Option<GrowthTrend> {
+    if series.len() < min_windows.max(2) {
+        return None;
+    }
+    if series.windows(2).any(|w| w[1].1 < w[0].1) {
+        return None;
+    }
+
+    let (first, last) = (series[0], series[series.len() - 1]);
+    if last.1 <= first.1 {
+        return None;
+    }
+
+    // A leak keeps growing; a cache or a fixed set of users being touched levels off
+    let mid = series[series.len() / 2].1;
+    if last.1 - mid < (mid - first.1) / 2.0 {
+        return None;
+    }
+
+    Some(GrowthTrend {
+        first: first.1,
+        last: last.1,
+        operations: last.0.saturating_sub(first.0),
+        windows: series.len(),
+    })
+}
+
+/// Average `memory_bytes` per operation over consecutive windows of
+/// `window_ops` operations, as (operations so far, average bytes). The trailing
+/// partial window is dropped.
+pub fn memory_windows(metrics: &[&OperationMetric], window_ops: u64) -> Vec<(u64, f64)> {
+    let window_ops = window_ops.max(1) as usize;
+    let memory: Vec<u64> = metrics
+        .iter()
+        .filter_map(|m| m.metadata.get(MEMORY_BYTES_KEY)?.parse().ok())
+        .collect();
+
+    memory
+        .chunks_exact(window_ops)
+        .enumerate()
+        .map(|(i, window)| {
+            let average = window.iter().sum::<u64>() as f64 / window.len() as f64;
+            (((i + 1) * window_ops) as u64, average)
+        })
+        .collect()
+}
+
+/// Report issues for every scenario whose ledger entries, and every operation
+/// type whose memory per operation, grows without bound
+pub fn leak_issues(collector: &MetricsCollector, config: &LeakDetectionConfig) -> Vec<String> {
+    if !config.enabled {
+        return Vec::new();
+    }
+    let mut issues = Vec::new();
+
+    let mut entries: BTreeMap<Option<String>, Vec<(u64, f64)>> = BTreeMap::new();
+    for sample in collector.ledger_entry_samples() {
+        entries
+            .entry(sample.scenario)
+            .or_default()
+            .push((sample.operations, sample.ledger_entries as f64));
+    }
+    for (scenario, series) in &entries {
+        if let Some(trend) = unbounded_growth(series, config.min_windows) {
+            issues.push(format!(
+                "Ledger entries grow without bound{}: {:.0} -> {:.0} over {} ops ({:.2} per 1k ops)",
+                scenario.as_deref().map(|s| format!(" in {}", s)).unwrap_or_default(),
+                trend.first,
+                trend.last,
+                trend.operations,
+                trend.per_1k_ops()
+            ));
+        }
+    }
+
+    collector.with_metrics(|metrics| {
+        let mut groups: BTreeMap<(Option<&str>, &str), Vec<&OperationMetric>> = BTreeMap::new();
+        for metric in metrics.iter().filter(|m| m.success) {
+            let scenario = metric.metadata.get("scenario").map(String::as_str);
+            groups
+                .entry((scenario, metric.operation.as_str()))
+                .or_default()
+                .push(metric);
+        }
+
+        for ((scenario, operation), group) in &groups {
+            let series = memory_windows(group, config.window_ops);
+            if let Some(trend) = unbounded_growth(&series, config.min_windows) {
+                issues.push(format!(
+                    "Memory per {} grows without bound{}: {:.0} -> {:.0} bytes over {} ops ({:.0} bytes per 1k ops)",
+                    operation,
+                    scenario.map(|s| format!(" in {}", s)).unwrap_or_default(),
+                    trend.first,
+                    trend.last,
+                    trend.operations,
+                    trend.per_1k_ops()
+                ));
+            }
+        }
+    });
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::OperationType;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    fn series(values: &[f64]) -> Vec<(u64, f64)> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| ((i as u64 + 1) * 1000, *v))
+            .collect()
+    }
+
+    #[test]
+    fn test_unbounded_growth() {
+        // Linear growth of one entry per operation
+        let trend = unbounded_growth(&series(&[100.0, 200.0, 300.0, 400.0, 500.0]), 5).unwrap();
+        assert_eq!(trend.operations, 4000);
+        assert_eq!(trend.per_1k_ops(), 100.0);
+
+        // Too few windows to judge
+        assert!(unbounded_growth(&series(&[100.0, 200.0, 300.0, 400.0]), 5).is_none());
+        // Flat, shrinking at some point, or levelling off
+        assert!(unbounded_growth(&series(&[100.0; 6]), 5).is_none());
+        assert!(unbounded_growth(&series(&[100.0, 200.0, 150.0, 300.0, 400.0]), 5).is_none());
+        assert!(
+            unbounded_growth(&series(&[100.0, 300.0, 390.0, 395.0, 398.0, 399.0]), 5).is_none()
+        );
+    }
+
+    #[test]
+    fn test_leak_issues() {
+        let config = LeakDetectionConfig {
+            enabled: true,
+            window_ops: 10,
+            min_windows: 5,
+        };
+        let collector = MetricsCollector::new();
+        let stakes = collector.scoped("bank-run");
+
+        for window in 0..6u64 {
+            for _ in 0..10 {
+                let mut metadata = HashMap::new();
+                metadata.insert(
+                    MEMORY_BYTES_KEY.to_string(),
+                    (1000 + window * 100).to_string(),
+                );
+                stakes.record_success(OperationType::Stake, Duration::from_micros(1), metadata);
+
+                let mut metadata = HashMap::new();
+                metadata.insert(MEMORY_BYTES_KEY.to_string(), "1000".to_string());
+                collector.record_success(OperationType::Swap, Duration::from_micros(1), metadata);
+            }
+            stakes.record_ledger_entries(50 + window * 10);
+        }
+
+        let issues = leak_issues(&collector, &config);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(
+            issues[0],
+            "Ledger entries grow without bound in bank-run: 50 -> 100 over 50 ops (1000.00 per 1k ops)"
+        );
+        assert!(issues[1]
+            .starts_with("Memory per stake grows without bound in bank-run: 1000 -> 1500 bytes"));
+
+        assert!(leak_issues(
+            &collector,
+            &LeakDetectionConfig {
+                enabled: false,
+                ..config
+            }
+        )
+        .is_empty());
+    }
+}
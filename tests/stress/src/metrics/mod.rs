@@ -2,8 +2,26 @@
 //!
 //! Modules for collecting, analyzing, and reporting stress test metrics.
 
+pub mod assertions;
 pub mod collector;
+pub mod cost;
+pub mod diff;
+pub mod exporter;
+pub mod head_to_head;
+pub mod leaks;
+pub mod raw;
 pub mod reporter;
+pub mod soak;
+pub mod taxonomy;
 
-pub use collector::{MetricsCollector, OperationMetric, OperationType};
-pub use reporter::{TestReport, ScenarioReport, PerformanceMetrics, ErrorStatistics};
+pub use assertions::{assertion_violations, AssertionViolation};
+pub use collector::{LedgerEntrySample, MetricsCollector, OperationMetric, OperationType};
+pub use cost::{CostReport, OperationCost};
+pub use diff::{DiffMetric, DiffThresholds, MetricDelta, ReportDiff, Verdict};
+pub use exporter::{LiveSnapshot, MetricsExporter};
+pub use head_to_head::{HeadToHeadReport, VenueStats};
+pub use leaks::{leak_issues, GrowthTrend};
+pub use raw::{save_raw_csv_gz, write_raw_csv};
+pub use soak::{CheckpointWriter, SoakCheckpoint, SoakCheckpointer};
+pub use taxonomy::{error_name, record_error_code, ErrorCodeCount, ERROR_CODE_KEY};
+pub use reporter::{TestReport, ScenarioReport, PerformanceMetrics, ErrorStatistics, AccountStats, PairStats};
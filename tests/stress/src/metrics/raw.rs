@@ -0,0 +1,150 @@
+//! Raw Metrics Export
+//!
+//! Streams every recorded `OperationMetric` to CSV, one row per operation, so
+//! large runs can be analysed outside the suite instead of only through the
+//! aggregated report. The fixed columns come first; every metadata key seen in
+//! the run becomes an extra column (sorted, empty where a metric lacks it).
+//! `save_raw_csv_gz` writes the file gzip-compressed.
+
+use super::collector::{MetricsCollector, OperationMetric};
+use chrono::SecondsFormat;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Columns written for every metric, ahead of the metadata columns
+pub const RAW_COLUMNS: [&str; 5] = [
+    "timestamp",
+    "operation",
+    "duration_micros",
+    "success",
+    "error",
+];
+
+/// Write `metrics` as CSV to `writer`
+pub fn write_raw_csv<W: Write>(metrics: &[OperationMetric], mut writer: W) -> io::Result<()> {
+    let metadata_keys: BTreeSet<&str> = metrics
+        .iter()
+        .flat_map(|m| m.metadata.keys().map(String::as_str))
+        .collect();
+
+    let header: Vec<Cow<str>> = RAW_COLUMNS
+        .iter()
+        .map(|column| Cow::Borrowed(*column))
+        .chain(metadata_keys.iter().map(|key| escape(key)))
+        .collect();
+    writeln!(writer, "{}", header.join(","))?;
+
+    for metric in metrics {
+        let timestamp = metric
+            .timestamp
+            .to_rfc3339_opts(SecondsFormat::Micros, true);
+        write!(
+            writer,
+            "{},{},{},{},{}",
+            timestamp,
+            metric.operation.as_str(),
+            metric.duration_micros,
+            metric.success,
+            escape(metric.error.as_deref().unwrap_or(""))
+        )?;
+        for key in &metadata_keys {
+            write!(
+                writer,
+                ",{}",
+                escape(metric.metadata.get(*key).map_or("", String::as_str))
+            )?;
+        }
+        writeln!(writer)?;
+    }
+    writer.flush()
+}
+
+/// Write every metric in `collector` to a gzip-compressed CSV file at `path`.
+/// Returns the number of rows written.
+pub fn save_raw_csv_gz<P: AsRef<Path>>(collector: &MetricsCollector, path: P) -> io::Result<usize> {
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = GzEncoder::new(file, Compression::default());
+    let rows = collector
+        .with_metrics(|metrics| write_raw_csv(metrics, &mut encoder).map(|_| metrics.len()))?;
+    encoder.finish()?.flush()?;
+    Ok(rows)
+}
+
+/// Quote a CSV field if it contains a delimiter, quote or line break
+fn escape(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::OperationType;
+    use flate2::read::GzDecoder;
+    use std::collections::HashMap;
+    use std::io::Read;
+    use std::time::Duration;
+
+    #[test]
+    fn test_write_raw_csv() {
+        let collector = MetricsCollector::new();
+        let mut metadata = HashMap::new();
+        metadata.insert("amount_in".to_string(), "100".to_string());
+        collector.record_success(OperationType::Swap, Duration::from_micros(42), metadata);
+        let mut metadata = HashMap::new();
+        metadata.insert("error_code".to_string(), "301".to_string());
+        collector.record_error(
+            OperationType::Stake,
+            Duration::from_micros(7),
+            "Stake rejected: \"cooldown\", retry".to_string(),
+            metadata,
+        );
+
+        let mut out = Vec::new();
+        write_raw_csv(&collector.get_metrics(), &mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            "timestamp,operation,duration_micros,success,error,amount_in,error_code"
+        );
+        assert!(lines[1].ends_with(",swap,42,true,,100,"));
+        assert!(
+            lines[2].ends_with(",stake,7,false,\"Stake rejected: \"\"cooldown\"\", retry\",,301")
+        );
+    }
+
+    #[test]
+    fn test_save_raw_csv_gz() {
+        let collector = MetricsCollector::new();
+        for _ in 0..3 {
+            collector.record_success(
+                OperationType::Swap,
+                Duration::from_micros(1),
+                HashMap::new(),
+            );
+        }
+
+        let path = std::env::temp_dir().join(format!("raw_metrics_{}.csv.gz", std::process::id()));
+        assert_eq!(save_raw_csv_gz(&collector, &path).unwrap(), 3);
+
+        let mut csv = String::new();
+        GzDecoder::new(File::open(&path).unwrap())
+            .read_to_string(&mut csv)
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(csv.lines().count(), 4);
+        assert!(csv.starts_with("timestamp,operation,duration_micros,success,error\n"));
+    }
+}
@@ -2,7 +2,12 @@
 //!
 //! Generates comprehensive reports from collected metrics.
 
+use super::assertions;
 use super::collector::{MetricsCollector, OperationMetric, OperationType};
+use super::cost::CostReport;
+use super::head_to_head::HeadToHeadReport;
+use super::leaks;
+use super::taxonomy::{self, ErrorCodeCount};
 use crate::config::StressConfig;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -19,6 +24,10 @@ pub struct TestReport {
     pub end_time: DateTime<Utc>,
     pub duration_seconds: f64,
     pub scenarios: Vec<ScenarioReport>,
+    pub cost: CostReport,
+    /// Router vs aggregator comparison, for runs that executed one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head_to_head: Option<HeadToHeadReport>,
     pub summary: TestSummary,
 }
 
@@ -29,6 +38,10 @@ pub struct ScenarioReport {
     pub performance: PerformanceMetrics,
     pub errors: ErrorStatistics,
     pub operation_breakdown: HashMap<String, OperationStats>,
+    pub account_breakdown: HashMap<String, AccountStats>,
+    pub token_class_breakdown: HashMap<String, OperationStats>,
+    /// Operations tagged with a pair index, keyed by the index
+    pub pair_breakdown: HashMap<String, PairStats>,
 }
 
 /// Performance metrics
@@ -53,6 +66,8 @@ pub struct ErrorStatistics {
     pub error_rate: f64,
     pub error_breakdown: HashMap<String, u64>,
     pub top_errors: Vec<(String, u64)>,
+    /// Failures carrying a contract error code, most frequent first
+    pub error_codes: Vec<ErrorCodeCount>,
 }
 
 /// Statistics for specific operation type
@@ -66,6 +81,31 @@ pub struct OperationStats {
     pub p95_latency_ms: f64,
 }
 
+/// Throughput and failures of the operations on one pair
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairStats {
+    pub count: usize,
+    /// Share of the scenario's operations that hit this pair
+    pub share: f64,
+    pub operations_per_second: f64,
+    pub success_rate: f64,
+    pub avg_latency_ms: f64,
+    pub p95_latency_ms: f64,
+    /// Failures by error message
+    pub error_breakdown: HashMap<String, u64>,
+}
+
+/// Statistics for a single test account
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccountStats {
+    pub target_balance: i128,
+    pub operations: usize,
+    pub success_count: usize,
+    pub failure_count: usize,
+    pub success_rate: f64,
+    pub refills: u64,
+}
+
 /// Overall test summary
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TestSummary {
@@ -89,77 +129,88 @@ impl TestReport {
         let end_time = Utc::now();
         let duration_seconds = (end_time - start_time).num_milliseconds() as f64 / 1000.0;
 
-        // For now, create a single scenario report from all metrics
-        let scenario_name = if config.scenarios.len() == 1 {
+        // Mixed workloads tag metrics per scenario: one sub-report per scenario
+        // followed by the combined report
+        let labels = collector.scenario_labels();
+        let mut scenarios: Vec<ScenarioReport> = labels
+            .iter()
+            .map(|label| {
+                let name = match Self::mix_share(&config, label) {
+                    Some(share) => format!("{} ({:.0}%)", label, share * 100.0),
+                    None => label.clone(),
+                };
+                ScenarioReport::from_metrics(&name, &collector.filtered(label))
+            })
+            .collect();
+
+        let scenario_name = if labels.is_empty() && config.scenarios.len() == 1 {
             format!("{:?}", config.scenarios[0])
         } else {
             "Combined".to_string()
         };
+        scenarios.push(ScenarioReport::from_metrics(&scenario_name, collector));
+
+        // Enforced assertion rules decide the outcome; the rest are only reported
+        let mut issues = Vec::new();
+        let mut assertions_met = true;
+        for violation in
+            assertions::assertion_violations(collector, &config.assertions, config.target_tps)
+        {
+            assertions_met &= !violation.enforced;
+            issues.push(violation.message);
+        }
+
+        issues.extend(leaks::leak_issues(collector, &config.leak_detection));
 
-        let scenario_report = ScenarioReport::from_metrics(&scenario_name, collector);
+        // Any error code over its threshold fails the run regardless of success rate
+        let violations = taxonomy::threshold_violations(
+            &taxonomy::error_code_counts(collector),
+            &config.error_thresholds,
+        );
+        let thresholds_met = violations.is_empty();
+        issues.extend(violations);
 
         let summary = TestSummary {
-            total_scenarios: 1,
+            total_scenarios: labels.len().max(1),
             total_operations: collector.total_operations(),
             overall_success_rate: collector.success_rate(),
             overall_tps: collector.operations_per_second(),
             overall_latency_ms: collector.average_latency_micros() as f64 / 1000.0,
-            test_passed: collector.success_rate() > 0.95, // 95% success threshold
-            issues: Self::identify_issues(collector, &config),
+            test_passed: assertions_met && thresholds_met,
+            issues,
         };
 
+        let cost = CostReport::from_collector(collector, &config.cost);
+        let head_to_head = HeadToHeadReport::from_collector(collector);
+
         Self {
             test_id,
             config,
             start_time,
             end_time,
             duration_seconds,
-            scenarios: vec![scenario_report],
+            scenarios,
+            cost,
+            head_to_head,
             summary,
         }
     }
 
-    /// Identify issues in the test results
-    fn identify_issues(collector: &MetricsCollector, config: &StressConfig) -> Vec<String> {
-        let mut issues = Vec::new();
-
-        // Check success rate
-        if collector.success_rate() < 0.95 {
-            issues.push(format!(
-                "Low success rate: {:.2}% (threshold: 95%)",
-                collector.success_rate() * 100.0
-            ));
-        }
-
-        // Check TPS
-        let actual_tps = collector.operations_per_second();
-        let target_tps = config.target_tps as f64;
-        if actual_tps < target_tps * 0.8 {
-            issues.push(format!(
-                "Low TPS: {:.2} (target: {:.2})",
-                actual_tps, target_tps
-            ));
-        }
-
-        // Check latency
-        let p95_latency_ms = collector.latency_percentile(0.95) as f64 / 1000.0;
-        if p95_latency_ms > 1000.0 {
-            issues.push(format!(
-                "High p95 latency: {:.2}ms (threshold: 1000ms)",
-                p95_latency_ms
-            ));
-        }
-
-        // Check error diversity
-        let error_counts = collector.error_counts();
-        if error_counts.len() > 5 {
-            issues.push(format!(
-                "Many error types: {} distinct errors",
-                error_counts.len()
-            ));
-        }
+    /// Share of a scenario in the configured mix, if it is part of one
+    fn mix_share(config: &StressConfig, label: &str) -> Option<f64> {
+        let total: u32 = config.mix.iter().map(|w| w.weight).sum();
+        config
+            .mix
+            .iter()
+            .find(|w| w.scenario.as_str() == label)
+            .filter(|_| total > 0)
+            .map(|w| w.weight as f64 / total as f64)
+    }
 
-        issues
+    /// Load a report saved with `save_json`
+    pub fn load_json<P: AsRef<Path>>(path: P) -> Result<Self, std::io::Error> {
+        let json = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
     }
 
     /// Save report to JSON file
@@ -227,6 +278,16 @@ impl TestReport {
                     }
                     md.push_str("\n");
                 }
+
+                if !scenario.errors.error_codes.is_empty() {
+                    md.push_str("**Contract Errors**:\n\n");
+                    md.push_str("| Code | Error | Count |\n");
+                    md.push_str("|------|-------|-------|\n");
+                    for error in &scenario.errors.error_codes {
+                        md.push_str(&format!("| {} | {} | {} |\n", error.code, error.name, error.count));
+                    }
+                    md.push_str("\n");
+                }
             }
 
             if !scenario.operation_breakdown.is_empty() {
@@ -246,6 +307,133 @@ impl TestReport {
                 }
                 md.push_str("\n");
             }
+
+            if !scenario.token_class_breakdown.is_empty() {
+                md.push_str("### Token Class Breakdown\n\n");
+                md.push_str("| Token Class | Count | Success Rate | Avg Latency (ms) | P95 Latency (ms) |\n");
+                md.push_str("|-------------|-------|--------------|------------------|------------------|\n");
+
+                for (class, stats) in &scenario.token_class_breakdown {
+                    md.push_str(&format!(
+                        "| {} | {} | {:.2}% | {:.2} | {:.2} |\n",
+                        class,
+                        stats.count,
+                        stats.success_rate * 100.0,
+                        stats.avg_latency_ms,
+                        stats.p95_latency_ms
+                    ));
+                }
+                md.push_str("\n");
+            }
+
+            if !scenario.pair_breakdown.is_empty() {
+                // Busiest pairs first so a hot spot leads the table
+                let mut pairs: Vec<_> = scenario.pair_breakdown.iter().collect();
+                pairs.sort_by(|a, b| b.1.count.cmp(&a.1.count).then_with(|| a.0.cmp(b.0)));
+
+                md.push_str("### Pair Breakdown\n\n");
+                md.push_str("| Pair | Count | Share | TPS | Success Rate | Avg Latency (ms) | P95 Latency (ms) | Top Error |\n");
+                md.push_str("|------|-------|-------|-----|--------------|------------------|------------------|-----------|\n");
+
+                for (pair, stats) in pairs {
+                    let top_error = stats
+                        .error_breakdown
+                        .iter()
+                        .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+                        .map(|(error, count)| format!("{} ({})", error, count))
+                        .unwrap_or_else(|| "-".to_string());
+                    md.push_str(&format!(
+                        "| {} | {} | {:.2}% | {:.2} | {:.2}% | {:.2} | {:.2} | {} |\n",
+                        pair,
+                        stats.count,
+                        stats.share * 100.0,
+                        stats.operations_per_second,
+                        stats.success_rate * 100.0,
+                        stats.avg_latency_ms,
+                        stats.p95_latency_ms,
+                        top_error
+                    ));
+                }
+                md.push_str("\n");
+            }
+
+            if !scenario.account_breakdown.is_empty() {
+                // Wealthiest accounts first so whale vs small-trader behaviour is visible
+                let mut accounts: Vec<_> = scenario.account_breakdown.iter().collect();
+                accounts.sort_by(|a, b| b.1.target_balance.cmp(&a.1.target_balance));
+
+                md.push_str("### Account Breakdown\n\n");
+                md.push_str("| Account | Target Balance | Operations | Success Rate | Refills |\n");
+                md.push_str("|---------|----------------|------------|--------------|---------|\n");
+
+                for (account, stats) in accounts.iter().take(20) {
+                    md.push_str(&format!(
+                        "| {} | {} | {} | {:.2}% | {} |\n",
+                        account,
+                        stats.target_balance,
+                        stats.operations,
+                        stats.success_rate * 100.0,
+                        stats.refills
+                    ));
+                }
+                if accounts.len() > 20 {
+                    md.push_str(&format!("\n_{} more accounts omitted_\n", accounts.len() - 20));
+                }
+                md.push_str("\n");
+            }
+        }
+
+        if !self.cost.per_operation.is_empty() {
+            md.push_str("## Cost Projection\n\n");
+            md.push_str("| Operation | Samples | Avg CPU | Max CPU | Avg Mem | CPU Limit % | Fee (stroops) |\n");
+            md.push_str("|-----------|---------|---------|---------|---------|-------------|---------------|\n");
+
+            let mut operations: Vec<_> = self.cost.per_operation.iter().collect();
+            operations.sort_by(|a, b| a.0.cmp(b.0));
+            for (op_name, cost) in operations {
+                md.push_str(&format!(
+                    "| {} | {} | {} | {} | {} | {:.2}% | {} |\n",
+                    op_name,
+                    cost.samples,
+                    cost.avg_cpu_instructions,
+                    cost.max_cpu_instructions,
+                    cost.avg_memory_bytes,
+                    cost.cpu_limit_utilization * 100.0,
+                    cost.fee_stroops
+                ));
+            }
+            md.push_str(&format!(
+                "\n**Projected daily cost** for {} operations: {} stroops ({:.4} XLM)\n\n",
+                self.cost.daily_operations, self.cost.daily_fee_stroops, self.cost.daily_fee_xlm
+            ));
+        }
+
+        if let Some(h2h) = &self.head_to_head {
+            md.push_str("## Router vs Aggregator\n\n");
+            md.push_str("| Venue | Trades | Failure Rate | Avg Hops | Avg CPU | Avg Mem |\n");
+            md.push_str("|-------|--------|--------------|----------|---------|---------|\n");
+            for (venue, stats) in [("Router", &h2h.router), ("Aggregator", &h2h.aggregator)] {
+                md.push_str(&format!(
+                    "| {} | {} | {:.2}% | {:.2} | {} | {} |\n",
+                    venue,
+                    stats.trades,
+                    stats.failure_rate * 100.0,
+                    stats.avg_hops,
+                    stats.avg_cpu_instructions,
+                    stats.avg_memory_bytes
+                ));
+            }
+            md.push_str(&format!(
+                "\nOn {} trades both filled, the aggregator returned more on {}, less on {} and the same on {}: \
+                 {:+.2} bps per trade on average ({} vs {} total output)\n\n",
+                h2h.both_filled,
+                h2h.aggregator_better,
+                h2h.router_better,
+                h2h.ties,
+                h2h.avg_output_diff_bps,
+                h2h.aggregator_output,
+                h2h.router_output
+            ));
         }
 
         md
@@ -258,13 +446,104 @@ impl ScenarioReport {
         let performance = PerformanceMetrics::from_collector(collector);
         let errors = ErrorStatistics::from_collector(collector);
         let operation_breakdown = Self::calculate_operation_breakdown(collector);
+        let account_breakdown = Self::calculate_account_breakdown(collector);
+        let token_class_breakdown = Self::calculate_token_class_breakdown(collector);
+        let pair_breakdown = Self::calculate_pair_breakdown(collector);
 
         Self {
             name: name.to_string(),
             performance,
             errors,
             operation_breakdown,
+            account_breakdown,
+            token_class_breakdown,
+            pair_breakdown,
+        }
+    }
+
+    fn calculate_pair_breakdown(collector: &MetricsCollector) -> HashMap<String, PairStats> {
+        let metrics = collector.get_metrics();
+        let total = metrics.len();
+        let mut by_pair: HashMap<String, Vec<OperationMetric>> = HashMap::new();
+        for metric in metrics {
+            if let Some(pair) = metric.metadata.get("pair").cloned() {
+                by_pair.entry(pair).or_default().push(metric);
+            }
+        }
+
+        // Per-pair throughput is the pair's share of the scenario's throughput
+        let operations_per_second = collector.operations_per_second();
+
+        by_pair
+            .into_iter()
+            .map(|(pair, metrics)| {
+                let stats = OperationStats::from_metrics(&metrics);
+                let share = stats.count as f64 / total as f64;
+                let mut error_breakdown: HashMap<String, u64> = HashMap::new();
+                for error in metrics.iter().filter(|m| !m.success).filter_map(|m| m.error.clone()) {
+                    *error_breakdown.entry(error).or_insert(0) += 1;
+                }
+
+                let pair_stats = PairStats {
+                    count: stats.count,
+                    share,
+                    operations_per_second: operations_per_second * share,
+                    success_rate: stats.success_rate,
+                    avg_latency_ms: stats.avg_latency_ms,
+                    p95_latency_ms: stats.p95_latency_ms,
+                    error_breakdown,
+                };
+                (pair, pair_stats)
+            })
+            .collect()
+    }
+
+    fn calculate_token_class_breakdown(collector: &MetricsCollector) -> HashMap<String, OperationStats> {
+        let mut by_class: HashMap<String, Vec<OperationMetric>> = HashMap::new();
+        for metric in collector.get_metrics() {
+            if let Some(class) = metric.metadata.get("token_class") {
+                by_class.entry(class.clone()).or_default().push(metric);
+            }
         }
+
+        by_class
+            .into_iter()
+            .map(|(class, metrics)| (class, OperationStats::from_metrics(&metrics)))
+            .collect()
+    }
+
+    fn calculate_account_breakdown(collector: &MetricsCollector) -> HashMap<String, AccountStats> {
+        let mut breakdown: HashMap<String, AccountStats> = HashMap::new();
+
+        for (account, balance) in collector.account_balances() {
+            breakdown.entry(account).or_default().target_balance = balance;
+        }
+
+        for metric in collector.get_metrics() {
+            if let Some(account) = metric.metadata.get("account") {
+                let stats = breakdown.entry(account.clone()).or_default();
+                stats.operations += 1;
+                if metric.success {
+                    stats.success_count += 1;
+                } else {
+                    stats.failure_count += 1;
+                }
+            }
+        }
+
+        for (account, refills) in collector.refill_counts() {
+            breakdown.entry(account).or_default().refills = refills;
+        }
+
+        for stats in breakdown.values_mut() {
+            stats.success_rate = if stats.operations > 0 {
+                stats.success_count as f64 / stats.operations as f64
+            } else {
+                0.0
+            };
+        }
+
+        breakdown
     }
 
     fn calculate_operation_breakdown(collector: &MetricsCollector) -> HashMap<String, OperationStats> {
@@ -274,6 +553,11 @@ impl ScenarioReport {
             OperationType::AddLiquidity,
             OperationType::RemoveLiquidity,
             OperationType::MultiHopSwap,
+            OperationType::CreatePair,
+            OperationType::Stake,
+            OperationType::Graduate,
+            OperationType::OracleUpdate,
+            OperationType::TwapQuery,
         ];
 
         for op_type in operation_types {
@@ -329,6 +613,7 @@ impl ErrorStatistics {
             error_rate,
             error_breakdown,
             top_errors,
+            error_codes: taxonomy::error_code_counts(collector),
         }
     }
 }
@@ -366,3 +651,50 @@ impl OperationStats {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn on_pair(pair: &str) -> HashMap<String, String> {
+        let mut metadata = HashMap::new();
+        metadata.insert("pair".to_string(), pair.to_string());
+        metadata
+    }
+
+    #[test]
+    fn test_pair_breakdown() {
+        let collector = MetricsCollector::new();
+        for _ in 0..7 {
+            collector.record_success(OperationType::Swap, Duration::from_millis(1), on_pair("0"));
+        }
+        for _ in 0..2 {
+            collector.record_error(
+                OperationType::Swap,
+                Duration::from_millis(1),
+                "Swap failed: SlippageExceeded".to_string(),
+                on_pair("0"),
+            );
+        }
+        collector.record_success(OperationType::Swap, Duration::from_millis(1), on_pair("1"));
+        collector.record_success(
+            OperationType::AddLiquidity,
+            Duration::from_millis(1),
+            HashMap::new(),
+        );
+
+        let breakdown = ScenarioReport::calculate_pair_breakdown(&collector);
+        assert_eq!(breakdown.len(), 2);
+
+        let hot = &breakdown["0"];
+        assert_eq!(hot.count, 9);
+        assert!((hot.share - 0.9).abs() < 1e-9);
+        assert!((hot.success_rate - 7.0 / 9.0).abs() < 1e-9);
+        assert_eq!(hot.error_breakdown["Swap failed: SlippageExceeded"], 2);
+
+        let cold = &breakdown["1"];
+        assert_eq!(cold.count, 1);
+        assert!(cold.error_breakdown.is_empty());
+    }
+}
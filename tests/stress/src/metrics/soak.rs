@@ -0,0 +1,213 @@
+//! Soak Checkpoints
+//!
+//! Multi-hour runs would otherwise hold every metric in memory and lose
+//! everything if the process dies before the end-of-run report. While a soak
+//! run is in progress, every `soak.checkpoint_interval_secs` the collector is
+//! rotated and the window since the previous checkpoint is written out:
+//!
+//! - `<test_id>.checkpoint-0001.json` - `TestReport` for the window
+//! - `<test_id>.checkpoint-0001.metrics.csv.gz` - its raw metrics (with `--raw-metrics`)
+//!
+//! Indices start at 1 and increase by one per checkpoint. Reports are written
+//! to a temporary file and renamed, so a crash never leaves a truncated one.
+
+use super::collector::MetricsCollector;
+use super::raw::save_raw_csv_gz;
+use super::reporter::TestReport;
+use crate::config::StressConfig;
+use chrono::{DateTime, Utc};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// How often the background thread checks for shutdown
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// A checkpoint written during a soak run
+#[derive(Debug, Clone)]
+pub struct SoakCheckpoint {
+    pub index: u32,
+    pub report_path: PathBuf,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub total_operations: usize,
+    pub test_passed: bool,
+}
+
+/// Writes numbered checkpoints of a collector's metrics, one window at a time
+pub struct CheckpointWriter {
+    test_id: String,
+    config: StressConfig,
+    output_dir: PathBuf,
+    raw_metrics: bool,
+    next_index: u32,
+    window_start: DateTime<Utc>,
+}
+
+impl CheckpointWriter {
+    /// Writer for `test_id` whose first window starts now
+    pub fn new<P: AsRef<Path>>(
+        test_id: String,
+        config: StressConfig,
+        output_dir: P,
+        raw_metrics: bool,
+    ) -> Self {
+        Self {
+            test_id,
+            config,
+            output_dir: output_dir.as_ref().to_path_buf(),
+            raw_metrics,
+            next_index: 1,
+            window_start: Utc::now(),
+        }
+    }
+
+    /// Rotate `collector` and write the window since the previous checkpoint
+    pub fn write(&mut self, collector: &MetricsCollector) -> io::Result<SoakCheckpoint> {
+        let index = self.next_index;
+        let window = collector.rotate();
+        let report = TestReport::from_metrics(
+            format!("{}.checkpoint-{:04}", self.test_id, index),
+            self.config.clone(),
+            self.window_start,
+            &window,
+        );
+
+        let report_path = self.path(index, "json");
+        let tmp_path = self.path(index, "json.tmp");
+        report.save_json(&tmp_path)?;
+        fs::rename(&tmp_path, &report_path)?;
+
+        if self.raw_metrics {
+            save_raw_csv_gz(&window, self.path(index, "metrics.csv.gz"))?;
+        }
+
+        self.next_index += 1;
+        self.window_start = report.end_time;
+
+        Ok(SoakCheckpoint {
+            index,
+            report_path,
+            start_time: report.start_time,
+            end_time: report.end_time,
+            total_operations: report.summary.total_operations,
+            test_passed: report.summary.test_passed,
+        })
+    }
+
+    fn path(&self, index: u32, extension: &str) -> PathBuf {
+        self.output_dir.join(format!(
+            "{}.checkpoint-{:04}.{}",
+            self.test_id, index, extension
+        ))
+    }
+}
+
+/// Background thread writing a checkpoint every `soak.checkpoint_interval_secs`.
+/// The window after the last checkpoint stays in the collector for the
+/// end-of-run report.
+pub struct SoakCheckpointer {
+    shutdown: Arc<AtomicBool>,
+    handle: Option<JoinHandle<Vec<SoakCheckpoint>>>,
+}
+
+impl SoakCheckpointer {
+    /// Start checkpointing `collector`
+    pub fn start(mut writer: CheckpointWriter, collector: MetricsCollector) -> Self {
+        let interval = Duration::from_secs(writer.config.soak.checkpoint_interval_secs.max(1));
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let stop = shutdown.clone();
+
+        let handle = thread::spawn(move || {
+            let mut checkpoints = Vec::new();
+            let mut last = Instant::now();
+            while !stop.load(Ordering::Relaxed) {
+                thread::sleep(POLL_INTERVAL);
+                if last.elapsed() < interval {
+                    continue;
+                }
+                last = Instant::now();
+
+                // A failed write loses one window, never the run
+                match writer.write(&collector) {
+                    Ok(checkpoint) => {
+                        log::info!(
+                            "Checkpoint {} ({} operations) saved to {}",
+                            checkpoint.index,
+                            checkpoint.total_operations,
+                            checkpoint.report_path.display()
+                        );
+                        checkpoints.push(checkpoint);
+                    }
+                    Err(e) => log::error!("Failed to write soak checkpoint: {}", e),
+                }
+            }
+            checkpoints
+        });
+
+        Self {
+            shutdown,
+            handle: Some(handle),
+        }
+    }
+
+    /// Stop checkpointing and return the checkpoints written
+    pub fn finish(mut self) -> Vec<SoakCheckpoint> {
+        self.stop()
+    }
+
+    fn stop(&mut self) -> Vec<SoakCheckpoint> {
+        self.shutdown.store(true, Ordering::Relaxed);
+        self.handle
+            .take()
+            .and_then(|handle| handle.join().ok())
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for SoakCheckpointer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::OperationType;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_checkpoints_rotate_metrics() {
+        let dir = std::env::temp_dir().join(format!("soak_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let collector = MetricsCollector::new();
+        let mut writer =
+            CheckpointWriter::new("soak".to_string(), StressConfig::default(), &dir, true);
+
+        for _ in 0..3 {
+            collector.record_success(OperationType::Swap, Duration::from_micros(1), HashMap::new());
+        }
+        let first = writer.write(&collector).unwrap();
+        collector.record_success(OperationType::Swap, Duration::from_micros(1), HashMap::new());
+        let second = writer.write(&collector).unwrap();
+
+        assert_eq!((first.index, second.index), (1, 2));
+        assert_eq!((first.total_operations, second.total_operations), (3, 1));
+        assert_eq!(second.start_time, first.end_time);
+        assert_eq!(collector.total_operations(), 0);
+
+        let report: TestReport =
+            serde_json::from_str(&fs::read_to_string(&second.report_path).unwrap()).unwrap();
+        assert_eq!(report.test_id, "soak.checkpoint-0002");
+        assert!(dir.join("soak.checkpoint-0001.metrics.csv.gz").exists());
+        assert!(!dir.join("soak.checkpoint-0001.json.tmp").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
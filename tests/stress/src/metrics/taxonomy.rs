@@ -0,0 +1,182 @@
+//! Failure Taxonomy
+//!
+//! Captures the contract error code from `try_` invocations, maps codes to the
+//! `AstroSwapError` / `OracleError` variant names, and checks the per-code
+//! thresholds configured in `StressConfig::error_thresholds`.
+
+use super::collector::MetricsCollector;
+use crate::config::ErrorCodeThreshold;
+use astroswap_oracle::OracleError;
+use astroswap_shared::AstroSwapError;
+use serde::{Deserialize, Serialize};
+use soroban_sdk::{Error, InvokeError};
+use std::collections::HashMap;
+
+/// Metadata key holding the contract error code of a failed operation
+pub const ERROR_CODE_KEY: &str = "error_code";
+
+/// Occurrences of one contract error code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorCodeCount {
+    pub code: u32,
+    pub name: String,
+    pub count: u64,
+}
+
+/// Contract error code carried by the result of a `try_` invocation, if any
+pub fn contract_error_code<T, C, E>(result: &Result<Result<T, C>, Result<E, InvokeError>>) -> Option<u32>
+where
+    E: Copy + Into<Error>,
+{
+    match result {
+        Err(Ok(err)) => Some((*err).into().get_code()),
+        Err(Err(InvokeError::Contract(code))) => Some(*code),
+        _ => None,
+    }
+}
+
+/// Human-readable name for a contract error code
+pub fn error_name(code: u32) -> String {
+    let error = Error::from_contract_error(code);
+    if let Ok(err) = AstroSwapError::try_from(error) {
+        format!("{:?}", err)
+    } else if let Ok(err) = OracleError::try_from(error) {
+        format!("{:?}", err)
+    } else {
+        format!("Unknown({})", code)
+    }
+}
+
+/// Record the error code of a failed `try_` result in `metadata` and return
+/// the failure message, e.g. "Swap failed: SlippageExceeded"
+pub fn record_error_code<T, C, E>(
+    result: &Result<Result<T, C>, Result<E, InvokeError>>,
+    metadata: &mut HashMap<String, String>,
+    context: &str,
+) -> String
+where
+    E: Copy + Into<Error>,
+{
+    match contract_error_code(result) {
+        Some(code) => {
+            metadata.insert(ERROR_CODE_KEY.to_string(), code.to_string());
+            format!("{}: {}", context, error_name(code))
+        }
+        None => context.to_string(),
+    }
+}
+
+/// Failed operations per contract error code, most frequent first
+pub fn error_code_counts(collector: &MetricsCollector) -> Vec<ErrorCodeCount> {
+    let mut counts: HashMap<u32, u64> = HashMap::new();
+    for metric in collector.get_metrics().iter().filter(|m| !m.success) {
+        if let Some(code) = metric
+            .metadata
+            .get(ERROR_CODE_KEY)
+            .and_then(|c| c.parse::<u32>().ok())
+        {
+            *counts.entry(code).or_insert(0) += 1;
+        }
+    }
+
+    let mut codes: Vec<ErrorCodeCount> = counts
+        .into_iter()
+        .map(|(code, count)| ErrorCodeCount {
+            code,
+            name: error_name(code),
+            count,
+        })
+        .collect();
+    codes.sort_by(|a, b| b.count.cmp(&a.count).then(a.code.cmp(&b.code)));
+    codes
+}
+
+/// Threshold violations, one message per error code over its limit
+pub fn threshold_violations(codes: &[ErrorCodeCount], thresholds: &[ErrorCodeThreshold]) -> Vec<String> {
+    thresholds
+        .iter()
+        .filter_map(|threshold| {
+            let seen = codes.iter().find(|c| c.code == threshold.code)?;
+            (seen.count > threshold.max_count).then(|| {
+                format!(
+                    "{} errors (code {}): {} (threshold: {})",
+                    seen.name, seen.code, seen.count, threshold.max_count
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::OperationType;
+    use std::time::Duration;
+
+    fn failure(collector: &MetricsCollector, code: u32) {
+        let mut metadata = HashMap::new();
+        metadata.insert(ERROR_CODE_KEY.to_string(), code.to_string());
+        collector.record_error(
+            OperationType::Swap,
+            Duration::from_millis(1),
+            format!("Swap failed: {}", error_name(code)),
+            metadata,
+        );
+    }
+
+    #[test]
+    fn test_error_names() {
+        assert_eq!(error_name(AstroSwapError::Reentrancy as u32), "Reentrancy");
+        assert_eq!(error_name(AstroSwapError::SlippageExceeded as u32), "SlippageExceeded");
+        assert_eq!(error_name(OracleError::StalePrice as u32), "StalePrice");
+        assert_eq!(error_name(99_999), "Unknown(99999)");
+    }
+
+    #[test]
+    fn test_contract_error_code() {
+        let err: Result<Result<i128, ()>, Result<AstroSwapError, InvokeError>> =
+            Err(Ok(AstroSwapError::DeadlineExpired));
+        assert_eq!(contract_error_code(&err), Some(301));
+
+        let invoke: Result<Result<i128, ()>, Result<AstroSwapError, InvokeError>> =
+            Err(Err(InvokeError::Contract(8)));
+        assert_eq!(contract_error_code(&invoke), Some(8));
+
+        let ok: Result<Result<i128, ()>, Result<AstroSwapError, InvokeError>> = Ok(Ok(1));
+        assert_eq!(contract_error_code(&ok), None);
+    }
+
+    #[test]
+    fn test_record_error_code() {
+        let mut metadata = HashMap::new();
+        let err: Result<Result<i128, ()>, Result<AstroSwapError, InvokeError>> =
+            Err(Ok(AstroSwapError::SlippageExceeded));
+        assert_eq!(record_error_code(&err, &mut metadata, "Swap failed"), "Swap failed: SlippageExceeded");
+        assert_eq!(metadata.get(ERROR_CODE_KEY).map(String::as_str), Some("300"));
+
+        let mut metadata = HashMap::new();
+        let abort: Result<Result<i128, ()>, Result<AstroSwapError, InvokeError>> = Err(Err(InvokeError::Abort));
+        assert_eq!(record_error_code(&abort, &mut metadata, "Swap failed"), "Swap failed");
+        assert!(metadata.is_empty());
+    }
+
+    #[test]
+    fn test_threshold_violations() {
+        let collector = MetricsCollector::new();
+        failure(&collector, AstroSwapError::SlippageExceeded as u32);
+        failure(&collector, AstroSwapError::SlippageExceeded as u32);
+        failure(&collector, AstroSwapError::Reentrancy as u32);
+
+        let codes = error_code_counts(&collector);
+        assert_eq!(codes[0].name, "SlippageExceeded");
+        assert_eq!(codes[0].count, 2);
+
+        let thresholds = vec![
+            ErrorCodeThreshold { code: AstroSwapError::Reentrancy as u32, max_count: 0 },
+            ErrorCodeThreshold { code: AstroSwapError::SlippageExceeded as u32, max_count: 10 },
+        ];
+        let violations = threshold_violations(&codes, &thresholds);
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].starts_with("Reentrancy"));
+    }
+}
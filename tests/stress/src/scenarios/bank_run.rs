@@ -0,0 +1,401 @@
+//! Staking Bank-Run Scenario
+//!
+//! Hundreds of stakers enter a single pool at its start, then all withdraw at
+//! once when the pool reaches `end_time`: some claim before unstaking, the rest
+//! unstake directly (which pays out pending rewards on the way). The scenario
+//! checks that every staker gets their full stake back, that the rewards paid
+//! never exceed what was funded, and reports the latency distribution of the
+//! withdrawal spike.
+
+use super::StressScenario;
+use crate::config::StressConfig;
+use crate::metrics::{record_error_code, MetricsCollector, OperationType};
+use crate::utils::{record_budget, TokenManager};
+use astroswap_staking::{AstroSwapStaking, AstroSwapStakingClient};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Token index of the reward token
+const REWARD_TOKEN: usize = 0;
+
+/// Token index of the staked token
+const STAKE_TOKEN: usize = 1;
+
+/// Stake size bounds per staker (7 decimals)
+const MIN_STAKE: i128 = 10_0000000;
+const MAX_STAKE: i128 = 10_000_0000000;
+
+/// A staker and the amount they put into the pool
+#[derive(Debug, Clone)]
+pub struct Staker {
+    pub address: Address,
+    pub staked: i128,
+}
+
+/// Outcome of the withdrawal spike
+#[derive(Debug, Clone, Default)]
+pub struct WithdrawalReport {
+    /// Stakers who got their full stake back
+    pub withdrawn: u32,
+    /// Stakers left with stake in the pool
+    pub locked_out: u32,
+    /// Reward tokens paid out across all stakers
+    pub rewards_paid: i128,
+    /// Withdrawal latencies in microseconds (claims and unstakes)
+    pub latencies: Vec<u64>,
+}
+
+impl WithdrawalReport {
+    /// Latency at `percentile` (0.0 - 1.0), 0 if nothing was measured
+    pub fn latency_percentile(&self, percentile: f64) -> u64 {
+        if self.latencies.is_empty() {
+            return 0;
+        }
+        let mut sorted = self.latencies.clone();
+        sorted.sort_unstable();
+        let index = ((sorted.len() as f64) * percentile) as usize;
+        sorted[index.min(sorted.len() - 1)]
+    }
+}
+
+pub struct BankRunScenario;
+
+impl BankRunScenario {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Setup test environment: one funded pool running from now to `end_time`
+    #[allow(clippy::type_complexity)]
+    fn setup_environment(
+        &self,
+        config: &StressConfig,
+    ) -> (
+        Env,
+        TokenManager,
+        AstroSwapStakingClient<'static>,
+        u32,
+        u64,
+        i128,
+    ) {
+        let env = Env::default();
+        // Use mock_all_auths_allowing_non_root_auth for contract-to-contract calls (SDK 23)
+        env.mock_all_auths_allowing_non_root_auth();
+
+        let admin = Address::generate(&env);
+        let bank_run = &config.bank_run;
+
+        let mut token_manager = TokenManager::new();
+        token_manager.create_tokens(&env, &admin, 2, 100_000_000_0000000);
+        let reward_token = token_manager.get(REWARD_TOKEN).unwrap().address.clone();
+        let stake_token = token_manager.get(STAKE_TOKEN).unwrap().address.clone();
+
+        let staking_address = env.register(AstroSwapStaking, ());
+        let staking = AstroSwapStakingClient::new(&env, &staking_address);
+        staking.initialize(&admin, &reward_token);
+
+        let start = env.ledger().timestamp();
+        let end_time = start + bank_run.pool_duration_secs;
+        let pool_id = staking.create_pool(
+            &admin,
+            &stake_token,
+            &bank_run.reward_per_second,
+            &start,
+            &end_time,
+        );
+
+        let emission = bank_run.reward_per_second * bank_run.pool_duration_secs as i128;
+        let funded = emission * bank_run.funding_bps as i128 / 10_000;
+        if funded > 0 {
+            token_manager.mint(REWARD_TOKEN, &admin, funded);
+            staking.fund_rewards(&admin, &funded);
+        }
+
+        (env, token_manager, staking, pool_id, end_time, funded)
+    }
+
+    /// Stake a random amount from a fresh address
+    fn enter(
+        &self,
+        env: &Env,
+        staking: &AstroSwapStakingClient,
+        token_manager: &TokenManager,
+        pool_id: u32,
+        collector: &MetricsCollector,
+    ) -> Option<Staker> {
+        let address = Address::generate(env);
+        let amount = rand::thread_rng().gen_range(MIN_STAKE..=MAX_STAKE);
+        token_manager.mint(STAKE_TOKEN, &address, amount);
+
+        let timer = collector.start_operation().with_budget(env);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            staking.try_stake(&address, &pool_id, &amount)
+        }));
+
+        let mut metadata = HashMap::new();
+        metadata.insert("amount".to_string(), amount.to_string());
+
+        match result {
+            Ok(Ok(Ok(()))) => {
+                record_budget(env, &mut metadata);
+                timer.success(OperationType::Stake, metadata);
+                Some(Staker {
+                    address,
+                    staked: amount,
+                })
+            }
+            Ok(failed) => {
+                let message = record_error_code(&failed, &mut metadata, "Stake rejected");
+                timer.error(OperationType::Stake, message, metadata);
+                None
+            }
+            Err(_) => {
+                timer.error(OperationType::Stake, "Stake panicked".to_string(), metadata);
+                None
+            }
+        }
+    }
+
+    /// Claim pending rewards, returning the time taken in microseconds
+    fn claim(
+        &self,
+        staking: &AstroSwapStakingClient,
+        staker: &Staker,
+        pool_id: u32,
+        collector: &MetricsCollector,
+    ) -> u64 {
+        let started = Instant::now();
+        let timer = collector.start_operation().with_budget(&staking.env);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            staking.try_claim_rewards(&staker.address, &pool_id)
+        }));
+        let elapsed = started.elapsed().as_micros() as u64;
+
+        let mut metadata = HashMap::new();
+        match result {
+            Ok(Ok(Ok(amount))) => {
+                metadata.insert("amount".to_string(), amount.to_string());
+                record_budget(&staking.env, &mut metadata);
+                timer.success(OperationType::ClaimRewards, metadata);
+            }
+            Ok(failed) => {
+                let message = record_error_code(&failed, &mut metadata, "Claim rejected");
+                timer.error(OperationType::ClaimRewards, message, metadata);
+            }
+            Err(_) => {
+                timer.error(
+                    OperationType::ClaimRewards,
+                    "Claim panicked".to_string(),
+                    metadata,
+                );
+            }
+        }
+        elapsed
+    }
+
+    /// Unstake the full position, returning the time taken in microseconds
+    fn unstake(
+        &self,
+        staking: &AstroSwapStakingClient,
+        staker: &Staker,
+        pool_id: u32,
+        collector: &MetricsCollector,
+    ) -> u64 {
+        let started = Instant::now();
+        let timer = collector.start_operation().with_budget(&staking.env);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            staking.try_unstake(&staker.address, &pool_id, &staker.staked)
+        }));
+        let elapsed = started.elapsed().as_micros() as u64;
+
+        let mut metadata = HashMap::new();
+        metadata.insert("amount".to_string(), staker.staked.to_string());
+
+        match result {
+            Ok(Ok(Ok(()))) => {
+                record_budget(&staking.env, &mut metadata);
+                timer.success(OperationType::Unstake, metadata);
+            }
+            Ok(failed) => {
+                let message = record_error_code(&failed, &mut metadata, "Unstake rejected");
+                timer.error(OperationType::Unstake, message, metadata);
+            }
+            Err(_) => {
+                timer.error(
+                    OperationType::Unstake,
+                    "Unstake panicked".to_string(),
+                    metadata,
+                );
+            }
+        }
+        elapsed
+    }
+
+    /// Withdraw every staker in random order at the current ledger time
+    fn withdraw_all(
+        &self,
+        config: &StressConfig,
+        staking: &AstroSwapStakingClient,
+        token_manager: &TokenManager,
+        stakers: &[Staker],
+        pool_id: u32,
+        collector: &MetricsCollector,
+    ) -> WithdrawalReport {
+        let mut rng = rand::thread_rng();
+        let mut order: Vec<&Staker> = stakers.iter().collect();
+        order.shuffle(&mut rng);
+
+        let mut report = WithdrawalReport::default();
+        for staker in order {
+            if rng.gen_range(0..10_000) < config.bank_run.claim_first_bps {
+                report
+                    .latencies
+                    .push(self.claim(staking, staker, pool_id, collector));
+            }
+            report
+                .latencies
+                .push(self.unstake(staking, staker, pool_id, collector));
+
+            // Stakers start with no reward tokens, so their balance is what they were paid
+            report.rewards_paid += token_manager.balance(REWARD_TOKEN, &staker.address);
+            if token_manager.balance(STAKE_TOKEN, &staker.address) == staker.staked {
+                report.withdrawn += 1;
+            } else {
+                report.locked_out += 1;
+            }
+        }
+        report
+    }
+}
+
+impl Default for BankRunScenario {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StressScenario for BankRunScenario {
+    fn run(&self, config: &StressConfig, collector: &MetricsCollector) {
+        let (env, token_manager, staking, pool_id, end_time, funded) =
+            self.setup_environment(config);
+        let test_start = Instant::now();
+
+        println!(
+            "Starting bank-run test: {} stakers, {} reward tokens funded",
+            config.bank_run.num_stakers, funded
+        );
+
+        let stakers: Vec<Staker> = (0..config.bank_run.num_stakers)
+            .filter_map(|_| self.enter(&env, &staking, &token_manager, pool_id, collector))
+            .collect();
+
+        // Everyone heads for the exit as the pool closes
+        env.ledger().with_mut(|li| {
+            let elapsed = end_time.saturating_sub(li.timestamp);
+            li.sequence_number += (elapsed / 5) as u32;
+            li.timestamp = end_time;
+        });
+
+        let report = self.withdraw_all(
+            config,
+            &staking,
+            &token_manager,
+            &stakers,
+            pool_id,
+            collector,
+        );
+
+        println!(
+            "Bank-run test completed: {}/{} stakers withdrew in {:.2}s",
+            report.withdrawn,
+            stakers.len(),
+            test_start.elapsed().as_secs_f64()
+        );
+        if report.locked_out > 0 {
+            println!(
+                "  LOCKED OUT: {} stakers could not withdraw their stake",
+                report.locked_out
+            );
+        }
+        println!(
+            "  Rewards paid: {} of {} funded{}",
+            report.rewards_paid,
+            funded,
+            if report.rewards_paid > funded {
+                " (BUDGET EXCEEDED)"
+            } else {
+                ""
+            }
+        );
+        println!(
+            "  Withdrawal latency: p50 {}us | p95 {}us | p99 {}us | max {}us",
+            report.latency_percentile(0.50),
+            report.latency_percentile(0.95),
+            report.latency_percentile(0.99),
+            report.latencies.iter().copied().max().unwrap_or(0)
+        );
+    }
+
+    fn name(&self) -> &str {
+        "Staking Bank Run"
+    }
+
+    fn description(&self) -> &str {
+        "Withdraws every staker at pool end, checking for lockouts and reward budget overruns"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bank_run_scenario() {
+        let scenario = BankRunScenario::new();
+        let mut config = StressConfig::default();
+        config.bank_run.num_stakers = 20;
+
+        let (env, token_manager, staking, pool_id, end_time, funded) =
+            scenario.setup_environment(&config);
+        let collector = MetricsCollector::new();
+
+        let stakers: Vec<Staker> = (0..config.bank_run.num_stakers)
+            .filter_map(|_| scenario.enter(&env, &staking, &token_manager, pool_id, &collector))
+            .collect();
+        assert_eq!(stakers.len(), 20);
+
+        env.ledger().set_timestamp(end_time);
+        let report = scenario.withdraw_all(
+            &config,
+            &staking,
+            &token_manager,
+            &stakers,
+            pool_id,
+            &collector,
+        );
+
+        assert_eq!(report.withdrawn, 20);
+        assert_eq!(report.locked_out, 0);
+        assert!(report.rewards_paid > 0);
+        assert!(report.rewards_paid <= funded);
+        assert!(report.latencies.len() >= 20);
+    }
+
+    #[test]
+    fn test_latency_percentile() {
+        let report = WithdrawalReport {
+            latencies: vec![40, 10, 30, 20],
+            ..Default::default()
+        };
+        assert_eq!(report.latency_percentile(0.0), 10);
+        assert_eq!(report.latency_percentile(0.5), 30);
+        assert_eq!(report.latency_percentile(1.0), 40);
+        assert_eq!(WithdrawalReport::default().latency_percentile(0.5), 0);
+    }
+}
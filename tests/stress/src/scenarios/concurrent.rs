@@ -4,11 +4,13 @@
 
 use super::StressScenario;
 use crate::config::StressConfig;
-use crate::metrics::{MetricsCollector, OperationType};
-use crate::utils::{AccountPool, TokenManager};
+use crate::metrics::{record_error_code, MetricsCollector, OperationType};
+use crate::utils::snapshot::{self, World};
+use crate::utils::{record_budget, AccountPool, LedgerClock, LedgerEntrySampler, TokenManager};
 use crate::pair_wasm;
 use astroswap_factory::{AstroSwapFactory, AstroSwapFactoryClient};
 use astroswap_router::{AstroSwapRouter, AstroSwapRouterClient};
+use astroswap_pair::AstroSwapPairClient;
 use astroswap_shared::interfaces::PairClient;
 use rand::Rng;
 use soroban_sdk::{testutils::Address as _, Address, Env};
@@ -29,7 +31,7 @@ impl ConcurrentScenario {
         Self
     }
 
-    /// Setup test environment
+    /// Setup test environment, restoring it from a snapshot when configured
     fn setup_environment(
         &self,
         config: &StressConfig,
@@ -42,6 +44,24 @@ impl ConcurrentScenario {
         AstroSwapRouterClient<'static>,
         Vec<Address>,
     ) {
+        let world = snapshot::load_or_build(config, "concurrent", || self.build_world(config));
+        let factory = AstroSwapFactoryClient::new(&world.env, &world.factory);
+        let router_address = world.router.expect("concurrent world has a router");
+        let router = AstroSwapRouterClient::new(&world.env, &router_address);
+
+        (
+            world.env,
+            world.admin,
+            world.token_manager,
+            world.account_pool,
+            factory,
+            router,
+            world.pairs,
+        )
+    }
+
+    /// Build the test environment from scratch
+    fn build_world(&self, config: &StressConfig) -> World {
         let env = Env::default();
         // Use mock_all_auths_allowing_non_root_auth for contract-to-contract calls (SDK 23)
         env.mock_all_auths_allowing_non_root_auth();
@@ -53,8 +73,9 @@ impl ConcurrentScenario {
         token_manager.create_tokens(&env, &admin, config.num_pairs * 2, 100_000_000_0000000);
 
         // Create account pool
-        let account_pool = AccountPool::new(&env, config.num_accounts);
-        token_manager.distribute(&admin, account_pool.all(), 10_000_000_0000000);
+        let mut account_pool = AccountPool::new(&env, config.num_accounts);
+        account_pool.assign_balances(&config.accounts, 10_000_000_0000000);
+        account_pool.fund(&token_manager);
 
         // Deploy contracts (SDK 23: use WASM bytes directly)
         let pair_wasm_hash = env.deployer().upload_contract_wasm(pair_wasm::WASM);
@@ -94,7 +115,15 @@ impl ConcurrentScenario {
             );
         }
 
-        (env, admin, token_manager, account_pool, factory, router, pair_addresses)
+        World {
+            env,
+            admin,
+            token_manager,
+            account_pool,
+            factory: factory_address,
+            router: Some(router_address),
+            pairs: pair_addresses,
+        }
     }
 
     /// Select operation type based on weights
@@ -113,63 +142,85 @@ impl ConcurrentScenario {
     }
 
     /// Execute swap operation
+    #[allow(clippy::too_many_arguments)]
     fn execute_swap(
         &self,
         env: &Env,
         pair_address: &Address,
         user: &Address,
+        account: &str,
         token_in: &Address,
         amount_in: i128,
         collector: &MetricsCollector,
     ) {
-        let timer = collector.start_operation();
-        let pair_client = PairClient::new(env, pair_address);
+        let timer = collector
+            .start_operation()
+            .for_account(account)
+            .with_budget(env);
+        let pair_client = AstroSwapPairClient::new(env, pair_address);
+        let deadline = env.ledger().timestamp() + 3600;
 
         let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-            pair_client.swap(user, token_in, amount_in, 1)
+            pair_client.try_swap(user, token_in, &amount_in, &1, &deadline)
         }));
 
+        let mut metadata = HashMap::new();
         match result {
-            Ok(Ok(amount_out)) => {
-                let mut metadata = HashMap::new();
+            Ok(Ok(Ok(amount_out))) => {
                 metadata.insert("amount_in".to_string(), amount_in.to_string());
                 metadata.insert("amount_out".to_string(), amount_out.to_string());
+                record_budget(env, &mut metadata);
                 timer.success(OperationType::Swap, metadata);
             }
-            _ => {
-                timer.error(OperationType::Swap, "Swap failed".to_string(), HashMap::new());
+            Ok(failed) => {
+                let message = record_error_code(&failed, &mut metadata, "Swap failed");
+                timer.error(OperationType::Swap, message, metadata);
+            }
+            Err(_) => {
+                timer.error(OperationType::Swap, "Swap failed".to_string(), metadata);
             }
         }
     }
 
     /// Execute add liquidity operation
+    #[allow(clippy::too_many_arguments)]
     fn execute_add_liquidity(
         &self,
         env: &Env,
         pair_address: &Address,
         user: &Address,
+        account: &str,
         amount_0: i128,
         amount_1: i128,
         collector: &MetricsCollector,
     ) {
-        let timer = collector.start_operation();
-        let pair_client = PairClient::new(env, pair_address);
+        let timer = collector
+            .start_operation()
+            .for_account(account)
+            .with_budget(env);
+        let pair_client = AstroSwapPairClient::new(env, pair_address);
+        let deadline = env.ledger().timestamp() + 3600;
 
         let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-            pair_client.deposit(user, amount_0, amount_1, 0, 0)
+            pair_client.try_deposit(user, &amount_0, &amount_1, &0, &0, &deadline)
         }));
 
+        let mut metadata = HashMap::new();
         match result {
-            Ok((_, _, shares)) => {
-                let mut metadata = HashMap::new();
+            Ok(Ok(Ok((_, _, shares)))) => {
                 metadata.insert("shares".to_string(), shares.to_string());
+                record_budget(env, &mut metadata);
                 timer.success(OperationType::AddLiquidity, metadata);
             }
-            _ => {
+            Ok(failed) => {
+                let message = record_error_code(&failed, &mut metadata, "Add liquidity failed");
+                timer.error(OperationType::AddLiquidity, message, metadata);
+            }
+            Err(_) => {
                 timer.error(
                     OperationType::AddLiquidity,
                     "Add liquidity failed".to_string(),
-                    HashMap::new(),
+                    metadata,
                 );
             }
         }
@@ -181,28 +232,38 @@ impl ConcurrentScenario {
         env: &Env,
         pair_address: &Address,
         user: &Address,
+        account: &str,
         shares: i128,
         collector: &MetricsCollector,
     ) {
-        let timer = collector.start_operation();
-        let pair_client = PairClient::new(env, pair_address);
+        let timer = collector
+            .start_operation()
+            .for_account(account)
+            .with_budget(env);
+        let pair_client = AstroSwapPairClient::new(env, pair_address);
+        let deadline = env.ledger().timestamp() + 3600;
 
         let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-            pair_client.withdraw(user, shares, 0, 0)
+            pair_client.try_withdraw(user, &shares, &0, &0, &deadline)
         }));
 
+        let mut metadata = HashMap::new();
         match result {
-            Ok((amount_0, amount_1)) => {
-                let mut metadata = HashMap::new();
+            Ok(Ok(Ok((amount_0, amount_1)))) => {
                 metadata.insert("amount_0".to_string(), amount_0.to_string());
                 metadata.insert("amount_1".to_string(), amount_1.to_string());
+                record_budget(env, &mut metadata);
                 timer.success(OperationType::RemoveLiquidity, metadata);
             }
-            _ => {
+            Ok(failed) => {
+                let message = record_error_code(&failed, &mut metadata, "Remove liquidity failed");
+                timer.error(OperationType::RemoveLiquidity, message, metadata);
+            }
+            Err(_) => {
                 timer.error(
                     OperationType::RemoveLiquidity,
                     "Remove liquidity failed".to_string(),
-                    HashMap::new(),
+                    metadata,
                 );
             }
         }
@@ -217,8 +278,9 @@ impl Default for ConcurrentScenario {
 
 impl StressScenario for ConcurrentScenario {
     fn run(&self, config: &StressConfig, collector: &MetricsCollector) {
-        let (env, _admin, _token_manager, account_pool, _factory, _router, pair_addresses) =
+        let (env, _admin, token_manager, account_pool, _factory, _router, pair_addresses) =
             self.setup_environment(config);
+        account_pool.register(collector);
 
         let test_start = Instant::now();
         let target_duration = std::time::Duration::from_secs(config.duration_seconds);
@@ -226,6 +288,8 @@ impl StressScenario for ConcurrentScenario {
 
         let mut rng = rand::thread_rng();
         let mut operation_count = 0u64;
+        let mut clock = LedgerClock::new(&env, &config.time_warp);
+        let mut entry_sampler = LedgerEntrySampler::new(&config.leak_detection);
 
         // Track LP positions for remove operations (use string keys - Address doesn't impl Hash)
         let mut lp_positions: HashMap<String, i128> = HashMap::new();
@@ -243,7 +307,9 @@ impl StressScenario for ConcurrentScenario {
                 let operation = self.select_operation(concurrent_config);
                 let pair_idx = rng.gen_range(0..pair_addresses.len());
                 let pair_address = &pair_addresses[pair_idx];
-                let user = account_pool.random().clone();
+                let user_idx = account_pool.random_index();
+                let user = account_pool.get_account(user_idx).clone();
+                let account = account_pool.label(user_idx);
 
                 match operation {
                     Operation::Swap => {
@@ -253,7 +319,15 @@ impl StressScenario for ConcurrentScenario {
                         let token_in = if rng.gen_bool(0.5) { &token_0 } else { &token_1 };
                         let amount_in = rng.gen_range(100_0000000..=1_000_0000000);
 
-                        self.execute_swap(&env, pair_address, &user, token_in, amount_in, collector);
+                        self.execute_swap(
+                            &env,
+                            pair_address,
+                            &user,
+                            &account,
+                            token_in,
+                            amount_in,
+                            collector,
+                        );
                     }
                     Operation::AddLiquidity => {
                         let amount_0 = rng.gen_range(10_000_0000000..=100_000_0000000);
@@ -263,6 +337,7 @@ impl StressScenario for ConcurrentScenario {
                             &env,
                             pair_address,
                             &user,
+                            &account,
                             amount_0,
                             amount_1,
                             collector,
@@ -286,6 +361,7 @@ impl StressScenario for ConcurrentScenario {
                                     &env,
                                     pair_address,
                                     &user,
+                                    &account,
                                     remove_shares,
                                     collector,
                                 );
@@ -305,11 +381,20 @@ impl StressScenario for ConcurrentScenario {
 
                 operation_count += 1;
 
+                // Accelerated ledger time between operations (no-op unless enabled)
+                clock.tick(&env);
+
                 if test_start.elapsed() >= target_duration {
                     break;
                 }
             }
 
+            // Top up accounts drained by the previous iteration
+            account_pool.refill_depleted(&token_manager, collector);
+
+            // Ledger entries once per window of operations, for leak detection
+            entry_sampler.sample(&env, collector);
+
             // Rate limiting
             let iteration_duration = iteration_start.elapsed();
             let target_iteration_duration = std::time::Duration::from_secs(1);
@@ -333,6 +418,7 @@ impl StressScenario for ConcurrentScenario {
             collector.total_operations(),
             test_start.elapsed().as_secs_f64()
         );
+        clock.print_summary();
     }
 
     fn name(&self) -> &str {
@@ -0,0 +1,524 @@
+//! Graduation Trading Storm Scenario
+//!
+//! Reproduces the launch pattern of a graduated token: the bridge graduates it
+//! into a fresh pair, then thousands of buys and sells from many accounts hit
+//! that pair straight away. Swaps are quoted in per-ledger batches against the
+//! reserves at the start of the ledger, so later swaps in a batch see a moved
+//! price and trip their slippage limit the way real front-run users do.
+//!
+//! Reports the price path of the new pair, classifies failed swaps as slippage
+//! or other errors, and checks pool health at the end: reserves backed by the
+//! pair's token balances and `k` never below its value at graduation.
+
+use super::StressScenario;
+use crate::config::StressConfig;
+use crate::metrics::taxonomy::contract_error_code;
+use crate::metrics::{record_error_code, MetricsCollector, OperationType};
+use crate::pair_wasm;
+use crate::utils::{record_budget, AccountPool, TokenManager};
+use astroswap_bridge::{AstroSwapBridge, AstroSwapBridgeClient};
+use astroswap_factory::{AstroSwapFactory, AstroSwapFactoryClient};
+use astroswap_pair::AstroSwapPairClient;
+use astroswap_shared::{AstroSwapError, TokenMetadata};
+use astroswap_staking::{AstroSwapStaking, AstroSwapStakingClient};
+use rand::Rng;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address, Env, String as SorobanString,
+};
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Token index of the quote token
+const QUOTE_TOKEN: usize = 0;
+
+/// Price precision (7 decimals)
+const PRICE_PRECISION: i128 = 10_000_000;
+
+/// Result of a single swap
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SwapOutcome {
+    Filled(i128),
+    Slippage,
+    Failed(String),
+}
+
+/// Failed swaps split by cause
+#[derive(Debug, Clone, Default)]
+pub struct FailureBreakdown {
+    pub filled: u32,
+    pub slippage: u32,
+    /// Other failures by error name
+    pub other: BTreeMap<String, u32>,
+}
+
+impl FailureBreakdown {
+    pub fn add(&mut self, outcome: &SwapOutcome) {
+        match outcome {
+            SwapOutcome::Filled(_) => self.filled += 1,
+            SwapOutcome::Slippage => self.slippage += 1,
+            SwapOutcome::Failed(name) => *self.other.entry(name.clone()).or_insert(0) += 1,
+        }
+    }
+
+    pub fn other_total(&self) -> u32 {
+        self.other.values().sum()
+    }
+}
+
+/// State of the graduated pair (token side / quote side)
+#[derive(Debug, Clone, Default)]
+pub struct PoolHealth {
+    pub reserve_token: i128,
+    pub reserve_quote: i128,
+    pub balance_token: i128,
+    pub balance_quote: i128,
+    /// `k` right after the graduation
+    pub k_start: i128,
+}
+
+impl PoolHealth {
+    pub fn k(&self) -> i128 {
+        self.reserve_token.saturating_mul(self.reserve_quote)
+    }
+
+    /// Quote tokens per graduated token (7 decimals)
+    pub fn price(&self) -> i128 {
+        if self.reserve_token == 0 {
+            return 0;
+        }
+        self.reserve_quote.saturating_mul(PRICE_PRECISION) / self.reserve_token
+    }
+
+    /// Reserves are non-empty, backed by the pair's balances, and `k` has
+    /// not shrunk since the graduation (fees only ever grow it)
+    pub fn is_healthy(&self) -> bool {
+        self.reserve_token > 0
+            && self.reserve_quote > 0
+            && self.balance_token >= self.reserve_token
+            && self.balance_quote >= self.reserve_quote
+            && self.k() >= self.k_start
+    }
+}
+
+/// The graduated pair and its two tokens
+struct GraduatedPair {
+    pair: Address,
+    token: Address,
+    quote: Address,
+}
+
+impl GraduatedPair {
+    fn health(&self, env: &Env, k_start: i128) -> PoolHealth {
+        let pair = AstroSwapPairClient::new(env, &self.pair);
+        let (reserve_0, reserve_1) = pair.get_reserves();
+        let (reserve_token, reserve_quote) = if pair.token_0() == self.token {
+            (reserve_0, reserve_1)
+        } else {
+            (reserve_1, reserve_0)
+        };
+        PoolHealth {
+            reserve_token,
+            reserve_quote,
+            balance_token: TokenClient::new(env, &self.token).balance(&self.pair),
+            balance_quote: TokenClient::new(env, &self.quote).balance(&self.pair),
+            k_start,
+        }
+    }
+}
+
+/// A swap quoted at the start of a ledger
+struct PlannedSwap {
+    user_index: usize,
+    token_in: Address,
+    amount_in: i128,
+    min_out: i128,
+}
+
+pub struct GraduationStormScenario;
+
+impl GraduationStormScenario {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Setup test environment: graduate a token and hand the launch-phase
+    /// holdings of it to the trading accounts
+    fn setup_environment(
+        &self,
+        config: &StressConfig,
+        collector: &MetricsCollector,
+    ) -> Option<(Env, TokenManager, AccountPool, GraduatedPair)> {
+        let env = Env::default();
+        // Use mock_all_auths_allowing_non_root_auth for contract-to-contract calls (SDK 23)
+        env.mock_all_auths_allowing_non_root_auth();
+
+        let admin = Address::generate(&env);
+        let launchpad = Address::generate(&env);
+        let storm = &config.graduation_storm;
+
+        let mut token_manager = TokenManager::new();
+        token_manager.create_tokens(&env, &admin, 1, 100_000_000_0000000);
+        let quote = token_manager.get(QUOTE_TOKEN).unwrap().address.clone();
+
+        let mut account_pool = AccountPool::new(&env, config.num_accounts);
+        account_pool.assign_balances(&config.accounts, storm.max_swap_amount * 10);
+        account_pool.fund(&token_manager);
+
+        let pair_wasm_hash = env.deployer().upload_contract_wasm(pair_wasm::WASM);
+        let factory_address = env.register(AstroSwapFactory, ());
+        let factory = AstroSwapFactoryClient::new(&env, &factory_address);
+        factory.initialize(&admin, &pair_wasm_hash, &30);
+
+        let staking_address = env.register(AstroSwapStaking, ());
+        AstroSwapStakingClient::new(&env, &staking_address).initialize(&admin, &quote);
+
+        let bridge_address = env.register(AstroSwapBridge, ());
+        let bridge = AstroSwapBridgeClient::new(&env, &bridge_address);
+        bridge.initialize(
+            &admin,
+            &factory_address,
+            &staking_address,
+            &launchpad,
+            &quote,
+        );
+
+        // Launch-phase holders carry the token at its graduation price
+        let token = env
+            .register_stellar_asset_contract_v2(admin.clone())
+            .address();
+        let token_admin = StellarAssetClient::new(&env, &token);
+        token_admin.mint(&launchpad, &storm.token_liquidity);
+        token_manager.mint(QUOTE_TOKEN, &launchpad, storm.quote_liquidity);
+        for (index, account) in account_pool.all().iter().enumerate() {
+            let holding =
+                account_pool.target_balance(index) * storm.token_liquidity / storm.quote_liquidity;
+            token_admin.mint(account, &holding.max(1));
+        }
+
+        let metadata = TokenMetadata {
+            name: SorobanString::from_str(&env, "Storm Token"),
+            symbol: SorobanString::from_str(&env, "STORM"),
+            decimals: 7,
+            total_supply: storm.token_liquidity,
+            creator: launchpad.clone(),
+            graduation_time: env.ledger().timestamp(),
+        };
+
+        let timer = collector.start_operation().with_budget(&env);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            bridge.try_graduate_token(
+                &launchpad,
+                &token,
+                &storm.token_liquidity,
+                &storm.quote_liquidity,
+                &metadata,
+            )
+        }));
+
+        let mut result_metadata = HashMap::new();
+        match result {
+            Ok(Ok(Ok(graduated))) => {
+                record_budget(&env, &mut result_metadata);
+                timer.success(OperationType::Graduate, result_metadata);
+                let pair = GraduatedPair {
+                    pair: graduated.pair,
+                    token,
+                    quote,
+                };
+                Some((env, token_manager, account_pool, pair))
+            }
+            Ok(failed) => {
+                let message =
+                    record_error_code(&failed, &mut result_metadata, "Graduation rejected");
+                timer.error(OperationType::Graduate, message, result_metadata);
+                None
+            }
+            Err(_) => {
+                timer.error(
+                    OperationType::Graduate,
+                    "Graduation panicked".to_string(),
+                    result_metadata,
+                );
+                None
+            }
+        }
+    }
+
+    /// Quote a ledger's worth of swaps against the current reserves
+    fn plan_ledger(
+        &self,
+        config: &StressConfig,
+        env: &Env,
+        account_pool: &AccountPool,
+        graduated: &GraduatedPair,
+        count: u32,
+    ) -> Vec<PlannedSwap> {
+        let storm = &config.graduation_storm;
+        let pair = AstroSwapPairClient::new(env, &graduated.pair);
+        let price = graduated.health(env, 0).price().max(1);
+        let mut rng = rand::thread_rng();
+
+        (0..count)
+            .filter_map(|_| {
+                let user_index = account_pool.random_index();
+                let user = account_pool.get_account(user_index);
+                let size = rng.gen_range(storm.min_swap_amount..=storm.max_swap_amount);
+
+                // Sizes are in quote terms; sells convert at the current price
+                let (token_in, amount) = if rng.gen_range(0..10_000) < storm.buy_bps {
+                    (graduated.quote.clone(), size)
+                } else {
+                    (graduated.token.clone(), size * PRICE_PRECISION / price)
+                };
+                let amount_in = amount.min(TokenClient::new(env, &token_in).balance(user));
+                if amount_in <= 0 {
+                    return None;
+                }
+
+                let quoted = pair.try_get_amount_out(&amount_in, &token_in).ok()?.ok()?;
+                let slippage_bps = rng.gen_range(storm.min_slippage_bps..=storm.max_slippage_bps);
+                Some(PlannedSwap {
+                    user_index,
+                    token_in,
+                    amount_in,
+                    min_out: quoted * (10_000 - slippage_bps as i128) / 10_000,
+                })
+            })
+            .collect()
+    }
+
+    /// Execute a planned swap
+    fn execute_swap(
+        &self,
+        env: &Env,
+        pair_address: &Address,
+        account_pool: &AccountPool,
+        swap: &PlannedSwap,
+        collector: &MetricsCollector,
+    ) -> SwapOutcome {
+        let user = account_pool.get_account(swap.user_index);
+        let timer = collector
+            .start_operation()
+            .for_account(&account_pool.label(swap.user_index))
+            .with_budget(env);
+        let pair = AstroSwapPairClient::new(env, pair_address);
+        let deadline = env.ledger().timestamp() + 3600;
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            pair.try_swap(
+                user,
+                &swap.token_in,
+                &swap.amount_in,
+                &swap.min_out,
+                &deadline,
+            )
+        }));
+
+        let mut metadata = HashMap::new();
+        metadata.insert("amount_in".to_string(), swap.amount_in.to_string());
+
+        match result {
+            Ok(Ok(Ok(amount_out))) => {
+                metadata.insert("amount_out".to_string(), amount_out.to_string());
+                record_budget(env, &mut metadata);
+                timer.success(OperationType::Swap, metadata);
+                SwapOutcome::Filled(amount_out)
+            }
+            Ok(failed) => {
+                let slippage =
+                    contract_error_code(&failed) == Some(AstroSwapError::SlippageExceeded as u32);
+                let message = record_error_code(&failed, &mut metadata, "Swap failed");
+                let name = message.trim_start_matches("Swap failed: ").to_string();
+                timer.error(OperationType::Swap, message, metadata);
+                if slippage {
+                    SwapOutcome::Slippage
+                } else {
+                    SwapOutcome::Failed(name)
+                }
+            }
+            Err(_) => {
+                timer.error(OperationType::Swap, "Swap panicked".to_string(), metadata);
+                SwapOutcome::Failed("Panic".to_string())
+            }
+        }
+    }
+
+    /// Print up to `samples` evenly spaced points of the price path
+    fn print_price_path(&self, path: &[(u32, i128)], samples: usize) {
+        let step = (path.len() / samples.max(1)).max(1);
+        let points: Vec<String> = path
+            .iter()
+            .step_by(step)
+            .chain(path.last())
+            .map(|(swaps, price)| format!("{}@{}", price, swaps))
+            .collect();
+        println!("  Price path (price@swaps): {}", points.join(" -> "));
+    }
+}
+
+impl Default for GraduationStormScenario {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StressScenario for GraduationStormScenario {
+    fn run(&self, config: &StressConfig, collector: &MetricsCollector) {
+        let Some((env, token_manager, account_pool, graduated)) =
+            self.setup_environment(config, collector)
+        else {
+            println!("Graduation storm aborted: graduation failed");
+            return;
+        };
+        account_pool.register(collector);
+
+        let test_start = Instant::now();
+        let target_duration = std::time::Duration::from_secs(config.duration_seconds);
+        let storm = &config.graduation_storm;
+
+        let start = graduated.health(&env, 0);
+        let k_start = start.k();
+        let mut path = vec![(0u32, start.price())];
+        let mut breakdown = FailureBreakdown::default();
+        let mut executed = 0u32;
+
+        println!(
+            "Starting graduation storm: {} swaps at {} per ledger, opening price {}",
+            storm.num_swaps,
+            storm.swaps_per_ledger,
+            start.price()
+        );
+
+        while executed < storm.num_swaps && test_start.elapsed() < target_duration {
+            let count = storm
+                .swaps_per_ledger
+                .max(1)
+                .min(storm.num_swaps - executed);
+            let planned = self.plan_ledger(config, &env, &account_pool, &graduated, count);
+            executed += count;
+
+            for swap in &planned {
+                let outcome =
+                    self.execute_swap(&env, &graduated.pair, &account_pool, swap, collector);
+                breakdown.add(&outcome);
+            }
+            path.push((executed, graduated.health(&env, k_start).price()));
+
+            account_pool.refill_depleted(&token_manager, collector);
+            env.ledger().with_mut(|li| {
+                li.sequence_number += 1;
+                li.timestamp += 5;
+            });
+        }
+
+        let end = graduated.health(&env, k_start);
+        let (low, high) = path
+            .iter()
+            .fold((i128::MAX, i128::MIN), |(low, high), (_, price)| {
+                (low.min(*price), high.max(*price))
+            });
+
+        println!(
+            "Graduation storm completed: {} swaps planned, {} filled in {:.2}s",
+            executed,
+            breakdown.filled,
+            test_start.elapsed().as_secs_f64()
+        );
+        println!(
+            "  Price: open {} | low {} | high {} | close {}",
+            start.price(),
+            low,
+            high,
+            end.price()
+        );
+        self.print_price_path(&path, 10);
+        println!(
+            "  Failed swaps: {} slippage, {} other",
+            breakdown.slippage,
+            breakdown.other_total()
+        );
+        for (name, count) in &breakdown.other {
+            println!("    {}: {}", name, count);
+        }
+        println!(
+            "  Pool health: {} (reserves {}/{}, k {} -> {})",
+            if end.is_healthy() { "OK" } else { "UNHEALTHY" },
+            end.reserve_token,
+            end.reserve_quote,
+            k_start,
+            end.k()
+        );
+    }
+
+    fn name(&self) -> &str {
+        "Graduation Storm"
+    }
+
+    fn description(&self) -> &str {
+        "Graduates a token, then hammers the new pair with buys and sells from many accounts"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graduation_storm_scenario() {
+        let scenario = GraduationStormScenario::new();
+        let mut config = StressConfig::default();
+        config.duration_seconds = 30;
+        config.num_accounts = 10;
+        config.graduation_storm.num_swaps = 200;
+
+        let collector = MetricsCollector::new();
+        scenario.run(&config, &collector);
+
+        // Graduation plus the swaps
+        assert!(collector.total_operations() > 1);
+        assert!(collector.successful_operations() > 1);
+    }
+
+    #[test]
+    fn test_pool_health() {
+        let health = PoolHealth {
+            reserve_token: 2_000,
+            reserve_quote: 500,
+            balance_token: 2_000,
+            balance_quote: 500,
+            k_start: 1_000_000,
+        };
+        assert!(health.is_healthy());
+        assert_eq!(health.price(), 2_500_000);
+
+        // Reserves not backed by balances
+        let drained = PoolHealth {
+            balance_quote: 499,
+            ..health.clone()
+        };
+        assert!(!drained.is_healthy());
+
+        // k below its graduation value
+        let shrunk = PoolHealth {
+            k_start: 1_000_001,
+            ..health
+        };
+        assert!(!shrunk.is_healthy());
+    }
+
+    #[test]
+    fn test_failure_breakdown() {
+        let mut breakdown = FailureBreakdown::default();
+        breakdown.add(&SwapOutcome::Filled(10));
+        breakdown.add(&SwapOutcome::Slippage);
+        breakdown.add(&SwapOutcome::Slippage);
+        breakdown.add(&SwapOutcome::Failed("InsufficientLiquidity".to_string()));
+
+        assert_eq!(breakdown.filled, 1);
+        assert_eq!(breakdown.slippage, 2);
+        assert_eq!(breakdown.other_total(), 1);
+    }
+}
@@ -0,0 +1,560 @@
+//! MEV Sandwich Scenario
+//!
+//! Wraps victim routes in attacker front-run / back-run swaps to measure how
+//! much value a sandwich can extract under a given per-hop slippage tolerance.
+//! Victims protect every hop the way the aggregator does: each hop's minimum
+//! output is its quoted output less the tolerance, with quotes taken before the
+//! attack. The attacker front-runs each hop with the largest trade that still
+//! lets the victim's hop clear, then sells back right after it.
+//!
+//! Each tolerance level runs on a fresh set of pools so the levels compare
+//! like for like. All tokens start at par, so extracted value is summed across
+//! tokens and reported in basis points of victim volume.
+
+use super::StressScenario;
+use crate::config::StressConfig;
+use crate::metrics::{record_error_code, MetricsCollector, OperationType};
+use crate::pair_wasm;
+use crate::utils::{record_budget, TokenManager};
+use astroswap_factory::{AstroSwapFactory, AstroSwapFactoryClient};
+use astroswap_pair::AstroSwapPairClient;
+use astroswap_router::{AstroSwapRouter, AstroSwapRouterClient};
+use astroswap_shared::{get_amount_out, MIN_TRADE_AMOUNT};
+use rand::Rng;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::TokenClient,
+    Address, Env,
+};
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Basis points denominator
+const BPS: i128 = 10_000;
+
+/// Steps of the front-run size search
+const SEARCH_STEPS: u32 = 64;
+
+/// Sandwich results for one per-hop tolerance level
+#[derive(Debug, Clone, Default)]
+pub struct SandwichStats {
+    pub tolerance_bps: u32,
+    pub victims: u32,
+    /// Victim input across all routes
+    pub victim_volume: i128,
+    /// Hops the attacker sandwiched
+    pub sandwiched_hops: u32,
+    /// Hops left alone because no sandwich was profitable
+    pub skipped_hops: u32,
+    /// Victim hops that failed their minimum output
+    pub reverted: u32,
+    /// Attacker profit across all sandwiches
+    pub extracted: i128,
+    /// Per hop index: (sum of victim loss in bps, hops measured)
+    pub hop_loss: Vec<(u64, u32)>,
+    /// Sum of end-to-end route loss against the quote, in bps
+    pub route_loss_bps: u64,
+    pub max_route_loss_bps: u64,
+}
+
+impl SandwichStats {
+    pub fn new(tolerance_bps: u32, hops: usize) -> Self {
+        Self {
+            tolerance_bps,
+            hop_loss: vec![(0, 0); hops],
+            ..Default::default()
+        }
+    }
+
+    /// Attacker profit in basis points of victim volume
+    pub fn extracted_bps(&self) -> f64 {
+        if self.victim_volume == 0 {
+            return 0.0;
+        }
+        self.extracted as f64 * 10_000.0 / self.victim_volume as f64
+    }
+
+    /// Average victim loss on hop `index` against an unattacked swap of the
+    /// same input, in bps
+    pub fn average_hop_loss_bps(&self, index: usize) -> f64 {
+        match self.hop_loss.get(index) {
+            Some(&(sum, count)) if count > 0 => sum as f64 / count as f64,
+            _ => 0.0,
+        }
+    }
+
+    /// Average end-to-end loss against the route quote, in bps
+    pub fn average_route_loss_bps(&self) -> f64 {
+        if self.victims == 0 {
+            return 0.0;
+        }
+        self.route_loss_bps as f64 / self.victims as f64
+    }
+}
+
+/// Loss of `actual` against `expected` in bps (0 if nothing was lost)
+pub fn loss_bps(expected: i128, actual: i128) -> u64 {
+    if expected <= 0 || actual >= expected {
+        return 0;
+    }
+    ((expected - actual) * BPS / expected) as u64
+}
+
+/// Outcome of a sandwich simulated against reserves `(reserve_in, reserve_out)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SandwichPlan {
+    pub front_run: i128,
+    pub victim_out: i128,
+    pub profit: i128,
+}
+
+/// Simulate front-running a victim swap of `victim_in` with `front_run`,
+/// then selling the proceeds back. None if any leg can't be priced.
+pub fn simulate_sandwich(
+    front_run: i128,
+    victim_in: i128,
+    reserve_in: i128,
+    reserve_out: i128,
+    fee_bps: u32,
+) -> Option<SandwichPlan> {
+    let front_out = get_amount_out(front_run, reserve_in, reserve_out, fee_bps).ok()?;
+    let (reserve_in, reserve_out) = (reserve_in + front_run, reserve_out - front_out);
+    let victim_out = get_amount_out(victim_in, reserve_in, reserve_out, fee_bps).ok()?;
+    let (reserve_in, reserve_out) = (reserve_in + victim_in, reserve_out - victim_out);
+    let back_out = get_amount_out(front_out, reserve_out, reserve_in, fee_bps).ok()?;
+    Some(SandwichPlan {
+        front_run,
+        victim_out,
+        profit: back_out - front_run,
+    })
+}
+
+/// Largest front-run up to `capital` that keeps the victim's output at or
+/// above `min_out`, if it is profitable
+pub fn plan_sandwich(
+    victim_in: i128,
+    min_out: i128,
+    reserve_in: i128,
+    reserve_out: i128,
+    fee_bps: u32,
+    capital: i128,
+) -> Option<SandwichPlan> {
+    let clears = |front_run: i128| {
+        simulate_sandwich(front_run, victim_in, reserve_in, reserve_out, fee_bps)
+            .filter(|plan| plan.victim_out >= min_out)
+    };
+
+    let (mut low, mut high) = (0i128, capital);
+    if clears(high).is_some() {
+        low = high;
+    } else {
+        for _ in 0..SEARCH_STEPS {
+            if high - low <= 1 {
+                break;
+            }
+            let mid = low + (high - low) / 2;
+            if clears(mid).is_some() {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+    }
+
+    if low < MIN_TRADE_AMOUNT {
+        return None;
+    }
+    clears(low).filter(|plan| plan.profit > 0)
+}
+
+/// Pools chained through `tokens`, plus the two trading accounts
+struct SandwichWorld {
+    env: Env,
+    victim: Address,
+    attacker: Address,
+    tokens: Vec<Address>,
+    pairs: Vec<Address>,
+}
+
+pub struct MevSandwichScenario;
+
+impl MevSandwichScenario {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Build a chain of `hops` pools at par
+    fn build_world(&self, config: &StressConfig) -> SandwichWorld {
+        let env = Env::default();
+        // Use mock_all_auths_allowing_non_root_auth for contract-to-contract calls (SDK 23)
+        env.mock_all_auths_allowing_non_root_auth();
+
+        let admin = Address::generate(&env);
+        let victim = Address::generate(&env);
+        let attacker = Address::generate(&env);
+        let sandwich = &config.mev_sandwich;
+        let hops = sandwich.hops.max(1);
+
+        let mut token_manager = TokenManager::new();
+        token_manager.create_tokens(&env, &admin, hops + 1, sandwich.pool_liquidity * 2);
+        token_manager.mint_all(
+            &victim,
+            sandwich.max_victim_amount * sandwich.victims_per_level as i128,
+        );
+        token_manager.mint_all(&attacker, sandwich.attacker_capital);
+
+        let pair_wasm_hash = env.deployer().upload_contract_wasm(pair_wasm::WASM);
+        let factory_address = env.register(AstroSwapFactory, ());
+        let factory = AstroSwapFactoryClient::new(&env, &factory_address);
+        factory.initialize(&admin, &pair_wasm_hash, &30);
+
+        let router_address = env.register(AstroSwapRouter, ());
+        let router = AstroSwapRouterClient::new(&env, &router_address);
+        router.initialize(&factory_address, &admin);
+
+        let tokens: Vec<Address> = (0..=hops as usize)
+            .map(|i| token_manager.get(i).unwrap().address.clone())
+            .collect();
+        let pairs = tokens
+            .windows(2)
+            .map(|window| {
+                let pair = factory.create_pair(&window[0], &window[1]);
+                router.add_liquidity(
+                    &admin,
+                    &window[0],
+                    &window[1],
+                    &sandwich.pool_liquidity,
+                    &sandwich.pool_liquidity,
+                    &0,
+                    &0,
+                    &(env.ledger().timestamp() + 3600),
+                );
+                pair
+            })
+            .collect();
+
+        SandwichWorld {
+            env,
+            victim,
+            attacker,
+            tokens,
+            pairs,
+        }
+    }
+
+    /// Execute a swap on `pair`, returning the output if it cleared
+    #[allow(clippy::too_many_arguments)]
+    fn swap(
+        &self,
+        env: &Env,
+        pair: &Address,
+        user: &Address,
+        role: &str,
+        token_in: &Address,
+        amount_in: i128,
+        min_out: i128,
+        collector: &MetricsCollector,
+    ) -> Option<i128> {
+        let timer = collector.start_operation().with_budget(env);
+        let pair_client = AstroSwapPairClient::new(env, pair);
+        let deadline = env.ledger().timestamp() + 3600;
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            pair_client.try_swap(user, token_in, &amount_in, &min_out, &deadline)
+        }));
+
+        let mut metadata = HashMap::new();
+        metadata.insert("role".to_string(), role.to_string());
+        metadata.insert("amount_in".to_string(), amount_in.to_string());
+
+        match result {
+            Ok(Ok(Ok(amount_out))) => {
+                metadata.insert("amount_out".to_string(), amount_out.to_string());
+                record_budget(env, &mut metadata);
+                timer.success(OperationType::Swap, metadata);
+                Some(amount_out)
+            }
+            Ok(failed) => {
+                let message = record_error_code(&failed, &mut metadata, "Swap failed");
+                timer.error(OperationType::Swap, message, metadata);
+                None
+            }
+            Err(_) => {
+                timer.error(OperationType::Swap, "Swap panicked".to_string(), metadata);
+                None
+            }
+        }
+    }
+
+    /// Reserves of `pair` ordered as (reserve of `token_in`, reserve of the other token)
+    fn reserves(&self, env: &Env, pair: &Address, token_in: &Address) -> (i128, i128, u32) {
+        let pair_client = AstroSwapPairClient::new(env, pair);
+        let (reserve_0, reserve_1) = pair_client.get_reserves();
+        let fee_bps = pair_client.fee_bps();
+        if &pair_client.token_0() == token_in {
+            (reserve_0, reserve_1, fee_bps)
+        } else {
+            (reserve_1, reserve_0, fee_bps)
+        }
+    }
+
+    /// Run one victim route through the pools, sandwiching every hop
+    fn attack_route(
+        &self,
+        world: &SandwichWorld,
+        amount_in: i128,
+        reverse: bool,
+        stats: &mut SandwichStats,
+        collector: &MetricsCollector,
+    ) {
+        let env = &world.env;
+        let tolerance = stats.tolerance_bps as i128;
+
+        // (pair, token_in, token_out) in route order
+        let mut route: Vec<(&Address, &Address, &Address)> = world
+            .pairs
+            .iter()
+            .zip(world.tokens.windows(2))
+            .map(|(pair, window)| (pair, &window[0], &window[1]))
+            .collect();
+        if reverse {
+            route = route
+                .into_iter()
+                .rev()
+                .map(|(pair, token_a, token_b)| (pair, token_b, token_a))
+                .collect();
+        }
+
+        // Quote the whole route before anyone moves the pools
+        let mut min_outs = Vec::with_capacity(route.len());
+        let mut quoted = amount_in;
+        for (pair, token_in, _) in &route {
+            let (reserve_in, reserve_out, fee_bps) = self.reserves(env, pair, token_in);
+            quoted = get_amount_out(quoted, reserve_in, reserve_out, fee_bps).unwrap_or(0);
+            min_outs.push(quoted * (BPS - tolerance) / BPS);
+        }
+        // `quoted` is now the route's expected output
+
+        stats.victims += 1;
+        stats.victim_volume += amount_in;
+
+        let mut current = amount_in;
+        for (index, (pair, token_in, token_out)) in route.iter().enumerate() {
+            let (reserve_in, reserve_out, fee_bps) = self.reserves(env, pair, token_in);
+            let clean_out = get_amount_out(current, reserve_in, reserve_out, fee_bps).unwrap_or(0);
+
+            let plan = plan_sandwich(
+                current,
+                min_outs[index],
+                reserve_in,
+                reserve_out,
+                fee_bps,
+                TokenClient::new(env, token_in).balance(&world.attacker),
+            );
+            let front_out = plan.and_then(|plan| {
+                self.swap(
+                    env,
+                    pair,
+                    &world.attacker,
+                    "attacker",
+                    token_in,
+                    plan.front_run,
+                    0,
+                    collector,
+                )
+            });
+            match front_out {
+                Some(_) => stats.sandwiched_hops += 1,
+                None => stats.skipped_hops += 1,
+            }
+
+            let victim_out = self.swap(
+                env,
+                pair,
+                &world.victim,
+                "victim",
+                token_in,
+                current,
+                min_outs[index],
+                collector,
+            );
+
+            // Unwind the front-run whether or not the victim cleared
+            if let (Some(plan), Some(front_out)) = (plan, front_out) {
+                if let Some(back_out) = self.swap(
+                    env,
+                    pair,
+                    &world.attacker,
+                    "attacker",
+                    token_out,
+                    front_out,
+                    0,
+                    collector,
+                ) {
+                    stats.extracted += back_out - plan.front_run;
+                }
+            }
+
+            match victim_out {
+                Some(out) => {
+                    let hop = &mut stats.hop_loss[index];
+                    hop.0 += loss_bps(clean_out, out);
+                    hop.1 += 1;
+                    current = out;
+                }
+                None => {
+                    stats.reverted += 1;
+                    return;
+                }
+            }
+        }
+
+        let route_loss = loss_bps(quoted, current);
+        stats.route_loss_bps += route_loss;
+        stats.max_route_loss_bps = stats.max_route_loss_bps.max(route_loss);
+    }
+
+    /// Simulate `victims_per_level` routes at one tolerance level
+    fn run_level(
+        &self,
+        config: &StressConfig,
+        tolerance_bps: u32,
+        collector: &MetricsCollector,
+    ) -> SandwichStats {
+        let sandwich = &config.mev_sandwich;
+        let world = self.build_world(config);
+        let mut stats = SandwichStats::new(tolerance_bps, world.pairs.len());
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..sandwich.victims_per_level {
+            let amount_in = rng.gen_range(sandwich.min_victim_amount..=sandwich.max_victim_amount);
+            self.attack_route(&world, amount_in, rng.gen_bool(0.5), &mut stats, collector);
+
+            world.env.ledger().with_mut(|li| {
+                li.sequence_number += 1;
+                li.timestamp += 5;
+            });
+        }
+        stats
+    }
+
+    fn print_summary(&self, levels: &[SandwichStats]) {
+        println!("Sandwich results per hop tolerance:");
+        println!("  Tolerance | Victims | Sandwiched | Skipped | Reverted | Extracted | Route Loss (avg/max) | Hop Loss");
+        for stats in levels {
+            let hop_loss: Vec<String> = (0..stats.hop_loss.len())
+                .map(|index| format!("{:.1}", stats.average_hop_loss_bps(index)))
+                .collect();
+            println!(
+                "  {:>6} bps | {:>7} | {:>10} | {:>7} | {:>8} | {:>5.1} bps | {:>8.1} / {:>4} bps | {} bps",
+                stats.tolerance_bps,
+                stats.victims,
+                stats.sandwiched_hops,
+                stats.skipped_hops,
+                stats.reverted,
+                stats.extracted_bps(),
+                stats.average_route_loss_bps(),
+                stats.max_route_loss_bps,
+                hop_loss.join(" / ")
+            );
+        }
+    }
+}
+
+impl Default for MevSandwichScenario {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StressScenario for MevSandwichScenario {
+    fn run(&self, config: &StressConfig, collector: &MetricsCollector) {
+        let test_start = Instant::now();
+        let target_duration = std::time::Duration::from_secs(config.duration_seconds);
+        let sandwich = &config.mev_sandwich;
+
+        println!(
+            "Starting MEV sandwich simulation: {} victims x {} tolerance levels, {} hops",
+            sandwich.victims_per_level,
+            sandwich.tolerance_levels_bps.len(),
+            sandwich.hops.max(1)
+        );
+
+        let mut levels = Vec::new();
+        for &tolerance_bps in &sandwich.tolerance_levels_bps {
+            if test_start.elapsed() >= target_duration {
+                println!("  Duration reached, skipping remaining tolerance levels");
+                break;
+            }
+            levels.push(self.run_level(config, tolerance_bps.min(9_999), collector));
+        }
+
+        println!(
+            "MEV sandwich simulation completed: {} levels in {:.2}s",
+            levels.len(),
+            test_start.elapsed().as_secs_f64()
+        );
+        self.print_summary(&levels);
+    }
+
+    fn name(&self) -> &str {
+        "MEV Sandwich"
+    }
+
+    fn description(&self) -> &str {
+        "Sandwiches victim routes to measure extractable value per hop slippage tolerance"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mev_sandwich_levels() {
+        let scenario = MevSandwichScenario::new();
+        let mut config = StressConfig::default();
+        config.mev_sandwich.victims_per_level = 10;
+
+        let collector = MetricsCollector::new();
+        for tolerance_bps in [10, 300] {
+            let stats = scenario.run_level(&config, tolerance_bps, &collector);
+
+            assert_eq!(stats.victims, 10);
+            // The attacker never pushes a victim past its minimum output
+            assert_eq!(stats.reverted, 0);
+            assert!(stats.max_route_loss_bps <= tolerance_bps as u64);
+        }
+        assert!(collector.total_operations() > 0);
+    }
+
+    #[test]
+    fn test_plan_sandwich() {
+        let reserve = 1_000_000_0000000;
+        let victim_in = 10_000_0000000;
+        let clean_out = get_amount_out(victim_in, reserve, reserve, 30).unwrap();
+
+        // No slack: nothing to extract
+        assert_eq!(
+            plan_sandwich(victim_in, clean_out, reserve, reserve, 30, reserve),
+            None
+        );
+
+        // 3% slack: the front-run uses it and the victim still clears
+        let min_out = clean_out * 9_700 / 10_000;
+        let plan = plan_sandwich(victim_in, min_out, reserve, reserve, 30, reserve).unwrap();
+        assert!(plan.victim_out >= min_out);
+        assert!(plan.profit > 0);
+        assert!(
+            simulate_sandwich(plan.front_run + 1_000_000, victim_in, reserve, reserve, 30)
+                .is_some_and(|bigger| bigger.victim_out < min_out)
+        );
+    }
+
+    #[test]
+    fn test_loss_bps() {
+        assert_eq!(loss_bps(10_000, 9_900), 100);
+        assert_eq!(loss_bps(10_000, 10_100), 0);
+        assert_eq!(loss_bps(0, 5), 0);
+    }
+}
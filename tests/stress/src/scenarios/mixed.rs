@@ -0,0 +1,122 @@
+//! Mixed Workload Scenario
+//!
+//! Runs the weighted scenarios of `StressConfig::mix` interleaved in a single
+//! run. Each component runs on its own thread (with its own `Env`) for the full
+//! duration, carrying its weight's share of the configured load, and records
+//! into a collector scoped with its scenario label so the report can break the
+//! run down per scenario as well as in total.
+
+use super::StressScenario;
+use crate::config::{StressConfig, WeightedScenario};
+use crate::metrics::MetricsCollector;
+
+pub struct MixedWorkloadScenario;
+
+impl MixedWorkloadScenario {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Share of the total load for each component of the mix
+    pub fn shares(mix: &[WeightedScenario]) -> Vec<f64> {
+        let total: u32 = mix.iter().map(|w| w.weight).sum();
+        mix.iter()
+            .map(|w| {
+                if total == 0 {
+                    0.0
+                } else {
+                    w.weight as f64 / total as f64
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for MixedWorkloadScenario {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StressScenario for MixedWorkloadScenario {
+    fn run(&self, config: &StressConfig, collector: &MetricsCollector) {
+        if config.mix.is_empty() {
+            println!("Mixed workload skipped: no scenarios in mix");
+            return;
+        }
+
+        let shares = Self::shares(&config.mix);
+        println!("Starting mixed workload for {} seconds:", config.duration_seconds);
+        for (weighted, share) in config.mix.iter().zip(&shares) {
+            println!("  {:>14}: {:.1}%", weighted.scenario.as_str(), share * 100.0);
+        }
+
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = config
+                .mix
+                .iter()
+                .zip(&shares)
+                .filter_map(|(weighted, &share)| {
+                    let scenario = super::build(weighted.scenario)?;
+                    let label = weighted.scenario.as_str().to_string();
+                    let scaled = config.scaled(share);
+                    let scoped = collector.scoped(&label);
+                    let handle = scope.spawn(move || scenario.run(&scaled, &scoped));
+                    Some((label, handle))
+                })
+                .collect();
+
+            for (label, handle) in handles {
+                if handle.join().is_err() {
+                    println!("  {} aborted with a panic", label);
+                }
+            }
+        });
+
+        println!(
+            "Mixed workload completed: {} operations across {} scenarios",
+            collector.total_operations(),
+            collector.scenario_labels().len()
+        );
+    }
+
+    fn name(&self) -> &str {
+        "Mixed Workload"
+    }
+
+    fn description(&self) -> &str {
+        "Runs weighted scenarios interleaved with per-scenario breakdowns"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Scenario;
+
+    #[test]
+    fn test_mixed_workload() {
+        let scenario = MixedWorkloadScenario::new();
+        let mut config = StressConfig::default();
+        config.duration_seconds = 5;
+        config.target_tps = 10;
+        config.num_accounts = 5;
+        config.mix = WeightedScenario::parse_mix("swap-load:70,pool-stress:30").unwrap();
+
+        let collector = MetricsCollector::new();
+        scenario.run(&config, &collector);
+
+        assert!(collector.total_operations() > 0);
+        for label in collector.scenario_labels() {
+            assert!(label == Scenario::SwapLoad.as_str() || label == Scenario::PoolStress.as_str());
+        }
+    }
+
+    #[test]
+    fn test_shares() {
+        let mix = WeightedScenario::parse_mix("swap-load:70,pool-stress:20,concurrent:10").unwrap();
+        let shares = MixedWorkloadScenario::shares(&mix);
+        assert!((shares[0] - 0.7).abs() < 1e-9);
+        assert!((shares[2] - 0.1).abs() < 1e-9);
+    }
+}
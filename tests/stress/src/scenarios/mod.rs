@@ -6,8 +6,15 @@ pub mod swap_load;
 pub mod pool_stress;
 pub mod router_paths;
 pub mod concurrent;
+pub mod storage_growth;
+pub mod oracle_twap;
+pub mod bank_run;
+pub mod graduation_storm;
+pub mod mev_sandwich;
+pub mod router_vs_aggregator;
+pub mod mixed;
 
-use crate::config::StressConfig;
+use crate::config::{Scenario, StressConfig};
 use crate::metrics::MetricsCollector;
 
 /// Trait for stress test scenarios
@@ -22,7 +29,31 @@ pub trait StressScenario {
     fn description(&self) -> &str;
 }
 
+/// Build the scenario implementation for a scenario kind (None for `All`)
+pub fn build(scenario: Scenario) -> Option<Box<dyn StressScenario + Send + Sync>> {
+    match scenario {
+        Scenario::SwapLoad => Some(Box::new(SwapLoadScenario::new())),
+        Scenario::PoolStress => Some(Box::new(PoolStressScenario::new())),
+        Scenario::RouterPaths => Some(Box::new(RouterPathsScenario::new())),
+        Scenario::Concurrent => Some(Box::new(ConcurrentScenario::new())),
+        Scenario::StorageGrowth => Some(Box::new(StorageGrowthScenario::new())),
+        Scenario::OracleTwap => Some(Box::new(OracleTwapScenario::new())),
+        Scenario::BankRun => Some(Box::new(BankRunScenario::new())),
+        Scenario::GraduationStorm => Some(Box::new(GraduationStormScenario::new())),
+        Scenario::MevSandwich => Some(Box::new(MevSandwichScenario::new())),
+        Scenario::RouterVsAggregator => Some(Box::new(RouterVsAggregatorScenario::new())),
+        Scenario::All => None,
+    }
+}
+
 pub use swap_load::SwapLoadScenario;
 pub use pool_stress::PoolStressScenario;
 pub use router_paths::RouterPathsScenario;
 pub use concurrent::ConcurrentScenario;
+pub use storage_growth::StorageGrowthScenario;
+pub use oracle_twap::OracleTwapScenario;
+pub use bank_run::BankRunScenario;
+pub use graduation_storm::GraduationStormScenario;
+pub use mev_sandwich::MevSandwichScenario;
+pub use router_vs_aggregator::RouterVsAggregatorScenario;
+pub use mixed::MixedWorkloadScenario;
@@ -0,0 +1,393 @@
+//! Oracle TWAP Consistency Scenario
+//!
+//! Pushes high-frequency price updates into the oracle while advancing ledger
+//! time, and checks every `get_twap` answer against an off-chain reference
+//! computed over the same observation series. Answers diverging by more than
+//! `tolerance_bps`, or rejected while the reference has an answer, are recorded
+//! as errors; update throughput is tracked through the collector.
+
+use super::StressScenario;
+use crate::config::StressConfig;
+use crate::metrics::{record_error_code, MetricsCollector, OperationType};
+use crate::utils::LedgerEntrySampler;
+use astroswap_oracle::{AstroSwapOracle, AstroSwapOracleClient};
+use rand::Rng;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env, String as SorobanString,
+};
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// TWAP window bounds in seconds (mirror the oracle's MIN/MAX_TWAP_WINDOW)
+pub const MIN_TWAP_WINDOW: u64 = 300;
+pub const MAX_TWAP_WINDOW: u64 = 86400;
+
+/// Starting price of every feed (7 decimals)
+const INITIAL_PRICE: i128 = 1_0000000;
+
+/// Off-chain TWAP reference over the full, unbounded observation series.
+///
+/// Uses the oracle's definition: each observation accumulates
+/// `price * seconds since the previous observation`, and the TWAP is the
+/// cumulative difference between the observation just before the window start
+/// and the latest observation, divided by the elapsed time.
+#[derive(Debug, Clone, Default)]
+pub struct TwapReference {
+    /// (timestamp, cumulative price)
+    observations: Vec<(u64, i128)>,
+}
+
+impl TwapReference {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a price update at `timestamp`
+    pub fn record(&mut self, timestamp: u64, price: i128) {
+        let cumulative = match self.observations.last() {
+            None => price,
+            Some(&(last_time, last_cumulative)) => {
+                last_cumulative + price * timestamp.saturating_sub(last_time) as i128
+            }
+        };
+        self.observations.push((timestamp, cumulative));
+    }
+
+    pub fn len(&self) -> usize {
+        self.observations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.observations.is_empty()
+    }
+
+    /// TWAP over `[now - window, now]`, or None where the oracle must reject
+    pub fn twap(&self, now: u64, window: u64) -> Option<i128> {
+        if !(MIN_TWAP_WINDOW..=MAX_TWAP_WINDOW).contains(&window) || self.observations.len() < 2 {
+            return None;
+        }
+
+        let window_start = now.saturating_sub(window);
+        let &(end_time, end_cumulative) = self.observations.last()?;
+
+        // Last observation before the window start, or the first one if none
+        let start_index = self
+            .observations
+            .iter()
+            .position(|&(time, _)| time >= window_start)
+            .map(|i| i.saturating_sub(1))
+            .unwrap_or(self.observations.len() - 1);
+        let (start_time, start_cumulative) = self.observations[start_index];
+
+        // A series that stopped before `now` must still start within the max window
+        if end_time < now && start_time < window_start.saturating_sub(MAX_TWAP_WINDOW) {
+            return None;
+        }
+
+        let elapsed = end_time.saturating_sub(start_time);
+        if elapsed == 0 {
+            return None;
+        }
+        Some((end_cumulative - start_cumulative) / elapsed as i128)
+    }
+}
+
+/// Absolute divergence between two prices in basis points of the reference
+pub fn divergence_bps(actual: i128, reference: i128) -> u64 {
+    if reference == 0 {
+        return if actual == 0 { 0 } else { u64::MAX };
+    }
+    let diff = (actual - reference).unsigned_abs();
+    (diff.saturating_mul(10_000) / reference.unsigned_abs()) as u64
+}
+
+/// Per-feed state tracked by the scenario
+struct Feed {
+    token: Address,
+    price: i128,
+    reference: TwapReference,
+}
+
+pub struct OracleTwapScenario;
+
+impl OracleTwapScenario {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Setup test environment
+    fn setup_environment(&self, config: &StressConfig) -> (Env, AstroSwapOracleClient<'static>, Vec<Feed>) {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().with_mut(|li| li.timestamp = 1_700_000_000);
+
+        let admin = Address::generate(&env);
+        let oracle_address = env.register(AstroSwapOracle, ());
+        let oracle = AstroSwapOracleClient::new(&env, &oracle_address);
+        oracle.initialize(&admin, &3600);
+
+        // The oracle only keys feeds by address, so no token contracts are needed
+        let feeds = (0..config.oracle_twap.num_tokens.max(1))
+            .map(|_| Feed {
+                token: Address::generate(&env),
+                price: INITIAL_PRICE,
+                reference: TwapReference::new(),
+            })
+            .collect();
+
+        (env, oracle, feeds)
+    }
+
+    /// Push one price update and mirror it into the reference
+    fn update_price(
+        &self,
+        env: &Env,
+        oracle: &AstroSwapOracleClient,
+        feed: &mut Feed,
+        price: i128,
+        collector: &MetricsCollector,
+    ) {
+        let timer = collector.start_operation();
+        let source = SorobanString::from_str(env, "stress");
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            oracle.try_update_price(&feed.token, &price, &7, &source)
+        }));
+
+        let mut metadata = HashMap::new();
+        match result {
+            Ok(Ok(Ok(()))) => {
+                feed.price = price;
+                feed.reference.record(env.ledger().timestamp(), price);
+                timer.success(OperationType::OracleUpdate, metadata);
+            }
+            Ok(failed) => {
+                let message = record_error_code(&failed, &mut metadata, "Price update rejected");
+                timer.error(OperationType::OracleUpdate, message, metadata);
+            }
+            Err(_) => {
+                timer.error(
+                    OperationType::OracleUpdate,
+                    "Price update panicked".to_string(),
+                    metadata,
+                );
+            }
+        }
+    }
+
+    /// Query the TWAP and compare it with the reference. Returns the divergence in
+    /// basis points when both sides produced a value.
+    fn check_twap(
+        &self,
+        env: &Env,
+        oracle: &AstroSwapOracleClient,
+        feed: &Feed,
+        window: u64,
+        tolerance_bps: u32,
+        collector: &MetricsCollector,
+    ) -> Option<u64> {
+        let expected = feed.reference.twap(env.ledger().timestamp(), window);
+        let timer = collector.start_operation();
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            oracle.try_get_twap(&feed.token, &window)
+        }));
+
+        let mut metadata = HashMap::new();
+        metadata.insert("window".to_string(), window.to_string());
+        metadata.insert("observations".to_string(), feed.reference.len().to_string());
+        if let Some(expected) = expected {
+            metadata.insert("reference".to_string(), expected.to_string());
+        }
+
+        let mut rejection = "TWAP rejected".to_string();
+        let actual = match result {
+            Ok(Ok(Ok(twap))) => Some(twap),
+            Ok(failed) => {
+                rejection = record_error_code(&failed, &mut metadata, "TWAP rejected");
+                None
+            }
+            Err(_) => {
+                timer.error(OperationType::TwapQuery, "TWAP query panicked".to_string(), metadata);
+                return None;
+            }
+        };
+
+        match (actual, expected) {
+            (Some(actual), Some(expected)) => {
+                let divergence = divergence_bps(actual, expected);
+                metadata.insert("twap".to_string(), actual.to_string());
+                metadata.insert("divergence_bps".to_string(), divergence.to_string());
+                if divergence > tolerance_bps as u64 {
+                    timer.error(OperationType::TwapQuery, "TWAP divergence".to_string(), metadata);
+                } else {
+                    timer.success(OperationType::TwapQuery, metadata);
+                }
+                Some(divergence)
+            }
+            (None, None) => {
+                // Both agree there is no answer yet (e.g. a single observation)
+                timer.success(OperationType::TwapQuery, metadata);
+                None
+            }
+            (Some(actual), None) => {
+                metadata.insert("twap".to_string(), actual.to_string());
+                timer.error(
+                    OperationType::TwapQuery,
+                    "TWAP returned without reference".to_string(),
+                    metadata,
+                );
+                None
+            }
+            (None, Some(_)) => {
+                timer.error(OperationType::TwapQuery, rejection, metadata);
+                None
+            }
+        }
+    }
+}
+
+impl Default for OracleTwapScenario {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StressScenario for OracleTwapScenario {
+    fn run(&self, config: &StressConfig, collector: &MetricsCollector) {
+        let (env, oracle, mut feeds) = self.setup_environment(config);
+
+        let test_start = Instant::now();
+        let target_duration = std::time::Duration::from_secs(config.duration_seconds);
+        let oracle_config = &config.oracle_twap;
+
+        let mut rng = rand::thread_rng();
+        let mut updates = 0u64;
+        let mut queries = 0u64;
+        let mut divergences = 0u64;
+        let mut max_divergence = 0u64;
+        let mut entry_sampler = LedgerEntrySampler::new(&config.leak_detection);
+
+        let min_interval = oracle_config.min_update_interval_secs.max(1);
+        let max_interval = oracle_config.max_update_interval_secs.max(min_interval);
+        let volatility = oracle_config.volatility_bps.min(5_000) as i64;
+
+        println!(
+            "Starting oracle TWAP test: {} feeds, {} updates/s, windows {:?}",
+            feeds.len(),
+            oracle_config.updates_per_second,
+            oracle_config.twap_windows
+        );
+
+        while test_start.elapsed() < target_duration {
+            let iteration_start = Instant::now();
+
+            for _ in 0..oracle_config.updates_per_second {
+                // Ledger time must move between observations for the TWAP to be defined
+                let interval = rng.gen_range(min_interval..=max_interval);
+                env.ledger().with_mut(|li| li.timestamp += interval);
+
+                let feed_idx = rng.gen_range(0..feeds.len());
+                let feed = &mut feeds[feed_idx];
+                let step_bps = rng.gen_range(-volatility..=volatility) as i128;
+                let price = (feed.price + feed.price * step_bps / 10_000).max(1);
+
+                self.update_price(&env, &oracle, feed, price, collector);
+                updates += 1;
+            }
+
+            for feed in &feeds {
+                for &window in &oracle_config.twap_windows {
+                    queries += 1;
+                    if let Some(divergence) = self.check_twap(
+                        &env,
+                        &oracle,
+                        feed,
+                        window,
+                        oracle_config.tolerance_bps,
+                        collector,
+                    ) {
+                        max_divergence = max_divergence.max(divergence);
+                        if divergence > oracle_config.tolerance_bps as u64 {
+                            divergences += 1;
+                        }
+                    }
+                }
+            }
+
+            // Ledger entries once per window of operations, for leak detection
+            entry_sampler.sample(&env, collector);
+
+            // Rate limiting
+            let iteration_duration = iteration_start.elapsed();
+            if iteration_duration < std::time::Duration::from_secs(1) {
+                std::thread::sleep(std::time::Duration::from_secs(1) - iteration_duration);
+            }
+        }
+
+        let elapsed = test_start.elapsed().as_secs_f64();
+        println!(
+            "Oracle TWAP test completed: {} updates ({:.2}/s), {} TWAP queries in {:.2}s",
+            updates,
+            updates as f64 / elapsed.max(f64::EPSILON),
+            queries,
+            elapsed
+        );
+        println!(
+            "TWAP divergences beyond {} bps: {} (max {} bps)",
+            oracle_config.tolerance_bps, divergences, max_divergence
+        );
+    }
+
+    fn name(&self) -> &str {
+        "Oracle TWAP"
+    }
+
+    fn description(&self) -> &str {
+        "Validates oracle TWAP against an off-chain reference under high-frequency updates"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_oracle_twap_scenario() {
+        let scenario = OracleTwapScenario::new();
+        let mut config = StressConfig::default();
+        config.duration_seconds = 3;
+        config.oracle_twap.updates_per_second = 20;
+
+        let collector = MetricsCollector::new();
+        scenario.run(&config, &collector);
+
+        assert!(collector.total_operations() > 0);
+    }
+
+    #[test]
+    fn test_twap_reference() {
+        let mut reference = TwapReference::new();
+        assert_eq!(reference.twap(1_000, 300), None);
+
+        reference.record(0, 100);
+        reference.record(100, 200); // cumulative 100 + 200 * 100
+        reference.record(400, 300); // cumulative 20_100 + 300 * 300
+
+        // Window starts at 100: bracketed by the observations at 0 and 400
+        assert_eq!(reference.twap(400, 300), Some((110_100 - 100) / 400));
+        // Window before the series: first observation is the start
+        assert_eq!(reference.twap(400, 400), Some((110_100 - 100) / 400));
+        // Windows outside the oracle bounds are rejected
+        assert_eq!(reference.twap(400, 60), None);
+        assert_eq!(reference.twap(400, MAX_TWAP_WINDOW + 1), None);
+    }
+
+    #[test]
+    fn test_divergence_bps() {
+        assert_eq!(divergence_bps(1_0010000, 1_0000000), 10);
+        assert_eq!(divergence_bps(9_990000, 1_0000000), 10);
+        assert_eq!(divergence_bps(5, 5), 0);
+    }
+}
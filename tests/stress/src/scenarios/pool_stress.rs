@@ -4,10 +4,12 @@
 
 use super::StressScenario;
 use crate::config::StressConfig;
-use crate::metrics::{MetricsCollector, OperationType};
-use crate::utils::{AccountPool, TokenManager};
+use crate::metrics::{record_error_code, MetricsCollector, OperationType};
+use crate::utils::snapshot::{self, World};
+use crate::utils::{record_budget, AccountPool, LedgerClock, LedgerEntrySampler, TokenManager};
 use crate::pair_wasm;
 use astroswap_factory::{AstroSwapFactory, AstroSwapFactoryClient};
+use astroswap_pair::AstroSwapPairClient;
 use astroswap_shared::interfaces::PairClient;
 use rand::Rng;
 use soroban_sdk::{testutils::Address as _, Address, Env};
@@ -21,7 +23,7 @@ impl PoolStressScenario {
         Self
     }
 
-    /// Setup test environment
+    /// Setup test environment, restoring it from a snapshot when configured
     fn setup_environment(
         &self,
         config: &StressConfig,
@@ -33,6 +35,23 @@ impl PoolStressScenario {
         AstroSwapFactoryClient<'static>,
         Vec<Address>,
     ) {
+        // The pool count shapes the world, so it is part of the snapshot label
+        let label = format!("pool-stress-{}", config.pool_stress.num_pools);
+        let world = snapshot::load_or_build(config, &label, || self.build_world(config));
+        let factory = AstroSwapFactoryClient::new(&world.env, &world.factory);
+
+        (
+            world.env,
+            world.admin,
+            world.token_manager,
+            world.account_pool,
+            factory,
+            world.pairs,
+        )
+    }
+
+    /// Build the test environment from scratch
+    fn build_world(&self, config: &StressConfig) -> World {
         let env = Env::default();
         // Use mock_all_auths_allowing_non_root_auth for contract-to-contract calls (SDK 23)
         env.mock_all_auths_allowing_non_root_auth();
@@ -45,10 +64,11 @@ impl PoolStressScenario {
         token_manager.create_tokens(&env, &admin, num_tokens, 100_000_000_0000000);
 
         // Create account pool
-        let account_pool = AccountPool::new(&env, config.num_accounts);
+        let mut account_pool = AccountPool::new(&env, config.num_accounts);
 
-        // Distribute tokens to all accounts
-        token_manager.distribute(&admin, account_pool.all(), 10_000_000_0000000);
+        // Fund accounts following the configured balance distribution
+        account_pool.assign_balances(&config.accounts, 10_000_000_0000000);
+        account_pool.fund(&token_manager);
 
         // Deploy pair WASM (SDK 23: use WASM bytes directly)
         let pair_wasm_hash = env.deployer().upload_contract_wasm(pair_wasm::WASM);
@@ -73,39 +93,58 @@ impl PoolStressScenario {
             pair_addresses.push(pair_addr);
         }
 
-        (env, admin, token_manager, account_pool, factory, pair_addresses)
+        World {
+            env,
+            admin,
+            token_manager,
+            account_pool,
+            factory: factory_address,
+            router: None,
+            pairs: pair_addresses,
+        }
     }
 
     /// Execute add liquidity operation
+    #[allow(clippy::too_many_arguments)]
     fn add_liquidity(
         &self,
         env: &Env,
         pair_address: &Address,
         user: &Address,
+        account: &str,
         amount_0: i128,
         amount_1: i128,
         collector: &MetricsCollector,
     ) {
-        let timer = collector.start_operation();
-        let pair_client = PairClient::new(env, pair_address);
+        let timer = collector
+            .start_operation()
+            .for_account(account)
+            .with_budget(env);
+        let pair_client = AstroSwapPairClient::new(env, pair_address);
+        let deadline = env.ledger().timestamp() + 3600;
 
         let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-            pair_client.deposit(user, amount_0, amount_1, 0, 0)
+            pair_client.try_deposit(user, &amount_0, &amount_1, &0, &0, &deadline)
         }));
 
+        let mut metadata = HashMap::new();
         match result {
-            Ok((actual_0, actual_1, shares)) => {
-                let mut metadata = HashMap::new();
+            Ok(Ok(Ok((actual_0, actual_1, shares)))) => {
                 metadata.insert("amount_0".to_string(), actual_0.to_string());
                 metadata.insert("amount_1".to_string(), actual_1.to_string());
                 metadata.insert("shares".to_string(), shares.to_string());
+                record_budget(env, &mut metadata);
                 timer.success(OperationType::AddLiquidity, metadata);
             }
+            Ok(failed) => {
+                let message = record_error_code(&failed, &mut metadata, "Add liquidity failed");
+                timer.error(OperationType::AddLiquidity, message, metadata);
+            }
             Err(_) => {
                 timer.error(
                     OperationType::AddLiquidity,
                     "Add liquidity failed".to_string(),
-                    HashMap::new(),
+                    metadata,
                 );
             }
         }
@@ -117,29 +156,39 @@ impl PoolStressScenario {
         env: &Env,
         pair_address: &Address,
         user: &Address,
+        account: &str,
         shares: i128,
         collector: &MetricsCollector,
     ) {
-        let timer = collector.start_operation();
-        let pair_client = PairClient::new(env, pair_address);
+        let timer = collector
+            .start_operation()
+            .for_account(account)
+            .with_budget(env);
+        let pair_client = AstroSwapPairClient::new(env, pair_address);
+        let deadline = env.ledger().timestamp() + 3600;
 
         let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-            pair_client.withdraw(user, shares, 0, 0)
+            pair_client.try_withdraw(user, &shares, &0, &0, &deadline)
         }));
 
+        let mut metadata = HashMap::new();
         match result {
-            Ok((amount_0, amount_1)) => {
-                let mut metadata = HashMap::new();
+            Ok(Ok(Ok((amount_0, amount_1)))) => {
                 metadata.insert("amount_0".to_string(), amount_0.to_string());
                 metadata.insert("amount_1".to_string(), amount_1.to_string());
                 metadata.insert("shares".to_string(), shares.to_string());
+                record_budget(env, &mut metadata);
                 timer.success(OperationType::RemoveLiquidity, metadata);
             }
+            Ok(failed) => {
+                let message = record_error_code(&failed, &mut metadata, "Remove liquidity failed");
+                timer.error(OperationType::RemoveLiquidity, message, metadata);
+            }
             Err(_) => {
                 timer.error(
                     OperationType::RemoveLiquidity,
                     "Remove liquidity failed".to_string(),
-                    HashMap::new(),
+                    metadata,
                 );
             }
         }
@@ -154,8 +203,9 @@ impl Default for PoolStressScenario {
 
 impl StressScenario for PoolStressScenario {
     fn run(&self, config: &StressConfig, collector: &MetricsCollector) {
-        let (env, _admin, _token_manager, account_pool, _factory, pair_addresses) =
+        let (env, _admin, token_manager, account_pool, _factory, pair_addresses) =
             self.setup_environment(config);
+        account_pool.register(collector);
 
         let test_start = Instant::now();
         let target_duration = std::time::Duration::from_secs(config.duration_seconds);
@@ -163,6 +213,8 @@ impl StressScenario for PoolStressScenario {
 
         let mut rng = rand::thread_rng();
         let mut operation_count = 0u64;
+        let mut clock = LedgerClock::new(&env, &config.time_warp);
+        let mut entry_sampler = LedgerEntrySampler::new(&config.leak_detection);
 
         // Track LP positions per user per pool (use string keys - Address doesn't impl Hash)
         let mut lp_positions: HashMap<String, i128> = HashMap::new();
@@ -185,7 +237,9 @@ impl StressScenario for PoolStressScenario {
                 let pair_address = &pair_addresses[pool_idx];
 
                 // Select random user
-                let user = account_pool.random().clone();
+                let user_idx = account_pool.random_index();
+                let user = account_pool.get_account(user_idx).clone();
+                let account = account_pool.label(user_idx);
 
                 // Decide: add or remove liquidity
                 let should_add = rng.gen_bool(pool_config.add_ratio);
@@ -214,7 +268,15 @@ impl StressScenario for PoolStressScenario {
                         rng.gen_range(pool_config.min_liquidity..=pool_config.max_liquidity)
                     };
 
-                    self.add_liquidity(&env, pair_address, &user, amount_0, amount_1, collector);
+                    self.add_liquidity(
+                        &env,
+                        pair_address,
+                        &user,
+                        &account,
+                        amount_0,
+                        amount_1,
+                        collector,
+                    );
 
                     // Track position (simplified - just track that they have shares)
                     let pair_client = PairClient::new(&env, pair_address);
@@ -234,6 +296,7 @@ impl StressScenario for PoolStressScenario {
                                 &env,
                                 pair_address,
                                 &user,
+                                &account,
                                 shares_to_remove,
                                 collector,
                             );
@@ -252,11 +315,20 @@ impl StressScenario for PoolStressScenario {
 
                 operation_count += 1;
 
+                // Accelerated ledger time between operations (no-op unless enabled)
+                clock.tick(&env);
+
                 if test_start.elapsed() >= target_duration {
                     break;
                 }
             }
 
+            // Top up accounts drained by the previous iteration
+            account_pool.refill_depleted(&token_manager, collector);
+
+            // Ledger entries once per window of operations, for leak detection
+            entry_sampler.sample(&env, collector);
+
             // Rate limiting
             let iteration_duration = iteration_start.elapsed();
             let target_iteration_duration = std::time::Duration::from_secs(1);
@@ -281,6 +353,7 @@ impl StressScenario for PoolStressScenario {
             collector.total_operations(),
             test_start.elapsed().as_secs_f64()
         );
+        clock.print_summary();
     }
 
     fn name(&self) -> &str {
@@ -1,19 +1,202 @@
 //! Router Path Stress Testing Scenario
 //!
-//! Tests multi-hop swap routing under load with complex paths.
+//! Tests multi-hop swap routing under load across a randomly generated pair
+//! graph. Every simple path between `min_hops` and `max_hops` is enumerated and
+//! swapped through the router, so latency and budget can be compared by path
+//! length and valid paths rejected by path validation are surfaced.
 
 use super::StressScenario;
 use crate::config::StressConfig;
-use crate::metrics::{MetricsCollector, OperationType};
-use crate::utils::{AccountPool, TokenManager};
+use crate::metrics::{record_error_code, MetricsCollector, OperationType};
+use crate::utils::{AccountPool, LedgerEntrySampler, TokenManager};
 use crate::pair_wasm;
 use astroswap_factory::{AstroSwapFactory, AstroSwapFactoryClient};
 use astroswap_router::{AstroSwapRouter, AstroSwapRouterClient};
+use astroswap_shared::AstroSwapError;
 use rand::Rng;
 use soroban_sdk::{testutils::Address as _, vec as soroban_vec, Address, Env, Vec as SorobanVec};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::time::Instant;
 
+/// Maximum number of tokens in a router path (mirrors the router's MAX_PATH_LENGTH)
+pub const ROUTER_MAX_PATH_LENGTH: usize = 5;
+
+/// Liquidity seeded into every pair of the graph (per token)
+const PAIR_LIQUIDITY: i128 = 10_000_000_0000000;
+
+/// Mean token balance of a test account
+const ACCOUNT_BALANCE: i128 = 10_000_000_0000000;
+
+/// Undirected token graph where each edge is a trading pair
+#[derive(Debug, Clone)]
+pub struct PairGraph {
+    edges: Vec<(usize, usize)>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl PairGraph {
+    /// Build a random connected graph.
+    ///
+    /// A chain `0 - 1 - ... - n-1` guarantees connectivity, then every other
+    /// token pair is added with probability `edge_probability`.
+    pub fn random<R: Rng>(num_tokens: usize, edge_probability: f64, rng: &mut R) -> Self {
+        let mut edges = Vec::new();
+        for i in 0..num_tokens {
+            for j in (i + 1)..num_tokens {
+                if j == i + 1 || rng.gen_bool(edge_probability.clamp(0.0, 1.0)) {
+                    edges.push((i, j));
+                }
+            }
+        }
+        Self::from_edges(num_tokens, edges)
+    }
+
+    /// Build a graph from an explicit edge list
+    pub fn from_edges(num_tokens: usize, edges: Vec<(usize, usize)>) -> Self {
+        let mut adjacency = vec![Vec::new(); num_tokens];
+        for &(a, b) in &edges {
+            adjacency[a].push(b);
+            adjacency[b].push(a);
+        }
+        Self { edges, adjacency }
+    }
+
+    /// Number of tokens (nodes)
+    pub fn num_tokens(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    /// All pairs (edges) in the graph
+    pub fn edges(&self) -> &[(usize, usize)] {
+        &self.edges
+    }
+
+    /// Number of pairs a token participates in
+    pub fn degree(&self, token: usize) -> usize {
+        self.adjacency.get(token).map(|n| n.len()).unwrap_or(0)
+    }
+
+    /// Enumerate simple paths (no repeated token) with `min_hops..=max_hops` hops.
+    ///
+    /// Paths are returned as token indices. Enumeration stops after `limit` paths.
+    pub fn simple_paths(&self, min_hops: usize, max_hops: usize, limit: usize) -> Vec<Vec<usize>> {
+        let mut paths = Vec::new();
+        let mut visited = vec![false; self.num_tokens()];
+        let mut current = Vec::new();
+
+        for start in 0..self.num_tokens() {
+            self.walk(start, min_hops, max_hops, limit, &mut visited, &mut current, &mut paths);
+            if paths.len() >= limit {
+                break;
+            }
+        }
+
+        paths
+    }
+
+    /// Path from `from` to `to` with the fewest hops, as token indices
+    pub fn shortest_path(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        let mut previous = vec![None; self.num_tokens()];
+        let mut visited = vec![false; self.num_tokens()];
+        let mut queue = std::collections::VecDeque::from([from]);
+        visited[from] = true;
+
+        while let Some(node) = queue.pop_front() {
+            if node == to {
+                let mut path = vec![to];
+                while let Some(prev) = previous[*path.last().unwrap()] {
+                    path.push(prev);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for &next in &self.adjacency[node] {
+                if !visited[next] {
+                    visited[next] = true;
+                    previous[next] = Some(node);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn walk(
+        &self,
+        node: usize,
+        min_hops: usize,
+        max_hops: usize,
+        limit: usize,
+        visited: &mut [bool],
+        current: &mut Vec<usize>,
+        paths: &mut Vec<Vec<usize>>,
+    ) {
+        if paths.len() >= limit {
+            return;
+        }
+
+        visited[node] = true;
+        current.push(node);
+
+        let hops = current.len() - 1;
+        if hops >= min_hops {
+            paths.push(current.clone());
+        }
+
+        if hops < max_hops {
+            for &next in &self.adjacency[node] {
+                if !visited[next] {
+                    self.walk(next, min_hops, max_hops, limit, visited, current, paths);
+                }
+            }
+        }
+
+        current.pop();
+        visited[node] = false;
+    }
+}
+
+/// Whether the router is expected to accept a simple path of `hops` hops
+pub fn is_routable(hops: usize) -> bool {
+    hops >= 1 && hops < ROUTER_MAX_PATH_LENGTH
+}
+
+/// Aggregated results for all paths of the same length
+#[derive(Debug, Clone, Default)]
+pub struct HopStats {
+    pub attempts: u64,
+    pub successes: u64,
+    pub false_negatives: u64,
+    pub false_positives: u64,
+    pub total_latency_micros: u64,
+    pub total_cpu_instructions: u64,
+    pub total_memory_bytes: u64,
+}
+
+impl HopStats {
+    pub fn avg_latency_ms(&self) -> f64 {
+        if self.attempts == 0 {
+            return 0.0;
+        }
+        self.total_latency_micros as f64 / self.attempts as f64 / 1000.0
+    }
+
+    pub fn avg_cpu_instructions(&self) -> u64 {
+        if self.attempts == 0 {
+            return 0;
+        }
+        self.total_cpu_instructions / self.attempts
+    }
+
+    pub fn avg_memory_bytes(&self) -> u64 {
+        if self.attempts == 0 {
+            return 0;
+        }
+        self.total_memory_bytes / self.attempts
+    }
+}
+
 pub struct RouterPathsScenario;
 
 impl RouterPathsScenario {
@@ -21,7 +204,7 @@ impl RouterPathsScenario {
         Self
     }
 
-    /// Setup test environment with interconnected pairs
+    /// Setup test environment with a random pair graph
     fn setup_environment(
         &self,
         config: &StressConfig,
@@ -39,15 +222,25 @@ impl RouterPathsScenario {
         env.mock_all_auths_allowing_non_root_auth();
 
         let admin = Address::generate(&env);
+        let router_config = &config.router_paths;
+        let mut rng = rand::thread_rng();
+
+        // Build the pair graph (need enough tokens for the longest path)
+        let num_tokens = router_config
+            .graph_tokens
+            .max(router_config.max_hops + 1) as usize;
+        let graph = PairGraph::random(num_tokens, router_config.edge_probability, &mut rng);
+        let max_degree = (0..num_tokens).map(|t| graph.degree(t)).max().unwrap_or(0) as i128;
 
-        // Create tokens (need enough for multi-hop paths)
+        // Admin supply covers liquidity for every pair a token is in
+        let supply = PAIR_LIQUIDITY * max_degree.max(1);
         let mut token_manager = TokenManager::new();
-        let num_tokens = (config.router_paths.max_hops + 2).max(6);
-        token_manager.create_tokens(&env, &admin, num_tokens, 100_000_000_0000000);
+        token_manager.create_tokens(&env, &admin, num_tokens as u32, supply);
 
-        // Create account pool
-        let account_pool = AccountPool::new(&env, config.num_accounts);
-        token_manager.distribute(&admin, account_pool.all(), 10_000_000_0000000);
+        // Create and fund account pool
+        let mut account_pool = AccountPool::new(&env, config.num_accounts);
+        account_pool.assign_balances(&config.accounts, ACCOUNT_BALANCE);
+        account_pool.fund(&token_manager);
 
         // Deploy pair WASM (SDK 23: use WASM bytes directly)
         let pair_wasm_hash = env.deployer().upload_contract_wasm(pair_wasm::WASM);
@@ -60,14 +253,10 @@ impl RouterPathsScenario {
         let router = AstroSwapRouterClient::new(&env, &router_address);
         router.initialize(&factory_address, &admin);
 
-        // Create connected pairs for multi-hop routing
-        // Build a path graph: Token0 <-> Token1 <-> Token2 <-> Token3 ...
-        let mut paths: Vec<Vec<Address>> = Vec::new();
-
-        // Create sequential pairs (for guaranteed paths)
-        for i in 0..(num_tokens - 1) {
-            let token_a = token_manager.get(i as usize).unwrap();
-            let token_b = token_manager.get((i + 1) as usize).unwrap();
+        // Create one pair per graph edge
+        for &(a, b) in graph.edges() {
+            let token_a = token_manager.get(a).unwrap();
+            let token_b = token_manager.get(b).unwrap();
 
             // SDK 23: client method returns Address directly
             factory.create_pair(&token_a.address, &token_b.address);
@@ -77,41 +266,61 @@ impl RouterPathsScenario {
                 &admin,
                 &token_a.address,
                 &token_b.address,
-                &10_000_000_0000000,
-                &10_000_000_0000000,
+                &PAIR_LIQUIDITY,
+                &PAIR_LIQUIDITY,
                 &0,
                 &0,
                 &(env.ledger().timestamp() + 3600),
             );
         }
 
-        // Generate various path configurations
-        for hops in config.router_paths.min_hops..=config.router_paths.max_hops {
-            if hops as u32 <= num_tokens - 1 {
-                let mut path = Vec::new();
-                for i in 0..=hops {
-                    let token = token_manager.get(i as usize).unwrap();
-                    path.push(token.address.clone());
-                }
-                paths.push(path);
-            }
-        }
+        // Enumerate every viable path in the graph
+        let paths: Vec<Vec<Address>> = graph
+            .simple_paths(
+                router_config.min_hops as usize,
+                router_config.max_hops as usize,
+                router_config.max_paths as usize,
+            )
+            .into_iter()
+            .map(|path| {
+                path.into_iter()
+                    .map(|idx| token_manager.get(idx).unwrap().address.clone())
+                    .collect()
+            })
+            .collect();
+
+        println!(
+            "Pair graph: {} tokens, {} pairs, {} paths ({}-{} hops)",
+            num_tokens,
+            graph.edges().len(),
+            paths.len(),
+            router_config.min_hops,
+            router_config.max_hops
+        );
 
         (env, admin, token_manager, account_pool, factory, router, paths)
     }
 
     /// Execute multi-hop swap
+    #[allow(clippy::too_many_arguments)]
     fn execute_multi_hop_swap(
         &self,
         env: &Env,
         router: &AstroSwapRouterClient,
         user: &Address,
+        account: &str,
         path: &[Address],
         amount_in: i128,
         min_out: i128,
         collector: &MetricsCollector,
+        hop_stats: &mut BTreeMap<usize, HopStats>,
     ) {
-        let timer = collector.start_operation();
+        let hops = path.len() - 1;
+        let expected_valid = is_routable(hops);
+        let timer = collector
+            .start_operation()
+            .for_account(account)
+            .with_budget(env);
 
         // Convert path to Soroban Vec
         let mut soroban_path: SorobanVec<Address> = soroban_vec![env];
@@ -121,16 +330,35 @@ impl RouterPathsScenario {
 
         let deadline = env.ledger().timestamp() + 3600;
 
-        // SDK 23: i128 params need references, client returns Vec<i128> directly (panics on error)
+        // try_ variant exposes the contract error so path rejections can be classified
         let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-            router.swap_exact_tokens_for_tokens(user, &amount_in, &min_out, &soroban_path, &deadline)
+            router.try_swap_exact_tokens_for_tokens(
+                user,
+                &amount_in,
+                &min_out,
+                &soroban_path,
+                &deadline,
+            )
         }));
+        let latency_micros = timer.elapsed().as_micros() as u64;
+
+        // Budget is reset before each top-level invocation, so this is the swap's cost
+        let budget = env.cost_estimate().budget();
+        let stats = hop_stats.entry(hops).or_default();
+        stats.attempts += 1;
+        stats.total_latency_micros += latency_micros;
+        stats.total_cpu_instructions += budget.cpu_instruction_cost();
+        stats.total_memory_bytes += budget.memory_bytes_cost();
+
+        let mut metadata = HashMap::new();
+        metadata.insert("hops".to_string(), hops.to_string());
+        metadata.insert("amount_in".to_string(), amount_in.to_string());
+        metadata.insert("expected_valid".to_string(), expected_valid.to_string());
+        metadata.insert("cpu_instructions".to_string(), budget.cpu_instruction_cost().to_string());
+        metadata.insert("memory_bytes".to_string(), budget.memory_bytes_cost().to_string());
 
         match result {
-            Ok(amounts) => {
-                let mut metadata = HashMap::new();
-                metadata.insert("hops".to_string(), (path.len() - 1).to_string());
-                metadata.insert("amount_in".to_string(), amount_in.to_string());
+            Ok(Ok(Ok(amounts))) => {
                 if let Some(amount_out) = amounts.last() {
                     metadata.insert("amount_out".to_string(), amount_out.to_string());
                     let price_impact = if amount_in > 0 {
@@ -140,11 +368,38 @@ impl RouterPathsScenario {
                     };
                     metadata.insert("price_impact_bps".to_string(), price_impact.to_string());
                 }
+
+                if expected_valid {
+                    stats.successes += 1;
+                    timer.success(OperationType::MultiHopSwap, metadata);
+                } else {
+                    stats.false_positives += 1;
+                    timer.error(
+                        OperationType::MultiHopSwap,
+                        format!("Path validation false positive ({} hops)", hops),
+                        metadata,
+                    );
+                }
+            }
+            Ok(Err(Ok(AstroSwapError::InvalidPath | AstroSwapError::RouteTooLong))) if expected_valid => {
+                stats.false_negatives += 1;
+                timer.error(
+                    OperationType::MultiHopSwap,
+                    format!("Path validation false negative ({} hops)", hops),
+                    metadata,
+                );
+            }
+            Ok(Err(Ok(AstroSwapError::RouteTooLong))) => {
+                // Over-length path correctly rejected by the router
+                metadata.insert("expected_rejection".to_string(), "true".to_string());
+                stats.successes += 1;
                 timer.success(OperationType::MultiHopSwap, metadata);
             }
+            Ok(failed) => {
+                let message = record_error_code(&failed, &mut metadata, "Multi-hop swap failed");
+                timer.error(OperationType::MultiHopSwap, message, metadata);
+            }
             Err(_) => {
-                let mut metadata = HashMap::new();
-                metadata.insert("hops".to_string(), (path.len() - 1).to_string());
                 timer.error(
                     OperationType::MultiHopSwap,
                     "Multi-hop swap failed".to_string(),
@@ -153,6 +408,25 @@ impl RouterPathsScenario {
             }
         }
     }
+
+    /// Print per-path-length latency, budget and validation results
+    fn print_hop_summary(&self, hop_stats: &BTreeMap<usize, HopStats>) {
+        println!("Path length breakdown:");
+        println!("  Hops | Attempts | Success | False Neg | False Pos | Avg ms | Avg CPU | Avg Mem");
+        for (hops, stats) in hop_stats {
+            println!(
+                "  {:>4} | {:>8} | {:>7} | {:>9} | {:>9} | {:>6.2} | {:>7} | {:>7}",
+                hops,
+                stats.attempts,
+                stats.successes,
+                stats.false_negatives,
+                stats.false_positives,
+                stats.avg_latency_ms(),
+                stats.avg_cpu_instructions(),
+                stats.avg_memory_bytes()
+            );
+        }
+    }
 }
 
 impl Default for RouterPathsScenario {
@@ -163,8 +437,14 @@ impl Default for RouterPathsScenario {
 
 impl StressScenario for RouterPathsScenario {
     fn run(&self, config: &StressConfig, collector: &MetricsCollector) {
-        let (env, _admin, _token_manager, account_pool, _factory, router, paths) =
+        let (env, _admin, token_manager, account_pool, _factory, router, paths) =
             self.setup_environment(config);
+        account_pool.register(collector);
+
+        if paths.is_empty() {
+            println!("Router paths test skipped: no paths in pair graph");
+            return;
+        }
 
         let test_start = Instant::now();
         let target_duration = std::time::Duration::from_secs(config.duration_seconds);
@@ -172,6 +452,8 @@ impl StressScenario for RouterPathsScenario {
 
         let mut rng = rand::thread_rng();
         let mut operation_count = 0u64;
+        let mut hop_stats: BTreeMap<usize, HopStats> = BTreeMap::new();
+        let mut entry_sampler = LedgerEntrySampler::new(&config.leak_detection);
 
         println!(
             "Starting router paths test: {} paths for {} seconds",
@@ -189,7 +471,8 @@ impl StressScenario for RouterPathsScenario {
                 let path = &paths[path_idx];
 
                 // Select random user
-                let user = account_pool.random();
+                let user_idx = account_pool.random_index();
+                let user = account_pool.get_account(user_idx);
 
                 // Generate swap amount
                 let amount_in = rng.gen_range(1_000_0000000..=100_000_0000000);
@@ -205,10 +488,12 @@ impl StressScenario for RouterPathsScenario {
                     &env,
                     &router,
                     user,
+                    &account_pool.label(user_idx),
                     path,
                     amount_in,
                     min_out.max(1),
                     collector,
+                    &mut hop_stats,
                 );
 
                 operation_count += 1;
@@ -218,6 +503,12 @@ impl StressScenario for RouterPathsScenario {
                 }
             }
 
+            // Top up accounts drained by the previous iteration
+            account_pool.refill_depleted(&token_manager, collector);
+
+            // Ledger entries once per window of operations, for leak detection
+            entry_sampler.sample(&env, collector);
+
             // Rate limiting
             let iteration_duration = iteration_start.elapsed();
             let target_iteration_duration = std::time::Duration::from_secs(1);
@@ -241,6 +532,7 @@ impl StressScenario for RouterPathsScenario {
             collector.total_operations(),
             test_start.elapsed().as_secs_f64()
         );
+        self.print_hop_summary(&hop_stats);
     }
 
     fn name(&self) -> &str {
@@ -248,7 +540,7 @@ impl StressScenario for RouterPathsScenario {
     }
 
     fn description(&self) -> &str {
-        "Multi-hop swap routing stress with complex paths"
+        "Multi-hop swap routing stress across a random pair graph"
     }
 }
 
@@ -276,4 +568,56 @@ mod tests {
             collector.success_rate() * 100.0
         );
     }
+
+    #[test]
+    fn test_pair_graph_is_connected_chain() {
+        let mut rng = rand::thread_rng();
+        let graph = PairGraph::random(6, 0.0, &mut rng);
+
+        // With zero edge probability only the chain remains
+        assert_eq!(graph.edges().len(), 5);
+        assert_eq!(graph.degree(0), 1);
+        assert_eq!(graph.degree(3), 2);
+    }
+
+    #[test]
+    fn test_simple_paths_respect_hop_bounds() {
+        // Square with a diagonal: 0-1, 1-2, 2-3, 3-0, 0-2
+        let graph = PairGraph::from_edges(4, vec![(0, 1), (1, 2), (2, 3), (0, 3), (0, 2)]);
+
+        let paths = graph.simple_paths(2, 3, usize::MAX);
+        assert!(!paths.is_empty());
+        for path in &paths {
+            let hops = path.len() - 1;
+            assert!((2..=3).contains(&hops));
+
+            // No repeated tokens
+            let mut sorted = path.clone();
+            sorted.sort_unstable();
+            sorted.dedup();
+            assert_eq!(sorted.len(), path.len());
+        }
+
+        let limited = graph.simple_paths(1, 3, 4);
+        assert_eq!(limited.len(), 4);
+    }
+
+    #[test]
+    fn test_shortest_path() {
+        // Chain 0-1-2-3 with a shortcut 0-2
+        let graph = PairGraph::from_edges(5, vec![(0, 1), (1, 2), (2, 3), (0, 2)]);
+
+        assert_eq!(graph.shortest_path(0, 3), Some(vec![0, 2, 3]));
+        assert_eq!(graph.shortest_path(3, 1), Some(vec![3, 2, 1]));
+        assert_eq!(graph.shortest_path(1, 1), Some(vec![1]));
+        assert_eq!(graph.shortest_path(0, 4), None);
+    }
+
+    #[test]
+    fn test_is_routable() {
+        assert!(!is_routable(0));
+        assert!(is_routable(1));
+        assert!(is_routable(4));
+        assert!(!is_routable(5));
+    }
 }
@@ -0,0 +1,487 @@
+//! Router vs Aggregator Scenario
+//!
+//! Runs one randomized trade list through the plain router and through the
+//! aggregator (AstroSwap pools only, no external adapters). Each venue trades
+//! on its own copy of the same pair graph, with pool prices skewed from par
+//! so an indirect route can beat the direct one. The router takes the path
+//! with the fewest hops, as a client without a route search would; the
+//! aggregator picks its own route. Both set their minimum output from their
+//! own quote less `slippage_bps`.
+//!
+//! Swaps are tagged with their venue and trade index, and the report compares
+//! realized output per trade, failure rates and budget (see
+//! `metrics::head_to_head`).
+
+use super::router_paths::PairGraph;
+use super::StressScenario;
+use crate::config::StressConfig;
+use crate::metrics::head_to_head::{AGGREGATOR_VENUE, ROUTER_VENUE, TRADE_KEY, VENUE_KEY};
+use crate::metrics::{record_error_code, HeadToHeadReport, MetricsCollector, OperationType};
+use crate::pair_wasm;
+use crate::utils::{record_budget, TokenManager};
+use astroswap_aggregator::{AstroSwapAggregator, AstroSwapAggregatorClient};
+use astroswap_factory::{AstroSwapFactory, AstroSwapFactoryClient};
+use astroswap_pair::AstroSwapPairClient;
+use astroswap_router::{AstroSwapRouter, AstroSwapRouterClient};
+use astroswap_shared::get_amount_out;
+use rand::Rng;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env, Vec as SorobanVec,
+};
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Basis points denominator
+const BPS: i128 = 10_000;
+
+/// Pair graph with the reserves of every pool, shared by both venues
+#[derive(Debug, Clone)]
+pub struct Market {
+    pub graph: PairGraph,
+    /// Reserves of each edge's (first, second) token, in edge order
+    pub reserves: Vec<(i128, i128)>,
+}
+
+impl Market {
+    /// Random connected market within `config.router_vs_aggregator` bounds
+    pub fn random<R: Rng>(config: &StressConfig, rng: &mut R) -> Self {
+        let h2h = &config.router_vs_aggregator;
+        let graph = PairGraph::random(h2h.graph_tokens.max(2) as usize, h2h.edge_probability, rng);
+        let skew = h2h.max_price_skew_bps.min(9_999) as i128;
+        let reserves = graph
+            .edges()
+            .iter()
+            .map(|_| {
+                let liquidity = rng.gen_range(h2h.min_liquidity..=h2h.max_liquidity);
+                let skew_bps = rng.gen_range(-skew..=skew);
+                (liquidity, liquidity * (BPS + skew_bps) / BPS)
+            })
+            .collect();
+        Self { graph, reserves }
+    }
+}
+
+/// One entry of the shared trade list, by token index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Trade {
+    pub token_in: usize,
+    pub token_out: usize,
+    pub amount_in: i128,
+}
+
+/// Random trades between distinct tokens of `market`
+pub fn random_trades<R: Rng>(config: &StressConfig, market: &Market, rng: &mut R) -> Vec<Trade> {
+    let h2h = &config.router_vs_aggregator;
+    let num_tokens = market.graph.num_tokens();
+    (0..h2h.num_trades)
+        .map(|_| {
+            let token_in = rng.gen_range(0..num_tokens);
+            let token_out = (token_in + rng.gen_range(1..num_tokens)) % num_tokens;
+            Trade {
+                token_in,
+                token_out,
+                amount_in: rng.gen_range(h2h.min_trade_amount..=h2h.max_trade_amount),
+            }
+        })
+        .collect()
+}
+
+/// One venue's copy of the market
+struct Venue {
+    env: Env,
+    trader: Address,
+    tokens: Vec<Address>,
+    factory: AstroSwapFactoryClient<'static>,
+    router: AstroSwapRouterClient<'static>,
+    aggregator: AstroSwapAggregatorClient<'static>,
+}
+
+pub struct RouterVsAggregatorScenario;
+
+impl RouterVsAggregatorScenario {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Deploy `market` in a fresh environment with a funded trader
+    fn build_venue(&self, config: &StressConfig, market: &Market) -> Venue {
+        let env = Env::default();
+        // Use mock_all_auths_allowing_non_root_auth for contract-to-contract calls (SDK 23)
+        env.mock_all_auths_allowing_non_root_auth();
+
+        let admin = Address::generate(&env);
+        let trader = Address::generate(&env);
+        let h2h = &config.router_vs_aggregator;
+        let num_tokens = market.graph.num_tokens();
+
+        // Admin supply covers every pool a token is in, at the largest skew
+        let supply = h2h.max_liquidity * 2 * num_tokens as i128;
+        let mut token_manager = TokenManager::new();
+        token_manager.create_tokens(&env, &admin, num_tokens as u32, supply);
+        token_manager.mint_all(
+            &trader,
+            h2h.max_trade_amount * h2h.num_trades.max(1) as i128,
+        );
+
+        let pair_wasm_hash = env.deployer().upload_contract_wasm(pair_wasm::WASM);
+        let factory_address = env.register(AstroSwapFactory, ());
+        let factory = AstroSwapFactoryClient::new(&env, &factory_address);
+        factory.initialize(&admin, &pair_wasm_hash, &30);
+
+        let router_address = env.register(AstroSwapRouter, ());
+        let router = AstroSwapRouterClient::new(&env, &router_address);
+        router.initialize(&factory_address, &admin);
+
+        // Protocol 0 (AstroSwap) is the only protocol registered
+        let aggregator_address = env.register(AstroSwapAggregator, ());
+        let aggregator = AstroSwapAggregatorClient::new(&env, &aggregator_address);
+        aggregator.initialize(&admin, &factory_address);
+
+        let tokens: Vec<Address> = (0..num_tokens)
+            .map(|i| token_manager.get(i).unwrap().address.clone())
+            .collect();
+        for (&(a, b), &(reserve_a, reserve_b)) in market.graph.edges().iter().zip(&market.reserves)
+        {
+            factory.create_pair(&tokens[a], &tokens[b]);
+            router.add_liquidity(
+                &admin,
+                &tokens[a],
+                &tokens[b],
+                &reserve_a,
+                &reserve_b,
+                &0,
+                &0,
+                &(env.ledger().timestamp() + 3600),
+            );
+        }
+
+        Venue {
+            env,
+            trader,
+            tokens,
+            factory,
+            router,
+            aggregator,
+        }
+    }
+
+    /// Router output of `amount_in` along `path` at current reserves
+    fn router_quote(&self, venue: &Venue, path: &[usize], amount_in: i128) -> Option<i128> {
+        let mut amount = amount_in;
+        for hop in path.windows(2) {
+            let (token_in, token_out) = (&venue.tokens[hop[0]], &venue.tokens[hop[1]]);
+            let pair =
+                AstroSwapPairClient::new(&venue.env, &venue.factory.get_pair(token_in, token_out)?);
+            let (reserve_0, reserve_1) = pair.get_reserves();
+            let (reserve_in, reserve_out) = if &pair.token_0() == token_in {
+                (reserve_0, reserve_1)
+            } else {
+                (reserve_1, reserve_0)
+            };
+            amount = get_amount_out(amount, reserve_in, reserve_out, pair.fee_bps()).ok()?;
+        }
+        Some(amount)
+    }
+
+    /// Swap `trade` through the router along the fewest-hop path
+    fn router_swap(
+        &self,
+        venue: &Venue,
+        market: &Market,
+        index: usize,
+        trade: &Trade,
+        slippage_bps: u32,
+        collector: &MetricsCollector,
+    ) {
+        let mut metadata = Self::metadata(ROUTER_VENUE, index, trade);
+        let path = market.graph.shortest_path(trade.token_in, trade.token_out);
+        let quote = path
+            .as_ref()
+            .and_then(|path| self.router_quote(venue, path, trade.amount_in));
+        let (Some(path), Some(quote)) = (path, quote) else {
+            collector.record_error(
+                OperationType::Swap,
+                std::time::Duration::ZERO,
+                "No router path".to_string(),
+                metadata,
+            );
+            return;
+        };
+
+        let env = &venue.env;
+        let mut soroban_path = SorobanVec::new(env);
+        for &token in &path {
+            soroban_path.push_back(venue.tokens[token].clone());
+        }
+        metadata.insert("hops".to_string(), (path.len() - 1).to_string());
+
+        let min_out = Self::min_out(quote, slippage_bps);
+        let deadline = env.ledger().timestamp() + 3600;
+        let timer = collector.start_operation().with_budget(env);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            venue.router.try_swap_exact_tokens_for_tokens(
+                &venue.trader,
+                &trade.amount_in,
+                &min_out,
+                &soroban_path,
+                &deadline,
+            )
+        }));
+
+        match result {
+            Ok(Ok(Ok(amounts))) => {
+                let amount_out = amounts.last().unwrap_or(0);
+                metadata.insert("amount_out".to_string(), amount_out.to_string());
+                record_budget(env, &mut metadata);
+                timer.success(OperationType::Swap, metadata);
+            }
+            Ok(failed) => {
+                record_budget(env, &mut metadata);
+                let message = record_error_code(&failed, &mut metadata, "Router swap failed");
+                timer.error(OperationType::Swap, message, metadata);
+            }
+            Err(_) => {
+                timer.error(
+                    OperationType::Swap,
+                    "Router swap panicked".to_string(),
+                    metadata,
+                );
+            }
+        }
+    }
+
+    /// Swap `trade` through the aggregator along its best route
+    fn aggregator_swap(
+        &self,
+        venue: &Venue,
+        index: usize,
+        trade: &Trade,
+        slippage_bps: u32,
+        collector: &MetricsCollector,
+    ) {
+        let env = &venue.env;
+        let mut metadata = Self::metadata(AGGREGATOR_VENUE, index, trade);
+        let (token_in, token_out) = (
+            &venue.tokens[trade.token_in],
+            &venue.tokens[trade.token_out],
+        );
+
+        // The quote is gross of the aggregator fee, which the tolerance absorbs
+        let min_out =
+            match venue
+                .aggregator
+                .try_find_best_route(token_in, token_out, &trade.amount_in)
+            {
+                Ok(Ok(route)) => {
+                    metadata.insert("hops".to_string(), route.steps.len().to_string());
+                    Self::min_out(route.expected_output, slippage_bps)
+                }
+                failed => {
+                    let message = record_error_code(&failed, &mut metadata, "No aggregator route");
+                    collector.record_error(
+                        OperationType::Swap,
+                        std::time::Duration::ZERO,
+                        message,
+                        metadata,
+                    );
+                    return;
+                }
+            };
+
+        let deadline = env.ledger().timestamp() + 3600;
+        let timer = collector.start_operation().with_budget(env);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            venue.aggregator.try_swap(
+                &venue.trader,
+                token_in,
+                token_out,
+                &trade.amount_in,
+                &min_out,
+                &deadline,
+            )
+        }));
+
+        match result {
+            Ok(Ok(Ok(amount_out))) => {
+                metadata.insert("amount_out".to_string(), amount_out.to_string());
+                record_budget(env, &mut metadata);
+                timer.success(OperationType::Swap, metadata);
+            }
+            Ok(failed) => {
+                record_budget(env, &mut metadata);
+                let message = record_error_code(&failed, &mut metadata, "Aggregator swap failed");
+                timer.error(OperationType::Swap, message, metadata);
+            }
+            Err(_) => {
+                timer.error(
+                    OperationType::Swap,
+                    "Aggregator swap panicked".to_string(),
+                    metadata,
+                );
+            }
+        }
+    }
+
+    fn metadata(venue: &str, index: usize, trade: &Trade) -> HashMap<String, String> {
+        let mut metadata = HashMap::new();
+        metadata.insert(VENUE_KEY.to_string(), venue.to_string());
+        metadata.insert(TRADE_KEY.to_string(), index.to_string());
+        metadata.insert("amount_in".to_string(), trade.amount_in.to_string());
+        metadata
+    }
+
+    fn min_out(quote: i128, slippage_bps: u32) -> i128 {
+        (quote * (BPS - slippage_bps.min(9_999) as i128) / BPS).max(1)
+    }
+
+    /// Run `trades` through both venues, returning how many were attempted
+    fn run_trades(
+        &self,
+        config: &StressConfig,
+        market: &Market,
+        trades: &[Trade],
+        collector: &MetricsCollector,
+    ) -> usize {
+        let slippage_bps = config.router_vs_aggregator.slippage_bps;
+        let router_venue = self.build_venue(config, market);
+        let aggregator_venue = self.build_venue(config, market);
+
+        let test_start = Instant::now();
+        let target_duration = std::time::Duration::from_secs(config.duration_seconds);
+        let mut attempted = 0;
+
+        for (index, trade) in trades.iter().enumerate() {
+            if test_start.elapsed() >= target_duration {
+                println!("  Duration reached, skipping remaining trades");
+                break;
+            }
+
+            self.router_swap(&router_venue, market, index, trade, slippage_bps, collector);
+            self.aggregator_swap(&aggregator_venue, index, trade, slippage_bps, collector);
+            attempted += 1;
+
+            for venue in [&router_venue, &aggregator_venue] {
+                venue.env.ledger().with_mut(|li| {
+                    li.sequence_number += 1;
+                    li.timestamp += 5;
+                });
+            }
+
+            if attempted % 200 == 0 {
+                println!("Progress: {} / {} trades", attempted, trades.len());
+            }
+        }
+        attempted
+    }
+
+    fn print_summary(&self, report: &HeadToHeadReport) {
+        println!("Router vs aggregator:");
+        println!("  Venue      | Trades | Failed | Avg Hops | Avg CPU     | Avg Mem");
+        for (venue, stats) in [
+            ("router", &report.router),
+            ("aggregator", &report.aggregator),
+        ] {
+            println!(
+                "  {:<10} | {:>6} | {:>5.1}% | {:>8.2} | {:>11} | {:>9}",
+                venue,
+                stats.trades,
+                stats.failure_rate * 100.0,
+                stats.avg_hops,
+                stats.avg_cpu_instructions,
+                stats.avg_memory_bytes
+            );
+        }
+        println!(
+            "  Both filled {}: aggregator better {}, router better {}, tied {} ({:+.2} bps avg)",
+            report.both_filled,
+            report.aggregator_better,
+            report.router_better,
+            report.ties,
+            report.avg_output_diff_bps
+        );
+    }
+}
+
+impl Default for RouterVsAggregatorScenario {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StressScenario for RouterVsAggregatorScenario {
+    fn run(&self, config: &StressConfig, collector: &MetricsCollector) {
+        let test_start = Instant::now();
+        let mut rng = rand::thread_rng();
+        let market = Market::random(config, &mut rng);
+        let trades = random_trades(config, &market, &mut rng);
+
+        println!(
+            "Starting router vs aggregator: {} trades over {} tokens, {} pairs",
+            trades.len(),
+            market.graph.num_tokens(),
+            market.graph.edges().len()
+        );
+
+        let attempted = self.run_trades(config, &market, &trades, collector);
+
+        println!(
+            "Router vs aggregator completed: {} trades in {:.2}s",
+            attempted,
+            test_start.elapsed().as_secs_f64()
+        );
+        if let Some(report) = HeadToHeadReport::from_collector(collector) {
+            self.print_summary(&report);
+        }
+    }
+
+    fn name(&self) -> &str {
+        "Router vs Aggregator"
+    }
+
+    fn description(&self) -> &str {
+        "Runs the same trade list through the router and the aggregator and compares them"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_router_vs_aggregator() {
+        let scenario = RouterVsAggregatorScenario::new();
+        let mut config = StressConfig::default();
+        config.router_vs_aggregator.num_trades = 20;
+        config.router_vs_aggregator.graph_tokens = 4;
+
+        let mut rng = rand::thread_rng();
+        let market = Market::random(&config, &mut rng);
+        let trades = random_trades(&config, &market, &mut rng);
+        let collector = MetricsCollector::new();
+        assert_eq!(
+            scenario.run_trades(&config, &market, &trades, &collector),
+            20
+        );
+
+        let report = HeadToHeadReport::from_collector(&collector).unwrap();
+        assert_eq!(report.router.trades, 20);
+        assert_eq!(report.aggregator.trades, 20);
+        // The graph is connected, so the router always has a path
+        assert!(report.router.failure_rate < 1.0);
+        assert!(report.both_filled > 0);
+    }
+
+    #[test]
+    fn test_random_trades_use_distinct_tokens() {
+        let config = StressConfig::default();
+        let mut rng = rand::thread_rng();
+        let market = Market::random(&config, &mut rng);
+
+        assert_eq!(market.reserves.len(), market.graph.edges().len());
+        for trade in random_trades(&config, &market, &mut rng) {
+            assert_ne!(trade.token_in, trade.token_out);
+            assert!(trade.token_out < market.graph.num_tokens());
+        }
+    }
+}
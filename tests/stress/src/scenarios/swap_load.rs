@@ -1,14 +1,18 @@
 //! Swap Load Testing Scenario
 //!
 //! Tests high-frequency swap operations across multiple token pairs.
+//! With `hot_pair_pct` set, that share of swaps hits the first pair so
+//! contention on a hot spot shows up in the per-pair breakdown.
 
 use super::StressScenario;
-use crate::config::StressConfig;
-use crate::metrics::{MetricsCollector, OperationType};
-use crate::utils::{AccountPool, TokenManager};
+use crate::config::{StressConfig, TokenClass};
+use crate::metrics::{record_error_code, MetricsCollector, OperationType};
+use crate::utils::snapshot::{self, World};
+use crate::utils::{record_budget, AccountPool, LedgerClock, LedgerEntrySampler, TokenManager};
 use crate::pair_wasm;
 use astroswap_factory::{AstroSwapFactory, AstroSwapFactoryClient};
 use astroswap_router::{AstroSwapRouter, AstroSwapRouterClient};
+use astroswap_pair::AstroSwapPairClient;
 use astroswap_shared::interfaces::PairClient;
 use rand::Rng;
 use soroban_sdk::{testutils::Address as _, Address, Env};
@@ -22,7 +26,7 @@ impl SwapLoadScenario {
         Self
     }
 
-    /// Setup test environment
+    /// Setup test environment, restoring it from a snapshot when configured
     fn setup_environment(
         &self,
         config: &StressConfig,
@@ -35,21 +39,46 @@ impl SwapLoadScenario {
         AstroSwapRouterClient<'static>,
         Vec<Address>,
     ) {
+        let world = snapshot::load_or_build(config, "swap-load", || self.build_world(config));
+        let factory = AstroSwapFactoryClient::new(&world.env, &world.factory);
+        let router_address = world.router.expect("swap-load world has a router");
+        let router = AstroSwapRouterClient::new(&world.env, &router_address);
+
+        (
+            world.env,
+            world.admin,
+            world.token_manager,
+            world.account_pool,
+            factory,
+            router,
+            world.pairs,
+        )
+    }
+
+    /// Build the test environment from scratch
+    fn build_world(&self, config: &StressConfig) -> World {
         let env = Env::default();
         // Use mock_all_auths_allowing_non_root_auth for contract-to-contract calls (SDK 23)
         env.mock_all_auths_allowing_non_root_auth();
 
         let admin = Address::generate(&env);
 
-        // Create tokens
+        // Create tokens (classes assigned round-robin from config)
         let mut token_manager = TokenManager::new();
-        token_manager.create_tokens(&env, &admin, config.num_pairs * 2, 10_000_000_0000000);
+        token_manager.create_tokens_with_classes(
+            &env,
+            &admin,
+            config.num_pairs * 2,
+            10_000_000_0000000,
+            &config.tokens,
+        );
 
         // Create account pool
-        let account_pool = AccountPool::new(&env, config.num_accounts);
+        let mut account_pool = AccountPool::new(&env, config.num_accounts);
 
-        // Distribute tokens to all accounts
-        token_manager.distribute(&admin, account_pool.all(), 100_000_0000000);
+        // Fund accounts following the configured balance distribution
+        account_pool.assign_balances(&config.accounts, 100_000_0000000);
+        account_pool.fund(&token_manager);
 
         // Deploy pair WASM (SDK 23: use WASM bytes directly)
         let pair_wasm_hash = env.deployer().upload_contract_wasm(pair_wasm::WASM);
@@ -70,9 +99,8 @@ impl SwapLoadScenario {
 
         for i in 0..config.num_pairs.min(pairs.len() as u32) {
             let (token_a_idx, token_b_idx) = pairs[i as usize];
-            let (token_a_addr, token_b_addr) = token_manager
-                .get_pair_addresses(token_a_idx, token_b_idx)
-                .unwrap();
+            let (token_a, token_b) = token_manager.get_pair(token_a_idx, token_b_idx).unwrap();
+            let (token_a_addr, token_b_addr) = (token_a.address.clone(), token_b.address.clone());
 
             // Create pair (SDK 23: client method returns Address directly, use try_create_pair for Result)
             let pair_addr = factory.create_pair(&token_a_addr, &token_b_addr);
@@ -83,56 +111,85 @@ impl SwapLoadScenario {
                 &admin,
                 &token_a_addr,
                 &token_b_addr,
-                &1_000_000_0000000,
-                &1_000_000_0000000,
+                &token_a.to_raw(1_000_000_0000000),
+                &token_b.to_raw(1_000_000_0000000),
                 &0,
                 &0,
                 &(env.ledger().timestamp() + 3600),
             );
         }
 
-        (
+        World {
             env,
             admin,
             token_manager,
             account_pool,
-            factory,
-            router,
-            pair_addresses,
-        )
+            factory: factory_address,
+            router: Some(router_address),
+            pairs: pair_addresses,
+        }
+    }
+
+    /// Pick the pair of the next swap: pair 0 takes `hot_pair_pct` percent
+    /// of swaps and the others share the rest uniformly
+    pub fn select_pair<R: Rng>(rng: &mut R, num_pairs: usize, hot_pair_pct: u32) -> usize {
+        if hot_pair_pct == 0 || num_pairs < 2 {
+            return rng.gen_range(0..num_pairs);
+        }
+        if rng.gen_bool(f64::from(hot_pair_pct.min(100)) / 100.0) {
+            0
+        } else {
+            rng.gen_range(1..num_pairs)
+        }
     }
 
     /// Execute a single swap operation
+    #[allow(clippy::too_many_arguments)]
     fn execute_swap(
         &self,
         env: &Env,
+        pair_idx: usize,
         pair_address: &Address,
         user: &Address,
+        account: &str,
         token_in: &Address,
+        token_class: TokenClass,
         amount_in: i128,
         min_out: i128,
         collector: &MetricsCollector,
     ) {
-        let timer = collector.start_operation();
-        let pair_client = PairClient::new(env, pair_address);
-
-        match pair_client.swap(user, token_in, amount_in, min_out) {
-            Ok(amount_out) => {
-                let mut metadata = HashMap::new();
+        let timer = collector
+            .start_operation()
+            .for_account(account)
+            .with_budget(env);
+        let pair_client = AstroSwapPairClient::new(env, pair_address);
+        let deadline = env.ledger().timestamp() + 3600;
+
+        let mut metadata = HashMap::new();
+        metadata.insert("token_class".to_string(), token_class.as_str().to_string());
+        metadata.insert("pair".to_string(), pair_idx.to_string());
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            pair_client.try_swap(user, token_in, &amount_in, &min_out, &deadline)
+        }));
+
+        match result {
+            Ok(Ok(Ok(amount_out))) => {
                 metadata.insert("amount_in".to_string(), amount_in.to_string());
                 metadata.insert("amount_out".to_string(), amount_out.to_string());
                 metadata.insert(
                     "slippage_bps".to_string(),
                     (((amount_in - amount_out) * 10000) / amount_in).to_string(),
                 );
+                record_budget(env, &mut metadata);
                 timer.success(OperationType::Swap, metadata);
             }
-            Err(e) => {
-                timer.error(
-                    OperationType::Swap,
-                    format!("Swap failed: {:?}", e),
-                    HashMap::new(),
-                );
+            Ok(failed) => {
+                let message = record_error_code(&failed, &mut metadata, "Swap failed");
+                timer.error(OperationType::Swap, message, metadata);
+            }
+            Err(_) => {
+                timer.error(OperationType::Swap, "Swap failed".to_string(), metadata);
             }
         }
     }
@@ -146,8 +203,9 @@ impl Default for SwapLoadScenario {
 
 impl StressScenario for SwapLoadScenario {
     fn run(&self, config: &StressConfig, collector: &MetricsCollector) {
-        let (env, _admin, _token_manager, account_pool, _factory, _router, pair_addresses) =
+        let (env, _admin, token_manager, account_pool, _factory, _router, pair_addresses) =
             self.setup_environment(config);
+        account_pool.register(collector);
 
         let test_start = Instant::now();
         let target_duration = std::time::Duration::from_secs(config.duration_seconds);
@@ -155,11 +213,19 @@ impl StressScenario for SwapLoadScenario {
 
         let mut rng = rand::thread_rng();
         let mut operation_count = 0u64;
+        let mut clock = LedgerClock::new(&env, &config.time_warp);
+        let mut entry_sampler = LedgerEntrySampler::new(&config.leak_detection);
 
         println!(
             "Starting swap load test: target {} TPS for {} seconds",
             config.target_tps, config.duration_seconds
         );
+        if swap_config.hot_pair_pct > 0 {
+            println!(
+                "  {}% of swaps on the hottest pair",
+                swap_config.hot_pair_pct.min(100)
+            );
+        }
 
         while test_start.elapsed() < target_duration {
             let iteration_start = Instant::now();
@@ -168,8 +234,9 @@ impl StressScenario for SwapLoadScenario {
             let swaps_per_iteration = config.target_tps as usize;
 
             for _ in 0..swaps_per_iteration {
-                // Select random pair
-                let pair_idx = rng.gen_range(0..pair_addresses.len());
+                // Select a pair, concentrating on the hot one if configured
+                let pair_idx =
+                    Self::select_pair(&mut rng, pair_addresses.len(), swap_config.hot_pair_pct);
                 let pair_address = &pair_addresses[pair_idx];
 
                 // Get pair tokens
@@ -184,35 +251,52 @@ impl StressScenario for SwapLoadScenario {
                     &token_0
                 };
 
-                // Generate random amount
+                // Generate random amount (scaled to the input token's decimals)
+                let token_info = token_manager.info_of(token_in);
+                let token_class = token_manager.class_of(token_in);
                 let amount_in = rng.gen_range(
                     swap_config.min_swap_amount..=swap_config.max_swap_amount,
                 );
+                let amount_in = token_info.map(|t| t.to_raw(amount_in)).unwrap_or(amount_in);
 
                 // Calculate minimum output with slippage
                 let slippage_bps = rng.gen_range(
                     swap_config.min_slippage_bps..=swap_config.max_slippage_bps,
                 );
 
-                // Estimate output (simplified)
-                let (reserve_in, reserve_out) = pair_client.get_reserves();
-                let expected_out = if token_in == &token_0 {
-                    (amount_in * 9970 * reserve_out) / (reserve_in * 10000 + amount_in * 9970)
+                // Estimate output (simplified, checked so high-decimal reserves can't overflow)
+                let (reserve_0, reserve_1) = pair_client.get_reserves();
+                let (reserve_in, reserve_out) = if token_in == &token_0 {
+                    (reserve_0, reserve_1)
                 } else {
-                    (amount_in * 9970 * reserve_in) / (reserve_out * 10000 + amount_in * 9970)
+                    (reserve_1, reserve_0)
                 };
+                let amount_in_with_fee = amount_in.saturating_mul(9970);
+                let expected_out = amount_in_with_fee
+                    .checked_mul(reserve_out)
+                    .and_then(|n| {
+                        reserve_in
+                            .checked_mul(10000)
+                            .and_then(|d| d.checked_add(amount_in_with_fee))
+                            .and_then(|d| n.checked_div(d))
+                    })
+                    .unwrap_or(0);
 
                 let min_out = (expected_out * (10000 - slippage_bps as i128)) / 10000;
 
                 // Select random user
-                let user = account_pool.random();
+                let user_idx = account_pool.random_index();
+                let user = account_pool.get_account(user_idx);
 
                 // Execute swap
                 self.execute_swap(
                     &env,
+                    pair_idx,
                     pair_address,
                     user,
+                    &account_pool.label(user_idx),
                     token_in,
+                    token_class,
                     amount_in,
                     min_out,
                     collector,
@@ -220,12 +304,28 @@ impl StressScenario for SwapLoadScenario {
 
                 operation_count += 1;
 
+                // Accelerated ledger time between operations (no-op unless enabled)
+                clock.tick(&env);
+
                 // Check if we should stop
                 if test_start.elapsed() >= target_duration {
                     break;
                 }
             }
 
+            // Top up accounts drained by the previous iteration
+            account_pool.refill_depleted(&token_manager, collector);
+
+            // Ledger entries once per window of operations, for leak detection
+            entry_sampler.sample(&env, collector);
+
+            // Pause / blocklist toggles on hostile token classes
+            token_manager.apply_hostile_events(
+                account_pool.all(),
+                config.tokens.hostile_toggle_probability,
+                &mut rng,
+            );
+
             // Rate limiting: wait to achieve target TPS
             let iteration_duration = iteration_start.elapsed();
             let target_iteration_duration = std::time::Duration::from_secs(1);
@@ -249,6 +349,7 @@ impl StressScenario for SwapLoadScenario {
             collector.total_operations(),
             test_start.elapsed().as_secs_f64()
         );
+        clock.print_summary();
     }
 
     fn name(&self) -> &str {
@@ -284,4 +385,28 @@ mod tests {
             collector.success_rate() * 100.0
         );
     }
+
+    #[test]
+    fn test_select_pair_concentrates_on_hot_pair() {
+        let mut rng = rand::thread_rng();
+        let mut counts = [0u32; 4];
+        for _ in 0..10_000 {
+            counts[SwapLoadScenario::select_pair(&mut rng, 4, 80)] += 1;
+        }
+        // ~8000 on the hot pair, ~667 on each of the others
+        assert!((7_500..8_500).contains(&counts[0]), "{:?}", counts);
+        assert!(
+            counts[1..].iter().all(|&c| (400..950).contains(&c)),
+            "{:?}",
+            counts
+        );
+
+        // A single pair takes every swap, 0% spreads them uniformly
+        assert_eq!(SwapLoadScenario::select_pair(&mut rng, 1, 80), 0);
+        let mut uniform = [0u32; 2];
+        for _ in 0..1_000 {
+            uniform[SwapLoadScenario::select_pair(&mut rng, 2, 0)] += 1;
+        }
+        assert!(uniform.iter().all(|&c| c > 300), "{:?}", uniform);
+    }
 }
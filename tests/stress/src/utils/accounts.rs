@@ -2,6 +2,9 @@
 //!
 //! Utilities for generating and managing test accounts for stress testing.
 
+use super::tokens::TokenManager;
+use crate::config::{AccountConfig, BalanceDistribution};
+use crate::metrics::MetricsCollector;
 use soroban_sdk::{testutils::Address as _, Address, Env};
 use std::collections::HashMap;
 
@@ -10,6 +13,8 @@ pub struct AccountPool {
     accounts: Vec<Address>,
     account_map: HashMap<String, usize>,
     next_index: usize,
+    target_balances: Vec<i128>,
+    refill_threshold_bps: u32,
 }
 
 impl AccountPool {
@@ -28,9 +33,110 @@ impl AccountPool {
             accounts,
             account_map,
             next_index: 0,
+            target_balances: Vec::new(),
+            refill_threshold_bps: 0,
         }
     }
 
+    /// Rebuild a pool from previously generated accounts (e.g. a restored snapshot)
+    pub fn restore(accounts: Vec<Address>, target_balances: Vec<i128>, refill_threshold_bps: u32) -> Self {
+        let account_map = (0..accounts.len())
+            .map(|i| (format!("account_{}", i), i))
+            .collect();
+
+        Self {
+            accounts,
+            account_map,
+            next_index: 0,
+            target_balances,
+            refill_threshold_bps,
+        }
+    }
+
+    /// Per-account target balances (empty if never assigned)
+    pub fn target_balances(&self) -> &[i128] {
+        &self.target_balances
+    }
+
+    /// Refill threshold in basis points of the target balance (0 = refill disabled)
+    pub fn refill_threshold_bps(&self) -> u32 {
+        self.refill_threshold_bps
+    }
+
+    /// Assign per-account target balances following the configured distribution.
+    ///
+    /// Balances are scaled so the average account holds `mean_balance` of each
+    /// token; account 0 is always the wealthiest under skewed distributions.
+    pub fn assign_balances(&mut self, config: &AccountConfig, mean_balance: i128) {
+        let weights = config.distribution.weights(self.accounts.len());
+        let total_weight: f64 = weights.iter().sum();
+        let scale = mean_balance as f64 * self.accounts.len() as f64 / total_weight;
+
+        self.target_balances = weights
+            .iter()
+            .map(|w| ((w * scale) as i128).max(1))
+            .collect();
+        self.refill_threshold_bps = if config.refill_enabled {
+            config.refill_threshold_bps
+        } else {
+            0
+        };
+    }
+
+    /// Mint every account its target balance of each token
+    pub fn fund(&self, token_manager: &TokenManager) {
+        for (index, account) in self.accounts.iter().enumerate() {
+            token_manager.mint_all(account, self.target_balance(index));
+        }
+    }
+
+    /// Register target balances with the collector for per-account reporting
+    pub fn register(&self, collector: &MetricsCollector) {
+        for index in 0..self.accounts.len() {
+            collector.register_account(self.label(index), self.target_balance(index));
+        }
+    }
+
+    /// Top up accounts whose balance of any token fell below the refill threshold.
+    ///
+    /// Returns the number of accounts refilled. Does nothing when refill is disabled.
+    pub fn refill_depleted(&self, token_manager: &TokenManager, collector: &MetricsCollector) -> u32 {
+        if self.refill_threshold_bps == 0 {
+            return 0;
+        }
+
+        let mut refilled = 0;
+        for (index, account) in self.accounts.iter().enumerate() {
+            let target = self.target_balance(index);
+            let threshold = target * self.refill_threshold_bps as i128 / 10_000;
+            let mut depleted = false;
+
+            for token_index in 0..token_manager.len() {
+                let balance = token_manager.balance_nominal(token_index, account);
+                if balance < threshold {
+                    token_manager.mint(token_index, account, target - balance);
+                    depleted = true;
+                }
+            }
+
+            if depleted {
+                collector.record_refill(&self.label(index));
+                refilled += 1;
+            }
+        }
+        refilled
+    }
+
+    /// Target balance of an account (0 if balances were never assigned)
+    pub fn target_balance(&self, index: usize) -> i128 {
+        self.target_balances.get(index).copied().unwrap_or(0)
+    }
+
+    /// Stable label of an account, used as the per-account report key
+    pub fn label(&self, index: usize) -> String {
+        format!("account_{}", index % self.accounts.len().max(1))
+    }
+
     /// Get the total number of accounts
     pub fn len(&self) -> usize {
         self.accounts.len()
@@ -68,6 +174,12 @@ impl AccountPool {
         &self.accounts[index]
     }
 
+    /// Get a random account index
+    pub fn random_index(&self) -> usize {
+        use rand::Rng;
+        rand::thread_rng().gen_range(0..self.accounts.len())
+    }
+
     /// Get all accounts
     pub fn all(&self) -> &[Address] {
         &self.accounts
@@ -159,4 +271,57 @@ mod tests {
         let slice = pool.slice(8, 15);
         assert_eq!(slice.len(), 2); // Only 8 and 9 exist
     }
+
+    #[test]
+    fn test_assign_uniform_balances() {
+        let env = Env::default();
+        let mut pool = AccountPool::new(&env, 4);
+
+        pool.assign_balances(&AccountConfig::default(), 1_000);
+
+        for i in 0..4 {
+            assert_eq!(pool.target_balance(i), 1_000);
+        }
+    }
+
+    #[test]
+    fn test_assign_skewed_balances() {
+        let env = Env::default();
+        let mut pool = AccountPool::new(&env, 10);
+        let config = AccountConfig {
+            distribution: BalanceDistribution::Zipf { exponent: 1.2 },
+            ..AccountConfig::default()
+        };
+
+        pool.assign_balances(&config, 1_000_000);
+
+        // Whale first, strictly decreasing, mean preserved (within rounding)
+        for i in 1..10 {
+            assert!(pool.target_balance(i - 1) > pool.target_balance(i));
+        }
+        let total: i128 = (0..10).map(|i| pool.target_balance(i)).sum();
+        assert!((total - 10_000_000).abs() <= 10);
+    }
+
+    #[test]
+    fn test_fund_and_refill() {
+        let env = Env::default();
+        env.mock_all_auths_allowing_non_root_auth();
+        let admin = Address::generate(&env);
+        let collector = MetricsCollector::new();
+
+        let mut tokens = TokenManager::new();
+        tokens.create_tokens(&env, &admin, 1, 1_000_000);
+
+        let mut pool = AccountPool::new(&env, 2);
+        pool.assign_balances(&AccountConfig::default(), 1_000);
+        pool.fund(&tokens);
+        assert_eq!(tokens.balance(0, pool.get(0).unwrap()), 1_000);
+
+        // Drain account 0 below the refill threshold
+        tokens.distribute_token(0, pool.get(0).unwrap(), &[admin.clone()], 950);
+        assert_eq!(pool.refill_depleted(&tokens, &collector), 1);
+        assert_eq!(tokens.balance(0, pool.get(0).unwrap()), 1_000);
+        assert_eq!(collector.refill_counts().get("account_0"), Some(&1));
+    }
 }
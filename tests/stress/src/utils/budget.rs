@@ -0,0 +1,49 @@
+//! Budget Measurement
+//!
+//! Helpers for attaching Soroban budget consumption to operation metadata, so
+//! the cost model can project fees from what each operation actually used.
+
+use serde::{Deserialize, Serialize};
+use soroban_sdk::Env;
+use std::collections::HashMap;
+
+/// Metadata key for CPU instructions consumed by an operation
+pub const CPU_INSTRUCTIONS_KEY: &str = "cpu_instructions";
+
+/// Metadata key for memory bytes consumed by an operation
+pub const MEMORY_BYTES_KEY: &str = "memory_bytes";
+
+/// How operation budgets are attributed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BudgetMode {
+    /// Scenarios read the budget after each successful operation. Host work
+    /// done since the last invocation (test-side mints, ledger edits) counts
+    /// towards the reading, and failed operations record nothing.
+    #[default]
+    Shared,
+    /// Timers reset the budget tracker when the operation starts and record
+    /// the budget when it completes, failed operations included. Costs one
+    /// reset per operation.
+    Isolated,
+}
+
+/// Zero the budget's CPU and memory counters, keeping its limits
+pub fn reset_budget(env: &Env) {
+    env.cost_estimate().budget().reset_tracker();
+}
+
+/// Record the budget of the last top-level invocation into `metadata`.
+///
+/// The test `Env` resets the budget before every top-level invocation, so this
+/// must be called right after the operation, before any other contract call.
+pub fn record_budget(env: &Env, metadata: &mut HashMap<String, String>) {
+    let budget = env.cost_estimate().budget();
+    metadata.insert(
+        CPU_INSTRUCTIONS_KEY.to_string(),
+        budget.cpu_instruction_cost().to_string(),
+    );
+    metadata.insert(
+        MEMORY_BYTES_KEY.to_string(),
+        budget.memory_bytes_cost().to_string(),
+    );
+}
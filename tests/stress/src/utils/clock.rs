@@ -0,0 +1,220 @@
+//! Ledger Time Control
+//!
+//! Advances `env.ledger()` on a schedule so time-dependent behaviour (staking
+//! emissions, deadlines, TTL expiry) is exercised under accelerated time instead
+//! of real-time sleeps.
+
+use crate::config::{TimeSchedule, TimeWarpConfig};
+use soroban_sdk::testutils::Ledger as _;
+use soroban_sdk::Env;
+
+/// Seconds per ledger close on Stellar
+pub const LEDGER_CLOSE_SECONDS: u64 = 5;
+
+/// Outcome of one clock step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeStep {
+    /// Regular forward step
+    Advanced(u64),
+    /// Large forward jump (bursty schedule)
+    Burst(u64),
+    /// Deliberate rewind (backwards schedule)
+    Rewound(u64),
+}
+
+/// Drives the ledger clock of one `Env`
+pub struct LedgerClock {
+    config: TimeWarpConfig,
+    steps: u64,
+    last_timestamp: u64,
+    advanced_seconds: u64,
+    bursts: u64,
+    rewinds: u64,
+    backwards_detected: u64,
+}
+
+impl LedgerClock {
+    pub fn new(env: &Env, config: &TimeWarpConfig) -> Self {
+        Self {
+            config: config.clone(),
+            steps: 0,
+            last_timestamp: env.ledger().timestamp(),
+            advanced_seconds: 0,
+            bursts: 0,
+            rewinds: 0,
+            backwards_detected: 0,
+        }
+    }
+
+    /// Advance the ledger by one scheduled step. Does nothing when disabled.
+    pub fn tick(&mut self, env: &Env) -> Option<TimeStep> {
+        if !self.config.enabled {
+            return None;
+        }
+        self.observe(env);
+        self.steps += 1;
+
+        let step = self.next_step();
+        match step {
+            TimeStep::Advanced(seconds) | TimeStep::Burst(seconds) => {
+                env.ledger().with_mut(|li| {
+                    li.timestamp += seconds;
+                    li.sequence_number += (seconds / LEDGER_CLOSE_SECONDS).max(1) as u32;
+                });
+                self.advanced_seconds += seconds;
+                if matches!(step, TimeStep::Burst(_)) {
+                    self.bursts += 1;
+                }
+            }
+            TimeStep::Rewound(seconds) => {
+                // Ledger sequence never goes back; only the close time does
+                env.ledger().with_mut(|li| {
+                    li.timestamp = li.timestamp.saturating_sub(seconds);
+                    li.sequence_number += 1;
+                });
+                self.rewinds += 1;
+            }
+        }
+
+        self.last_timestamp = env.ledger().timestamp();
+        Some(step)
+    }
+
+    /// Check the ledger against the last timestamp this clock set and count
+    /// backwards movement it did not schedule itself
+    pub fn observe(&mut self, env: &Env) -> bool {
+        let now = env.ledger().timestamp();
+        let backwards = now < self.last_timestamp;
+        if backwards {
+            self.backwards_detected += 1;
+        }
+        self.last_timestamp = now;
+        backwards
+    }
+
+    fn next_step(&self) -> TimeStep {
+        let config = &self.config;
+        match config.schedule {
+            TimeSchedule::Linear => TimeStep::Advanced(config.seconds_per_step),
+            TimeSchedule::Bursty if config.burst_every > 0 && self.steps % config.burst_every == 0 => {
+                TimeStep::Burst(config.burst_seconds)
+            }
+            TimeSchedule::Backwards if config.rewind_every > 0 && self.steps % config.rewind_every == 0 => {
+                TimeStep::Rewound(config.rewind_seconds)
+            }
+            TimeSchedule::Bursty | TimeSchedule::Backwards => TimeStep::Advanced(config.seconds_per_step),
+        }
+    }
+
+    /// Simulated seconds moved forward
+    pub fn advanced_seconds(&self) -> u64 {
+        self.advanced_seconds
+    }
+
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    pub fn bursts(&self) -> u64 {
+        self.bursts
+    }
+
+    pub fn rewinds(&self) -> u64 {
+        self.rewinds
+    }
+
+    /// Backwards movements not scheduled by this clock
+    pub fn backwards_detected(&self) -> u64 {
+        self.backwards_detected
+    }
+
+    /// Print a one-line summary of the simulated time
+    pub fn print_summary(&self) {
+        if !self.config.enabled {
+            return;
+        }
+        println!(
+            "Time warp ({}): {} steps, {:.1}h simulated, {} bursts, {} rewinds, {} unscheduled backwards moves",
+            self.config.schedule.as_str(),
+            self.steps,
+            self.advanced_seconds as f64 / 3600.0,
+            self.bursts,
+            self.rewinds,
+            self.backwards_detected
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(schedule: TimeSchedule) -> TimeWarpConfig {
+        TimeWarpConfig {
+            enabled: true,
+            schedule,
+            seconds_per_step: 10,
+            burst_seconds: 3600,
+            burst_every: 4,
+            rewind_seconds: 30,
+            rewind_every: 3,
+        }
+    }
+
+    #[test]
+    fn test_linear_schedule() {
+        let env = Env::default();
+        let start = env.ledger().timestamp();
+        let start_sequence = env.ledger().sequence();
+        let mut clock = LedgerClock::new(&env, &config(TimeSchedule::Linear));
+
+        for _ in 0..6 {
+            assert_eq!(clock.tick(&env), Some(TimeStep::Advanced(10)));
+        }
+        assert_eq!(env.ledger().timestamp(), start + 60);
+        assert_eq!(env.ledger().sequence(), start_sequence + 12);
+        assert_eq!(clock.advanced_seconds(), 60);
+    }
+
+    #[test]
+    fn test_bursty_schedule() {
+        let env = Env::default();
+        let mut clock = LedgerClock::new(&env, &config(TimeSchedule::Bursty));
+
+        let steps: Vec<_> = (0..8).filter_map(|_| clock.tick(&env)).collect();
+        assert_eq!(steps[3], TimeStep::Burst(3600));
+        assert_eq!(clock.bursts(), 2);
+        assert_eq!(clock.advanced_seconds(), 6 * 10 + 2 * 3600);
+    }
+
+    #[test]
+    fn test_backwards_detection() {
+        let env = Env::default();
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let mut clock = LedgerClock::new(&env, &config(TimeSchedule::Backwards));
+
+        clock.tick(&env);
+        clock.tick(&env);
+        assert_eq!(clock.tick(&env), Some(TimeStep::Rewound(30)));
+        assert_eq!(env.ledger().timestamp(), 1_000 + 20 - 30);
+        // Scheduled rewinds are not reported as unexpected
+        assert!(!clock.observe(&env));
+
+        env.ledger().with_mut(|li| li.timestamp -= 1);
+        assert!(clock.observe(&env));
+        assert_eq!(clock.backwards_detected(), 1);
+        assert_eq!(clock.rewinds(), 1);
+    }
+
+    #[test]
+    fn test_disabled_clock() {
+        let env = Env::default();
+        let start = env.ledger().timestamp();
+        let mut disabled = config(TimeSchedule::Linear);
+        disabled.enabled = false;
+        let mut clock = LedgerClock::new(&env, &disabled);
+
+        assert_eq!(clock.tick(&env), None);
+        assert_eq!(env.ledger().timestamp(), start);
+    }
+}
@@ -0,0 +1,38 @@
+//! Ledger Entry Sampling
+//!
+//! Counts the ledger entries held by a scenario's `Env` once per window of
+//! operations, so `metrics::leaks` can flag entries that keep piling up over a
+//! long run.
+
+use crate::config::LeakDetectionConfig;
+use crate::metrics::MetricsCollector;
+use soroban_sdk::Env;
+
+/// Samples the ledger-entry count of one `Env`
+pub struct LedgerEntrySampler {
+    enabled: bool,
+    window_ops: usize,
+    next_sample: usize,
+}
+
+impl LedgerEntrySampler {
+    pub fn new(config: &LeakDetectionConfig) -> Self {
+        Self {
+            enabled: config.enabled,
+            window_ops: config.window_ops.max(1) as usize,
+            next_sample: 0,
+        }
+    }
+
+    /// Record the entry count if a full window of operations was recorded since
+    /// the last sample. Cheap to call after every batch.
+    pub fn sample(&mut self, env: &Env, collector: &MetricsCollector) {
+        let operations = collector.total_operations();
+        if !self.enabled || operations < self.next_sample {
+            return;
+        }
+        let entries = env.to_ledger_snapshot().ledger_entries.len();
+        collector.record_ledger_entries(entries as u64);
+        self.next_sample = operations + self.window_ops;
+    }
+}
@@ -0,0 +1,337 @@
+//! Configurable Mock Token
+//!
+//! SEP-41 style token used to exercise the DEX with hostile or unusual assets:
+//! transfer fees, arbitrary decimals, blocklisted holders and pausing.
+//! Registered natively in the test `Env`, so no WASM build is required.
+
+use soroban_sdk::{contract, contracterror, contractimpl, contracttype, Address, Env, String};
+
+/// Storage keys for the mock token
+#[contracttype]
+#[derive(Clone)]
+enum DataKey {
+    Admin,
+    Decimals,
+    Name,
+    Symbol,
+    FeeBps,
+    Paused,
+    TotalSupply,
+    Balance(Address),
+    Allowance(Address, Address),
+    Blocked(Address),
+}
+
+/// Mock token errors
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum MockTokenError {
+    InsufficientBalance = 1,
+    InsufficientAllowance = 2,
+    Paused = 3,
+    Blocked = 4,
+    InvalidAmount = 5,
+}
+
+#[contract]
+pub struct MockToken;
+
+#[contractimpl]
+impl MockToken {
+    /// Initialize the token. `fee_bps` is withheld (burned) on every transfer.
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        decimals: u32,
+        name: String,
+        symbol: String,
+        fee_bps: u32,
+    ) {
+        let storage = env.storage().instance();
+        storage.set(&DataKey::Admin, &admin);
+        storage.set(&DataKey::Decimals, &decimals);
+        storage.set(&DataKey::Name, &name);
+        storage.set(&DataKey::Symbol, &symbol);
+        storage.set(&DataKey::FeeBps, &fee_bps.min(10_000));
+        storage.set(&DataKey::Paused, &false);
+        storage.set(&DataKey::TotalSupply, &0i128);
+    }
+
+    // ==================== Admin ====================
+
+    /// Mint tokens (same signature as the Stellar Asset Contract)
+    pub fn mint(env: Env, to: Address, amount: i128) -> Result<(), MockTokenError> {
+        Self::admin(&env).require_auth();
+        if amount < 0 {
+            return Err(MockTokenError::InvalidAmount);
+        }
+
+        Self::set_balance(&env, &to, Self::balance(env.clone(), to.clone()) + amount);
+        let supply = Self::total_supply(env.clone());
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalSupply, &(supply + amount));
+        Ok(())
+    }
+
+    /// Change the transfer fee
+    pub fn set_fee_bps(env: Env, fee_bps: u32) {
+        Self::admin(&env).require_auth();
+        env.storage()
+            .instance()
+            .set(&DataKey::FeeBps, &fee_bps.min(10_000));
+    }
+
+    /// Pause or unpause all transfers
+    pub fn set_paused(env: Env, paused: bool) {
+        Self::admin(&env).require_auth();
+        env.storage().instance().set(&DataKey::Paused, &paused);
+    }
+
+    /// Block or unblock an address from sending and receiving
+    pub fn set_blocked(env: Env, account: Address, blocked: bool) {
+        Self::admin(&env).require_auth();
+        env.storage()
+            .persistent()
+            .set(&DataKey::Blocked(account), &blocked);
+    }
+
+    // ==================== SEP-41 ====================
+
+    pub fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Allowance(from, spender))
+            .unwrap_or(0)
+    }
+
+    pub fn approve(
+        env: Env,
+        from: Address,
+        spender: Address,
+        amount: i128,
+        _expiration_ledger: u32,
+    ) -> Result<(), MockTokenError> {
+        from.require_auth();
+        if amount < 0 {
+            return Err(MockTokenError::InvalidAmount);
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::Allowance(from, spender), &amount);
+        Ok(())
+    }
+
+    pub fn balance(env: Env, id: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Balance(id))
+            .unwrap_or(0)
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, amount: i128) -> Result<(), MockTokenError> {
+        from.require_auth();
+        Self::move_balance(&env, &from, &to, amount)
+    }
+
+    pub fn transfer_from(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        amount: i128,
+    ) -> Result<(), MockTokenError> {
+        spender.require_auth();
+        Self::spend_allowance(&env, &from, &spender, amount)?;
+        Self::move_balance(&env, &from, &to, amount)
+    }
+
+    pub fn burn(env: Env, from: Address, amount: i128) -> Result<(), MockTokenError> {
+        from.require_auth();
+        Self::burn_balance(&env, &from, amount)
+    }
+
+    pub fn burn_from(
+        env: Env,
+        spender: Address,
+        from: Address,
+        amount: i128,
+    ) -> Result<(), MockTokenError> {
+        spender.require_auth();
+        Self::spend_allowance(&env, &from, &spender, amount)?;
+        Self::burn_balance(&env, &from, amount)
+    }
+
+    pub fn decimals(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::Decimals).unwrap_or(7)
+    }
+
+    pub fn name(env: Env) -> String {
+        env.storage().instance().get(&DataKey::Name).unwrap()
+    }
+
+    pub fn symbol(env: Env) -> String {
+        env.storage().instance().get(&DataKey::Symbol).unwrap()
+    }
+
+    pub fn total_supply(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalSupply)
+            .unwrap_or(0)
+    }
+
+    pub fn fee_bps(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::FeeBps).unwrap_or(0)
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        env.storage().instance().get(&DataKey::Paused).unwrap_or(false)
+    }
+
+    pub fn is_blocked(env: Env, account: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Blocked(account))
+            .unwrap_or(false)
+    }
+
+    // ==================== Internal ====================
+
+    fn admin(env: &Env) -> Address {
+        env.storage().instance().get(&DataKey::Admin).unwrap()
+    }
+
+    fn set_balance(env: &Env, id: &Address, amount: i128) {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Balance(id.clone()), &amount);
+    }
+
+    fn spend_allowance(
+        env: &Env,
+        from: &Address,
+        spender: &Address,
+        amount: i128,
+    ) -> Result<(), MockTokenError> {
+        let allowance = Self::allowance(env.clone(), from.clone(), spender.clone());
+        if allowance < amount {
+            return Err(MockTokenError::InsufficientAllowance);
+        }
+        env.storage().persistent().set(
+            &DataKey::Allowance(from.clone(), spender.clone()),
+            &(allowance - amount),
+        );
+        Ok(())
+    }
+
+    /// Move `amount` out of `from`; the recipient gets it minus the transfer fee,
+    /// which is burned
+    fn move_balance(env: &Env, from: &Address, to: &Address, amount: i128) -> Result<(), MockTokenError> {
+        if amount < 0 {
+            return Err(MockTokenError::InvalidAmount);
+        }
+        if Self::is_paused(env.clone()) {
+            return Err(MockTokenError::Paused);
+        }
+        if Self::is_blocked(env.clone(), from.clone()) || Self::is_blocked(env.clone(), to.clone()) {
+            return Err(MockTokenError::Blocked);
+        }
+
+        let from_balance = Self::balance(env.clone(), from.clone());
+        if from_balance < amount {
+            return Err(MockTokenError::InsufficientBalance);
+        }
+
+        let fee = amount * Self::fee_bps(env.clone()) as i128 / 10_000;
+        Self::set_balance(env, from, from_balance - amount);
+        Self::set_balance(env, to, Self::balance(env.clone(), to.clone()) + amount - fee);
+
+        if fee > 0 {
+            let supply = Self::total_supply(env.clone());
+            env.storage()
+                .instance()
+                .set(&DataKey::TotalSupply, &(supply - fee));
+        }
+        Ok(())
+    }
+
+    fn burn_balance(env: &Env, from: &Address, amount: i128) -> Result<(), MockTokenError> {
+        if amount < 0 {
+            return Err(MockTokenError::InvalidAmount);
+        }
+        let balance = Self::balance(env.clone(), from.clone());
+        if balance < amount {
+            return Err(MockTokenError::InsufficientBalance);
+        }
+        Self::set_balance(env, from, balance - amount);
+        let supply = Self::total_supply(env.clone());
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalSupply, &(supply - amount));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+
+    fn setup(env: &Env, fee_bps: u32) -> (Address, MockTokenClient<'_>) {
+        env.mock_all_auths();
+        let admin = Address::generate(env);
+        let id = env.register(MockToken, ());
+        let client = MockTokenClient::new(env, &id);
+        client.initialize(
+            &admin,
+            &7,
+            &String::from_str(env, "Mock"),
+            &String::from_str(env, "MOCK"),
+            &fee_bps,
+        );
+        (admin, client)
+    }
+
+    #[test]
+    fn test_fee_on_transfer() {
+        let env = Env::default();
+        let (_admin, token) = setup(&env, 100); // 1%
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+
+        token.mint(&alice, &10_000);
+        token.transfer(&alice, &bob, &1_000);
+
+        assert_eq!(token.balance(&alice), 9_000);
+        assert_eq!(token.balance(&bob), 990);
+        assert_eq!(token.total_supply(), 9_990);
+    }
+
+    #[test]
+    fn test_paused_and_blocked() {
+        let env = Env::default();
+        let (_admin, token) = setup(&env, 0);
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        token.mint(&alice, &1_000);
+
+        token.set_paused(&true);
+        assert_eq!(
+            token.try_transfer(&alice, &bob, &10),
+            Err(Ok(MockTokenError::Paused))
+        );
+
+        token.set_paused(&false);
+        token.set_blocked(&bob, &true);
+        assert_eq!(
+            token.try_transfer(&alice, &bob, &10),
+            Err(Ok(MockTokenError::Blocked))
+        );
+
+        token.set_blocked(&bob, &false);
+        token.transfer(&alice, &bob, &10);
+        assert_eq!(token.balance(&bob), 10);
+    }
+}
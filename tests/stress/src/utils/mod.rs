@@ -3,7 +3,17 @@
 //! Common utilities for stress testing including account management and token setup.
 
 pub mod accounts;
+pub mod budget;
+pub mod clock;
+pub mod ledger_entries;
+pub mod mock_token;
+pub mod snapshot;
 pub mod tokens;
 
 pub use accounts::AccountPool;
-pub use tokens::TokenManager;
+pub use budget::{record_budget, reset_budget, BudgetMode};
+pub use clock::{LedgerClock, TimeStep};
+pub use ledger_entries::LedgerEntrySampler;
+pub use mock_token::{MockToken, MockTokenClient, MockTokenError};
+pub use snapshot::{World, WorldSnapshot};
+pub use tokens::{TokenInfo, TokenManager};
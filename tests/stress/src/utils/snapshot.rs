@@ -0,0 +1,253 @@
+//! Environment Snapshots
+//!
+//! Saves a fully set-up world (tokens, accounts, factory, router, pairs with
+//! liquidity) to disk with the `Env` snapshot facilities and restores it at the
+//! start of later runs, so iterative runs skip the expensive setup.
+//!
+//! Each snapshot is two files in the snapshot directory:
+//! - `<key>.env.json` - the `Env` snapshot (ledger entries, generators)
+//! - `<key>.world.json` - addresses and bookkeeping needed to rebuild helpers
+//!
+//! The key hashes the scenario name, the setup-relevant configuration and the
+//! pair WASM, so changing any of them builds (and saves) a fresh world.
+
+use super::accounts::AccountPool;
+use super::tokens::{TokenInfo, TokenManager};
+use crate::config::{StressConfig, TokenClass};
+use crate::pair_wasm;
+use astroswap_factory::{AstroSwapFactory, AstroSwapFactoryClient};
+use astroswap_router::AstroSwapRouter;
+use serde::{Deserialize, Serialize};
+use soroban_sdk::{Address, Env, String as SorobanString};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A set-up scenario world
+pub struct World {
+    pub env: Env,
+    pub admin: Address,
+    pub token_manager: TokenManager,
+    pub account_pool: AccountPool,
+    pub factory: Address,
+    pub router: Option<Address>,
+    pub pairs: Vec<Address>,
+}
+
+/// Serializable token description
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SavedToken {
+    address: String,
+    name: String,
+    decimals: u32,
+    class: TokenClass,
+}
+
+/// Serializable part of a world (everything except the ledger itself)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct WorldManifest {
+    key: String,
+    admin: String,
+    tokens: Vec<SavedToken>,
+    accounts: Vec<String>,
+    target_balances: Vec<i128>,
+    refill_threshold_bps: u32,
+    factory: String,
+    router: Option<String>,
+    pairs: Vec<String>,
+}
+
+fn address_to_string(address: &Address) -> String {
+    address.to_string().to_string()
+}
+
+fn address_from_str(env: &Env, s: &str) -> Address {
+    Address::from_string(&SorobanString::from_str(env, s))
+}
+
+/// Snapshot location for one scenario world
+pub struct WorldSnapshot {
+    dir: PathBuf,
+    key: String,
+}
+
+impl WorldSnapshot {
+    /// Snapshot of `scenario`'s world under `dir`, keyed by the current config
+    pub fn new<P: AsRef<Path>>(dir: P, scenario: &str, config: &StressConfig) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            key: Self::key(scenario, config),
+        }
+    }
+
+    /// Cache key: scenario name plus a hash of everything that shapes the world
+    pub fn key(scenario: &str, config: &StressConfig) -> String {
+        let mut hasher = DefaultHasher::new();
+        scenario.hash(&mut hasher);
+        config.num_accounts.hash(&mut hasher);
+        config.num_pairs.hash(&mut hasher);
+        serde_json::to_string(&config.accounts)
+            .unwrap_or_default()
+            .hash(&mut hasher);
+        serde_json::to_string(&config.tokens)
+            .unwrap_or_default()
+            .hash(&mut hasher);
+        pair_wasm::WASM.hash(&mut hasher);
+        format!("{}-{:016x}", scenario, hasher.finish())
+    }
+
+    fn env_path(&self) -> PathBuf {
+        self.dir.join(format!("{}.env.json", self.key))
+    }
+
+    fn world_path(&self) -> PathBuf {
+        self.dir.join(format!("{}.world.json", self.key))
+    }
+
+    /// Write the world to disk
+    pub fn save(&self, world: &World) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+
+        let manifest = WorldManifest {
+            key: self.key.clone(),
+            admin: address_to_string(&world.admin),
+            tokens: world
+                .token_manager
+                .all()
+                .iter()
+                .map(|t| SavedToken {
+                    address: address_to_string(&t.address),
+                    name: t.name.clone(),
+                    decimals: t.decimals,
+                    class: t.class,
+                })
+                .collect(),
+            accounts: world.account_pool.all().iter().map(address_to_string).collect(),
+            target_balances: world.account_pool.target_balances().to_vec(),
+            refill_threshold_bps: world.account_pool.refill_threshold_bps(),
+            factory: address_to_string(&world.factory),
+            router: world.router.as_ref().map(address_to_string),
+            pairs: world.pairs.iter().map(address_to_string).collect(),
+        };
+
+        world.env.to_snapshot_file(self.env_path());
+        fs::write(
+            self.world_path(),
+            serde_json::to_string_pretty(&manifest)?,
+        )
+    }
+
+    /// Restore the world, or None if there is no usable snapshot.
+    ///
+    /// Native contracts (factory, router, mock tokens) are re-registered at
+    /// their saved addresses; their storage comes from the restored ledger.
+    pub fn load(&self) -> Option<World> {
+        let manifest: WorldManifest =
+            serde_json::from_str(&fs::read_to_string(self.world_path()).ok()?).ok()?;
+        if manifest.key != self.key || !self.env_path().exists() {
+            return None;
+        }
+
+        let env_path = self.env_path();
+        let env = std::panic::catch_unwind(|| Env::from_snapshot_file(env_path)).ok()?;
+        env.mock_all_auths_allowing_non_root_auth();
+
+        let factory = address_from_str(&env, &manifest.factory);
+        env.register_at(&factory, AstroSwapFactory, ());
+        let router = manifest.router.as_ref().map(|r| {
+            let router = address_from_str(&env, r);
+            env.register_at(&router, AstroSwapRouter, ());
+            router
+        });
+
+        let tokens = manifest
+            .tokens
+            .iter()
+            .map(|t| TokenInfo {
+                address: address_from_str(&env, &t.address),
+                name: t.name.clone(),
+                decimals: t.decimals,
+                class: t.class,
+            })
+            .collect();
+        let token_manager = TokenManager::restore(&env, tokens);
+
+        let account_pool = AccountPool::restore(
+            manifest.accounts.iter().map(|a| address_from_str(&env, a)).collect(),
+            manifest.target_balances.clone(),
+            manifest.refill_threshold_bps,
+        );
+
+        let pairs: Vec<Address> = manifest.pairs.iter().map(|p| address_from_str(&env, p)).collect();
+
+        // Guard against a restore that lost contract state
+        let factory_client = AstroSwapFactoryClient::new(&env, &factory);
+        let pair_count = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            factory_client.all_pairs_length()
+        }))
+        .ok()?;
+        if pair_count as usize != pairs.len() {
+            return None;
+        }
+
+        Some(World {
+            admin: address_from_str(&env, &manifest.admin),
+            env,
+            token_manager,
+            account_pool,
+            factory,
+            router,
+            pairs,
+        })
+    }
+}
+
+/// Restore the scenario's world from `config.snapshot_dir`, or build it with
+/// `build` (saving it for next time). Without a snapshot dir this just builds.
+pub fn load_or_build<F>(config: &StressConfig, scenario: &str, build: F) -> World
+where
+    F: FnOnce() -> World,
+{
+    let Some(dir) = &config.snapshot_dir else {
+        return build();
+    };
+
+    let snapshot = WorldSnapshot::new(dir, scenario, config);
+    if let Some(world) = snapshot.load() {
+        println!("Restored {} world from snapshot {}", scenario, snapshot.key);
+        return world;
+    }
+
+    let world = build();
+    match snapshot.save(&world) {
+        Ok(()) => println!("Saved {} world to snapshot {}", scenario, snapshot.key),
+        Err(e) => println!("Failed to save {} snapshot: {}", scenario, e),
+    }
+    world
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_tracks_setup_config() {
+        let config = StressConfig::default();
+        let key = WorldSnapshot::key("swap-load", &config);
+        assert_eq!(key, WorldSnapshot::key("swap-load", &config));
+        assert!(key.starts_with("swap-load-"));
+
+        // Run-time knobs don't invalidate the world
+        let mut longer = config.clone();
+        longer.duration_seconds *= 10;
+        longer.target_tps *= 2;
+        assert_eq!(key, WorldSnapshot::key("swap-load", &longer));
+
+        let mut more_pairs = config.clone();
+        more_pairs.num_pairs += 1;
+        assert_ne!(key, WorldSnapshot::key("swap-load", &more_pairs));
+        assert_ne!(key, WorldSnapshot::key("pool-stress", &config));
+    }
+}
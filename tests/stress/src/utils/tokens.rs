@@ -1,19 +1,49 @@
 //! Token Management
 //!
 //! Utilities for setting up and managing test tokens for stress testing.
+//!
+//! Amounts passed to `TokenManager` (supplies, mints, distributions) are expressed
+//! in 7-decimal units and scaled to each token's decimals; balances are raw.
 
+use super::mock_token::{MockToken, MockTokenClient};
+use crate::config::{TokenClass, TokenConfig};
+use rand::Rng;
 use soroban_sdk::{
     token::{Client as TokenClient, StellarAssetClient},
-    Address, Env,
+    Address, Env, String as SorobanString,
 };
 use std::collections::HashMap;
 
+/// Decimals of the unit TokenManager amounts are expressed in
+const BASE_DECIMALS: u32 = 7;
+
 /// Token information (SDK 23: store address only, create clients on demand)
 #[derive(Clone, Debug)]
 pub struct TokenInfo {
     pub address: Address,
     pub name: String,
     pub decimals: u32,
+    pub class: TokenClass,
+}
+
+impl TokenInfo {
+    /// Convert a 7-decimal amount to this token's raw units (saturating)
+    pub fn to_raw(&self, amount: i128) -> i128 {
+        if self.decimals >= BASE_DECIMALS {
+            amount.saturating_mul(10i128.pow(self.decimals - BASE_DECIMALS))
+        } else {
+            amount / 10i128.pow(BASE_DECIMALS - self.decimals)
+        }
+    }
+
+    /// Convert raw units of this token to a 7-decimal amount
+    pub fn from_raw(&self, raw: i128) -> i128 {
+        if self.decimals >= BASE_DECIMALS {
+            raw / 10i128.pow(self.decimals - BASE_DECIMALS)
+        } else {
+            raw.saturating_mul(10i128.pow(BASE_DECIMALS - self.decimals))
+        }
+    }
 }
 
 /// Manages test tokens for stress testing
@@ -33,6 +63,26 @@ impl TokenManager {
         }
     }
 
+    /// Rebuild a manager from previously created tokens on a restored `Env`.
+    ///
+    /// Mock tokens are native contracts, so they are re-registered at their
+    /// addresses; their storage comes from the restored ledger.
+    pub fn restore(env: &Env, tokens: Vec<TokenInfo>) -> Self {
+        let mut token_map = HashMap::new();
+        for (index, token) in tokens.iter().enumerate() {
+            if token.class != TokenClass::Standard {
+                env.register_at(&token.address, MockToken, ());
+            }
+            token_map.insert(token.name.clone(), index);
+        }
+
+        Self {
+            tokens,
+            token_map,
+            env: Some(env.clone()),
+        }
+    }
+
     /// Create and register a new token
     pub fn create_token(
         &mut self,
@@ -58,6 +108,7 @@ impl TokenManager {
             address: token_address,
             name: name.clone(),
             decimals,
+            class: TokenClass::Standard,
         };
 
         self.token_map.insert(name, index);
@@ -66,6 +117,54 @@ impl TokenManager {
         &self.tokens[index]
     }
 
+    /// Create and register a token of the given class.
+    ///
+    /// Standard tokens are Stellar Asset Contracts; every other class is a
+    /// `MockToken` configured for that behaviour.
+    pub fn create_token_of_class(
+        &mut self,
+        env: &Env,
+        admin: &Address,
+        name: String,
+        class: TokenClass,
+        transfer_fee_bps: u32,
+        initial_supply: i128,
+    ) -> &TokenInfo {
+        if class == TokenClass::Standard {
+            return self.create_token(env, admin, name, class.decimals(), initial_supply);
+        }
+
+        if self.env.is_none() {
+            self.env = Some(env.clone());
+        }
+
+        let token_address = env.register(MockToken, ());
+        let fee_bps = if class == TokenClass::FeeOnTransfer {
+            transfer_fee_bps
+        } else {
+            0
+        };
+        MockTokenClient::new(env, &token_address).initialize(
+            admin,
+            &class.decimals(),
+            &SorobanString::from_str(env, &name),
+            &SorobanString::from_str(env, &name),
+            &fee_bps,
+        );
+
+        let index = self.tokens.len();
+        self.tokens.push(TokenInfo {
+            address: token_address,
+            name: name.clone(),
+            decimals: class.decimals(),
+            class,
+        });
+        self.token_map.insert(name, index);
+        self.mint(index, admin, initial_supply);
+
+        &self.tokens[index]
+    }
+
     /// Create multiple tokens at once
     pub fn create_tokens(
         &mut self,
@@ -80,6 +179,33 @@ impl TokenManager {
         }
     }
 
+    /// Create multiple tokens, assigning the configured classes round-robin
+    pub fn create_tokens_with_classes(
+        &mut self,
+        env: &Env,
+        admin: &Address,
+        count: u32,
+        initial_supply: i128,
+        config: &TokenConfig,
+    ) {
+        for i in 0..count {
+            let class = config
+                .classes
+                .get(i as usize % config.classes.len().max(1))
+                .copied()
+                .unwrap_or(TokenClass::Standard);
+            let name = format!("TOKEN_{}", i);
+            self.create_token_of_class(
+                env,
+                admin,
+                name,
+                class,
+                config.transfer_fee_bps,
+                initial_supply,
+            );
+        }
+    }
+
     /// Get token by index
     pub fn get(&self, index: usize) -> Option<&TokenInfo> {
         self.tokens.get(index)
@@ -92,6 +218,18 @@ impl TokenManager {
             .and_then(|&index| self.tokens.get(index))
     }
 
+    /// Get token by address
+    pub fn info_of(&self, address: &Address) -> Option<&TokenInfo> {
+        self.tokens.iter().find(|t| &t.address == address)
+    }
+
+    /// Get the class of a token by address (Standard if unknown)
+    pub fn class_of(&self, address: &Address) -> TokenClass {
+        self.info_of(address)
+            .map(|t| t.class)
+            .unwrap_or(TokenClass::Standard)
+    }
+
     /// Get all tokens
     pub fn all(&self) -> &[TokenInfo] {
         &self.tokens
@@ -118,7 +256,7 @@ impl TokenManager {
         for token in &self.tokens {
             let client = TokenClient::new(env, &token.address);
             for account in to_accounts {
-                client.transfer(from, account, &amount_per_account);
+                client.transfer(from, account, &token.to_raw(amount_per_account));
             }
         }
     }
@@ -135,11 +273,26 @@ impl TokenManager {
         if let Some(token) = self.tokens.get(token_index) {
             let client = TokenClient::new(env, &token.address);
             for account in to_accounts {
-                client.transfer(from, account, &amount_per_account);
+                client.transfer(from, account, &token.to_raw(amount_per_account));
             }
         }
     }
 
+    /// Mint a specific token to an account (TokenManager's admin must be the token admin)
+    pub fn mint(&self, token_index: usize, to: &Address, amount: i128) {
+        let env = self.env.as_ref().expect("TokenManager not initialized with env");
+        if let Some(token) = self.tokens.get(token_index) {
+            StellarAssetClient::new(env, &token.address).mint(to, &token.to_raw(amount));
+        }
+    }
+
+    /// Mint every token to an account
+    pub fn mint_all(&self, to: &Address, amount: i128) {
+        for token_index in 0..self.tokens.len() {
+            self.mint(token_index, to, amount);
+        }
+    }
+
     /// Get balance of account for specific token
     pub fn balance(&self, token_index: usize, account: &Address) -> i128 {
         let env = self.env.as_ref().expect("TokenManager not initialized with env");
@@ -152,6 +305,34 @@ impl TokenManager {
             .unwrap_or(0)
     }
 
+    /// Get balance of account for specific token in 7-decimal units
+    pub fn balance_nominal(&self, token_index: usize, account: &Address) -> i128 {
+        self.tokens
+            .get(token_index)
+            .map(|token| token.from_raw(self.balance(token_index, account)))
+            .unwrap_or(0)
+    }
+
+    /// Randomly pause/unpause pausable tokens and block/unblock a random account
+    /// on blocklist tokens, each with the given probability
+    pub fn apply_hostile_events<R: Rng>(&self, accounts: &[Address], probability: f64, rng: &mut R) {
+        let env = self.env.as_ref().expect("TokenManager not initialized with env");
+        for token in &self.tokens {
+            if !rng.gen_bool(probability.clamp(0.0, 1.0)) {
+                continue;
+            }
+            let client = MockTokenClient::new(env, &token.address);
+            match token.class {
+                TokenClass::Pausable => client.set_paused(&!client.is_paused()),
+                TokenClass::Blocklist if !accounts.is_empty() => {
+                    let account = &accounts[rng.gen_range(0..accounts.len())];
+                    client.set_blocked(account, &!client.is_blocked(account));
+                }
+                _ => {}
+            }
+        }
+    }
+
     /// Get token pair (useful for creating trading pairs)
     pub fn get_pair(&self, index_a: usize, index_b: usize) -> Option<(&TokenInfo, &TokenInfo)> {
         if index_a < self.tokens.len() && index_b < self.tokens.len() && index_a != index_b {
@@ -267,4 +448,33 @@ mod tests {
         assert!(manager.get_pair(0, 0).is_none()); // Same index
         assert!(manager.get_pair(0, 10).is_none()); // Out of bounds
     }
+
+    #[test]
+    fn test_create_tokens_with_classes() {
+        let env = Env::default();
+        env.mock_all_auths_allowing_non_root_auth();
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+        let mut manager = TokenManager::new();
+        let config = TokenConfig {
+            classes: vec![TokenClass::Standard, TokenClass::FeeOnTransfer, TokenClass::HighDecimals],
+            transfer_fee_bps: 100,
+            hostile_toggle_probability: 0.0,
+        };
+
+        manager.create_tokens_with_classes(&env, &admin, 3, 1_000_0000000, &config);
+
+        assert_eq!(manager.get(0).unwrap().class, TokenClass::Standard);
+        assert_eq!(manager.class_of(&manager.get(1).unwrap().address), TokenClass::FeeOnTransfer);
+
+        // High-decimal supply is scaled to 18 decimals
+        let high = manager.get(2).unwrap();
+        assert_eq!(high.decimals, 18);
+        assert_eq!(manager.balance(2, &admin), 1_000 * 10i128.pow(18));
+        assert_eq!(manager.balance_nominal(2, &admin), 1_000_0000000);
+
+        // Fee-on-transfer recipient receives 1% less
+        manager.distribute_token(1, &admin, &[user.clone()], 100_0000000);
+        assert_eq!(manager.balance(1, &user), 99_0000000);
+    }
 }